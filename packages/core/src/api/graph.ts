@@ -193,39 +193,303 @@ interface WasmEngine extends SpatialQueryEngine {
   /** Remove an edge by ID */
   removeEdge(id: number): boolean;
   /** Compute tidy tree layout from graph's own edges */
-  computeTreeLayoutFromGraph(
-    rootId: number,
-    levelSeparation: number,
-    siblingSeparation: number,
-    subtreeSeparation: number,
-    radial: boolean,
-  ): Float32Array;
+  computeTreeLayoutFromGraph(options: {
+    rootId?: number;
+    levelSeparation?: number;
+    siblingSeparation?: number;
+    subtreeSeparation?: number;
+    coordinateMode?: "linear" | "radial";
+  }): Float32Array;
   /** Get upper bound on node indices (max index + 1) */
   nodeBound(): number;
+  /** Summed edge weights per node slot as [out0, in0, out1, in1, ...] */
+  getNodeStrengths(): Float32Array;
   /** Detect communities using Louvain algorithm. Returns assignments array with community count as last element. */
   detectCommunities(
     resolution: number,
     maxIterations: number,
     minModularityGain: number,
   ): Uint32Array;
-  /** Detect communities and compute layout in a single call (legacy — prefer detectCommunities) */
-  computeCommunityLayoutFromGraph(
+  /** Detect communities with Louvain and return every level (finest first) with its merge tree */
+  detectCommunityHierarchy(
     resolution: number,
     maxIterations: number,
-    communitySpacing: number,
-    nodeSpacing: number,
-    spreadFactor: number,
+    minModularityGain: number,
+  ): {
+    levels: {
+      assignments: number[];
+      communityCount: number;
+      modularity: number;
+      parents: number[];
+    }[];
+    bestLevel?: number;
+  };
+  /** Girvan–Newman dendrogram, finest level first; throws above 2000 edges */
+  detectCommunitiesGirvanNewman(): {
+    levels: {
+      assignments: number[];
+      communityCount: number;
+      modularity: number;
+      parents: number[];
+    }[];
+    bestLevel?: number;
+  };
+  /** Run community detection several times and return the consensus partition with per-node stability */
+  detectCommunitiesConsensus(
+    runs: number,
+    options?: {
+      algorithm?: "louvain" | "leiden" | "spectral";
+      resolution?: number;
+      maxIterations?: number;
+      minModularityGain?: number;
+      clusters?: number;
+      threshold?: number;
+    },
+  ): { assignments: number[]; communityCount: number; stability: number[] };
+  /** Detect communities among the given nodes only. Same return layout as detectCommunities, 0xFFFFFFFF outside the subset. */
+  detectCommunitiesInSubset(
+    nodeIds: Uint32Array,
+    options?: {
+      algorithm?: "louvain" | "leiden" | "spectral";
+      resolution?: number;
+      maxIterations?: number;
+      minModularityGain?: number;
+      clusters?: number;
+    },
+  ): Uint32Array;
+  /** Re-detect communities, keeping previous community IDs where members still overlap */
+  detectCommunitiesStable(
+    previousAssignments: Uint32Array,
+    options?: {
+      algorithm?: "louvain" | "leiden" | "spectral";
+      resolution?: number;
+      maxIterations?: number;
+      minModularityGain?: number;
+      clusters?: number;
+    },
+  ): { assignments: number[]; communityCount: number; changed: number[] };
+  /** Detect communities at evenly spaced resolutions; modularity is standard (resolution 1) modularity */
+  sweepResolution(
+    minResolution: number,
+    maxResolution: number,
+    steps: number,
+    options?: {
+      algorithm?: "louvain" | "leiden";
+      maxIterations?: number;
+      minModularityGain?: number;
+    },
+  ): { resolution: number; communityCount: number; modularity: number }[];
+  /** Build a new engine with one node per community at its members' centroid and summed inter-community edge weights */
+  buildCommunityGraph(assignments: Uint32Array): WasmEngine;
+  /** Bounding circle [cx, cy, r] per community for the given positions (empty = current) */
+  computeCommunityBounds(
+    assignments: Uint32Array,
+    communityCount: number,
+    positions: Float32Array,
   ): Float32Array;
+  /** Summed edges between community pairs as [a, b, weight, count, ...] */
+  aggregateEdgesByCommunity(assignments: Uint32Array): Float32Array;
+  /** Per-node color index where adjacent communities differ (0xFFFFFFFF = left out) */
+  assignCommunityColors(assignments: Uint32Array, paletteSize: number): Uint32Array;
+  /** Reassign communities around changed nodes and move only the nodes that switched */
+  updateCommunities(
+    previousAssignments: Uint32Array,
+    changedNodes: Uint32Array,
+    options?: { resolution?: number; nodeSpacing?: number },
+  ): {
+    assignments: number[];
+    communityCount: number;
+    changed: number[];
+    positions: number[];
+  };
+  /** Split into a fixed number of communities by spectral clustering. Same return layout as detectCommunities. */
+  detectCommunitiesSpectral(clusters: number): Uint32Array;
+  /** Detect communities using the Leiden algorithm; every community is connected. Same return layout as detectCommunities. */
+  detectCommunitiesLeiden(
+    resolution: number,
+    maxIterations: number,
+    minModularityGain: number,
+  ): Uint32Array;
+  /** Detect communities and compute layout in a single call (legacy — prefer detectCommunities) */
+  computeCommunityLayoutFromGraph(options: {
+    resolution?: number;
+    maxIterations?: number;
+    communitySpacing?: number;
+    nodeSpacing?: number;
+    spreadFactor?: number;
+    arrangement?: "circle" | "grid" | "packed";
+    /** Largest seeded random turn of each community's spiral, in radians */
+    spiralRotation?: number;
+    keepCenters?: boolean;
+  }): Float32Array;
   /** Compute codebase layout using the graph's own edges */
   computeCodebaseLayoutFromGraph(
     nodeCategories: Uint8Array,
-    rootId: number,
-    directoryPadding: number,
-    filePadding: number,
-    spreadFactor: number,
+    options: {
+      rootId?: number;
+      directoryPadding?: number;
+      filePadding?: number;
+      spreadFactor?: number;
+      /** Per-node packing order by slot (ascending; unkeyed siblings last) */
+      sortKeys?: number[];
+      /** Append per-node circle radii after the positions */
+      includeRadii?: boolean;
+    },
+  ): Float32Array;
+  /** Update a codebase layout around added/removed nodes; returns only the nodes that moved */
+  updateCodebaseLayoutFromGraph(
+    nodeCategories: Uint8Array,
+    previous: Float32Array,
+    changedNodes: Uint32Array,
+    options: {
+      rootId?: number;
+      directoryPadding?: number;
+      filePadding?: number;
+      spreadFactor?: number;
+      sortKeys?: number[];
+    },
+  ): { slots: number[]; positions: number[]; radii: number[] };
+  /** Compute bubble data (wellRadius + depth) per tree for nested bubble layout mode */
+  computeBubbleData(
+    baseRadius: number,
+    padding: number,
+    componentDepthOffset?: number,
+    weights?: Float32Array,
+  ): Float32Array;
+  /** Count descendants per node of a containment hierarchy (rootId u32::MAX = auto-detect) */
+  computeSubtreeCounts(containmentEdges: Uint32Array, rootId: number): Uint32Array;
+  /** Nodes bucketed per depth: [levelCount, offsets (levelCount + 1)..., ids...] */
+  groupNodesByDepth(containmentEdges: Uint32Array, rootId: number): Uint32Array;
+  /** Bubble well circles [x, y, radius, depth] per container node, outermost first */
+  computeBubbleCircles(bubbleData: Float32Array): Float32Array;
+  /** Bubble well outlines as a line-list vertex buffer [x0, y0, x1, y1, ...] */
+  tessellateBubbleOutlines(bubbleData: Float32Array, segments?: number): Float32Array;
+  /** Breadth-first visit order, parent and hop distance per slot (u32::MAX = unreached) */
+  bfsFrom(
+    nodeId: number,
+    undirected?: boolean,
+  ): { order: number[]; parents: number[]; depths: number[] };
+  /** Depth-first preorder, parent and DFS-tree depth per slot (u32::MAX = unreached) */
+  dfsFrom(
+    nodeId: number,
+    undirected?: boolean,
+  ): { order: number[]; parents: number[]; depths: number[] };
+  /** Relevance per slot to the seed nodes (personalized PageRank; empty seeds = PageRank) */
+  personalizedPageRank(
+    seeds: Uint32Array,
+    options?: {
+      restartProbability?: number;
+      maxIterations?: number;
+      tolerance?: number;
+      undirected?: boolean;
+    },
+  ): Float32Array;
+  /** Betweenness per node slot, then per edge ID with includeEdges (Brandes, optional sampling) */
+  computeBetweenness(options?: {
+    samples?: number;
+    normalized?: boolean;
+    undirected?: boolean;
+    includeEdges?: boolean;
+  }): Float32Array;
+  /** Closeness per node slot: inverse mean hop distance to reached nodes */
+  computeCloseness(options?: { samples?: number; undirected?: boolean }): Float32Array;
+  /** Harmonic centrality per node slot: sum of inverse hop distances (safe across components) */
+  computeHarmonicCentrality(options?: {
+    samples?: number;
+    undirected?: boolean;
+    normalized?: boolean;
+  }): Float32Array;
+  /** Eigenvector centrality per node slot by power iteration; throws if it does not converge */
+  computeEigenvectorCentrality(options?: {
+    maxIterations?: number;
+    tolerance?: number;
+    weighted?: boolean;
+    undirected?: boolean;
+  }): Float32Array;
+  /** Katz centrality per node slot; throws if it does not converge (alpha too large) */
+  computeKatzCentrality(options?: {
+    alpha?: number;
+    beta?: number;
+    maxIterations?: number;
+    tolerance?: number;
+    weighted?: boolean;
+    undirected?: boolean;
+    normalized?: boolean;
+  }): Float32Array;
+  /** Maximal cliques as [count, offsets..., ids...] (CSR-style offsets) */
+  findMaximalCliques(options?: { minSize?: number; maxCliques?: number }): Uint32Array;
+  /** Minimum spanning forest: edge IDs, parent per node slot, total weight */
+  minimumSpanningTree(rootId?: number): {
+    edges: number[];
+    parents: number[];
+    totalWeight: number;
+  };
+  /** IDs of the edges in a maximum cardinality matching (direction ignored), ascending */
+  maximumMatching(): Uint32Array;
+  /** Node/edge counts, density, components, degree histogram, diameter and clustering */
+  computeGraphStats(): {
+    nodeCount: number;
+    edgeCount: number;
+    density: number;
+    componentCount: number;
+    degreeHistogram: number[];
+    diameter: number;
+    averageClustering: number;
+  };
+  /** Rich-club coefficient per degree threshold 0..maxK (NaN where undefined) */
+  richClubCoefficients(
+    maxK: number,
+    options?: { normalized?: boolean; swapsPerEdge?: number },
   ): Float32Array;
-  /** Compute bubble data (wellRadius + depth) for nested bubble layout mode */
-  computeBubbleData(baseRadius: number, padding: number): Float32Array;
+  /** Elementary directed cycles as [count, offsets..., ids...]; 0 lifts either cap */
+  findCycles(maxCycles: number, maxLength: number): Uint32Array;
+  /** IDs of edges implied by other paths, whose removal leaves the transitive reduction (DAGs) */
+  transitiveReduction(): Uint32Array;
+  /** Reachable slot pairs [source0, target0, ...] of a DAG */
+  transitiveClosure(): Uint32Array;
+  /** Precompute the reachability index isReachable uses until the next node or edge change */
+  buildReachabilityIndex(): void;
+  /** Whether the reachability index is built and current */
+  hasReachabilityIndex(): boolean;
+  /** Whether target is reachable from source along edge directions */
+  isReachable(source: number, target: number): boolean;
+  /** Lightest weighted path (Dijkstra); undefined if the nodes are not connected */
+  shortestPath(
+    source: number,
+    target: number,
+    undirected?: boolean,
+  ): { nodes: number[]; edges: number[]; distance: number } | undefined;
+  /** A* path guided by straight-line distance to the target; same result as shortestPath */
+  shortestPathAStar(
+    source: number,
+    target: number,
+    undirected?: boolean,
+    heuristicScale?: number,
+  ): { nodes: number[]; edges: number[]; distance: number } | undefined;
+  /** Up to k loopless paths lightest first (Yen's algorithm), in the shortestPath format */
+  kShortestPaths(
+    source: number,
+    target: number,
+    k: number,
+    undirected?: boolean,
+  ): { nodes: number[]; edges: number[]; distance: number }[];
+  /** Shortest-path bitmasks [nodeWordCount, nodeWords..., edgeWords...] (bit per slot / edge ID) */
+  computePathHighlight(source: number, target: number, undirected?: boolean): Uint32Array;
+  /** Bellman–Ford path allowing negative weights, or a reachable negative cycle */
+  shortestPathBellmanFord(
+    source: number,
+    target: number,
+    undirected?: boolean,
+  ): {
+    path?: { nodes: number[]; edges: number[]; distance: number };
+    negativeCycle?: { nodes: number[]; edges: number[]; distance: number };
+  };
+  /** Maximum flow with weights as capacities, flow per edge ID and min-cut edge IDs */
+  maxFlow(
+    source: number,
+    target: number,
+    undirected?: boolean,
+  ): { value: number; flows: number[]; cutEdges: number[] };
 }
 
 /**
@@ -3564,15 +3828,13 @@ export class HeroineGraph {
     }
 
     const config = this.forceConfig;
-    const root = rootId ?? 0xFFFFFFFF; // u32::MAX = auto-detect
-
-    const positions = this.wasmEngine.computeTreeLayoutFromGraph(
-      root,
-      config.tidyTreeLevelSeparation,
-      config.tidyTreeSiblingSeparation,
-      config.tidyTreeSubtreeSeparation,
-      config.tidyTreeRadial,
-    );
+    const positions = this.wasmEngine.computeTreeLayoutFromGraph({
+      rootId, // undefined = auto-detect
+      levelSeparation: config.tidyTreeLevelSeparation,
+      siblingSeparation: config.tidyTreeSiblingSeparation,
+      subtreeSeparation: config.tidyTreeSubtreeSeparation,
+      coordinateMode: config.tidyTreeRadial ? "radial" : "linear",
+    });
 
     this.currentAlgorithm.uploadTargetPositions(
       this.gpuContext.device,
@@ -59,20 +59,22 @@ if ! command -v wasm-pack &> /dev/null; then
     exit 1
 fi
 
-# Set RUSTFLAGS for SIMD if enabled
+# Set RUSTFLAGS and crate features for SIMD if enabled
 RUSTFLAGS=""
+FEATURES=""
 if [ "$SIMD_ENABLED" = true ]; then
     echo "Building with WASM SIMD enabled..."
     RUSTFLAGS="-C target-feature=+simd128"
+    FEATURES="--features simd"
 fi
 
 # Build
 echo "Building heroine-graph-wasm (profile: $PROFILE)..."
 
 if [ "$PROFILE" = "release" ]; then
-    RUSTFLAGS="$RUSTFLAGS" wasm-pack build --target web --release --out-dir pkg
+    RUSTFLAGS="$RUSTFLAGS" wasm-pack build --target web --release --out-dir pkg -- $FEATURES
 else
-    RUSTFLAGS="$RUSTFLAGS" wasm-pack build --target web --dev --out-dir pkg
+    RUSTFLAGS="$RUSTFLAGS" wasm-pack build --target web --dev --out-dir pkg -- $FEATURES
 fi
 
 # Post-build verification
@@ -0,0 +1,852 @@
+//! Node relevance and centrality scores.
+//!
+//! Scores run over the engine's slot adjacency lists and come back as one
+//! value per slot.
+//!
+//! # Personalized PageRank
+//!
+//! A random walker follows out-edges with probability proportional to
+//! their weight, and with the restart probability (or from a node without
+//! out-edges) jumps back to one of the seed nodes. The score of a node is
+//! the share of time the walker spends there, so it measures relevance to
+//! the seeds: high near them and along heavily weighted routes from them.
+//! Scores are found by power iteration and sum to 1. Seeding every node
+//! gives classic PageRank.
+//!
+//! # Betweenness
+//!
+//! The betweenness of a node (or edge) is the number of shortest paths
+//! between other nodes that pass through it, each pair's paths sharing one
+//! unit; bridges between clusters score highest. Paths are counted in hops.
+//! Brandes' algorithm finds every shortest path from one source with a
+//! breadth-first search, then hands each node's share of them back to its
+//! predecessors, for O(nodes × edges) in total. On large graphs a random
+//! sample of sources (pivots) gives an unbiased estimate in proportionally
+//! less time.
+//!
+//! # Closeness
+//!
+//! Closeness is the inverse of a node's mean hop distance to the nodes it
+//! reaches, so it favors nodes a few hops from everything. Distances come
+//! from a breadth-first search per node along reversed edges, each search
+//! from `s` giving every node's distance to `s`. Sampling searches from a
+//! random subset of pivots and takes each node's mean distance to the
+//! pivots it reaches as the estimate. Nodes reaching nothing score 0, and
+//! since only reached nodes count, a node in a small component can
+//! outscore the hubs of a large one.
+//!
+//! # Harmonic Centrality
+//!
+//! Harmonic centrality sums the inverse distances instead, so unreachable
+//! nodes simply add nothing and scores stay comparable across components.
+//! It comes from the same searches as closeness; a sampled sum is scaled up
+//! by the share of nodes sampled.
+//!
+//! # Eigenvector Centrality
+//!
+//! A node is central when central nodes link to it: scores are the
+//! principal eigenvector of the (weighted) adjacency matrix, following
+//! in-edges. Power iteration repeatedly sets each score to itself plus the
+//! weighted sum over in-neighbors, rescaling to unit length; adding the
+//! node's own score shifts the spectrum so bipartite graphs converge too.
+//!
+//! # Katz Centrality
+//!
+//! On directed graphs, eigenvector scores drain away from every node that
+//! no cycle feeds. Katz centrality gives each node a baseline `beta` and
+//! adds `alpha` times the weighted sum over in-neighbors, so every walk
+//! into a node counts, damped by `alpha` per step. The sum only converges
+//! while `alpha` is below the inverse of the largest eigenvalue.
+
+use serde::Deserialize;
+
+use super::path::Arc;
+use super::traversal::{breadth_first, UNREACHED};
+use crate::layout::Stepped;
+use crate::rng::{SeededRng, DEFAULT_SEED};
+
+/// Configuration for personalized PageRank. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PageRankConfig {
+    /// Chance per step that the walker restarts at a seed (default: 0.15).
+    /// Higher values keep relevance closer to the seeds.
+    pub restart_probability: f32,
+    /// Maximum power iterations (default: 100).
+    pub max_iterations: u32,
+    /// Stop once scores change by less than this in total (default: 1e-6).
+    pub tolerance: f64,
+    /// Follow edges in both directions (default: false).
+    pub undirected: bool,
+}
+
+impl Default for PageRankConfig {
+    fn default() -> Self {
+        Self {
+            restart_probability: 0.15,
+            max_iterations: 100,
+            tolerance: 1e-6,
+            undirected: false,
+        }
+    }
+}
+
+/// Personalized PageRank score per slot of `adjacency`, restarting at
+/// `seeds` (slots; out-of-range and repeated slots are skipped). All zero
+/// without a valid seed. Negative and NaN weights count as 0.
+pub fn personalized_pagerank(
+    adjacency: &[Vec<Arc>],
+    seeds: &[u32],
+    config: &PageRankConfig,
+) -> Vec<f32> {
+    let node_count = adjacency.len();
+    let mut restart = vec![0.0f64; node_count];
+    for &seed in seeds.iter().filter(|&&s| (s as usize) < node_count) {
+        restart[seed as usize] = 1.0;
+    }
+    let seed_count: f64 = restart.iter().sum();
+    if seed_count == 0.0 {
+        return vec![0.0; node_count];
+    }
+    restart.iter_mut().for_each(|r| *r /= seed_count);
+
+    let weight = |&(_, _, w): &Arc| if w > 0.0 { f64::from(w) } else { 0.0 };
+    let out_weight: Vec<f64> = adjacency.iter().map(|arcs| arcs.iter().map(weight).sum()).collect();
+    let alpha = f64::from(config.restart_probability.clamp(0.0, 1.0));
+
+    let mut scores = restart.clone();
+    for _ in 0..config.max_iterations {
+        // Walkers at nodes without out-edges restart too
+        let stuck: f64 = (0..node_count).filter(|&u| out_weight[u] == 0.0).map(|u| scores[u]).sum();
+        let restarting = alpha + (1.0 - alpha) * stuck;
+        let mut next: Vec<f64> = restart.iter().map(|r| r * restarting).collect();
+        for (u, arcs) in adjacency.iter().enumerate().filter(|&(u, _)| out_weight[u] > 0.0) {
+            let share = (1.0 - alpha) * scores[u] / out_weight[u];
+            for arc in arcs {
+                next[arc.0 as usize] += share * weight(arc);
+            }
+        }
+
+        let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < config.tolerance {
+            break;
+        }
+    }
+    scores.into_iter().map(|s| s as f32).collect()
+}
+
+/// Configuration for eigenvector centrality. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EigenvectorConfig {
+    /// Maximum power iterations (default: 100).
+    pub max_iterations: u32,
+    /// Stop once scores change by less than this per node on average
+    /// (default: 1e-6).
+    pub tolerance: f64,
+    /// Scale each in-edge by its weight (default: true).
+    pub weighted: bool,
+    /// Follow edges in both directions (default: false).
+    pub undirected: bool,
+}
+
+impl Default for EigenvectorConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            tolerance: 1e-6,
+            weighted: true,
+            undirected: false,
+        }
+    }
+}
+
+/// Eigenvector centrality per slot of `adjacency`, with unit Euclidean
+/// length, starting from equal scores on the slots in `nodes` (the
+/// existing nodes). `None` if it does not converge within the iterations.
+pub fn eigenvector_centrality(
+    adjacency: &[Vec<Arc>],
+    nodes: &[u32],
+    config: &EigenvectorConfig,
+) -> Option<Vec<f32>> {
+    let mut scores = vec![0.0f64; adjacency.len()];
+    for &node in nodes {
+        scores[node as usize] = 1.0 / (nodes.len() as f64).sqrt();
+    }
+    let tolerance = config.tolerance * nodes.len() as f64;
+
+    for _ in 0..config.max_iterations {
+        let mut next = scores.clone();
+        propagate(adjacency, config.weighted, &scores, &mut next);
+        let length = next.iter().map(|s| s * s).sum::<f64>().sqrt();
+        if length == 0.0 {
+            return Some(vec![0.0; adjacency.len()]);
+        }
+        next.iter_mut().for_each(|s| *s /= length);
+
+        let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < tolerance {
+            return Some(scores.into_iter().map(|s| s as f32).collect());
+        }
+    }
+    None
+}
+
+/// Configuration for Katz centrality. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct KatzConfig {
+    /// Attenuation per step of a walk (default: 0.1).
+    pub alpha: f64,
+    /// Baseline score every node gets (default: 1.0).
+    pub beta: f64,
+    /// Maximum iterations (default: 1000).
+    pub max_iterations: u32,
+    /// Stop once scores change by less than this per node on average
+    /// (default: 1e-6).
+    pub tolerance: f64,
+    /// Scale each in-edge by its weight (default: true).
+    pub weighted: bool,
+    /// Follow edges in both directions (default: false).
+    pub undirected: bool,
+    /// Scale scores to unit Euclidean length (default: true).
+    pub normalized: bool,
+}
+
+impl Default for KatzConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.1,
+            beta: 1.0,
+            max_iterations: 1000,
+            tolerance: 1e-6,
+            weighted: true,
+            undirected: false,
+            normalized: true,
+        }
+    }
+}
+
+/// Katz centrality per slot of `adjacency` for the slots in `nodes` (the
+/// existing nodes), 0 elsewhere. `None` if it does not converge within the
+/// iterations, as happens when `alpha` is too large.
+pub fn katz_centrality(
+    adjacency: &[Vec<Arc>],
+    nodes: &[u32],
+    config: &KatzConfig,
+) -> Option<Vec<f32>> {
+    let mut scores = vec![0.0f64; adjacency.len()];
+    let tolerance = config.tolerance * nodes.len() as f64;
+
+    for _ in 0..config.max_iterations {
+        let mut next = vec![0.0; adjacency.len()];
+        propagate(adjacency, config.weighted, &scores, &mut next);
+        for &node in nodes {
+            let slot = node as usize;
+            next[slot] = config.alpha * next[slot] + config.beta;
+        }
+        if next.iter().any(|s| !s.is_finite()) {
+            return None;
+        }
+
+        let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < tolerance {
+            let length = scores.iter().map(|s| s * s).sum::<f64>().sqrt();
+            let scale = if config.normalized && length > 0.0 { 1.0 / length } else { 1.0 };
+            return Some(scores.into_iter().map(|s| (s * scale) as f32).collect());
+        }
+    }
+    None
+}
+
+/// Add to each slot of `next` the sum of `scores` over its in-neighbors,
+/// scaled by edge weight when `weighted` (negative and NaN weights count
+/// as 0).
+fn propagate(adjacency: &[Vec<Arc>], weighted: bool, scores: &[f64], next: &mut [f64]) {
+    for (node, arcs) in adjacency.iter().enumerate().filter(|&(node, _)| scores[node] != 0.0) {
+        for &(target, _, weight) in arcs {
+            let weight = if !weighted {
+                1.0
+            } else if weight > 0.0 {
+                f64::from(weight)
+            } else {
+                0.0
+            };
+            next[target as usize] += weight * scores[node];
+        }
+    }
+}
+
+/// Configuration for betweenness centrality. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BetweennessConfig {
+    /// Sources to sample; 0 or at least the node count is exact (default: 0).
+    pub samples: u32,
+    /// Divide by the number of node pairs, so scores fall in [0, 1]
+    /// (default: false).
+    pub normalized: bool,
+    /// Follow edges in both directions, counting each unordered pair once
+    /// (default: false).
+    pub undirected: bool,
+    /// Also score edges (default: false).
+    pub include_edges: bool,
+    /// Seed for pivot sampling (not read from JS; the engine seed applies).
+    #[serde(skip)]
+    pub seed: u64,
+}
+
+impl Default for BetweennessConfig {
+    fn default() -> Self {
+        Self {
+            samples: 0,
+            normalized: false,
+            undirected: false,
+            include_edges: false,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Betweenness scores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Betweenness {
+    /// Score per slot.
+    pub nodes: Vec<f32>,
+    /// Score per edge ID (empty unless edges were requested).
+    pub edges: Vec<f32>,
+}
+
+/// Betweenness centrality over `adjacency`, counting paths between the
+/// slots in `nodes` (the existing nodes). Edge scores cover IDs below
+/// `edge_id_bound`.
+pub fn betweenness(
+    adjacency: &[Vec<Arc>],
+    nodes: &[u32],
+    edge_id_bound: usize,
+    config: &BetweennessConfig,
+) -> Betweenness {
+    let mut search = BetweennessSearch::new(adjacency, nodes, edge_id_bound, config);
+    while !search.step() {}
+    search.into_result()
+}
+
+/// Number of steps a [`BetweennessSearch`] splits its sources into.
+const BETWEENNESS_STEPS: usize = 100;
+
+/// Betweenness computed a batch of sources at a time, so a host can report
+/// progress or stop between batches. [`betweenness`] runs it to the end.
+pub struct BetweennessSearch<'a> {
+    brandes: Brandes<'a>,
+    sources: Vec<u32>,
+    /// Sources searched so far.
+    searched: usize,
+    /// Sources searched per step.
+    batch: usize,
+    node_count: usize,
+    node_scores: Vec<f64>,
+    edge_scores: Vec<f64>,
+    normalized: bool,
+    undirected: bool,
+}
+
+impl<'a> BetweennessSearch<'a> {
+    /// Prepare a search over `adjacency`, with arguments as for
+    /// [`betweenness`].
+    pub fn new(
+        adjacency: &'a [Vec<Arc>],
+        nodes: &[u32],
+        edge_id_bound: usize,
+        config: &BetweennessConfig,
+    ) -> Self {
+        let sources = sample_sources(nodes, config.samples, config.seed);
+        Self {
+            brandes: Brandes::new(adjacency),
+            batch: sources.len().div_ceil(BETWEENNESS_STEPS).max(1),
+            sources,
+            searched: 0,
+            node_count: nodes.len(),
+            node_scores: vec![0.0; adjacency.len()],
+            edge_scores: vec![0.0; if config.include_edges { edge_id_bound } else { 0 }],
+            normalized: config.normalized,
+            undirected: config.undirected,
+        }
+    }
+
+    /// Search from the next batch of sources. Returns true once every
+    /// source has been searched.
+    pub fn step(&mut self) -> bool {
+        let end = (self.searched + self.batch).min(self.sources.len());
+        for &source in &self.sources[self.searched..end] {
+            self.brandes.search(source as usize);
+            self.brandes.accumulate(source as usize, &mut self.node_scores, &mut self.edge_scores);
+        }
+        self.searched = end;
+        self.is_done()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.searched == self.sources.len()
+    }
+
+    /// Fraction of the sources searched.
+    pub fn progress(&self) -> f32 {
+        if self.sources.is_empty() {
+            1.0
+        } else {
+            self.searched as f32 / self.sources.len() as f32
+        }
+    }
+
+    /// Scores from the sources searched so far.
+    pub fn into_result(self) -> Betweenness {
+        // Each pivot stands for node_count / samples sources
+        let mut scale = self.node_count as f64 / self.sources.len().max(1) as f64;
+        if self.normalized {
+            let n = self.node_count as f64;
+            let pairs = ((n - 1.0) * (n - 2.0)).max(1.0);
+            let edge_pairs = (n * (n - 1.0)).max(1.0);
+            return Betweenness {
+                nodes: self.node_scores.iter().map(|s| (s * scale / pairs) as f32).collect(),
+                edges: self.edge_scores.iter().map(|s| (s * scale / edge_pairs) as f32).collect(),
+            };
+        }
+        if self.undirected {
+            scale *= 0.5;
+        }
+        Betweenness {
+            nodes: self.node_scores.iter().map(|s| (s * scale) as f32).collect(),
+            edges: self.edge_scores.iter().map(|s| (s * scale) as f32).collect(),
+        }
+    }
+}
+
+impl Stepped for BetweennessSearch<'_> {
+    fn step(&mut self) -> bool {
+        BetweennessSearch::step(self)
+    }
+
+    fn progress(&self) -> f32 {
+        BetweennessSearch::progress(self)
+    }
+
+    fn phase(&self) -> &'static str {
+        if self.is_done() { "done" } else { "betweenness" }
+    }
+}
+
+/// Configuration for closeness centrality. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClosenessConfig {
+    /// Pivots to sample; 0 or at least the node count is exact (default: 0).
+    pub samples: u32,
+    /// Follow edges in both directions (default: false).
+    pub undirected: bool,
+    /// Seed for pivot sampling (not read from JS; the engine seed applies).
+    #[serde(skip)]
+    pub seed: u64,
+}
+
+impl Default for ClosenessConfig {
+    fn default() -> Self {
+        Self {
+            samples: 0,
+            undirected: false,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Closeness centrality per slot of `adjacency` (outgoing neighbor slots
+/// per slot), measuring distances to the slots in `nodes` (the existing
+/// nodes).
+pub fn closeness(adjacency: &[Vec<u32>], nodes: &[u32], config: &ClosenessConfig) -> Vec<f32> {
+    let mut reached = vec![0u32; adjacency.len()];
+    let mut total = vec![0u64; adjacency.len()];
+    for_each_distance(adjacency, nodes, config.samples, config.seed, |node, depth| {
+        reached[node] += 1;
+        total[node] += u64::from(depth);
+    });
+    reached
+        .iter()
+        .zip(&total)
+        .map(|(&r, &t)| if t > 0 { r as f32 / t as f32 } else { 0.0 })
+        .collect()
+}
+
+/// Configuration for harmonic centrality. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HarmonicConfig {
+    /// Pivots to sample; 0 or at least the node count is exact (default: 0).
+    pub samples: u32,
+    /// Follow edges in both directions (default: false).
+    pub undirected: bool,
+    /// Divide by the number of other nodes, so scores fall in [0, 1]
+    /// (default: false).
+    pub normalized: bool,
+    /// Seed for pivot sampling (not read from JS; the engine seed applies).
+    #[serde(skip)]
+    pub seed: u64,
+}
+
+impl Default for HarmonicConfig {
+    fn default() -> Self {
+        Self {
+            samples: 0,
+            undirected: false,
+            normalized: false,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Harmonic centrality per slot of `adjacency` (outgoing neighbor slots
+/// per slot), summing inverse distances to the slots in `nodes` (the
+/// existing nodes).
+pub fn harmonic_centrality(
+    adjacency: &[Vec<u32>],
+    nodes: &[u32],
+    config: &HarmonicConfig,
+) -> Vec<f32> {
+    let mut sums = vec![0.0f64; adjacency.len()];
+    let pivots = for_each_distance(adjacency, nodes, config.samples, config.seed, |node, depth| {
+        sums[node] += 1.0 / f64::from(depth);
+    });
+
+    let n = nodes.len() as f64;
+    let mut scale = n / pivots.max(1) as f64;
+    if config.normalized {
+        scale /= (n - 1.0).max(1.0);
+    }
+    sums.iter().map(|s| (s * scale) as f32).collect()
+}
+
+/// Search from the sampled pivots among `nodes` along reversed arcs,
+/// calling `visit` with each other node that reaches a pivot and its hop
+/// distance to it. Returns the number of pivots.
+fn for_each_distance(
+    adjacency: &[Vec<u32>],
+    nodes: &[u32],
+    samples: u32,
+    seed: u64,
+    mut visit: impl FnMut(usize, u32),
+) -> usize {
+    let reversed = reverse(adjacency);
+    let pivots = sample_sources(nodes, samples, seed);
+    for &pivot in &pivots {
+        let depths = breadth_first(&reversed, pivot as usize).depths;
+        for (node, &depth) in depths.iter().enumerate().filter(|&(_, &d)| d != UNREACHED && d > 0) {
+            visit(node, depth);
+        }
+    }
+    pivots.len()
+}
+
+/// `adjacency` with every arc turned around.
+fn reverse(adjacency: &[Vec<u32>]) -> Vec<Vec<u32>> {
+    let mut reversed = vec![Vec::new(); adjacency.len()];
+    for (node, neighbors) in adjacency.iter().enumerate() {
+        for &next in neighbors {
+            reversed[next as usize].push(node as u32);
+        }
+    }
+    reversed
+}
+
+/// `samples` of `nodes` picked at random, or all of them when `samples` is
+/// 0 or not below the node count.
+fn sample_sources(nodes: &[u32], samples: u32, seed: u64) -> Vec<u32> {
+    let mut sources = nodes.to_vec();
+    if samples > 0 && (samples as usize) < nodes.len() {
+        SeededRng::new(seed).shuffle(&mut sources);
+        sources.truncate(samples as usize);
+    }
+    sources
+}
+
+/// Buffers for Brandes' single-source steps, reset between sources.
+struct Brandes<'a> {
+    adjacency: &'a [Vec<Arc>],
+    /// Shortest paths from the source per slot.
+    sigma: Vec<f64>,
+    /// Hops from the source per slot.
+    distance: Vec<u32>,
+    /// Dependency of the source on each slot.
+    delta: Vec<f64>,
+    /// (slot, edge ID) a shortest path arrives from, per slot.
+    predecessors: Vec<Vec<(u32, u32)>>,
+    /// Slots reached, in order of distance.
+    order: Vec<usize>,
+}
+
+impl<'a> Brandes<'a> {
+    fn new(adjacency: &'a [Vec<Arc>]) -> Self {
+        let node_count = adjacency.len();
+        Self {
+            adjacency,
+            sigma: vec![0.0; node_count],
+            distance: vec![UNREACHED; node_count],
+            delta: vec![0.0; node_count],
+            predecessors: vec![Vec::new(); node_count],
+            order: Vec::new(),
+        }
+    }
+
+    /// Breadth-first search from `source`, counting shortest paths.
+    fn search(&mut self, source: usize) {
+        for &node in &self.order {
+            (self.sigma[node], self.distance[node], self.delta[node]) = (0.0, UNREACHED, 0.0);
+            self.predecessors[node].clear();
+        }
+        self.order.clear();
+        (self.sigma[source], self.distance[source]) = (1.0, 0);
+        self.order.push(source);
+
+        let mut i = 0;
+        while i < self.order.len() {
+            self.expand(self.order[i]);
+            i += 1;
+        }
+    }
+
+    /// Follow the arcs out of `node`, recording it as a predecessor of the
+    /// nodes one hop farther.
+    fn expand(&mut self, node: usize) {
+        for &(next, edge, _) in &self.adjacency[node] {
+            let next = next as usize;
+            if self.distance[next] == UNREACHED {
+                self.distance[next] = self.distance[node] + 1;
+                self.order.push(next);
+            }
+            if self.distance[next] == self.distance[node] + 1 {
+                self.sigma[next] += self.sigma[node];
+                self.predecessors[next].push((node as u32, edge));
+            }
+        }
+    }
+
+    /// Hand dependencies back from the farthest nodes, adding them to the
+    /// node and (if tracked) edge scores.
+    fn accumulate(&mut self, source: usize, nodes: &mut [f64], edges: &mut [f64]) {
+        for i in (0..self.order.len()).rev() {
+            let node = self.order[i];
+            self.hand_back(node, edges);
+            if node != source {
+                nodes[node] += self.delta[node];
+            }
+        }
+    }
+
+    /// Split `node`'s dependency among its predecessors and the edges to
+    /// them.
+    fn hand_back(&mut self, node: usize, edges: &mut [f64]) {
+        for &(previous, edge) in &self.predecessors[node] {
+            let previous = previous as usize;
+            let share = self.sigma[previous] / self.sigma[node] * (1.0 + self.delta[node]);
+            self.delta[previous] += share;
+            if let Some(score) = edges.get_mut(edge as usize) {
+                *score += share;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_personalized_pagerank() {
+        // Chain 0 → 1 → 2 → 3, and a heavier 0 → 4 branch
+        let adjacency = vec![
+            vec![(1, 0, 1.0), (4, 1, 3.0)],
+            vec![(2, 2, 1.0)],
+            vec![(3, 3, 1.0)],
+            vec![],
+            vec![],
+        ];
+        let scores = personalized_pagerank(&adjacency, &[0], &PageRankConfig::default());
+
+        assert!((scores.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!(scores[0] > scores[4]);
+        assert!(scores[4] > scores[1]);
+        assert!(scores[1] > scores[2] && scores[2] > scores[3]);
+
+        // Seeding the end of the chain keeps the walker away from the start
+        let scores = personalized_pagerank(&adjacency, &[3, 99], &PageRankConfig::default());
+        assert_eq!(scores[3], 1.0);
+        assert!(personalized_pagerank(&adjacency, &[], &PageRankConfig::default())
+            .iter()
+            .all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_eigenvector_centrality() {
+        // Star around 0 (both ways) plus a heavy 1 ↔ 2 link
+        let mut adjacency = vec![Vec::new(); 5];
+        let links = [(0, 1, 1.0), (0, 2, 1.0), (0, 3, 1.0), (0, 4, 1.0), (1, 2, 3.0)];
+        for (id, &(u, v, weight)) in links.iter().enumerate() {
+            adjacency[u].push((v as u32, id as u32, weight));
+            adjacency[v].push((u as u32, id as u32, weight));
+        }
+        let nodes: Vec<u32> = (0..5).collect();
+        let scores = eigenvector_centrality(&adjacency, &nodes, &EigenvectorConfig::default())
+            .unwrap();
+
+        assert!((scores.iter().map(|s| s * s).sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!(scores[1] > scores[0] && scores[0] > scores[3]);
+        assert!((scores[1] - scores[2]).abs() < 1e-4);
+        assert!((scores[3] - scores[4]).abs() < 1e-4);
+
+        // Unweighted, the hub leads
+        let config = EigenvectorConfig {
+            weighted: false,
+            ..Default::default()
+        };
+        let scores = eigenvector_centrality(&adjacency, &nodes, &config).unwrap();
+        assert!(scores[0] > scores[1] && scores[1] > scores[3]);
+
+        let config = EigenvectorConfig {
+            max_iterations: 1,
+            ..Default::default()
+        };
+        assert!(eigenvector_centrality(&adjacency, &nodes, &config).is_none());
+    }
+
+    #[test]
+    fn test_katz_centrality() {
+        // Chain 0 → 1 → 2 plus 3 → 2: no cycle, so eigenvector scores vanish
+        let mut adjacency = vec![Vec::new(); 4];
+        for (id, &(u, v)) in [(0, 1), (1, 2), (3, 2)].iter().enumerate() {
+            adjacency[u].push((v as u32, id as u32, 1.0));
+        }
+        let nodes: Vec<u32> = (0..4).collect();
+        let config = KatzConfig {
+            alpha: 0.5,
+            normalized: false,
+            ..Default::default()
+        };
+        let scores = katz_centrality(&adjacency, &nodes, &config).unwrap();
+        // x2 = 1 + 0.5 * (x1 + x3) with x1 = 1.5
+        assert_eq!(scores, vec![1.0, 1.5, 2.25, 1.0]);
+
+        let scores = katz_centrality(&adjacency, &nodes, &KatzConfig::default()).unwrap();
+        assert!((scores.iter().map(|s| s * s).sum::<f32>() - 1.0).abs() < 1e-4);
+
+        // A 2-cycle has eigenvalue 1, so alpha ≥ 1 diverges
+        let cycle = vec![vec![(1, 0, 1.0)], vec![(0, 1, 1.0)]];
+        let config = KatzConfig {
+            alpha: 1.5,
+            ..Default::default()
+        };
+        assert!(katz_centrality(&cycle, &[0, 1], &config).is_none());
+    }
+
+    #[test]
+    fn test_betweenness() {
+        // Two triangles 0-1-2 and 3-4-5 joined by the bridge 2-3 (edge 6)
+        let pairs = [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5), (2, 3)];
+        let mut adjacency = vec![Vec::new(); 6];
+        for (id, &(u, v)) in pairs.iter().enumerate() {
+            adjacency[u].push((v as u32, id as u32, 1.0));
+            adjacency[v].push((u as u32, id as u32, 1.0));
+        }
+        let nodes: Vec<u32> = (0..6).collect();
+        let config = BetweennessConfig {
+            undirected: true,
+            include_edges: true,
+            ..Default::default()
+        };
+        let exact = betweenness(&adjacency, &nodes, 7, &config);
+
+        // 2 and 3 each sit between their side's other two nodes and the 3
+        // nodes across
+        assert_eq!(exact.nodes, vec![0.0, 0.0, 6.0, 6.0, 0.0, 0.0]);
+        assert_eq!(exact.edges[6], 9.0);
+        assert_eq!(exact.edges[0], 1.0);
+
+        let normalized = betweenness(&adjacency, &nodes, 7, &BetweennessConfig {
+            normalized: true,
+            ..config.clone()
+        });
+        assert_eq!(normalized.nodes[2], 0.6);
+
+        // Sampling still finds the bridge nodes
+        let sampled = betweenness(&adjacency, &nodes, 0, &BetweennessConfig {
+            samples: 3,
+            ..config
+        });
+        assert!(sampled.edges.is_empty());
+        assert!(sampled.nodes[2] > sampled.nodes[0] && sampled.nodes[3] > sampled.nodes[5]);
+    }
+
+
+    #[test]
+    fn test_betweenness_search_in_steps() {
+        // Path of 300 nodes: 100 steps of 3 sources
+        let adjacency: Vec<Vec<Arc>> =
+            (0..300).map(|i| if i < 299 { vec![(i + 1, i, 1.0)] } else { vec![] }).collect();
+        let nodes: Vec<u32> = (0..300).collect();
+        let config = BetweennessConfig::default();
+
+        let mut search = BetweennessSearch::new(&adjacency, &nodes, 0, &config);
+        let mut steps = 1;
+        while !search.step() {
+            assert!(search.progress() > 0.0 && search.progress() < 1.0);
+            steps += 1;
+        }
+        assert_eq!(steps, BETWEENNESS_STEPS);
+        assert_eq!(search.phase(), "done");
+        assert_eq!(search.into_result(), betweenness(&adjacency, &nodes, 0, &config));
+    }
+    #[test]
+    fn test_closeness() {
+        // Path 0 - 1 - 2 - 3 - 4 both ways, and 5 → 0
+        let adjacency = vec![vec![1], vec![0, 2], vec![1, 3], vec![2, 4], vec![3], vec![0]];
+        let nodes: Vec<u32> = (0..6).collect();
+        let scores = closeness(&adjacency, &nodes, &ClosenessConfig::default());
+
+        // The middle node is 1, 1, 2, 2 hops from the rest of the path
+        assert_eq!(scores[2], 4.0 / 6.0);
+        assert_eq!(scores[0], 4.0 / 10.0);
+        // 5 reaches the path one hop further out than 0; nothing reaches 5
+        assert_eq!(scores[5], 5.0 / 15.0);
+
+        // With every pivot sampled the estimate is exact
+        let config = ClosenessConfig {
+            samples: 6,
+            ..Default::default()
+        };
+        assert_eq!(closeness(&adjacency, &nodes, &config), scores);
+    }
+
+    #[test]
+    fn test_harmonic_centrality() {
+        // A 3-node path beside a lone edge
+        let adjacency = vec![vec![1], vec![0, 2], vec![1], vec![4], vec![3]];
+        let nodes: Vec<u32> = (0..5).collect();
+        let scores = harmonic_centrality(&adjacency, &nodes, &HarmonicConfig::default());
+        assert_eq!(scores, vec![1.5, 2.0, 1.5, 1.0, 1.0]);
+
+        // Unlike closeness, the lone edge does not outscore the path's ends
+        let closeness = closeness(&adjacency, &nodes, &ClosenessConfig::default());
+        assert!(closeness[3] > closeness[0]);
+
+        let config = HarmonicConfig {
+            normalized: true,
+            ..Default::default()
+        };
+        assert_eq!(harmonic_centrality(&adjacency, &nodes, &config)[1], 0.5);
+    }
+}
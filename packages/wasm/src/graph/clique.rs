@@ -0,0 +1,210 @@
+//! Maximal clique enumeration.
+//!
+//! A clique is a set of nodes all linked to each other; it is maximal when
+//! no other node links to all of them. Cliques are listed by Bron–Kerbosch
+//! with Tomita's pivoting: each call extends a clique `r` with candidates
+//! `p`, skipping the neighbors of a pivot (every clique through them also
+//! goes through the pivot or one of its non-neighbors), while `x` holds the
+//! nodes already covered so non-maximal cliques are dropped. Following
+//! Eppstein, Löffler and Strash, the outer level walks the nodes in
+//! degeneracy order so every call starts from a small candidate set, which
+//! keeps sparse graphs fast.
+//!
+//! The graph is taken as undirected and simple.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use serde::Deserialize;
+
+/// Configuration for clique enumeration. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CliqueConfig {
+    /// Smallest clique to report (default: 3).
+    pub min_size: u32,
+    /// Stop after this many cliques; 0 lists them all (default: 10 000).
+    pub max_cliques: u32,
+}
+
+impl Default for CliqueConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 3,
+            max_cliques: 10_000,
+        }
+    }
+}
+
+/// Maximal cliques among the slots in `nodes` (the existing nodes), each
+/// sorted ascending. `neighbors` lists each slot's neighbors, sorted and
+/// without the slot itself.
+pub fn maximal_cliques(
+    neighbors: &[Vec<u32>],
+    nodes: &[u32],
+    config: &CliqueConfig,
+) -> Vec<Vec<u32>> {
+    let order = degeneracy_order(neighbors, nodes);
+    let mut rank = vec![0; neighbors.len()];
+    for (position, &node) in order.iter().enumerate() {
+        rank[node as usize] = position;
+    }
+
+    let mut search = Search {
+        neighbors,
+        min_size: config.min_size.max(1) as usize,
+        max_cliques: config.max_cliques as usize,
+        found: Vec::new(),
+    };
+    for &node in &order {
+        if search.is_full() {
+            break;
+        }
+        let (later, earlier) = neighbors[node as usize]
+            .iter()
+            .partition(|&&next| rank[next as usize] > rank[node as usize]);
+        search.expand(&mut vec![node], later, earlier);
+    }
+    search.found
+}
+
+/// Slots of `nodes` by repeatedly taking one of least remaining degree.
+fn degeneracy_order(neighbors: &[Vec<u32>], nodes: &[u32]) -> Vec<u32> {
+    let mut degree: Vec<usize> = neighbors.iter().map(Vec::len).collect();
+    let mut removed = vec![false; neighbors.len()];
+    let mut heap: BinaryHeap<Reverse<(usize, u32)>> =
+        nodes.iter().map(|&node| Reverse((degree[node as usize], node))).collect();
+
+    let mut order = Vec::with_capacity(nodes.len());
+    while let Some(Reverse((node_degree, node))) = heap.pop() {
+        // Skip entries left behind when the degree dropped
+        if removed[node as usize] || node_degree != degree[node as usize] {
+            continue;
+        }
+        removed[node as usize] = true;
+        order.push(node);
+        for &next in &neighbors[node as usize] {
+            if !removed[next as usize] {
+                degree[next as usize] -= 1;
+                heap.push(Reverse((degree[next as usize], next)));
+            }
+        }
+    }
+    order
+}
+
+/// Bron–Kerbosch state shared across calls.
+struct Search<'a> {
+    neighbors: &'a [Vec<u32>],
+    min_size: usize,
+    /// 0 for no cap.
+    max_cliques: usize,
+    found: Vec<Vec<u32>>,
+}
+
+impl Search<'_> {
+    fn is_full(&self) -> bool {
+        self.max_cliques > 0 && self.found.len() >= self.max_cliques
+    }
+
+    /// Report every maximal clique that extends `clique` by candidates from
+    /// `p` and by none of `x`. Both lists are sorted.
+    fn expand(&mut self, clique: &mut Vec<u32>, mut p: Vec<u32>, mut x: Vec<u32>) {
+        if p.is_empty() {
+            if x.is_empty() && clique.len() >= self.min_size {
+                let mut found = clique.clone();
+                found.sort_unstable();
+                self.found.push(found);
+            }
+            return;
+        }
+        if clique.len() + p.len() < self.min_size {
+            return;
+        }
+
+        let neighbors = self.neighbors;
+        let pivot = p
+            .iter()
+            .chain(&x)
+            .copied()
+            .max_by_key(|&u| intersect(&p, &neighbors[u as usize]).len())
+            .unwrap_or(p[0]);
+        let candidates: Vec<u32> = p
+            .iter()
+            .copied()
+            .filter(|v| neighbors[pivot as usize].binary_search(v).is_err())
+            .collect();
+
+        for v in candidates {
+            if self.is_full() {
+                return;
+            }
+            let around = &neighbors[v as usize];
+            clique.push(v);
+            self.expand(clique, intersect(&p, around), intersect(&x, around));
+            clique.pop();
+
+            p.retain(|&u| u != v);
+            let at = x.partition_point(|&u| u < v);
+            x.insert(at, v);
+        }
+    }
+}
+
+/// Values in both sorted lists, ascending.
+fn intersect(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (mut i, mut j) = (0, 0);
+    let mut both = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if a[i] > b[j] {
+            j += 1;
+        } else {
+            both.push(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    both
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maximal_cliques() {
+        // Triangles 0-1-2 and 1-2-3 sharing an edge, K4 on 4..=7, an edge
+        // 7-8, isolated 9 and a vacant slot 10
+        let links = [
+            (0, 1), (0, 2), (1, 2), (1, 3), (2, 3),
+            (4, 5), (4, 6), (4, 7), (5, 6), (5, 7), (6, 7), (7, 8),
+        ];
+        let mut neighbors = vec![Vec::new(); 11];
+        for &(u, v) in &links {
+            neighbors[u as usize].push(v);
+            neighbors[v as usize].push(u);
+        }
+        for list in &mut neighbors {
+            list.sort_unstable();
+        }
+        let nodes: Vec<u32> = (0..10).collect();
+        let cliques = |min_size, max_cliques| {
+            let config = CliqueConfig {
+                min_size,
+                max_cliques,
+            };
+            let mut found = maximal_cliques(&neighbors, &nodes, &config);
+            found.sort();
+            found
+        };
+
+        assert_eq!(cliques(3, 0), vec![vec![0, 1, 2], vec![1, 2, 3], vec![4, 5, 6, 7]]);
+        assert_eq!(cliques(4, 0), vec![vec![4, 5, 6, 7]]);
+        assert_eq!(cliques(1, 0).len(), 5);
+        assert!(cliques(1, 0).contains(&vec![7, 8]) && cliques(1, 0).contains(&vec![9]));
+        assert_eq!(cliques(2, 2).len(), 2);
+    }
+}
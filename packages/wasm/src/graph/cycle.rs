@@ -0,0 +1,191 @@
+//! Elementary cycle enumeration in directed graphs.
+//!
+//! Johnson's algorithm lists every cycle that visits no node twice. Taking
+//! each node `s` in slot order, a depth-first search from `s` over the
+//! nodes of its strongly connected component with slots above `s` finds
+//! the cycles whose lowest node is `s`. A node is blocked while it is on
+//! the path or known not to lead back to `s`, and is unblocked as soon as a
+//! cycle is found through a node it leads to, so no dead end is explored
+//! twice between two cycles.
+//!
+//! A length cap cuts paths short; a node whose search was cut is treated as
+//! leading back to `s`, since a shorter path might reach it later, which
+//! keeps the search exact at some cost in pruning.
+
+use super::traversal::strongly_connected_components;
+
+/// Elementary cycles of `adjacency` (sorted, deduplicated out-neighbor
+/// slots per slot), each as its slots in order from the lowest. Stops after
+/// `max_cycles` cycles and skips cycles of more than `max_length` nodes;
+/// 0 lifts either cap.
+pub fn elementary_cycles(
+    adjacency: &[Vec<u32>],
+    max_cycles: usize,
+    max_length: usize,
+) -> Vec<Vec<u32>> {
+    let component = strongly_connected_components(adjacency);
+    let mut search = Johnson {
+        adjacency,
+        component,
+        start: 0,
+        blocked: vec![false; adjacency.len()],
+        blocking: vec![Vec::new(); adjacency.len()],
+        max_cycles,
+        max_length,
+        cycles: Vec::new(),
+    };
+    for start in 0..adjacency.len() as u32 {
+        if search.is_full() {
+            break;
+        }
+        search.search_from(start);
+    }
+    search.cycles
+}
+
+/// Johnson's search state for the current start slot.
+struct Johnson<'a> {
+    adjacency: &'a [Vec<u32>],
+    component: Vec<u32>,
+    start: u32,
+    blocked: Vec<bool>,
+    /// Slots to unblock along with each slot.
+    blocking: Vec<Vec<u32>>,
+    max_cycles: usize,
+    max_length: usize,
+    cycles: Vec<Vec<u32>>,
+}
+
+/// One slot on the search path.
+struct Frame {
+    node: u32,
+    /// Next out-neighbor to try.
+    cursor: usize,
+    /// Whether a cycle (or a cut path) was found through this slot.
+    closed: bool,
+}
+
+impl Frame {
+    fn at(node: u32) -> Self {
+        Self {
+            node,
+            cursor: 0,
+            closed: false,
+        }
+    }
+}
+
+impl Johnson<'_> {
+    fn is_full(&self) -> bool {
+        self.max_cycles > 0 && self.cycles.len() >= self.max_cycles
+    }
+
+    /// Whether the search from the current start may visit `node`.
+    fn allowed(&self, node: u32) -> bool {
+        node >= self.start
+            && self.component[node as usize] == self.component[self.start as usize]
+    }
+
+    /// List the cycles whose lowest slot is `start`.
+    fn search_from(&mut self, start: u32) {
+        self.start = start;
+        let mut path = vec![start];
+        let mut frames = vec![Frame::at(start)];
+        self.blocked[start as usize] = true;
+
+        while let Some(frame) = frames.last_mut() {
+            let Some(&next) = self.adjacency[frame.node as usize].get(frame.cursor) else {
+                self.leave(&mut frames);
+                path.pop();
+                continue;
+            };
+            frame.cursor += 1;
+            let open = next != start && self.allowed(next) && !self.blocked[next as usize];
+            let cut = self.max_length > 0 && path.len() >= self.max_length;
+            if next == start {
+                frame.closed = true;
+                self.cycles.push(path.clone());
+            } else if open && cut {
+                frame.closed = true;
+            } else if open {
+                self.blocked[next as usize] = true;
+                path.push(next);
+                frames.push(Frame::at(next));
+            }
+            if self.is_full() {
+                break;
+            }
+        }
+        self.reset();
+    }
+
+    /// Pop the last frame for good: unblock its slot if it led to a cycle,
+    /// or else have its allowed out-neighbors unblock it once they do.
+    fn leave(&mut self, frames: &mut Vec<Frame>) {
+        let Some(Frame { node, closed, .. }) = frames.pop() else {
+            return;
+        };
+        if let Some(parent) = frames.last_mut() {
+            parent.closed |= closed;
+        }
+        if closed {
+            self.unblock(node);
+            return;
+        }
+        let targets: Vec<u32> = self.adjacency[node as usize]
+            .iter()
+            .copied()
+            .filter(|&next| next != node && self.allowed(next))
+            .collect();
+        for next in targets {
+            let waiting = &mut self.blocking[next as usize];
+            if !waiting.contains(&node) {
+                waiting.push(node);
+            }
+        }
+    }
+
+    /// Unblock `node` and, in turn, every slot waiting on it.
+    fn unblock(&mut self, node: u32) {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut self.blocked[node as usize], false) {
+                stack.append(&mut self.blocking[node as usize]);
+            }
+        }
+    }
+
+    /// Clear the blocking state of the start's component.
+    fn reset(&mut self) {
+        let component = self.component[self.start as usize];
+        for node in self.start as usize..self.adjacency.len() {
+            if self.component[node] == component {
+                self.blocked[node] = false;
+                self.blocking[node].clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elementary_cycles() {
+        // 0 → 1 → 2 → 0, 1 → 3 → 0, 2 ↔ 4, a self-loop on 5 and a tail 5 → 6
+        let adjacency = vec![vec![1], vec![2, 3], vec![0, 4], vec![0], vec![2], vec![5, 6], vec![]];
+        let cycles = elementary_cycles(&adjacency, 0, 0);
+        assert_eq!(cycles, vec![vec![0, 1, 2], vec![0, 1, 3], vec![2, 4], vec![5]]);
+
+        assert_eq!(elementary_cycles(&adjacency, 2, 0).len(), 2);
+        assert_eq!(elementary_cycles(&adjacency, 0, 2), vec![vec![2, 4], vec![5]]);
+
+        // Complete digraph on 4 nodes: 6 two-cycles, 8 three-cycles and
+        // 6 four-cycles
+        let complete: Vec<Vec<u32>> =
+            (0..4).map(|u| (0..4).filter(|&v| v != u).collect()).collect();
+        assert_eq!(elementary_cycles(&complete, 0, 0).len(), 20);
+        assert_eq!(elementary_cycles(&complete, 0, 3).len(), 14);
+    }
+}
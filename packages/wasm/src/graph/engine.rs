@@ -7,13 +7,40 @@
 use petgraph::stable_graph::{NodeIndex, EdgeIndex, StableGraph};
 use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
 use petgraph::{Directed, Direction};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-
+use std::rc::Rc;
+
+use super::centrality::{
+    self, Betweenness, BetweennessConfig, BetweennessSearch, ClosenessConfig, EigenvectorConfig,
+    HarmonicConfig, KatzConfig, PageRankConfig,
+};
+use super::clique::{self, CliqueConfig};
+use super::cycle;
+use super::dag;
 use super::edge::EdgeId;
+use super::flow::{self, MaxFlow};
+use super::matching;
 use super::node::{NodeId, NodeState};
+use super::path::{self, Arc, BellmanFordPath, Path};
+use super::reachability::ReachabilityIndex;
+use super::stats::{self, GraphStats, RichClubConfig};
+use super::traversal::{self, Traversal};
+use crate::layout::progressive::{self, CancelToken, Cancelled};
+use crate::layout::SENTINEL;
+use crate::layout::constraints::{self, Constraint, ConstraintConfig, ConstraintNodes};
+use crate::layout::snap::{self, SnapConfig};
+use crate::layout::spanning_tree::{self, MinimumSpanningForest};
+use crate::layout::transform::{self, Transform};
+use crate::rng::SeededRng;
+use crate::simulation::force::{ForceSimulation, SimulationFrame};
+use crate::simulation::target;
+use crate::simulation::transition::{Easing, Transition};
 use crate::spatial::SpatialIndex;
 
+/// Weighted adjacency shared between the engine's cache and its queries.
+type SharedArcs = Rc<Vec<Vec<Arc>>>;
+
 /// The core graph engine.
 ///
 /// This struct manages:
@@ -62,6 +89,21 @@ pub struct GraphEngine {
 
     /// Whether the spatial index needs rebuilding
     spatial_dirty: Cell<bool>,
+
+    /// Published (front) X/Y positions when double buffering is enabled.
+    /// The simulation writes `pos_x`/`pos_y`; `swap_buffers` publishes them.
+    front: Option<(Vec<f32>, Vec<f32>)>,
+
+    /// Reachability index, if built; dropped whenever nodes or edges change
+    reachability: Option<ReachabilityIndex>,
+
+    /// Weighted adjacency, directed then undirected, if built; dropped
+    /// whenever nodes or edges change
+    weighted_arcs: [RefCell<Option<SharedArcs>>; 2],
+
+    /// A* admissible scale, if computed; dropped whenever nodes, edges or
+    /// positions change
+    admissible_scale: Cell<Option<f32>>,
 }
 
 impl GraphEngine {
@@ -81,6 +123,10 @@ impl GraphEngine {
             states: Vec::new(),
             spatial: SpatialIndex::new(),
             spatial_dirty: Cell::new(false),
+            front: None,
+            reachability: None,
+            weighted_arcs: Default::default(),
+            admissible_scale: Cell::new(None),
         }
     }
 
@@ -100,6 +146,10 @@ impl GraphEngine {
             states: Vec::with_capacity(node_capacity),
             spatial: SpatialIndex::with_capacity(node_capacity),
             spatial_dirty: Cell::new(false),
+            front: None,
+            reachability: None,
+            weighted_arcs: Default::default(),
+            admissible_scale: Cell::new(None),
         }
     }
 
@@ -113,6 +163,7 @@ impl GraphEngine {
         self.next_node_id += 1;
 
         let index = self.graph.add_node(id);
+        self.invalidate_topology();
         self.node_id_to_index.insert(id, index);
 
         self.pos_x.push(x);
@@ -120,8 +171,14 @@ impl GraphEngine {
         self.vel_x.push(0.0);
         self.vel_y.push(0.0);
         self.states.push(NodeState::new());
+        // Keep the front buffer as long as the working one; the new slot
+        // reads as unplaced until the next swap publishes it
+        if let Some((front_x, front_y)) = &mut self.front {
+            front_x.push(SENTINEL);
+            front_y.push(SENTINEL);
+        }
 
-        self.spatial_dirty.set(true);
+        self.mark_moved();
         id
     }
 
@@ -143,7 +200,7 @@ impl GraphEngine {
             self.add_node(x, y);
         }
 
-        self.spatial_dirty.set(true);
+        self.mark_moved();
         count as u32
     }
 
@@ -173,7 +230,8 @@ impl GraphEngine {
             }
 
             self.graph.remove_node(index);
-            self.spatial_dirty.set(true);
+            self.invalidate_topology();
+            self.mark_moved();
             true
         } else {
             false
@@ -192,6 +250,32 @@ impl GraphEngine {
         self.graph.node_bound() as u32
     }
 
+    /// Mask of slots that currently hold a node (length `node_bound`).
+    ///
+    /// Removed nodes leave holes in the slot space; layouts use this to skip
+    /// them while still placing nodes that have no edges.
+    pub fn occupied_slots(&self) -> Vec<bool> {
+        let mut occupied = vec![false; self.graph.node_bound()];
+        for index in self.graph.node_indices() {
+            occupied[index.index()] = true;
+        }
+        occupied
+    }
+
+    /// Mask of slots holding a pinned node (length `node_bound`).
+    pub fn pinned_slots(&self) -> Vec<bool> {
+        let mut pinned = vec![false; self.graph.node_bound()];
+        for index in self.graph.node_indices() {
+            pinned[index.index()] = self.states[index.index()].is_pinned();
+        }
+        pinned
+    }
+
+    /// Whether a node with this ID exists.
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        self.node_id_to_index.contains_key(&id)
+    }
+
     /// Get a node's position.
     pub fn get_node_position(&self, id: NodeId) -> Option<(f32, f32)> {
         self.node_id_to_index.get(&id).map(|&index| {
@@ -206,7 +290,7 @@ impl GraphEngine {
             let i = index.index();
             self.pos_x[i] = x;
             self.pos_y[i] = y;
-            self.spatial_dirty.set(true);
+            self.mark_moved();
         }
     }
 
@@ -232,6 +316,238 @@ impl GraphEngine {
             .unwrap_or(false)
     }
 
+    /// Offset every unpinned node by a seeded random amount in
+    /// `[-amount, amount]` on each axis.
+    ///
+    /// Nodes are visited in slot order, so the result depends only on the
+    /// graph and the seed.
+    pub fn jitter_positions(&mut self, amount: f32, seed: u64) {
+        let mut rng = SeededRng::new(seed);
+        let mut indices: Vec<NodeIndex> = self.graph.node_indices().collect();
+        indices.sort_unstable();
+
+        for index in indices {
+            let i = index.index();
+            let dx = rng.range_f32(-amount, amount);
+            let dy = rng.range_f32(-amount, amount);
+            if self.states[i].is_pinned() {
+                continue;
+            }
+            self.pos_x[i] += dx;
+            self.pos_y[i] += dy;
+        }
+
+        self.mark_moved();
+    }
+
+    /// Position freshly added nodes without moving any other node.
+    ///
+    /// Each node in `ids` is placed near the centroid of its already placed
+    /// neighbors (in either direction), offset by `spacing` in a seeded random
+    /// direction so siblings do not pile up. Nodes are placed in rounds, so a
+    /// chain of new nodes grows outward from the existing graph. A new node
+    /// with no placed neighbor (disconnected, or only linked to other new
+    /// nodes) goes on the periphery, `spacing` beyond the farthest placed
+    /// node, and its new neighbors follow it. Velocities of placed nodes are
+    /// reset. Unknown IDs are ignored.
+    ///
+    /// Returns the number of nodes placed.
+    pub fn place_new_nodes(&mut self, ids: &[NodeId], spacing: f32, seed: u64) -> u32 {
+        let mut rng = SeededRng::new(seed);
+        let mut pending: Vec<usize> = ids
+            .iter()
+            .filter_map(|id| self.node_id_to_index.get(id).map(|index| index.index()))
+            .collect();
+        pending.sort_unstable();
+        pending.dedup();
+        let mut placed = vec![false; self.pos_x.len()];
+        for index in self.graph.node_indices() {
+            placed[index.index()] = true;
+        }
+        for &i in &pending {
+            placed[i] = false;
+        }
+
+        let count = pending.len() as u32;
+        while !pending.is_empty() {
+            let before = pending.len();
+            pending.retain(|&i| !self.place_near_neighbors(i, &mut placed, spacing, &mut rng));
+            if pending.len() < before {
+                continue;
+            }
+
+            // No new node touches the placed graph: seed the next one outside
+            let i = pending.remove(0);
+            let (x, y) = self.periphery_point(&placed, spacing, &mut rng);
+            self.pos_x[i] = x;
+            self.pos_y[i] = y;
+            placed[i] = true;
+        }
+
+        for index in ids.iter().filter_map(|id| self.node_id_to_index.get(id)) {
+            self.vel_x[index.index()] = 0.0;
+            self.vel_y[index.index()] = 0.0;
+        }
+        self.mark_moved();
+        count
+    }
+
+    /// Place slot `i` `spacing` away from the centroid of its placed
+    /// neighbors. Returns false (and leaves it alone) if it has none.
+    fn place_near_neighbors(
+        &mut self,
+        i: usize,
+        placed: &mut [bool],
+        spacing: f32,
+        rng: &mut SeededRng,
+    ) -> bool {
+        let Some((cx, cy)) = self.placed_neighbor_centroid(i, placed) else {
+            return false;
+        };
+        let angle = rng.range_f32(0.0, std::f32::consts::TAU);
+        self.pos_x[i] = cx + spacing * angle.cos();
+        self.pos_y[i] = cy + spacing * angle.sin();
+        placed[i] = true;
+        true
+    }
+
+    /// Centroid of the placed neighbors of slot `i`, if it has any.
+    fn placed_neighbor_centroid(&self, i: usize, placed: &[bool]) -> Option<(f32, f32)> {
+        let (sum_x, sum_y, n) = self
+            .graph
+            .neighbors_undirected(NodeIndex::new(i))
+            .map(|n| n.index())
+            .filter(|&j| placed[j])
+            .fold((0.0f32, 0.0f32, 0u32), |(x, y, n), j| {
+                (x + self.pos_x[j], y + self.pos_y[j], n + 1)
+            });
+        (n > 0).then(|| (sum_x / n as f32, sum_y / n as f32))
+    }
+
+    /// Random point `spacing` outside the circle around the placed nodes'
+    /// centroid that contains all of them (the origin if none is placed).
+    fn periphery_point(&self, placed: &[bool], spacing: f32, rng: &mut SeededRng) -> (f32, f32) {
+        let slots: Vec<usize> = (0..placed.len()).filter(|&i| placed[i]).collect();
+        if slots.is_empty() {
+            return (0.0, 0.0);
+        }
+        let n = slots.len() as f32;
+        let cx = slots.iter().map(|&i| self.pos_x[i]).sum::<f32>() / n;
+        let cy = slots.iter().map(|&i| self.pos_y[i]).sum::<f32>() / n;
+        let radius = slots
+            .iter()
+            .map(|&i| (self.pos_x[i] - cx).hypot(self.pos_y[i] - cy))
+            .fold(0.0f32, f32::max)
+            + spacing;
+        let angle = rng.range_f32(0.0, std::f32::consts::TAU);
+        (cx + radius * angle.cos(), cy + radius * angle.sin())
+    }
+
+    /// Move unpinned nodes toward layout target positions.
+    ///
+    /// `targets` is interleaved [x0, y0, x1, y1, ...] indexed by node slot, as
+    /// produced by the layout algorithms. Nodes with sentinel targets are left
+    /// in place. Returns the number of nodes moved.
+    pub fn apply_target_attraction(&mut self, targets: &[f32], strength: f32) -> u32 {
+        let graph = &self.graph;
+        let states = &self.states;
+        let moved = target::apply_target_attraction(
+            &mut self.pos_x,
+            &mut self.pos_y,
+            targets,
+            strength,
+            |i| !graph.contains_node(NodeIndex::new(i)) || states[i].is_pinned(),
+        );
+
+        if moved > 0 {
+            self.mark_moved();
+        }
+        moved
+    }
+
+    /// Apply a transform to the positions of `ids` (every node if `None`),
+    /// about their centroid. Velocities are turned and scaled along. Unknown
+    /// IDs are ignored. Returns the number of nodes moved.
+    pub fn transform_positions(&mut self, transform: &Transform, ids: Option<&[NodeId]>) -> u32 {
+        let slots: Vec<usize> = match ids {
+            Some(ids) => ids
+                .iter()
+                .filter_map(|id| self.node_id_to_index.get(id))
+                .map(|index| index.index())
+                .collect(),
+            None => self.graph.node_indices().map(|index| index.index()).collect(),
+        };
+        let pivot = transform::centroid(slots.iter().map(|&i| (self.pos_x[i], self.pos_y[i])));
+        for &i in &slots {
+            (self.pos_x[i], self.pos_y[i]) = transform.apply((self.pos_x[i], self.pos_y[i]), pivot);
+            (self.vel_x[i], self.vel_y[i]) = transform.apply_linear((self.vel_x[i], self.vel_y[i]));
+        }
+
+        self.mark_moved();
+        slots.len() as u32
+    }
+
+    /// Snap node positions to edge angles and a grid, keeping pinned nodes
+    /// in place. Returns the number of nodes moved.
+    pub fn snap_positions(&mut self, config: &SnapConfig) -> u32 {
+        let occupied = self.occupied_slots();
+        let mut positions: Vec<f32> = occupied
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &present)| {
+                if present {
+                    [self.pos_x[i], self.pos_y[i]]
+                } else {
+                    [SENTINEL, SENTINEL]
+                }
+            })
+            .collect();
+        let csr = self.get_edges_csr();
+        let moved = snap::snap_positions(&mut positions, &csr, &self.pinned_slots(), config);
+
+        for (i, _) in occupied.iter().enumerate().filter(|&(_, &present)| present) {
+            (self.pos_x[i], self.pos_y[i]) = (positions[i * 2], positions[i * 2 + 1]);
+        }
+        if moved > 0 {
+            self.mark_moved();
+        }
+        moved
+    }
+
+    /// Start a transition from the current positions to `targets`.
+    ///
+    /// The engine does not hold the transition; drive it with
+    /// [`Self::step_transition`].
+    pub fn start_transition(&self, targets: &[f32], duration: f64, easing: Easing) -> Transition {
+        Transition::new(&self.pos_x, &self.pos_y, targets, duration, easing)
+    }
+
+    /// Write a transition's positions for time `now`, leaving pinned and
+    /// removed nodes in place. Returns true while it is still running.
+    pub fn step_transition(&mut self, transition: &mut Transition, now: f64) -> bool {
+        let graph = &self.graph;
+        let states = &self.states;
+        let running = transition.step(now, &mut self.pos_x, &mut self.pos_y, |i| {
+            !graph.contains_node(NodeIndex::new(i)) || states[i].is_pinned()
+        });
+        self.mark_moved();
+        running
+    }
+
+    /// Move nodes to satisfy layout constraints on node slots, keeping pinned
+    /// nodes in place. Returns the largest remaining violation.
+    pub fn apply_constraints(&mut self, list: &[Constraint], config: &ConstraintConfig) -> f32 {
+        let (present, fixed) = (self.occupied_slots(), self.pinned_slots());
+        let nodes = ConstraintNodes {
+            present: &present,
+            fixed: &fixed,
+        };
+        let violation =
+            constraints::project_constraints(&mut self.pos_x, &mut self.pos_y, &nodes, list, config);
+        self.mark_moved();
+        violation
+    }
+
     // =========================================================================
     // Edge Operations
     // =========================================================================
@@ -245,6 +561,7 @@ impl GraphEngine {
         self.next_edge_id += 1;
 
         let index = self.graph.add_edge(*source_index, *target_index, weight);
+        self.invalidate_topology();
         self.edge_id_to_index.insert(id, index);
         self.edge_index_to_id.insert(index, id);
 
@@ -272,6 +589,7 @@ impl GraphEngine {
         if let Some(index) = self.edge_id_to_index.remove(&id) {
             self.edge_index_to_id.remove(&index);
             self.graph.remove_edge(index);
+            self.invalidate_topology();
             true
         } else {
             false
@@ -283,6 +601,12 @@ impl GraphEngine {
         self.graph.edge_count() as u32
     }
 
+    /// One more than the highest edge ID handed out since the last clear,
+    /// the length of per-edge-ID buffers.
+    pub fn edge_id_bound(&self) -> u32 {
+        self.next_edge_id
+    }
+
     /// Get neighbors of a node.
     pub fn get_neighbors(&self, id: NodeId) -> Vec<u32> {
         self.node_id_to_index
@@ -296,6 +620,379 @@ impl GraphEngine {
             .unwrap_or_default()
     }
 
+    /// Breadth-first traversal from a node over outgoing edges, or edges in
+    /// both directions when `undirected`. `None` if the node does not exist.
+    pub fn bfs_from(&self, id: NodeId, undirected: bool) -> Option<Traversal> {
+        let &index = self.node_id_to_index.get(&id)?;
+        Some(traversal::breadth_first(&self.adjacency(undirected), index.index()))
+    }
+
+    /// Depth-first traversal from a node over outgoing edges, or edges in
+    /// both directions when `undirected`. `None` if the node does not exist.
+    pub fn dfs_from(&self, id: NodeId, undirected: bool) -> Option<Traversal> {
+        let &index = self.node_id_to_index.get(&id)?;
+        Some(traversal::depth_first(&self.adjacency(undirected), index.index()))
+    }
+
+    /// Personalized PageRank score per slot (length `node_bound`),
+    /// restarting at `seeds`; nodes that do not exist are skipped. No seeds
+    /// seeds every node, giving classic PageRank.
+    pub fn personalized_pagerank(&self, seeds: &[NodeId], config: &PageRankConfig) -> Vec<f32> {
+        let slots: Vec<u32> = if seeds.is_empty() {
+            self.graph.node_indices().map(|index| index.index() as u32).collect()
+        } else {
+            seeds
+                .iter()
+                .filter_map(|id| self.node_id_to_index.get(id))
+                .map(|index| index.index() as u32)
+                .collect()
+        };
+        let adjacency = self.weighted_adjacency(config.undirected);
+        centrality::personalized_pagerank(&adjacency, &slots, config)
+    }
+
+    /// Betweenness centrality per slot (length `node_bound`) and, if
+    /// requested, per edge ID (length `edge_id_bound`).
+    pub fn betweenness(&self, config: &BetweennessConfig) -> Betweenness {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        let adjacency = self.weighted_adjacency(config.undirected);
+        centrality::betweenness(&adjacency, &nodes, self.next_edge_id as usize, config)
+    }
+
+    /// Betweenness like [`Self::betweenness`], calling `report(progress,
+    /// phase)` after every batch of sources and stopping with
+    /// `Err(Cancelled)` once `token` is cancelled.
+    pub fn betweenness_reported(
+        &self,
+        config: &BetweennessConfig,
+        token: &CancelToken,
+        report: impl FnMut(f32, &'static str),
+    ) -> Result<Betweenness, Cancelled> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        let adjacency = self.weighted_adjacency(config.undirected);
+        let mut search =
+            BetweennessSearch::new(&adjacency, &nodes, self.next_edge_id as usize, config);
+        progressive::run_to_end(&mut search, token, report)?;
+        Ok(search.into_result())
+    }
+
+    /// Closeness centrality per slot (length `node_bound`).
+    pub fn closeness(&self, config: &ClosenessConfig) -> Vec<f32> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        centrality::closeness(&self.adjacency(config.undirected), &nodes, config)
+    }
+
+    /// Harmonic centrality per slot (length `node_bound`).
+    pub fn harmonic_centrality(&self, config: &HarmonicConfig) -> Vec<f32> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        centrality::harmonic_centrality(&self.adjacency(config.undirected), &nodes, config)
+    }
+
+    /// Eigenvector centrality per slot (length `node_bound`), or `None` if
+    /// power iteration does not converge.
+    pub fn eigenvector_centrality(&self, config: &EigenvectorConfig) -> Option<Vec<f32>> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        let adjacency = self.weighted_adjacency(config.undirected);
+        centrality::eigenvector_centrality(&adjacency, &nodes, config)
+    }
+
+    /// Katz centrality per slot (length `node_bound`), or `None` if it does
+    /// not converge (usually because `alpha` is too large).
+    pub fn katz_centrality(&self, config: &KatzConfig) -> Option<Vec<f32>> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        let adjacency = self.weighted_adjacency(config.undirected);
+        centrality::katz_centrality(&adjacency, &nodes, config)
+    }
+
+    /// Maximal cliques of the graph taken as undirected and simple, as
+    /// sorted slot lists in the order found.
+    pub fn maximal_cliques(&self, config: &CliqueConfig) -> Vec<Vec<u32>> {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        clique::maximal_cliques(&self.simple_adjacency(), &nodes, config)
+    }
+
+    /// Minimum spanning forest by edge weight, edges counting as undirected,
+    /// with its edge IDs (ascending) and a parent slot per slot. Each
+    /// component is rooted like `extractSpanningTree`, `root` first.
+    pub fn minimum_spanning_forest(&self, root: Option<NodeId>) -> MinimumSpanningForest {
+        let root = root
+            .and_then(|id| self.node_id_to_index.get(&id))
+            .map(|index| index.index() as u32);
+        let mut forest = spanning_tree::minimum_spanning_forest_with_parents(
+            self.graph.node_bound(),
+            &self.weighted_edges(),
+            root,
+        );
+        let ids: Vec<u32> =
+            self.graph.edge_references().map(|edge| self.edge_index_to_id[&edge.id()].0).collect();
+        forest.edges = forest.edges.iter().map(|&index| ids[index as usize]).collect();
+        forest.edges.sort_unstable();
+        forest
+    }
+
+    /// IDs of the edges in a maximum cardinality matching of the graph
+    /// taken as undirected and simple, ascending. Of parallel edges between
+    /// a matched pair, the lowest ID is used.
+    pub fn maximum_matching(&self) -> Vec<u32> {
+        let mate = matching::maximum_matching(&self.simple_adjacency());
+        let mut matched: Vec<((usize, usize), u32)> = self
+            .graph
+            .edge_references()
+            .map(|edge| (edge.source().index(), edge.target().index(), edge.id()))
+            .filter(|&(source, target, _)| source != target && mate[source] == target as u32)
+            .map(|(source, target, index)| {
+                ((source.min(target), source.max(target)), self.edge_index_to_id[&index].0)
+            })
+            .collect();
+        matched.sort_unstable();
+        matched.dedup_by_key(|&mut (pair, _)| pair);
+        let mut ids: Vec<u32> = matched.into_iter().map(|(_, id)| id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Summary statistics over the graph taken as undirected and simple
+    /// (the edge count still counts every edge).
+    pub fn graph_stats(&self) -> GraphStats {
+        let nodes: Vec<u32> = self.graph.node_indices().map(|index| index.index() as u32).collect();
+        let links = stats::links(&self.adjacency(true));
+        let edge_count = self.graph.edge_count() as u32;
+        stats::graph_stats(&links, &nodes, self.graph.node_bound(), edge_count)
+    }
+
+    /// Rich-club coefficient for each degree threshold `0..=max_k`, over the
+    /// graph taken as undirected and simple. NaN where fewer than two nodes
+    /// have a degree above the threshold.
+    pub fn rich_club_coefficients(&self, max_k: u32, config: &RichClubConfig) -> Vec<f32> {
+        let links = stats::links(&self.adjacency(true));
+        stats::rich_club(&links, self.graph.node_bound(), max_k, config)
+    }
+
+    /// Elementary directed cycles as slot sequences, each from its lowest
+    /// slot, ordered by that slot. At most `max_cycles` cycles of at most
+    /// `max_length` nodes; 0 lifts either cap.
+    pub fn elementary_cycles(&self, max_cycles: u32, max_length: u32) -> Vec<Vec<u32>> {
+        cycle::elementary_cycles(&self.adjacency(false), max_cycles as usize, max_length as usize)
+    }
+
+    /// IDs of the edges implied by others (`u → v` where `v` is also
+    /// reached through another path), ascending; hiding them leaves the
+    /// transitive reduction. `None` if the graph has a cycle.
+    pub fn transitive_reduction(&self) -> Option<Vec<u32>> {
+        dag::transitive_reduction(&self.weighted_adjacency(false))
+    }
+
+    /// Every pair of distinct slots where the first reaches the second,
+    /// flattened [source0, target0, ...] and sorted. `None` if the graph
+    /// has a cycle.
+    pub fn transitive_closure(&self) -> Option<Vec<u32>> {
+        dag::transitive_closure(&self.weighted_adjacency(false))
+    }
+
+    /// Build the reachability index `is_reachable` answers from until nodes
+    /// or edges next change. `seed` picks the index's random visiting order.
+    pub fn build_reachability_index(&mut self, seed: u64) {
+        self.reachability = Some(ReachabilityIndex::new(&self.adjacency(false), seed));
+    }
+
+    /// Whether the reachability index is built and current.
+    pub fn has_reachability_index(&self) -> bool {
+        self.reachability.is_some()
+    }
+
+    /// Whether `target` can be reached from `source` along edge directions
+    /// (every node reaches itself). Uses the reachability index if one is
+    /// current, and a breadth-first search otherwise. `None` if either node
+    /// does not exist.
+    pub fn is_reachable(&self, source: NodeId, target: NodeId) -> Option<bool> {
+        let &source = self.node_id_to_index.get(&source)?;
+        let &target = self.node_id_to_index.get(&target)?;
+        let (source, target) = (source.index(), target.index());
+        Some(match &self.reachability {
+            Some(index) => index.is_reachable(source, target),
+            None => {
+                let traversal = traversal::breadth_first(&self.adjacency(false), source);
+                traversal.depths[target] != traversal::UNREACHED
+            }
+        })
+    }
+
+    /// Lightest path between two nodes by Dijkstra's algorithm over edge
+    /// weights, which must be non-negative. Follows edges forwards, or both
+    /// ways when `undirected`. `None` if either node does not exist or no
+    /// path connects them.
+    pub fn shortest_path(&self, source: NodeId, target: NodeId, undirected: bool) -> Option<Path> {
+        let &source = self.node_id_to_index.get(&source)?;
+        let &target = self.node_id_to_index.get(&target)?;
+        path::dijkstra(&self.weighted_adjacency(undirected), source.index(), target.index())
+    }
+
+    /// Up to `k` loopless paths between two nodes, lightest first (Yen's
+    /// algorithm). Weights must be non-negative. Empty if either node does
+    /// not exist or no path connects them.
+    pub fn k_shortest_paths(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        k: usize,
+        undirected: bool,
+    ) -> Vec<Path> {
+        match (self.node_id_to_index.get(&source), self.node_id_to_index.get(&target)) {
+            (Some(source), Some(target)) => path::k_shortest_paths(
+                &self.weighted_adjacency(undirected),
+                source.index(),
+                target.index(),
+                k,
+            ),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Shortest path between two nodes by A*, estimating the remaining
+    /// distance as `scale` times the straight-line distance to the target.
+    ///
+    /// Without a scale, the largest one that never overestimates is used:
+    /// the smallest ratio of edge weight to edge length, which keeps the
+    /// path a shortest one. Larger scales search less and may return a
+    /// longer path. Weights must be non-negative. `None` if either node does
+    /// not exist or no path connects them.
+    pub fn shortest_path_a_star(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        undirected: bool,
+        scale: Option<f32>,
+    ) -> Option<Path> {
+        let &source = self.node_id_to_index.get(&source)?;
+        let &target = self.node_id_to_index.get(&target)?;
+        let scale = scale.unwrap_or_else(|| self.admissible_scale());
+        let (tx, ty) = (self.pos_x[target.index()], self.pos_y[target.index()]);
+        let estimate = |slot: usize| {
+            let remaining = (self.pos_x[slot] - tx).hypot(self.pos_y[slot] - ty);
+            if remaining.is_finite() {
+                remaining * scale
+            } else {
+                0.0
+            }
+        };
+        path::a_star(
+            &self.weighted_adjacency(undirected),
+            source.index(),
+            target.index(),
+            estimate,
+        )
+    }
+
+    /// Shortest path between two nodes by Bellman–Ford, which allows
+    /// negative weights and reports a reachable negative cycle instead of a
+    /// path. `None` if either node does not exist.
+    pub fn shortest_path_bellman_ford(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        undirected: bool,
+    ) -> Option<BellmanFordPath> {
+        let &source = self.node_id_to_index.get(&source)?;
+        let &target = self.node_id_to_index.get(&target)?;
+        let adjacency = self.weighted_adjacency(undirected);
+        Some(path::bellman_ford(&adjacency, source.index(), target.index()))
+    }
+
+    /// Maximum flow between two nodes with edge weights as capacities, and
+    /// the edges of a minimum cut. With `undirected`, edges carry flow
+    /// either way. `None` if either node does not exist or a weight is
+    /// negative, NaN or infinite.
+    pub fn max_flow(&self, source: NodeId, target: NodeId, undirected: bool) -> Option<MaxFlow> {
+        let &source = self.node_id_to_index.get(&source)?;
+        let &target = self.node_id_to_index.get(&target)?;
+        flow::max_flow(
+            &self.weighted_adjacency(false),
+            source.index(),
+            target.index(),
+            undirected,
+            self.next_edge_id as usize,
+        )
+    }
+
+    /// Smallest edge weight per unit of edge length (0 without edges of
+    /// measurable length). Kept until nodes, edges or positions change.
+    fn admissible_scale(&self) -> f32 {
+        if let Some(scale) = self.admissible_scale.get() {
+            return scale;
+        }
+        let scale = self
+            .graph
+            .edge_references()
+            .filter_map(|edge| {
+                let (u, v) = (edge.source().index(), edge.target().index());
+                let length = (self.pos_x[u] - self.pos_x[v]).hypot(self.pos_y[u] - self.pos_y[v]);
+                (length.is_finite() && length > f32::EPSILON).then(|| *edge.weight() / length)
+            })
+            .reduce(f32::min)
+            .unwrap_or(0.0);
+        self.admissible_scale.set(Some(scale));
+        scale
+    }
+
+    /// Outgoing arcs per slot (length `node_bound`), with each edge also
+    /// reversed when `undirected`. Built on first use and shared until nodes
+    /// or edges change, so repeated path queries don't rescan the edges.
+    fn weighted_adjacency(&self, undirected: bool) -> SharedArcs {
+        let cached = &self.weighted_arcs[usize::from(undirected)];
+        let mut cached = cached.borrow_mut();
+        Rc::clone(cached.get_or_insert_with(|| Rc::new(self.build_weighted_adjacency(undirected))))
+    }
+
+    /// Build the arcs of `weighted_adjacency`.
+    fn build_weighted_adjacency(&self, undirected: bool) -> Vec<Vec<Arc>> {
+        let mut adjacency = vec![Vec::new(); self.graph.node_bound()];
+        for edge in self.graph.edge_references() {
+            let id = self.edge_index_to_id[&edge.id()].0;
+            let (source, target) = (edge.source().index(), edge.target().index());
+            adjacency[source].push((target as u32, id, *edge.weight()));
+            if undirected {
+                adjacency[target].push((source as u32, id, *edge.weight()));
+            }
+        }
+        adjacency
+    }
+
+    /// Sorted, deduplicated neighbor slots per slot (length `node_bound`).
+    fn adjacency(&self, undirected: bool) -> Vec<Vec<u32>> {
+        let mut adjacency = vec![Vec::new(); self.graph.node_bound()];
+        for edge in self.graph.edge_references() {
+            let (source, target) = (edge.source().index(), edge.target().index());
+            adjacency[source].push(target as u32);
+            if undirected {
+                adjacency[target].push(source as u32);
+            }
+        }
+        for neighbors in &mut adjacency {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        adjacency
+    }
+
+    /// Neighbor slots per slot ignoring direction, sorted and without
+    /// self-loops (length `node_bound`).
+    fn simple_adjacency(&self) -> Vec<Vec<u32>> {
+        let mut adjacency = self.adjacency(true);
+        for (slot, neighbors) in adjacency.iter_mut().enumerate() {
+            neighbors.retain(|&next| next as usize != slot);
+        }
+        adjacency
+    }
+
+    /// Get every edge as (source slot, target slot, weight), in edge-index
+    /// order.
+    pub fn weighted_edges(&self) -> Vec<(u32, u32, f32)> {
+        self.graph
+            .edge_references()
+            .map(|e| (e.source().index() as u32, e.target().index() as u32, *e.weight()))
+            .collect()
+    }
+
     // =========================================================================
     // Buffer Access
     // =========================================================================
@@ -310,6 +1007,45 @@ impl GraphEngine {
         &self.pos_y
     }
 
+    /// Enable or disable double-buffered position output.
+    ///
+    /// When enabled, `published_positions_x/y` return a front buffer that
+    /// only changes on `swap_buffers`, so a reader never observes a
+    /// partially written tick. Enabling publishes the current positions.
+    pub fn set_double_buffered(&mut self, enabled: bool) {
+        self.front = enabled.then(|| (self.pos_x.clone(), self.pos_y.clone()));
+    }
+
+    /// Check whether double-buffered output is enabled.
+    pub fn is_double_buffered(&self) -> bool {
+        self.front.is_some()
+    }
+
+    /// Publish the working positions to the front buffer.
+    ///
+    /// No-op when double buffering is disabled.
+    pub fn swap_buffers(&mut self) {
+        if let Some((front_x, front_y)) = &mut self.front {
+            front_x.clone_from(&self.pos_x);
+            front_y.clone_from(&self.pos_y);
+        }
+    }
+
+    /// X positions as seen by readers: the front buffer when double
+    /// buffering is enabled, the working buffer otherwise.
+    ///
+    /// The front buffer always has one entry per slot, but its values lag
+    /// until the next `swap_buffers`: nodes added since read as `SENTINEL`,
+    /// and moved or removed nodes keep their last published position.
+    pub fn published_positions_x(&self) -> &[f32] {
+        self.front.as_ref().map_or(&self.pos_x, |(front_x, _)| front_x)
+    }
+
+    /// Y positions as seen by readers (see `published_positions_x`).
+    pub fn published_positions_y(&self) -> &[f32] {
+        self.front.as_ref().map_or(&self.pos_y, |(_, front_y)| front_y)
+    }
+
     /// Get X velocities slice.
     pub fn velocities_x(&self) -> &[f32] {
         &self.vel_x
@@ -320,6 +1056,105 @@ impl GraphEngine {
         &self.vel_y
     }
 
+    // =========================================================================
+    // Simulation
+    // =========================================================================
+
+    /// Advance the CPU force simulation by one tick.
+    ///
+    /// When the simulation has a viewport, only nodes inside it are simulated;
+    /// nodes outside keep their position and velocity, acting as fixed spring
+    /// endpoints for edges that cross the viewport boundary. Pinned nodes
+    /// exert forces but never move, as do nodes frozen by adaptive freezing.
+    ///
+    /// Returns the number of nodes that moved.
+    pub fn tick(&mut self, simulation: &mut ForceSimulation) -> u32 {
+        let mut frame = self.build_simulation_frame(simulation);
+        let moved = simulation.step(&mut frame);
+
+        for entry in 0..frame.participants {
+            if !frame.movable[entry] {
+                continue;
+            }
+            let i = frame.slots[entry];
+            self.pos_x[i] = frame.pos_x[entry];
+            self.pos_y[i] = frame.pos_y[entry];
+            self.vel_x[i] = frame.vel_x[entry];
+            self.vel_y[i] = frame.vel_y[entry];
+        }
+
+        if moved > 0 {
+            self.mark_moved();
+        }
+        moved
+    }
+
+    /// Gather the nodes taking part in a tick into a compact frame.
+    ///
+    /// Participants (in slot order) come first, followed by anchors: nodes
+    /// outside the viewport that share an edge with a participant.
+    fn build_simulation_frame(&self, simulation: &ForceSimulation) -> SimulationFrame {
+        let viewport = simulation.viewport();
+        let mut compact = vec![u32::MAX; self.graph.node_bound()];
+        let mut frame = SimulationFrame::default();
+
+        for index in self.graph.node_indices() {
+            let i = index.index();
+            if viewport.is_some_and(|v| !v.contains(self.pos_x[i], self.pos_y[i])) {
+                continue;
+            }
+            compact[i] = frame.slots.len() as u32;
+            frame.slots.push(i);
+            frame.movable.push(!self.states[i].is_pinned() && !simulation.is_frozen(i));
+        }
+        frame.participants = frame.slots.len();
+
+        // Edges touching at least one participant: (source, target, edge id)
+        // with endpoints in compact indices
+        let mut edges: Vec<(u32, u32, u32)> = Vec::new();
+        for entry in 0..frame.participants {
+            let index = NodeIndex::new(frame.slots[entry]);
+            for edge in self.graph.edges_directed(index, Direction::Outgoing) {
+                let target = Self::compact_entry(&mut compact, &mut frame.slots, edge.target().index());
+                edges.push((entry as u32, target, self.edge_id_of(edge.id())));
+            }
+            // Edges between two participants were already added from the source
+            let outside_sources: Vec<(usize, u32)> = self
+                .graph
+                .edges_directed(index, Direction::Incoming)
+                .map(|edge| (edge.source().index(), self.edge_id_of(edge.id())))
+                .filter(|&(source, _)| compact[source] as usize >= frame.participants)
+                .collect();
+            for (source, edge_id) in outside_sources {
+                let source = Self::compact_entry(&mut compact, &mut frame.slots, source);
+                edges.push((source, entry as u32, edge_id));
+            }
+        }
+
+        for &i in &frame.slots {
+            frame.pos_x.push(self.pos_x[i]);
+            frame.pos_y.push(self.pos_y[i]);
+            frame.vel_x.push(self.vel_x[i]);
+            frame.vel_y.push(self.vel_y[i]);
+        }
+        (frame.csr, frame.edge_ids) = edges_to_csr(frame.slots.len(), &edges);
+        frame
+    }
+
+    /// Stable id of an edge, or `u32::MAX` if it is unknown.
+    fn edge_id_of(&self, index: EdgeIndex) -> u32 {
+        self.edge_index_to_id.get(&index).map_or(u32::MAX, |id| id.0)
+    }
+
+    /// Compact index of `slot`, appending it as an anchor on first use.
+    fn compact_entry(compact: &mut [u32], slots: &mut Vec<usize>, slot: usize) -> u32 {
+        if compact[slot] == u32::MAX {
+            compact[slot] = slots.len() as u32;
+            slots.push(slot);
+        }
+        compact[slot]
+    }
+
     // =========================================================================
     // Spatial Queries
     // =========================================================================
@@ -361,6 +1196,21 @@ impl GraphEngine {
         self.spatial_dirty.set(false);
     }
 
+    /// Note that node positions changed: the spatial index and the A*
+    /// scale are stale.
+    fn mark_moved(&self) {
+        self.spatial_dirty.set(true);
+        self.admissible_scale.set(None);
+    }
+
+    /// Drop the indices built over nodes and edges; called whenever either
+    /// changes.
+    fn invalidate_topology(&mut self) {
+        self.reachability = None;
+        self.weighted_arcs = Default::default();
+        self.admissible_scale.set(None);
+    }
+
     fn ensure_spatial_index_up_to_date(&self) {
         if self.spatial_dirty.get() {
             // Note: spatial index rebuild requires &mut self for the spatial field.
@@ -408,6 +1258,7 @@ impl GraphEngine {
     /// Clear all nodes and edges, resetting the engine to its initial state.
     pub fn clear(&mut self) {
         self.graph.clear();
+        self.invalidate_topology();
         self.node_id_to_index.clear();
         self.edge_id_to_index.clear();
         self.edge_index_to_id.clear();
@@ -420,6 +1271,7 @@ impl GraphEngine {
         self.states.clear();
         self.spatial.clear();
         self.spatial_dirty.set(false);
+        self.swap_buffers();
     }
 
     /// Get edge list in CSR format.
@@ -447,7 +1299,22 @@ impl GraphEngine {
             offsets[i] += offsets[i - 1];
         }
 
-        // Build targets array
+        #[cfg(feature = "threads")]
+        self.fill_csr_targets_parallel(&offsets, &mut targets);
+        #[cfg(not(feature = "threads"))]
+        self.fill_csr_targets(&offsets, &mut targets);
+
+        // Combine offsets and targets
+        let mut result = Vec::with_capacity(offsets.len() + targets.len());
+        result.extend(offsets);
+        result.extend(targets);
+        result
+    }
+
+    /// Fill the CSR targets array in edge-index order.
+    #[cfg(not(feature = "threads"))]
+    fn fill_csr_targets(&self, offsets: &[u32], targets: &mut [u32]) {
+        let node_bound = offsets.len() - 1;
         let mut current_offsets = offsets[..node_bound].to_vec();
         for edge in self.graph.edge_references() {
             let source = edge.source().index();
@@ -461,12 +1328,42 @@ impl GraphEngine {
                 current_offsets[source] += 1;
             }
         }
+    }
+
+    /// Parallel variant of `fill_csr_targets`.
+    ///
+    /// Each node owns a disjoint slice of `targets`, so rows are filled
+    /// independently. Within a row, edges are sorted by edge index to match
+    /// the sequential ordering exactly.
+    #[cfg(feature = "threads")]
+    fn fill_csr_targets_parallel(&self, offsets: &[u32], targets: &mut [u32]) {
+        use rayon::prelude::*;
+
+        let node_bound = offsets.len() - 1;
+        let mut rows: Vec<(usize, &mut [u32])> = Vec::with_capacity(node_bound);
+        let mut rest = targets;
+        for i in 0..node_bound {
+            let len = (offsets[i + 1] - offsets[i]) as usize;
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(len);
+            rows.push((i, row));
+            rest = tail;
+        }
 
-        // Combine offsets and targets
-        let mut result = Vec::with_capacity(offsets.len() + targets.len());
-        result.extend(offsets);
-        result.extend(targets);
-        result
+        // Borrow only the graph: the engine itself isn't Sync (spatial_dirty is a Cell)
+        let graph = &self.graph;
+        rows.into_par_iter().for_each(|(i, row)| {
+            if row.is_empty() {
+                return;
+            }
+            let mut edges: Vec<(EdgeIndex, u32)> = graph
+                .edges(NodeIndex::new(i))
+                .map(|e| (e.id(), e.target().index() as u32))
+                .collect();
+            edges.sort_unstable_by_key(|&(id, _)| id);
+            for (slot, (_, target)) in row.iter_mut().zip(edges) {
+                *slot = target;
+            }
+        });
     }
 
     /// Get inverse edge list in CSR format (incoming edges).
@@ -545,6 +1442,47 @@ impl GraphEngine {
 
         degrees
     }
+
+    /// Get node strengths (summed out-edge weight, summed in-edge weight)
+    /// as a flat array.
+    ///
+    /// Returns [out_0, in_0, out_1, in_1, ...] with 2 * node_bound elements,
+    /// laid out like `get_node_degrees`. A self-loop counts on both sides.
+    pub fn get_node_strengths(&self) -> Vec<f32> {
+        let mut strengths = vec![0.0f32; self.graph.node_bound() * 2];
+        for edge in self.graph.edge_references() {
+            strengths[edge.source().index() * 2] += *edge.weight();
+            strengths[edge.target().index() * 2 + 1] += *edge.weight();
+        }
+        strengths
+    }
+}
+
+/// Build a CSR buffer [offsets...(node_count+1), targets...] from
+/// (source, target, edge id) triples.
+///
+/// Also returns the edge id of every CSR target entry.
+fn edges_to_csr(node_count: usize, edges: &[(u32, u32, u32)]) -> (Vec<u32>, Vec<u32>) {
+    let mut offsets = vec![0u32; node_count + 1];
+    for &(source, _, _) in edges {
+        offsets[source as usize + 1] += 1;
+    }
+    for i in 1..=node_count {
+        offsets[i] += offsets[i - 1];
+    }
+
+    let mut targets = vec![0u32; edges.len()];
+    let mut ids = vec![0u32; edges.len()];
+    let mut current = offsets[..node_count].to_vec();
+    for &(source, target, id) in edges {
+        let slot = current[source as usize] as usize;
+        targets[slot] = target;
+        ids[slot] = id;
+        current[source as usize] += 1;
+    }
+
+    offsets.extend(targets);
+    (offsets, ids)
 }
 
 impl Default for GraphEngine {
@@ -556,6 +1494,7 @@ impl Default for GraphEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulation::force::{SimulationConfig, Viewport};
 
     #[test]
     fn test_add_node() {
@@ -603,6 +1542,71 @@ mod tests {
         assert!(neighbors.contains(&c.0));
     }
 
+    #[test]
+    fn test_shortest_path_direction() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(1.0, 0.0);
+        let c = engine.add_node(2.0, 0.0);
+        engine.add_edge(a, b, 2.0);
+        let back = engine.add_edge(c, b, 3.0).unwrap();
+
+        assert!(engine.shortest_path(a, c, false).is_none());
+        let path = engine.shortest_path(a, c, true).unwrap();
+        assert_eq!(path.nodes, vec![a.0, b.0, c.0]);
+        assert_eq!(path.edges[1], back.0);
+        assert_eq!(path.distance, 5.0);
+
+        // The cached adjacency follows edits
+        let direct = engine.add_edge(a, c, 1.0).unwrap();
+        assert_eq!(engine.shortest_path(a, c, false).unwrap().edges, vec![direct.0]);
+        engine.remove_edge(direct);
+        assert!(engine.shortest_path(a, c, false).is_none());
+    }
+
+    #[test]
+    fn test_reachability_index_tracks_edits() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(1.0, 0.0);
+        let c = engine.add_node(2.0, 0.0);
+        engine.add_edge(a, b, 1.0);
+        engine.build_reachability_index(0);
+
+        assert!(engine.has_reachability_index());
+        assert_eq!(engine.is_reachable(a, b), Some(true));
+        assert_eq!(engine.is_reachable(b, a), Some(false));
+        assert_eq!(engine.is_reachable(a, NodeId(9)), None);
+
+        engine.add_edge(b, c, 1.0);
+        assert!(!engine.has_reachability_index());
+        assert_eq!(engine.is_reachable(a, c), Some(true));
+    }
+
+    #[test]
+    fn test_a_star_finds_shortest_path() {
+        // Square a-b-c-d where the short way round leads away from c at first
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(10.0, 0.0);
+        let c = engine.add_node(20.0, 0.0);
+        let d = engine.add_node(-10.0, 0.0);
+        engine.add_edge(a, b, 30.0);
+        engine.add_edge(b, c, 30.0);
+        engine.add_edge(a, d, 10.0);
+        engine.add_edge(d, c, 30.0);
+
+        let path = engine.shortest_path_a_star(a, c, false, None).unwrap();
+        assert_eq!(path.nodes, vec![a.0, d.0, c.0]);
+        assert_eq!(path, engine.shortest_path(a, c, false).unwrap());
+
+        // Moving d far away shrinks the admissible scale; the cached one
+        // would now overestimate and miss the short way round
+        engine.set_node_position(d, -1000.0, 0.0);
+        let path = engine.shortest_path_a_star(a, c, false, None).unwrap();
+        assert_eq!(path.nodes, vec![a.0, d.0, c.0]);
+    }
+
     #[test]
     fn test_pin_unpin() {
         let mut engine = GraphEngine::new();
@@ -617,6 +1621,263 @@ mod tests {
         assert!(!engine.is_node_pinned(id));
     }
 
+    #[test]
+    fn test_target_attraction_skips_pinned_and_removed() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(0.0, 0.0);
+        let c = engine.add_node(0.0, 0.0);
+        engine.pin_node(b);
+        engine.remove_node(c);
+
+        let targets = [10.0, 10.0, 10.0, 10.0, 10.0, 10.0];
+        let moved = engine.apply_target_attraction(&targets, 0.25);
+
+        assert_eq!(moved, 1);
+        assert_eq!(engine.get_node_position(a), Some((2.5, 2.5)));
+        assert_eq!(engine.get_node_position(b), Some((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_transform_subset_about_its_centroid() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(10.0, 0.0);
+        let c = engine.add_node(50.0, 50.0);
+        let transform = Transform {
+            mirror: true,
+            translate: (0.0, 5.0),
+            ..Default::default()
+        };
+
+        assert_eq!(engine.transform_positions(&transform, Some(&[a, b, NodeId(99)])), 2);
+        assert_eq!(engine.get_node_position(a), Some((10.0, 5.0)));
+        assert_eq!(engine.get_node_position(b), Some((0.0, 5.0)));
+        assert_eq!(engine.get_node_position(c), Some((50.0, 50.0)));
+    }
+
+    #[test]
+    fn test_transition_skips_pinned_nodes() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(0.0, 0.0);
+        engine.pin_node(b);
+
+        let targets = [10.0, 10.0, 10.0, 10.0];
+        let mut transition = engine.start_transition(&targets, 100.0, Easing::Linear);
+        assert!(engine.step_transition(&mut transition, 0.0));
+        assert!(engine.step_transition(&mut transition, 50.0));
+        assert_eq!(engine.get_node_position(a), Some((5.0, 5.0)));
+        assert!(!engine.step_transition(&mut transition, 100.0));
+        assert_eq!(engine.get_node_position(a), Some((10.0, 10.0)));
+        assert_eq!(engine.get_node_position(b), Some((0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_tick_moves_unpinned_nodes() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(-1.0, 0.0);
+        let b = engine.add_node(1.0, 0.0);
+        engine.pin_node(b);
+
+        let moved = engine.tick(&mut ForceSimulation::default());
+
+        assert_eq!(moved, 1);
+        assert!(engine.get_node_position(a).unwrap().0 < -1.0);
+        assert_eq!(engine.get_node_position(b), Some((1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_moving_a_pinned_node_wakes_frozen_neighbors() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(30.0, 0.0);
+        engine.add_edge(a, b, 1.0);
+        engine.pin_node(b);
+
+        let mut simulation = ForceSimulation::new(SimulationConfig {
+            freeze_threshold: 0.01,
+            freeze_ticks: 2,
+            repulsion: 0.0,
+            gravity: 0.0,
+            theta: 0.0,
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            engine.tick(&mut simulation);
+        }
+        assert!(simulation.is_frozen(0));
+
+        engine.set_node_position(b, 100.0, 0.0);
+        engine.tick(&mut simulation);
+        assert!(!simulation.is_frozen(0), "Dragging a pinned node should wake its neighbors");
+        engine.tick(&mut simulation);
+        assert!(engine.get_node_position(a).unwrap().0 > 0.0, "The woken node follows");
+    }
+
+    #[test]
+    fn test_tick_viewport_freezes_offscreen_nodes() {
+        let mut engine = GraphEngine::new();
+        let inside = engine.add_node(0.0, 0.0);
+        let outside = engine.add_node(1000.0, 0.0);
+        let far = engine.add_node(-1000.0, -1000.0);
+        engine.add_edge(inside, outside, 1.0);
+
+        let mut simulation = ForceSimulation::default();
+        simulation.set_viewport(Some(Viewport::with_margin(-10.0, -10.0, 10.0, 10.0, 5.0)));
+        let moved = engine.tick(&mut simulation);
+
+        assert_eq!(moved, 1);
+        assert!(engine.get_node_position(inside).unwrap().0 > 0.0, "Pulled toward anchor");
+        assert_eq!(engine.get_node_position(outside), Some((1000.0, 0.0)));
+        assert_eq!(engine.get_node_position(far), Some((-1000.0, -1000.0)));
+        assert_eq!(engine.velocities_x()[outside.0 as usize], 0.0);
+    }
+
+    #[test]
+    fn test_simulation_frame_carries_edge_ids() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(10.0, 0.0);
+        let c = engine.add_node(20.0, 0.0);
+        engine.add_edge(b, c, 1.0);
+        engine.add_edge(a, b, 1.0);
+
+        let frame = engine.build_simulation_frame(&ForceSimulation::default());
+
+        // CSR rows are grouped by source: a's edge (id 1) comes before b's (id 0)
+        assert_eq!(&frame.csr[4..], &[1, 2]);
+        assert_eq!(frame.edge_ids, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_double_buffered_positions_publish_on_swap() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(1.0, 2.0);
+        engine.set_double_buffered(true);
+
+        engine.set_node_position(a, 5.0, 6.0);
+        assert_eq!(engine.published_positions_x(), &[1.0]);
+        assert_eq!(engine.positions_x(), &[5.0]);
+
+        engine.swap_buffers();
+        assert_eq!(engine.published_positions_x(), &[5.0]);
+        assert_eq!(engine.published_positions_y(), &[6.0]);
+
+        // New slots show up at once, unplaced until the next swap
+        engine.add_node(3.0, 4.0);
+        assert_eq!(engine.published_positions_x(), &[5.0, SENTINEL]);
+        assert_eq!(engine.published_positions_y().len(), 2);
+        engine.swap_buffers();
+        assert_eq!(engine.published_positions_x(), &[5.0, 3.0]);
+
+        engine.set_double_buffered(false);
+        engine.set_node_position(a, 7.0, 8.0);
+        assert_eq!(engine.published_positions_x(), &[7.0, 3.0]);
+    }
+
+    #[test]
+    fn test_jitter_is_seeded() {
+        let build = || {
+            let mut engine = GraphEngine::new();
+            engine.add_nodes_from_positions(&[0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+            engine
+        };
+
+        let mut a = build();
+        let mut b = build();
+        a.jitter_positions(5.0, 42);
+        b.jitter_positions(5.0, 42);
+        assert_eq!(a.positions_x(), b.positions_x());
+        assert_eq!(a.positions_y(), b.positions_y());
+
+        // Coincident nodes should be separated
+        assert_ne!(a.positions_x()[0], a.positions_x()[1]);
+
+        let mut c = build();
+        c.jitter_positions(5.0, 43);
+        assert_ne!(a.positions_x(), c.positions_x());
+    }
+
+    #[test]
+    fn test_place_new_nodes_near_neighbors() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(100.0, 0.0);
+        let c = engine.add_node(0.0, 0.0);
+        let d = engine.add_node(0.0, 0.0);
+        engine.add_edge(a, c, 1.0);
+        engine.add_edge(c, b, 1.0);
+        // d only hangs off the other new node c
+        engine.add_edge(d, c, 1.0);
+
+        assert_eq!(engine.place_new_nodes(&[c, d], 10.0, 1), 2);
+
+        // Existing nodes stay put; c sits 10 from the midpoint of a and b
+        assert_eq!(engine.get_node_position(a), Some((0.0, 0.0)));
+        assert_eq!(engine.get_node_position(b), Some((100.0, 0.0)));
+        let (cx, cy) = engine.get_node_position(c).unwrap();
+        assert!(((cx - 50.0).hypot(cy) - 10.0).abs() < 1e-3);
+        let (dx, dy) = engine.get_node_position(d).unwrap();
+        assert!(((dx - cx).hypot(dy - cy) - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_place_disconnected_nodes_on_periphery() {
+        let mut engine = GraphEngine::new();
+        engine.add_node(-50.0, 0.0);
+        engine.add_node(50.0, 0.0);
+        let lone = engine.add_node(0.0, 0.0);
+
+        engine.place_new_nodes(&[lone, NodeId(99)], 10.0, 3);
+        let (x, y) = engine.get_node_position(lone).unwrap();
+        assert!((x.hypot(y) - 60.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_constraints_keep_pinned_nodes() {
+        use crate::layout::constraints::Axis;
+
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 10.0);
+        let b = engine.add_node(5.0, 40.0);
+        engine.pin_node(a);
+        let list = [Constraint::Align {
+            axis: Axis::Y,
+            nodes: vec![a.0, b.0],
+        }];
+
+        let violation = engine.apply_constraints(&list, &ConstraintConfig::default());
+        assert_eq!(violation, 0.0);
+        assert_eq!(engine.get_node_position(a), Some((0.0, 10.0)));
+        assert_eq!(engine.get_node_position(b), Some((5.0, 10.0)));
+    }
+
+    #[test]
+    fn test_snap_keeps_pinned_and_removed_nodes() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(3.0, 3.0);
+        let gone = engine.add_node(7.0, 7.0);
+        let b = engine.add_node(48.0, 12.0);
+        engine.add_edge(a, b, 1.0);
+        engine.pin_node(a);
+        engine.remove_node(gone);
+
+        let moved = engine.snap_positions(&SnapConfig::default());
+        assert_eq!(moved, 1);
+        assert_eq!(engine.get_node_position(a), Some((3.0, 3.0)));
+        assert_eq!(engine.get_node_position(b), Some((50.0, 10.0)));
+    }
+
+    #[test]
+    fn test_jitter_skips_pinned() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(1.0, 2.0);
+        engine.pin_node(a);
+        engine.jitter_positions(5.0, 7);
+        assert_eq!(engine.get_node_position(a), Some((1.0, 2.0)));
+    }
+
     #[test]
     fn test_bounds() {
         let mut engine = GraphEngine::new();
@@ -676,6 +1937,36 @@ mod tests {
         assert!(!degrees.is_empty());
     }
 
+    #[test]
+    fn test_node_strengths() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(1.0, 1.0);
+        let c = engine.add_node(2.0, 2.0);
+
+        engine.add_edge(a, b, 2.5);
+        engine.add_edge(a, c, 1.0);
+        engine.add_edge(c, b, 0.5);
+        engine.add_edge(c, c, 0.25);
+        engine.remove_node(b);
+
+        assert_eq!(engine.get_node_strengths(), vec![1.0, 0.0, 0.0, 0.0, 0.25, 1.25]);
+    }
+
+    #[test]
+    fn test_weighted_edges_use_slots() {
+        let mut engine = GraphEngine::new();
+        let a = engine.add_node(0.0, 0.0);
+        let b = engine.add_node(1.0, 1.0);
+        let c = engine.add_node(2.0, 2.0);
+
+        engine.add_edge(a, b, 2.5);
+        engine.add_edge(b, c, 0.5);
+        engine.remove_node(a);
+
+        assert_eq!(engine.weighted_edges(), vec![(1, 2, 0.5)]);
+    }
+
     #[test]
     fn test_node_bound() {
         let mut engine = GraphEngine::new();
@@ -689,6 +1980,7 @@ mod tests {
         // node_count drops but node_bound stays
         assert_eq!(engine.node_count(), 2);
         assert_eq!(engine.node_bound(), 3);
+        assert_eq!(engine.occupied_slots(), vec![false, true, true]);
     }
 
     #[test]
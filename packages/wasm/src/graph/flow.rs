@@ -0,0 +1,263 @@
+//! Maximum s–t flow and minimum cut.
+//!
+//! Edge weights are capacities. Dinic's algorithm alternates a
+//! breadth-first search, layering the residual graph by distance from the
+//! source, with a blocking flow: depth-first pushes along strictly rising
+//! layers until no such path is left, each node keeping a cursor over its
+//! arcs so dead ends are never retried. At most one phase per distinct
+//! path length gives O(nodes² × edges) overall, and far less in practice.
+//!
+//! Once no augmenting path remains, the nodes the source still reaches in
+//! the residual graph form one side of a minimum cut, and the edges leaving
+//! that side are saturated bottlenecks whose capacities sum to the flow.
+
+use serde::Serialize;
+
+use super::path::Arc;
+use super::traversal::UNREACHED;
+
+/// Residual capacity below which an arc counts as saturated.
+const EPSILON: f64 = 1e-9;
+
+/// Result of a maximum flow computation.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaxFlow {
+    /// Total flow from source to target.
+    pub value: f64,
+    /// Flow per edge ID, negative where it runs against the edge's
+    /// direction (undirected only); 0 for unused and vacant IDs.
+    pub flows: Vec<f32>,
+    /// IDs of the edges crossing the minimum cut, ascending.
+    pub cut_edges: Vec<u32>,
+}
+
+/// Maximum flow from `source` to `target` over `adjacency` (each edge once,
+/// from its source slot), weights as capacities. With `undirected`, every
+/// edge carries up to its capacity either way. `flows` has one entry per
+/// edge ID below `edge_id_bound`. `None` if a capacity is negative, NaN or
+/// infinite, which would leave the residual capacities undefined.
+pub fn max_flow(
+    adjacency: &[Vec<Arc>],
+    source: usize,
+    target: usize,
+    undirected: bool,
+    edge_id_bound: usize,
+) -> Option<MaxFlow> {
+    let valid = |&(_, _, capacity): &Arc| capacity >= 0.0 && capacity.is_finite();
+    if !adjacency.iter().flatten().all(valid) {
+        return None;
+    }
+    let mut network = Network::new(adjacency, undirected);
+    let mut value = 0.0;
+    while source != target && network.layer(source) && network.level[target] != UNREACHED {
+        value += network.blocking_flow(source, target);
+    }
+
+    // Forward arcs are the even ones, in `edges` order
+    let mut flows = vec![0.0; edge_id_bound];
+    let mut cut_edges = Vec::new();
+    network.layer(source);
+    for (i, &(edge, capacity)) in network.edges.iter().enumerate() {
+        let arc = 2 * i;
+        flows[edge as usize] = (capacity - network.residual[arc]) as f32;
+        let (from, to) = (network.to[arc + 1] as usize, network.to[arc] as usize);
+        let (from_side, to_side) =
+            (network.level[from] != UNREACHED, network.level[to] != UNREACHED);
+        if from_side != to_side && (undirected || from_side) {
+            cut_edges.push(edge);
+        }
+    }
+    cut_edges.sort_unstable();
+
+    Some(MaxFlow {
+        value,
+        flows,
+        cut_edges,
+    })
+}
+
+/// Residual network: arcs `2i` and `2i + 1` are edge `i` and its reverse.
+struct Network {
+    /// (edge ID, capacity) per edge, self-loops left out.
+    edges: Vec<(u32, f64)>,
+    /// Head slot per arc.
+    to: Vec<u32>,
+    residual: Vec<f64>,
+    /// Arcs leaving each slot.
+    arcs: Vec<Vec<usize>>,
+    /// Breadth-first layer per slot in the current phase.
+    level: Vec<u32>,
+    /// Next arc to try per slot in the current phase.
+    cursor: Vec<usize>,
+}
+
+impl Network {
+    fn new(adjacency: &[Vec<Arc>], undirected: bool) -> Self {
+        let node_count = adjacency.len();
+        let mut network = Self {
+            edges: Vec::new(),
+            to: Vec::new(),
+            residual: Vec::new(),
+            arcs: vec![Vec::new(); node_count],
+            level: vec![UNREACHED; node_count],
+            cursor: vec![0; node_count],
+        };
+        let reverse_share = if undirected { 1.0 } else { 0.0 };
+        for (source, list) in adjacency.iter().enumerate() {
+            let links = list.iter().filter(|&&(target, _, _)| target as usize != source);
+            for &(target, edge, capacity) in links {
+                let capacity = f64::from(capacity);
+                let arc = network.to.len();
+                network.edges.push((edge, capacity));
+                network.to.extend([target, source as u32]);
+                network.residual.extend([capacity, reverse_share * capacity]);
+                network.arcs[source].push(arc);
+                network.arcs[target as usize].push(arc + 1);
+            }
+        }
+        network
+    }
+
+    /// Layer the slots by residual distance from `source`. False if the
+    /// source has no slot.
+    fn layer(&mut self, source: usize) -> bool {
+        self.level.iter_mut().for_each(|level| *level = UNREACHED);
+        self.cursor.iter_mut().for_each(|cursor| *cursor = 0);
+        let Some(level) = self.level.get_mut(source) else {
+            return false;
+        };
+        *level = 0;
+        let mut queue = vec![source as u32];
+        let mut head = 0;
+        while let Some(&node) = queue.get(head) {
+            head += 1;
+            self.reach(node as usize, &mut queue);
+        }
+        true
+    }
+
+    /// Queue the unlayered slots `node` has residual arcs to.
+    fn reach(&mut self, node: usize, queue: &mut Vec<u32>) {
+        let next = self.level[node] + 1;
+        for &arc in &self.arcs[node] {
+            let to = self.to[arc] as usize;
+            if self.residual[arc] > EPSILON && self.level[to] == UNREACHED {
+                self.level[to] = next;
+                queue.push(to as u32);
+            }
+        }
+    }
+
+    /// Push flow along rising layers until the target is cut off, returning
+    /// the amount pushed.
+    fn blocking_flow(&mut self, source: usize, target: usize) -> f64 {
+        let mut total = 0.0;
+        // Arcs from the source to `node`
+        let mut path: Vec<usize> = Vec::new();
+        let mut node = source;
+        loop {
+            if node == target {
+                total += self.augment(&mut path);
+                node = path.last().map_or(source, |&arc| self.to[arc] as usize);
+                continue;
+            }
+            if let Some(arc) = self.advance(node) {
+                path.push(arc);
+                node = self.to[arc] as usize;
+                continue;
+            }
+            if node == source {
+                return total;
+            }
+            // Dead end: drop the node from this phase and back up
+            self.level[node] = UNREACHED;
+            path.pop();
+            node = path.last().map_or(source, |&arc| self.to[arc] as usize);
+        }
+    }
+
+    /// Push the bottleneck along `path` and cut it back to just before its
+    /// first saturated arc.
+    fn augment(&mut self, path: &mut Vec<usize>) -> f64 {
+        let push = path.iter().map(|&arc| self.residual[arc]).fold(f64::INFINITY, f64::min);
+        for &arc in path.iter() {
+            self.residual[arc] -= push;
+            self.residual[arc ^ 1] += push;
+        }
+        let saturated = path.iter().position(|&arc| self.residual[arc] <= EPSILON);
+        path.truncate(saturated.unwrap_or(path.len()));
+        push
+    }
+
+    /// Next arc from `node` with residual capacity into the next layer.
+    fn advance(&mut self, node: usize) -> Option<usize> {
+        while let Some(&arc) = self.arcs[node].get(self.cursor[node]) {
+            let to = self.to[arc] as usize;
+            if self.residual[arc] > EPSILON && self.level[to] == self.level[node] + 1 {
+                return Some(arc);
+            }
+            self.cursor[node] += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjacency(node_count: usize, edges: &[(u32, u32, f32)]) -> Vec<Vec<Arc>> {
+        let mut adjacency = vec![Vec::new(); node_count];
+        for (id, &(u, v, capacity)) in edges.iter().enumerate() {
+            adjacency[u as usize].push((v, id as u32, capacity));
+        }
+        adjacency
+    }
+
+    #[test]
+    fn test_max_flow() {
+        // Classic network: 0 → {1, 2} → {3, 4} → 5, with a cross link
+        let edges = [
+            (0, 1, 10.0),
+            (0, 2, 10.0),
+            (1, 2, 2.0),
+            (1, 3, 4.0),
+            (1, 4, 8.0),
+            (2, 4, 9.0),
+            (4, 3, 6.0),
+            (3, 5, 10.0),
+            (4, 5, 10.0),
+        ];
+        let network = adjacency(6, &edges);
+        let flow = max_flow(&network, 0, 5, false, edges.len()).unwrap();
+        assert!((flow.value - 19.0).abs() < 1e-9);
+
+        // Conservation at every inner node
+        for node in 1..5 {
+            let net: f32 = edges
+                .iter()
+                .zip(&flow.flows)
+                .map(|(&(u, v, _), &f)| if v == node { f } else if u == node { -f } else { 0.0 })
+                .sum();
+            assert!(net.abs() < 1e-5, "node {node}");
+        }
+        // The cut's capacity equals the flow
+        let cut: f32 = flow.cut_edges.iter().map(|&e| edges[e as usize].2).sum();
+        assert!((f64::from(cut) - flow.value).abs() < 1e-5);
+
+        // Against the edges only the reverse direction helps
+        let backwards = max_flow(&network, 5, 0, false, edges.len()).unwrap();
+        assert_eq!(backwards.value, 0.0);
+        assert!(backwards.cut_edges.is_empty());
+        let undirected = max_flow(&network, 5, 0, true, edges.len()).unwrap();
+        assert!((undirected.value - 20.0).abs() < 1e-9);
+        assert!(undirected.flows[7] < 0.0);
+
+        // Infinite, NaN and negative capacities are rejected
+        for capacity in [f32::INFINITY, f32::NAN, -1.0] {
+            let bad = adjacency(2, &[(0, 1, capacity)]);
+            assert!(max_flow(&bad, 0, 1, false, 1).is_none());
+        }
+    }
+}
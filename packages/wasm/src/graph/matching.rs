@@ -0,0 +1,466 @@
+//! Maximum cardinality matching.
+//!
+//! A matching is a set of links no two of which share a node; a maximum
+//! one has as many links as possible. The graph is taken as undirected and
+//! simple, and both algorithms start from a greedy matching.
+//!
+//! Bipartite graphs (found by two-coloring) use Hopcroft–Karp: each phase
+//! layers the graph by a breadth-first search from the unmatched nodes of
+//! one side, then augments along a maximal set of shortest augmenting
+//! paths, for O(edges × √nodes) in total.
+//!
+//! Other graphs use Edmonds' blossom algorithm: a search tree grows from
+//! each unmatched node, and an odd cycle (blossom) is contracted into its
+//! base so alternating paths through it are found. Every search touches
+//! only the nodes in its tree, but the worst case is cubic.
+
+use std::collections::VecDeque;
+
+/// Partner of a slot that is not matched.
+pub const UNMATCHED: u32 = u32::MAX;
+
+/// Maximum cardinality matching over `neighbors` (each slot's neighbors,
+/// sorted and without the slot itself), as each slot's partner or
+/// `UNMATCHED`.
+pub fn maximum_matching(neighbors: &[Vec<u32>]) -> Vec<u32> {
+    let mut mate = greedy(neighbors);
+    match two_coloring(neighbors) {
+        Some(side) => HopcroftKarp::new(neighbors, &side, &mut mate).run(),
+        None => Blossom::new(neighbors, &mut mate).run(),
+    }
+    mate
+}
+
+/// Match each slot to its first unmatched neighbor, in slot order.
+fn greedy(neighbors: &[Vec<u32>]) -> Vec<u32> {
+    let mut mate = vec![UNMATCHED; neighbors.len()];
+    for (node, list) in neighbors.iter().enumerate() {
+        if mate[node] != UNMATCHED {
+            continue;
+        }
+        if let Some(&next) = list.iter().find(|&&next| mate[next as usize] == UNMATCHED) {
+            mate[node] = next;
+            mate[next as usize] = node as u32;
+        }
+    }
+    mate
+}
+
+/// Side of each slot if the graph is bipartite.
+fn two_coloring(neighbors: &[Vec<u32>]) -> Option<Vec<bool>> {
+    let mut side: Vec<Option<bool>> = vec![None; neighbors.len()];
+    for root in 0..neighbors.len() {
+        if side[root].is_none() && !color_component(neighbors, &mut side, root) {
+            return None;
+        }
+    }
+    Some(side.into_iter().map(|s| s.unwrap_or(false)).collect())
+}
+
+/// Two-color the component of `root` breadth-first. False if two linked
+/// slots get the same side.
+fn color_component(neighbors: &[Vec<u32>], side: &mut [Option<bool>], root: usize) -> bool {
+    side[root] = Some(false);
+    let mut queue = VecDeque::from([root]);
+    while let Some(node) = queue.pop_front() {
+        if neighbors[node].iter().any(|&next| side[next as usize] == side[node]) {
+            return false;
+        }
+        for &next in &neighbors[node] {
+            if side[next as usize].is_none() {
+                side[next as usize] = side[node].map(|s| !s);
+                queue.push_back(next as usize);
+            }
+        }
+    }
+    true
+}
+
+/// Hopcroft–Karp state; `dist` and `cursor` cover the left side only.
+struct HopcroftKarp<'a> {
+    neighbors: &'a [Vec<u32>],
+    mate: &'a mut [u32],
+    /// Slots on the left side with at least one neighbor.
+    left: Vec<u32>,
+    /// Layer of each left slot in the current phase.
+    dist: Vec<u32>,
+    /// Layer at which the phase's shortest augmenting paths end.
+    limit: u32,
+    /// Next neighbor to try per left slot in the current phase.
+    cursor: Vec<usize>,
+}
+
+impl<'a> HopcroftKarp<'a> {
+    fn new(neighbors: &'a [Vec<u32>], side: &[bool], mate: &'a mut [u32]) -> Self {
+        let left = (0..neighbors.len() as u32)
+            .filter(|&node| !side[node as usize] && !neighbors[node as usize].is_empty())
+            .collect();
+        Self {
+            neighbors,
+            mate,
+            left,
+            dist: vec![UNMATCHED; neighbors.len()],
+            limit: UNMATCHED,
+            cursor: vec![0; neighbors.len()],
+        }
+    }
+
+    fn run(&mut self) {
+        while self.layer() {
+            self.cursor.iter_mut().for_each(|c| *c = 0);
+            for i in 0..self.left.len() {
+                self.augment(self.left[i]);
+            }
+        }
+    }
+
+    /// Layer the left side from its unmatched slots. False if no augmenting
+    /// path is left.
+    fn layer(&mut self) -> bool {
+        self.limit = UNMATCHED;
+        let mut queue = Vec::new();
+        for &node in &self.left {
+            let free = self.mate[node as usize] == UNMATCHED;
+            self.dist[node as usize] = if free { 0 } else { UNMATCHED };
+            if free {
+                queue.push(node);
+            }
+        }
+        let mut head = 0;
+        while let Some(&node) = queue.get(head) {
+            head += 1;
+            self.reach(node, &mut queue);
+        }
+        self.limit != UNMATCHED
+    }
+
+    /// Queue the left slots matched to neighbors of `node`.
+    fn reach(&mut self, node: u32, queue: &mut Vec<u32>) {
+        let next = self.dist[node as usize] + 1;
+        for &right in &self.neighbors[node as usize] {
+            let partner = self.mate[right as usize];
+            if partner == UNMATCHED {
+                self.limit = self.limit.min(next);
+            } else if self.dist[partner as usize] == UNMATCHED {
+                self.dist[partner as usize] = next;
+                queue.push(partner);
+            }
+        }
+    }
+
+    /// Look for a shortest augmenting path from `root`, if unmatched, along
+    /// the layers, flipping it if found.
+    fn augment(&mut self, root: u32) -> bool {
+        if self.mate[root as usize] != UNMATCHED {
+            return false;
+        }
+        // Left slots on the path, and the right slot taken from each
+        let mut path = vec![root];
+        let mut through = Vec::new();
+        while let Some(&node) = path.last() {
+            let Some(right) = self.advance(node) else {
+                self.dist[node as usize] = UNMATCHED;
+                path.pop();
+                through.pop();
+                continue;
+            };
+            through.push(right);
+            let partner = self.mate[right as usize];
+            if partner == UNMATCHED {
+                self.flip(&path, &through);
+                return true;
+            }
+            path.push(partner);
+        }
+        false
+    }
+
+    /// Match each left slot of an augmenting path to the right slot after it.
+    fn flip(&mut self, path: &[u32], through: &[u32]) {
+        for (&left, &right) in path.iter().zip(through) {
+            self.mate[left as usize] = right;
+            self.mate[right as usize] = left;
+        }
+    }
+
+    /// Next neighbor of `node` leading one layer on, or to a free right
+    /// slot at the final layer.
+    fn advance(&mut self, node: u32) -> Option<u32> {
+        let next = self.dist[node as usize] + 1;
+        let list = &self.neighbors[node as usize];
+        while let Some(&right) = list.get(self.cursor[node as usize]) {
+            self.cursor[node as usize] += 1;
+            let partner = self.mate[right as usize];
+            let onward = match partner {
+                UNMATCHED => next == self.limit,
+                _ => self.dist[partner as usize] == next,
+            };
+            if onward {
+                return Some(right);
+            }
+        }
+        None
+    }
+}
+
+/// Edmonds' blossom algorithm state. `parent` and `base` follow the
+/// current search tree, whose slots are listed in `tree`.
+struct Blossom<'a> {
+    neighbors: &'a [Vec<u32>],
+    mate: &'a mut [u32],
+    /// Slot an odd slot was reached from.
+    parent: Vec<u32>,
+    /// Base of the contracted blossom each slot belongs to.
+    base: Vec<u32>,
+    /// Even slots, whose neighbors the search scans.
+    even: Vec<bool>,
+    /// Bases inside the blossom being contracted.
+    in_blossom: Vec<bool>,
+    /// Stamp per slot for finding the lowest common ancestor.
+    mark: Vec<u32>,
+    stamp: u32,
+    tree: Vec<u32>,
+    queue: VecDeque<u32>,
+}
+
+impl<'a> Blossom<'a> {
+    fn new(neighbors: &'a [Vec<u32>], mate: &'a mut [u32]) -> Self {
+        let node_count = neighbors.len();
+        Self {
+            neighbors,
+            mate,
+            parent: vec![UNMATCHED; node_count],
+            base: (0..node_count as u32).collect(),
+            even: vec![false; node_count],
+            in_blossom: vec![false; node_count],
+            mark: vec![0; node_count],
+            stamp: 0,
+            tree: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn run(&mut self) {
+        for root in 0..self.neighbors.len() as u32 {
+            if self.mate[root as usize] != UNMATCHED || self.neighbors[root as usize].is_empty() {
+                continue;
+            }
+            if let Some(end) = self.search(root) {
+                self.flip(end);
+            }
+            self.reset();
+        }
+    }
+
+    /// Grow a search tree from `root` until it reaches an unmatched slot,
+    /// returning it.
+    fn search(&mut self, root: u32) -> Option<u32> {
+        self.set_even(root);
+        while let Some(node) = self.queue.pop_front() {
+            if let Some(end) = self.scan(node) {
+                return Some(end);
+            }
+        }
+        None
+    }
+
+    /// Follow every link from the even slot `node`.
+    fn scan(&mut self, node: u32) -> Option<u32> {
+        let neighbors = self.neighbors;
+        for &next in &neighbors[node as usize] {
+            let (n, x) = (node as usize, next as usize);
+            if self.base[n] == self.base[x] || self.mate[n] == next {
+                continue;
+            }
+            let partner = self.mate[x];
+            let rooted = partner != UNMATCHED && self.parent[partner as usize] != UNMATCHED;
+            // An even slot closes an odd cycle
+            if self.even[x] || rooted {
+                self.contract(node, next);
+            } else if self.parent[x] == UNMATCHED && self.grow(node, next) {
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    /// Add the slot `next`, reached from `node`, to the tree as odd and its
+    /// partner as even. True if `next` is unmatched, ending the search.
+    fn grow(&mut self, node: u32, next: u32) -> bool {
+        self.parent[next as usize] = node;
+        self.tree.push(next);
+        let partner = self.mate[next as usize];
+        if partner == UNMATCHED {
+            return true;
+        }
+        self.set_even(partner);
+        false
+    }
+
+    /// Contract the blossom closed by the link between even slots `u` and
+    /// `v` into its base, making its odd slots even.
+    fn contract(&mut self, u: u32, v: u32) {
+        let base = self.common_base(u, v);
+        self.mark_path(u, base, v);
+        self.mark_path(v, base, u);
+        for i in 0..self.tree.len() {
+            let node = self.tree[i];
+            if self.in_blossom[self.base[node as usize] as usize] {
+                self.base[node as usize] = base;
+                self.set_even(node);
+            }
+        }
+        for &node in &self.tree {
+            self.in_blossom[node as usize] = false;
+        }
+    }
+
+    /// Lowest common ancestor of the blossoms of `u` and `v`.
+    fn common_base(&mut self, mut u: u32, mut v: u32) -> u32 {
+        self.stamp += 1;
+        loop {
+            u = self.base[u as usize];
+            self.mark[u as usize] = self.stamp;
+            if self.mate[u as usize] == UNMATCHED {
+                break;
+            }
+            u = self.parent[self.mate[u as usize] as usize];
+        }
+        loop {
+            v = self.base[v as usize];
+            if self.mark[v as usize] == self.stamp {
+                return v;
+            }
+            v = self.parent[self.mate[v as usize] as usize];
+        }
+    }
+
+    /// Mark the blossoms from `node` down to `base`, pointing odd slots on
+    /// the way back across the cycle so paths can run either way round.
+    fn mark_path(&mut self, mut node: u32, base: u32, mut child: u32) {
+        while self.base[node as usize] != base {
+            let partner = self.mate[node as usize];
+            self.in_blossom[self.base[node as usize] as usize] = true;
+            self.in_blossom[self.base[partner as usize] as usize] = true;
+            self.parent[node as usize] = child;
+            child = partner;
+            node = self.parent[partner as usize];
+        }
+    }
+
+    /// Flip the augmenting path ending at the unmatched slot `end`.
+    fn flip(&mut self, mut end: u32) {
+        while end != UNMATCHED {
+            let from = self.parent[end as usize];
+            let next = self.mate[from as usize];
+            self.mate[end as usize] = from;
+            self.mate[from as usize] = end;
+            end = next;
+        }
+    }
+
+    fn set_even(&mut self, node: u32) {
+        if !std::mem::replace(&mut self.even[node as usize], true) {
+            self.tree.push(node);
+            self.queue.push_back(node);
+        }
+    }
+
+    /// Clear the search tree for the next root.
+    fn reset(&mut self) {
+        for &node in &self.tree {
+            self.parent[node as usize] = UNMATCHED;
+            self.base[node as usize] = node;
+            self.even[node as usize] = false;
+        }
+        self.tree.clear();
+        self.queue.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbors(node_count: usize, links: &[(u32, u32)]) -> Vec<Vec<u32>> {
+        let mut neighbors = vec![Vec::new(); node_count];
+        for &(u, v) in links {
+            neighbors[u as usize].push(v);
+            neighbors[v as usize].push(u);
+        }
+        for list in &mut neighbors {
+            list.sort_unstable();
+        }
+        neighbors
+    }
+
+    /// Number of links in `mate`, checking it is symmetric and uses links.
+    fn size(neighbors: &[Vec<u32>], mate: &[u32]) -> usize {
+        let mut matched = 0;
+        for (node, &partner) in mate.iter().enumerate().filter(|&(_, &p)| p != UNMATCHED) {
+            assert_eq!(mate[partner as usize], node as u32);
+            assert!(neighbors[node].contains(&partner));
+            matched += 1;
+        }
+        matched / 2
+    }
+
+    #[test]
+    fn test_bipartite_matching() {
+        // Greedy takes 0-4 first, leaving 1 stuck until 0 moves to 5
+        let graph = neighbors(8, &[(0, 4), (0, 5), (1, 4), (2, 5), (2, 6), (3, 6), (3, 7)]);
+        assert!(two_coloring(&graph).is_some());
+        assert_eq!(size(&graph, &maximum_matching(&graph)), 4);
+
+        // Path 0-1-2-3-4-5 has a perfect matching
+        let path = neighbors(6, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+        assert_eq!(size(&path, &maximum_matching(&path)), 3);
+    }
+
+    /// Largest matching by trying every subset of links.
+    fn brute_force(links: &[(u32, u32)], used: &mut [bool]) -> usize {
+        let Some((&(u, v), rest)) = links.split_first() else {
+            return 0;
+        };
+        let mut best = brute_force(rest, used);
+        if !used[u as usize] && !used[v as usize] {
+            used[u as usize] = true;
+            used[v as usize] = true;
+            best = best.max(1 + brute_force(rest, used));
+            used[u as usize] = false;
+            used[v as usize] = false;
+        }
+        best
+    }
+
+    #[test]
+    fn test_general_matching() {
+        let mut rng = crate::rng::SeededRng::new(5);
+        for _ in 0..300 {
+            let mut links: Vec<(u32, u32)> = (0..rng.below(14))
+                .map(|_| (rng.below(9) as u32, rng.below(9) as u32))
+                .filter(|&(u, v)| u < v)
+                .collect();
+            links.sort_unstable();
+            links.dedup();
+            let graph = neighbors(9, &links);
+            let expected = brute_force(&links, &mut [false; 9]);
+            assert_eq!(size(&graph, &maximum_matching(&graph)), expected, "{links:?}");
+        }
+
+        // Petersen graph: perfect matching of 5
+        let petersen = neighbors(
+            10,
+            &[
+                (0, 1), (1, 2), (2, 3), (3, 4), (4, 0),
+                (0, 5), (1, 6), (2, 7), (3, 8), (4, 9),
+                (5, 7), (7, 9), (9, 6), (6, 8), (8, 5),
+            ],
+        );
+        assert_eq!(size(&petersen, &maximum_matching(&petersen)), 5);
+
+        // A triangle matches one link
+        let triangle = neighbors(3, &[(0, 1), (1, 2), (0, 2)]);
+        assert_eq!(size(&triangle, &maximum_matching(&triangle)), 1);
+    }
+}
@@ -4,10 +4,30 @@
 //! for stable node/edge indices, with Structure of Arrays (SoA) layout for
 //! positions and velocities to enable SIMD operations and cache-friendly access.
 
+mod centrality;
+mod clique;
+mod cycle;
+mod dag;
 mod edge;
 mod engine;
+mod flow;
+mod matching;
 mod node;
+mod path;
+mod reachability;
+mod stats;
+mod traversal;
 
+pub use centrality::{
+    Betweenness, BetweennessConfig, ClosenessConfig, EigenvectorConfig, HarmonicConfig,
+    KatzConfig, PageRankConfig,
+};
+pub use clique::CliqueConfig;
 pub use edge::EdgeId;
 pub use engine::GraphEngine;
+pub use flow::MaxFlow;
 pub use node::NodeId;
+pub use path::{path_masks, BellmanFordPath, Path};
+pub use reachability::ReachabilityIndex;
+pub use stats::{GraphStats, RichClubConfig};
+pub use traversal::{strongly_connected_components, Traversal, UNREACHED};
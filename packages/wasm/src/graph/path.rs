@@ -0,0 +1,461 @@
+//! Weighted shortest paths.
+//!
+//! Paths run over a slot adjacency list of `(target slot, edge ID, weight)`
+//! arcs built by the engine, so a path names the edges it takes as well as
+//! the nodes; of parallel edges the lightest wins. Dijkstra's algorithm
+//! needs non-negative weights.
+//!
+//! A* is Dijkstra guided by an estimate of the distance left to the
+//! target, expanding nodes in order of distance so far plus estimate. The
+//! engine estimates from node positions. As long as the estimate never
+//! exceeds the true remaining distance the path found is a shortest one;
+//! larger estimates head for the target more greedily and may settle for a
+//! longer path.
+//!
+//! Bellman–Ford allows negative weights. It relaxes every arc until no
+//! distance improves, which takes at most one round per node; a distance
+//! still improving after that means a negative cycle is reachable from the
+//! source and no shortest path exists. The cycle is found by walking back
+//! along predecessors from the last improved node.
+//!
+//! The k shortest loopless paths come from Yen's algorithm: each path found
+//! is branched at every node along it, searching from there for a path that
+//! avoids the route back to the source and every edge earlier paths with
+//! the same route took next. The lightest branch becomes the next path.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use serde::Serialize;
+
+/// An arc out of a slot: (target slot, edge ID, weight).
+pub type Arc = (u32, u32, f32);
+
+/// A path between two nodes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Path {
+    /// Node slots along the path, the source first.
+    pub nodes: Vec<u32>,
+    /// Edge IDs along the path (one fewer than `nodes`).
+    pub edges: Vec<u32>,
+    /// Sum of the edge weights.
+    pub distance: f32,
+}
+
+/// Result of a Bellman–Ford search; at most one field is set.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BellmanFordPath {
+    /// The shortest path, if the target is reachable and no negative cycle
+    /// is.
+    pub path: Option<Path>,
+    /// A negative cycle reachable from the source, its first node repeated
+    /// at the end.
+    pub negative_cycle: Option<Path>,
+}
+
+/// Heap entry that pops the smallest distance first, ties by lowest slot.
+#[derive(PartialEq)]
+struct Entry(f32, usize);
+
+impl Eq for Entry {}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then_with(|| other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Shortest path from `source` to `target` by Dijkstra's algorithm.
+///
+/// Weights must be non-negative. Returns `None` if `target` cannot be
+/// reached or either slot is out of range.
+pub fn dijkstra(adjacency: &[Vec<Arc>], source: usize, target: usize) -> Option<Path> {
+    a_star(adjacency, source, target, |_| 0.0)
+}
+
+/// Shortest path from `source` to `target` by A*, with `estimate` giving
+/// the remaining distance from a slot to `target`.
+///
+/// Weights must be non-negative. Returns `None` if `target` cannot be
+/// reached or either slot is out of range.
+pub fn a_star(
+    adjacency: &[Vec<Arc>],
+    source: usize,
+    target: usize,
+    estimate: impl Fn(usize) -> f32,
+) -> Option<Path> {
+    search(adjacency, source, target, estimate, |_| true)
+}
+
+/// A* over the arcs `allowed` keeps.
+fn search(
+    adjacency: &[Vec<Arc>],
+    source: usize,
+    target: usize,
+    estimate: impl Fn(usize) -> f32,
+    allowed: impl Fn(&Arc) -> bool,
+) -> Option<Path> {
+    let node_count = adjacency.len();
+    if source >= node_count || target >= node_count {
+        return None;
+    }
+
+    let mut distances = vec![f32::INFINITY; node_count];
+    let mut settled = vec![false; node_count];
+    let mut via = vec![None; node_count];
+    distances[source] = 0.0;
+    let mut heap = BinaryHeap::from([Entry(estimate(source), source)]);
+
+    while let Some(Entry(_, node)) = heap.pop() {
+        if node == target {
+            break;
+        }
+        if std::mem::replace(&mut settled[node], true) {
+            continue;
+        }
+        for &(next, edge, weight) in adjacency[node].iter().filter(|arc| allowed(arc)) {
+            let next = next as usize;
+            let candidate = distances[node] + weight;
+            if candidate < distances[next] {
+                distances[next] = candidate;
+                via[next] = Some((node, edge));
+                heap.push(Entry(candidate + estimate(next), next));
+            }
+        }
+    }
+
+    trace(&via, source, target, distances[target])
+}
+
+/// Up to `k` loopless paths from `source` to `target`, lightest first, by
+/// Yen's algorithm.
+///
+/// Weights must be non-negative. Paths of equal weight come out fewest
+/// edges first.
+pub fn k_shortest_paths(
+    adjacency: &[Vec<Arc>],
+    source: usize,
+    target: usize,
+    k: usize,
+) -> Vec<Path> {
+    let mut paths: Vec<Path> = Vec::new();
+    let mut candidates: Vec<Path> = dijkstra(adjacency, source, target).into_iter().collect();
+
+    while paths.len() < k {
+        let Some(best) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                a.distance.total_cmp(&b.distance).then(a.nodes.len().cmp(&b.nodes.len()))
+            })
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        paths.push(candidates.swap_remove(best));
+
+        let last = &paths[paths.len() - 1];
+        for spur in 0..last.edges.len() {
+            let (root_nodes, root_edges) = (&last.nodes[..spur], &last.edges[..spur]);
+            // Edges earlier paths with this root took out of the spur node
+            let taken: Vec<u32> = paths
+                .iter()
+                .filter(|p| p.edges.len() > spur && p.edges[..spur] == *root_edges)
+                .map(|p| p.edges[spur])
+                .collect();
+            let allowed =
+                |&(next, edge, _): &Arc| !root_nodes.contains(&next) && !taken.contains(&edge);
+            let spur_node = last.nodes[spur] as usize;
+            let Some(branch) = search(adjacency, spur_node, target, |_| 0.0, allowed) else {
+                continue;
+            };
+
+            let mut nodes = root_nodes.to_vec();
+            nodes.extend(&branch.nodes);
+            let mut edges = root_edges.to_vec();
+            edges.extend(&branch.edges);
+            let root_distance: f32 = (0..spur)
+                .map(|i| arc_weight(adjacency, last.nodes[i], last.edges[i]))
+                .sum();
+            let path = Path {
+                nodes,
+                edges,
+                distance: root_distance + branch.distance,
+            };
+            if !candidates.iter().chain(&paths).any(|p| p.edges == path.edges) {
+                candidates.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// Weight of edge `edge` out of slot `from`.
+fn arc_weight(adjacency: &[Vec<Arc>], from: u32, edge: u32) -> f32 {
+    adjacency[from as usize]
+        .iter()
+        .find(|&&(_, id, _)| id == edge)
+        .map_or(0.0, |&(_, _, weight)| weight)
+}
+
+/// Shortest path from `source` to `target` by Bellman–Ford, allowing
+/// negative weights.
+///
+/// Returns the path, or a negative cycle reachable from `source` instead
+/// (edges taken in both directions make every negative edge such a cycle).
+/// Neither is set if `target` cannot be reached or a slot is out of range.
+pub fn bellman_ford(adjacency: &[Vec<Arc>], source: usize, target: usize) -> BellmanFordPath {
+    let node_count = adjacency.len();
+    let mut result = BellmanFordPath {
+        path: None,
+        negative_cycle: None,
+    };
+    if source >= node_count || target >= node_count {
+        return result;
+    }
+
+    let mut distances = vec![f32::INFINITY; node_count];
+    let mut via = vec![None; node_count];
+    distances[source] = 0.0;
+
+    let mut improved = None;
+    for _ in 0..node_count {
+        improved = relax(adjacency, &mut distances, &mut via);
+        if improved.is_none() {
+            break;
+        }
+    }
+
+    match improved {
+        Some(node) => result.negative_cycle = cycle_through(adjacency, &via, node),
+        None => result.path = trace(&via, source, target, distances[target]),
+    }
+    result
+}
+
+/// One Bellman–Ford round over every arc out of a reached node. Returns
+/// the last node whose distance improved.
+fn relax(
+    adjacency: &[Vec<Arc>],
+    distances: &mut [f32],
+    via: &mut [Option<(usize, u32)>],
+) -> Option<usize> {
+    let mut improved = None;
+    for (node, arcs) in adjacency.iter().enumerate() {
+        let distance = distances[node];
+        if !distance.is_finite() {
+            continue;
+        }
+        for &(next, edge, weight) in arcs {
+            let next = next as usize;
+            if distance + weight < distances[next] {
+                distances[next] = distance + weight;
+                via[next] = Some((node, edge));
+                improved = Some(next);
+            }
+        }
+    }
+    improved
+}
+
+/// The cycle in the predecessors `via` behind `node`, which still improved
+/// after every round.
+fn cycle_through(
+    adjacency: &[Vec<Arc>],
+    via: &[Option<(usize, u32)>],
+    node: usize,
+) -> Option<Path> {
+    // Enough steps back to be certain of standing on the cycle
+    let mut start = node;
+    for _ in 0..via.len() {
+        start = via[start]?.0;
+    }
+
+    let mut nodes = vec![start as u32];
+    let mut edges = Vec::new();
+    let mut distance = 0.0;
+    let mut node = start;
+    loop {
+        let (previous, edge) = via[node]?;
+        distance += arc_weight(adjacency, previous as u32, edge);
+        nodes.push(previous as u32);
+        edges.push(edge);
+        node = previous;
+        if node == start {
+            break;
+        }
+    }
+    nodes.reverse();
+    edges.reverse();
+    Some(Path {
+        nodes,
+        edges,
+        distance,
+    })
+}
+
+/// Pack a path into bitmasks for upload: `[node word count, node words...,
+/// edge words...]`, where bit `i % 32` of word `i / 32` is set for node
+/// slot (or edge ID) `i` on the path. Both masks are all zero without a
+/// path.
+pub fn path_masks(path: Option<&Path>, node_count: usize, edge_count: usize) -> Vec<u32> {
+    let node_words = node_count.div_ceil(32);
+    let mut masks = vec![0u32; 1 + node_words + edge_count.div_ceil(32)];
+    masks[0] = node_words as u32;
+    if let Some(path) = path {
+        let (nodes, edges) = masks[1..].split_at_mut(node_words);
+        for (mask, bits) in [(nodes, &path.nodes), (edges, &path.edges)] {
+            for &bit in bits {
+                mask[bit as usize / 32] |= 1 << (bit % 32);
+            }
+        }
+    }
+    masks
+}
+
+/// Follow `via` (the predecessor slot and edge per slot) back from
+/// `target` to `source`.
+fn trace(
+    via: &[Option<(usize, u32)>],
+    source: usize,
+    target: usize,
+    distance: f32,
+) -> Option<Path> {
+    let mut nodes = vec![target as u32];
+    let mut edges = Vec::new();
+    let mut node = target;
+    while node != source {
+        let (previous, edge) = via[node]?;
+        nodes.push(previous as u32);
+        edges.push(edge);
+        node = previous;
+    }
+    nodes.reverse();
+    edges.reverse();
+    Some(Path {
+        nodes,
+        edges,
+        distance,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dijkstra_prefers_light_edges() {
+        // 0 → 1 → 3 costs 2, the direct 0 → 3 costs 5, and of the two
+        // parallel 1 → 3 edges the lighter one (ID 3) is taken
+        let adjacency = vec![
+            vec![(1, 0, 1.0), (3, 1, 5.0)],
+            vec![(3, 2, 4.0), (3, 3, 1.0)],
+            vec![],
+            vec![(2, 4, 0.5)],
+        ];
+        let path = dijkstra(&adjacency, 0, 2).unwrap();
+        assert_eq!(path.nodes, vec![0, 1, 3, 2]);
+        assert_eq!(path.edges, vec![0, 3, 4]);
+        assert_eq!(path.distance, 2.5);
+
+        let path = dijkstra(&adjacency, 1, 1).unwrap();
+        assert_eq!(path.nodes, vec![1]);
+        assert!(path.edges.is_empty());
+        assert_eq!(path.distance, 0.0);
+
+        assert!(dijkstra(&adjacency, 2, 0).is_none());
+
+        let path = dijkstra(&adjacency, 0, 2);
+        let masks = path_masks(path.as_ref(), 40, 5);
+        assert_eq!(masks, vec![2, 0b1111, 0, 0b11001]);
+        assert_eq!(path_masks(None, 40, 5), vec![2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_a_star_matches_dijkstra() {
+        // 4x4 grid with unit edges both ways, estimate = Manhattan distance
+        let slot = |x: usize, y: usize| y * 4 + x;
+        let mut adjacency = vec![Vec::new(); 16];
+        let mut id = 0;
+        for (x, y) in (0..4).flat_map(|y| (0..4).map(move |x| (x, y))) {
+            for (nx, ny) in [(x + 1, y), (x, y + 1)].into_iter().filter(|&(a, b)| a < 4 && b < 4) {
+                adjacency[slot(x, y)].push((slot(nx, ny) as u32, id, 1.0));
+                adjacency[slot(nx, ny)].push((slot(x, y) as u32, id, 1.0));
+                id += 1;
+            }
+        }
+        let estimate = |s: usize| ((3 - s % 4) + (3 - s / 4)) as f32;
+
+        let path = a_star(&adjacency, 0, 15, estimate).unwrap();
+        assert_eq!(path.distance, dijkstra(&adjacency, 0, 15).unwrap().distance);
+        assert_eq!(path.distance, 6.0);
+        assert_eq!(path.nodes.len(), 7);
+    }
+
+    #[test]
+    fn test_k_shortest_paths() {
+        // The example from Yen's paper: C D E F G H as slots 0..6
+        let edges = [
+            (0, 1, 3.0),
+            (0, 2, 2.0),
+            (1, 3, 4.0),
+            (2, 1, 1.0),
+            (2, 3, 2.0),
+            (2, 4, 3.0),
+            (3, 4, 2.0),
+            (3, 5, 1.0),
+            (4, 5, 2.0),
+        ];
+        let mut adjacency = vec![Vec::new(); 6];
+        for (id, &(u, v, weight)) in edges.iter().enumerate() {
+            adjacency[u].push((v as u32, id as u32, weight));
+        }
+
+        let paths = k_shortest_paths(&adjacency, 0, 5, 4);
+        let nodes: Vec<&[u32]> = paths.iter().map(|p| &p.nodes[..]).collect();
+        assert_eq!(nodes, vec![&[0, 2, 3, 5][..], &[0, 2, 4, 5], &[0, 1, 3, 5], &[0, 2, 1, 3, 5]]);
+        let distances: Vec<f32> = paths.iter().map(|p| p.distance).collect();
+        assert_eq!(distances, vec![5.0, 7.0, 8.0, 8.0]);
+
+        // Only 7 loopless paths exist
+        assert_eq!(k_shortest_paths(&adjacency, 0, 5, 20).len(), 7);
+        assert!(k_shortest_paths(&adjacency, 5, 0, 3).is_empty());
+    }
+
+    #[test]
+    fn test_bellman_ford() {
+        // 0 → 2 directly costs 1, through 1 it costs 2 - 3 = -1
+        let mut adjacency = vec![
+            vec![(1, 0, 2.0), (2, 1, 1.0)],
+            vec![(2, 2, -3.0)],
+            vec![(3, 3, 1.0)],
+            vec![],
+        ];
+        let result = bellman_ford(&adjacency, 0, 3);
+        let path = result.path.unwrap();
+        assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+        assert_eq!(path.edges, vec![0, 2, 3]);
+        assert_eq!(path.distance, 0.0);
+        assert!(result.negative_cycle.is_none());
+
+        // 3 → 1 closes the cycle 1 → 2 → 3 → 1 of weight -1
+        adjacency[3].push((1, 4, 1.0));
+        let result = bellman_ford(&adjacency, 0, 3);
+        assert!(result.path.is_none());
+        let cycle = result.negative_cycle.unwrap();
+        assert_eq!(cycle.nodes.first(), cycle.nodes.last());
+        assert_eq!(cycle.nodes.len(), 4);
+        let mut edges = cycle.edges.clone();
+        edges.sort_unstable();
+        assert_eq!(edges, vec![2, 3, 4]);
+        assert_eq!(cycle.distance, -1.0);
+    }
+}
@@ -0,0 +1,200 @@
+//! Reachability index for repeated "can `a` reach `b`" queries.
+//!
+//! The graph is condensed to its DAG of strongly connected components, and
+//! every component is labeled with intervals in the style of GRAIL: each of
+//! a few depth-first passes, visiting children in a different random
+//! rotation, gives a component its postorder rank and the lowest rank in
+//! its subtree. If `a` reaches `b`, each of `b`'s intervals lies inside
+//! `a`'s, so most negative queries end after a few comparisons. Queries
+//! that pass every interval test walk the condensation from `a`, skipping
+//! every branch whose intervals already rule `b` out.
+
+use std::collections::HashSet;
+
+use super::traversal::strongly_connected_components;
+use crate::rng::SeededRng;
+
+/// Depth-first passes, each giving every component one interval.
+const LABEL_COUNT: usize = 3;
+
+/// Precomputed reachability between node slots.
+#[derive(Debug, Clone)]
+pub struct ReachabilityIndex {
+    /// Strongly connected component per slot.
+    component: Vec<u32>,
+    /// Successor components per component, deduplicated. Every edge points
+    /// from a higher component ID to a lower one.
+    successors: Vec<Vec<u32>>,
+    /// (lowest rank in subtree, postorder rank) per pass per component.
+    labels: Vec<[(u32, u32); LABEL_COUNT]>,
+}
+
+impl ReachabilityIndex {
+    /// Build the index over `adjacency` (outgoing neighbor slots per slot).
+    pub fn new(adjacency: &[Vec<u32>], seed: u64) -> Self {
+        let component = strongly_connected_components(adjacency);
+        let component_count = component.iter().map(|&c| c as usize + 1).max().unwrap_or(0);
+
+        let mut successors = vec![Vec::new(); component_count];
+        for (node, neighbors) in adjacency.iter().enumerate() {
+            let from = component[node];
+            successors[from as usize].extend(
+                neighbors.iter().map(|&next| component[next as usize]).filter(|&to| to != from),
+            );
+        }
+        for next in &mut successors {
+            next.sort_unstable();
+            next.dedup();
+        }
+
+        let mut rng = SeededRng::new(seed);
+        let passes: Vec<Vec<(u32, u32)>> =
+            (0..LABEL_COUNT).map(|_| label_pass(&successors, &mut rng)).collect();
+        let labels = (0..component_count)
+            .map(|c| std::array::from_fn(|pass| passes[pass][c]))
+            .collect();
+
+        Self {
+            component,
+            successors,
+            labels,
+        }
+    }
+
+    /// Whether slot `to` can be reached from slot `from` (every slot
+    /// reaches itself). False for slots outside the index.
+    pub fn is_reachable(&self, from: usize, to: usize) -> bool {
+        let (Some(&from), Some(&to)) = (self.component.get(from), self.component.get(to)) else {
+            return false;
+        };
+        if from == to {
+            return true;
+        }
+        if !self.may_reach(from, to) {
+            return false;
+        }
+
+        let mut seen = HashSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(component) = stack.pop() {
+            let successors = &self.successors[component as usize];
+            if successors.contains(&to) {
+                return true;
+            }
+            stack.extend(
+                successors
+                    .iter()
+                    .copied()
+                    .filter(|&next| self.may_reach(next, to) && seen.insert(next)),
+            );
+        }
+        false
+    }
+
+    /// Whether every interval of `to` lies inside the matching interval of
+    /// `from`, and `to` comes later in topological order. False means `from`
+    /// cannot reach `to`.
+    fn may_reach(&self, from: u32, to: u32) -> bool {
+        from > to
+            && self.labels[from as usize]
+                .iter()
+                .zip(&self.labels[to as usize])
+                .all(|(&(low, rank), &(to_low, to_rank))| low <= to_low && to_rank <= rank)
+    }
+}
+
+/// One depth-first pass over the condensation from every component in
+/// random order, children in a random rotation. Returns each component's
+/// (lowest rank in subtree, postorder rank).
+fn label_pass(successors: &[Vec<u32>], rng: &mut SeededRng) -> Vec<(u32, u32)> {
+    let component_count = successors.len();
+    let mut roots: Vec<usize> = (0..component_count).collect();
+    rng.shuffle(&mut roots);
+
+    let mut pass = LabelPass {
+        successors,
+        labels: vec![(0, 0); component_count],
+        visited: vec![false; component_count],
+        rank: 0,
+    };
+    for root in roots {
+        if !pass.visited[root] {
+            pass.visit(root, rng);
+        }
+    }
+    pass.labels
+}
+
+/// State of one labeling pass.
+struct LabelPass<'a> {
+    successors: &'a [Vec<u32>],
+    labels: Vec<(u32, u32)>,
+    visited: Vec<bool>,
+    rank: u32,
+}
+
+impl LabelPass<'_> {
+    /// Label every unvisited component reachable from `root`.
+    fn visit(&mut self, root: usize, rng: &mut SeededRng) {
+        self.visited[root] = true;
+        // Each frame is a component, its child rotation and children done
+        let mut calls = vec![(root, rng.below(self.successors[root].len()), 0)];
+        while let Some((component, rotation, done)) = calls.pop() {
+            let children = &self.successors[component];
+            if done == children.len() {
+                self.finish(component);
+                continue;
+            }
+            calls.push((component, rotation, done + 1));
+            let child = children[(rotation + done) % children.len()] as usize;
+            if !std::mem::replace(&mut self.visited[child], true) {
+                calls.push((child, rng.below(self.successors[child].len()), 0));
+            }
+        }
+    }
+
+    /// Rank `component` once its children are all labeled, which they are
+    /// as the condensation has no cycles.
+    fn finish(&mut self, component: usize) {
+        let low = self.successors[component]
+            .iter()
+            .map(|&c| self.labels[c as usize].0)
+            .fold(self.rank, u32::min);
+        self.labels[component] = (low, self.rank);
+        self.rank += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::traversal::breadth_first;
+
+    #[test]
+    fn test_matches_breadth_first() {
+        let mut rng = SeededRng::new(7);
+        let node_count = 60;
+        let mut adjacency = vec![Vec::new(); node_count];
+        for _ in 0..90 {
+            let (u, v) = (rng.below(node_count), rng.below(node_count));
+            adjacency[u].push(v as u32);
+        }
+        // A cycle so the condensation is not trivial
+        adjacency[3].push(1);
+        adjacency[1].push(2);
+        adjacency[2].push(3);
+        for neighbors in &mut adjacency {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        let index = ReachabilityIndex::new(&adjacency, 1);
+        for from in 0..node_count {
+            let depths = breadth_first(&adjacency, from).depths;
+            for (to, &depth) in depths.iter().enumerate() {
+                assert_eq!(index.is_reachable(from, to), depth != u32::MAX, "{from} → {to}");
+            }
+        }
+        assert!(!index.is_reachable(0, node_count));
+    }
+}
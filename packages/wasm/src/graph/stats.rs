@@ -0,0 +1,369 @@
+//! Whole-graph statistics for network analysis.
+//!
+//! These treat the graph as undirected and simple: edge direction, weights,
+//! parallel edges and self-loops are ignored.
+//!
+//! # Summary
+//!
+//! The overview statistics come from one breadth-first pass per component.
+//! The diameter is estimated by a double sweep: the farthest node from any
+//! start is usually at one end of a longest shortest path, so a second
+//! search from it gives a lower bound that is often exact. Local clustering
+//! is the share of a node's neighbor pairs that are linked, counted by
+//! merging sorted neighbor lists.
+//!
+//! # Rich-Club Coefficient
+//!
+//! The rich-club coefficient at degree `k` is the density of the subgraph
+//! of nodes with degree above `k`: the share of their possible pairs that
+//! are linked. It rises with `k` in any graph, since hubs have more links to
+//! spend, so it is normalized by the same coefficient on a random graph with
+//! the same degrees (made by double edge swaps). A normalized value above 1
+//! means hubs link to each other more than their degrees alone explain.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::traversal::UNREACHED;
+use crate::rng::{SeededRng, DEFAULT_SEED};
+
+/// Summary statistics of a graph, for overview panels.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphStats {
+    /// Nodes in the graph.
+    pub node_count: u32,
+    /// Edges in the graph, counting parallel edges and self-loops.
+    pub edge_count: u32,
+    /// Share of node pairs that are linked.
+    pub density: f64,
+    /// Connected components, isolated nodes included.
+    pub component_count: u32,
+    /// Nodes per degree: entry `d` counts the nodes with `d` neighbors.
+    pub degree_histogram: Vec<u32>,
+    /// Lower bound on the longest shortest path in hops, from a double
+    /// sweep in each component.
+    pub diameter: u32,
+    /// Mean local clustering coefficient, nodes with fewer than two
+    /// neighbors counting as 0.
+    pub average_clustering: f64,
+}
+
+/// Summary statistics over `links` between the slots in `nodes` (the
+/// existing nodes, all below `node_bound`); `edge_count` is reported as is.
+pub fn graph_stats(
+    links: &[(u32, u32)],
+    nodes: &[u32],
+    node_bound: usize,
+    edge_count: u32,
+) -> GraphStats {
+    let mut neighbors = vec![Vec::new(); node_bound];
+    for &(u, v) in links {
+        neighbors[u as usize].push(v);
+        neighbors[v as usize].push(u);
+    }
+    for list in &mut neighbors {
+        list.sort_unstable();
+    }
+
+    let node_count = nodes.len();
+    let density = match node_count {
+        0 | 1 => 0.0,
+        n => 2.0 * links.len() as f64 / (n * (n - 1)) as f64,
+    };
+
+    let max_degree = nodes.iter().map(|&node| neighbors[node as usize].len()).max();
+    let mut degree_histogram = vec![0; max_degree.map_or(0, |d| d + 1)];
+    for &node in nodes {
+        degree_histogram[neighbors[node as usize].len()] += 1;
+    }
+
+    let mut seen = vec![false; node_bound];
+    let mut sweep = Sweep::new(&neighbors);
+    let (mut component_count, mut diameter) = (0, 0);
+    for &node in nodes {
+        if seen[node as usize] {
+            continue;
+        }
+        component_count += 1;
+        let (far, _) = sweep.run(node);
+        for &slot in &sweep.queue {
+            seen[slot as usize] = true;
+        }
+        diameter = diameter.max(sweep.run(far).1);
+    }
+
+    let clustering: f64 = nodes.iter().map(|&node| local_clustering(&neighbors, node)).sum();
+    GraphStats {
+        node_count: node_count as u32,
+        edge_count,
+        density,
+        component_count,
+        degree_histogram,
+        diameter,
+        average_clustering: if node_count == 0 { 0.0 } else { clustering / node_count as f64 },
+    }
+}
+
+/// Breadth-first search buffers reused across searches.
+struct Sweep<'a> {
+    neighbors: &'a [Vec<u32>],
+    /// Hops from the last source per slot, `UNREACHED` outside its component.
+    depth: Vec<u32>,
+    /// Slots the last search reached, in visit order.
+    queue: Vec<u32>,
+}
+
+impl<'a> Sweep<'a> {
+    fn new(neighbors: &'a [Vec<u32>]) -> Self {
+        Self {
+            neighbors,
+            depth: vec![UNREACHED; neighbors.len()],
+            queue: Vec::new(),
+        }
+    }
+
+    /// Search from `source`, returning the last slot reached and its depth.
+    fn run(&mut self, source: u32) -> (u32, u32) {
+        for &slot in &self.queue {
+            self.depth[slot as usize] = UNREACHED;
+        }
+        self.queue.clear();
+        self.depth[source as usize] = 0;
+        self.queue.push(source);
+
+        let mut head = 0;
+        while let Some(&node) = self.queue.get(head) {
+            head += 1;
+            self.visit(node);
+        }
+        let last = self.queue[self.queue.len() - 1];
+        (last, self.depth[last as usize])
+    }
+
+    /// Queue the unreached neighbors of `node`.
+    fn visit(&mut self, node: u32) {
+        let depth = self.depth[node as usize] + 1;
+        for &next in &self.neighbors[node as usize] {
+            if self.depth[next as usize] == UNREACHED {
+                self.depth[next as usize] = depth;
+                self.queue.push(next);
+            }
+        }
+    }
+}
+
+/// Share of the neighbor pairs of `node` that are linked.
+fn local_clustering(neighbors: &[Vec<u32>], node: u32) -> f64 {
+    let own = &neighbors[node as usize];
+    let degree = own.len();
+    if degree < 2 {
+        return 0.0;
+    }
+    // Every link between two neighbors is found from both ends
+    let found: usize = own.iter().map(|&next| common(own, &neighbors[next as usize])).sum();
+    found as f64 / (degree * (degree - 1)) as f64
+}
+
+/// Number of values in both sorted lists.
+fn common(a: &[u32], b: &[u32]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Configuration for the rich-club coefficient. Deserializes from camelCase
+/// JS options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RichClubConfig {
+    /// Divide by the coefficient of a degree-preserving randomization
+    /// (default: true).
+    pub normalized: bool,
+    /// Double edge swaps attempted per edge when randomizing (default: 10).
+    pub swaps_per_edge: u32,
+    /// Seed for randomizing (not read from JS; the engine seed applies).
+    #[serde(skip)]
+    pub seed: u64,
+}
+
+impl Default for RichClubConfig {
+    fn default() -> Self {
+        Self {
+            normalized: true,
+            swaps_per_edge: 10,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Each undirected link of `adjacency` once, as (lower slot, higher slot),
+/// without self-loops. `adjacency` must list every link from both ends.
+pub fn links(adjacency: &[Vec<u32>]) -> Vec<(u32, u32)> {
+    adjacency
+        .iter()
+        .enumerate()
+        .flat_map(|(node, neighbors)| {
+            let node = node as u32;
+            neighbors.iter().filter(move |&&next| node < next).map(move |&next| (node, next))
+        })
+        .collect()
+}
+
+/// Rich-club coefficient for each degree threshold `0..=max_k` over the
+/// links between `node_count` slots. Entries are NaN where fewer than two
+/// nodes have a degree above the threshold.
+pub fn rich_club(
+    links: &[(u32, u32)],
+    node_count: usize,
+    max_k: u32,
+    config: &RichClubConfig,
+) -> Vec<f32> {
+    let raw = rich_club_raw(links, node_count, max_k);
+    if !config.normalized {
+        return raw.into_iter().map(|phi| phi as f32).collect();
+    }
+    let random = randomize(links, config.swaps_per_edge, config.seed);
+    let baseline = rich_club_raw(&random, node_count, max_k);
+    raw.iter().zip(&baseline).map(|(phi, base)| (phi / base) as f32).collect()
+}
+
+/// Unnormalized rich-club coefficients for thresholds `0..=max_k`.
+fn rich_club_raw(links: &[(u32, u32)], node_count: usize, max_k: u32) -> Vec<f64> {
+    let mut degree = vec![0usize; node_count];
+    for &(u, v) in links {
+        degree[u as usize] += 1;
+        degree[v as usize] += 1;
+    }
+
+    // Nodes, and links between nodes, whose (lower) degree is exactly d
+    let bound = degree.iter().copied().max().unwrap_or(0) + 1;
+    let mut nodes_at = vec![0usize; bound];
+    for &d in &degree {
+        nodes_at[d] += 1;
+    }
+    let mut links_at = vec![0usize; bound];
+    for &(u, v) in links {
+        links_at[degree[u as usize].min(degree[v as usize])] += 1;
+    }
+
+    // Running counts above k, from the top degree down
+    let (mut nodes, mut club_links) = (0, 0);
+    let mut coefficients = vec![0.0; max_k as usize + 1];
+    for k in (0..bound.max(max_k as usize + 1)).rev() {
+        if k <= max_k as usize {
+            coefficients[k] = if nodes < 2 {
+                f64::NAN
+            } else {
+                2.0 * club_links as f64 / (nodes * (nodes - 1)) as f64
+            };
+        }
+        nodes += nodes_at.get(k).copied().unwrap_or(0);
+        club_links += links_at.get(k).copied().unwrap_or(0);
+    }
+    coefficients
+}
+
+/// A copy of `links` with the same degrees, randomized by attempting
+/// `swaps_per_edge` double edge swaps per link: `a–b, c–d` becomes
+/// `a–d, c–b` unless that would add a self-loop or a parallel link.
+fn randomize(links: &[(u32, u32)], swaps_per_edge: u32, seed: u64) -> Vec<(u32, u32)> {
+    let mut links = links.to_vec();
+    if links.len() < 2 {
+        return links;
+    }
+    let mut present: HashSet<(u32, u32)> = links.iter().copied().collect();
+    let ordered = |u: u32, v: u32| (u.min(v), u.max(v));
+
+    let mut rng = SeededRng::new(seed);
+    for _ in 0..links.len() * swaps_per_edge as usize {
+        let (i, j) = (rng.below(links.len()), rng.below(links.len()));
+        let (a, b) = links[i];
+        let (c, d) = match rng.below(2) {
+            0 => links[j],
+            _ => (links[j].1, links[j].0),
+        };
+        let (first, second) = (ordered(a, d), ordered(c, b));
+        let degenerate = a == d || c == b || first == second;
+        if degenerate || present.contains(&first) || present.contains(&second) {
+            continue;
+        }
+        present.remove(&links[i]);
+        present.remove(&links[j]);
+        present.extend([first, second]);
+        links[i] = first;
+        links[j] = second;
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_stats() {
+        // Triangle 0-1-2 with a tail 2-3, isolated 4, and a pair 5-6
+        let links = [(0, 1), (0, 2), (1, 2), (2, 3), (5, 6)];
+        let nodes: Vec<u32> = (0..7).collect();
+        let stats = graph_stats(&links, &nodes, 7, 6);
+
+        assert_eq!(stats.node_count, 7);
+        assert_eq!(stats.edge_count, 6);
+        assert!((stats.density - 10.0 / 42.0).abs() < 1e-12);
+        assert_eq!(stats.component_count, 3);
+        assert_eq!(stats.degree_histogram, vec![1, 3, 2, 1]);
+        assert_eq!(stats.diameter, 2);
+        // 0 and 1 score 1, 2 scores 1/3
+        assert!((stats.average_clustering - 1.0 / 3.0).abs() < 1e-12);
+
+        let empty = graph_stats(&[], &[], 0, 0);
+        assert_eq!(empty.component_count, 0);
+        assert!(empty.degree_histogram.is_empty());
+    }
+
+    #[test]
+    fn test_rich_club() {
+        // Complete graph on 0..4, each hub also holding two leaves
+        let mut links = Vec::new();
+        for u in 0..4 {
+            links.extend((u + 1..4).map(|v| (u, v)));
+            links.extend([(u, 4 + 2 * u), (u, 5 + 2 * u)]);
+        }
+        let config = RichClubConfig {
+            normalized: false,
+            ..Default::default()
+        };
+        let phi = rich_club(&links, 12, 6, &config);
+        // k = 0: 14 links over 12 nodes; k = 1..4: the four hubs, fully linked
+        assert!((phi[0] - 14.0 / 66.0).abs() < 1e-6);
+        assert_eq!(&phi[1..5], &[1.0; 4]);
+        assert!(phi[5].is_nan() && phi[6].is_nan());
+
+        // Randomizing keeps degrees and never links a node to itself
+        let random = randomize(&links, 10, 3);
+        let mut degree = vec![0; 12];
+        for &(u, v) in &random {
+            assert!(u < v);
+            degree[u as usize] += 1;
+            degree[v as usize] += 1;
+        }
+        assert_eq!(degree, vec![5, 5, 5, 5, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(random.iter().collect::<HashSet<_>>().len(), random.len());
+
+        let normalized = rich_club(&links, 12, 4, &RichClubConfig::default());
+        assert!(normalized[0] == 1.0 && normalized[4] >= 1.0);
+    }
+}
@@ -0,0 +1,238 @@
+//! Breadth- and depth-first traversal.
+//!
+//! Both walk from one source over the nodes it can reach, by slot, on an
+//! adjacency list built by the engine (outgoing edges, or edges in both
+//! directions). Neighbors are taken in ascending slot order so results are
+//! deterministic. Depth-first order is the preorder of a recursive DFS.
+//!
+//! Strongly connected components use Tarjan's algorithm, run iteratively
+//! so deep graphs cannot overflow the stack.
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+/// Parent and depth of the source and of nodes the traversal never reached.
+pub const UNREACHED: u32 = u32::MAX;
+
+/// Result of a traversal. The per-slot arrays have one entry per node slot.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Traversal {
+    /// Reached slots in visit order, the source first.
+    pub order: Vec<u32>,
+    /// Slot each node was reached from, per slot ([`UNREACHED`] for the
+    /// source and unreached nodes).
+    pub parents: Vec<u32>,
+    /// Depth in the traversal tree per slot: the hop distance from the
+    /// source for breadth-first ([`UNREACHED`] for unreached nodes).
+    pub depths: Vec<u32>,
+}
+
+impl Traversal {
+    fn new(node_count: usize) -> Self {
+        Self {
+            order: Vec::new(),
+            parents: vec![UNREACHED; node_count],
+            depths: vec![UNREACHED; node_count],
+        }
+    }
+
+    /// Record `node` as reached from `parent`.
+    fn visit(&mut self, node: usize, parent: Option<usize>) {
+        self.order.push(node as u32);
+        if let Some(parent) = parent {
+            self.parents[node] = parent as u32;
+            self.depths[node] = self.depths[parent] + 1;
+        } else {
+            self.depths[node] = 0;
+        }
+    }
+}
+
+/// Breadth-first traversal from `source` over `adjacency` (sorted neighbor
+/// slots per slot).
+pub fn breadth_first(adjacency: &[Vec<u32>], source: usize) -> Traversal {
+    let mut traversal = Traversal::new(adjacency.len());
+    if source >= adjacency.len() {
+        return traversal;
+    }
+
+    traversal.visit(source, None);
+    let mut queue = VecDeque::from([source]);
+    while let Some(node) = queue.pop_front() {
+        for &next in &adjacency[node] {
+            let next = next as usize;
+            if next != source && traversal.depths[next] == UNREACHED {
+                traversal.visit(next, Some(node));
+                queue.push_back(next);
+            }
+        }
+    }
+    traversal
+}
+
+/// Depth-first traversal from `source` over `adjacency` (sorted neighbor
+/// slots per slot).
+pub fn depth_first(adjacency: &[Vec<u32>], source: usize) -> Traversal {
+    let mut traversal = Traversal::new(adjacency.len());
+    if source >= adjacency.len() {
+        return traversal;
+    }
+
+    // Neighbors go on the stack in reverse so the lowest is visited first
+    let mut stack = vec![(source, None)];
+    while let Some((node, parent)) = stack.pop() {
+        if traversal.depths[node] != UNREACHED {
+            continue;
+        }
+        traversal.visit(node, parent);
+        stack.extend(
+            adjacency[node]
+                .iter()
+                .rev()
+                .map(|&next| (next as usize, Some(node)))
+                .filter(|&(next, _)| traversal.depths[next] == UNREACHED),
+        );
+    }
+    traversal
+}
+
+/// Strongly connected component per slot of `adjacency`.
+///
+/// Components are numbered in the order Tarjan's algorithm completes them,
+/// sinks first, so every edge between two components points from the
+/// higher component ID to the lower.
+pub fn strongly_connected_components(adjacency: &[Vec<u32>]) -> Vec<u32> {
+    let node_count = adjacency.len();
+    let mut tarjan = Tarjan {
+        adjacency,
+        index: vec![UNREACHED; node_count],
+        lowlink: vec![0; node_count],
+        on_stack: vec![false; node_count],
+        stack: Vec::new(),
+        component: vec![UNREACHED; node_count],
+        component_count: 0,
+        next_index: 0,
+    };
+    for root in 0..node_count {
+        if tarjan.index[root] == UNREACHED {
+            tarjan.connect(root);
+        }
+    }
+    tarjan.component
+}
+
+/// State of Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    adjacency: &'a [Vec<u32>],
+    /// Discovery index per slot.
+    index: Vec<u32>,
+    /// Lowest discovery index reachable through the DFS subtree per slot.
+    lowlink: Vec<u32>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    component: Vec<u32>,
+    component_count: u32,
+    next_index: u32,
+}
+
+impl Tarjan<'_> {
+    /// Depth-first search from `root`, completing every component found.
+    fn connect(&mut self, root: usize) {
+        self.discover(root);
+        // Each frame is a node and the position of its next neighbor
+        let mut calls = vec![(root, 0)];
+        while let Some((node, position)) = calls.pop() {
+            if let Some(&next) = self.adjacency[node].get(position) {
+                calls.push((node, position + 1));
+                calls.extend(self.follow(node, next as usize).map(|child| (child, 0)));
+                continue;
+            }
+
+            if let Some(&(parent, _)) = calls.last() {
+                self.lowlink[parent] = self.lowlink[parent].min(self.lowlink[node]);
+            }
+            if self.lowlink[node] == self.index[node] {
+                self.complete(node);
+            }
+        }
+    }
+
+    /// Take the edge `node` → `next`, returning `next` if it is newly
+    /// discovered and needs searching.
+    fn follow(&mut self, node: usize, next: usize) -> Option<usize> {
+        if self.index[next] == UNREACHED {
+            self.discover(next);
+            return Some(next);
+        }
+        if self.on_stack[next] {
+            self.lowlink[node] = self.lowlink[node].min(self.index[next]);
+        }
+        None
+    }
+
+    fn discover(&mut self, node: usize) {
+        self.index[node] = self.next_index;
+        self.lowlink[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+    }
+
+    /// Pop the component rooted at `node` off the stack.
+    fn complete(&mut self, node: usize) {
+        while let Some(member) = self.stack.pop() {
+            self.on_stack[member] = false;
+            self.component[member] = self.component_count;
+            if member == node {
+                break;
+            }
+        }
+        self.component_count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 → {1, 2}, 1 → 3, 2 → 3, 3 → 0; node 4 is unreachable.
+    fn diamond() -> Vec<Vec<u32>> {
+        vec![vec![1, 2], vec![3], vec![3], vec![0], vec![]]
+    }
+
+    #[test]
+    fn test_breadth_first() {
+        let traversal = breadth_first(&diamond(), 0);
+        assert_eq!(traversal.order, vec![0, 1, 2, 3]);
+        assert_eq!(traversal.parents, vec![UNREACHED, 0, 0, 1, UNREACHED]);
+        assert_eq!(traversal.depths, vec![0, 1, 1, 2, UNREACHED]);
+    }
+
+    #[test]
+    fn test_depth_first() {
+        let traversal = depth_first(&diamond(), 0);
+        // 3 is reached through 1 before 2 is visited
+        assert_eq!(traversal.order, vec![0, 1, 3, 2]);
+        assert_eq!(traversal.parents, vec![UNREACHED, 0, 0, 1, UNREACHED]);
+        assert_eq!(traversal.depths, vec![0, 1, 1, 2, UNREACHED]);
+
+        let traversal = depth_first(&diamond(), 9);
+        assert!(traversal.order.is_empty());
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // The 0 → 1 → 3 → 0 and 0 → 2 → 3 → 0 cycles form one component
+        let mut adjacency = diamond();
+        adjacency[4].push(0);
+        let component = strongly_connected_components(&adjacency);
+        assert!(component[..4].iter().all(|&c| c == component[0]));
+        // 4 only reaches the cycle, so it completes after it
+        assert!(component[4] > component[0]);
+
+        let component = strongly_connected_components(&[vec![1], vec![2], vec![]]);
+        assert_eq!(component, vec![2, 1, 0]);
+    }
+}
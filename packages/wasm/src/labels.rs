@@ -0,0 +1,282 @@
+//! Label placement.
+//!
+//! Decides where each node's label goes and which labels are shown, so the
+//! renderer can draw text without labels covering each other. Label sizes
+//! are measured in JS (screen pixels) and do not scale with zoom, while node
+//! positions do, so the result is computed per zoom level: zoomed out, only
+//! the most important labels fit; zoomed in, more of them do.
+//!
+//! # Algorithm
+//!
+//! Greedy placement per zoom level. Labels are visited by descending
+//! priority and each tries four anchors around its node (right, left, above,
+//! below), taking the first whose screen rectangle does not overlap an
+//! already placed label. Labels that fit nowhere are hidden. Overlap tests
+//! use a uniform grid sized to the largest label, so a level costs O(n) for
+//! evenly spread layouts.
+//!
+//! Levels are processed in the given order (typically ascending zoom). Labels
+//! shown at the previous level are placed first and try their previous
+//! anchor first, so labels rarely jump or disappear while zooming in.
+
+use std::collections::HashMap;
+
+use crate::layout::{SENTINEL, placed_point};
+
+/// Configuration for label placement.
+pub struct LabelPlacementConfig {
+    /// Distance in screen pixels from the node center to the nearest label
+    /// edge (default: 8.0).
+    pub node_gap: f32,
+    /// Minimum gap in screen pixels between two labels (default: 2.0).
+    pub padding: f32,
+}
+
+impl Default for LabelPlacementConfig {
+    fn default() -> Self {
+        Self {
+            node_gap: 8.0,
+            padding: 2.0,
+        }
+    }
+}
+
+/// Candidate anchors in order of preference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    Right,
+    Left,
+    Above,
+    Below,
+}
+
+const ANCHORS: [Anchor; 4] = [Anchor::Right, Anchor::Left, Anchor::Above, Anchor::Below];
+
+impl Anchor {
+    /// Offset of the label's top-left corner from the node center for a
+    /// `width` × `height` label (screen pixels, y down).
+    fn offset(self, width: f32, height: f32, gap: f32) -> (f32, f32) {
+        match self {
+            Anchor::Right => (gap, -height * 0.5),
+            Anchor::Left => (-gap - width, -height * 0.5),
+            Anchor::Above => (-width * 0.5, -gap - height),
+            Anchor::Below => (-width * 0.5, gap),
+        }
+    }
+}
+
+/// Compute label offsets and visibility for each zoom level.
+///
+/// # Arguments
+///
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] in world
+///   units; sentinel entries have no label
+/// * `sizes` - Label size per slot [w0, h0, w1, h1, ...] in screen pixels;
+///   missing or empty sizes mean no label
+/// * `priorities` - Priority per slot, higher placed first (missing = 0)
+/// * `zoom_levels` - Screen pixels per world unit for each level
+/// * `config` - Placement configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` with one block per zoom level, each holding the offset of
+/// every slot's label top-left corner from its node in screen pixels
+/// [dx0, dy0, dx1, dy1, ...]. Hidden labels get sentinel values (f32::MAX).
+pub fn compute_label_placement(
+    positions: &[f32],
+    sizes: &[f32],
+    priorities: &[f32],
+    zoom_levels: &[f32],
+    config: &LabelPlacementConfig,
+) -> Vec<f32> {
+    let node_count = positions.len() / 2;
+    let mut offsets = vec![SENTINEL; zoom_levels.len() * node_count * 2];
+
+    let mut labeled: Vec<usize> = (0..node_count)
+        .filter(|&i| placed_point(positions, i).is_some())
+        .filter(|&i| label_size(sizes, i).is_some())
+        .collect();
+    labeled.sort_by(|&a, &b| priority(priorities, b).total_cmp(&priority(priorities, a)));
+
+    let cell = labeled
+        .iter()
+        .filter_map(|&i| label_size(sizes, i))
+        .map(|(w, h)| w.max(h) + config.padding)
+        .fold(1.0f32, f32::max);
+
+    let mut previous: Vec<Option<Anchor>> = vec![None; node_count];
+    for (level, &zoom) in zoom_levels.iter().enumerate() {
+        // Stable sort: labels shown last level first, priority order within
+        let mut order = labeled.clone();
+        order.sort_by_key(|&i| previous[i].is_none());
+
+        let mut board = Board::new(cell);
+        let mut current = vec![None; node_count];
+        for i in order {
+            let (w, h) = label_size(sizes, i).unwrap_or_default();
+            let center = (positions[i * 2] * zoom, positions[i * 2 + 1] * zoom);
+            current[i] = board.place(center, (w, h), previous[i], config);
+        }
+
+        let block = &mut offsets[level * node_count * 2..(level + 1) * node_count * 2];
+        for (i, anchor) in current.iter().enumerate() {
+            let Some(anchor) = anchor else { continue };
+            let (w, h) = label_size(sizes, i).unwrap_or_default();
+            let (dx, dy) = anchor.offset(w, h, config.node_gap);
+            block[i * 2] = dx;
+            block[i * 2 + 1] = dy;
+        }
+        previous = current;
+    }
+
+    offsets
+}
+
+/// Size of a slot's label, if it has a non-empty one.
+fn label_size(sizes: &[f32], slot: usize) -> Option<(f32, f32)> {
+    let w = *sizes.get(slot * 2)?;
+    let h = *sizes.get(slot * 2 + 1)?;
+    (w > 0.0 && h > 0.0 && w.is_finite() && h.is_finite()).then_some((w, h))
+}
+
+/// Priority of a slot; missing and NaN count as 0.
+fn priority(priorities: &[f32], slot: usize) -> f32 {
+    match priorities.get(slot) {
+        Some(&p) if !p.is_nan() => p,
+        _ => 0.0,
+    }
+}
+
+/// Placed label rectangles in screen space with a uniform grid for
+/// overlap queries.
+struct Board {
+    /// Rectangles as [min_x, min_y, max_x, max_y].
+    rects: Vec<[f32; 4]>,
+    grid: HashMap<(i32, i32), Vec<usize>>,
+    cell: f32,
+}
+
+impl Board {
+    fn new(cell: f32) -> Self {
+        Self {
+            rects: Vec::new(),
+            grid: HashMap::new(),
+            cell,
+        }
+    }
+
+    /// Place a label at the first free anchor, trying `preferred` first.
+    /// Returns the anchor used, or `None` if every anchor overlaps.
+    fn place(
+        &mut self,
+        center: (f32, f32),
+        size: (f32, f32),
+        preferred: Option<Anchor>,
+        config: &LabelPlacementConfig,
+    ) -> Option<Anchor> {
+        let (w, h) = size;
+        let pad = config.padding * 0.5;
+        let candidates = preferred.into_iter().chain(ANCHORS);
+        let (anchor, rect) = candidates
+            .map(|anchor| {
+                let (dx, dy) = anchor.offset(w, h, config.node_gap);
+                let (x, y) = (center.0 + dx, center.1 + dy);
+                (anchor, [x - pad, y - pad, x + w + pad, y + h + pad])
+            })
+            .find(|(_, rect)| self.is_free(rect))?;
+        self.insert(rect);
+        Some(anchor)
+    }
+
+    fn is_free(&self, rect: &[f32; 4]) -> bool {
+        self.cells(rect)
+            .filter_map(|key| self.grid.get(&key))
+            .flatten()
+            .all(|&k| !overlaps(&self.rects[k], rect))
+    }
+
+    fn insert(&mut self, rect: [f32; 4]) {
+        let index = self.rects.len();
+        let keys: Vec<(i32, i32)> = self.cells(&rect).collect();
+        for key in keys {
+            self.grid.entry(key).or_default().push(index);
+        }
+        self.rects.push(rect);
+    }
+
+    /// Grid cells covered by a rectangle.
+    fn cells(&self, rect: &[f32; 4]) -> impl Iterator<Item = (i32, i32)> + use<> {
+        let cell = |v: f32| (v / self.cell).floor() as i32;
+        let (x0, y0, x1, y1) = (cell(rect[0]), cell(rect[1]), cell(rect[2]), cell(rect[3]));
+        (x0..=x1).flat_map(move |cx| (y0..=y1).map(move |cy| (cx, cy)))
+    }
+}
+
+/// Whether two rectangles overlap with positive area.
+fn overlaps(a: &[f32; 4], b: &[f32; 4]) -> bool {
+    a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(offsets: &[f32], node_count: usize, level: usize, slot: usize) -> (f32, f32) {
+        let base = (level * node_count + slot) * 2;
+        (offsets[base], offsets[base + 1])
+    }
+
+    #[test]
+    fn test_separate_labels_go_right() {
+        let positions = [0.0, 0.0, 500.0, 0.0];
+        let sizes = [40.0, 10.0, 40.0, 10.0];
+        let offsets = compute_label_placement(&positions, &sizes, &[], &[1.0], &Default::default());
+
+        assert_eq!(offset(&offsets, 2, 0, 0), (8.0, -5.0));
+        assert_eq!(offset(&offsets, 2, 0, 1), (8.0, -5.0));
+    }
+
+    #[test]
+    fn test_crowded_labels_move_or_hide_by_priority() {
+        // Three nodes 10 world units apart with 40×10 labels; node 2 matters most
+        let positions = [0.0, 0.0, 10.0, 0.0, 20.0, 0.0];
+        let sizes = [40.0, 10.0, 40.0, 10.0, 40.0, 10.0];
+        let priorities = [1.0, 0.0, 5.0];
+        let config = LabelPlacementConfig::default();
+        let offsets =
+            compute_label_placement(&positions, &sizes, &priorities, &[1.0, 10.0], &config);
+
+        // Zoomed out: node 2 takes the right, node 0 goes left and node 1,
+        // blocked on both sides, moves above
+        assert_eq!(offset(&offsets, 3, 0, 2), (8.0, -5.0));
+        assert_eq!(offset(&offsets, 3, 0, 0), (-48.0, -5.0));
+        assert_eq!(offset(&offsets, 3, 0, 1), (-20.0, -18.0));
+
+        // Zoomed in: nodes are 100px apart, everyone keeps their anchor
+        for slot in 0..3 {
+            assert_eq!(offset(&offsets, 3, 1, slot), offset(&offsets, 3, 0, slot));
+        }
+    }
+
+    #[test]
+    fn test_hidden_when_no_anchor_fits() {
+        // Five coincident nodes: four anchors, the lowest priority loses
+        let positions = [0.0; 10];
+        let sizes = [20.0, 10.0].repeat(5);
+        let priorities = [4.0, 3.0, 2.0, 1.0, 0.0];
+        let offsets =
+            compute_label_placement(&positions, &sizes, &priorities, &[1.0], &Default::default());
+
+        assert!((0..4).all(|slot| offset(&offsets, 5, 0, slot).0 < SENTINEL));
+        assert_eq!(offset(&offsets, 5, 0, 4), (SENTINEL, SENTINEL));
+    }
+
+    #[test]
+    fn test_missing_nodes_and_sizes_have_no_label() {
+        let positions = [0.0, 0.0, SENTINEL, SENTINEL, 100.0, 0.0];
+        let sizes = [0.0, 10.0, 40.0, 10.0];
+        let offsets = compute_label_placement(&positions, &sizes, &[], &[1.0], &Default::default());
+
+        assert!(offsets.iter().all(|&v| v == SENTINEL));
+    }
+}
@@ -0,0 +1,155 @@
+//! Procrustes alignment of layouts.
+//!
+//! Layouts are only defined up to rotation, translation and reflection, so
+//! recomputing one after a small graph change can spin or mirror the whole
+//! drawing. Aligning the new result to the positions on screen picks the
+//! rigid transform (plus optional flip) that moves nodes the least, keeping
+//! the user's mental map. Scale is left alone, so layouts keep their own
+//! spacing.
+//!
+//! In 2D the optimal rotation has a closed form: with both point sets
+//! centered on the centroid of their shared nodes, the angle is
+//! `atan2(Σ x·y' − y·x', Σ x·x' + y·y')`. The mirrored set is solved the same
+//! way and wins if it correlates better.
+
+use super::placed_point;
+
+/// Align `targets` to `reference` in place by rotation, translation and,
+/// if `allow_reflection`, a flip.
+///
+/// The transform is fitted on nodes positioned in both buffers and applied
+/// to every positioned node in `targets`; sentinel entries stay as they
+/// are. With fewer than two shared nodes only the translation is fitted
+/// (or nothing, with none).
+///
+/// # Arguments
+///
+/// * `targets` - Interleaved positions to align [x0, y0, x1, y1, ...]
+/// * `reference` - Interleaved positions to align to, same slot order
+/// * `allow_reflection` - Whether mirroring may be part of the transform
+///
+/// # Returns
+///
+/// The number of shared nodes the transform was fitted on.
+pub fn align_positions(targets: &mut [f32], reference: &[f32], allow_reflection: bool) -> u32 {
+    let shared: Vec<usize> = (0..targets.len().min(reference.len()) / 2)
+        .filter(|&i| placed_point(targets, i).is_some() && placed_point(reference, i).is_some())
+        .collect();
+    if shared.is_empty() {
+        return 0;
+    }
+
+    let n = shared.len() as f32;
+    let centroid = |buffer: &[f32]| {
+        let (sx, sy) = shared.iter().fold((0.0f32, 0.0f32), |(sx, sy), &i| {
+            (sx + buffer[i * 2], sy + buffer[i * 2 + 1])
+        });
+        (sx / n, sy / n)
+    };
+    let (tx, ty) = centroid(targets);
+    let (rx, ry) = centroid(reference);
+
+    // Correlation terms for the plain and mirrored (x → -x) targets
+    let (mut dot, mut cross, mut dot_flip, mut cross_flip) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for &i in &shared {
+        let (x, y) = (targets[i * 2] - tx, targets[i * 2 + 1] - ty);
+        let (u, v) = (reference[i * 2] - rx, reference[i * 2 + 1] - ry);
+        dot += x * u + y * v;
+        cross += x * v - y * u;
+        dot_flip += -x * u + y * v;
+        cross_flip += -x * v - y * u;
+    }
+    let flip = allow_reflection && dot_flip.hypot(cross_flip) > dot.hypot(cross);
+    let (dot, cross) = if flip {
+        (dot_flip, cross_flip)
+    } else {
+        (dot, cross)
+    };
+    let angle = if shared.len() < 2 {
+        0.0
+    } else {
+        cross.atan2(dot)
+    };
+    let (sin, cos) = angle.sin_cos();
+
+    for i in 0..targets.len() / 2 {
+        if placed_point(targets, i).is_none() {
+            continue;
+        }
+        let x = targets[i * 2] - tx;
+        let x = if flip { -x } else { x };
+        let y = targets[i * 2 + 1] - ty;
+        targets[i * 2] = rx + x * cos - y * sin;
+        targets[i * 2 + 1] = ry + x * sin + y * cos;
+    }
+
+    shared.len() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+
+    /// An asymmetric shape so the rotation and flip are unambiguous.
+    const SHAPE: [f32; 8] = [0.0, 0.0, 40.0, 0.0, 40.0, 10.0, 0.0, 30.0];
+
+    fn transform(points: &[f32], angle: f32, flip: bool, shift: (f32, f32)) -> Vec<f32> {
+        let (sin, cos) = angle.sin_cos();
+        points
+            .chunks_exact(2)
+            .flat_map(|p| {
+                let x = if flip { -p[0] } else { p[0] };
+                [
+                    x * cos - p[1] * sin + shift.0,
+                    x * sin + p[1] * cos + shift.1,
+                ]
+            })
+            .collect()
+    }
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-3, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_undoes_rotation_and_translation() {
+        let mut targets = transform(&SHAPE, 2.0, false, (500.0, -80.0));
+        let shared = align_positions(&mut targets, &SHAPE, false);
+
+        assert_eq!(shared, 4);
+        assert_close(&targets, &SHAPE);
+    }
+
+    #[test]
+    fn test_undoes_flip_only_when_allowed() {
+        let mirrored = transform(&SHAPE, 0.7, true, (10.0, 10.0));
+
+        let mut targets = mirrored.clone();
+        align_positions(&mut targets, &SHAPE, true);
+        assert_close(&targets, &SHAPE);
+
+        // Without reflection the fit stays a proper rotation of the mirror
+        let mut targets = mirrored;
+        align_positions(&mut targets, &SHAPE, false);
+        let cross = (targets[2] - targets[0]) * (targets[7] - targets[1])
+            - (targets[3] - targets[1]) * (targets[6] - targets[0]);
+        assert!(cross < 0.0);
+    }
+
+    #[test]
+    fn test_new_nodes_follow_and_sentinels_stay() {
+        // Slot 4 is new in the targets, slot 5 has no target
+        let mut reference = SHAPE.to_vec();
+        reference.extend([SENTINEL, SENTINEL, 1.0, 1.0]);
+        let mut targets = transform(&SHAPE, -1.0, false, (3.0, 4.0));
+        targets.extend(transform(&[20.0, 20.0], -1.0, false, (3.0, 4.0)));
+        targets.extend([SENTINEL, SENTINEL]);
+
+        assert_eq!(align_positions(&mut targets, &reference, false), 4);
+        assert_close(&targets[8..10], &[20.0, 20.0]);
+        assert_eq!(&targets[10..], &[SENTINEL, SENTINEL]);
+    }
+}
@@ -0,0 +1,171 @@
+//! Arc diagram layout.
+//!
+//! Places every node on a single horizontal axis and draws each edge as a
+//! semicircle-like arc between its endpoints. The order along the axis uses
+//! the same orderings as the circular layout (degree, community, custom).
+//!
+//! Each edge gets the control point of a quadratic Bézier curve from source
+//! to target. Arcs span proportionally to the distance between endpoints, so
+//! long-range edges rise higher and stay readable.
+//!
+//! Works on the slot space directly: removed slots get the sentinel, while
+//! nodes without edges are still placed.
+
+use super::SENTINEL;
+use super::circular::{CircularOrdering, circular_order};
+use super::distance::csr_pairs;
+
+/// Configuration for the arc diagram layout.
+pub struct ArcLayoutConfig {
+    /// Distance between neighbors on the axis (default: 30.0).
+    pub node_spacing: f32,
+    /// Arc height as a fraction of half the span; 1.0 draws semicircle-like
+    /// arcs (default: 1.0).
+    pub arc_height: f32,
+    /// Draw edges running right-to-left below the axis instead of above,
+    /// which shows direction in directed graphs (default: false).
+    pub split_by_direction: bool,
+}
+
+impl Default for ArcLayoutConfig {
+    fn default() -> Self {
+        Self {
+            node_spacing: 30.0,
+            arc_height: 1.0,
+            split_by_direction: false,
+        }
+    }
+}
+
+/// Result of the arc diagram layout.
+pub struct ArcLayout {
+    /// Interleaved node positions [x0, y0, x1, y1, ...] (sentinel if empty).
+    pub positions: Vec<f32>,
+    /// Quadratic Bézier control point of every edge, in CSR target order.
+    /// Edges with an empty endpoint get the sentinel; self loops get the
+    /// node's own position.
+    pub controls: Vec<(f32, f32)>,
+}
+
+impl ArcLayout {
+    /// Flatten into a single buffer:
+    /// `[x0, y0, ..., x_{n-1}, y_{n-1}, edge_count, cx0, cy0, cx1, cy1, ...]`
+    pub fn to_flat(&self) -> Vec<f32> {
+        let mut flat = Vec::with_capacity(self.positions.len() + 1 + self.controls.len() * 2);
+        flat.extend_from_slice(&self.positions);
+        flat.push(self.controls.len() as f32);
+        flat.extend(self.controls.iter().flat_map(|&(x, y)| [x, y]));
+        flat
+    }
+}
+
+/// Compute an arc diagram layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+/// * `ordering` - Order of nodes along the axis, left to right
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// Node positions on the line y = 0 (centered on x = 0) and one arc control
+/// point per edge. Arcs rise above the axis (negative y).
+pub fn compute_arc_layout(
+    csr: &[u32],
+    present: &[bool],
+    ordering: &CircularOrdering,
+    config: &ArcLayoutConfig,
+) -> ArcLayout {
+    let n = present.len();
+    let mut positions = vec![SENTINEL; n * 2];
+    let order = circular_order(present, ordering);
+    let offset = (order.len() as f32 - 1.0) * 0.5 * config.node_spacing;
+    for (index, &slot) in order.iter().enumerate() {
+        positions[slot * 2] = index as f32 * config.node_spacing - offset;
+        positions[slot * 2 + 1] = 0.0;
+    }
+
+    // A quadratic curve peaks halfway to its control point, so the control
+    // sits at twice the arc height (arc_height * span / 2)
+    let x = |slot: usize| (slot < n && present[slot]).then(|| positions[slot * 2]);
+    let controls = csr_pairs(csr, n)
+        .map(|(src, tgt)| match (x(src), x(tgt)) {
+            (Some(xs), Some(xt)) => {
+                let span = xt - xs;
+                let side = if config.split_by_direction && span < 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                ((xs + xt) * 0.5, side * span.abs() * config.arc_height)
+            }
+            _ => (SENTINEL, SENTINEL),
+        })
+        .collect();
+
+    ArcLayout {
+        positions,
+        controls,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    #[test]
+    fn test_nodes_on_axis_in_order() {
+        let present = [true, false, true, true];
+        let layout = compute_arc_layout(
+            &csr_from_edges(4, &[]),
+            &present,
+            &CircularOrdering::Custom(&[3, 0, 2]),
+            &ArcLayoutConfig::default(),
+        );
+
+        assert_eq!(
+            layout.positions,
+            vec![0.0, 0.0, SENTINEL, SENTINEL, 30.0, 0.0, -30.0, 0.0]
+        );
+        assert!(layout.controls.is_empty());
+    }
+
+    #[test]
+    fn test_arc_controls_scale_with_span() {
+        // 0 → 1 spans one gap, 0 → 2 two gaps; 2 → 0 runs backwards
+        let csr = csr_from_edges(3, &[(0, 1), (0, 2), (2, 0)]);
+        let present = [true; 3];
+        let config = ArcLayoutConfig {
+            split_by_direction: true,
+            ..Default::default()
+        };
+        let layout = compute_arc_layout(&csr, &present, &CircularOrdering::Custom(&[]), &config);
+
+        assert_eq!(
+            layout.controls,
+            vec![(-15.0, -30.0), (0.0, -60.0), (0.0, 60.0)]
+        );
+    }
+
+    #[test]
+    fn test_flat_output_and_missing_endpoint() {
+        let csr = csr_from_edges(2, &[(0, 1), (0, 5), (1, 1)]);
+        let present = [true, true];
+        let layout = compute_arc_layout(
+            &csr,
+            &present,
+            &CircularOrdering::Custom(&[]),
+            &ArcLayoutConfig::default(),
+        );
+
+        assert_eq!(
+            layout.to_flat(),
+            vec![
+                -15.0, 0.0, 15.0, 0.0, 3.0, 0.0, -30.0, SENTINEL, SENTINEL, 15.0, 0.0,
+            ]
+        );
+    }
+}
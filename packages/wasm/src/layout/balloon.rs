@@ -0,0 +1,178 @@
+//! Balloon tree layout.
+//!
+//! Every subtree occupies a circle ("balloon") and the balloons of a node's
+//! children sit on a ring around it. Sits between the tidy tree and circle
+//! packing: parents stay right next to their children like in packing, but
+//! edges remain visible like in a tree. Works best for wide, shallow trees.
+//!
+//! # Algorithm
+//!
+//! 1. **Build hierarchy tree** from containment edges, see [`Hierarchy`].
+//! 2. **Bottom-up radii**: a leaf's balloon is the node itself. An internal
+//!    node gives each child a wedge of the full circle proportional to the
+//!    child's balloon radius, then picks the smallest ring radius at which
+//!    every child balloon fits its wedge and clears the parent node. Its own
+//!    balloon encloses that ring.
+//! 3. **Top-down placement**: the root sits at the origin; each node lays
+//!    its children out around itself starting from the direction of its own
+//!    parent, so the edge to the parent runs between two wedges.
+
+use std::f32::consts::{PI, TAU};
+
+use super::SENTINEL;
+use super::hierarchy::Hierarchy;
+
+/// Configuration for the balloon tree layout.
+pub struct BalloonLayoutConfig {
+    /// Radius reserved for each node (default: 10.0).
+    pub node_radius: f32,
+    /// Minimum gap between neighboring balloons and between a balloon and
+    /// its parent node (default: 10.0).
+    pub spacing: f32,
+}
+
+impl Default for BalloonLayoutConfig {
+    fn default() -> Self {
+        Self {
+            node_radius: 10.0,
+            spacing: 10.0,
+        }
+    }
+}
+
+/// Compute a balloon tree layout from the containment hierarchy.
+///
+/// # Arguments
+///
+/// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+/// * `node_count` - Total number of node slots
+/// * `root_id` - Optional root node ID (None = auto-detect)
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...] with
+/// the root at the origin. Nodes not in the tree get sentinel values
+/// (f32::MAX).
+pub fn compute_balloon_layout(
+    containment_edges: &[u32],
+    node_count: usize,
+    root_id: Option<u32>,
+    config: &BalloonLayoutConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; node_count * 2];
+    let Some(tree) = Hierarchy::from_containment(containment_edges, node_count, root_id) else {
+        return positions;
+    };
+
+    // Bottom-up: balloon radius per node and ring radius of its children
+    let mut balloon = vec![config.node_radius; node_count];
+    let mut ring = vec![0.0f32; node_count];
+    for &node in tree.order.iter().rev() {
+        let radii: Vec<f32> = tree.children[node].iter().map(|&c| balloon[c]).collect();
+        let total: f32 = radii.iter().sum();
+        let Some(largest) = radii.iter().copied().reduce(f32::max) else {
+            continue;
+        };
+        ring[node] = radii
+            .iter()
+            .map(|&r| ring_radius_for(r, TAU * r / total, config))
+            .fold(0.0, f32::max);
+        balloon[node] = ring[node] + largest;
+    }
+
+    // Top-down: children around each node, starting from the parent side
+    let mut facing = vec![-PI / 2.0; node_count];
+    positions[tree.root * 2] = 0.0;
+    positions[tree.root * 2 + 1] = 0.0;
+    for &node in &tree.order {
+        let total: f32 = tree.children[node].iter().map(|&c| balloon[c]).sum();
+        let (x, y) = (positions[node * 2], positions[node * 2 + 1]);
+        let mut cursor = facing[node];
+        for &child in &tree.children[node] {
+            let wedge = TAU * balloon[child] / total;
+            let angle = cursor + wedge * 0.5;
+            cursor += wedge;
+            positions[child * 2] = x + ring[node] * angle.cos();
+            positions[child * 2 + 1] = y + ring[node] * angle.sin();
+            facing[child] = angle + PI;
+        }
+    }
+
+    positions
+}
+
+/// Smallest distance from a parent at which a child balloon of radius `r`
+/// fits a wedge of `wedge` radians and clears the parent node.
+fn ring_radius_for(r: f32, wedge: f32, config: &BalloonLayoutConfig) -> f32 {
+    let clear_parent = config.node_radius + config.spacing + r;
+    // A wedge narrower than half the circle bounds the balloon by its sides
+    let fit_wedge = if wedge < PI {
+        (r + config.spacing * 0.5) / (wedge * 0.5).sin()
+    } else {
+        0.0
+    };
+    clear_parent.max(fit_wedge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(positions: &[f32], slot: usize) -> (f32, f32) {
+        (positions[slot * 2], positions[slot * 2 + 1])
+    }
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        (a.0 - b.0).hypot(a.1 - b.1)
+    }
+
+    #[test]
+    fn test_star_children_evenly_around_root() {
+        let edges = [0, 1, 0, 2, 0, 3, 0, 4];
+        let positions = compute_balloon_layout(&edges, 6, None, &Default::default());
+
+        assert_eq!(point(&positions, 0), (0.0, 0.0));
+        let r = distance(point(&positions, 1), (0.0, 0.0));
+        for slot in 2..5 {
+            assert!((distance(point(&positions, slot), (0.0, 0.0)) - r).abs() < 1e-3);
+        }
+        // Neighbors a quarter turn apart
+        let gap = distance(point(&positions, 1), point(&positions, 2));
+        assert!((gap - r * std::f32::consts::SQRT_2).abs() < 1e-3);
+        assert_eq!(positions[10], SENTINEL);
+    }
+
+    #[test]
+    fn test_single_child_continues_away_from_parent() {
+        let positions = compute_balloon_layout(&[0, 1, 1, 2], 3, Some(0), &Default::default());
+        let (a, b, c) = (
+            point(&positions, 0),
+            point(&positions, 1),
+            point(&positions, 2),
+        );
+        // Collinear: 0 → 1 → 2 in a straight line
+        let cross = (b.0 - a.0) * (c.1 - b.1) - (b.1 - a.1) * (c.0 - b.0);
+        assert!(cross.abs() < 1e-2);
+        assert!(distance(a, c) > distance(a, b));
+    }
+
+    #[test]
+    fn test_sibling_balloons_do_not_overlap() {
+        // Root with one big subtree (1 with six leaves) and three leaves
+        let mut edges = vec![0, 1, 0, 2, 0, 3, 0, 4];
+        for leaf in 5..11 {
+            edges.extend([1, leaf]);
+        }
+        let config = BalloonLayoutConfig::default();
+        let positions = compute_balloon_layout(&edges, 11, None, &config);
+
+        // Leaves of 1 stay within its balloon, clear of the other children
+        for leaf in 5..11 {
+            for other in 2..5 {
+                let gap = distance(point(&positions, leaf), point(&positions, other));
+                assert!(gap >= 2.0 * config.node_radius + config.spacing - 1e-3);
+            }
+        }
+    }
+}
@@ -2,14 +2,29 @@
 //!
 //! Computes two per-node values from the graph's containment hierarchy:
 //! - **Well radius** (bubble size): Bottom-up from subtree, leaves get a base
-//!   radius, internal nodes get `sqrt(sum_child_areas / (pi * packing_eff)) + padding`.
-//! - **Depth**: BFS distance from the auto-detected root.
+//!   radius scaled by the square root of their weight (so bubble area grows
+//!   with the weight, e.g. lines of code), internal nodes get
+//!   `sqrt(sum_child_areas / (pi * packing_eff)) + padding`.
+//! - **Depth**: BFS distance from the root of the node's tree.
+//!
+//! The hierarchy may be a forest (several repositories in one graph): every
+//! node without a parent roots its own tree, largest first, and each
+//! component gets its own radii and depths. Depths can be offset per
+//! component so separate trees sit in separate wells.
 //!
 //! These values are uploaded to GPU buffers and used by the Relativity Atlas
 //! algorithm's bubble mode for depth-decaying gravity, wellRadius-based phantom
 //! zones, and scaled orbit springs.
+//!
+//! For drawing the wells, [`bubble_circles`] turns the data into one circle
+//! per internal node at its current position (SDF parameters for instanced
+//! drawing), and [`tessellate_circles`] turns circles into a line-list
+//! vertex buffer for renderers without an SDF pass.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use super::is_placed;
 
 /// Configuration for bubble radius computation.
 pub struct BubbleConfig {
@@ -19,6 +34,10 @@ pub struct BubbleConfig {
     pub padding: f32,
     /// Packing efficiency for circle packing (default: 0.82).
     pub packing_efficiency: f32,
+    /// Depth added per component, in root order: the k-th tree's depths
+    /// start at `k * component_depth_offset` (default: 0.0, every root at
+    /// depth 0).
+    pub component_depth_offset: f32,
 }
 
 impl Default for BubbleConfig {
@@ -27,6 +46,7 @@ impl Default for BubbleConfig {
             base_radius: 10.0,
             padding: 5.0,
             packing_efficiency: 0.82,
+            component_depth_offset: 0.0,
         }
     }
 }
@@ -48,8 +68,12 @@ struct TreeNode {
 /// # Arguments
 ///
 /// * `containment_edges` - Flat `[parent0, child0, parent1, child1, ...]`
+/// * `weights` - Optional size per leaf slot (empty = 1 per leaf); a leaf of
+///   weight `w` gets radius `base_radius * sqrt(w)`. Missing entries count
+///   as 1; negative and NaN weights as 0.
 /// * `node_count` - Total number of node slots (node_bound)
-/// * `root_id` - Optional root node ID (None = auto-detect)
+/// * `root_id` - Optional root of the first tree (None = largest tree
+///   first); other components are computed too
 /// * `config` - Bubble configuration
 ///
 /// # Returns
@@ -58,6 +82,7 @@ struct TreeNode {
 /// `[wellRadius_0, ..., wellRadius_{n-1}, depth_0_as_f32, ..., depth_{n-1}_as_f32]`
 pub fn compute_bubble_data(
     containment_edges: &[u32],
+    weights: &[f32],
     node_count: usize,
     root_id: Option<u32>,
     config: &BubbleConfig,
@@ -66,8 +91,10 @@ pub fn compute_bubble_data(
         return Vec::new();
     }
 
-    // Default values: base_radius for wellRadius, 0.0 for depth
-    let mut well_radii = vec![config.base_radius; node_count];
+    // Default values: the leaf radius for wellRadius, 0.0 for depth
+    let mut well_radii: Vec<f32> = (0..node_count)
+        .map(|slot| config.base_radius * leaf_weight(weights, slot).sqrt())
+        .collect();
     let mut depths = vec![0.0_f32; node_count];
 
     if containment_edges.len() < 2 || containment_edges.len() % 2 != 0 {
@@ -79,7 +106,7 @@ pub fn compute_bubble_data(
     // Build parent→children adjacency
     let mut children_map: HashMap<u32, Vec<u32>> = HashMap::new();
     let mut has_parent: HashSet<u32> = HashSet::new();
-    let mut all_nodes: HashSet<u32> = HashSet::new();
+    let mut all_nodes: BTreeSet<u32> = BTreeSet::new();
 
     let edge_count = containment_edges.len() / 2;
     for i in 0..edge_count {
@@ -105,60 +132,54 @@ pub fn compute_bubble_data(
         return result;
     }
 
-    // Find root (same heuristic as codebase.rs)
-    let root = if let Some(r) = root_id {
-        r
-    } else {
-        let roots: Vec<u32> = all_nodes
-            .iter()
-            .filter(|n| !has_parent.contains(n))
-            .copied()
-            .collect();
-
-        if roots.is_empty() {
-            *all_nodes.iter().min().unwrap_or(&0)
-        } else if roots.len() == 1 {
-            roots[0]
-        } else {
-            roots
-                .iter()
-                .max_by_key(|&&r| count_descendants(r, &children_map))
-                .copied()
-                .unwrap_or(roots[0])
-        }
-    };
+    // Every parentless node roots a tree: the requested root first, then
+    // by descending size (lowest ID on ties). Nodes left over sit on
+    // cycles; each such component starts from its lowest ID.
+    let mut roots: Vec<u32> = all_nodes
+        .iter()
+        .filter(|n| !has_parent.contains(n))
+        .copied()
+        .collect();
+    roots.sort_by_key(|&r| (Reverse(count_descendants(r, &children_map)), r));
+    if let Some(r) = root_id {
+        roots.retain(|&other| other != r);
+        roots.insert(0, r);
+    }
 
-    // Build tree via DFS with cycle detection
+    // Build trees via DFS with cycle detection; each tree's nodes are
+    // contiguous, starting at its root
     let mut tree_nodes: Vec<TreeNode> = Vec::new();
     let mut slot_to_tree: HashMap<u32, usize> = HashMap::new();
     let mut visited: HashSet<u32> = HashSet::new();
+    let mut tree_roots: Vec<usize> = Vec::new();
 
-    build_tree(
-        root,
-        node_count,
-        &children_map,
-        &mut tree_nodes,
-        &mut slot_to_tree,
-        &mut visited,
-    );
-
-    if tree_nodes.is_empty() {
-        let mut result = well_radii;
-        result.extend_from_slice(&depths);
-        return result;
+    for start in roots.into_iter().chain(all_nodes.iter().copied()) {
+        if visited.contains(&start) {
+            continue;
+        }
+        let root_idx = tree_nodes.len();
+        build_tree(
+            start,
+            node_count,
+            &children_map,
+            &mut tree_nodes,
+            &mut slot_to_tree,
+            &mut visited,
+        );
+        if tree_nodes.len() > root_idx {
+            tree_roots.push(root_idx);
+        }
     }
 
-    // Compute depths via BFS from root (index 0 in tree_nodes)
-    compute_depths(&mut tree_nodes);
-
-    // Bottom-up radius computation
-    compute_radii(0, &mut tree_nodes, config);
+    for (component, &root_idx) in tree_roots.iter().enumerate() {
+        compute_depths(&mut tree_nodes, root_idx);
+        compute_radii(root_idx, &mut tree_nodes, weights, config);
 
-    // Write results back to per-slot arrays
-    for node in &tree_nodes {
-        if node.slot < node_count {
+        let end = tree_roots.get(component + 1).copied().unwrap_or(tree_nodes.len());
+        let offset = component as f32 * config.component_depth_offset;
+        for node in &tree_nodes[root_idx..end] {
             well_radii[node.slot] = node.radius;
-            depths[node.slot] = node.depth as f32;
+            depths[node.slot] = node.depth as f32 + offset;
         }
     }
 
@@ -168,6 +189,74 @@ pub fn compute_bubble_data(
     result
 }
 
+/// Well circle of every internal node (a node with children), for drawing
+/// container boundaries.
+///
+/// # Arguments
+///
+/// * `containment_edges` - Flat `[parent0, child0, parent1, child1, ...]`
+/// * `bubble_data` - Output of [`compute_bubble_data`]: well radii, then
+///   depths
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...]; nodes
+///   with sentinel or non-finite positions are skipped
+///
+/// # Returns
+///
+/// `[x, y, radius, depth]` per circle, outermost (shallowest) first so
+/// nested circles draw back to front; equal depths by slot.
+pub fn bubble_circles(
+    containment_edges: &[u32],
+    bubble_data: &[f32],
+    positions: &[f32],
+) -> Vec<[f32; 4]> {
+    let node_count = (bubble_data.len() / 2).min(positions.len() / 2);
+    let (radii, depths) = bubble_data.split_at(bubble_data.len() / 2);
+    let parents: BTreeSet<usize> = containment_edges
+        .chunks_exact(2)
+        .filter(|pair| pair[0] != pair[1] && (pair[1] as usize) < node_count)
+        .map(|pair| pair[0] as usize)
+        .filter(|&parent| parent < node_count)
+        .collect();
+
+    let mut circles: Vec<[f32; 4]> = parents
+        .into_iter()
+        .filter_map(|slot| {
+            let (x, y) = (positions[slot * 2], positions[slot * 2 + 1]);
+            (is_placed(x, y) && radii[slot] > 0.0).then_some([x, y, radii[slot], depths[slot]])
+        })
+        .collect();
+    // Stable, so equal depths keep slot order
+    circles.sort_by(|a, b| a[3].total_cmp(&b[3]));
+    circles
+}
+
+/// Tessellate circles into a line list: `segments` line segments per
+/// circle, each as two vertices `[x0, y0, x1, y1]`, ready for a single
+/// line-list draw call.
+///
+/// # Arguments
+///
+/// * `circles` - `[x, y, radius, ...]` per circle, as from [`bubble_circles`]
+/// * `segments` - Segments per circle (at least 3)
+pub fn tessellate_circles(circles: &[[f32; 4]], segments: u32) -> Vec<f32> {
+    let segments = segments.max(3) as usize;
+    let unit: Vec<(f32, f32)> = (0..=segments)
+        .map(|k| {
+            let angle = k as f32 / segments as f32 * std::f32::consts::TAU;
+            (angle.cos(), angle.sin())
+        })
+        .collect();
+
+    let mut vertices = Vec::with_capacity(circles.len() * segments * 4);
+    for &[x, y, r, _] in circles {
+        for pair in unit.windows(2) {
+            let ((c0, s0), (c1, s1)) = (pair[0], pair[1]);
+            vertices.extend([x + r * c0, y + r * s0, x + r * c1, y + r * s1]);
+        }
+    }
+    vertices
+}
+
 /// Count descendants for root selection heuristic.
 fn count_descendants(node: u32, children_map: &HashMap<u32, Vec<u32>>) -> usize {
     let mut count = 0;
@@ -238,15 +327,11 @@ fn build_tree(
     }
 }
 
-/// Compute depths via BFS from root (tree_nodes[0]).
-fn compute_depths(tree_nodes: &mut [TreeNode]) {
-    if tree_nodes.is_empty() {
-        return;
-    }
-
+/// Compute depths via BFS from a tree's root.
+fn compute_depths(tree_nodes: &mut [TreeNode], root: usize) {
     let mut queue = VecDeque::new();
-    tree_nodes[0].depth = 0;
-    queue.push_back(0_usize);
+    tree_nodes[root].depth = 0;
+    queue.push_back(root);
 
     while let Some(idx) = queue.pop_front() {
         let depth = tree_nodes[idx].depth;
@@ -260,16 +345,17 @@ fn compute_depths(tree_nodes: &mut [TreeNode]) {
 
 /// Bottom-up radius computation.
 ///
-/// Leaf nodes get `base_radius`. Internal nodes get a radius that encloses
-/// all children circles: `sqrt(sum_areas / (pi * packing_eff)) + padding`.
-fn compute_radii(idx: usize, nodes: &mut Vec<TreeNode>, config: &BubbleConfig) {
+/// Leaf nodes get `base_radius` scaled by the square root of their weight.
+/// Internal nodes get a radius that encloses all children circles:
+/// `sqrt(sum_areas / (pi * packing_eff)) + padding`.
+fn compute_radii(idx: usize, nodes: &mut Vec<TreeNode>, weights: &[f32], config: &BubbleConfig) {
     let children: Vec<usize> = nodes[idx].children.clone();
     for &child_idx in &children {
-        compute_radii(child_idx, nodes, config);
+        compute_radii(child_idx, nodes, weights, config);
     }
 
     if children.is_empty() {
-        nodes[idx].radius = config.base_radius;
+        nodes[idx].radius = config.base_radius * leaf_weight(weights, nodes[idx].slot).sqrt();
     } else {
         let total_area: f32 = children
             .iter()
@@ -286,19 +372,29 @@ fn compute_radii(idx: usize, nodes: &mut Vec<TreeNode>, config: &BubbleConfig) {
     }
 }
 
+/// Size of a leaf: its weight, 1 if unspecified, 0 if negative or NaN.
+fn leaf_weight(weights: &[f32], slot: usize) -> f32 {
+    match weights.get(slot) {
+        None => 1.0,
+        Some(&w) if w > 0.0 => w,
+        Some(_) => 0.0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layout::SENTINEL;
 
     #[test]
     fn test_empty_graph() {
-        let result = compute_bubble_data(&[], 0, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&[], &[], 0, None, &BubbleConfig::default());
         assert!(result.is_empty());
     }
 
     #[test]
     fn test_single_node_no_edges() {
-        let result = compute_bubble_data(&[], 1, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&[], &[], 1, None, &BubbleConfig::default());
         assert_eq!(result.len(), 2); // [wellRadius, depth]
         assert_eq!(result[0], 10.0); // base_radius
         assert_eq!(result[1], 0.0); // depth
@@ -308,7 +404,7 @@ mod tests {
     fn test_simple_parent_child() {
         // Node 0 -> Node 1
         let edges = [0u32, 1];
-        let result = compute_bubble_data(&edges, 2, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&edges, &[], 2, None, &BubbleConfig::default());
         assert_eq!(result.len(), 4); // 2 radii + 2 depths
 
         let radii = &result[0..2];
@@ -327,7 +423,7 @@ mod tests {
     fn test_wide_tree() {
         // Node 0 -> [1, 2, 3, 4, 5] (root with 5 children)
         let edges = [0u32, 1, 0, 2, 0, 3, 0, 4, 0, 5];
-        let result = compute_bubble_data(&edges, 6, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&edges, &[], 6, None, &BubbleConfig::default());
         assert_eq!(result.len(), 12);
 
         let radii = &result[0..6];
@@ -352,7 +448,7 @@ mod tests {
             padding: 2.0,
             ..Default::default()
         };
-        let result = compute_bubble_data(&edges, 5, None, &config);
+        let result = compute_bubble_data(&edges, &[], 5, None, &config);
         assert_eq!(result.len(), 10);
 
         let radii = &result[0..5];
@@ -375,7 +471,7 @@ mod tests {
     fn test_cycle_handling() {
         // 0 -> 1 -> 2 -> 0 (cycle)
         let edges = [0u32, 1, 1, 2, 2, 0];
-        let result = compute_bubble_data(&edges, 3, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&edges, &[], 3, None, &BubbleConfig::default());
         assert_eq!(result.len(), 6);
         // Should not panic or infinite loop
     }
@@ -384,7 +480,7 @@ mod tests {
     fn test_disconnected_nodes() {
         // Only 0 -> 1, nodes 2 and 3 are disconnected
         let edges = [0u32, 1];
-        let result = compute_bubble_data(&edges, 4, None, &BubbleConfig::default());
+        let result = compute_bubble_data(&edges, &[], 4, None, &BubbleConfig::default());
         assert_eq!(result.len(), 8);
 
         let radii = &result[0..4];
@@ -397,11 +493,69 @@ mod tests {
         assert_eq!(depths[3], 0.0);
     }
 
+    #[test]
+    fn test_forest_components() {
+        // Two repositories: 0 -> {1, 2 -> 3} and 4 -> 5; node 6 is isolated
+        let edges = [0u32, 1, 0, 2, 2, 3, 4, 5];
+        let config = BubbleConfig {
+            component_depth_offset: 10.0,
+            ..Default::default()
+        };
+        let result = compute_bubble_data(&edges, &[], 7, None, &config);
+        let (radii, depths) = result.split_at(7);
+
+        // The second tree gets its own radii, depths offset by one component
+        assert!(radii[4] > radii[5]);
+        assert_eq!(&depths[..4], &[0.0, 1.0, 1.0, 2.0]);
+        assert_eq!(&depths[4..6], &[10.0, 11.0]);
+        assert_eq!((radii[6], depths[6]), (10.0, 0.0));
+
+        // An explicit root goes first
+        let result = compute_bubble_data(&edges, &[], 7, Some(4), &config);
+        assert_eq!(&result[7 + 4..7 + 6], &[0.0, 1.0]);
+        assert_eq!(result[7], 10.0);
+    }
+
+    #[test]
+    fn test_weighted_leaves() {
+        // 0 -> {1, 2}, 3 -> {4, 5}: leaf 1 weighs 4, leaf 5 is unspecified
+        let edges = [0u32, 1, 0, 2, 3, 4, 3, 5];
+        let weights = [0.0, 4.0, 1.0, 0.0, 1.0];
+        let result = compute_bubble_data(&edges, &weights, 6, None, &BubbleConfig::default());
+        let radii = &result[..6];
+
+        // Four times the weight, four times the area
+        assert_eq!(radii[1], 20.0);
+        assert_eq!((radii[2], radii[4], radii[5]), (10.0, 10.0, 10.0));
+        // ...and the parent encloses more area
+        assert!(radii[0] > radii[3]);
+    }
+
+    #[test]
+    fn test_bubble_outlines() {
+        // 0 -> {1, 2 -> 3}; node 2 has no position
+        let edges = [0u32, 1, 0, 2, 2, 3];
+        let data = compute_bubble_data(&edges, &[], 4, None, &BubbleConfig::default());
+        let positions = [0.0, 0.0, 5.0, 0.0, SENTINEL, SENTINEL, 1.0, 1.0];
+
+        let circles = bubble_circles(&edges, &data, &positions);
+        assert_eq!(circles, vec![[0.0, 0.0, data[0], 0.0]]);
+
+        let vertices = tessellate_circles(&circles, 16);
+        assert_eq!(vertices.len(), 16 * 4);
+        for point in vertices.chunks_exact(2) {
+            assert!((point[0].hypot(point[1]) - data[0]).abs() < 1e-3);
+        }
+        // Segments join end to start, closing the outline
+        assert_eq!(&vertices[2..4], &vertices[4..6]);
+        assert!((vertices[vertices.len() - 2] - vertices[0]).abs() < 1e-3);
+    }
+
     #[test]
     fn test_explicit_root() {
         // 0 -> 1, 0 -> 2, but we specify root as 1
         let edges = [0u32, 1, 0, 2];
-        let result = compute_bubble_data(&edges, 3, Some(0), &BubbleConfig::default());
+        let result = compute_bubble_data(&edges, &[], 3, Some(0), &BubbleConfig::default());
         assert_eq!(result.len(), 6);
 
         let depths = &result[3..6];
@@ -424,7 +578,8 @@ mod tests {
             }
         }
         let node_count = 61;
-        let result = compute_bubble_data(&edges, node_count, Some(0), &BubbleConfig::default());
+        let config = BubbleConfig::default();
+        let result = compute_bubble_data(&edges, &[], node_count, Some(0), &config);
         assert_eq!(result.len(), node_count * 2);
 
         let radii = &result[0..node_count];
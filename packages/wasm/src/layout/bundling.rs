@@ -0,0 +1,507 @@
+//! Edge bundling.
+//!
+//! Routes edges as polylines that merge with similar edges, turning dense
+//! hairballs of inter-community edges into a few readable bundles. Two
+//! variants:
+//!
+//! - **Force-directed** ([`compute_force_bundling`], Holten & van Wijk 2009):
+//!   every edge is split into subdivision points that are attracted to the
+//!   matching points of compatible edges (similar angle, length, position
+//!   and mutual visibility) and held together by springs along the edge.
+//!   Runs in cycles; each cycle doubles the subdivision points and halves
+//!   the step size.
+//! - **Hierarchical** ([`compute_hierarchical_bundling`], Holten 2006): each
+//!   edge follows the containment tree from its source up to the lowest
+//!   common ancestor and back down to its target, using the ancestors'
+//!   positions as control points. `beta` blends the route with the straight
+//!   line, from 0 (straight) to 1 (tight bundles).
+//!
+//! Both return one polyline per edge, in CSR target order, that includes
+//! both endpoints. Edges with an endpoint without a position get an empty
+//! polyline.
+
+use super::placed_point;
+use super::distance::csr_pairs;
+use super::hierarchy::Hierarchy;
+
+/// Configuration for force-directed edge bundling.
+pub struct EdgeBundlingConfig {
+    /// Number of subdivision cycles; the last one has 2^(cycles-1)
+    /// subdivision points per edge (default: 6).
+    pub cycles: u32,
+    /// Iterations of the first cycle; each later cycle runs 2/3 as many
+    /// (default: 50).
+    pub iterations: u32,
+    /// Step of the first cycle as a fraction of the average edge length;
+    /// halved every cycle (default: 0.04).
+    pub step_size: f32,
+    /// Spring constant holding subdivision points along their edge
+    /// (default: 0.1).
+    pub stiffness: f32,
+    /// Minimum compatibility in [0, 1] for two edges to attract each other
+    /// (default: 0.6).
+    pub compatibility_threshold: f32,
+}
+
+impl Default for EdgeBundlingConfig {
+    fn default() -> Self {
+        Self {
+            cycles: 6,
+            iterations: 50,
+            step_size: 0.04,
+            stiffness: 0.1,
+            compatibility_threshold: 0.6,
+        }
+    }
+}
+
+/// Configuration for hierarchical edge bundling.
+pub struct HierarchicalBundlingConfig {
+    /// Bundling strength: 0 draws straight edges, 1 follows the hierarchy
+    /// exactly (default: 0.85).
+    pub beta: f32,
+}
+
+impl Default for HierarchicalBundlingConfig {
+    fn default() -> Self {
+        Self { beta: 0.85 }
+    }
+}
+
+/// One polyline per edge.
+pub struct EdgePolylines {
+    /// Point range of each edge: edge `e` uses points
+    /// `offsets[e]..offsets[e + 1]` (length `edge_count + 1`).
+    pub offsets: Vec<u32>,
+    /// All points, edge after edge.
+    pub points: Vec<(f32, f32)>,
+}
+
+impl EdgePolylines {
+    fn with_capacity(edge_count: usize) -> Self {
+        let mut offsets = Vec::with_capacity(edge_count + 1);
+        offsets.push(0);
+        Self {
+            offsets,
+            points: Vec::new(),
+        }
+    }
+
+    /// Append the polyline of the next edge.
+    fn push(&mut self, polyline: impl IntoIterator<Item = (f32, f32)>) {
+        self.points.extend(polyline);
+        self.offsets.push(self.points.len() as u32);
+    }
+
+    /// Flatten into a single buffer:
+    /// `[edge_count, offset0, ..., offset_edge_count, x0, y0, x1, y1, ...]`
+    /// where edge `e` uses points `offset_e..offset_{e+1}`.
+    pub fn to_flat(&self) -> Vec<f32> {
+        let mut flat = Vec::with_capacity(self.offsets.len() + 1 + self.points.len() * 2);
+        flat.push((self.offsets.len() - 1) as f32);
+        flat.extend(self.offsets.iter().map(|&o| o as f32));
+        flat.extend(self.points.iter().flat_map(|&(x, y)| [x, y]));
+        flat
+    }
+}
+
+type Point = (f32, f32);
+type Segment = (Point, Point);
+
+/// Bundle edges with force-directed edge bundling.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for removed slots (length `node_count * 2`)
+/// * `config` - Bundling configuration
+///
+/// # Returns
+///
+/// One polyline per edge in CSR target order: source, the subdivision
+/// points, target. Self loops stay at their node.
+pub fn compute_force_bundling(
+    csr: &[u32],
+    positions: &[f32],
+    config: &EdgeBundlingConfig,
+) -> EdgePolylines {
+    let node_count = positions.len() / 2;
+    let segments: Vec<Option<Segment>> = csr_pairs(csr, node_count)
+        .map(|(src, tgt)| Some((placed_point(positions, src)?, placed_point(positions, tgt)?)))
+        .collect();
+
+    let lengths: Vec<f32> = segments
+        .iter()
+        .flatten()
+        .map(|&(a, b)| distance(a, b))
+        .collect();
+    let average = lengths.iter().sum::<f32>() / lengths.len().max(1) as f32;
+
+    // Compatible pairs, with whether the partner runs the other way
+    let valid: Vec<usize> = (0..segments.len())
+        .filter(|&e| segments[e].is_some())
+        .collect();
+    let mut compatible: Vec<Vec<(usize, bool)>> = vec![Vec::new(); segments.len()];
+    let pairs = valid
+        .iter()
+        .enumerate()
+        .flat_map(|(k, &e)| valid[k + 1..].iter().map(move |&f| (e, f)));
+    for (e, f) in pairs {
+        let (p, q) = (segments[e].unwrap(), segments[f].unwrap());
+        if compatibility(p, q) >= config.compatibility_threshold {
+            let reversed = dot(sub(p.1, p.0), sub(q.1, q.0)) < 0.0;
+            compatible[e].push((f, reversed));
+            compatible[f].push((e, reversed));
+        }
+    }
+
+    let mut subdivisions: Vec<Vec<Point>> = segments
+        .iter()
+        .map(|segment| segment.map_or_else(Vec::new, |(a, b)| vec![lerp(a, b, 0.5)]))
+        .collect();
+    let mut step = config.step_size * average;
+    let mut iterations = config.iterations as f32;
+    for cycle in 0..config.cycles {
+        if cycle > 0 {
+            subdivisions = segments
+                .iter()
+                .zip(&subdivisions)
+                .map(|(segment, points)| subdivide(*segment, points))
+                .collect();
+            step *= 0.5;
+            iterations *= 2.0 / 3.0;
+        }
+        for _ in 0..iterations.round() as u32 {
+            subdivisions = (0..segments.len())
+                .map(|e| move_subdivisions(e, &segments, &subdivisions, &compatible, step, config))
+                .collect();
+        }
+    }
+
+    let mut polylines = EdgePolylines::with_capacity(segments.len());
+    for (segment, points) in segments.iter().zip(&subdivisions) {
+        match segment {
+            Some((a, b)) => polylines.push(
+                std::iter::once(*a)
+                    .chain(points.iter().copied())
+                    .chain([*b]),
+            ),
+            None => polylines.push([]),
+        }
+    }
+    polylines
+}
+
+/// One iteration for edge `e`: its subdivision points after spring and
+/// electrostatic forces. Each point moves at most `step`.
+fn move_subdivisions(
+    e: usize,
+    segments: &[Option<Segment>],
+    subdivisions: &[Vec<Point>],
+    compatible: &[Vec<(usize, bool)>],
+    step: f32,
+    config: &EdgeBundlingConfig,
+) -> Vec<Point> {
+    let Some((a, b)) = segments[e] else {
+        return Vec::new();
+    };
+    let points = &subdivisions[e];
+    let count = points.len();
+    let length = distance(a, b);
+    if length <= f32::EPSILON {
+        return points.clone();
+    }
+    let spring = config.stiffness / (length * (count + 1) as f32);
+
+    (0..count)
+        .map(|i| {
+            let p = points[i];
+            let prev = if i == 0 { a } else { points[i - 1] };
+            let next = if i + 1 == count { b } else { points[i + 1] };
+            let mut force = scale(add(sub(prev, p), sub(next, p)), spring);
+            for &(f, reversed) in &compatible[e] {
+                let j = if reversed { count - 1 - i } else { i };
+                let pull = sub(subdivisions[f][j], p);
+                let d = norm(pull);
+                if d > f32::EPSILON {
+                    force = add(force, scale(pull, 1.0 / d));
+                }
+            }
+            let magnitude = norm(force);
+            if magnitude > 1.0 {
+                force = scale(force, 1.0 / magnitude);
+            }
+            add(p, scale(force, step))
+        })
+        .collect()
+}
+
+/// Double the subdivision points of an edge, spaced evenly along its
+/// current polyline.
+fn subdivide(segment: Option<Segment>, points: &[Point]) -> Vec<Point> {
+    let Some((a, b)) = segment else {
+        return Vec::new();
+    };
+    let polyline: Vec<Point> = std::iter::once(a)
+        .chain(points.iter().copied())
+        .chain([b])
+        .collect();
+    let mut cumulative = vec![0.0f32];
+    for pair in polyline.windows(2) {
+        cumulative.push(cumulative[cumulative.len() - 1] + distance(pair[0], pair[1]));
+    }
+    let total = cumulative[cumulative.len() - 1];
+    let count = points.len() * 2;
+    if total <= f32::EPSILON {
+        return vec![a; count];
+    }
+
+    (1..=count)
+        .map(|k| {
+            let target = total * k as f32 / (count + 1) as f32;
+            let s = cumulative
+                .partition_point(|&c| c < target)
+                .clamp(1, polyline.len() - 1);
+            let span = cumulative[s] - cumulative[s - 1];
+            let t = if span > 0.0 {
+                (target - cumulative[s - 1]) / span
+            } else {
+                0.0
+            };
+            lerp(polyline[s - 1], polyline[s], t)
+        })
+        .collect()
+}
+
+/// Edge compatibility in [0, 1]: product of angle, scale, position and
+/// visibility compatibility.
+fn compatibility(p: Segment, q: Segment) -> f32 {
+    let (vp, vq) = (sub(p.1, p.0), sub(q.1, q.0));
+    let (lp, lq) = (norm(vp), norm(vq));
+    if lp <= f32::EPSILON || lq <= f32::EPSILON {
+        return 0.0;
+    }
+    let average = (lp + lq) * 0.5;
+
+    let angle = (dot(vp, vq) / (lp * lq)).abs();
+    let scale = 2.0 / (average / lp.min(lq) + lp.max(lq) / average);
+    let midpoints = distance(lerp(p.0, p.1, 0.5), lerp(q.0, q.1, 0.5));
+    let position = average / (average + midpoints);
+    let visibility = visibility(p, q).min(visibility(q, p));
+    angle * scale * position * visibility
+}
+
+/// How much of `q` is visible from `p`: 1 when the projection of `q` onto
+/// the line through `p` is centered on `p`'s midpoint, 0 when it misses it.
+fn visibility(p: Segment, q: Segment) -> f32 {
+    let direction = sub(p.1, p.0);
+    let length_sq = dot(direction, direction);
+    let project = |v: Point| {
+        let t = dot(sub(v, p.0), direction) / length_sq;
+        add(p.0, scale(direction, t))
+    };
+    let (i0, i1) = (project(q.0), project(q.1));
+    let span = distance(i0, i1);
+    if span <= f32::EPSILON {
+        return 0.0;
+    }
+    let offset = distance(lerp(p.0, p.1, 0.5), lerp(i0, i1, 0.5));
+    (1.0 - 2.0 * offset / span).max(0.0)
+}
+
+/// Bundle edges along the containment hierarchy.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for removed slots (length `node_count * 2`)
+/// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+/// * `root_id` - Optional root node ID (None = auto-detect)
+/// * `config` - Bundling configuration
+///
+/// # Returns
+///
+/// One polyline per edge in CSR target order: source, the positioned
+/// ancestors on the tree path (straightened by `beta`), target. Edges with
+/// an endpoint outside the tree are straight.
+pub fn compute_hierarchical_bundling(
+    csr: &[u32],
+    positions: &[f32],
+    containment_edges: &[u32],
+    root_id: Option<u32>,
+    config: &HierarchicalBundlingConfig,
+) -> EdgePolylines {
+    let node_count = positions.len() / 2;
+    let tree = Hierarchy::from_containment(containment_edges, node_count, root_id);
+    let mut parent: Vec<Option<usize>> = vec![None; node_count];
+    let mut in_tree = vec![false; node_count];
+    if let Some(tree) = &tree {
+        for &node in &tree.order {
+            in_tree[node] = true;
+            for &child in &tree.children[node] {
+                parent[child] = Some(node);
+            }
+        }
+    }
+    let depth = tree.map(|tree| tree.depth).unwrap_or_default();
+
+    let pairs: Vec<(usize, usize)> = csr_pairs(csr, node_count).collect();
+    let mut polylines = EdgePolylines::with_capacity(pairs.len());
+    for (src, tgt) in pairs {
+        let (Some(a), Some(b)) = (placed_point(positions, src), placed_point(positions, tgt)) else {
+            polylines.push([]);
+            continue;
+        };
+        if !in_tree[src] || !in_tree[tgt] {
+            polylines.push([a, b]);
+            continue;
+        }
+        let route: Vec<Point> = tree_path(src, tgt, &parent, &depth)
+            .into_iter()
+            .filter_map(|node| placed_point(positions, node))
+            .collect();
+        let last = (route.len() - 1).max(1) as f32;
+        polylines.push(route.iter().enumerate().map(|(i, &control)| {
+            let straight = lerp(a, b, i as f32 / last);
+            lerp(straight, control, config.beta)
+        }));
+    }
+    polylines
+}
+
+/// Tree path from `a` to `b` through their lowest common ancestor,
+/// including both ends.
+fn tree_path(a: usize, b: usize, parent: &[Option<usize>], depth: &[u32]) -> Vec<usize> {
+    let (mut up, mut down) = (vec![a], vec![b]);
+    let (mut x, mut y) = (a, b);
+    while x != y {
+        if depth[x] >= depth[y] {
+            let Some(p) = parent[x] else { break };
+            x = p;
+            up.push(x);
+        } else {
+            let Some(p) = parent[y] else { break };
+            y = p;
+            down.push(y);
+        }
+    }
+    // The common ancestor ends both halves; keep it once
+    if x == y {
+        down.pop();
+    }
+    up.extend(down.into_iter().rev());
+    up
+}
+
+fn add(a: Point, b: Point) -> Point {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn scale(a: Point, s: f32) -> Point {
+    (a.0 * s, a.1 * s)
+}
+
+fn dot(a: Point, b: Point) -> f32 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn norm(a: Point) -> f32 {
+    a.0.hypot(a.1)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    norm(sub(a, b))
+}
+
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+    use crate::layout::distance::csr_from_edges;
+
+    fn polyline(polylines: &EdgePolylines, edge: usize) -> &[Point] {
+        let range = polylines.offsets[edge] as usize..polylines.offsets[edge + 1] as usize;
+        &polylines.points[range]
+    }
+
+    #[test]
+    fn test_parallel_edges_are_pulled_together() {
+        // Two parallel edges 20 apart, one running the other way
+        let positions = [0.0, 0.0, 200.0, 0.0, 200.0, 20.0, 0.0, 20.0];
+        let csr = csr_from_edges(4, &[(0, 1), (2, 3)]);
+        let config = EdgeBundlingConfig {
+            cycles: 4,
+            ..Default::default()
+        };
+        let polylines = compute_force_bundling(&csr, &positions, &config);
+
+        let (first, second) = (polyline(&polylines, 0), polyline(&polylines, 1));
+        assert_eq!(first.len(), 10);
+        assert_eq!((first[0], first[9]), ((0.0, 0.0), (200.0, 0.0)));
+        assert_eq!((second[0], second[9]), ((200.0, 20.0), (0.0, 20.0)));
+        // Middles meet near the centerline
+        let gap = second[4].1 - first[5].1;
+        assert!(gap.abs() < 5.0, "gap {gap}");
+        assert!((first[5].1 - 10.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_perpendicular_edges_stay_straight() {
+        let positions = [
+            -100.0, 0.0, 100.0, 0.0, 0.0, -100.0, 0.0, 100.0, SENTINEL, SENTINEL,
+        ];
+        let csr = csr_from_edges(5, &[(0, 1), (2, 3), (3, 4)]);
+        let polylines = compute_force_bundling(&csr, &positions, &Default::default());
+
+        assert!(polyline(&polylines, 0).iter().all(|&(_, y)| y.abs() < 1e-3));
+        assert!(polyline(&polylines, 1).iter().all(|&(x, _)| x.abs() < 1e-3));
+        assert!(polyline(&polylines, 2).is_empty());
+        assert_eq!(polylines.to_flat()[..5], [3.0, 0.0, 34.0, 68.0, 68.0]);
+    }
+
+    #[test]
+    fn test_hierarchical_route_through_common_ancestor() {
+        // 0 → {1, 2}, 1 → 3, 2 → 4; graph edge 3 → 4 and 4 → 5 (outside)
+        let positions = [
+            0.0, 0.0, -50.0, 50.0, 50.0, 50.0, -60.0, 100.0, 60.0, 100.0, 0.0, 200.0,
+        ];
+        let containment = [0, 1, 0, 2, 1, 3, 2, 4];
+        let csr = csr_from_edges(6, &[(3, 4), (4, 5)]);
+
+        let tight = HierarchicalBundlingConfig { beta: 1.0 };
+        let polylines = compute_hierarchical_bundling(&csr, &positions, &containment, None, &tight);
+        assert_eq!(
+            polyline(&polylines, 0),
+            &[
+                (-60.0, 100.0),
+                (-50.0, 50.0),
+                (0.0, 0.0),
+                (50.0, 50.0),
+                (60.0, 100.0)
+            ]
+        );
+        assert_eq!(polyline(&polylines, 1), &[(60.0, 100.0), (0.0, 200.0)]);
+
+        let straight = HierarchicalBundlingConfig { beta: 0.0 };
+        let polylines =
+            compute_hierarchical_bundling(&csr, &positions, &containment, None, &straight);
+        assert!(polyline(&polylines, 0).iter().all(|&(_, y)| y == 100.0));
+    }
+
+    #[test]
+    fn test_tree_path_to_ancestor() {
+        let parent = [None, Some(0), Some(1)];
+        assert_eq!(tree_path(2, 0, &parent, &[0, 1, 2]), vec![2, 1, 0]);
+        assert_eq!(tree_path(1, 1, &parent, &[0, 1, 2]), vec![1]);
+    }
+}
@@ -0,0 +1,191 @@
+//! Circular layout.
+//!
+//! Places every node evenly on a single circle. The order around the circle
+//! decides what the layout shows: sorting by degree puts hubs next to each
+//! other, grouping by community keeps clusters together, and a custom order
+//! lets the caller decide.
+//!
+//! Works on the slot space directly: removed slots (holes below
+//! `node_bound`) get the sentinel, while nodes without edges are still
+//! placed.
+
+use std::f32::consts::TAU;
+
+use super::SENTINEL;
+
+/// How nodes are ordered around the circle.
+pub enum CircularOrdering<'a> {
+    /// Highest degree first (ties by slot). One degree per slot.
+    Degree(&'a [u32]),
+    /// Grouped by community id, highest degree first within a community.
+    Community {
+        /// Community id per slot.
+        assignments: &'a [u32],
+        /// Degree per slot.
+        degrees: &'a [u32],
+    },
+    /// Node slots in circle order. Slots not listed (or listed twice) are
+    /// appended in slot order after the listed ones.
+    Custom(&'a [u32]),
+}
+
+/// Configuration for the circular layout.
+pub struct CircularLayoutConfig {
+    /// Circle radius. 0 picks a radius from `node_spacing` (default: 0.0).
+    pub radius: f32,
+    /// Arc length between neighbors when the radius is automatic (default: 30.0).
+    pub node_spacing: f32,
+    /// Angle of the first node in radians, 0 = +x, y down (default: -π/2, top).
+    pub start_angle: f32,
+}
+
+impl Default for CircularLayoutConfig {
+    fn default() -> Self {
+        Self {
+            radius: 0.0,
+            node_spacing: 30.0,
+            start_angle: -std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+/// Order the present slots according to `ordering`.
+pub fn circular_order(present: &[bool], ordering: &CircularOrdering) -> Vec<usize> {
+    let mut slots: Vec<usize> = (0..present.len()).filter(|&i| present[i]).collect();
+    let key = |values: &[u32], slot: usize| values.get(slot).copied().unwrap_or(0);
+    match ordering {
+        CircularOrdering::Degree(degrees) => {
+            slots.sort_by_key(|&slot| std::cmp::Reverse(key(degrees, slot)));
+        }
+        CircularOrdering::Community {
+            assignments,
+            degrees,
+        } => {
+            slots.sort_by_key(|&slot| {
+                (
+                    assignments.get(slot).copied().unwrap_or(u32::MAX),
+                    std::cmp::Reverse(key(degrees, slot)),
+                )
+            });
+        }
+        CircularOrdering::Custom(order) => {
+            let mut placed = vec![false; present.len()];
+            let listed: Vec<usize> = order
+                .iter()
+                .map(|&slot| slot as usize)
+                .filter(|&slot| slot < present.len() && present[slot])
+                .filter(|&slot| !std::mem::replace(&mut placed[slot], true))
+                .collect();
+            slots.retain(|&slot| !placed[slot]);
+            slots.splice(0..0, listed);
+        }
+    }
+    slots
+}
+
+/// Compute a circular layout.
+///
+/// # Arguments
+///
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+/// * `ordering` - Order of nodes around the circle
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...],
+/// centered on the origin. Empty slots get sentinel values (f32::MAX).
+pub fn compute_circular_layout(
+    present: &[bool],
+    ordering: &CircularOrdering,
+    config: &CircularLayoutConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; present.len() * 2];
+    let order = circular_order(present, ordering);
+    let n = order.len();
+    if n == 0 {
+        return positions;
+    }
+
+    let radius = if config.radius > 0.0 {
+        config.radius
+    } else {
+        n as f32 * config.node_spacing / TAU
+    };
+    // A single node sits at the center
+    let radius = if n == 1 { 0.0 } else { radius };
+
+    for (index, &slot) in order.iter().enumerate() {
+        let angle = config.start_angle + TAU * index as f32 / n as f32;
+        positions[slot * 2] = radius * angle.cos();
+        positions[slot * 2 + 1] = radius * angle.sin();
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_evenly_on_circle_and_holes_skipped() {
+        let present = [true, false, true, true, true];
+        let config = CircularLayoutConfig {
+            radius: 100.0,
+            ..Default::default()
+        };
+        let positions = compute_circular_layout(&present, &CircularOrdering::Custom(&[]), &config);
+
+        assert_eq!(positions[2], SENTINEL);
+        assert_eq!(positions[3], SENTINEL);
+        for slot in [0, 2, 3, 4] {
+            let r = positions[slot * 2].hypot(positions[slot * 2 + 1]);
+            assert!((r - 100.0).abs() < 1e-3);
+        }
+        // First node at the top, neighbors a quarter turn apart
+        assert!((positions[1] + 100.0).abs() < 1e-3);
+        let gap = (positions[0] - positions[4]).hypot(positions[1] - positions[5]);
+        assert!((gap - 100.0 * std::f32::consts::SQRT_2).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_degree_ordering() {
+        let present = [true; 4];
+        let degrees = [1, 5, 3, 5];
+        let order = circular_order(&present, &CircularOrdering::Degree(&degrees));
+        assert_eq!(order, vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_community_ordering_groups_members() {
+        let present = [true; 5];
+        let assignments = [1, 0, 1, 0, 1];
+        let degrees = [1, 1, 4, 2, 1];
+        let ordering = CircularOrdering::Community {
+            assignments: &assignments,
+            degrees: &degrees,
+        };
+        assert_eq!(circular_order(&present, &ordering), vec![3, 1, 2, 0, 4]);
+    }
+
+    #[test]
+    fn test_custom_ordering_appends_unlisted() {
+        let present = [true, true, false, true];
+        // Duplicate, empty slot and out-of-range entries are ignored
+        let order = circular_order(&present, &CircularOrdering::Custom(&[3, 2, 3, 9, 0]));
+        assert_eq!(order, vec![3, 0, 1]);
+    }
+
+    #[test]
+    fn test_auto_radius_and_single_node() {
+        let present = [true; 10];
+        let config = CircularLayoutConfig::default();
+        let positions = compute_circular_layout(&present, &CircularOrdering::Custom(&[]), &config);
+        let r = positions[0].hypot(positions[1]);
+        assert!((r - 10.0 * 30.0 / TAU).abs() < 1e-3);
+
+        let single = compute_circular_layout(&[true], &CircularOrdering::Custom(&[]), &config);
+        assert_eq!(single, vec![0.0, 0.0]);
+    }
+}
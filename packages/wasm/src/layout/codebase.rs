@@ -8,19 +8,39 @@
 //! # Algorithm
 //!
 //! 1. **Build hierarchy tree** from containment edges (parent→child).
-//! 2. **Bottom-up radius computation**: Leaves get a base radius from their
-//!    node category. Internal nodes get a radius computed by packing their
-//!    children circles.
+//! 2. **Bottom-up packing**: Leaves get a base radius from their node
+//!    category. Each internal node packs its children's circles and takes
+//!    the radius of their enclosing circle.
 //! 3. **Top-down position assignment**: Starting from the root at (0,0),
-//!    recursively place children within each parent's circle.
+//!    recursively place children at their packed offsets.
 //!
 //! # Layout Strategy
 //!
-//! Children within a parent are arranged using a sunflower spiral, which
-//! provides approximately uniform density. The parent's radius is computed
-//! as the minimum enclosing circle of all packed children plus padding.
+//! Children within a parent are packed largest first with front-chain
+//! packing, each circle tangent to two on the outer boundary and as close
+//! to the center as possible, so siblings never overlap. The parent's
+//! radius is the exact minimum enclosing circle of the packed children
+//! (Welzl) plus padding. See [`super::packing`].
+//!
+//! # Incremental Updates
+//!
+//! [`update_codebase_layout`] starts from a previous layout instead of
+//! repacking everything. Only the parent chains of added nodes and of
+//! nodes whose children changed are revisited: siblings keep their
+//! previous offsets and are pushed apart only where a grown circle now
+//! overlaps them, new subtrees are packed from scratch and dropped into
+//! the gap nearest their parent's center, and each revisited parent is
+//! re-enclosed in place. Untouched subtrees keep their internal layout and
+//! move only with their ancestors, so most nodes stay exactly where they
+//! were.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
 
-use std::collections::{HashMap, HashSet};
+use super::{SENTINEL, is_placed};
+use super::packing::{self, Circle};
 
 /// Node type categories for layout sizing.
 #[repr(u8)]
@@ -50,7 +70,10 @@ impl From<u8> for NodeCategory {
     }
 }
 
-/// Configuration for the codebase circle packing layout.
+/// Configuration for the codebase circle packing layout. Deserializes from
+/// camelCase JS options, with defaults for omitted fields.
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct CodebaseLayoutConfig {
     /// Padding within directory circles (space between boundary and children).
     pub directory_padding: f32,
@@ -64,6 +87,12 @@ pub struct CodebaseLayoutConfig {
     pub directory_radius: f32,
     /// Global scale multiplier applied to all positions.
     pub spread_factor: f32,
+    /// Per-node packing order by slot: siblings pack in ascending key
+    /// order, so a stable key (name hash, explicit order) keeps files at
+    /// roughly the same angle across sessions. Nodes without a key (past
+    /// the end or NaN) pack after keyed siblings, largest first
+    /// (default: empty, largest first).
+    pub sort_keys: Vec<f32>,
 }
 
 impl Default for CodebaseLayoutConfig {
@@ -75,10 +104,25 @@ impl Default for CodebaseLayoutConfig {
             file_radius: 12.0,
             directory_radius: 25.0,
             spread_factor: 1.5,
+            sort_keys: Vec::new(),
         }
     }
 }
 
+/// Options of the codebase layout entry points, as a JS object: the fields
+/// of [`CodebaseLayoutConfig`] plus `rootId`, all optional.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CodebaseLayoutOptions {
+    /// Root node ID (default: auto-detect).
+    pub root_id: Option<u32>,
+    /// Append each node's circle radius after the positions (default: false).
+    pub include_radii: bool,
+    /// Layout configuration.
+    #[serde(flatten)]
+    pub config: CodebaseLayoutConfig,
+}
+
 /// Internal node used during layout computation.
 struct LayoutNode {
     /// Original node index (slot in the graph).
@@ -89,14 +133,51 @@ struct LayoutNode {
     children: Vec<usize>,
     /// Computed radius (from bottom-up pass).
     radius: f32,
+    /// Center relative to the parent's center (from bottom-up pass).
+    offset: (f32, f32),
     /// Final X position (from top-down pass).
     x: f32,
     /// Final Y position (from top-down pass).
     y: f32,
 }
 
+/// Result of the codebase layout.
+pub struct CodebaseLayout {
+    /// Interleaved node positions [x0, y0, x1, y1, ...]; sentinel (f32::MAX)
+    /// for nodes not in the tree.
+    pub positions: Vec<f32>,
+    /// Radius of each node's packed circle, in the same scale as the
+    /// positions; 0 for nodes not in the tree.
+    pub radii: Vec<f32>,
+}
+
+impl CodebaseLayout {
+    /// Flatten into a single buffer: `[x0, y0, ..., x_{n-1}, y_{n-1}, r0, ..., r_{n-1}]`.
+    pub fn to_flat(&self) -> Vec<f32> {
+        let mut out = self.positions.clone();
+        out.extend_from_slice(&self.radii);
+        out
+    }
+}
+
+/// Changed nodes of an incremental codebase layout update.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodebaseLayoutUpdate {
+    /// Slots whose position or radius changed, ascending.
+    pub slots: Vec<u32>,
+    /// New positions of those slots, interleaved [x0, y0, x1, y1, ...];
+    /// sentinel (f32::MAX) for nodes no longer in the tree.
+    pub positions: Vec<f32>,
+    /// New radii of those slots; 0 for nodes no longer in the tree.
+    pub radii: Vec<f32>,
+}
+
 /// Compute codebase layout from containment hierarchy.
 ///
+/// Positions only; see [`compute_codebase_layout_with_radii`] for the circle
+/// radii as well.
+///
 /// # Arguments
 ///
 /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
@@ -116,23 +197,178 @@ pub fn compute_codebase_layout(
     root_id: Option<u32>,
     config: &CodebaseLayoutConfig,
 ) -> Vec<f32> {
-    const SENTINEL: f32 = 3.402_823e+38;
+    compute_codebase_layout_with_radii(
+        containment_edges,
+        node_categories,
+        node_count,
+        root_id,
+        config,
+    )
+    .positions
+}
 
+/// Compute codebase layout from containment hierarchy, with the radius of
+/// every node's circle so renderers can draw boundaries that match the
+/// packing exactly. Arguments as in [`compute_codebase_layout`].
+pub fn compute_codebase_layout_with_radii(
+    containment_edges: &[u32],
+    node_categories: &[u8],
+    node_count: usize,
+    root_id: Option<u32>,
+    config: &CodebaseLayoutConfig,
+) -> CodebaseLayout {
+    let mut layout = CodebaseLayout {
+        positions: vec![SENTINEL; node_count * 2],
+        radii: vec![0.0; node_count],
+    };
     if node_count == 0 {
-        return Vec::new();
+        return layout;
+    }
+
+    let mut layout_nodes = build_tree(containment_edges, node_categories, node_count, root_id);
+    if layout_nodes.is_empty() {
+        return layout;
+    }
+
+    // Bottom-up pass: compute radii
+    compute_radii(0, &mut layout_nodes, config);
+
+    // Top-down pass: assign positions (root at origin)
+    layout_nodes[0].x = 0.0;
+    layout_nodes[0].y = 0.0;
+    assign_positions(0, &mut layout_nodes);
+
+    // Write positions and radii to output
+    for node in &layout_nodes {
+        let idx = node.slot * 2;
+        if idx + 1 < layout.positions.len() {
+            layout.positions[idx] = node.x * config.spread_factor;
+            layout.positions[idx + 1] = node.y * config.spread_factor;
+            layout.radii[node.slot] = node.radius * config.spread_factor;
+        }
+    }
+
+    layout
+}
+
+/// Update a codebase layout after nodes were added or removed, moving as
+/// little as possible.
+///
+/// # Arguments
+///
+/// * `containment_edges`, `node_categories`, `node_count`, `root_id`,
+///   `config` - The current hierarchy, as in [`compute_codebase_layout`]
+/// * `previous` - The previous layout, positions and radii
+/// * `changed` - Slots whose children were added or removed, or whose
+///   category changed. Nodes without a previous position are found
+///   automatically; list the parents of removed nodes so their circles
+///   shrink.
+///
+/// # Returns
+///
+/// The nodes whose position or radius changed, including nodes that
+/// dropped out of the tree.
+pub fn update_codebase_layout(
+    containment_edges: &[u32],
+    node_categories: &[u8],
+    node_count: usize,
+    root_id: Option<u32>,
+    previous: &CodebaseLayout,
+    changed: &[u32],
+    config: &CodebaseLayoutConfig,
+) -> CodebaseLayoutUpdate {
+    let mut nodes = build_tree(containment_edges, node_categories, node_count, root_id);
+
+    let previous_circle = |slot: usize| {
+        let r = *previous.radii.get(slot)?;
+        let x = *previous.positions.get(slot * 2)?;
+        let y = *previous.positions.get(slot * 2 + 1)?;
+        (r > 0.0 && is_placed(x, y)).then_some((x, y, r))
+    };
+
+    // Previous circles in layout units (before the spread factor)
+    let scale = config.spread_factor;
+    let circles: Vec<Option<(f32, f32, f32)>> = nodes
+        .iter()
+        .map(|node| previous_circle(node.slot).filter(|_| scale != 0.0))
+        .map(|circle| circle.map(|(x, y, r)| (x / scale, y / scale, r / scale)))
+        .collect();
+
+    // Revisit changed and new nodes along with all their ancestors
+    let mut parent = vec![usize::MAX; nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        for &child in &node.children {
+            parent[child] = idx;
+        }
+    }
+    let changed: HashSet<usize> = changed.iter().map(|&slot| slot as usize).collect();
+    let mut dirty = vec![false; nodes.len()];
+    for idx in 0..nodes.len() {
+        if circles[idx].is_some() && !changed.contains(&nodes[idx].slot) {
+            continue;
+        }
+        let mut at = idx;
+        while at != usize::MAX && !dirty[at] {
+            dirty[at] = true;
+            at = parent[at];
+        }
+    }
+
+    if !nodes.is_empty() {
+        let (dx, dy) = update_radii(0, &mut nodes, &circles, &dirty, config);
+        let (x, y, _) = circles[0].unwrap_or((0.0, 0.0, 0.0));
+        nodes[0].x = x + dx;
+        nodes[0].y = y + dy;
+        assign_positions(0, &mut nodes);
     }
 
-    let mut positions = vec![SENTINEL; node_count * 2];
+    let mut current = vec![None; node_count];
+    for node in &nodes {
+        if let Some(circle) = current.get_mut(node.slot) {
+            *circle = Some((node.x * scale, node.y * scale, node.radius * scale));
+        }
+    }
 
+    let mut update = CodebaseLayoutUpdate::default();
+    for (slot, circle) in current.into_iter().enumerate() {
+        let moved = match (previous_circle(slot), circle) {
+            (Some(a), Some(b)) => {
+                (a.0 - b.0)
+                    .abs()
+                    .max((a.1 - b.1).abs())
+                    .max((a.2 - b.2).abs())
+                    > 1e-3
+            }
+            (None, None) => false,
+            _ => true,
+        };
+        if moved {
+            let (x, y, r) = circle.unwrap_or((SENTINEL, SENTINEL, 0.0));
+            update.slots.push(slot as u32);
+            update.positions.extend([x, y]);
+            update.radii.push(r);
+        }
+    }
+    update
+}
+
+/// Build the layout tree from containment edges, root first. Empty when
+/// there are no valid edges.
+fn build_tree(
+    containment_edges: &[u32],
+    node_categories: &[u8],
+    node_count: usize,
+    root_id: Option<u32>,
+) -> Vec<LayoutNode> {
     // Validate edge array
     if containment_edges.len() % 2 != 0 {
-        return positions;
+        return Vec::new();
     }
 
     // Build parent→children adjacency
     let mut children_map: HashMap<u32, Vec<u32>> = HashMap::new();
     let mut has_parent: HashSet<u32> = HashSet::new();
-    let mut all_nodes: HashSet<u32> = HashSet::new();
+    let mut all_nodes: BTreeSet<u32> = BTreeSet::new();
 
     let edge_count = containment_edges.len() / 2;
     for i in 0..edge_count {
@@ -155,7 +391,7 @@ pub fn compute_codebase_layout(
     }
 
     if all_nodes.is_empty() {
-        return positions;
+        return Vec::new();
     }
 
     // Find root
@@ -175,10 +411,10 @@ pub fn compute_codebase_layout(
         } else if roots.len() == 1 {
             roots[0]
         } else {
-            // Multiple roots: pick the one with most descendants
+            // Multiple roots: pick the one with most descendants (lowest ID on ties)
             roots
                 .iter()
-                .max_by_key(|&&r| count_descendants(r, &children_map))
+                .max_by_key(|&&r| (count_descendants(r, &children_map), Reverse(r)))
                 .copied()
                 .unwrap_or(roots[0])
         }
@@ -199,28 +435,7 @@ pub fn compute_codebase_layout(
         &mut visited,
     );
 
-    if layout_nodes.is_empty() {
-        return positions;
-    }
-
-    // Bottom-up pass: compute radii
-    compute_radii(0, &mut layout_nodes, config);
-
-    // Top-down pass: assign positions (root at origin)
-    layout_nodes[0].x = 0.0;
-    layout_nodes[0].y = 0.0;
-    assign_positions(0, &mut layout_nodes, config);
-
-    // Write positions to output
-    for node in &layout_nodes {
-        let idx = node.slot * 2;
-        if idx + 1 < positions.len() {
-            positions[idx] = node.x * config.spread_factor;
-            positions[idx + 1] = node.y * config.spread_factor;
-        }
-    }
-
-    positions
+    layout_nodes
 }
 
 /// Count descendants of a node (for root selection heuristic).
@@ -273,6 +488,7 @@ fn build_layout_tree(
         category,
         children: Vec::new(),
         radius: 0.0,
+        offset: (0.0, 0.0),
         x: 0.0,
         y: 0.0,
     });
@@ -305,7 +521,7 @@ fn build_layout_tree(
 /// Bottom-up radius computation.
 ///
 /// Leaf nodes get a base radius from their category.
-/// Internal nodes get a radius that encloses all children circles.
+/// Internal nodes pack their children and get a radius that encloses them.
 fn compute_radii(idx: usize, nodes: &mut Vec<LayoutNode>, config: &CodebaseLayoutConfig) {
     // First, recursively compute children's radii
     let children: Vec<usize> = nodes[idx].children.clone();
@@ -316,31 +532,44 @@ fn compute_radii(idx: usize, nodes: &mut Vec<LayoutNode>, config: &CodebaseLayou
     if children.is_empty() {
         // Leaf node: base radius from category
         nodes[idx].radius = base_radius(nodes[idx].category, config);
-    } else {
-        // Internal node: compute enclosing radius for all children
-        // Sum of children circle areas determines minimum enclosing radius
-        let total_area: f32 = children.iter()
-            .map(|&c| {
-                let r = nodes[c].radius;
-                std::f32::consts::PI * r * r
-            })
-            .sum();
-
-        // Enclosing circle radius from total area: A = π * R² → R = √(A/π)
-        // Apply a packing efficiency factor (~0.9 for circles)
-        let packing_efficiency = 0.82; // Typical for random circle packing
-        let enclosing_radius = (total_area / (std::f32::consts::PI * packing_efficiency)).sqrt();
-
-        // Add padding based on node category
-        let padding = match nodes[idx].category {
-            NodeCategory::Repository | NodeCategory::Directory => config.directory_padding,
-            NodeCategory::File => config.file_padding,
-            _ => config.file_padding,
-        };
+        return;
+    }
 
-        // Ensure minimum radius for the category
-        let min_radius = base_radius(nodes[idx].category, config);
-        nodes[idx].radius = enclosing_radius.max(min_radius) + padding;
+    // Internal node: pack children by sort key, then largest first (stable,
+    // so equal radii keep their order) around the enclosing circle's center
+    let sort_key = |c: usize| {
+        config
+            .sort_keys
+            .get(nodes[c].slot)
+            .copied()
+            .filter(|key| !key.is_nan())
+            .unwrap_or(f32::INFINITY)
+    };
+    let mut sorted_children = children;
+    sorted_children.sort_by(|&a, &b| {
+        sort_key(a)
+            .total_cmp(&sort_key(b))
+            .then(nodes[b].radius.total_cmp(&nodes[a].radius))
+    });
+    let mut circles: Vec<Circle> = sorted_children
+        .iter()
+        .map(|&c| Circle::new(0.0, 0.0, nodes[c].radius as f64))
+        .collect();
+    let enclosing = packing::pack_siblings(&mut circles);
+    for (&child_idx, circle) in sorted_children.iter().zip(&circles) {
+        nodes[child_idx].offset = (circle.x as f32, circle.y as f32);
+    }
+
+    // Ensure minimum radius for the category, then add padding
+    let min_radius = base_radius(nodes[idx].category, config);
+    nodes[idx].radius = (enclosing.r as f32).max(min_radius) + padding(nodes[idx].category, config);
+}
+
+/// Padding between a node's circle and its children.
+fn padding(category: NodeCategory, config: &CodebaseLayoutConfig) -> f32 {
+    match category {
+        NodeCategory::Repository | NodeCategory::Directory => config.directory_padding,
+        _ => config.file_padding,
     }
 }
 
@@ -355,160 +584,191 @@ fn base_radius(category: NodeCategory, config: &CodebaseLayoutConfig) -> f32 {
     }
 }
 
-/// Top-down position assignment using sunflower spiral within each parent.
-fn assign_positions(idx: usize, nodes: &mut Vec<LayoutNode>, config: &CodebaseLayoutConfig) {
+/// Top-down position assignment: children sit at their packed offsets
+/// from the parent's center.
+fn assign_positions(idx: usize, nodes: &mut [LayoutNode]) {
     let children: Vec<usize> = nodes[idx].children.clone();
-
-    if children.is_empty() {
-        return;
+    let (parent_x, parent_y) = (nodes[idx].x, nodes[idx].y);
+    for child_idx in children {
+        let (dx, dy) = nodes[child_idx].offset;
+        nodes[child_idx].x = parent_x + dx;
+        nodes[child_idx].y = parent_y + dy;
+        assign_positions(child_idx, nodes);
     }
+}
 
-    let parent_x = nodes[idx].x;
-    let parent_y = nodes[idx].y;
-    let parent_radius = nodes[idx].radius;
-
-    // Determine padding to use
-    let padding = match nodes[idx].category {
-        NodeCategory::Repository | NodeCategory::Directory => config.directory_padding,
-        NodeCategory::File => config.file_padding,
-        _ => config.file_padding,
+/// Bottom-up pass of an incremental update. Fills in radii and child
+/// offsets as [`compute_radii`] does and returns how far the node's center
+/// moved from its previous one (zero for new nodes).
+fn update_radii(
+    idx: usize,
+    nodes: &mut Vec<LayoutNode>,
+    previous: &[Option<(f32, f32, f32)>],
+    dirty: &[bool],
+    config: &CodebaseLayoutConfig,
+) -> (f32, f32) {
+    let Some((px, py, pr)) = previous[idx] else {
+        // New subtree: pack it from scratch
+        compute_radii(idx, nodes, config);
+        return (0.0, 0.0);
     };
-
-    // Available radius for placing children (subtract padding)
-    let available_radius = (parent_radius - padding).max(0.0);
-
-    let n = children.len();
-
-    if n == 1 {
-        // Single child: place at parent center
-        let child_idx = children[0];
-        nodes[child_idx].x = parent_x;
-        nodes[child_idx].y = parent_y;
-        assign_positions(child_idx, nodes, config);
-        return;
+    let children = nodes[idx].children.clone();
+    if !dirty[idx] {
+        // Untouched subtree: keep its circle and its children's offsets
+        nodes[idx].radius = pr;
+        for child in children {
+            keep_previous(child, (px, py), nodes, previous);
+        }
+        return (0.0, 0.0);
+    }
+    if children.is_empty() {
+        nodes[idx].radius = base_radius(nodes[idx].category, config);
+        return (0.0, 0.0);
     }
 
-    // Sort children by radius (largest first) for better packing
-    let mut sorted_children: Vec<(usize, f32)> = children.iter()
-        .map(|&c| (c, nodes[c].radius))
+    // Siblings stay where they were, relative to the previous center
+    let mut circles = Vec::with_capacity(children.len());
+    let mut fresh = Vec::new();
+    for (i, &child) in children.iter().enumerate() {
+        let (dx, dy) = update_radii(child, nodes, previous, dirty, config);
+        let r = nodes[child].radius as f64;
+        match previous[child] {
+            Some((cx, cy, _)) => {
+                circles.push(Circle::new((cx - px + dx) as f64, (cy - py + dy) as f64, r));
+            }
+            None => {
+                circles.push(Circle::new(0.0, 0.0, r));
+                fresh.push(i);
+            }
+        }
+    }
+    let known: Vec<bool> = (0..children.len()).map(|i| !fresh.contains(&i)).collect();
+    separate_siblings(&mut circles, &known);
+
+    // New children go into the gap nearest the center, largest first
+    fresh.sort_by(|&a, &b| circles[b].r.total_cmp(&circles[a].r));
+    let mut placed: Vec<Circle> = (0..children.len())
+        .filter(|&i| known[i])
+        .map(|i| circles[i])
         .collect();
-    sorted_children.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-    // Use sunflower spiral placement
-    // The golden angle ensures approximately uniform distribution
-    let golden_angle = std::f32::consts::TAU * (1.0 - 1.0 / ((1.0 + 5.0f32.sqrt()) / 2.0));
-
-    // Compute placement radius: scale based on child sizes relative to parent
-    let max_child_radius = sorted_children.iter()
-        .map(|&(_, r)| r)
-        .fold(0.0f32, f32::max);
-
-    for (i, &(child_idx, child_radius)) in sorted_children.iter().enumerate() {
-        // Spiral parameter: how far from center to place this child
-        let t = if n <= 2 {
-            // For 1-2 children, place at specific positions
-            (i as f32 + 1.0) / (n as f32 + 1.0)
-        } else {
-            (i as f32 + 0.5) / n as f32
-        };
-
-        // Distance from parent center, scaled so children don't overlap parent boundary
-        let placement_radius = (available_radius - child_radius).max(0.0) * t.sqrt();
+    for i in fresh {
+        let (x, y) = nearest_gap(&placed, circles[i].r);
+        circles[i].x = x;
+        circles[i].y = y;
+        placed.push(circles[i]);
+    }
 
-        // Angle: golden angle spiral
-        let angle = i as f32 * golden_angle;
+    // Re-enclose in place: children keep their positions and the center
+    // moves to the new enclosing circle's
+    let enclosing = packing::enclose(&circles);
+    for (&child, circle) in children.iter().zip(&circles) {
+        nodes[child].offset = (
+            (circle.x - enclosing.x) as f32,
+            (circle.y - enclosing.y) as f32,
+        );
+    }
+    let min_radius = base_radius(nodes[idx].category, config);
+    nodes[idx].radius = (enclosing.r as f32).max(min_radius) + padding(nodes[idx].category, config);
+    (enclosing.x as f32, enclosing.y as f32)
+}
 
-        nodes[child_idx].x = parent_x + placement_radius * angle.cos();
-        nodes[child_idx].y = parent_y + placement_radius * angle.sin();
+/// Restore an untouched subtree from its previous circles.
+fn keep_previous(
+    idx: usize,
+    parent_center: (f32, f32),
+    nodes: &mut [LayoutNode],
+    previous: &[Option<(f32, f32, f32)>],
+) {
+    let (x, y, r) = previous[idx].unwrap_or((parent_center.0, parent_center.1, 0.0));
+    nodes[idx].radius = r;
+    nodes[idx].offset = (x - parent_center.0, y - parent_center.1);
+    for child in nodes[idx].children.clone() {
+        keep_previous(child, (x, y), nodes, previous);
+    }
+}
 
-        // If child overlaps parent boundary, clamp it
-        let dist_from_parent = placement_radius + child_radius;
-        if dist_from_parent > parent_radius - padding * 0.5 && placement_radius > f32::EPSILON {
-            let clamped_dist = (parent_radius - padding * 0.5 - child_radius).max(0.0);
-            let scale = clamped_dist / placement_radius;
-            nodes[child_idx].x = parent_x + placement_radius * scale * angle.cos();
-            nodes[child_idx].y = parent_y + placement_radius * scale * angle.sin();
+/// Push overlapping siblings apart, moving both of each pair equally.
+/// Only circles marked in `active` take part.
+fn separate_siblings(circles: &mut [Circle], active: &[bool]) {
+    const MAX_SWEEPS: usize = 50;
+
+    let active: Vec<usize> = (0..circles.len()).filter(|&i| active[i]).collect();
+    for _ in 0..MAX_SWEEPS {
+        let mut overlapping = false;
+        for (n, &i) in active.iter().enumerate() {
+            for &j in &active[n + 1..] {
+                overlapping |= push_apart(circles, i, j);
+            }
+        }
+        if !overlapping {
+            break;
         }
-
-        // Recurse into child
-        assign_positions(child_idx, nodes, config);
     }
-
-    // Avoid overlaps between siblings by checking pairwise distances
-    // and pushing apart if needed (single pass relaxation)
-    resolve_overlaps(&sorted_children, nodes, parent_x, parent_y, available_radius, max_child_radius);
 }
 
-/// Single-pass overlap resolution for sibling circles.
-/// Pushes overlapping children apart radially from the parent center.
-fn resolve_overlaps(
-    children: &[(usize, f32)],
-    nodes: &mut Vec<LayoutNode>,
-    parent_x: f32,
-    parent_y: f32,
-    available_radius: f32,
-    _max_child_radius: f32,
-) {
-    let n = children.len();
-    if n <= 1 {
-        return;
+/// Move circles `i` and `j` apart until they touch. Returns whether they
+/// overlapped.
+fn push_apart(circles: &mut [Circle], i: usize, j: usize) -> bool {
+    let (dx, dy) = (circles[j].x - circles[i].x, circles[j].y - circles[i].y);
+    let distance = dx.hypot(dy);
+    let overlap = circles[i].r + circles[j].r - distance;
+    if overlap <= 1e-6 {
+        return false;
     }
+    // Coincident centers split along x
+    let (ux, uy) = if distance > 1e-9 {
+        (dx / distance, dy / distance)
+    } else {
+        (1.0, 0.0)
+    };
+    let half = overlap * 0.5 + 1e-6;
+    circles[i].x -= ux * half;
+    circles[i].y -= uy * half;
+    circles[j].x += ux * half;
+    circles[j].y += uy * half;
+    true
+}
 
-    // Run a few relaxation iterations for better results
-    for _ in 0..3 {
-        for i in 0..n {
-            let (ci, ri) = children[i];
-            for j in (i + 1)..n {
-                let (cj, rj) = children[j];
-
-                let dx = nodes[cj].x - nodes[ci].x;
-                let dy = nodes[cj].y - nodes[ci].y;
-                let dist_sq = dx * dx + dy * dy;
-                let min_dist = ri + rj;
-                let min_dist_sq = min_dist * min_dist;
-
-                if dist_sq < min_dist_sq && dist_sq > f32::EPSILON {
-                    let dist = dist_sq.sqrt();
-                    let overlap = min_dist - dist;
-                    let push = overlap * 0.5;
-
-                    // Push apart along the line connecting their centers
-                    let nx = dx / dist;
-                    let ny = dy / dist;
-
-                    nodes[ci].x -= nx * push;
-                    nodes[ci].y -= ny * push;
-                    nodes[cj].x += nx * push;
-                    nodes[cj].y += ny * push;
-
-                    // Clamp to stay within parent
-                    clamp_to_parent(ci, ri, parent_x, parent_y, available_radius, nodes);
-                    clamp_to_parent(cj, rj, parent_x, parent_y, available_radius, nodes);
-                }
-            }
+/// Center of a circle of radius `r` as close to the origin as possible
+/// without overlapping `placed`, searching a fan of directions.
+fn nearest_gap(placed: &[Circle], r: f64) -> (f64, f64) {
+    const DIRECTIONS: usize = 64;
+
+    let mut best = (f64::INFINITY, 0.0, 0.0);
+    for step in 0..DIRECTIONS {
+        let angle = step as f64 * std::f64::consts::TAU / DIRECTIONS as f64;
+        let (ux, uy) = (angle.cos(), angle.sin());
+        let distance = free_distance(placed, (ux, uy), r);
+        if distance < best.0 {
+            best = (distance, ux * distance, uy * distance);
         }
     }
+    (best.1, best.2)
 }
 
-/// Clamp a child's position so it stays within the parent's available radius.
-fn clamp_to_parent(
-    child_idx: usize,
-    child_radius: f32,
-    parent_x: f32,
-    parent_y: f32,
-    available_radius: f32,
-    nodes: &mut [LayoutNode],
-) {
-    let dx = nodes[child_idx].x - parent_x;
-    let dy = nodes[child_idx].y - parent_y;
-    let dist = (dx * dx + dy * dy).sqrt();
-    let max_dist = (available_radius - child_radius).max(0.0);
+/// Smallest distance along unit direction `u` where a circle of radius `r`
+/// overlaps none of `placed`.
+fn free_distance(placed: &[Circle], u: (f64, f64), r: f64) -> f64 {
+    // Each circle blocks the distances where |d·u - c| < r + c.r
+    let mut blocked: Vec<(f64, f64)> = placed
+        .iter()
+        .filter_map(|c| {
+            let along = u.0 * c.x + u.1 * c.y;
+            let reach = r + c.r;
+            let disc = along * along - (c.x * c.x + c.y * c.y) + reach * reach;
+            (disc > 0.0).then(|| (along - disc.sqrt(), along + disc.sqrt()))
+        })
+        .collect();
+    blocked.sort_by(|a, b| a.0.total_cmp(&b.0));
 
-    if dist > max_dist && dist > f32::EPSILON {
-        let scale = max_dist / dist;
-        nodes[child_idx].x = parent_x + dx * scale;
-        nodes[child_idx].y = parent_y + dy * scale;
+    let mut distance = 0.0f64;
+    for (start, end) in blocked {
+        if start > distance {
+            break;
+        }
+        distance = distance.max(end);
     }
+    distance
 }
 
 #[cfg(test)]
@@ -540,11 +800,10 @@ mod tests {
         let positions = compute_codebase_layout(&edges, &categories, 4, Some(0), &config);
         assert_eq!(positions.len(), 8);
 
-        let sentinel = 3.402_823e+38_f32;
         // All 4 nodes should have non-sentinel positions
         for i in 0..4 {
             assert!(
-                positions[i * 2] < sentinel,
+                positions[i * 2] < SENTINEL,
                 "Node {i} should have valid x position, got {}",
                 positions[i * 2]
             );
@@ -575,10 +834,9 @@ mod tests {
         let positions = compute_codebase_layout(&edges, &categories, 6, Some(0), &config);
         assert_eq!(positions.len(), 12);
 
-        let sentinel = 3.402_823e+38_f32;
         for i in 0..6 {
             assert!(
-                positions[i * 2] < sentinel,
+                positions[i * 2] < SENTINEL,
                 "Node {i} should have valid position"
             );
         }
@@ -632,9 +890,8 @@ mod tests {
         let config = CodebaseLayoutConfig::default();
 
         let positions = compute_codebase_layout(&edges, &categories, 2, Some(0), &config);
-        let sentinel = 3.402_823e+38_f32;
         for i in 0..2 {
-            assert!(positions[i * 2] < sentinel, "Node {i} should have valid position");
+            assert!(positions[i * 2] < SENTINEL, "Node {i} should have valid position");
         }
     }
 
@@ -645,10 +902,9 @@ mod tests {
         let config = CodebaseLayoutConfig::default();
 
         let positions = compute_codebase_layout(&edges, &categories, 2, Some(0), &config);
-        let sentinel = 3.402_823e+38_f32;
         // Node 0 and 1 should have valid positions
-        assert!(positions[0] < sentinel);
-        assert!(positions[2] < sentinel);
+        assert!(positions[0] < SENTINEL);
+        assert!(positions[2] < SENTINEL);
     }
 
     #[test]
@@ -659,8 +915,7 @@ mod tests {
 
         let positions = compute_codebase_layout(&edges, &categories, 3, None, &config);
         // Should return sentinel positions (no valid edges)
-        let sentinel = 3.402_823e+38_f32;
-        assert!(positions[0] >= sentinel, "Invalid edge array should produce sentinel");
+        assert!(positions[0] >= SENTINEL, "Invalid edge array should produce sentinel");
     }
 
     #[test]
@@ -707,9 +962,8 @@ mod tests {
         let positions = compute_codebase_layout(&edges, &categories, n, Some(0), &config);
         assert_eq!(positions.len(), n * 2);
 
-        let sentinel = 3.402_823e+38_f32;
         let valid_count = (0..n)
-            .filter(|&i| positions[i * 2] < sentinel)
+            .filter(|&i| positions[i * 2] < SENTINEL)
             .count();
         assert_eq!(valid_count, n, "All {} nodes should have valid positions", n);
 
@@ -723,6 +977,172 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_packed_siblings_do_not_overlap() {
+        // dir(0) → 12 files, every other one holding 4 symbols
+        let mut edges = Vec::new();
+        let mut categories = vec![1u8];
+        for f in 1..=12u32 {
+            edges.extend([0, f]);
+            categories.push(2);
+        }
+        for f in (1..=12u32).step_by(2) {
+            for _ in 0..4 {
+                edges.extend([f, categories.len() as u32]);
+                categories.push(3);
+            }
+        }
+        let n = categories.len();
+        let config = CodebaseLayoutConfig {
+            spread_factor: 1.0,
+            ..Default::default()
+        };
+        let positions = compute_codebase_layout(&edges, &categories, n, Some(0), &config);
+
+        // Bare files are circles of the file radius; files with symbols at
+        // least that big
+        let distance = |a: usize, b: usize| {
+            (positions[a * 2] - positions[b * 2]).hypot(positions[a * 2 + 1] - positions[b * 2 + 1])
+        };
+        for a in 1..=12 {
+            for b in a + 1..=12 {
+                assert!(distance(a, b) >= 2.0 * config.file_radius - 1e-3, "{a} and {b} overlap");
+            }
+        }
+        // Symbols are packed around their file
+        for s in 13..n {
+            let file = edges[edges.iter().rposition(|&c| c == s as u32).unwrap() - 1] as usize;
+            assert!(distance(s, file) <= config.file_radius + config.file_padding);
+        }
+    }
+
+    #[test]
+    fn test_radii_match_packing() {
+        // repo(0) → dir(1) → files 2..5; node 6 is outside the tree
+        let edges = [0u32, 1, 1, 2, 1, 3, 1, 4, 1, 5];
+        let categories = [0u8, 1, 2, 2, 2, 2, 2];
+        let config = CodebaseLayoutConfig::default();
+        let layout = compute_codebase_layout_with_radii(&edges, &categories, 7, Some(0), &config);
+
+        assert_eq!(layout.radii[2], config.file_radius * config.spread_factor);
+        assert_eq!(layout.radii[6], 0.0);
+        let p = &layout.positions;
+        let distance =
+            |a: usize, b: usize| (p[a * 2] - p[b * 2]).hypot(p[a * 2 + 1] - p[b * 2 + 1]);
+        for child in 2..6 {
+            // Each file lies inside the directory's circle, padding included
+            let reach = distance(1, child) + layout.radii[child];
+            let inner = layout.radii[1] - config.directory_padding * config.spread_factor;
+            assert!(reach <= inner + 1e-3, "file {child} sticks out");
+        }
+        assert!(layout.radii[0] > layout.radii[1]);
+        assert_eq!(layout.to_flat().len(), 7 * 3);
+    }
+
+    #[test]
+    fn test_sort_keys_control_packing_order() {
+        // dir(0) → files 1..4, file 1 the largest (it holds two symbols)
+        let edges = [0u32, 1, 0, 2, 0, 3, 0, 4, 1, 5, 1, 6];
+        let categories = [1u8, 2, 2, 2, 2, 3, 3];
+        let default = compute_codebase_layout(&edges, &categories, 7, Some(0), &Default::default());
+        // Reversing the keys packs file 4 first, where file 1 used to go
+        let config = CodebaseLayoutConfig {
+            sort_keys: vec![0.0, 4.0, 3.0, 2.0, 1.0],
+            ..Default::default()
+        };
+        let keyed = compute_codebase_layout(&edges, &categories, 7, Some(0), &config);
+        assert_ne!(default, keyed);
+
+        // Adding a sibling without a key keeps the keyed files where they
+        // were relative to each other
+        let edges_plus: Vec<u32> = edges.iter().copied().chain([0, 7]).collect();
+        let categories_plus = [1u8, 2, 2, 2, 2, 3, 3, 3];
+        let grown = compute_codebase_layout(&edges_plus, &categories_plus, 8, Some(0), &config);
+        let direction = |p: &[f32]| (p[7] - p[9]).atan2(p[6] - p[8]);
+        assert!((direction(&keyed) - direction(&grown)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        // repo(0) → dirs 1, 2; dir 1 → files 3..=7; dir 2 → files 8..=10
+        let mut edges = vec![0u32, 1, 0, 2, 1, 3, 1, 4, 1, 5, 1, 6, 1, 7, 2, 8, 2, 9, 2, 10];
+        let mut categories = vec![0u8, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2];
+        let config = CodebaseLayoutConfig::default();
+        let mut layout = compute_codebase_layout_with_radii(&edges, &categories, 11, None, &config);
+        let unchanged =
+            update_codebase_layout(&edges, &categories, 11, None, &layout, &[], &config);
+        assert!(unchanged.slots.is_empty());
+
+        // A new file 11 in dir 1 leaves its siblings where they were
+        edges.extend([1, 11]);
+        categories.push(2);
+        let update = update_codebase_layout(&edges, &categories, 12, None, &layout, &[], &config);
+        assert!(update.slots.contains(&11));
+        assert!(update.slots.iter().all(|slot| !(3..=7).contains(slot)));
+        apply_update(&mut layout, &update, 12);
+
+        // File 4 gains three symbols: overlapping siblings are pushed apart,
+        // dir 2 moves as a whole
+        edges.extend([4, 12, 4, 13, 4, 14]);
+        categories.extend([3, 3, 3]);
+        let before = layout.positions.clone();
+        let update = update_codebase_layout(&edges, &categories, 15, None, &layout, &[], &config);
+        apply_update(&mut layout, &update, 15);
+
+        let p = &layout.positions;
+        let distance =
+            |a: usize, b: usize| (p[a * 2] - p[b * 2]).hypot(p[a * 2 + 1] - p[b * 2 + 1]);
+        for pair in edges.chunks(2) {
+            let (parent, child) = (pair[0] as usize, pair[1] as usize);
+            let reach = distance(parent, child) + layout.radii[child];
+            assert!(reach <= layout.radii[parent] + 1e-2, "{child} sticks out of {parent}");
+        }
+        for (a, b) in siblings(&edges) {
+            assert!(distance(a, b) >= layout.radii[a] + layout.radii[b] - 1e-2, "{a}, {b}");
+        }
+        let shift = |slot: usize| {
+            let (x, y) = (slot * 2, slot * 2 + 1);
+            (p[x] - before[x], p[y] - before[y])
+        };
+        for file in 8..=10 {
+            let (dx, dy) = (shift(file).0 - shift(2).0, shift(file).1 - shift(2).1);
+            assert!(dx.abs() < 1e-3 && dy.abs() < 1e-3);
+        }
+
+        // Removing file 11 again drops it
+        edges.drain(20..22);
+        let removed = update_codebase_layout(&edges, &categories, 15, None, &layout, &[1], &config);
+        let at = removed.slots.iter().position(|&slot| slot == 11).unwrap();
+        assert_eq!(removed.positions[at * 2], SENTINEL);
+        assert_eq!(removed.radii[at], 0.0);
+    }
+
+    fn apply_update(layout: &mut CodebaseLayout, update: &CodebaseLayoutUpdate, node_count: usize) {
+        layout.positions.resize(node_count * 2, SENTINEL);
+        layout.radii.resize(node_count, 0.0);
+        for (i, &slot) in update.slots.iter().enumerate() {
+            let slot = slot as usize;
+            layout.positions[slot * 2] = update.positions[i * 2];
+            layout.positions[slot * 2 + 1] = update.positions[i * 2 + 1];
+            layout.radii[slot] = update.radii[i];
+        }
+    }
+
+    /// Pairs of nodes sharing a parent.
+    fn siblings(edges: &[u32]) -> Vec<(usize, usize)> {
+        let pairs: Vec<(usize, usize)> = edges
+            .chunks(2)
+            .map(|e| (e[0] as usize, e[1] as usize))
+            .collect();
+        let mut siblings = Vec::new();
+        for (i, &(p, a)) in pairs.iter().enumerate() {
+            for &(_, b) in pairs[i + 1..].iter().filter(|&&(q, _)| q == p) {
+                siblings.push((a, b));
+            }
+        }
+        siblings
+    }
+
     #[test]
     fn test_children_within_parent_radius() {
         // Simple test: 1 dir with 3 files
@@ -20,15 +20,46 @@
 //! single-level Louvain produces thousands of tiny communities. The coarsening
 //! step iteratively merges these into meaningful larger clusters.
 //!
+//! **Leiden:** Louvain can leave communities badly connected, or even split
+//! in two, when a node holding them together moves elsewhere. Leiden adds a
+//! refinement phase between local moving and aggregation: each community is
+//! rebuilt from singletons by merging nodes only into well-connected
+//! subcommunities they have edges to, and the graph is aggregated by those
+//! subcommunities (with the unrefined communities as the starting partition
+//! of the next level). Every community it returns is connected.
+//!
+//! **Spectral:** Modularity optimization picks the number of communities
+//! itself. Spectral clustering instead splits the graph into a requested
+//! number of groups, from the leading eigenvectors of the random-walk
+//! Laplacian (see [`spectral`]).
+//!
+//! **Consensus:** Results depend on the order nodes are visited in.
+//! Consensus clustering runs detection several times with seeded random
+//! orders and keeps together the edge endpoints most runs agree on, with a
+//! per-node stability score for how settled each node's community is.
+//!
 //! **Layout:**
-//! 1. Arrange community centers on a circle, sized proportional to member count.
+//! 1. Arrange community centers on a circle, sized proportional to member count
+//!    (or in a grid, or as packed discs).
 //! 2. Place nodes within each community using a spiral layout for even spacing.
 //!
 //! # References
 //!
 //! - Blondel et al., "Fast unfolding of communities in large networks" (2008)
+//! - Traag, Waltman & van Eck, "From Louvain to Leiden: guaranteeing
+//!   well-connected communities" (2019)
+//! - Lancichinetti & Fortunato, "Consensus clustering in complex networks"
+//!   (2012)
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use super::{SENTINEL, is_placed, placed_point};
+use super::distance::{csr_from_edges, csr_pairs};
+use super::fit;
+use super::spectral::{self, SpectralLayoutConfig};
+use crate::rng::{DEFAULT_SEED, SeededRng};
 
 /// Result of community detection.
 pub struct CommunityResult {
@@ -41,7 +72,39 @@ pub struct CommunityResult {
     pub modularity: f64,
 }
 
-/// Configuration for community layout.
+/// One level of a community hierarchy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityLevel {
+    /// Community per node slot at this level, numbered in order of first
+    /// node like [`CommunityResult::assignments`].
+    pub assignments: Vec<u32>,
+    /// Number of communities at this level.
+    pub community_count: u32,
+    /// Modularity of this level's partition on the input graph.
+    pub modularity: f64,
+    /// For each community, the community of the next (coarser) level that
+    /// it merged into; empty on the last level.
+    pub parents: Vec<u32>,
+}
+
+/// Every level multi-level Louvain (or Girvan–Newman) went through, finest
+/// first. The `parents` of consecutive levels form the merge tree.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityHierarchy {
+    /// Levels from the first local moving pass to the last aggregation.
+    pub levels: Vec<CommunityLevel>,
+    /// Index of the level [`detect_communities`] returns (for
+    /// Girvan–Newman, the level of highest modularity), if any level beat
+    /// every node on its own.
+    pub best_level: Option<u32>,
+}
+
+/// Configuration for community layout. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct CommunityLayoutConfig {
     /// Louvain resolution parameter (default: 1.0).
     /// Higher values produce more, smaller communities.
@@ -56,6 +119,21 @@ pub struct CommunityLayoutConfig {
     pub node_spacing: f32,
     /// Global scale multiplier (default: 1.5).
     pub spread_factor: f32,
+    /// Arrangement of community centers (default: circle).
+    pub arrangement: CommunityArrangement,
+    /// Node sizes [w0, h0, w1, h1, ...] by slot; nodes are packed by their
+    /// bounding circles, node spacing apart edge to edge, and positions are
+    /// not normalized (not read from JS; the engine's node extents apply).
+    #[serde(skip)]
+    pub extents: Vec<f32>,
+    /// Largest random turn, in radians, given to each community's spiral so
+    /// neighbouring communities don't share a visible seam; 0 leaves every
+    /// spiral unrotated (default: 0.0).
+    pub spiral_rotation: f32,
+    /// Seed for the per-community spiral rotation offsets (not read from JS;
+    /// the engine seed applies).
+    #[serde(skip)]
+    pub seed: u64,
 }
 
 impl Default for CommunityLayoutConfig {
@@ -67,10 +145,40 @@ impl Default for CommunityLayoutConfig {
             community_spacing: 50.0,
             node_spacing: 10.0,
             spread_factor: 1.5,
+            arrangement: CommunityArrangement::Circle,
+            extents: Vec::new(),
+            spiral_rotation: 0.0,
+            seed: DEFAULT_SEED,
         }
     }
 }
 
+/// How community centers are arranged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommunityArrangement {
+    /// On a circle, each community taking an arc proportional to its size.
+    #[default]
+    Circle,
+    /// In rows, each community in a cell sized to it.
+    Grid,
+    /// Packed discs sized by member count, largest in the middle; wastes
+    /// little space when one community holds most nodes.
+    Packed,
+}
+
+/// Options of the engine's community layout entry points: the layout
+/// configuration plus where community centers come from.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CommunityLayoutOptions {
+    /// Keep each community centered on its members' current centroid and
+    /// only rearrange nodes within it (default: false, centers on a circle).
+    pub keep_centers: bool,
+    #[serde(flatten)]
+    pub config: CommunityLayoutConfig,
+}
+
 /// Adjacency representation for Louvain: CSR-like per-node neighbor lists.
 /// Stores both outgoing and incoming edges as undirected for modularity.
 struct AdjacencyList {
@@ -147,27 +255,33 @@ fn coarsen_graph(
     community: &[usize],
     num_communities: usize,
 ) -> AdjacencyList {
-    // Accumulate weighted edges between communities
-    // Using HashMap<(src_comm, tgt_comm), weight>
-    let mut inter_edges: HashMap<(usize, usize), f64> = HashMap::new();
-    let node_count = community.len();
-
-    for src in 0..node_count {
-        let src_comm = community[src];
-        for &(tgt, w) in &adj.neighbors[src] {
-            let tgt_comm = community[tgt];
-            // Only count each directed edge once (the adjacency stores both directions)
-            // We add the full weight; since both (src→tgt) and (tgt→src) are in adj,
-            // inter_edges will accumulate both directions automatically.
-            *inter_edges.entry((src_comm, tgt_comm)).or_insert(0.0) += w;
-        }
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); num_communities];
+    for (node, &comm) in community.iter().enumerate() {
+        members[comm].push(node);
     }
 
+    // One row of weights per community, by ascending target community, so
+    // float sums come out in the same order on every run. Rows are
+    // independent, so they parallelize with the `threads` feature.
+    let row = |comm: usize| community_row(adj, community, &members[comm], comm);
+
+    #[cfg(feature = "threads")]
+    let rows: Vec<Vec<(usize, f64)>> = {
+        use rayon::prelude::*;
+        (0..num_communities).into_par_iter().map(row).collect()
+    };
+    #[cfg(not(feature = "threads"))]
+    let rows: Vec<Vec<(usize, f64)>> = (0..num_communities).map(row).collect();
+
     let mut neighbors: Vec<Vec<(usize, f64)>> = vec![Vec::new(); num_communities];
     let mut degree = vec![0.0f64; num_communities];
     let mut total_weight = 0.0f64;
 
-    for (&(src_comm, tgt_comm), &w) in &inter_edges {
+    let inter_edges = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(src_comm, row)| row.iter().map(move |&(tgt_comm, w)| (src_comm, tgt_comm, w)));
+    for (src_comm, tgt_comm, w) in inter_edges {
         if src_comm == tgt_comm {
             // Self-loop: internal edges. Count only once for total_weight.
             // The degree contribution is already handled by both directed edges.
@@ -192,6 +306,84 @@ fn coarsen_graph(
     }
 }
 
+/// Edge weight from the `members` of community `comm` to each community,
+/// including its own (the internal weight), by ascending community.
+fn community_row(
+    adj: &AdjacencyList,
+    community: &[usize],
+    members: &[usize],
+    comm: usize,
+) -> Vec<(usize, f64)> {
+    let mut row: BTreeMap<usize, f64> = BTreeMap::new();
+    for &src in members {
+        // The adjacency stores both directions of every edge, so each
+        // direction adds its full weight here
+        for &(tgt, w) in &adj.neighbors[src] {
+            *row.entry(community[tgt]).or_insert(0.0) += w;
+        }
+        // Self-loops of an already coarsened graph live only in its degrees;
+        // carry them over so internal weight is not lost between levels
+        let listed: f64 = adj.neighbors[src].iter().map(|&(_, w)| w).sum();
+        let hidden = adj.degree[src] - listed;
+        if hidden > f64::EPSILON {
+            *row.entry(comm).or_insert(0.0) += hidden;
+        }
+    }
+    row.into_iter().collect()
+}
+
+/// Edge weight from one node to each of its neighboring communities, in
+/// order of first appearance. Unlike a HashMap, iteration order does not
+/// change between runs, so ties between equal gains always break the same
+/// way.
+struct CommunityWeights {
+    /// Position of each community in `weights`, usize::MAX if absent.
+    index: Vec<usize>,
+    weights: Vec<(usize, f64)>,
+}
+
+impl CommunityWeights {
+    fn new(community_count: usize) -> Self {
+        Self {
+            index: vec![usize::MAX; community_count],
+            weights: Vec::new(),
+        }
+    }
+
+    /// Replace the contents with the weights of `neighbors`.
+    fn gather(&mut self, neighbors: &[(usize, f64)], community: &[usize]) {
+        self.gather_where(neighbors, community, |_| true);
+    }
+
+    /// Like [`Self::gather`], skipping neighbors `keep` rejects.
+    fn gather_where(
+        &mut self,
+        neighbors: &[(usize, f64)],
+        community: &[usize],
+        keep: impl Fn(usize) -> bool,
+    ) {
+        for &(comm, _) in &self.weights {
+            self.index[comm] = usize::MAX;
+        }
+        self.weights.clear();
+        for &(neighbor, weight) in neighbors.iter().filter(|&&(n, _)| keep(n)) {
+            let comm = community[neighbor];
+            if self.index[comm] == usize::MAX {
+                self.index[comm] = self.weights.len();
+                self.weights.push((comm, 0.0));
+            }
+            self.weights[self.index[comm]].1 += weight;
+        }
+    }
+
+    fn get(&self, comm: usize) -> f64 {
+        match self.index[comm] {
+            usize::MAX => 0.0,
+            i => self.weights[i].1,
+        }
+    }
+}
+
 /// Run Phase 1 of Louvain: local moving optimization.
 ///
 /// Returns the community assignment for each node (0-indexed, NOT compacted).
@@ -202,27 +394,32 @@ fn louvain_local_moving(
     max_iterations: u32,
     min_modularity_gain: f64,
 ) -> Vec<usize> {
-    if adj.total_weight < f64::EPSILON {
-        return (0..node_count).collect();
-    }
-
-    let m2 = 2.0 * adj.total_weight;
-
-    // Initialize: each node in its own community
-    let mut community: Vec<usize> = (0..node_count).collect();
-    let mut sigma_tot: Vec<f64> = adj.degree.clone();
-    let mut sigma_in: Vec<f64> = vec![0.0; node_count];
+    local_moving(
+        adj,
+        (0..node_count).collect(),
+        resolution,
+        max_iterations,
+        min_modularity_gain,
+    )
+}
 
-    // For the coarsened graph, self-loops represent internal edges from previous level.
-    // Initialize sigma_in from self-loops in adjacency.
-    for node in 0..node_count {
-        for &(neighbor, weight) in &adj.neighbors[node] {
-            if neighbor == node {
-                sigma_in[node] += weight;
-            }
-        }
+/// Local moving starting from the partition `community`, whose IDs must be
+/// below the node count.
+///
+/// Returns the community assignment for each node (0-indexed, NOT compacted).
+fn local_moving(
+    adj: &AdjacencyList,
+    mut community: Vec<usize>,
+    resolution: f64,
+    max_iterations: u32,
+    min_modularity_gain: f64,
+) -> Vec<usize> {
+    let node_count = community.len();
+    if adj.total_weight < f64::EPSILON {
+        return community;
     }
 
+    let mut moves = LocalMoves::new(adj, &community, resolution);
     let mut improved = true;
     let mut iteration = 0u32;
 
@@ -233,60 +430,144 @@ fn louvain_local_moving(
 
         for node in 0..node_count {
             let node_comm = community[node];
-            let k_i = adj.degree[node];
+            let gain = moves.move_node(adj, node, &mut community);
+            if community[node] != node_comm {
+                improved = true;
+                total_gain += gain;
+            }
+        }
 
-            if k_i < f64::EPSILON {
-                continue;
+        if total_gain < min_modularity_gain {
+            break;
+        }
+    }
+
+    community
+}
+
+/// Local moving that only visits `frontier` nodes, queueing the neighbors
+/// of every node that moves (the queue of Leiden's fast local moving). The
+/// cost follows the size of the change rather than the graph.
+fn local_moving_from(
+    adj: &AdjacencyList,
+    mut community: Vec<usize>,
+    resolution: f64,
+    frontier: &[usize],
+) -> Vec<usize> {
+    if adj.total_weight < f64::EPSILON {
+        return community;
+    }
+
+    let mut moves = LocalMoves::new(adj, &community, resolution);
+    let mut queued = vec![false; community.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for &node in frontier {
+        if !queued[node] {
+            queued[node] = true;
+            queue.push_back(node);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        queued[node] = false;
+        let node_comm = community[node];
+        moves.move_node(adj, node, &mut community);
+        if community[node] == node_comm {
+            continue;
+        }
+        for &(neighbor, _) in &adj.neighbors[node] {
+            if !queued[neighbor] && community[neighbor] != community[node] {
+                queued[neighbor] = true;
+                queue.push_back(neighbor);
             }
+        }
+    }
+
+    community
+}
 
-            // Compute edge weights to each neighboring community
-            let mut comm_weights: HashMap<usize, f64> = HashMap::new();
+/// Community totals for local moving.
+struct LocalMoves {
+    sigma_tot: Vec<f64>,
+    sigma_in: Vec<f64>,
+    weights: CommunityWeights,
+    m2: f64,
+    resolution: f64,
+}
+
+impl LocalMoves {
+    fn new(adj: &AdjacencyList, community: &[usize], resolution: f64) -> Self {
+        let node_count = community.len();
+        let mut sigma_tot: Vec<f64> = vec![0.0; node_count];
+        let mut sigma_in: Vec<f64> = vec![0.0; node_count];
+
+        // Edges inside a starting community count towards its sigma_in; for
+        // singletons on the coarsened graph, these are the self-loops holding
+        // the previous level's internal edges.
+        for node in 0..node_count {
+            let comm = community[node];
+            sigma_tot[comm] += adj.degree[node];
             for &(neighbor, weight) in &adj.neighbors[node] {
-                let neighbor_comm = community[neighbor];
-                *comm_weights.entry(neighbor_comm).or_insert(0.0) += weight;
+                if community[neighbor] == comm {
+                    sigma_in[comm] += weight;
+                }
             }
+        }
+
+        Self {
+            sigma_tot,
+            sigma_in,
+            weights: CommunityWeights::new(node_count),
+            m2: 2.0 * adj.total_weight,
+            resolution,
+        }
+    }
+
+    /// Move `node` to the neighboring community with the best modularity
+    /// gain, if any beats staying. Returns the gain.
+    fn move_node(&mut self, adj: &AdjacencyList, node: usize, community: &mut [usize]) -> f64 {
+        let node_comm = community[node];
+        let k_i = adj.degree[node];
+        let m2 = self.m2;
 
-            let k_i_in = comm_weights.get(&node_comm).copied().unwrap_or(0.0);
+        if k_i < f64::EPSILON {
+            return 0.0;
+        }
 
-            // Remove node from its current community
-            sigma_tot[node_comm] -= k_i;
-            sigma_in[node_comm] -= 2.0 * k_i_in;
+        // Compute edge weights to each neighboring community
+        self.weights.gather(&adj.neighbors[node], community);
 
-            // Find the best community to move to
-            let mut best_comm = node_comm;
-            let mut best_gain = 0.0f64;
+        let k_i_in = self.weights.get(node_comm);
 
-            for (&target_comm, &k_i_to_c) in &comm_weights {
-                let delta_q = k_i_to_c / m2
-                    - resolution * sigma_tot[target_comm] * k_i / (m2 * m2);
-                let delta_q_back = k_i_in / m2
-                    - resolution * sigma_tot[node_comm] * k_i / (m2 * m2);
-                let net_gain = delta_q - delta_q_back;
+        // Remove node from its current community
+        self.sigma_tot[node_comm] -= k_i;
+        self.sigma_in[node_comm] -= 2.0 * k_i_in;
 
-                if net_gain > best_gain {
-                    best_gain = net_gain;
-                    best_comm = target_comm;
-                }
-            }
+        // Find the best community to move to
+        let mut best_comm = node_comm;
+        let mut best_gain = 0.0f64;
 
-            // Move node to best community
-            community[node] = best_comm;
-            let k_i_to_best = comm_weights.get(&best_comm).copied().unwrap_or(0.0);
-            sigma_tot[best_comm] += k_i;
-            sigma_in[best_comm] += 2.0 * k_i_to_best;
+        for &(target_comm, k_i_to_c) in &self.weights.weights {
+            let delta_q = k_i_to_c / m2
+                - self.resolution * self.sigma_tot[target_comm] * k_i / (m2 * m2);
+            let delta_q_back = k_i_in / m2
+                - self.resolution * self.sigma_tot[node_comm] * k_i / (m2 * m2);
+            let net_gain = delta_q - delta_q_back;
 
-            if best_comm != node_comm {
-                improved = true;
-                total_gain += best_gain;
+            if net_gain > best_gain {
+                best_gain = net_gain;
+                best_comm = target_comm;
             }
         }
 
-        if total_gain < min_modularity_gain {
-            break;
-        }
-    }
+        // Move node to best community
+        community[node] = best_comm;
+        let k_i_to_best = self.weights.get(best_comm);
+        self.sigma_tot[best_comm] += k_i;
+        self.sigma_in[best_comm] += 2.0 * k_i_to_best;
 
-    community
+        best_gain
+    }
 }
 
 /// Compact community IDs to be contiguous (0, 1, 2, ...).
@@ -363,192 +644,1613 @@ pub fn detect_communities(
     max_iterations: u32,
     min_modularity_gain: f64,
 ) -> CommunityResult {
-    if node_count == 0 {
-        return CommunityResult {
-            assignments: Vec::new(),
-            community_count: 0,
-            modularity: 0.0,
-        };
+    let mut louvain = Louvain::new(
+        csr,
+        node_count,
+        resolution,
+        max_iterations,
+        min_modularity_gain,
+    );
+    while !louvain.step() {}
+    louvain.into_result()
+}
+
+/// Number the communities in `community` (IDs below `bound`) in order of
+/// their first node. Returns the new number of each ID and the count.
+fn number_by_first_node(community: &[usize], bound: usize) -> (Vec<u32>, u32) {
+    let mut renumber = vec![u32::MAX; bound];
+    let mut next = 0u32;
+    for &comm in community {
+        if renumber[comm] == u32::MAX {
+            renumber[comm] = next;
+            next += 1;
+        }
     }
+    (renumber, next)
+}
 
-    let orig_adj = AdjacencyList::from_csr(csr, node_count);
+/// Run multi-level Louvain and return every level with its merge tree,
+/// for drilling down from coarse to fine communities. Arguments as in
+/// [`detect_communities`].
+pub fn detect_community_hierarchy(
+    csr: &[u32],
+    node_count: usize,
+    resolution: f32,
+    max_iterations: u32,
+    min_modularity_gain: f64,
+) -> CommunityHierarchy {
+    let mut louvain = Louvain::new(
+        csr,
+        node_count,
+        resolution,
+        max_iterations,
+        min_modularity_gain,
+    );
+    while !louvain.step() {}
+    louvain.into_hierarchy()
+}
 
-    // Handle degenerate case: no edges
-    if orig_adj.total_weight < f64::EPSILON {
-        let assignments: Vec<u32> = (0..node_count as u32).collect();
-        return CommunityResult {
-            assignments,
+/// Most aggregation levels Louvain runs.
+const MAX_LEVELS: u32 = 20;
+
+/// Multi-level Louvain one level at a time, for detection spread over
+/// several frames. [`detect_communities`] runs it to the end.
+pub struct Louvain {
+    /// The input graph, for evaluating each level's modularity.
+    orig_adj: AdjacencyList,
+    /// The graph coarsened by the levels so far.
+    current_adj: AdjacencyList,
+    current_node_count: usize,
+    node_count: usize,
+    resolution: f64,
+    max_iterations: u32,
+    min_modularity_gain: f64,
+    /// Compacted assignments of every level.
+    levels: Vec<Vec<usize>>,
+    /// Modularity of every level on the input graph.
+    level_modularity: Vec<f64>,
+    /// Index of the level `best` came from.
+    best_level: Option<u32>,
+    level: u32,
+    /// Best partition so far, by modularity on the input graph.
+    best: CommunityResult,
+    done: bool,
+}
+
+impl Louvain {
+    /// Prepare detection. Arguments as in [`detect_communities`].
+    pub fn new(
+        csr: &[u32],
+        node_count: usize,
+        resolution: f32,
+        max_iterations: u32,
+        min_modularity_gain: f64,
+    ) -> Self {
+        let orig_adj = AdjacencyList::from_csr(csr, node_count);
+
+        // Degenerate cases: no nodes, or no edges (every node on its own)
+        let trivial = node_count == 0 || orig_adj.total_weight < f64::EPSILON;
+        let best = CommunityResult {
+            assignments: (0..node_count as u32).collect(),
             community_count: node_count as u32,
-            modularity: 0.0,
+            modularity: if trivial { 0.0 } else { -1.0 },
         };
+
+        Self {
+            current_adj: AdjacencyList::from_csr(csr, node_count),
+            orig_adj,
+            current_node_count: node_count,
+            node_count,
+            resolution: resolution as f64,
+            max_iterations,
+            min_modularity_gain,
+            levels: Vec::new(),
+            level_modularity: Vec::new(),
+            best_level: None,
+            level: 0,
+            best,
+            done: trivial,
+        }
+    }
+
+    /// Whether detection has finished.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Rough fraction of the work done: levels so far over the most levels
+    /// that can run, 1 once done.
+    pub fn progress(&self) -> f32 {
+        if self.done {
+            1.0
+        } else {
+            self.level as f32 / MAX_LEVELS as f32
+        }
     }
 
-    let resolution_f64 = resolution as f64;
+    /// Run one level: local moving, then aggregation. Returns true once
+    /// done.
+    pub fn step(&mut self) -> bool {
+        if self.done {
+            return true;
+        }
+        self.level += 1;
+        self.done = self.run_level() || self.level >= MAX_LEVELS;
+        self.done
+    }
+
+    /// The best partition found.
+    pub fn into_result(self) -> CommunityResult {
+        self.best
+    }
 
-    // Multi-level Louvain with modularity tracking.
-    // We evaluate modularity on the original graph at each level and keep
-    // the partition that maximizes it. This prevents over-coarsening on
-    // tree-structured graphs where unchecked merging collapses to 1 community.
-    let mut levels: Vec<Vec<usize>> = Vec::new();
-    let mut current_adj = AdjacencyList::from_csr(csr, node_count);
-    let mut current_node_count = node_count;
-    let max_levels = 20;
+    /// Every level run so far, not just the best.
+    pub fn into_hierarchy(self) -> CommunityHierarchy {
+        let mut levels: Vec<CommunityLevel> = Vec::new();
+        // Level community of each node, and the first-node numbering of the
+        // previous level's communities
+        let mut raw: Vec<usize> = (0..self.node_count).collect();
+        let mut prev_renumber: Vec<u32> = Vec::new();
+
+        for (mapping, &modularity) in self.levels.iter().zip(&self.level_modularity) {
+            for comm in &mut raw {
+                *comm = mapping[*comm];
+            }
+            let community_count = mapping.iter().max().map_or(0, |&c| c + 1);
+            let (renumber, next) = number_by_first_node(&raw, community_count);
+            let assignments = raw.iter().map(|&comm| renumber[comm]).collect();
+
+            let mut parents = vec![0; prev_renumber.len()];
+            for (&numbered, &comm) in prev_renumber.iter().zip(mapping) {
+                parents[numbered as usize] = renumber[comm];
+            }
+            if let Some(prev) = levels.last_mut() {
+                prev.parents = parents;
+            }
+            levels.push(CommunityLevel {
+                assignments,
+                community_count: next,
+                modularity,
+                parents: Vec::new(),
+            });
+            prev_renumber = renumber;
+        }
 
-    // Track the best result across all levels
-    let mut best_assignments: Vec<u32> = (0..node_count as u32).collect();
-    let mut best_community_count = node_count as u32;
-    let mut best_modularity = -1.0f64;
+        CommunityHierarchy {
+            levels,
+            best_level: self.best_level,
+        }
+    }
 
-    for _level in 0..max_levels {
+    /// One Louvain level. Returns true when no further level can help.
+    fn run_level(&mut self) -> bool {
         // Phase 1: Local moving
         let community = louvain_local_moving(
-            &current_adj,
-            current_node_count,
-            resolution_f64,
-            max_iterations,
-            min_modularity_gain,
+            &self.current_adj,
+            self.current_node_count,
+            self.resolution,
+            self.max_iterations,
+            self.min_modularity_gain,
         );
 
         // Compact community IDs
         let (compacted, num_communities) = compact_communities(&community);
 
         // If no reduction happened, we're done
-        if num_communities >= current_node_count {
-            break;
+        if num_communities >= self.current_node_count {
+            return true;
         }
 
-        levels.push(compacted.clone());
+        self.levels.push(compacted.clone());
 
-        // Map current levels back to original nodes and evaluate modularity
-        let candidate = map_levels_to_original(&levels, node_count);
+        // Map current levels back to original nodes and evaluate modularity.
+        // Keeping the best level prevents over-coarsening on tree-structured
+        // graphs where unchecked merging collapses to 1 community.
+        let candidate = map_levels_to_original(&self.levels, self.node_count);
         let candidate_count = *candidate.iter().max().unwrap_or(&0) + 1;
-        let candidate_mod = compute_modularity(
-            &candidate,
-            candidate_count,
-            &orig_adj,
-            resolution_f64,
-        );
+        let candidate_mod =
+            compute_modularity(&candidate, candidate_count, &self.orig_adj, self.resolution);
+
+        self.level_modularity.push(candidate_mod);
 
         // Keep this level if it improves modularity (and has more than 1 community)
-        if candidate_mod > best_modularity && candidate_count > 1 {
-            best_assignments = candidate;
-            best_community_count = candidate_count;
-            best_modularity = candidate_mod;
+        if candidate_mod > self.best.modularity && candidate_count > 1 {
+            self.best_level = Some(self.levels.len() as u32 - 1);
+            self.best = CommunityResult {
+                assignments: candidate,
+                community_count: candidate_count,
+                modularity: candidate_mod,
+            };
         }
 
         // If modularity dropped significantly, further coarsening is harmful — stop
-        if candidate_mod < best_modularity - 0.01 {
-            break;
+        if candidate_mod < self.best.modularity - 0.01 {
+            return true;
         }
 
         // Phase 2: Coarsen the graph
-        let new_adj = coarsen_graph(&current_adj, &compacted, num_communities);
-        current_adj = new_adj;
-        current_node_count = num_communities;
-    }
-
-    CommunityResult {
-        assignments: best_assignments,
-        community_count: best_community_count,
-        modularity: best_modularity,
+        self.current_adj = coarsen_graph(&self.current_adj, &compacted, num_communities);
+        self.current_node_count = num_communities;
+        false
     }
 }
 
-/// Compute modularity Q for a given community assignment.
+/// Detect communities using the Leiden algorithm.
 ///
-/// Q = (1/2m) * Σ_ij [A_ij - resolution * k_i * k_j / (2m)] * δ(c_i, c_j)
-fn compute_modularity(
-    assignments: &[u32],
-    community_count: u32,
-    adj: &AdjacencyList,
-    resolution: f64,
-) -> f64 {
-    if adj.total_weight < f64::EPSILON {
-        return 0.0;
+/// Like [`detect_communities`], but refines each level's communities into
+/// well-connected subcommunities before aggregating, so no community comes
+/// out disconnected. Refinement merges greedily in node order rather than
+/// at random, so results are reproducible. Arguments as in
+/// [`detect_communities`].
+pub fn detect_communities_leiden(
+    csr: &[u32],
+    node_count: usize,
+    resolution: f32,
+    max_iterations: u32,
+    min_modularity_gain: f64,
+) -> CommunityResult {
+    let orig_adj = AdjacencyList::from_csr(csr, node_count);
+    if node_count == 0 || orig_adj.total_weight < f64::EPSILON {
+        return CommunityResult {
+            assignments: (0..node_count as u32).collect(),
+            community_count: node_count as u32,
+            modularity: 0.0,
+        };
     }
 
-    let m2 = 2.0 * adj.total_weight;
-
-    // Accumulate per-community: internal weight and total degree
-    let mut internal_weight = vec![0.0f64; community_count as usize];
-    let mut community_degree = vec![0.0f64; community_count as usize];
+    let resolution = resolution as f64;
+    let mut adj = AdjacencyList::from_csr(csr, node_count);
+    // Aggregate node holding each input node, and the partition of the
+    // aggregate nodes
+    let mut membership: Vec<usize> = (0..node_count).collect();
+    let mut partition: Vec<usize> = (0..node_count).collect();
+
+    for _ in 0..MAX_LEVELS {
+        let moved = local_moving(
+            &adj,
+            partition,
+            resolution,
+            max_iterations,
+            min_modularity_gain,
+        );
+        let (moved, community_count) = compact_communities(&moved);
+        let aggregate_count = moved.len();
+        partition = moved;
+        if community_count == aggregate_count {
+            break;
+        }
 
-    let node_count = assignments.len();
-    for node in 0..node_count {
-        let c = assignments[node] as usize;
-        community_degree[c] += adj.degree[node];
+        let (refined, refined_count) =
+            compact_communities(&refine_partition(&adj, &partition, resolution));
+        if refined_count == aggregate_count {
+            break;
+        }
 
-        for &(neighbor, weight) in &adj.neighbors[node] {
-            if assignments[neighbor] == assignments[node] {
-                internal_weight[c] += weight;
-            }
+        // Each subcommunity starts the next level in its community
+        let mut next = vec![0; refined_count];
+        for (node, &sub) in refined.iter().enumerate() {
+            next[sub] = partition[node];
         }
+        for m in &mut membership {
+            *m = refined[*m];
+        }
+        adj = coarsen_graph(&adj, &refined, refined_count);
+        partition = next;
     }
 
-    // Each internal edge is counted twice (once from each endpoint)
-    // so internal_weight[c] is already 2 * actual internal weight.
-
-    let mut q = 0.0f64;
-    for c in 0..community_count as usize {
-        let l_c = internal_weight[c] / 2.0; // Actual internal weight
-        let d_c = community_degree[c];
-        q += l_c / adj.total_weight - resolution * (d_c / m2).powi(2);
+    let grouped: Vec<usize> = membership.iter().map(|&m| partition[m]).collect();
+    let assignments = split_disconnected(&orig_adj, &grouped);
+    let community_count = assignments.iter().max().map_or(0, |&c| c + 1);
+    let modularity = compute_modularity(&assignments, community_count, &orig_adj, resolution);
+    CommunityResult {
+        assignments,
+        community_count,
+        modularity,
     }
-
-    q
 }
 
-/// Compute layout positions from community assignments.
-///
-/// Communities are arranged in a circle with radius proportional to total
-/// node count. Nodes within each community are placed in a spiral pattern
-/// for even distribution.
-///
-/// # Arguments
+/// Largest number of edges [`detect_communities_girvan_newman`] accepts;
+/// its cost grows with the square of the edge count times the node count.
+pub const GIRVAN_NEWMAN_MAX_EDGES: usize = 2000;
+
+/// Exact divisive clustering (Girvan–Newman), for small graphs, teaching
+/// and checking Louvain on fixtures.
 ///
-/// * `assignments` - Community ID per node (from `detect_communities`)
-/// * `community_count` - Number of distinct communities
-/// * `node_count` - Total number of nodes
-/// * `config` - Layout configuration parameters
+/// Repeatedly removes the edge with the highest betweenness (shortest
+/// paths through it, recomputed after every removal in the component it
+/// left), recording a level each time a community splits in two, until no
+/// edges are left. Edges are unweighted and undirected; ties go to the
+/// lowest node pair, so results are reproducible.
 ///
 /// # Returns
 ///
-/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...].
-/// Sentinel value (f32::MAX) is used for invalid/absent nodes.
-pub fn compute_community_layout(
-    assignments: &[u32],
-    community_count: u32,
+/// The full dendrogram as a [`CommunityHierarchy`], finest (every node on
+/// its own) first and the connected components last, with `best_level`
+/// the level of highest modularity. `None` if the graph has more than
+/// [`GIRVAN_NEWMAN_MAX_EDGES`] edges.
+pub fn detect_communities_girvan_newman(
+    csr: &[u32],
     node_count: usize,
-    config: &CommunityLayoutConfig,
-) -> Vec<f32> {
-    const SENTINEL: f32 = 3.402_823e+38;
-
-    if node_count == 0 || community_count == 0 {
-        return Vec::new();
+) -> Option<CommunityHierarchy> {
+    let orig_adj = AdjacencyList::from_csr(csr, node_count);
+    let mut neighbors: Vec<Vec<usize>> = orig_adj
+        .neighbors
+        .iter()
+        .enumerate()
+        .map(|(u, list)| {
+            let mut distinct: Vec<usize> =
+                list.iter().map(|&(v, _)| v).filter(|&v| v != u).collect();
+            distinct.sort_unstable();
+            distinct.dedup();
+            distinct
+        })
+        .collect();
+    let edge_count = neighbors.iter().map(Vec::len).sum::<usize>() / 2;
+    if edge_count > GIRVAN_NEWMAN_MAX_EDGES {
+        return None;
     }
 
-    let mut positions = vec![SENTINEL; node_count * 2];
-
-    // Gather nodes per community
-    let mut community_members: Vec<Vec<usize>> = vec![Vec::new(); community_count as usize];
-    for (node, &comm) in assignments.iter().enumerate() {
-        if node < node_count && (comm as usize) < community_members.len() {
-            community_members[comm as usize].push(node);
+    // Partitions from the components down to single nodes
+    let mut partitions = vec![component_labels(&neighbors)];
+    let mut betweenness: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    edge_betweenness(&neighbors, &(0..node_count).collect::<Vec<_>>(), &mut betweenness);
+    while let Some((u, v)) = highest_betweenness(&betweenness) {
+        neighbors[u].retain(|&n| n != v);
+        neighbors[v].retain(|&n| n != u);
+        betweenness.remove(&(u, v));
+
+        let side = reachable(&neighbors, u);
+        edge_betweenness(&neighbors, &side, &mut betweenness);
+        if !side.contains(&v) {
+            edge_betweenness(&neighbors, &reachable(&neighbors, v), &mut betweenness);
+            partitions.push(component_labels(&neighbors));
         }
     }
 
-    // Compute community center positions on a circle.
-    // The circle radius scales with the total number of nodes and spacing.
-    let base_radius = if community_count <= 1 {
-        0.0
+    partitions.reverse();
+    let mut levels: Vec<CommunityLevel> = partitions
+        .into_iter()
+        .map(|assignments| {
+            let community_count = assignments.iter().max().map_or(0, |&c| c + 1);
+            let modularity = compute_modularity(&assignments, community_count, &orig_adj, 1.0);
+            CommunityLevel {
+                assignments,
+                community_count,
+                modularity,
+                parents: Vec::new(),
+            }
+        })
+        .collect();
+    for i in 1..levels.len() {
+        let mut parents = vec![0u32; levels[i - 1].community_count as usize];
+        for (&fine, &coarse) in levels[i - 1].assignments.iter().zip(&levels[i].assignments) {
+            parents[fine as usize] = coarse;
+        }
+        levels[i - 1].parents = parents;
+    }
+
+    let best_level = (0..levels.len())
+        .filter(|&i| levels[i].modularity > levels[0].modularity)
+        .max_by(|&a, &b| levels[a].modularity.total_cmp(&levels[b].modularity))
+        .map(|i| i as u32);
+    Some(CommunityHierarchy { levels, best_level })
+}
+
+/// Recompute the betweenness of the edges among `nodes`, a union of whole
+/// components, with Brandes' algorithm. Keys are (low, high) node pairs.
+fn edge_betweenness(
+    neighbors: &[Vec<usize>],
+    nodes: &[usize],
+    betweenness: &mut BTreeMap<(usize, usize), f64>,
+) {
+    for &u in nodes {
+        for &v in neighbors[u].iter().filter(|&&v| u < v) {
+            betweenness.insert((u, v), 0.0);
+        }
+    }
+
+    let n = neighbors.len();
+    let mut sigma = vec![0.0f64; n];
+    let mut distance = vec![usize::MAX; n];
+    let mut delta = vec![0.0f64; n];
+    let mut order = Vec::new();
+    for &source in nodes {
+        for &node in &order {
+            (sigma[node], distance[node], delta[node]) = (0.0, usize::MAX, 0.0);
+        }
+        count_shortest_paths(neighbors, source, &mut sigma, &mut distance, &mut order);
+
+        // Dependencies flow back from the farthest nodes
+        for &node in order.iter().rev() {
+            let predecessors = neighbors[node]
+                .iter()
+                .filter(|&&prev| distance[prev] + 1 == distance[node]);
+            for &prev in predecessors {
+                let share = sigma[prev] / sigma[node] * (1.0 + delta[node]);
+                *betweenness.entry((prev.min(node), prev.max(node))).or_insert(0.0) += share;
+                delta[prev] += share;
+            }
+        }
+    }
+}
+
+/// Breadth-first search from `source`, counting the shortest paths to each
+/// node into `sigma` and recording nodes in visiting order. `sigma` and
+/// `distance` must be reset (0 and `usize::MAX`) for reachable nodes.
+fn count_shortest_paths(
+    neighbors: &[Vec<usize>],
+    source: usize,
+    sigma: &mut [f64],
+    distance: &mut [usize],
+    order: &mut Vec<usize>,
+) {
+    order.clear();
+    (sigma[source], distance[source]) = (1.0, 0);
+    order.push(source);
+    let mut i = 0;
+    while i < order.len() {
+        let node = order[i];
+        for &next in &neighbors[node] {
+            if distance[next] == usize::MAX {
+                distance[next] = distance[node] + 1;
+                order.push(next);
+            }
+            if distance[next] == distance[node] + 1 {
+                sigma[next] += sigma[node];
+            }
+        }
+        i += 1;
+    }
+}
+
+/// The edge of highest betweenness, the lowest pair on (near) ties.
+fn highest_betweenness(betweenness: &BTreeMap<(usize, usize), f64>) -> Option<(usize, usize)> {
+    let mut best: Option<((usize, usize), f64)> = None;
+    for (&edge, &value) in betweenness {
+        if !matches!(best, Some((_, top)) if value <= top + 1e-9) {
+            best = Some((edge, value));
+        }
+    }
+    best.map(|(edge, _)| edge)
+}
+
+/// Nodes connected to `start`, including it.
+fn reachable(neighbors: &[Vec<usize>], start: usize) -> Vec<usize> {
+    let mut seen = vec![false; neighbors.len()];
+    seen[start] = true;
+    let mut nodes = vec![start];
+    let mut i = 0;
+    while i < nodes.len() {
+        for &next in &neighbors[nodes[i]] {
+            if !seen[next] {
+                seen[next] = true;
+                nodes.push(next);
+            }
+        }
+        i += 1;
+    }
+    nodes
+}
+
+/// Split the graph into a fixed number of communities by spectral
+/// clustering, for views that need exactly `clusters` groups.
+///
+/// See [`spectral::spectral_clusters`]; nodes without edges join the
+/// smallest community. The reported modularity is the standard
+/// (resolution 1) one.
+pub fn detect_communities_spectral(
+    csr: &[u32],
+    node_count: usize,
+    clusters: u32,
+    seed: u64,
+) -> CommunityResult {
+    let config = SpectralLayoutConfig {
+        seed,
+        ..Default::default()
+    };
+    let assignments = spectral::spectral_clusters(csr, node_count, clusters as usize, &config);
+    let community_count = assignments.iter().max().map_or(0, |&c| c + 1);
+    let adj = AdjacencyList::from_csr(csr, node_count);
+    let modularity = compute_modularity(&assignments, community_count, &adj, 1.0);
+    CommunityResult {
+        assignments,
+        community_count,
+        modularity,
+    }
+}
+
+/// Leiden refinement: split each community of `community` into
+/// subcommunities, starting from singletons.
+///
+/// Nodes are visited in order; a node still on its own and well connected
+/// to the rest of its community joins the neighboring subcommunity of the
+/// same community with the best positive modularity gain, provided that
+/// subcommunity is itself well connected. A node only ever joins a
+/// subcommunity it has edges to, so every subcommunity is connected.
+///
+/// Returns the subcommunity of each node (0-indexed, NOT compacted).
+fn refine_partition(adj: &AdjacencyList, community: &[usize], resolution: f64) -> Vec<usize> {
+    let node_count = community.len();
+    let m2 = 2.0 * adj.total_weight;
+
+    let mut community_degree = vec![0.0f64; node_count];
+    for node in 0..node_count {
+        community_degree[community[node]] += adj.degree[node];
+    }
+    // Weight of the edges from each subcommunity to the rest of its community
+    let mut external: Vec<f64> = (0..node_count)
+        .map(|node| {
+            adj.neighbors[node]
+                .iter()
+                .filter(|&&(n, _)| n != node && community[n] == community[node])
+                .map(|&(_, w)| w)
+                .sum()
+        })
+        .collect();
+    let mut refined: Vec<usize> = (0..node_count).collect();
+    let mut sub_degree = adj.degree.clone();
+    let mut sub_size = vec![1u32; node_count];
+    // Well connected: edge weight to the rest of the community at least
+    // what the null model expects
+    let well_connected = |weight: f64, degree: f64, comm: usize| {
+        weight >= resolution * degree * (community_degree[comm] - degree) / m2
+    };
+
+    let mut comm_weights = CommunityWeights::new(node_count);
+    for node in 0..node_count {
+        let comm = community[node];
+        let k_i = adj.degree[node];
+        if sub_size[node] != 1 || !well_connected(external[node], k_i, comm) {
+            continue;
+        }
+
+        comm_weights.gather_where(&adj.neighbors[node], &refined, |n| {
+            n != node && community[n] == comm
+        });
+
+        let mut best = node;
+        let mut best_gain = 0.0f64;
+        for &(sub, k_i_to_sub) in &comm_weights.weights {
+            if !well_connected(external[sub], sub_degree[sub], comm) {
+                continue;
+            }
+            let gain = k_i_to_sub / m2 - resolution * sub_degree[sub] * k_i / (m2 * m2);
+            if gain > best_gain {
+                best_gain = gain;
+                best = sub;
+            }
+        }
+
+        if best != node {
+            let k_i_to_best = comm_weights.get(best);
+            refined[node] = best;
+            sub_degree[best] += k_i;
+            sub_size[best] += 1;
+            sub_size[node] = 0;
+            external[best] += external[node] - 2.0 * k_i_to_best;
+        }
+    }
+
+    refined
+}
+
+/// Split communities into their connected components, numbering them in
+/// order of their first node.
+fn split_disconnected(adj: &AdjacencyList, community: &[usize]) -> Vec<u32> {
+    let inside: Vec<Vec<usize>> = (0..community.len())
+        .map(|node| {
+            adj.neighbors[node]
+                .iter()
+                .map(|&(neighbor, _)| neighbor)
+                .filter(|&neighbor| community[neighbor] == community[node])
+                .collect()
+        })
+        .collect();
+    component_labels(&inside)
+}
+
+/// Community detection algorithm for repeated runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommunityAlgorithm {
+    /// Multi-level Louvain ([`detect_communities`]).
+    #[default]
+    Louvain,
+    /// Leiden ([`detect_communities_leiden`]).
+    Leiden,
+    /// Spectral clustering into a fixed number of communities
+    /// ([`detect_communities_spectral`]); ignores the resolution.
+    Spectral,
+}
+
+/// Algorithm and parameters for a detection run. Deserializes from
+/// camelCase JS options, with defaults for omitted fields.
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DetectionConfig {
+    /// Algorithm to run (default: Louvain).
+    pub algorithm: CommunityAlgorithm,
+    /// Resolution parameter (default: 1.0).
+    pub resolution: f32,
+    /// Maximum iterations per level (default: 100).
+    pub max_iterations: u32,
+    /// Convergence threshold for modularity gain (default: 0.0001).
+    pub min_modularity_gain: f64,
+    /// Number of communities for the spectral algorithm (default: 2).
+    pub clusters: u32,
+    /// Seed for the spectral algorithm (not read from JS; the engine seed
+    /// applies).
+    #[serde(skip)]
+    pub seed: u64,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: CommunityAlgorithm::Louvain,
+            resolution: 1.0,
+            max_iterations: 100,
+            min_modularity_gain: 0.0001,
+            clusters: 2,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+impl DetectionConfig {
+    /// Run the configured algorithm on a CSR graph.
+    pub fn detect(&self, csr: &[u32], node_count: usize) -> CommunityResult {
+        let detect = match self.algorithm {
+            CommunityAlgorithm::Louvain => detect_communities,
+            CommunityAlgorithm::Leiden => detect_communities_leiden,
+            CommunityAlgorithm::Spectral => {
+                return detect_communities_spectral(csr, node_count, self.clusters, self.seed);
+            }
+        };
+        detect(
+            csr,
+            node_count,
+            self.resolution,
+            self.max_iterations,
+            self.min_modularity_gain,
+        )
+    }
+}
+
+/// Configuration for consensus clustering. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConsensusConfig {
+    /// Algorithm run each time and its parameters.
+    #[serde(flatten)]
+    pub detection: DetectionConfig,
+    /// Fraction of runs that must put an edge's endpoints together for
+    /// the consensus to keep them together (default: 0.5).
+    pub threshold: f32,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            detection: DetectionConfig::default(),
+            threshold: 0.5,
+        }
+    }
+}
+
+/// Consensus partition over several detection runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusResult {
+    /// Consensus community per node slot, numbered in order of first node.
+    pub assignments: Vec<u32>,
+    /// Number of consensus communities.
+    pub community_count: u32,
+    /// Per node, the fraction of runs agreeing with the consensus about
+    /// which of its neighbors share its community (1 = every run agreed;
+    /// 1 for nodes without edges).
+    pub stability: Vec<f32>,
+}
+
+/// Detect communities several times and combine the runs.
+///
+/// Each run visits the nodes in a different seeded random order, which is
+/// what makes single runs differ. Endpoints of an edge end up together in
+/// the consensus when more than `threshold` of the runs put them in one
+/// community; consensus communities are the connected groups of such
+/// edges.
+///
+/// # Arguments
+///
+/// * `csr` - Graph edges in CSR format: [offsets..., targets...]
+/// * `node_count` - Number of nodes in the graph
+/// * `runs` - Number of detection runs (at least 1)
+/// * `seed` - Seed for the visiting orders
+/// * `config` - Algorithm and its parameters
+pub fn detect_communities_consensus(
+    csr: &[u32],
+    node_count: usize,
+    runs: u32,
+    seed: u64,
+    config: &ConsensusConfig,
+) -> ConsensusResult {
+    let runs = runs.max(1);
+    let edges: Vec<(usize, usize)> = csr_pairs(csr, node_count)
+        .filter(|&(u, v)| u != v && v < node_count)
+        .collect();
+
+    // Runs that put each edge's endpoints in one community
+    let mut together = vec![0u32; edges.len()];
+    let mut rng = SeededRng::new(seed);
+    let mut order: Vec<u32> = (0..node_count as u32).collect();
+    for _ in 0..runs {
+        rng.shuffle(&mut order);
+        let assignments = detect_relabeled(&edges, &order, config);
+        for (count, &(u, v)) in together.iter_mut().zip(&edges) {
+            *count += u32::from(assignments[u] == assignments[v]);
+        }
+    }
+
+    let kept = |count: u32| count as f32 > config.threshold * runs as f32;
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for (&(u, v), _) in edges.iter().zip(&together).filter(|&(_, &c)| kept(c)) {
+        neighbors[u].push(v);
+        neighbors[v].push(u);
+    }
+    let assignments = component_labels(&neighbors);
+    let community_count = assignments.iter().max().map_or(0, |&c| c + 1);
+
+    // Agreement with the consensus, summed over incident edges
+    let mut agreement = vec![0u32; node_count];
+    let mut degree = vec![0u32; node_count];
+    for (&(u, v), &count) in edges.iter().zip(&together) {
+        let agreed = if assignments[u] == assignments[v] {
+            count
+        } else {
+            runs - count
+        };
+        for node in [u, v] {
+            agreement[node] += agreed;
+            degree[node] += 1;
+        }
+    }
+    let stability = agreement
+        .iter()
+        .zip(&degree)
+        .map(|(&a, &d)| {
+            if d == 0 {
+                1.0
+            } else {
+                a as f32 / (d * runs) as f32
+            }
+        })
+        .collect();
+
+    ConsensusResult {
+        assignments,
+        community_count,
+        stability,
+    }
+}
+
+/// Run the configured detection with node `i` relabeled `label[i]`, and
+/// return the communities under the original labels.
+fn detect_relabeled(
+    edges: &[(usize, usize)],
+    label: &[u32],
+    config: &ConsensusConfig,
+) -> Vec<u32> {
+    let node_count = label.len();
+    let relabeled: Vec<(u32, u32)> = edges
+        .iter()
+        .map(|&(u, v)| (label[u], label[v]))
+        .collect();
+    let csr = csr_from_edges(node_count, &relabeled);
+    let result = config.detection.detect(&csr, node_count);
+    label
+        .iter()
+        .map(|&l| result.assignments[l as usize])
+        .collect()
+}
+
+/// Detect communities among `nodes` only, as if the rest of the graph did
+/// not exist, e.g. for clustering a filtered view.
+///
+/// Edges leaving the subset are ignored. Assignments are indexed by global
+/// slot like [`detect_communities`], with `u32::MAX` for slots outside the
+/// subset; modularity is that of the subgraph. Out-of-range and repeated
+/// IDs in `nodes` are ignored.
+pub fn detect_communities_in_subset(
+    csr: &[u32],
+    node_count: usize,
+    nodes: &[u32],
+    config: &DetectionConfig,
+) -> CommunityResult {
+    let mut local = vec![u32::MAX; node_count];
+    let mut global = Vec::new();
+    for &node in nodes.iter().filter(|&&n| (n as usize) < node_count) {
+        if local[node as usize] == u32::MAX {
+            local[node as usize] = global.len() as u32;
+            global.push(node as usize);
+        }
+    }
+    let edges: Vec<(u32, u32)> = csr_pairs(csr, node_count)
+        .filter(|&(u, v)| v < node_count && local[u] != u32::MAX && local[v] != u32::MAX)
+        .map(|(u, v)| (local[u], local[v]))
+        .collect();
+
+    let result = config.detect(&csr_from_edges(global.len(), &edges), global.len());
+    let mut assignments = vec![u32::MAX; node_count];
+    for (&slot, &comm) in global.iter().zip(&result.assignments) {
+        assignments[slot] = comm;
+    }
+    CommunityResult {
+        assignments,
+        ..result
+    }
+}
+
+/// Community assignments renumbered to follow an earlier detection.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StableAssignments {
+    /// Community per node slot, reusing the previous IDs where possible.
+    /// IDs need not be contiguous.
+    pub assignments: Vec<u32>,
+    /// Number of distinct communities.
+    pub community_count: u32,
+    /// Slots whose community ID differs from the previous one, including
+    /// slots that had none.
+    pub changed: Vec<u32>,
+}
+
+/// Renumber `current` communities to match `previous` ones by member
+/// overlap, so community colors survive a re-detection.
+///
+/// Pairs of a current and a previous community are matched greedily from
+/// the largest number of shared nodes down, each community matched at most
+/// once. Unmatched current communities get fresh IDs above every previous
+/// one. Slots past the end of `previous`, or holding `u32::MAX`, have no
+/// previous community.
+pub fn match_communities(previous: &[u32], current: &[u32]) -> StableAssignments {
+    let had = |slot: usize| previous.get(slot).copied().filter(|&p| p != u32::MAX);
+
+    let mut overlap: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+    for (slot, &comm) in current.iter().enumerate() {
+        if let Some(prev) = had(slot) {
+            *overlap.entry((comm, prev)).or_insert(0) += 1;
+        }
+    }
+    let mut pairs: Vec<((u32, u32), u32)> = overlap.into_iter().collect();
+    // Largest overlap first; ties keep the (current, previous) order
+    pairs.sort_by_key(|&(_, shared)| std::cmp::Reverse(shared));
+
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut taken: HashSet<u32> = HashSet::new();
+    for ((comm, prev), _) in pairs {
+        if !remap.contains_key(&comm) && !taken.contains(&prev) {
+            remap.insert(comm, prev);
+            taken.insert(prev);
+        }
+    }
+
+    let mut next = previous
+        .iter()
+        .filter(|&&p| p != u32::MAX)
+        .max()
+        .map_or(0, |&p| p + 1);
+    let mut assignments = Vec::with_capacity(current.len());
+    for &comm in current {
+        let id = *remap.entry(comm).or_insert_with(|| {
+            next += 1;
+            next - 1
+        });
+        assignments.push(id);
+    }
+
+    let changed = (0..assignments.len())
+        .filter(|&slot| had(slot) != Some(assignments[slot]))
+        .map(|slot| slot as u32)
+        .collect();
+    StableAssignments {
+        assignments,
+        community_count: remap.len() as u32,
+        changed,
+    }
+}
+
+/// Update communities after a small graph change without re-running the
+/// whole detection.
+///
+/// Starts from the previous partition, with nodes that had no community as
+/// singletons, and runs local moving only around the change: the
+/// `changed` nodes and their neighbors are visited first, and the
+/// neighbors of every node that moves are queued in turn. Communities can
+/// grow, shrink and merge but are never split, so re-run a full detection
+/// now and then after many updates.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Number of node slots
+/// * `previous` - Community per slot from an earlier detection; slots past
+///   its end or holding `u32::MAX` have none
+/// * `changed` - Slots of added nodes and of the ends of added or removed
+///   edges
+/// * `resolution` - Resolution parameter (1.0 = standard modularity)
+///
+/// # Returns
+///
+/// Assignments keeping the previous IDs, as in [`match_communities`].
+pub fn update_communities(
+    csr: &[u32],
+    node_count: usize,
+    previous: &[u32],
+    changed: &[u32],
+    resolution: f32,
+) -> StableAssignments {
+    let adj = AdjacencyList::from_csr(csr, node_count);
+
+    // Dense labels below the node count, as local moving expects
+    let mut dense: HashMap<u32, usize> = HashMap::new();
+    let mut next = 0usize;
+    let mut fresh = || {
+        next += 1;
+        next - 1
+    };
+    let start: Vec<usize> = (0..node_count)
+        .map(|slot| match previous.get(slot).copied().filter(|&p| p != u32::MAX) {
+            Some(prev) => *dense.entry(prev).or_insert_with(&mut fresh),
+            None => fresh(),
+        })
+        .collect();
+
+    let mut frontier: Vec<usize> = Vec::new();
+    for slot in changed.iter().map(|&s| s as usize).filter(|&s| s < node_count) {
+        frontier.push(slot);
+        frontier.extend(adj.neighbors[slot].iter().map(|&(n, _)| n));
+    }
+    let community = local_moving_from(&adj, start, resolution as f64, &frontier);
+
+    let current: Vec<u32> = community.iter().map(|&c| c as u32).collect();
+    match_communities(previous, &current)
+}
+
+/// Communities and positions after an incremental update.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityUpdate {
+    /// Updated communities, keeping the previous IDs.
+    #[serde(flatten)]
+    pub communities: StableAssignments,
+    /// Interleaved target positions by slot, with only the changed nodes
+    /// moved.
+    pub positions: Vec<f32>,
+}
+
+/// Move nodes that changed community next to their new community.
+///
+/// Each node goes to the mean of its placed neighbors in its community that
+/// stay put, or else to the centroid of the community's unmoved members,
+/// offset by up to the node spacing in a seeded direction so nodes don't
+/// stack. Nodes with neither keep their position. All other positions are
+/// untouched.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `assignments` - Community per slot; `u32::MAX` leaves a slot out
+/// * `moved` - Slots to place, e.g. [`StableAssignments::changed`]
+/// * `positions` - Interleaved positions by slot, with sentinel values for
+///   slots without a position; updated in place
+/// * `config` - Layout configuration (node spacing and seed)
+pub fn place_updated_nodes(
+    csr: &[u32],
+    assignments: &[u32],
+    moved: &[u32],
+    positions: &mut [f32],
+    config: &CommunityLayoutConfig,
+) {
+    let node_count = positions.len() / 2;
+    let mut anchored: Vec<u32> = assignments.to_vec();
+    anchored.resize(node_count, u32::MAX);
+    for &slot in moved.iter().filter(|&&s| (s as usize) < node_count) {
+        anchored[slot as usize] = u32::MAX;
+    }
+    let count = assignments
+        .iter()
+        .filter(|&&c| c != u32::MAX)
+        .max()
+        .map_or(0, |&c| c as usize + 1);
+    let centroids = community_centroids(&anchored, count, positions);
+    let adj = AdjacencyList::from_csr(csr, node_count);
+
+    let mut rng = SeededRng::new(config.seed);
+    for slot in moved.iter().map(|&s| s as usize).filter(|&s| s < node_count) {
+        let Some(&comm) = assignments.get(slot).filter(|&&c| c != u32::MAX) else {
+            continue;
+        };
+        let (sum, n) = adj.neighbors[slot]
+            .iter()
+            .map(|&(v, _)| v)
+            .filter(|&v| anchored[v] == comm && placed_point(positions, v).is_some())
+            .fold(((0.0f32, 0.0f32), 0u32), |((sx, sy), n), v| {
+                ((sx + positions[v * 2], sy + positions[v * 2 + 1]), n + 1)
+            });
+        let target = if n > 0 {
+            Some((sum.0 / n as f32, sum.1 / n as f32))
+        } else {
+            centroids[comm as usize]
+        };
+        let Some((x, y)) = target else {
+            continue;
+        };
+        let angle = rng.range_f32(0.0, std::f32::consts::TAU);
+        let distance = rng.range_f32(0.5, 1.0) * config.node_spacing;
+        positions[slot * 2] = x + distance * angle.cos();
+        positions[slot * 2 + 1] = y + distance * angle.sin();
+    }
+}
+
+/// Detection outcome at one resolution of a sweep.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionSample {
+    /// Resolution detection ran at.
+    pub resolution: f32,
+    /// Number of communities found.
+    pub community_count: u32,
+    /// Standard (resolution 1) modularity of the partition, comparable
+    /// across samples.
+    pub modularity: f64,
+}
+
+/// Detect communities at `steps` evenly spaced resolutions from
+/// `min_resolution` to `max_resolution` (both included), for choosing a
+/// resolution from the modularity curve. `config.resolution` is ignored.
+pub fn sweep_resolution(
+    csr: &[u32],
+    node_count: usize,
+    min_resolution: f32,
+    max_resolution: f32,
+    steps: u32,
+    config: &DetectionConfig,
+) -> Vec<ResolutionSample> {
+    let adj = AdjacencyList::from_csr(csr, node_count);
+    (0..steps)
+        .map(|step| {
+            let t = if steps > 1 {
+                step as f32 / (steps - 1) as f32
+            } else {
+                0.0
+            };
+            let resolution = min_resolution + (max_resolution - min_resolution) * t;
+            let sample = DetectionConfig {
+                resolution,
+                ..*config
+            };
+            let result = sample.detect(csr, node_count);
+            ResolutionSample {
+                resolution,
+                community_count: result.community_count,
+                modularity: compute_modularity(
+                    &result.assignments,
+                    result.community_count,
+                    &adj,
+                    1.0,
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Graph with one super-node per community.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommunityGraph {
+    /// Members per community (indexed by community ID).
+    pub sizes: Vec<u32>,
+    /// Interleaved centroid [x0, y0, x1, y1, ...] of each community's
+    /// placed members; the origin for communities without any.
+    pub centroids: Vec<f32>,
+    /// Summed weight of the edges inside each community.
+    pub internal_weight: Vec<f32>,
+    /// Edges between communities as (a, b, summed weight) with a < b,
+    /// sorted; edges in both directions add up.
+    pub edges: Vec<(u32, u32, f32)>,
+}
+
+/// Collapse every community into a super-node, summing the weights of the
+/// edges between communities.
+///
+/// # Arguments
+///
+/// * `edges` - Graph edges as (source slot, target slot, weight)
+/// * `assignments` - Community per slot; `u32::MAX` leaves a slot out
+/// * `positions` - Interleaved positions by slot, with sentinel values for
+///   slots without a position
+pub fn community_graph(
+    edges: &[(u32, u32, f32)],
+    assignments: &[u32],
+    positions: &[f32],
+) -> CommunityGraph {
+    let count = assignments
+        .iter()
+        .filter(|&&c| c != u32::MAX)
+        .max()
+        .map_or(0, |&c| c as usize + 1);
+    let mut sizes = vec![0u32; count];
+    for &comm in assignments.iter().filter(|&&c| c != u32::MAX) {
+        sizes[comm as usize] += 1;
+    }
+    let centroids = community_centroids(assignments, count, positions)
+        .into_iter()
+        .flat_map(|center| {
+            let (x, y) = center.unwrap_or((0.0, 0.0));
+            [x, y]
+        })
+        .collect();
+
+    let mut internal_weight = vec![0.0f32; count];
+    for (a, b, weight) in community_edges(edges, assignments) {
+        if a == b {
+            internal_weight[a as usize] += weight;
+        }
+    }
+
+    CommunityGraph {
+        sizes,
+        centroids,
+        internal_weight,
+        edges: aggregate_community_edges(edges, assignments)
+            .into_iter()
+            .map(|edge| (edge.source, edge.target, edge.weight))
+            .collect(),
+    }
+}
+
+/// Edges joining two communities, summed per community pair.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommunityEdge {
+    /// Lower community ID of the pair.
+    pub source: u32,
+    /// Higher community ID of the pair.
+    pub target: u32,
+    /// Summed edge weight.
+    pub weight: f32,
+    /// Number of edges.
+    pub count: u32,
+}
+
+/// Sum the edges between each pair of communities, for drawing one bundled
+/// edge per pair.
+///
+/// # Arguments
+///
+/// * `edges` - Graph edges as (source slot, target slot, weight)
+/// * `assignments` - Community per slot; `u32::MAX` leaves a slot out
+///
+/// # Returns
+///
+/// One entry per community pair with at least one edge between them,
+/// sorted by pair; edges in both directions add up.
+pub fn aggregate_community_edges(
+    edges: &[(u32, u32, f32)],
+    assignments: &[u32],
+) -> Vec<CommunityEdge> {
+    let mut between: BTreeMap<(u32, u32), (f32, u32)> = BTreeMap::new();
+    for (a, b, weight) in community_edges(edges, assignments).filter(|&(a, b, _)| a != b) {
+        let entry = between.entry((a.min(b), a.max(b))).or_insert((0.0, 0));
+        *entry = (entry.0 + weight, entry.1 + 1);
+    }
+    between
+        .into_iter()
+        .map(|((source, target), (weight, count))| CommunityEdge {
+            source,
+            target,
+            weight,
+            count,
+        })
+        .collect()
+}
+
+/// Color communities so adjacent ones get different color indices.
+///
+/// Greedy (Welsh–Powell) coloring of the community meta-graph: communities
+/// in order of falling degree take the lowest index their colored
+/// neighbors don't use. When all `palette_size` indices are taken around a
+/// community, it takes the one shared with the least edge weight, so
+/// clashes fall on weakly connected pairs.
+///
+/// # Arguments
+///
+/// * `edges` - Graph edges as (source slot, target slot, weight)
+/// * `assignments` - Community per slot; `u32::MAX` leaves a slot out
+/// * `palette_size` - Number of available colors (at least 1)
+///
+/// # Returns
+///
+/// The color index per slot, `u32::MAX` for slots left out.
+pub fn assign_community_colors(
+    edges: &[(u32, u32, f32)],
+    assignments: &[u32],
+    palette_size: u32,
+) -> Vec<u32> {
+    let palette = palette_size.max(1) as usize;
+    let count = assignments
+        .iter()
+        .filter(|&&c| c != u32::MAX)
+        .max()
+        .map_or(0, |&c| c as usize + 1);
+    let mut neighbors: Vec<Vec<(usize, f32)>> = vec![Vec::new(); count];
+    for edge in aggregate_community_edges(edges, assignments) {
+        let (a, b) = (edge.source as usize, edge.target as usize);
+        neighbors[a].push((b, edge.weight));
+        neighbors[b].push((a, edge.weight));
+    }
+
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by_key(|&c| std::cmp::Reverse(neighbors[c].len()));
+    let mut colors = vec![usize::MAX; count];
+    for c in order {
+        // Edge weight to neighbors already holding each color
+        let mut clash = vec![0.0f32; palette];
+        let mut taken = vec![false; palette];
+        for &(n, weight) in neighbors[c].iter().filter(|&&(n, _)| colors[n] != usize::MAX) {
+            taken[colors[n]] = true;
+            clash[colors[n]] += weight;
+        }
+        colors[c] = taken.iter().position(|&t| !t).unwrap_or_else(|| {
+            (0..palette)
+                .min_by(|&a, &b| clash[a].total_cmp(&clash[b]))
+                .unwrap_or(0)
+        });
+    }
+
+    assignments
+        .iter()
+        .map(|&c| match colors.get(c as usize) {
+            Some(&color) if c != u32::MAX => color as u32,
+            _ => u32::MAX,
+        })
+        .collect()
+}
+
+/// Edges as (source community, target community, weight), skipping edges
+/// with an end left out.
+fn community_edges<'a>(
+    edges: &'a [(u32, u32, f32)],
+    assignments: &'a [u32],
+) -> impl Iterator<Item = (u32, u32, f32)> + 'a {
+    let community_of = |slot: u32| {
+        assignments
+            .get(slot as usize)
+            .copied()
+            .filter(|&c| c != u32::MAX)
+    };
+    edges.iter().filter_map(move |&(source, target, weight)| {
+        Some((community_of(source)?, community_of(target)?, weight))
+    })
+}
+
+/// Centroid of the placed members of each of the first `count`
+/// communities; `None` for communities without any. Slots assigned
+/// `u32::MAX` or a community past `count` are skipped.
+fn community_centroids(
+    assignments: &[u32],
+    count: usize,
+    positions: &[f32],
+) -> Vec<Option<(f32, f32)>> {
+    let mut sums = vec![(0.0f32, 0.0f32, 0u32); count];
+    for (slot, &comm) in assignments.iter().enumerate() {
+        let (Some(sum), Some(&x), Some(&y)) = (
+            sums.get_mut(comm as usize),
+            positions.get(slot * 2),
+            positions.get(slot * 2 + 1),
+        ) else {
+            continue;
+        };
+        if is_placed(x, y) {
+            *sum = (sum.0 + x, sum.1 + y, sum.2 + 1);
+        }
+    }
+    sums.into_iter()
+        .map(|(sx, sy, n)| (n > 0).then(|| (sx / n as f32, sy / n as f32)))
+        .collect()
+}
+
+/// Connected components of an adjacency list, numbered in order of their
+/// first node.
+fn component_labels(neighbors: &[Vec<usize>]) -> Vec<u32> {
+    let mut labels = vec![u32::MAX; neighbors.len()];
+    let mut next = 0u32;
+    let mut stack = Vec::new();
+    for start in 0..neighbors.len() {
+        if labels[start] != u32::MAX {
+            continue;
+        }
+        labels[start] = next;
+        stack.push(start);
+        while let Some(node) = stack.pop() {
+            for &neighbor in &neighbors[node] {
+                if labels[neighbor] == u32::MAX {
+                    labels[neighbor] = next;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        next += 1;
+    }
+    labels
+}
+
+/// Compute modularity Q for a given community assignment.
+///
+/// Q = (1/2m) * Σ_ij [A_ij - resolution * k_i * k_j / (2m)] * δ(c_i, c_j)
+fn compute_modularity(
+    assignments: &[u32],
+    community_count: u32,
+    adj: &AdjacencyList,
+    resolution: f64,
+) -> f64 {
+    if adj.total_weight < f64::EPSILON {
+        return 0.0;
+    }
+
+    let m2 = 2.0 * adj.total_weight;
+
+    // Per-node contribution: (community, degree, weight to own community).
+    // Independent per node, so it parallelizes with the `threads` feature.
+    let contribution = |node: usize| {
+        let internal: f64 = adj.neighbors[node]
+            .iter()
+            .filter(|&&(neighbor, _)| assignments[neighbor] == assignments[node])
+            .map(|&(_, weight)| weight)
+            .sum();
+        (assignments[node] as usize, adj.degree[node], internal)
+    };
+
+    #[cfg(feature = "threads")]
+    let contributions: Vec<(usize, f64, f64)> = {
+        use rayon::prelude::*;
+        (0..assignments.len()).into_par_iter().map(contribution).collect()
+    };
+    #[cfg(not(feature = "threads"))]
+    let contributions: Vec<(usize, f64, f64)> = (0..assignments.len()).map(contribution).collect();
+
+    // Accumulate per-community: internal weight and total degree
+    let mut internal_weight = vec![0.0f64; community_count as usize];
+    let mut community_degree = vec![0.0f64; community_count as usize];
+
+    for (c, degree, internal) in contributions {
+        community_degree[c] += degree;
+        internal_weight[c] += internal;
+    }
+
+    // Each internal edge is counted twice (once from each endpoint)
+    // so internal_weight[c] is already 2 * actual internal weight.
+
+    let mut q = 0.0f64;
+    for c in 0..community_count as usize {
+        let l_c = internal_weight[c] / 2.0; // Actual internal weight
+        let d_c = community_degree[c];
+        q += l_c / adj.total_weight - resolution * (d_c / m2).powi(2);
+    }
+
+    q
+}
+
+/// Compute layout positions from community assignments.
+///
+/// Communities are arranged in a circle with radius proportional to total
+/// node count. Nodes within each community are placed in a spiral pattern
+/// for even distribution.
+///
+/// # Arguments
+///
+/// * `assignments` - Community ID per node (from `detect_communities`)
+/// * `community_count` - Number of distinct communities
+/// * `node_count` - Total number of nodes
+/// * `config` - Layout configuration parameters
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...].
+/// Sentinel value (f32::MAX) is used for invalid/absent nodes.
+pub fn compute_community_layout(
+    assignments: &[u32],
+    community_count: u32,
+    node_count: usize,
+    config: &CommunityLayoutConfig,
+) -> Vec<f32> {
+    layout_communities(assignments, community_count, node_count, &[], config)
+}
+
+/// Compute community layout positions around the communities' current
+/// centers, for re-clustering a live view without moving clusters.
+///
+/// Each community stays centered on the centroid of its placed members in
+/// `current` and only its nodes are rearranged. Communities without placed
+/// members are centered as in [`compute_community_layout`]. Positions are
+/// not normalized, so they stay in the current coordinate space.
+///
+/// # Arguments
+///
+/// * `assignments` - Community ID per node (from `detect_communities`)
+/// * `community_count` - Number of distinct communities
+/// * `current` - Current interleaved positions by slot, with sentinel
+///   values for slots without a position (length `node_count * 2`)
+/// * `config` - Layout configuration parameters
+pub fn compute_community_layout_around(
+    assignments: &[u32],
+    community_count: u32,
+    current: &[f32],
+    config: &CommunityLayoutConfig,
+) -> Vec<f32> {
+    let centers = community_centroids(assignments, community_count as usize, current);
+    layout_communities(assignments, community_count, current.len() / 2, &centers, config)
+}
+
+/// Bounding circle of each community, for drawing community backgrounds.
+///
+/// Each circle is centered on the centroid of the community's placed
+/// members and reaches the far edge of its farthest member, so sized nodes
+/// are covered whole.
+///
+/// # Arguments
+///
+/// * `assignments` - Community ID per node (from `detect_communities`)
+/// * `community_count` - Number of distinct communities
+/// * `positions` - Interleaved positions by slot, with sentinel values for
+///   slots without a position
+/// * `extents` - Node sizes [w0, h0, w1, h1, ...] by slot; may be empty
+///
+/// # Returns
+///
+/// A `Vec<f32>` of [cx0, cy0, r0, cx1, cy1, r1, ...] per community.
+/// Communities without placed members get sentinel centers (f32::MAX) and
+/// radius 0.
+pub fn community_bounds(
+    assignments: &[u32],
+    community_count: u32,
+    positions: &[f32],
+    extents: &[f32],
+) -> Vec<f32> {
+    let count = community_count as usize;
+    let centers = community_centroids(assignments, count, positions);
+    let node_radii = node_radii(extents, positions.len() / 2).unwrap_or_default();
+    let mut radii = vec![0.0f32; count];
+    for (slot, &comm) in assignments.iter().enumerate().take(positions.len() / 2) {
+        let Some(&Some((cx, cy))) = centers.get(comm as usize) else {
+            continue;
+        };
+        let (x, y) = (positions[slot * 2], positions[slot * 2 + 1]);
+        if !is_placed(x, y) {
+            continue;
+        }
+        let reach = (x - cx).hypot(y - cy) + node_radii.get(slot).copied().unwrap_or(0.0);
+        radii[comm as usize] = radii[comm as usize].max(reach);
+    }
+
+    centers
+        .iter()
+        .zip(radii)
+        .flat_map(|(center, r)| {
+            let (cx, cy) = center.unwrap_or((SENTINEL, SENTINEL));
+            [cx, cy, r]
+        })
+        .collect()
+}
+
+/// Shared body of the community layouts. `centers` holds a fixed center
+/// per community; communities past its end or without one are centered on
+/// the circle. Normalization only runs when no center is fixed.
+fn layout_communities(
+    assignments: &[u32],
+    community_count: u32,
+    node_count: usize,
+    centers: &[Option<(f32, f32)>],
+    config: &CommunityLayoutConfig,
+) -> Vec<f32> {
+    if node_count == 0 || community_count == 0 {
+        return Vec::new();
+    }
+
+    let mut positions = vec![SENTINEL; node_count * 2];
+
+    // Gather nodes per community
+    let mut community_members: Vec<Vec<usize>> = vec![Vec::new(); community_count as usize];
+    for (node, &comm) in assignments.iter().enumerate() {
+        if node < node_count && (comm as usize) < community_members.len() {
+            community_members[comm as usize].push(node);
+        }
+    }
+
+    // With node sizes, each community is packed around its center first
+    // and arranged by the radius it actually takes
+    let node_radii = node_radii(&config.extents, node_count);
+    let packs: Vec<Option<SizedPack>> = community_members
+        .iter()
+        .map(|members| {
+            let radii = node_radii.as_deref().filter(|_| !members.is_empty())?;
+            Some(pack_sized_members(members, radii, config))
+        })
+        .collect();
+    let inner: Vec<f32> = community_members
+        .iter()
+        .zip(&packs)
+        .map(|(members, pack)| match pack {
+            Some(pack) if config.spread_factor > 0.0 => pack.radius / config.spread_factor,
+            Some(pack) => pack.radius,
+            None => community_inner_radius(members.len(), config.node_spacing),
+        })
+        .collect();
+
+    let arranged = match config.arrangement {
+        CommunityArrangement::Circle => circle_centers(&community_members, &inner, config),
+        CommunityArrangement::Grid => grid_centers(&community_members, &inner, config),
+        CommunityArrangement::Packed => packed_centers(&community_members, &inner, config),
+    };
+
+    let mut rng = SeededRng::new(config.seed);
+
+    for comm_id in 0..community_count as usize {
+        let members = &community_members[comm_id];
+        if members.is_empty() {
+            continue;
+        }
+
+        let (cx, cy) = centers
+            .get(comm_id)
+            .copied()
+            .flatten()
+            .unwrap_or(arranged[comm_id]);
+
+        // Place nodes within the community using spiral layout, rotated by a
+        // seeded offset when asked to
+        let rotation = if config.spiral_rotation > 0.0 {
+            rng.range_f32(0.0, config.spiral_rotation)
+        } else {
+            0.0
+        };
+        match &packs[comm_id] {
+            Some(pack) => place_sized_pack(members, pack, (cx, cy), rotation, &mut positions),
+            None => {
+                let inner_radius = inner[comm_id];
+                place_nodes_in_community(members, cx, cy, inner_radius, rotation, config, &mut positions);
+            }
+        }
+    }
+
+    // Normalize positions to a target bounding radius.
+    // Without normalization, the outer radius grows linearly with community count,
+    // which causes layouts to spread far beyond the viewport for graphs with many
+    // communities (common in trees). Target radius scales as sqrt(N) * spacing * spread.
+    // Grid and packed arrangements already grow with the square root of the
+    // area, and shrinking them (or sized nodes) would make nodes overlap.
+    let circle = config.arrangement == CommunityArrangement::Circle;
+    if circle && node_radii.is_none() && centers.iter().all(Option::is_none) {
+        normalize_positions(&mut positions, node_count, config);
+    }
+
+    positions
+}
+
+/// Community centers on a circle, each community taking an arc
+/// proportional to its member count.
+fn circle_centers(
+    community_members: &[Vec<usize>],
+    inner: &[f32],
+    config: &CommunityLayoutConfig,
+) -> Vec<(f32, f32)> {
+    // The circle radius scales with the total number of nodes and spacing.
+    let base_radius = if community_members.len() <= 1 {
+        0.0
     } else {
         // Circumference should be large enough to space communities apart.
         // Each community gets an arc proportional to its member count.
-        let total_arc = community_members.iter()
-            .map(|m| {
-                let r = community_inner_radius(m.len(), config.node_spacing);
-                r * 2.0 + config.community_spacing
-            })
+        let total_arc = inner.iter()
+            .map(|r| r * 2.0 + config.community_spacing)
             .sum::<f32>();
         total_arc / std::f32::consts::TAU
     };
@@ -564,89 +2266,247 @@ pub fn compute_community_layout(
     // Prevent division by zero for empty graphs
     let total_weighted_count = if total_weighted_count < 1.0 { 1.0 } else { total_weighted_count };
 
-    for comm_id in 0..community_count as usize {
-        let members = &community_members[comm_id];
-        if members.is_empty() {
-            continue;
+    community_members
+        .iter()
+        .map(|members| {
+            // Fraction of circle this community occupies (weighted by size)
+            let fraction = members.len() as f32 / total_weighted_count;
+            let center_angle = angle + fraction * std::f32::consts::TAU / 2.0;
+            angle += fraction * std::f32::consts::TAU;
+            (outer_radius * center_angle.cos(), outer_radius * center_angle.sin())
+        })
+        .collect()
+}
+
+/// Radius of the disc a community with inner radius `inner` fills, at
+/// least half the node spacing so single nodes keep their distance.
+fn community_disc_radius(inner: f32, config: &CommunityLayoutConfig) -> f32 {
+    (inner * config.spread_factor).max(config.node_spacing * 0.5)
+}
+
+/// Community centers in rows, each community in a cell as wide as its disc,
+/// rows as tall as their largest disc, centered on the origin.
+fn grid_centers(
+    community_members: &[Vec<usize>],
+    inner: &[f32],
+    config: &CommunityLayoutConfig,
+) -> Vec<(f32, f32)> {
+    let gap = config.community_spacing;
+    let placed: Vec<usize> = (0..community_members.len())
+        .filter(|&c| !community_members[c].is_empty())
+        .collect();
+    let columns = (placed.len() as f32).sqrt().ceil().max(1.0) as usize;
+
+    let mut centers = vec![(0.0, 0.0); community_members.len()];
+    let mut y = 0.0f32;
+    let mut width = 0.0f32;
+    for row in placed.chunks(columns) {
+        let radii: Vec<f32> = row
+            .iter()
+            .map(|&c| community_disc_radius(inner[c], config))
+            .collect();
+        let height = radii.iter().fold(0.0f32, |a, &r| a.max(r * 2.0));
+        let mut x = 0.0f32;
+        for (&c, &r) in row.iter().zip(&radii) {
+            centers[c] = (x + r, y + height * 0.5);
+            x += r * 2.0 + gap;
         }
+        width = width.max(x - gap);
+        y += height + gap;
+    }
 
-        // Fraction of circle this community occupies (weighted by size)
-        let fraction = members.len() as f32 / total_weighted_count;
-        let center_angle = angle + fraction * std::f32::consts::TAU / 2.0;
+    let (dx, dy) = (width * 0.5, (y - gap).max(0.0) * 0.5);
+    for center in &mut centers {
+        *center = (center.0 - dx, center.1 - dy);
+    }
+    centers
+}
 
-        let cx = outer_radius * center_angle.cos();
-        let cy = outer_radius * center_angle.sin();
+/// Passes of the packed arrangement pulling discs in and pushing them apart.
+const PACKING_PASSES: u32 = 200;
+
+/// Community discs packed around the origin, largest first.
+///
+/// Discs start on a sunflower spiral in order of size, then alternate a
+/// pull towards the origin with pushing overlapping discs apart (the
+/// smaller disc moves more). Final passes without the pull remove the
+/// remaining overlaps. Overlap checks sweep along x, so each pass costs
+/// about O(k log k) for k communities of similar sizes.
+fn packed_centers(
+    community_members: &[Vec<usize>],
+    inner: &[f32],
+    config: &CommunityLayoutConfig,
+) -> Vec<(f32, f32)> {
+    let gap = config.community_spacing;
+    let mut order: Vec<usize> = (0..community_members.len())
+        .filter(|&c| !community_members[c].is_empty())
+        .collect();
+    order.sort_by(|&a, &b| inner[b].total_cmp(&inner[a]));
+    let radii: Vec<f32> = order
+        .iter()
+        .map(|&c| community_disc_radius(inner[c], config) + gap * 0.5)
+        .collect();
 
-        // Place nodes within the community using spiral layout
-        let inner_radius = community_inner_radius(members.len(), config.node_spacing);
-        place_nodes_in_community(members, cx, cy, inner_radius, config, &mut positions);
+    let mut discs = sunflower_discs(&radii);
 
-        angle += fraction * std::f32::consts::TAU;
+    for _ in 0..PACKING_PASSES {
+        for disc in &mut discs {
+            *disc = (disc.0 * 0.95, disc.1 * 0.95);
+        }
+        separate_discs(&mut discs, &radii);
+    }
+    for _ in 0..PACKING_PASSES {
+        if !separate_discs(&mut discs, &radii) {
+            break;
+        }
     }
 
-    // Normalize positions to a target bounding radius.
-    // Without normalization, the outer radius grows linearly with community count,
-    // which causes layouts to spread far beyond the viewport for graphs with many
-    // communities (common in trees). Target radius scales as sqrt(N) * spacing * spread.
-    normalize_positions(&mut positions, node_count, config);
+    let mut centers = vec![(0.0, 0.0); community_members.len()];
+    for (&c, &disc) in order.iter().zip(&discs) {
+        centers[c] = disc;
+    }
+    centers
+}
 
-    positions
+/// Sunflower start for packing discs: the k-th disc at the radius
+/// enclosing the area of the discs before it, turned by the golden angle.
+fn sunflower_discs(radii: &[f32]) -> Vec<(f32, f32)> {
+    let golden_angle = std::f32::consts::TAU / (1.0 + 5.0f32.sqrt());
+    let mut area = 0.0f32;
+    radii
+        .iter()
+        .enumerate()
+        .map(|(k, &r)| {
+            let distance = (area / std::f32::consts::PI).sqrt();
+            area += std::f32::consts::PI * r * r;
+            let angle = k as f32 * golden_angle;
+            (distance * angle.cos(), distance * angle.sin())
+        })
+        .collect()
 }
 
-/// Normalize all non-sentinel positions so the layout fits within a target radius.
-///
-/// Target radius = `node_spacing * sqrt(node_count) * spread_factor`.
-/// This ensures the layout scales predictably regardless of the number of communities.
-fn normalize_positions(positions: &mut [f32], node_count: usize, config: &CommunityLayoutConfig) {
-    const SENTINEL: f32 = 3.402_823e+38;
+/// Nodes of one community packed by size around its center.
+struct SizedPack {
+    /// Offset of each member from the center, in member order.
+    offsets: Vec<(f32, f32)>,
+    /// Radius of the smallest circle about the center holding every node.
+    radius: f32,
+}
 
-    if node_count == 0 {
-        return;
-    }
+/// Bounding-circle radius of each node slot from interleaved extents
+/// [w0, h0, w1, h1, ...]; missing, NaN or negative entries count as 0.
+/// `None` when no node has a size, so point placement applies.
+fn node_radii(extents: &[f32], node_count: usize) -> Option<Vec<f32>> {
+    let extent = |i: usize| {
+        let e = extents.get(i).copied().unwrap_or(0.0);
+        if e > 0.0 { e } else { 0.0 }
+    };
+    let radii: Vec<f32> = (0..node_count)
+        .map(|slot| 0.5 * extent(slot * 2).hypot(extent(slot * 2 + 1)))
+        .collect();
+    radii.iter().any(|&r| r > 0.0).then_some(radii)
+}
 
-    // Find the maximum distance from origin across all placed nodes
-    let mut max_dist_sq: f32 = 0.0;
-    for i in 0..node_count {
-        let idx = i * 2;
-        if idx + 1 >= positions.len() {
+/// Pack a community's nodes as discs of their radius plus half the node
+/// spacing, largest (hubs) in the middle, so no two nodes overlap.
+fn pack_sized_members(
+    members: &[usize],
+    node_radii: &[f32],
+    config: &CommunityLayoutConfig,
+) -> SizedPack {
+    let radius_of = |slot: usize| node_radii.get(slot).copied().unwrap_or(0.0);
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    order.sort_by(|&a, &b| radius_of(members[b]).total_cmp(&radius_of(members[a])));
+    let radii: Vec<f32> = order
+        .iter()
+        .map(|&k| radius_of(members[k]) + config.node_spacing * 0.5)
+        .collect();
+
+    let mut discs = sunflower_discs(&radii);
+    for _ in 0..PACKING_PASSES {
+        if !separate_discs(&mut discs, &radii) {
             break;
         }
-        let x = positions[idx];
-        let y = positions[idx + 1];
-        if x >= SENTINEL * 0.5 || y >= SENTINEL * 0.5 {
-            continue; // Skip unplaced sentinel nodes
-        }
-        let d = x * x + y * y;
-        if d > max_dist_sq {
-            max_dist_sq = d;
-        }
     }
 
-    let max_dist = max_dist_sq.sqrt();
-    if max_dist < 1.0 {
-        return; // Layout already tiny or single-community, no normalization needed
+    let mut offsets = vec![(0.0, 0.0); members.len()];
+    let mut radius = 0.0f32;
+    for (&k, &(x, y)) in order.iter().zip(&discs) {
+        offsets[k] = (x, y);
+        radius = radius.max(x.hypot(y) + radius_of(members[k]));
     }
+    SizedPack { offsets, radius }
+}
 
-    // Target radius: proportional to sqrt(N), giving a visually balanced density
-    let target_radius = config.node_spacing * (node_count as f32).sqrt() * config.spread_factor;
-    let scale = target_radius / max_dist;
-
-    // Only normalize if layout is significantly larger than target (avoid shrinking compact layouts)
-    if scale >= 1.0 {
-        return;
+/// Place a sized pack at `center`, turned by `rotation`.
+fn place_sized_pack(
+    members: &[usize],
+    pack: &SizedPack,
+    center: (f32, f32),
+    rotation: f32,
+    positions: &mut [f32],
+) {
+    let (sin, cos) = rotation.sin_cos();
+    for (&node, &(x, y)) in members.iter().zip(&pack.offsets) {
+        positions[node * 2] = center.0 + x * cos - y * sin;
+        positions[node * 2 + 1] = center.1 + x * sin + y * cos;
     }
+}
 
-    for i in 0..node_count {
-        let idx = i * 2;
-        if idx + 1 >= positions.len() {
-            break;
-        }
-        if positions[idx] >= SENTINEL * 0.5 {
-            continue;
+/// Push overlapping discs apart once, moving each by the share of the
+/// overlap the other disc's area bears. Returns whether any overlapped.
+fn separate_discs(discs: &mut [(f32, f32)], radii: &[f32]) -> bool {
+    let max_radius = radii.iter().fold(0.0f32, |a, &r| a.max(r));
+    let mut by_x: Vec<usize> = (0..discs.len()).collect();
+    by_x.sort_by(|&a, &b| discs[a].0.total_cmp(&discs[b].0));
+
+    let mut overlapped = false;
+    for (k, &i) in by_x.iter().enumerate() {
+        for &j in &by_x[k + 1..] {
+            if discs[j].0 - discs[i].0 > radii[i] + max_radius {
+                break;
+            }
+            overlapped |= push_apart(discs, radii, i, j);
         }
-        positions[idx] *= scale;
-        positions[idx + 1] *= scale;
     }
+    overlapped
+}
+
+/// Push discs `i` and `j` apart if they overlap. Returns whether they did.
+fn push_apart(discs: &mut [(f32, f32)], radii: &[f32], i: usize, j: usize) -> bool {
+    let (dx, dy) = (discs[j].0 - discs[i].0, discs[j].1 - discs[i].1);
+    let distance = dx.hypot(dy);
+    let overlap = radii[i] + radii[j] - distance;
+    if overlap <= 1e-3 * (radii[i] + radii[j]) {
+        return false;
+    }
+    // Coincident discs separate along a direction fixed by their indices
+    let (ux, uy) = if distance > f32::EPSILON {
+        (dx / distance, dy / distance)
+    } else {
+        let angle = (i + j) as f32;
+        (angle.cos(), angle.sin())
+    };
+    let (wi, wj) = (radii[i] * radii[i], radii[j] * radii[j]);
+    let (share_i, share_j) = (wj / (wi + wj), wi / (wi + wj));
+    discs[i] = (discs[i].0 - ux * overlap * share_i, discs[i].1 - uy * overlap * share_i);
+    discs[j] = (discs[j].0 + ux * overlap * share_j, discs[j].1 + uy * overlap * share_j);
+    true
+}
+
+/// Normalize all non-sentinel positions so the layout fits within a target radius.
+///
+/// Target radius = `node_spacing * sqrt(node_count) * spread_factor`.
+/// This ensures the layout scales predictably regardless of the number of communities.
+/// Compact layouts already within the target are not enlarged.
+fn normalize_positions(positions: &mut [f32], node_count: usize, config: &CommunityLayoutConfig) {
+    if node_count == 0 {
+        return;
+    }
+
+    // Target radius: proportional to sqrt(N), giving a visually balanced density
+    let target_radius = config.node_spacing * (node_count as f32).sqrt() * config.spread_factor;
+    fit::shrink_to_radius(positions, target_radius);
 }
 
 /// Compute the inner radius needed to fit `n` nodes with given spacing.
@@ -661,12 +2521,14 @@ fn community_inner_radius(n: usize, node_spacing: f32) -> f32 {
 /// Place nodes within a community using a sunflower spiral.
 ///
 /// The sunflower spiral (Fermat's spiral with golden angle) provides
-/// approximately uniform density distribution within a circle.
+/// approximately uniform density distribution within a circle. `rotation`
+/// offsets the starting angle of the spiral.
 fn place_nodes_in_community(
     members: &[usize],
     cx: f32,
     cy: f32,
     radius: f32,
+    rotation: f32,
     config: &CommunityLayoutConfig,
     positions: &mut [f32],
 ) {
@@ -699,7 +2561,7 @@ fn place_nodes_in_community(
 
         let t = (i as f32 + 0.5) / n as f32; // 0..1, offset by 0.5 for better distribution
         let r = scaled_radius * t.sqrt();
-        let theta = i as f32 * golden_angle;
+        let theta = rotation + i as f32 * golden_angle;
 
         positions[idx] = cx + r * theta.cos();
         positions[idx + 1] = cy + r * theta.sin();
@@ -854,12 +2716,11 @@ mod tests {
 
         assert_eq!(positions.len(), 12); // 6 nodes * 2 coords
 
-        let sentinel = 3.402_823e+38_f32;
         for i in 0..6 {
             let x = positions[i * 2];
             let y = positions[i * 2 + 1];
-            assert!(x < sentinel, "Node {i} should have valid x position");
-            assert!(y < sentinel, "Node {i} should have valid y position");
+            assert!(x < SENTINEL, "Node {i} should have valid x position");
+            assert!(y < SENTINEL, "Node {i} should have valid y position");
             assert!(x.is_finite(), "Node {i} x should be finite");
             assert!(y.is_finite(), "Node {i} y should be finite");
         }
@@ -891,6 +2752,141 @@ mod tests {
         assert!(dist > 10.0, "Community centroids should be well-separated, got distance {dist}");
     }
 
+    #[test]
+    fn test_community_layout_is_seeded() {
+        let assignments = vec![0, 0, 0, 0, 1, 1, 1, 2, 2];
+        let rotated = |seed| CommunityLayoutConfig {
+            spiral_rotation: std::f32::consts::TAU,
+            seed,
+            ..Default::default()
+        };
+
+        let a = compute_community_layout(&assignments, 3, 9, &rotated(11));
+        let b = compute_community_layout(&assignments, 3, 9, &rotated(11));
+        assert_eq!(a, b, "Same seed should produce identical positions");
+
+        let c = compute_community_layout(&assignments, 3, 9, &rotated(12));
+        assert_ne!(a, c, "Different seeds should rotate the spirals differently");
+
+        // Without rotation the seed has no effect
+        let plain = |seed| CommunityLayoutConfig { seed, ..Default::default() };
+        assert_eq!(
+            compute_community_layout(&assignments, 3, 9, &plain(11)),
+            compute_community_layout(&assignments, 3, 9, &plain(12)),
+        );
+    }
+
+    #[test]
+    fn test_community_layout_keeps_current_centers() {
+        // Communities 0 and 1 sit around (100, 0) and (0, -50); community 2
+        // has no placed member yet
+        let assignments = vec![0, 0, 0, 1, 1, 2];
+        let current = vec![
+            90.0, 0.0, 110.0, 0.0, 100.0, 30.0, 0.0, -40.0, 0.0, -60.0, SENTINEL, SENTINEL,
+        ];
+        let config = CommunityLayoutConfig::default();
+        let positions = compute_community_layout_around(&assignments, 3, &current, &config);
+
+        let centroid = |members: &[usize]| {
+            let n = members.len() as f32;
+            (
+                members.iter().map(|&i| positions[i * 2]).sum::<f32>() / n,
+                members.iter().map(|&i| positions[i * 2 + 1]).sum::<f32>() / n,
+            )
+        };
+        let (x0, y0) = centroid(&[0, 1, 2]);
+        assert!((x0 - 100.0).abs() < 15.0 && (y0 - 10.0).abs() < 15.0, "{x0} {y0}");
+        let (x1, y1) = centroid(&[3, 4]);
+        assert!(x1.abs() < 15.0 && (y1 + 50.0).abs() < 15.0, "{x1} {y1}");
+        assert!(positions[10] < SENTINEL && positions[11] < SENTINEL);
+    }
+
+    /// Bounding radius of the placed positions and whether any two nodes of
+    /// different communities are closer than `min_gap`.
+    fn spread_and_mixing(positions: &[f32], assignments: &[u32], min_gap: f32) -> (f32, bool) {
+        let n = assignments.len();
+        let radius = (0..n)
+            .map(|i| positions[i * 2].hypot(positions[i * 2 + 1]))
+            .fold(0.0f32, f32::max);
+        let mixed = (0..n).any(|i| {
+            (i + 1..n).any(|j| {
+                let d = (positions[i * 2] - positions[j * 2])
+                    .hypot(positions[i * 2 + 1] - positions[j * 2 + 1]);
+                assignments[i] != assignments[j] && d < min_gap
+            })
+        });
+        (radius, mixed)
+    }
+
+    #[test]
+    fn test_grid_and_packed_arrangements() {
+        // One community with 90% of the nodes and ten small ones
+        let assignments: Vec<u32> = (0..200u32)
+            .map(|i| if i < 180 { 0 } else { 1 + (i - 180) / 2 })
+            .collect();
+        let layout = |arrangement| {
+            let config = CommunityLayoutConfig {
+                arrangement,
+                ..Default::default()
+            };
+            compute_community_layout(&assignments, 11, 200, &config)
+        };
+
+        let (grid_radius, grid_mixed) =
+            spread_and_mixing(&layout(CommunityArrangement::Grid), &assignments, 20.0);
+        let (packed_radius, packed_mixed) =
+            spread_and_mixing(&layout(CommunityArrangement::Packed), &assignments, 20.0);
+        assert!(!grid_mixed && !packed_mixed);
+
+        // The circle spans the big community twice over and only fits the
+        // target radius by squeezing communities into each other
+        let config = CommunityLayoutConfig::default();
+        let circle = compute_community_layout(&assignments, 11, 200, &config);
+        let (circle_radius, circle_mixed) = spread_and_mixing(&circle, &assignments, 20.0);
+        assert!(circle_mixed);
+        assert!(packed_radius < grid_radius, "{packed_radius} {grid_radius}");
+        assert!(packed_radius < circle_radius, "{packed_radius} {circle_radius}");
+    }
+
+    #[test]
+    fn test_sized_nodes_do_not_overlap() {
+        // Two communities of 30 with one big hub each
+        let assignments: Vec<u32> = (0..60).map(|i| i / 30).collect();
+        let extents: Vec<f32> = (0..60)
+            .flat_map(|i| if i % 30 == 0 { [80.0, 60.0] } else { [12.0, 12.0] })
+            .collect();
+        for arrangement in [CommunityArrangement::Circle, CommunityArrangement::Packed] {
+            let config = CommunityLayoutConfig {
+                arrangement,
+                extents: extents.clone(),
+                ..Default::default()
+            };
+            let positions = compute_community_layout(&assignments, 2, 60, &config);
+
+            // Packed keeps whole communities apart; circle only members
+            let radius = |i: usize| 0.5 * extents[i * 2].hypot(extents[i * 2 + 1]);
+            let pairs = (0..60).flat_map(|i| (i + 1..60).map(move |j| (i, j)));
+            let checked = if arrangement == CommunityArrangement::Packed { 60 } else { 30 };
+            for (i, j) in pairs.filter(|&(i, j)| j < checked || i / 30 == j / 30) {
+                let d = (positions[i * 2] - positions[j * 2])
+                    .hypot(positions[i * 2 + 1] - positions[j * 2 + 1]);
+                assert!(d >= radius(i) + radius(j), "{i} and {j} overlap");
+            }
+        }
+    }
+
+    #[test]
+    fn test_community_bounds() {
+        // Community 0: two points 20 apart, one 10 wide; community 1 empty;
+        // community 2: a single point
+        let assignments = vec![0, 0, 2, 0];
+        let positions = vec![0.0, 0.0, 20.0, 0.0, 50.0, 50.0, SENTINEL, SENTINEL];
+        let extents = vec![0.0, 0.0, 10.0, 0.0];
+        let bounds = community_bounds(&assignments, 3, &positions, &extents);
+
+        assert_eq!(bounds, vec![10.0, 0.0, 15.0, SENTINEL, SENTINEL, 0.0, 50.0, 50.0, 0.0]);
+    }
+
     #[test]
     fn test_layout_single_community() {
         let assignments = vec![0, 0, 0, 0];
@@ -907,6 +2903,337 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detection_is_reproducible() {
+        // A ring is full of equal-gain ties; every run must break them alike
+        let edges: Vec<(u32, u32)> = (0..24).map(|i| (i, (i + 1) % 24)).collect();
+        let csr = build_csr(24, &edges);
+        let first = detect_communities(&csr, 24, 1.0, 100, 0.0001);
+        for _ in 0..10 {
+            let again = detect_communities(&csr, 24, 1.0, 100, 0.0001);
+            assert_eq!(again.assignments, first.assignments);
+            assert_eq!(again.modularity.to_bits(), first.modularity.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_hierarchy_levels_and_merge_tree() {
+        // Four triangles, paired by double bridges, the pairs joined once;
+        // a low resolution lets the pairs merge on a second level
+        let mut edges = Vec::new();
+        for t in 0..4u32 {
+            let b = t * 3;
+            edges.extend([(b, b + 1), (b + 1, b + 2), (b + 2, b)]);
+        }
+        edges.extend([(2, 3), (0, 4), (8, 9), (6, 10), (5, 6)]);
+        let csr = build_csr(12, &edges);
+        let hierarchy = detect_community_hierarchy(&csr, 12, 0.5, 100, 0.0001);
+
+        assert!(hierarchy.levels.len() >= 2);
+        let best = hierarchy.best_level.expect("best level") as usize;
+        let result = detect_communities(&csr, 12, 0.5, 100, 0.0001);
+        assert_eq!(hierarchy.levels[best].assignments, result.assignments);
+        assert_eq!(hierarchy.levels[best].modularity, result.modularity);
+
+        // Parents carry each node from one level to the next
+        for pair in hierarchy.levels.windows(2) {
+            let (fine, coarse) = (&pair[0], &pair[1]);
+            assert_eq!(fine.parents.len(), fine.community_count as usize);
+            assert!(coarse.community_count < fine.community_count);
+            for node in 0..12 {
+                let parent = fine.parents[fine.assignments[node] as usize];
+                assert_eq!(parent, coarse.assignments[node]);
+            }
+        }
+        assert!(hierarchy.levels.last().unwrap().parents.is_empty());
+
+        let empty = detect_community_hierarchy(&[], 0, 1.0, 100, 0.0001);
+        assert!(empty.levels.is_empty() && empty.best_level.is_none());
+    }
+
+    #[test]
+    fn test_consensus_on_clear_communities() {
+        // Four 6-cliques joined in a ring by single edges
+        let mut edges = Vec::new();
+        for c in 0..4u32 {
+            let b = c * 6;
+            edges.extend((0..6).flat_map(|i| (i + 1..6).map(move |j| (b + i, b + j))));
+            edges.push((b, (b + 6) % 24));
+        }
+        let csr = build_csr(24, &edges);
+        let config = ConsensusConfig::default();
+        let result = detect_communities_consensus(&csr, 24, 8, 3, &config);
+
+        assert_eq!(result.community_count, 4);
+        let expected: Vec<u32> = (0..24).map(|i| i / 6).collect();
+        assert_eq!(result.assignments, expected);
+        assert!(result.stability.iter().all(|&s| s == 1.0), "{:?}", result.stability);
+
+        let leiden = ConsensusConfig {
+            detection: DetectionConfig {
+                algorithm: CommunityAlgorithm::Leiden,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = detect_communities_consensus(&csr, 24, 8, 3, &leiden);
+        assert_eq!(result.assignments, expected);
+    }
+
+    #[test]
+    fn test_consensus_stability_on_ambiguous_graph() {
+        // A ring has no preferred cut, so runs disagree
+        let edges: Vec<(u32, u32)> = (0..30).map(|i| (i, (i + 1) % 30)).collect();
+        let csr = build_csr(31, &edges);
+        let config = ConsensusConfig::default();
+        let result = detect_communities_consensus(&csr, 31, 10, 9, &config);
+
+        assert_eq!(result.stability.len(), 31);
+        assert!(result.stability[..30].iter().any(|&s| s < 1.0), "{:?}", result.stability);
+        // Node 30 has no edges
+        assert_eq!(result.stability[30], 1.0);
+
+        let again = detect_communities_consensus(&csr, 31, 10, 9, &config);
+        assert_eq!(again, result);
+    }
+
+    #[test]
+    fn test_detection_in_subset() {
+        // Two triangles joined through node 6, which the subset leaves out
+        let edges = [
+            (0, 1), (1, 2), (2, 0),
+            (3, 4), (4, 5), (5, 3),
+            (2, 6), (6, 3),
+        ];
+        let csr = build_csr(7, &edges);
+        let result = detect_communities_in_subset(
+            &csr,
+            7,
+            &[5, 4, 3, 2, 1, 0, 0, 42],
+            &DetectionConfig::default(),
+        );
+
+        assert_eq!(result.community_count, 2);
+        assert_eq!(result.assignments[6], u32::MAX);
+        assert_eq!(result.assignments[0], result.assignments[2]);
+        assert_eq!(result.assignments[3], result.assignments[5]);
+        assert_ne!(result.assignments[0], result.assignments[3]);
+        assert!(result.modularity > 0.4, "{}", result.modularity);
+    }
+
+    #[test]
+    fn test_update_communities_locally() {
+        // Two triangles as before; node 6 is new and joins the second one
+        let before = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        let mut edges = before.to_vec();
+        edges.extend([(6, 3), (6, 4), (6, 5)]);
+        let csr = csr_from_edges(7, &edges);
+        let previous = [4, 4, 4, 9, 9, 9];
+
+        let update = update_communities(&csr, 7, &previous, &[6, 3, 4, 5], 1.0);
+        assert_eq!(update.assignments, vec![4, 4, 4, 9, 9, 9, 9]);
+        assert_eq!(update.changed, vec![6]);
+        assert_eq!(update.community_count, 2);
+
+        // The new node lands next to its neighbors; the rest stay
+        let placed = [0.0, 0.0, 10.0, 0.0, 5.0, 8.0, 100.0, 0.0, 110.0, 0.0, 105.0, 8.0];
+        let mut positions = placed.to_vec();
+        positions.extend([SENTINEL, SENTINEL]);
+        let config = CommunityLayoutConfig::default();
+        place_updated_nodes(&csr, &update.assignments, &update.changed, &mut positions, &config);
+        let (x, y) = (positions[12], positions[13]);
+        assert!((x - 105.0).hypot(y - 8.0 / 3.0) <= config.node_spacing);
+        assert_eq!(positions[..12], placed);
+    }
+
+    #[test]
+    fn test_match_communities_keeps_ids() {
+        // Same groups under new numbering, node 4 moved, node 6 is new and
+        // alone in a new community
+        let previous = [7, 7, 3, 3, 3, 0];
+        let current = [1, 1, 0, 0, 1, 2, 5];
+        let stable = match_communities(&previous, &current);
+
+        assert_eq!(stable.assignments, vec![7, 7, 3, 3, 7, 0, 8]);
+        assert_eq!(stable.community_count, 4);
+        assert_eq!(stable.changed, vec![4, 6]);
+
+        // Without a previous detection, IDs are numbered from 0 as they come
+        let fresh = match_communities(&[], &[4, 4, 2]);
+        assert_eq!(fresh.assignments, vec![0, 0, 1]);
+        assert_eq!(fresh.changed, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_sweep_resolution() {
+        // Four triangles in a ring: resolution decides how many merge
+        let mut edges = Vec::new();
+        for t in 0..4u32 {
+            let b = t * 3;
+            edges.extend([(b, b + 1), (b + 1, b + 2), (b + 2, b), (b, (b + 3) % 12)]);
+        }
+        let csr = build_csr(12, &edges);
+        let samples = sweep_resolution(&csr, 12, 0.1, 3.0, 5, &DetectionConfig::default());
+
+        assert_eq!(samples.len(), 5);
+        assert_eq!(samples[0].resolution, 0.1);
+        assert_eq!(samples[4].resolution, 3.0);
+        assert!(samples[0].community_count < samples[4].community_count);
+        // Standard modularity peaks at the triangles, not at the ends
+        let best = samples
+            .iter()
+            .max_by(|a, b| a.modularity.total_cmp(&b.modularity))
+            .unwrap();
+        assert_eq!(best.community_count, 4, "{samples:?}");
+
+        assert!(sweep_resolution(&csr, 12, 0.5, 1.0, 0, &DetectionConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_community_graph_aggregates_edges() {
+        let edges = [
+            (0, 1, 1.0),
+            (1, 2, 2.0),
+            (2, 1, 0.5),
+            (3, 0, 4.0),
+            (2, 4, 9.0), // slot 4 is left out
+        ];
+        let assignments = [0, 0, 2, 2, u32::MAX];
+        let positions = [0.0, 0.0, 10.0, 4.0, 5.0, 5.0, SENTINEL, SENTINEL, 1.0, 1.0];
+        let meta = community_graph(&edges, &assignments, &positions);
+
+        assert_eq!(meta.sizes, vec![2, 0, 2]);
+        assert_eq!(meta.internal_weight, vec![1.0, 0.0, 0.0]);
+        assert_eq!(meta.edges, vec![(0, 2, 6.5)]);
+        // Community 2 has a single placed member; community 1 is empty
+        assert_eq!(meta.centroids, vec![5.0, 2.0, 0.0, 0.0, 5.0, 5.0]);
+
+        let aggregated = aggregate_community_edges(&edges, &assignments);
+        assert_eq!(
+            aggregated,
+            vec![CommunityEdge {
+                source: 0,
+                target: 2,
+                weight: 6.5,
+                count: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_community_colors() {
+        // Communities 0-1-2 in a triangle, 3 hangs off 0; slot 8 left out
+        let assignments = [0, 0, 1, 1, 2, 2, 3, 3, u32::MAX];
+        let edges = [
+            (0, 2, 1.0),
+            (3, 4, 1.0),
+            (5, 1, 1.0),
+            (0, 6, 5.0),
+            (6, 7, 1.0),
+            (7, 8, 1.0),
+        ];
+        let colors = assign_community_colors(&edges, &assignments, 3);
+        let of = |c: usize| colors[c * 2];
+        assert!(of(0) != of(1) && of(1) != of(2) && of(0) != of(2) && of(3) != of(0));
+        assert!(colors.iter().take(8).all(|&color| color < 3));
+        assert_eq!(colors[1], of(0));
+        assert_eq!(colors[8], u32::MAX);
+
+        // Two colors cannot color the triangle, but community 3 still
+        // differs from its only neighbor
+        let colors = assign_community_colors(&edges, &assignments, 2);
+        assert!(colors.iter().take(8).all(|&color| color < 2));
+        assert_ne!(colors[0], colors[6]);
+    }
+
+    #[test]
+    fn test_girvan_newman_dendrogram() {
+        // Two triangles joined by the bridge 2-3, plus isolated node 6
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+        let csr = csr_from_edges(7, &edges);
+        let hierarchy = detect_communities_girvan_newman(&csr, 7).unwrap();
+
+        let levels = &hierarchy.levels;
+        assert_eq!(levels[0].community_count, 7);
+        assert_eq!(levels.last().unwrap().assignments, vec![0, 0, 0, 0, 0, 0, 1]);
+        // The bridge goes first, leaving the triangles as the best split
+        let best = &levels[hierarchy.best_level.unwrap() as usize];
+        assert_eq!(best.assignments, vec![0, 0, 0, 1, 1, 1, 2]);
+        for pair in levels.windows(2) {
+            assert_eq!(pair[1].community_count, pair[0].community_count - 1);
+            let fine = &pair[0].assignments;
+            assert!((0..7).all(|n| pair[0].parents[fine[n] as usize] == pair[1].assignments[n]));
+        }
+    }
+
+    /// Whether every community induces a connected subgraph.
+    fn communities_connected(csr: &[u32], node_count: usize, assignments: &[u32]) -> bool {
+        let adj = AdjacencyList::from_csr(csr, node_count);
+        let community: Vec<usize> = assignments.iter().map(|&c| c as usize).collect();
+        let components = split_disconnected(&adj, &community);
+        let count = |labels: &[u32]| labels.iter().max().map_or(0, |&c| c + 1);
+        count(&components) == count(assignments)
+    }
+
+    #[test]
+    fn test_leiden_finds_cliques() {
+        let edges = [
+            (0, 1), (1, 0), (0, 2), (2, 0), (1, 2), (2, 1),
+            (3, 4), (4, 3), (3, 5), (5, 3), (4, 5), (5, 4),
+            (2, 3), // bridge
+        ];
+        let csr = build_csr(6, &edges);
+        let result = detect_communities_leiden(&csr, 6, 1.0, 100, 0.0001);
+
+        assert_eq!(result.community_count, 2);
+        assert_eq!(result.assignments, vec![0, 0, 0, 1, 1, 1]);
+        assert!(result.modularity > 0.3, "{}", result.modularity);
+    }
+
+    #[test]
+    fn test_refinement_splits_disconnected_community() {
+        // Two separate edges put in one community come out as two
+        let csr = build_csr(4, &[(0, 1), (2, 3)]);
+        let adj = AdjacencyList::from_csr(&csr, 4);
+        let (refined, count) = compact_communities(&refine_partition(&adj, &[0; 4], 1.0));
+        assert_eq!(count, 2);
+        assert_eq!(refined, vec![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_leiden_communities_are_connected() {
+        // Sparse pseudo-random graph with planted groups of 20
+        let n = 400u32;
+        let mut rng = SeededRng::new(7);
+        let mut target = |i: u32| {
+            if rng.next_f32() < 0.8 {
+                (i / 20) * 20 + (rng.next_f32() * 20.0) as u32 % 20
+            } else {
+                (rng.next_f32() * n as f32) as u32 % n
+            }
+        };
+        let edges: Vec<(u32, u32)> = (0..n * 3)
+            .map(|k| (k / 3, target(k / 3)))
+            .filter(|&(i, j)| i != j)
+            .collect();
+        let csr = build_csr(n as usize, &edges);
+
+        let leiden = detect_communities_leiden(&csr, n as usize, 1.0, 100, 0.0001);
+        assert!(communities_connected(&csr, n as usize, &leiden.assignments));
+        assert!(leiden.community_count > 1);
+
+        let louvain = detect_communities(&csr, n as usize, 1.0, 100, 0.0001);
+        assert!(
+            leiden.modularity >= louvain.modularity - 0.01,
+            "leiden {} louvain {}",
+            leiden.modularity,
+            louvain.modularity,
+        );
+
+        let again = detect_communities_leiden(&csr, n as usize, 1.0, 100, 0.0001);
+        assert_eq!(again.assignments, leiden.assignments);
+    }
+
     #[test]
     fn test_large_graph_performance() {
         // 10000 nodes, 5 clear communities connected in a ring
@@ -950,9 +3277,8 @@ mod tests {
         let positions = compute_community_layout(&result.assignments, result.community_count, n, &config);
         assert_eq!(positions.len(), n * 2);
 
-        let sentinel = 3.402_823e+38_f32;
         let valid_count = (0..n)
-            .filter(|&i| positions[i * 2] < sentinel && positions[i * 2 + 1] < sentinel)
+            .filter(|&i| positions[i * 2] < SENTINEL && positions[i * 2 + 1] < SENTINEL)
             .count();
         assert_eq!(valid_count, n, "All nodes should have valid positions");
     }
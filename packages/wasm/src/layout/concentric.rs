@@ -0,0 +1,175 @@
+//! Concentric layout.
+//!
+//! Places nodes on concentric rings bucketed by a per-node metric (degree,
+//! tree depth, centrality, ...), with the highest values in the middle and
+//! even angular spacing within each ring.
+//!
+//! Works on the slot space directly: removed slots get the sentinel, while
+//! nodes without edges are still placed.
+
+use std::f32::consts::TAU;
+
+use super::SENTINEL;
+
+/// Configuration for the concentric layout.
+pub struct ConcentricLayoutConfig {
+    /// Number of rings. Metric values are split into this many equal-width
+    /// bins; 0 gives every distinct value its own ring (default: 0).
+    pub levels: u32,
+    /// Minimum distance between consecutive rings (default: 60.0).
+    pub ring_spacing: f32,
+    /// Minimum arc length between neighbors on a ring; crowded rings grow
+    /// outward to keep it (default: 30.0).
+    pub node_spacing: f32,
+    /// Angle of the first node of each ring in radians (default: -π/2, top).
+    pub start_angle: f32,
+}
+
+impl Default for ConcentricLayoutConfig {
+    fn default() -> Self {
+        Self {
+            levels: 0,
+            ring_spacing: 60.0,
+            node_spacing: 30.0,
+            start_angle: -std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+/// Group present slots into rings, innermost (highest metric) first.
+///
+/// Missing or non-finite metric values count as 0. Within a ring, nodes are
+/// ordered by metric (descending), then slot.
+pub fn concentric_rings(present: &[bool], metric: &[f32], levels: u32) -> Vec<Vec<usize>> {
+    let value = |slot: usize| {
+        metric
+            .get(slot)
+            .copied()
+            .filter(|v| v.is_finite())
+            .unwrap_or(0.0)
+    };
+    let mut slots: Vec<usize> = (0..present.len()).filter(|&i| present[i]).collect();
+    slots.sort_by(|&a, &b| value(b).total_cmp(&value(a)).then(a.cmp(&b)));
+    let Some((&first, &last)) = slots.first().zip(slots.last()) else {
+        return Vec::new();
+    };
+
+    let (max, min) = (value(first), value(last));
+    let width = (max - min) / levels.max(1) as f32;
+    let bucket = |slot: usize| -> u32 {
+        if levels == 0 || width <= 0.0 {
+            return 0;
+        }
+        (((max - value(slot)) / width) as u32).min(levels - 1)
+    };
+
+    let mut rings: Vec<Vec<usize>> = Vec::new();
+    let mut previous: Option<(u32, f32)> = None;
+    for slot in slots {
+        // One ring per distinct value, or per bin
+        let key = (bucket(slot), if levels == 0 { value(slot) } else { 0.0 });
+        if previous != Some(key) {
+            rings.push(Vec::new());
+            previous = Some(key);
+        }
+        if let Some(ring) = rings.last_mut() {
+            ring.push(slot);
+        }
+    }
+    rings
+}
+
+/// Compute a concentric layout.
+///
+/// # Arguments
+///
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+/// * `metric` - Value per slot; higher values go closer to the center
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...],
+/// centered on the origin. Empty slots get sentinel values (f32::MAX).
+pub fn compute_concentric_layout(
+    present: &[bool],
+    metric: &[f32],
+    config: &ConcentricLayoutConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; present.len() * 2];
+    let mut radius: Option<f32> = None;
+
+    for ring in concentric_rings(present, metric, config.levels) {
+        let n = ring.len();
+        let crowded = n as f32 * config.node_spacing / TAU;
+        let r = match radius {
+            // A lone center node sits at the origin
+            None if n == 1 => 0.0,
+            None => crowded,
+            Some(inner) => (inner + config.ring_spacing).max(crowded),
+        };
+        radius = Some(r);
+
+        for (index, slot) in ring.into_iter().enumerate() {
+            let angle = config.start_angle + TAU * index as f32 / n as f32;
+            positions[slot * 2] = r * angle.cos();
+            positions[slot * 2 + 1] = r * angle.sin();
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radius(positions: &[f32], slot: usize) -> f32 {
+        positions[slot * 2].hypot(positions[slot * 2 + 1])
+    }
+
+    #[test]
+    fn test_one_ring_per_distinct_value() {
+        let present = [true, true, true, true, false, true];
+        let metric = [3.0, 1.0, 3.0, 2.0, 9.0, 1.0];
+        let rings = concentric_rings(&present, &metric, 0);
+        assert_eq!(rings, vec![vec![0, 2], vec![3], vec![1, 5]]);
+    }
+
+    #[test]
+    fn test_binned_levels() {
+        let present = [true; 5];
+        let metric = [0.0, 10.0, 4.0, 6.0, f32::NAN];
+        let rings = concentric_rings(&present, &metric, 2);
+        // Bins of width 5 from the top: [10, 5) and [5, 0]; NaN counts as 0
+        assert_eq!(rings, vec![vec![1, 3], vec![2, 0, 4]]);
+    }
+
+    #[test]
+    fn test_rings_grow_outward() {
+        let present = [true; 8];
+        let metric = [5.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let config = ConcentricLayoutConfig::default();
+        let positions = compute_concentric_layout(&present, &metric, &config);
+
+        assert_eq!(radius(&positions, 0), 0.0);
+        for slot in 1..8 {
+            assert!((radius(&positions, slot) - 60.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_crowded_ring_expands_and_holes_are_sentinel() {
+        let mut present = vec![true; 41];
+        present[40] = false;
+        let mut metric = vec![1.0; 41];
+        metric[0] = 2.0;
+        let config = ConcentricLayoutConfig::default();
+        let positions = compute_concentric_layout(&present, &metric, &config);
+
+        // 39 nodes at 30 units apart need a larger radius than 60
+        let expected = 39.0 * 30.0 / TAU;
+        assert!((radius(&positions, 1) - expected).abs() < 1e-3);
+        assert_eq!(positions[80], SENTINEL);
+    }
+}
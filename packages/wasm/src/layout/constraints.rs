@@ -0,0 +1,327 @@
+//! Layout constraints.
+//!
+//! Projects positions onto user constraints, for hand-curating diagrams on
+//! top of an automatic layout:
+//!
+//! - **Align**: nodes share one x (or y) coordinate.
+//! - **Order**: nodes follow each other left to right (or top to bottom),
+//!   at least `gap` apart.
+//! - **Separation**: `right` lies at least (or, if `exact`, exactly) `gap`
+//!   beyond `left` along an axis.
+//!
+//! Constraints come from JS as descriptors tagged by `type`, like the layout
+//! pipeline stages:
+//!
+//! ```text
+//! [
+//!   { type: "align", axis: "y", nodes: [1, 2, 3] },
+//!   { type: "order", axis: "x", nodes: [4, 5, 6], gap: 40 },
+//!   { type: "separation", axis: "y", left: 1, right: 7, gap: 100, exact: true },
+//! ]
+//! ```
+//!
+//! # Algorithm
+//!
+//! Gauss–Seidel projection: every sweep satisfies each constraint in turn
+//! with the smallest move, split evenly between the movable nodes it
+//! involves (fixed nodes stay and the others take the whole move). Sweeps
+//! repeat until no constraint is violated by more than `tolerance`, so
+//! consistent constraints converge and conflicting ones settle on a
+//! compromise. Constraints only move nodes along their own axis.
+
+use serde::Deserialize;
+
+/// Coordinate axis of a constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// One constraint on node slots.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Constraint {
+    /// All `nodes` share one coordinate on `axis`.
+    Align { axis: Axis, nodes: Vec<u32> },
+    /// `nodes` are in increasing order on `axis`, consecutive ones at least
+    /// `gap` apart (default: 0).
+    Order {
+        axis: Axis,
+        nodes: Vec<u32>,
+        gap: Option<f32>,
+    },
+    /// `right` is at least `gap` beyond `left` on `axis`; exactly `gap` if
+    /// `exact`.
+    Separation {
+        axis: Axis,
+        left: u32,
+        right: u32,
+        gap: f32,
+        exact: Option<bool>,
+    },
+}
+
+/// Configuration for constraint projection.
+pub struct ConstraintConfig {
+    /// Maximum projection sweeps (default: 50).
+    pub iterations: u32,
+    /// Largest violation accepted as satisfied (default: 0.01).
+    pub tolerance: f32,
+}
+
+impl Default for ConstraintConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 50,
+            tolerance: 0.01,
+        }
+    }
+}
+
+/// Which slots constraints may see and move.
+pub struct ConstraintNodes<'a> {
+    /// Whether each slot holds a node; constraint terms on other slots are
+    /// ignored.
+    pub present: &'a [bool],
+    /// Whether each slot must stay where it is (pinned nodes).
+    pub fixed: &'a [bool],
+}
+
+impl ConstraintNodes<'_> {
+    fn present(&self, slot: usize) -> bool {
+        self.present.get(slot).copied().unwrap_or(false)
+    }
+
+    fn movable(&self, slot: usize) -> bool {
+        self.present(slot) && !self.fixed.get(slot).copied().unwrap_or(false)
+    }
+}
+
+/// Move positions to satisfy `constraints`.
+///
+/// # Arguments
+///
+/// * `xs`, `ys` - Node coordinates by slot, updated in place
+/// * `nodes` - Present and fixed slots
+/// * `constraints` - Constraints to satisfy
+/// * `config` - Projection configuration
+///
+/// # Returns
+///
+/// The largest remaining violation (0 when every constraint holds).
+pub fn project_constraints(
+    xs: &mut [f32],
+    ys: &mut [f32],
+    nodes: &ConstraintNodes,
+    constraints: &[Constraint],
+    config: &ConstraintConfig,
+) -> f32 {
+    let mut violation = 0.0;
+    for _ in 0..config.iterations.max(1) {
+        violation = constraints
+            .iter()
+            .map(|constraint| {
+                let axis = match constraint {
+                    Constraint::Align { axis, .. }
+                    | Constraint::Order { axis, .. }
+                    | Constraint::Separation { axis, .. } => *axis,
+                };
+                let coords = match axis {
+                    Axis::X => &mut *xs,
+                    Axis::Y => &mut *ys,
+                };
+                project(coords, nodes, constraint)
+            })
+            .fold(0.0, f32::max);
+        if violation <= config.tolerance {
+            break;
+        }
+    }
+    violation
+}
+
+/// Project one constraint on its axis coordinates. Returns the violation
+/// found before the move.
+fn project(coords: &mut [f32], nodes: &ConstraintNodes, constraint: &Constraint) -> f32 {
+    match constraint {
+        Constraint::Align { nodes: ids, .. } => align(coords, nodes, ids),
+        Constraint::Order {
+            nodes: ids, gap, ..
+        } => {
+            let slots: Vec<usize> = ids
+                .iter()
+                .map(|&id| id as usize)
+                .filter(|&slot| nodes.present(slot) && slot < coords.len())
+                .collect();
+            slots
+                .windows(2)
+                .map(|pair| separate(coords, nodes, pair[0], pair[1], gap.unwrap_or(0.0), false))
+                .fold(0.0, f32::max)
+        }
+        Constraint::Separation {
+            left,
+            right,
+            gap,
+            exact,
+            ..
+        } => {
+            let (left, right) = (*left as usize, *right as usize);
+            let valid = |slot: usize| nodes.present(slot) && slot < coords.len();
+            if !valid(left) || !valid(right) || left == right {
+                return 0.0;
+            }
+            separate(coords, nodes, left, right, *gap, exact.unwrap_or(false))
+        }
+    }
+}
+
+/// Move the aligned nodes onto a common coordinate: the mean of the fixed
+/// ones if any, otherwise the mean of all.
+fn align(coords: &mut [f32], nodes: &ConstraintNodes, ids: &[u32]) -> f32 {
+    let slots: Vec<usize> = ids
+        .iter()
+        .map(|&id| id as usize)
+        .filter(|&slot| nodes.present(slot) && slot < coords.len())
+        .collect();
+    let fixed: Vec<f32> = slots
+        .iter()
+        .filter(|&&slot| !nodes.movable(slot))
+        .map(|&slot| coords[slot])
+        .collect();
+    let anchors = if fixed.is_empty() {
+        slots.iter().map(|&slot| coords[slot]).collect()
+    } else {
+        fixed
+    };
+    if anchors.is_empty() {
+        return 0.0;
+    }
+    let target = anchors.iter().sum::<f32>() / anchors.len() as f32;
+
+    let mut violation = 0.0f32;
+    for &slot in &slots {
+        violation = violation.max((coords[slot] - target).abs());
+        if nodes.movable(slot) {
+            coords[slot] = target;
+        }
+    }
+    violation
+}
+
+/// Enforce `coords[right] - coords[left] >= gap` (`== gap` if `exact`).
+fn separate(
+    coords: &mut [f32],
+    nodes: &ConstraintNodes,
+    left: usize,
+    right: usize,
+    gap: f32,
+    exact: bool,
+) -> f32 {
+    let shortfall = gap - (coords[right] - coords[left]);
+    if shortfall <= 0.0 && !exact {
+        return 0.0;
+    }
+    let (move_left, move_right) = (nodes.movable(left), nodes.movable(right));
+    let share = match (move_left, move_right) {
+        (true, true) => 0.5,
+        (false, false) => 0.0,
+        _ => 1.0,
+    };
+    if move_left {
+        coords[left] -= shortfall * share;
+    }
+    if move_right {
+        coords[right] += shortfall * share;
+    }
+    shortfall.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_all(
+        xs: &mut [f32],
+        ys: &mut [f32],
+        fixed: &[bool],
+        constraints: &[Constraint],
+    ) -> f32 {
+        let present = vec![true; xs.len()];
+        let nodes = ConstraintNodes {
+            present: &present,
+            fixed,
+        };
+        project_constraints(xs, ys, &nodes, constraints, &Default::default())
+    }
+
+    #[test]
+    fn test_align_follows_fixed_nodes() {
+        let (mut xs, mut ys) = (vec![0.0, 10.0, 20.0], vec![0.0, 30.0, 60.0]);
+        let constraints = [Constraint::Align {
+            axis: Axis::Y,
+            nodes: vec![0, 1, 2],
+        }];
+
+        project_all(&mut xs, &mut ys, &[false; 3], &constraints);
+        assert_eq!(ys, vec![30.0, 30.0, 30.0]);
+        assert_eq!(xs, vec![0.0, 10.0, 20.0]);
+
+        let mut ys = vec![0.0, 30.0, 60.0];
+        project_all(&mut xs, &mut ys, &[false, false, true], &constraints);
+        assert_eq!(ys, vec![60.0, 60.0, 60.0]);
+    }
+
+    #[test]
+    fn test_order_and_separation() {
+        // 2 must come after 1 after 0, 10 apart; 3 exactly 5 below 0
+        let (mut xs, mut ys) = (vec![30.0, 20.0, 0.0, 0.0], vec![0.0, 0.0, 0.0, 50.0]);
+        let constraints = [
+            Constraint::Order {
+                axis: Axis::X,
+                nodes: vec![0, 1, 2],
+                gap: Some(10.0),
+            },
+            Constraint::Separation {
+                axis: Axis::Y,
+                left: 0,
+                right: 3,
+                gap: 5.0,
+                exact: Some(true),
+            },
+        ];
+        let violation = project_all(&mut xs, &mut ys, &[false; 4], &constraints);
+
+        assert!(violation <= 0.01);
+        assert!(xs[1] - xs[0] >= 10.0 - 0.01 && xs[2] - xs[1] >= 10.0 - 0.01);
+        assert!((ys[3] - ys[0] - 5.0).abs() <= 0.01);
+    }
+
+    #[test]
+    fn test_missing_and_fixed_nodes() {
+        // Slot 9 does not exist; slot 0 is pinned, so slot 1 takes all moves
+        let constraints = [
+            Constraint::Align {
+                axis: Axis::X,
+                nodes: vec![0, 1, 9],
+            },
+            Constraint::Separation {
+                axis: Axis::Y,
+                left: 0,
+                right: 1,
+                gap: 4.0,
+                exact: None,
+            },
+        ];
+        let (mut xs, mut ys) = (vec![0.0, 8.0], vec![0.0, 0.0]);
+        project_all(&mut xs, &mut ys, &[true, false], &constraints);
+
+        assert_eq!(xs, vec![0.0, 0.0]);
+        assert_eq!(ys, vec![0.0, 4.0]);
+    }
+}
@@ -0,0 +1,231 @@
+//! Edge crossing counting.
+//!
+//! Counts pairs of edges that cross in a layout, the most common readability
+//! measure and a handy number to pin down in layout regression tests. Edges
+//! are treated as undirected straight segments; self loops, edges to nodes
+//! without a position and pairs sharing a node are ignored, and touching or
+//! overlapping collinear segments do not count as crossing.
+//!
+//! The count uses a sweep over x: segments enter in order of their left end
+//! and are only tested against the segments still open at that point (and
+//! overlapping in y), which skips most pairs in a typical layout. Up to
+//! `exact_edges` edges the count is exact. Larger graphs are estimated from
+//! a seeded random sample of `sample_edges` edges: a crossing pair survives
+//! the sample with probability `k(k-1) / (m(m-1))`, so the sampled count is
+//! scaled back up by its inverse.
+
+use super::placed_point;
+use super::distance::csr_pairs;
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+type Point = (f32, f32);
+
+/// Configuration for edge crossing counting.
+pub struct EdgeCrossingConfig {
+    /// Largest edge count counted exactly (default: 5000).
+    pub exact_edges: u32,
+    /// Edges sampled for the estimate on larger graphs (default: 5000).
+    pub sample_edges: u32,
+    /// Seed for sampling.
+    pub seed: u64,
+}
+
+impl Default for EdgeCrossingConfig {
+    fn default() -> Self {
+        Self {
+            exact_edges: 5000,
+            sample_edges: 5000,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Result of counting edge crossings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeCrossings {
+    /// Number of crossing edge pairs (rounded estimate when sampled).
+    pub count: f64,
+    /// Whether `count` was estimated from a sample of edges.
+    pub estimated: bool,
+}
+
+/// A positioned edge: endpoints and their node slots.
+struct Segment {
+    a: Point,
+    b: Point,
+    nodes: (usize, usize),
+}
+
+/// Count edge crossings.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for nodes without a position (length `node_count * 2`)
+/// * `config` - Counting configuration
+pub fn count_edge_crossings(
+    csr: &[u32],
+    positions: &[f32],
+    config: &EdgeCrossingConfig,
+) -> EdgeCrossings {
+    let node_count = positions.len() / 2;
+    let mut pairs: Vec<(usize, usize)> = csr_pairs(csr, node_count)
+        .filter(|&(u, v)| u != v)
+        .map(|(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    let mut segments: Vec<Segment> = pairs
+        .into_iter()
+        .filter_map(|(u, v)| {
+            // Left end first, so the sweep can key on `a.0`
+            let (a, b) = (placed_point(positions, u)?, placed_point(positions, v)?);
+            let (a, b) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+            Some(Segment {
+                a,
+                b,
+                nodes: (u, v),
+            })
+        })
+        .collect();
+
+    let m = segments.len();
+    let k = config.sample_edges.max(2) as usize;
+    if m <= (config.exact_edges as usize).max(k) {
+        return EdgeCrossings {
+            count: sweep(&mut segments) as f64,
+            estimated: false,
+        };
+    }
+
+    let mut rng = SeededRng::new(config.seed);
+    rng.shuffle(&mut segments);
+    segments.truncate(k);
+    let scale = (m as f64 * (m - 1) as f64) / (k as f64 * (k - 1) as f64);
+    EdgeCrossings {
+        count: (sweep(&mut segments) as f64 * scale).round(),
+        estimated: true,
+    }
+}
+
+/// Exact number of crossing pairs among `segments` (reordered).
+fn sweep(segments: &mut [Segment]) -> usize {
+    segments.sort_by(|s, t| s.a.0.total_cmp(&t.a.0));
+    let mut open: Vec<usize> = Vec::new();
+    let mut count = 0;
+    for i in 0..segments.len() {
+        let s = &segments[i];
+        // Segments ending left of this one can cross nothing further right
+        open.retain(|&j| segments[j].b.0 >= s.a.0);
+        count += open.iter().filter(|&&j| crosses(s, &segments[j])).count();
+        open.push(i);
+    }
+    count
+}
+
+fn crosses(s: &Segment, t: &Segment) -> bool {
+    let (u, v) = s.nodes;
+    if u == t.nodes.0 || u == t.nodes.1 || v == t.nodes.0 || v == t.nodes.1 {
+        return false;
+    }
+    let overlap_y = s.a.1.min(s.b.1) <= t.a.1.max(t.b.1) && t.a.1.min(t.b.1) <= s.a.1.max(s.b.1);
+    overlap_y && segments_cross(s.a, s.b, t.a, t.b)
+}
+
+/// Whether segments `ab` and `cd` properly intersect (cross at a single
+/// interior point).
+pub fn segments_cross(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let orient = |p: Point, q: Point, r: Point| {
+        let v = (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0);
+        if v > 0.0 {
+            1
+        } else if v < 0.0 {
+            -1
+        } else {
+            0
+        }
+    };
+    orient(a, b, c) * orient(a, b, d) < 0 && orient(c, d, a) * orient(c, d, b) < 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+    use crate::layout::distance::csr_from_edges;
+
+    /// Complete graph on `n` nodes placed on a circle, which has C(n, 4)
+    /// crossings.
+    fn circle_clique(n: u32) -> (Vec<u32>, Vec<f32>) {
+        let edges: Vec<(u32, u32)> = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect();
+        let positions = (0..n)
+            .flat_map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / n as f32;
+                [100.0 * angle.cos(), 100.0 * angle.sin()]
+            })
+            .collect();
+        (csr_from_edges(n as usize, &edges), positions)
+    }
+
+    #[test]
+    fn test_exact_count_on_circle_clique() {
+        let (csr, positions) = circle_clique(8);
+        let crossings = count_edge_crossings(&csr, &positions, &Default::default());
+        assert_eq!(crossings.count, 70.0);
+        assert!(!crossings.estimated);
+    }
+
+    #[test]
+    fn test_duplicates_shared_nodes_and_sentinels_ignored() {
+        // Bow tie 0-1 × 2-3 (one crossing), drawn twice and reversed, plus
+        // an edge to an unplaced node across it and a self loop
+        let csr = csr_from_edges(5, &[(0, 1), (1, 0), (2, 3), (0, 2), (1, 4), (3, 3)]);
+        let positions = [
+            0.0, 0.0, 10.0, 10.0, 10.0, 0.0, 0.0, 10.0, SENTINEL, SENTINEL,
+        ];
+        let crossings = count_edge_crossings(&csr, &positions, &Default::default());
+        assert_eq!(crossings.count, 1.0);
+    }
+
+    #[test]
+    fn test_sampled_estimate_is_close() {
+        // K40 on a circle: 780 edges, C(40, 4) = 91390 crossings
+        let (csr, positions) = circle_clique(40);
+        let config = EdgeCrossingConfig {
+            exact_edges: 0,
+            sample_edges: 400,
+            ..Default::default()
+        };
+        let crossings = count_edge_crossings(&csr, &positions, &config);
+        assert!(crossings.estimated);
+        assert!(
+            (crossings.count - 91390.0).abs() < 91390.0 * 0.15,
+            "{crossings:?}"
+        );
+    }
+
+    #[test]
+    fn test_touching_segments_do_not_cross() {
+        assert!(segments_cross(
+            (0.0, 0.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+            (2.0, 0.0)
+        ));
+        assert!(!segments_cross(
+            (0.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+            (2.0, 0.0)
+        ));
+        assert!(!segments_cross(
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (3.0, 0.0)
+        ));
+    }
+}
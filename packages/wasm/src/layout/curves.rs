@@ -0,0 +1,193 @@
+//! Curved edge geometry.
+//!
+//! Tessellates every edge into a quadratic Bézier line strip, ready to
+//! upload as a vertex buffer. The control point sits on the perpendicular
+//! bisector of the edge, `curvature × length` away from its midpoint.
+//!
+//! Parallel edges (several edges between the same two nodes, in either
+//! direction) fan out so they stay distinguishable: with `m` edges between a
+//! pair, edge `j` bends by `curvature × (2j - (m - 1))`, measured on the same
+//! side for both directions, so an odd count keeps a straight middle edge.
+//! A lone edge bends by `curvature` to the right of its direction (y down).
+//! Self loops become circles above their node, growing with each extra loop.
+
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use super::{SENTINEL, placed_point};
+use super::distance::csr_pairs;
+
+/// Configuration for edge curve tessellation.
+pub struct EdgeCurveConfig {
+    /// Control point offset as a fraction of the edge length (default: 0.2).
+    pub curvature: f32,
+    /// Line segments per edge; every edge gets `segments + 1` vertices
+    /// (default: 16).
+    pub segments: u32,
+    /// Radius of the first self loop on a node (default: 20.0).
+    pub loop_radius: f32,
+}
+
+impl Default for EdgeCurveConfig {
+    fn default() -> Self {
+        Self {
+            curvature: 0.2,
+            segments: 16,
+            loop_radius: 20.0,
+        }
+    }
+}
+
+/// Tessellate every edge into a curved line strip.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for removed slots (length `node_count * 2`)
+/// * `config` - Curve configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of `segments + 1` interleaved vertices [x0, y0, x1, y1, ...]
+/// per edge, in CSR target order, from source to target. Edges with an
+/// endpoint without a position get sentinel values (f32::MAX).
+pub fn compute_edge_curves(csr: &[u32], positions: &[f32], config: &EdgeCurveConfig) -> Vec<f32> {
+    let node_count = positions.len() / 2;
+    let pairs: Vec<(usize, usize)> = csr_pairs(csr, node_count).collect();
+    let segments = config.segments.max(1) as usize;
+    let stride = (segments + 1) * 2;
+    let mut vertices = vec![SENTINEL; pairs.len() * stride];
+
+    // Lane of every edge among the edges joining the same two nodes
+    let mut groups: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (edge, &(src, tgt)) in pairs.iter().enumerate() {
+        groups
+            .entry((src.min(tgt), src.max(tgt)))
+            .or_default()
+            .push(edge);
+    }
+    let mut lanes = vec![(0usize, 1usize); pairs.len()];
+    for group in groups.values() {
+        for (lane, &edge) in group.iter().enumerate() {
+            lanes[edge] = (lane, group.len());
+        }
+    }
+
+    for (edge, &(src, tgt)) in pairs.iter().enumerate() {
+        let (Some(a), Some(b)) = (placed_point(positions, src), placed_point(positions, tgt)) else {
+            continue;
+        };
+        let strip = &mut vertices[edge * stride..(edge + 1) * stride];
+        let (lane, count) = lanes[edge];
+        if src == tgt {
+            tessellate_loop(strip, a, config.loop_radius * (1.0 + lane as f32 * 0.5));
+            continue;
+        }
+
+        // Fanned bends are in the frame of the pair's lower slot, so both
+        // directions share one axis
+        let fan = config.curvature * (2.0 * lane as f32 - (count - 1) as f32);
+        let bend = match (count, src < tgt) {
+            (1, _) => config.curvature,
+            (_, true) => fan,
+            (_, false) => -fan,
+        };
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let control = ((a.0 + b.0) * 0.5 - dy * bend, (a.1 + b.1) * 0.5 + dx * bend);
+        tessellate_quadratic(strip, a, control, b);
+    }
+
+    vertices
+}
+
+/// Fill `strip` with evenly spaced vertices of the quadratic Bézier curve
+/// from `a` to `b` with control point `c`.
+fn tessellate_quadratic(strip: &mut [f32], a: (f32, f32), c: (f32, f32), b: (f32, f32)) {
+    let last = (strip.len() / 2 - 1) as f32;
+    for (k, vertex) in strip.chunks_exact_mut(2).enumerate() {
+        let t = k as f32 / last;
+        let u = 1.0 - t;
+        vertex[0] = u * u * a.0 + 2.0 * u * t * c.0 + t * t * b.0;
+        vertex[1] = u * u * a.1 + 2.0 * u * t * c.1 + t * t * b.1;
+    }
+}
+
+/// Fill `strip` with a circle of `radius` above `node`, starting and ending
+/// at the node.
+fn tessellate_loop(strip: &mut [f32], node: (f32, f32), radius: f32) {
+    let last = (strip.len() / 2 - 1) as f32;
+    let center = (node.0, node.1 - radius);
+    for (k, vertex) in strip.chunks_exact_mut(2).enumerate() {
+        let angle = FRAC_PI_2 + TAU * k as f32 / last;
+        vertex[0] = center.0 + radius * angle.cos();
+        vertex[1] = center.1 + radius * angle.sin();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn vertex(vertices: &[f32], config: &EdgeCurveConfig, edge: usize, k: usize) -> (f32, f32) {
+        let base = (edge * (config.segments as usize + 1) + k) * 2;
+        (vertices[base], vertices[base + 1])
+    }
+
+    #[test]
+    fn test_single_edge_bends_by_curvature() {
+        let positions = [0.0, 0.0, 100.0, 0.0, SENTINEL, SENTINEL];
+        let csr = csr_from_edges(3, &[(0, 1), (1, 2)]);
+        let config = EdgeCurveConfig {
+            segments: 4,
+            ..Default::default()
+        };
+        let vertices = compute_edge_curves(&csr, &positions, &config);
+
+        assert_eq!(vertices.len(), 2 * 5 * 2);
+        assert_eq!(vertex(&vertices, &config, 0, 0), (0.0, 0.0));
+        assert_eq!(vertex(&vertices, &config, 0, 4), (100.0, 0.0));
+        // Apex at half the control offset: 0.2 × 100 / 2
+        let apex = vertex(&vertices, &config, 0, 2);
+        assert!((apex.0 - 50.0).abs() < 1e-4 && (apex.1 - 10.0).abs() < 1e-4);
+        assert!(vertices[10..].iter().all(|&v| v == SENTINEL));
+    }
+
+    #[test]
+    fn test_parallel_edges_fan_out() {
+        // Three edges between 0 and 1, one of them reversed
+        let positions = [0.0, 0.0, 100.0, 0.0];
+        let csr = csr_from_edges(2, &[(0, 1), (0, 1), (1, 0)]);
+        let config = EdgeCurveConfig {
+            segments: 2,
+            ..Default::default()
+        };
+        let vertices = compute_edge_curves(&csr, &positions, &config);
+
+        let apexes: Vec<f32> = (0..3).map(|e| vertex(&vertices, &config, e, 1).1).collect();
+        assert_eq!(apexes, vec![-20.0, 0.0, 20.0]);
+        assert_eq!(vertex(&vertices, &config, 2, 0), (100.0, 0.0));
+    }
+
+    #[test]
+    fn test_self_loop_is_closed_circle() {
+        let positions = [10.0, 10.0];
+        let csr = csr_from_edges(1, &[(0, 0)]);
+        let config = EdgeCurveConfig {
+            segments: 4,
+            loop_radius: 5.0,
+            ..Default::default()
+        };
+        let vertices = compute_edge_curves(&csr, &positions, &config);
+
+        let (start, top, end) = (
+            vertex(&vertices, &config, 0, 0),
+            vertex(&vertices, &config, 0, 2),
+            vertex(&vertices, &config, 0, 4),
+        );
+        assert!((start.0 - 10.0).abs() < 1e-4 && (start.1 - 10.0).abs() < 1e-4);
+        assert!((top.1 - 0.0).abs() < 1e-4);
+        assert!((end.0 - 10.0).abs() < 1e-4 && (end.1 - 10.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,296 @@
+//! Graph-theoretic distances for distance-based layouts.
+//!
+//! Stress majorization, spectral and MDS layouts all work on an undirected
+//! view of the graph and on hop distances from BFS. This module builds that
+//! view from the engine's directed CSR and provides BFS and max–min pivot
+//! sampling on top of it.
+
+use crate::rng::SeededRng;
+
+/// Distance value for nodes not reachable from the BFS source.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+/// Undirected adjacency in CSR form with duplicate edges and self loops
+/// removed.
+pub struct UndirectedCsr {
+    offsets: Vec<u32>,
+    neighbors: Vec<u32>,
+}
+
+impl UndirectedCsr {
+    /// Build from a directed CSR: [offsets...(node_count+1), targets...]
+    ///
+    /// Every directed edge A→B becomes the undirected edge {A, B}. Targets
+    /// out of range are ignored.
+    pub fn from_csr(csr: &[u32], node_count: usize) -> Self {
+        let mut lists: Vec<Vec<u32>> = vec![Vec::new(); node_count];
+        for (src, tgt) in directed_edges(csr, node_count) {
+            lists[src].push(tgt as u32);
+            lists[tgt].push(src as u32);
+        }
+
+        let mut offsets = Vec::with_capacity(node_count + 1);
+        let mut neighbors = Vec::new();
+        offsets.push(0);
+        for mut list in lists {
+            list.sort_unstable();
+            list.dedup();
+            neighbors.extend(list);
+            offsets.push(neighbors.len() as u32);
+        }
+
+        Self { offsets, neighbors }
+    }
+
+    /// Number of node slots.
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Neighbors of `node`, sorted ascending.
+    pub fn neighbors(&self, node: usize) -> &[u32] {
+        &self.neighbors[self.offsets[node] as usize..self.offsets[node + 1] as usize]
+    }
+
+    /// Number of distinct neighbors of `node`.
+    pub fn degree(&self, node: usize) -> usize {
+        (self.offsets[node + 1] - self.offsets[node]) as usize
+    }
+
+    /// Hop distances from `source` to every node ([`UNREACHABLE`] if none).
+    ///
+    /// `dist` and `queue` are reused across calls to avoid reallocating.
+    pub fn bfs(&self, source: usize, dist: &mut Vec<u32>, queue: &mut Vec<u32>) {
+        dist.clear();
+        dist.resize(self.node_count(), UNREACHABLE);
+        queue.clear();
+
+        dist[source] = 0;
+        queue.push(source as u32);
+        let mut head = 0;
+        while head < queue.len() {
+            let node = queue[head] as usize;
+            head += 1;
+            let next = dist[node] + 1;
+            // Neighbor lists are deduplicated, so each fresh node is queued once
+            let start = queue.len();
+            queue.extend(
+                self.neighbors(node)
+                    .iter()
+                    .copied()
+                    .filter(|&neighbor| dist[neighbor as usize] == UNREACHABLE),
+            );
+            for &neighbor in &queue[start..] {
+                dist[neighbor as usize] = next;
+            }
+        }
+    }
+
+    /// Connected components of the nodes that have at least one edge.
+    ///
+    /// Components are listed in order of their lowest slot; the slots within a
+    /// component are in BFS order from that slot.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.node_count()];
+        let mut components = Vec::new();
+        for start in 0..self.node_count() {
+            if !seen[start] && self.degree(start) > 0 {
+                components.push(self.flood(start, &mut seen));
+            }
+        }
+        components
+    }
+
+    /// Breadth-first collect every unseen node reachable from `start`,
+    /// marking them as seen.
+    fn flood(&self, start: usize, seen: &mut [bool]) -> Vec<usize> {
+        seen[start] = true;
+        let mut component = vec![start];
+        let mut head = 0;
+        while head < component.len() {
+            let node = component[head];
+            head += 1;
+            let fresh = component.len();
+            component.extend(
+                self.neighbors(node)
+                    .iter()
+                    .map(|&n| n as usize)
+                    .filter(|&n| !seen[n]),
+            );
+            for &n in &component[fresh..] {
+                seen[n] = true;
+            }
+        }
+        component
+    }
+}
+
+/// Select up to `count` pivots among `candidates` by max–min sampling.
+///
+/// The first pivot is drawn from `rng`; each following pivot is the
+/// candidate farthest (in hops) from all pivots chosen so far, so pivots
+/// spread over the whole graph and cover every connected component before
+/// doubling up. Ties go to the lowest slot.
+///
+/// # Returns
+///
+/// The pivots and, for each pivot, its BFS distances to every node.
+pub fn select_pivots(
+    graph: &UndirectedCsr,
+    candidates: &[usize],
+    count: usize,
+    rng: &mut SeededRng,
+) -> (Vec<usize>, Vec<Vec<u32>>) {
+    let count = count.min(candidates.len());
+    let mut pivots = Vec::with_capacity(count);
+    let mut distances: Vec<Vec<u32>> = Vec::with_capacity(count);
+    if count == 0 {
+        return (pivots, distances);
+    }
+
+    let mut nearest = vec![UNREACHABLE; graph.node_count()];
+    let mut queue = Vec::new();
+    let mut next = candidates[rng.below(candidates.len())];
+
+    for _ in 0..count {
+        let mut dist = Vec::new();
+        graph.bfs(next, &mut dist, &mut queue);
+        for &c in candidates {
+            nearest[c] = nearest[c].min(dist[c]);
+        }
+        pivots.push(next);
+        distances.push(dist);
+
+        // Farthest candidate from the current pivot set; unreachable counts
+        // as farthest so new components get a pivot first
+        match candidates
+            .iter()
+            .copied()
+            .filter(|&c| nearest[c] > 0)
+            .max_by(|&a, &b| nearest[a].cmp(&nearest[b]).then(b.cmp(&a)))
+        {
+            Some(candidate) => next = candidate,
+            None => break,
+        }
+    }
+
+    (pivots, distances)
+}
+
+/// Every CSR target entry as (source, target), including invalid targets so
+/// the result stays aligned with the CSR.
+pub fn csr_pairs(csr: &[u32], node_count: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let (offsets, targets): (&[u32], &[u32]) = if csr.len() > node_count + 1 {
+        (&csr[..node_count + 1], &csr[node_count + 1..])
+    } else {
+        (&[], &[])
+    };
+    (0..offsets.len().saturating_sub(1)).flat_map(move |src| {
+        let end = (offsets[src + 1] as usize).min(targets.len());
+        let start = (offsets[src] as usize).min(end);
+        targets[start..end]
+            .iter()
+            .map(move |&tgt| (src, tgt as usize))
+    })
+}
+
+/// Build a directed CSR [offsets...(node_count+1), targets...] from
+/// (source, target) pairs. Pairs with an out-of-range source are dropped.
+pub fn csr_from_edges(node_count: usize, edges: &[(u32, u32)]) -> Vec<u32> {
+    let edges: Vec<(u32, u32)> = edges
+        .iter()
+        .copied()
+        .filter(|&(src, _)| (src as usize) < node_count)
+        .collect();
+    let mut offsets = vec![0u32; node_count + 1];
+    for &(src, _) in &edges {
+        offsets[src as usize + 1] += 1;
+    }
+    for i in 1..=node_count {
+        offsets[i] += offsets[i - 1];
+    }
+    let mut targets = vec![0u32; edges.len()];
+    let mut current = offsets[..node_count].to_vec();
+    for &(src, tgt) in &edges {
+        targets[current[src as usize] as usize] = tgt;
+        current[src as usize] += 1;
+    }
+    offsets.extend(targets);
+    offsets
+}
+
+/// Iterate the (source, target) pairs of a directed CSR, skipping self
+/// loops and out-of-range targets.
+fn directed_edges(csr: &[u32], node_count: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let (offsets, targets): (&[u32], &[u32]) = if csr.len() > node_count + 1 {
+        (&csr[..node_count + 1], &csr[node_count + 1..])
+    } else {
+        (&[], &[])
+    };
+    (0..offsets.len().saturating_sub(1))
+        .flat_map(move |src| {
+            let end = (offsets[src + 1] as usize).min(targets.len());
+            let start = (offsets[src] as usize).min(end);
+            targets[start..end]
+                .iter()
+                .map(move |&tgt| (src, tgt as usize))
+        })
+        .filter(move |&(src, tgt)| tgt < node_count && tgt != src)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undirected_dedup_and_self_loops() {
+        let csr = csr_from_edges(3, &[(0, 1), (1, 0), (1, 1), (1, 2), (2, 7)]);
+        let graph = UndirectedCsr::from_csr(&csr, 3);
+
+        assert_eq!(graph.neighbors(0), &[1]);
+        assert_eq!(graph.neighbors(1), &[0, 2]);
+        assert_eq!(graph.neighbors(2), &[1]);
+        assert_eq!(graph.degree(1), 2);
+    }
+
+    #[test]
+    fn test_bfs_hops() {
+        // 0 - 1 - 2   3 (isolated)
+        let csr = csr_from_edges(4, &[(0, 1), (2, 1)]);
+        let graph = UndirectedCsr::from_csr(&csr, 4);
+        let (mut dist, mut queue) = (Vec::new(), Vec::new());
+
+        graph.bfs(0, &mut dist, &mut queue);
+        assert_eq!(dist, vec![0, 1, 2, UNREACHABLE]);
+    }
+
+    #[test]
+    fn test_components_skip_isolated_nodes() {
+        // 0 - 1   2 (isolated)   3 - 4 - 5
+        let csr = csr_from_edges(6, &[(0, 1), (4, 3), (4, 5)]);
+        let graph = UndirectedCsr::from_csr(&csr, 6);
+
+        assert_eq!(graph.components(), vec![vec![0, 1], vec![3, 4, 5]]);
+    }
+
+    #[test]
+    fn test_pivots_spread_across_components() {
+        // Path 0-1-2-3-4 and a separate edge 5-6
+        let csr = csr_from_edges(7, &[(0, 1), (1, 2), (2, 3), (3, 4), (5, 6)]);
+        let graph = UndirectedCsr::from_csr(&csr, 7);
+        let candidates: Vec<usize> = (0..7).collect();
+
+        let (pivots, distances) = select_pivots(&graph, &candidates, 3, &mut SeededRng::new(1));
+
+        assert_eq!(pivots.len(), 3);
+        assert_eq!(distances.len(), 3);
+        assert!(
+            pivots.iter().any(|&p| p >= 5),
+            "Second component needs a pivot: {pivots:?}"
+        );
+        let mut unique = pivots.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), 3);
+    }
+}
@@ -0,0 +1,113 @@
+//! Fitting position buffers to a size.
+//!
+//! Every layout picks its own scale: some grow with the node count, some
+//! with the spacing settings, some are normalized. These helpers rescale any
+//! interleaved position buffer [x0, y0, x1, y1, ...] after the fact, leaving
+//! sentinel entries (nodes outside the layout) untouched.
+
+use super::is_placed;
+
+/// Uniformly scale and translate positions so their bounding box fits
+/// `bounds` (min_x, min_y, max_x, max_y) shrunk by `padding`, centered.
+///
+/// Keeps the aspect ratio and scales up as well as down. A layout with no
+/// extent (a single node) is moved to the center. Sentinel and non-finite
+/// entries are left as is.
+pub fn fit_to_bounds(positions: &mut [f32], bounds: (f32, f32, f32, f32), padding: f32) {
+    let placed: Vec<usize> = (0..positions.len() / 2)
+        .filter(|&i| is_placed(positions[i * 2], positions[i * 2 + 1]))
+        .collect();
+    if placed.is_empty() {
+        return;
+    }
+
+    let (mut lo_x, mut lo_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut hi_x, mut hi_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for &i in &placed {
+        lo_x = lo_x.min(positions[i * 2]);
+        hi_x = hi_x.max(positions[i * 2]);
+        lo_y = lo_y.min(positions[i * 2 + 1]);
+        hi_y = hi_y.max(positions[i * 2 + 1]);
+    }
+
+    let (min_x, min_y, max_x, max_y) = bounds;
+    let width = (max_x - min_x - 2.0 * padding).max(0.0);
+    let height = (max_y - min_y - 2.0 * padding).max(0.0);
+    let scale_x = if hi_x > lo_x {
+        width / (hi_x - lo_x)
+    } else {
+        f32::INFINITY
+    };
+    let scale_y = if hi_y > lo_y {
+        height / (hi_y - lo_y)
+    } else {
+        f32::INFINITY
+    };
+    let scale = scale_x.min(scale_y);
+    let scale = if scale.is_finite() { scale } else { 0.0 };
+
+    let (source_x, source_y) = ((lo_x + hi_x) * 0.5, (lo_y + hi_y) * 0.5);
+    let (target_x, target_y) = ((min_x + max_x) * 0.5, (min_y + max_y) * 0.5);
+    for &i in &placed {
+        positions[i * 2] = target_x + (positions[i * 2] - source_x) * scale;
+        positions[i * 2 + 1] = target_y + (positions[i * 2 + 1] - source_y) * scale;
+    }
+}
+
+/// Scale positions about the origin so no node is farther than `radius`
+/// from it.
+///
+/// Only shrinks: layouts already within `radius`, and layouts smaller than
+/// one unit, are left as they are.
+pub fn shrink_to_radius(positions: &mut [f32], radius: f32) {
+    let placed: Vec<usize> = (0..positions.len() / 2)
+        .filter(|&i| is_placed(positions[i * 2], positions[i * 2 + 1]))
+        .collect();
+    let max_dist = placed
+        .iter()
+        .map(|&i| positions[i * 2].hypot(positions[i * 2 + 1]))
+        .fold(0.0f32, f32::max);
+    if max_dist < 1.0 {
+        return;
+    }
+
+    let scale = radius / max_dist;
+    if scale >= 1.0 {
+        return;
+    }
+    for &i in &placed {
+        positions[i * 2] *= scale;
+        positions[i * 2 + 1] *= scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+
+    #[test]
+    fn test_fit_to_bounds_keeps_aspect_ratio() {
+        let mut positions = vec![-10.0, -5.0, 10.0, 5.0, SENTINEL, SENTINEL];
+        fit_to_bounds(&mut positions, (0.0, 0.0, 100.0, 100.0), 10.0);
+
+        assert_eq!(positions, vec![10.0, 30.0, 90.0, 70.0, SENTINEL, SENTINEL]);
+    }
+
+    #[test]
+    fn test_fit_single_node_to_center() {
+        let mut positions = vec![7.0, 7.0];
+        fit_to_bounds(&mut positions, (0.0, 0.0, 200.0, 100.0), 0.0);
+        assert_eq!(positions, vec![100.0, 50.0]);
+    }
+
+    #[test]
+    fn test_shrink_to_radius_only_shrinks() {
+        let mut positions = vec![30.0, 40.0, -5.0, 0.0, SENTINEL, SENTINEL];
+        shrink_to_radius(&mut positions, 10.0);
+        assert_eq!(positions, vec![6.0, 8.0, -1.0, 0.0, SENTINEL, SENTINEL]);
+
+        shrink_to_radius(&mut positions, 100.0);
+        assert_eq!(positions[..2], [6.0, 8.0]);
+    }
+}
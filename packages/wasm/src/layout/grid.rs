@@ -0,0 +1,129 @@
+//! Grid layout.
+//!
+//! Assigns nodes to the cells of a rectangular grid in row-major order,
+//! sorted by an optional per-node key. Useful as a neutral "reset" layout and
+//! for small-multiples views.
+//!
+//! Works on the slot space directly: removed slots get the sentinel, while
+//! nodes without edges are still placed.
+
+use super::SENTINEL;
+
+/// Configuration for the grid layout.
+pub struct GridLayoutConfig {
+    /// Number of columns. 0 picks `ceil(sqrt(n))` for a square grid
+    /// (default: 0).
+    pub columns: u32,
+    /// Cell width (default: 50.0).
+    pub cell_width: f32,
+    /// Cell height (default: 50.0).
+    pub cell_height: f32,
+}
+
+impl Default for GridLayoutConfig {
+    fn default() -> Self {
+        Self {
+            columns: 0,
+            cell_width: 50.0,
+            cell_height: 50.0,
+        }
+    }
+}
+
+/// Compute a grid layout.
+///
+/// # Arguments
+///
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+/// * `sort_keys` - Optional key per slot; nodes fill the grid in ascending
+///   key order, ties by slot. Missing and NaN keys sort last. Empty keeps
+///   slot order.
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...] at cell
+/// centers, with the grid centered on the origin. Empty slots get sentinel
+/// values (f32::MAX).
+pub fn compute_grid_layout(
+    present: &[bool],
+    sort_keys: &[f32],
+    config: &GridLayoutConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; present.len() * 2];
+    let mut order: Vec<usize> = (0..present.len()).filter(|&i| present[i]).collect();
+    let n = order.len();
+    if n == 0 {
+        return positions;
+    }
+    if !sort_keys.is_empty() {
+        let key = |slot: usize| sort_keys.get(slot).copied().unwrap_or(f32::INFINITY);
+        order.sort_by(|&a, &b| key(a).total_cmp(&key(b)).then(a.cmp(&b)));
+    }
+
+    let columns = if config.columns > 0 {
+        config.columns as usize
+    } else {
+        (n as f32).sqrt().ceil() as usize
+    };
+    let rows = n.div_ceil(columns);
+    let used_columns = columns.min(n);
+
+    // Offsets that center the occupied cells on the origin
+    let origin_x = (used_columns as f32 - 1.0) * 0.5 * config.cell_width;
+    let origin_y = (rows as f32 - 1.0) * 0.5 * config.cell_height;
+
+    for (index, slot) in order.into_iter().enumerate() {
+        let (row, column) = (index / columns, index % columns);
+        positions[slot * 2] = column as f32 * config.cell_width - origin_x;
+        positions[slot * 2 + 1] = row as f32 * config.cell_height - origin_y;
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square_grid_centered() {
+        let present = [true; 4];
+        let positions = compute_grid_layout(&present, &[], &GridLayoutConfig::default());
+        assert_eq!(
+            positions,
+            vec![-25.0, -25.0, 25.0, -25.0, -25.0, 25.0, 25.0, 25.0]
+        );
+    }
+
+    #[test]
+    fn test_columns_cell_size_and_holes() {
+        let present = [true, false, true, true];
+        let config = GridLayoutConfig {
+            columns: 3,
+            cell_width: 10.0,
+            cell_height: 20.0,
+        };
+        let positions = compute_grid_layout(&present, &[], &config);
+
+        assert_eq!(&positions[0..2], &[-10.0, 0.0]);
+        assert_eq!(positions[2], SENTINEL);
+        assert_eq!(&positions[4..6], &[0.0, 0.0]);
+        assert_eq!(&positions[6..8], &[10.0, 0.0]);
+    }
+
+    #[test]
+    fn test_sort_keys_order_cells() {
+        let present = [true; 3];
+        let config = GridLayoutConfig {
+            columns: 3,
+            ..Default::default()
+        };
+        // Slot 2 sorts first; the NaN key of slot 1 sorts last
+        let positions = compute_grid_layout(&present, &[5.0, f32::NAN, -1.0], &config);
+
+        assert_eq!(positions[4], -50.0);
+        assert_eq!(positions[0], 0.0);
+        assert_eq!(positions[2], 50.0);
+    }
+}
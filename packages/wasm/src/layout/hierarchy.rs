@@ -0,0 +1,182 @@
+//! Containment hierarchy shared by the tree-shaped layouts.
+//!
+//! Builds a tree from flat parent→child containment edges with the same root
+//! detection as the codebase and bubble layouts, and cuts it down to tree
+//! edges: a node reachable from several parents (or around a cycle) belongs
+//! to the first one in DFS preorder.
+
+use std::collections::HashSet;
+
+/// Rooted tree built from containment edges.
+pub struct Hierarchy {
+    /// Root slot.
+    pub root: usize,
+    /// Tree nodes in DFS preorder, root first.
+    pub order: Vec<usize>,
+    /// Tree children per slot, in edge order.
+    pub children: Vec<Vec<usize>>,
+    /// Depth per slot (0 = root and nodes outside the tree).
+    pub depth: Vec<u32>,
+}
+
+impl Hierarchy {
+    /// Build the hierarchy from containment edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `node_count` - Total number of node slots
+    /// * `root_id` - Optional root node ID (None = node without a parent and
+    ///   with the most descendants; on a pure cycle, the lowest ID)
+    ///
+    /// Returns `None` if the edge array is malformed, has no valid edge, or
+    /// `root_id` is out of range.
+    pub fn from_containment(
+        containment_edges: &[u32],
+        node_count: usize,
+        root_id: Option<u32>,
+    ) -> Option<Self> {
+        if containment_edges.len() % 2 != 0 {
+            return None;
+        }
+
+        // Build parent→children adjacency
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+        let mut has_parent = vec![false; node_count];
+        let mut in_tree = vec![false; node_count];
+        for pair in containment_edges.chunks_exact(2) {
+            let (parent, child) = (pair[0] as usize, pair[1] as usize);
+            if parent >= node_count || child >= node_count || parent == child {
+                continue;
+            }
+            edges[parent].push(child);
+            has_parent[child] = true;
+            in_tree[parent] = true;
+            in_tree[child] = true;
+        }
+
+        let root = match root_id {
+            Some(r) if (r as usize) < node_count => r as usize,
+            Some(_) => return None,
+            None => (0..node_count)
+                .filter(|&i| in_tree[i] && !has_parent[i])
+                .max_by_key(|&r| (preorder(r, &edges).len(), std::cmp::Reverse(r)))
+                .or_else(|| in_tree.iter().position(|&t| t))?,
+        };
+
+        // Tree edges only: the first parent reached in preorder claims a child
+        let order = preorder(root, &edges);
+        let mut claimed = vec![false; node_count];
+        claimed[root] = true;
+        let mut depth = vec![0u32; node_count];
+        let mut children: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+        let pairs = order
+            .iter()
+            .flat_map(|&node| edges[node].iter().map(move |&child| (node, child)));
+        for (node, child) in pairs {
+            if !std::mem::replace(&mut claimed[child], true) {
+                depth[child] = depth[node] + 1;
+                children[node].push(child);
+            }
+        }
+
+        Some(Self {
+            root,
+            order,
+            children,
+            depth,
+        })
+    }
+
+    /// Number of descendants per slot, the node itself excluded (0 for
+    /// leaves and nodes outside the tree).
+    pub fn descendant_counts(&self) -> Vec<u32> {
+        let mut counts = vec![0u32; self.children.len()];
+        // Reverse preorder visits every child before its parent
+        for &node in self.order.iter().rev() {
+            counts[node] = self.children[node].iter().map(|&c| counts[c] + 1).sum();
+        }
+        counts
+    }
+
+    /// Tree nodes grouped by depth, CSR-style: the nodes at depth `d` are
+    /// `nodes[offsets[d]..offsets[d + 1]]`, each level in preorder so
+    /// siblings stay together. Returns `(offsets, nodes)`.
+    pub fn levels(&self) -> (Vec<u32>, Vec<u32>) {
+        let level_count = self.order.iter().map(|&n| self.depth[n] as usize + 1).max().unwrap_or(0);
+        let mut offsets = vec![0u32; level_count + 1];
+        for &node in &self.order {
+            offsets[self.depth[node] as usize + 1] += 1;
+        }
+        for level in 0..level_count {
+            offsets[level + 1] += offsets[level];
+        }
+
+        let mut next = offsets.clone();
+        let mut nodes = vec![0u32; self.order.len()];
+        for &node in &self.order {
+            let at = &mut next[self.depth[node] as usize];
+            nodes[*at as usize] = node as u32;
+            *at += 1;
+        }
+        (offsets, nodes)
+    }
+}
+
+/// Nodes reachable from `root` in DFS preorder, each visited once.
+fn preorder(root: usize, children: &[Vec<usize>]) -> Vec<usize> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node) {
+            continue;
+        }
+        order.push(node);
+        // Reversed so the first child is visited first
+        stack.extend(
+            children[node]
+                .iter()
+                .rev()
+                .filter(|c| !visited.contains(*c)),
+        );
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_root_prefers_largest_tree() {
+        // Trees 0 → 1 and 2 → {3, 4}
+        let hierarchy = Hierarchy::from_containment(&[0, 1, 2, 3, 2, 4], 5, None).unwrap();
+        assert_eq!(hierarchy.root, 2);
+        assert_eq!(hierarchy.order, vec![2, 3, 4]);
+        assert_eq!(hierarchy.depth[4], 1);
+    }
+
+    #[test]
+    fn test_shared_child_and_cycle_keep_first_parent() {
+        // 1 is reached from 0 first; the 3 → 0 back edge is dropped
+        let edges = [0, 1, 0, 2, 2, 1, 2, 3, 3, 0];
+        let hierarchy = Hierarchy::from_containment(&edges, 4, Some(0)).unwrap();
+        assert_eq!(
+            hierarchy.children,
+            vec![vec![1, 2], vec![], vec![3], vec![]]
+        );
+        assert_eq!(hierarchy.depth, vec![0, 1, 1, 2]);
+        assert_eq!(hierarchy.descendant_counts(), vec![3, 0, 1, 0]);
+        let (offsets, nodes) = hierarchy.levels();
+        assert_eq!(offsets, vec![0, 1, 3, 4]);
+        assert_eq!(nodes, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(Hierarchy::from_containment(&[0, 1, 2], 3, None).is_none());
+        assert!(Hierarchy::from_containment(&[0, 0, 5, 1], 3, None).is_none());
+        assert!(Hierarchy::from_containment(&[0, 1], 3, Some(7)).is_none());
+    }
+}
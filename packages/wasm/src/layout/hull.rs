@@ -0,0 +1,254 @@
+//! Group outlines.
+//!
+//! Computes one padded convex outline per group (community, cluster,
+//! category) from node positions, for drawing group "blobs" behind the
+//! nodes. The outline is the convex hull of the group's nodes grown by
+//! `padding`: rounded outlines follow a circle of radius `padding` around
+//! each hull corner (the exact Minkowski sum, sampled), sharp ones cut the
+//! corners with at most one extra vertex per quarter turn. Single nodes and
+//! pairs get a circle or capsule (rounded) or a diamond or stretched hexagon
+//! (sharp).
+//!
+//! Outlines are counter-clockwise in math coordinates, which is clockwise
+//! on a y-down screen.
+
+use std::collections::BTreeMap;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_8, TAU};
+
+use super::is_placed;
+
+/// Group id for nodes that belong to no group.
+pub const NO_GROUP: u32 = u32::MAX;
+
+/// Configuration for group outlines.
+pub struct GroupHullConfig {
+    /// Distance between the outline and the outermost nodes (default: 20.0).
+    pub padding: f32,
+    /// Round the corners instead of cutting them (default: true).
+    pub rounded: bool,
+}
+
+impl Default for GroupHullConfig {
+    fn default() -> Self {
+        Self {
+            padding: 20.0,
+            rounded: true,
+        }
+    }
+}
+
+/// Outline of one group.
+pub struct GroupHull {
+    /// Group id from the assignments.
+    pub group: u32,
+    /// Outline vertices, not repeating the first at the end.
+    pub points: Vec<(f32, f32)>,
+}
+
+/// Flatten outlines into a single buffer:
+/// `[group_count, group0, point_count0, x, y, ..., group1, point_count1, ...]`
+pub fn hulls_to_flat(hulls: &[GroupHull]) -> Vec<f32> {
+    let mut flat = vec![hulls.len() as f32];
+    for hull in hulls {
+        flat.push(hull.group as f32);
+        flat.push(hull.points.len() as f32);
+        flat.extend(hull.points.iter().flat_map(|&(x, y)| [x, y]));
+    }
+    flat
+}
+
+/// Compute the outline of every group.
+///
+/// # Arguments
+///
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for removed slots
+/// * `assignments` - Group id per slot ([`NO_GROUP`] or missing = no group)
+/// * `config` - Outline configuration
+///
+/// # Returns
+///
+/// One outline per group with at least one positioned node, by ascending
+/// group id.
+pub fn compute_group_hulls(
+    positions: &[f32],
+    assignments: &[u32],
+    config: &GroupHullConfig,
+) -> Vec<GroupHull> {
+    let mut groups: BTreeMap<u32, Vec<(f32, f32)>> = BTreeMap::new();
+    for (slot, &group) in assignments.iter().enumerate() {
+        let (Some(&x), Some(&y)) = (positions.get(slot * 2), positions.get(slot * 2 + 1)) else {
+            break;
+        };
+        if group != NO_GROUP && is_placed(x, y) {
+            groups.entry(group).or_default().push((x, y));
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, points)| GroupHull {
+            group,
+            points: pad_hull(&convex_hull(points), config),
+        })
+        .collect()
+}
+
+/// Convex hull by Andrew's monotone chain, counter-clockwise, without
+/// collinear or duplicate vertices.
+fn convex_hull(mut points: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Lower chain left to right, then upper chain right to left
+    let mut hull = half_hull(points.iter().copied());
+    hull.extend(half_hull(points.iter().rev().copied()));
+    hull
+}
+
+/// One chain of the monotone chain hull, without its last point (the first
+/// point of the other chain).
+fn half_hull(points: impl Iterator<Item = (f32, f32)>) -> Vec<(f32, f32)> {
+    let mut chain: Vec<(f32, f32)> = Vec::new();
+    for p in points {
+        while chain.len() >= 2 && cross(chain[chain.len() - 2], chain[chain.len() - 1], p) <= 0.0 {
+            chain.pop();
+        }
+        chain.push(p);
+    }
+    chain.pop();
+    chain
+}
+
+fn cross(o: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Grow a counter-clockwise convex polygon by `padding`.
+fn pad_hull(hull: &[(f32, f32)], config: &GroupHullConfig) -> Vec<(f32, f32)> {
+    let padding = config.padding;
+    if padding <= 0.0 {
+        return hull.to_vec();
+    }
+    let max_step = if config.rounded { FRAC_PI_8 } else { FRAC_PI_2 };
+    if hull.len() == 1 {
+        return arc(hull[0], 0.0, TAU, padding, max_step, false);
+    }
+
+    let n = hull.len();
+    (0..n)
+        .flat_map(|i| {
+            let (prev, v, next) = (hull[(i + n - 1) % n], hull[i], hull[(i + 1) % n]);
+            let from = outward_angle(prev, v);
+            let mut to = outward_angle(v, next);
+            if to < from {
+                to += TAU;
+            }
+            arc(v, from, to, padding, max_step, true)
+        })
+        .collect()
+}
+
+/// Direction of the outward normal of edge `a → b` of a counter-clockwise
+/// polygon.
+fn outward_angle(a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    (-dx).atan2(dy).rem_euclid(TAU)
+}
+
+/// Points on the circle of `radius` around `center` from angle `from` to
+/// `to`, at most `max_step` apart. `closed` includes the end point.
+fn arc(
+    center: (f32, f32),
+    from: f32,
+    to: f32,
+    radius: f32,
+    max_step: f32,
+    closed: bool,
+) -> Vec<(f32, f32)> {
+    // The tolerance keeps exact quarter turns from getting an extra step
+    let steps = ((to - from) / max_step - 1e-4).ceil().max(1.0) as usize;
+    let count = if closed { steps + 1 } else { steps };
+    (0..count)
+        .map(|k| {
+            let angle = from + (to - from) * k as f32 / steps as f32;
+            (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+
+    #[test]
+    fn test_convex_hull_drops_interior_and_collinear_points() {
+        let points = vec![
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+            (0.0, 2.0),
+            (0.0, 2.0),
+        ];
+        assert_eq!(
+            convex_hull(points),
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_groups_get_padded_outlines() {
+        // Group 1: square of four nodes; group 0: one node; slot 5 removed
+        let positions = [
+            0.0, 0.0, 10.0, 0.0, 10.0, 10.0, 0.0, 10.0, 100.0, 100.0, SENTINEL, SENTINEL, 5.0, 5.0,
+        ];
+        let assignments = [1, 1, 1, 1, 0, 0, NO_GROUP];
+        let config = GroupHullConfig {
+            padding: 5.0,
+            rounded: false,
+        };
+        let hulls = compute_group_hulls(&positions, &assignments, &config);
+
+        assert_eq!(hulls.len(), 2);
+        assert_eq!(hulls[0].group, 0);
+        assert_eq!(hulls[0].points.len(), 4);
+        // Square corners become two points each, 5 outside the sides
+        let square = &hulls[1].points;
+        assert_eq!(square.len(), 8);
+        for &(x, y) in square {
+            let outside = (-x).max(x - 10.0).max(-y).max(y - 10.0);
+            assert!((outside - 5.0).abs() < 1e-4, "({x}, {y})");
+        }
+
+        let flat = hulls_to_flat(&hulls);
+        assert_eq!(flat[..3], [2.0, 0.0, 4.0]);
+        assert_eq!(flat.len(), 1 + 2 + 8 + 2 + 16);
+    }
+
+    #[test]
+    fn test_rounded_outline_keeps_padding_distance() {
+        let positions = [0.0, 0.0, 30.0, 0.0];
+        let config = GroupHullConfig {
+            padding: 4.0,
+            rounded: true,
+        };
+        let hulls = compute_group_hulls(&positions, &[7, 7], &config);
+
+        // A capsule: every vertex exactly 4 from the segment
+        let capsule = &hulls[0].points;
+        assert!(capsule.len() > 8);
+        for &(x, y) in capsule {
+            let nearest = x.clamp(0.0, 30.0);
+            assert!(((x - nearest).hypot(y) - 4.0).abs() < 1e-4);
+        }
+    }
+}
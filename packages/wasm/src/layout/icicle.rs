@@ -0,0 +1,227 @@
+//! Icicle (adjacency partition) layout.
+//!
+//! Lays out the containment hierarchy as stacked rectangles: depth maps to
+//! one axis and subtree size to extent on the other, so every node spans
+//! exactly the breadth of its descendants. Unlike the radial tree or circle
+//! packing, no space is lost to whitespace, which keeps deep and wide trees
+//! readable.
+//!
+//! # Algorithm
+//!
+//! 1. **Build hierarchy tree** from containment edges (parent→child), see
+//!    [`Hierarchy`].
+//! 2. **Bottom-up sizes**: a leaf's size is its weight (1 by default); an
+//!    internal node's size is the sum of its children's sizes.
+//! 3. **Top-down partition**: the root spans the full breadth and each node
+//!    splits its span among its children in proportion to their sizes.
+//!
+//! Steps 1–3 are exposed as [`partition_spans`] so the sunburst layout can
+//! map the same spans to angles.
+
+use super::SENTINEL;
+use super::hierarchy::Hierarchy;
+
+/// Configuration for the icicle layout.
+pub struct IcicleLayoutConfig {
+    /// Total extent of the root along the breadth axis (default: 1000.0).
+    pub breadth: f32,
+    /// Thickness of each depth level (default: 40.0).
+    pub level_size: f32,
+    /// If true, depth runs along x (left to right); if false, along y
+    /// (top-down) (default: false).
+    pub horizontal: bool,
+}
+
+impl Default for IcicleLayoutConfig {
+    fn default() -> Self {
+        Self {
+            breadth: 1000.0,
+            level_size: 40.0,
+            horizontal: false,
+        }
+    }
+}
+
+/// Compute an icicle layout from the containment hierarchy.
+///
+/// # Arguments
+///
+/// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+/// * `weights` - Optional size per leaf slot (empty = 1 per leaf). Missing
+///   entries count as 1; negative and NaN weights as 0.
+/// * `node_count` - Total number of node slots
+/// * `root_id` - Optional root node ID (None = auto-detect)
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of per-node rectangles [x0, y0, w0, h0, x1, y1, w1, h1, ...]
+/// with (x, y) the top-left corner. The root's rectangle starts at depth 0
+/// and is centered on the breadth axis. Nodes not in the tree get sentinel
+/// values (f32::MAX).
+pub fn compute_icicle_layout(
+    containment_edges: &[u32],
+    weights: &[f32],
+    node_count: usize,
+    root_id: Option<u32>,
+    config: &IcicleLayoutConfig,
+) -> Vec<f32> {
+    let mut rects = vec![SENTINEL; node_count * 4];
+    let Some(spans) = partition_spans(containment_edges, weights, node_count, root_id) else {
+        return rects;
+    };
+
+    for &node in &spans.order {
+        let across = (
+            (spans.start[node] - 0.5) as f32 * config.breadth,
+            spans.extent[node] as f32 * config.breadth,
+        );
+        let along = (
+            spans.depth[node] as f32 * config.level_size,
+            config.level_size,
+        );
+        let ((x, w), (y, h)) = if config.horizontal {
+            (along, across)
+        } else {
+            (across, along)
+        };
+        rects[node * 4..node * 4 + 4].copy_from_slice(&[x, y, w, h]);
+    }
+
+    rects
+}
+
+/// Hierarchy partitioned by subtree size, shared by the icicle and sunburst
+/// layouts.
+pub struct PartitionSpans {
+    /// Tree nodes in DFS preorder, root first.
+    pub order: Vec<usize>,
+    /// Depth per slot (0 = root).
+    pub depth: Vec<u32>,
+    /// Start of each slot's span as a fraction of the root's span [0, 1).
+    pub start: Vec<f64>,
+    /// Length of each slot's span as a fraction of the root's span.
+    pub extent: Vec<f64>,
+}
+
+/// Build the hierarchy from containment edges and split the unit span among
+/// children by subtree size.
+///
+/// Returns `None` if there is no valid edge or `root_id` is out of range.
+pub fn partition_spans(
+    containment_edges: &[u32],
+    weights: &[f32],
+    node_count: usize,
+    root_id: Option<u32>,
+) -> Option<PartitionSpans> {
+    let Hierarchy {
+        root,
+        order,
+        children,
+        depth,
+    } = Hierarchy::from_containment(containment_edges, node_count, root_id)?;
+
+    // Bottom-up sizes
+    let mut size = vec![0.0f64; node_count];
+    for &node in order.iter().rev() {
+        size[node] = if children[node].is_empty() {
+            leaf_weight(weights, node)
+        } else {
+            children[node].iter().map(|&c| size[c]).sum()
+        };
+    }
+
+    // Top-down spans
+    let mut start = vec![0.0f64; node_count];
+    let mut extent = vec![0.0f64; node_count];
+    extent[root] = 1.0;
+    for &node in &order {
+        let count = children[node].len();
+        let mut cursor = start[node];
+        for &child in &children[node] {
+            // Children of a zero-size node split its span evenly
+            let share = if size[node] > 0.0 {
+                size[child] / size[node]
+            } else {
+                1.0 / count as f64
+            };
+            start[child] = cursor;
+            extent[child] = extent[node] * share;
+            cursor += extent[child];
+        }
+    }
+
+    Some(PartitionSpans {
+        order,
+        depth,
+        start,
+        extent,
+    })
+}
+
+/// Size of a leaf: its weight, 1 if unspecified, 0 if negative or NaN.
+fn leaf_weight(weights: &[f32], slot: usize) -> f64 {
+    match weights.get(slot) {
+        None => 1.0,
+        Some(&w) if w > 0.0 => w as f64,
+        Some(_) => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(rects: &[f32], slot: usize) -> [f32; 4] {
+        [
+            rects[slot * 4],
+            rects[slot * 4 + 1],
+            rects[slot * 4 + 2],
+            rects[slot * 4 + 3],
+        ]
+    }
+
+    #[test]
+    fn test_children_partition_parent_by_leaf_count() {
+        // 0 → {1, 2}, 1 → {3, 4}: 1 has two leaves, 2 has one
+        let edges = [0, 1, 0, 2, 1, 3, 1, 4];
+        let config = IcicleLayoutConfig {
+            breadth: 300.0,
+            ..Default::default()
+        };
+        let rects = compute_icicle_layout(&edges, &[], 6, None, &config);
+
+        assert_eq!(rect(&rects, 0), [-150.0, 0.0, 300.0, 40.0]);
+        assert_eq!(rect(&rects, 1), [-150.0, 40.0, 200.0, 40.0]);
+        assert_eq!(rect(&rects, 2), [50.0, 40.0, 100.0, 40.0]);
+        assert_eq!(rect(&rects, 3), [-150.0, 80.0, 100.0, 40.0]);
+        assert_eq!(rect(&rects, 4), [-50.0, 80.0, 100.0, 40.0]);
+        assert_eq!(rect(&rects, 5), [SENTINEL; 4]);
+    }
+
+    #[test]
+    fn test_weights_and_horizontal() {
+        let edges = [0, 1, 0, 2];
+        let config = IcicleLayoutConfig {
+            breadth: 100.0,
+            level_size: 10.0,
+            horizontal: true,
+        };
+        let rects = compute_icicle_layout(&edges, &[0.0, 3.0, 1.0], 3, None, &config);
+
+        assert_eq!(rect(&rects, 0), [0.0, -50.0, 10.0, 100.0]);
+        assert_eq!(rect(&rects, 1), [10.0, -50.0, 10.0, 75.0]);
+        assert_eq!(rect(&rects, 2), [10.0, 25.0, 10.0, 25.0]);
+    }
+
+    #[test]
+    fn test_shared_child_and_cycle_are_drawn_once() {
+        // 1 is claimed by 0 first; the 3 → 0 back edge is ignored
+        let edges = [0, 1, 0, 2, 2, 1, 2, 3, 3, 0];
+        let rects = compute_icicle_layout(&edges, &[], 4, Some(0), &Default::default());
+
+        assert_eq!(rect(&rects, 1)[1], 40.0);
+        assert_eq!(rect(&rects, 3)[1], 80.0);
+        assert_eq!(rect(&rects, 1)[2] + rect(&rects, 2)[2], 1000.0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Layout quality metrics.
+//!
+//! Numbers for comparing layouts (or layout parameters) without eyeballing
+//! screenshots. All metrics treat edges as undirected and ignore nodes
+//! without a position.
+//!
+//! - **Stress**: mean squared relative difference between layout distance
+//!   and graph (hop) distance, `((s·d - g) / g)²`, over node pairs, after
+//!   choosing the scale `s` that minimizes it. Scale invariant; 0 is a
+//!   perfect distance embedding.
+//! - **Edge length variance**: variance of edge lengths divided by the
+//!   squared mean (squared coefficient of variation). 0 = all edges equally
+//!   long.
+//! - **Neighborhood preservation**: mean Jaccard similarity between each
+//!   node's graph neighbors and its equally many nearest nodes in the layout.
+//!   1 = every node's neighbors are its closest nodes.
+//! - **Edge crossings**: number of pairs of edges that cross, not counting
+//!   edges that share a node.
+//!
+//! Stress and neighborhood preservation are computed from a seeded sample of
+//! source nodes, so they stay cheap on large graphs. Crossings come from
+//! [`crossings::count_edge_crossings`](super::crossings::count_edge_crossings),
+//! exact up to `exact_crossing_edges` edges and estimated beyond that.
+
+use serde::Serialize;
+
+use super::placed_point;
+use super::crossings::{self, EdgeCrossingConfig};
+use super::distance::{UNREACHABLE, UndirectedCsr};
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+/// Configuration for layout evaluation.
+pub struct LayoutMetricsConfig {
+    /// Source nodes sampled for stress and neighborhood preservation
+    /// (default: 100).
+    pub samples: u32,
+    /// Largest edge count for which crossings are counted exactly
+    /// (default: 5000).
+    pub exact_crossing_edges: u32,
+    /// Edges sampled when estimating crossings (default: 5000).
+    pub sampled_crossing_edges: u32,
+    /// Seed for sampling.
+    pub seed: u64,
+}
+
+impl Default for LayoutMetricsConfig {
+    fn default() -> Self {
+        Self {
+            samples: 100,
+            exact_crossing_edges: 5000,
+            sampled_crossing_edges: 5000,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Quality metrics of one layout.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutMetrics {
+    /// Normalized stress (lower is better).
+    pub stress: f32,
+    /// Edge length variance over squared mean (lower is more uniform).
+    pub edge_length_variance: f32,
+    /// Mean neighborhood Jaccard similarity in [0, 1] (higher is better).
+    pub neighborhood_preservation: f32,
+    /// Number of edge crossings (estimated for large graphs).
+    pub edge_crossings: f32,
+    /// Whether `edge_crossings` is an estimate.
+    pub crossings_estimated: bool,
+}
+
+type Point = (f32, f32);
+
+/// Evaluate a layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `positions` - Interleaved node positions [x0, y0, x1, y1, ...] with
+///   sentinel values for nodes without a position (length `node_count * 2`)
+/// * `config` - Evaluation configuration
+pub fn evaluate_layout(
+    csr: &[u32],
+    positions: &[f32],
+    config: &LayoutMetricsConfig,
+) -> LayoutMetrics {
+    let node_count = positions.len() / 2;
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let points: Vec<Option<Point>> = (0..node_count).map(|i| placed_point(positions, i)).collect();
+    let edges: Vec<(Point, Point)> = (0..node_count)
+        .flat_map(|u| graph.neighbors(u).iter().map(move |&v| (u, v as usize)))
+        .filter(|&(u, v)| u < v)
+        .filter_map(|(u, v)| Some((points[u]?, points[v]?)))
+        .collect();
+
+    let mut rng = SeededRng::new(config.seed);
+    let mut sources: Vec<usize> = (0..node_count)
+        .filter(|&i| points[i].is_some() && graph.degree(i) > 0)
+        .collect();
+    rng.shuffle(&mut sources);
+    sources.truncate(config.samples as usize);
+
+    let crossing_config = EdgeCrossingConfig {
+        exact_edges: config.exact_crossing_edges,
+        sample_edges: config.sampled_crossing_edges,
+        seed: config.seed,
+    };
+    let crossings = crossings::count_edge_crossings(csr, positions, &crossing_config);
+    LayoutMetrics {
+        stress: stress(&graph, &points, &sources),
+        edge_length_variance: edge_length_variance(&edges),
+        neighborhood_preservation: neighborhood_preservation(&graph, &points, &sources),
+        edge_crossings: crossings.count as f32,
+        crossings_estimated: crossings.estimated,
+    }
+}
+
+/// Normalized stress over all pairs with a sampled source.
+fn stress(graph: &UndirectedCsr, points: &[Option<Point>], sources: &[usize]) -> f32 {
+    // (layout distance, graph distance) per connected pair
+    let (mut dist, mut queue) = (Vec::new(), Vec::new());
+    let mut pairs: Vec<(f32, f32)> = Vec::new();
+    for &source in sources {
+        graph.bfs(source, &mut dist, &mut queue);
+        let from = points[source].unwrap_or_default();
+        pairs.extend(
+            (0..points.len())
+                .filter(|&j| j != source && dist[j] != UNREACHABLE)
+                .filter_map(|j| Some((distance(from, points[j]?), dist[j] as f32))),
+        );
+    }
+    if pairs.is_empty() {
+        return 0.0;
+    }
+
+    // Best scale s minimizes Σ ((s·d - g) / g)²
+    let (num, den) = pairs.iter().fold((0.0f64, 0.0f64), |(num, den), &(d, g)| {
+        let r = (d / g) as f64;
+        (num + r, den + r * r)
+    });
+    let scale = if den > 0.0 { num / den } else { 1.0 };
+    let total: f64 = pairs
+        .iter()
+        .map(|&(d, g)| {
+            let r = (scale * d as f64 - g as f64) / g as f64;
+            r * r
+        })
+        .sum();
+    (total / pairs.len() as f64) as f32
+}
+
+/// Variance of edge lengths over the squared mean.
+fn edge_length_variance(edges: &[(Point, Point)]) -> f32 {
+    if edges.is_empty() {
+        return 0.0;
+    }
+    let lengths: Vec<f64> = edges.iter().map(|&(a, b)| distance(a, b) as f64).collect();
+    let n = lengths.len() as f64;
+    let mean = lengths.iter().sum::<f64>() / n;
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    let variance = lengths.iter().map(|l| (l - mean) * (l - mean)).sum::<f64>() / n;
+    (variance / (mean * mean)) as f32
+}
+
+/// Mean Jaccard similarity of graph neighbors and nearest layout nodes.
+fn neighborhood_preservation(
+    graph: &UndirectedCsr,
+    points: &[Option<Point>],
+    sources: &[usize],
+) -> f32 {
+    let scores: Vec<f32> = sources
+        .iter()
+        .filter_map(|&source| {
+            let from = points[source]?;
+            let mut neighbors: Vec<usize> = graph
+                .neighbors(source)
+                .iter()
+                .map(|&n| n as usize)
+                .filter(|&n| points[n].is_some())
+                .collect();
+            if neighbors.is_empty() {
+                return None;
+            }
+            neighbors.sort_unstable();
+
+            // The k nearest other nodes, ties by slot
+            let mut nearest: Vec<(f32, usize)> = (0..points.len())
+                .filter(|&j| j != source)
+                .filter_map(|j| Some((distance(from, points[j]?), j)))
+                .collect();
+            let k = neighbors.len().min(nearest.len());
+            nearest.select_nth_unstable_by(k - 1, |a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let shared = nearest[..k]
+                .iter()
+                .filter(|(_, j)| neighbors.binary_search(j).is_ok())
+                .count();
+            Some(shared as f32 / (neighbors.len() + k - shared) as f32)
+        })
+        .collect();
+    if scores.is_empty() {
+        return 1.0;
+    }
+    scores.iter().sum::<f32>() / scores.len() as f32
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    #[test]
+    fn test_path_on_a_line_is_perfect() {
+        // Path 0-1-2-3 laid out evenly on a line
+        let csr = csr_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let positions = [0.0, 0.0, 50.0, 0.0, 100.0, 0.0, 150.0, 0.0];
+        let metrics = evaluate_layout(&csr, &positions, &Default::default());
+
+        assert!(metrics.stress < 1e-6);
+        assert!(metrics.edge_length_variance < 1e-6);
+        assert_eq!(metrics.neighborhood_preservation, 1.0);
+        assert_eq!(metrics.edge_crossings, 0.0);
+        assert!(!metrics.crossings_estimated);
+    }
+
+    #[test]
+    fn test_crossed_square_and_uneven_edges() {
+        // Cycle 0-1-2-3 drawn as a bow tie: 0-1 and 2-3 cross
+        let csr = csr_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        let positions = [0.0, 0.0, 10.0, 10.0, 10.0, 0.0, 0.0, 10.0];
+        let metrics = evaluate_layout(&csr, &positions, &Default::default());
+
+        assert_eq!(metrics.edge_crossings, 1.0);
+        assert!(metrics.edge_length_variance > 0.0);
+        assert!(metrics.stress > 0.0);
+    }
+
+    #[test]
+    fn test_crossings_on_complete_graph() {
+        // K6 on a circle has C(6, 4) = 15 crossings
+        let n = 6;
+        let edges: Vec<(u32, u32)> = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect();
+        let csr = csr_from_edges(n as usize, &edges);
+        let positions: Vec<f32> = (0..n)
+            .flat_map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / n as f32;
+                [100.0 * angle.cos(), 100.0 * angle.sin()]
+            })
+            .collect();
+
+        let exact = evaluate_layout(&csr, &positions, &Default::default());
+        assert_eq!(exact.edge_crossings, 15.0);
+
+        let config = LayoutMetricsConfig {
+            exact_crossing_edges: 0,
+            sampled_crossing_edges: 10,
+            ..Default::default()
+        };
+        let estimate = evaluate_layout(&csr, &positions, &config);
+        assert!(estimate.crossings_estimated);
+        assert_eq!(estimate.stress, exact.stress);
+    }
+}
@@ -4,12 +4,97 @@
 //! for nodes. These positions can then be uploaded to GPU buffers and used with
 //! spring-to-target force algorithms to animate the graph into the computed layout.
 
+pub mod align;
+pub mod arc;
+pub mod balloon;
+pub mod bipartite;
 pub mod bubble;
+pub mod bundling;
+pub mod circular;
 pub mod codebase;
 pub mod community;
+pub mod concentric;
+pub mod constraints;
+pub mod crossings;
+pub mod curves;
+pub mod distance;
+pub mod fit;
+pub mod grid;
+pub mod hierarchy;
+pub mod hull;
+pub mod icicle;
+pub mod metrics;
+pub mod multilevel;
+pub mod orthogonal;
+pub mod overlap;
+pub mod packing;
+pub mod pipeline;
+pub mod pivot_mds;
+pub mod progressive;
+pub mod snap;
+pub mod spanning_tree;
+pub mod spectral;
+pub mod stress;
+pub mod suggest;
+pub mod sunburst;
 pub mod tidy_tree;
+pub mod transform;
 
+pub use arc::{ArcLayout, ArcLayoutConfig};
+pub use balloon::BalloonLayoutConfig;
+pub use bipartite::BipartiteLayoutConfig;
 pub use bubble::BubbleConfig;
-pub use codebase::CodebaseLayoutConfig;
-pub use community::{CommunityLayoutConfig, CommunityResult};
-pub use tidy_tree::TidyTreeLayout;
+pub use bundling::{EdgeBundlingConfig, EdgePolylines, HierarchicalBundlingConfig};
+pub use circular::{CircularLayoutConfig, CircularOrdering};
+pub use codebase::{
+    CodebaseLayout, CodebaseLayoutConfig, CodebaseLayoutOptions, CodebaseLayoutUpdate,
+};
+pub use community::{
+    CommunityAlgorithm, CommunityArrangement, CommunityEdge, CommunityGraph,
+    CommunityLayoutConfig, CommunityLayoutOptions, CommunityResult, CommunityUpdate,
+    ConsensusConfig, ConsensusResult, DetectionConfig, ResolutionSample, StableAssignments,
+};
+pub use concentric::ConcentricLayoutConfig;
+pub use constraints::{Axis, Constraint, ConstraintConfig};
+pub use crossings::{EdgeCrossingConfig, EdgeCrossings};
+pub use curves::EdgeCurveConfig;
+pub use grid::GridLayoutConfig;
+pub use hierarchy::Hierarchy;
+pub use hull::{GroupHull, GroupHullConfig};
+pub use icicle::{IcicleLayoutConfig, PartitionSpans};
+pub use metrics::{LayoutMetrics, LayoutMetricsConfig};
+pub use multilevel::MultilevelConfig;
+pub use orthogonal::{OrthogonalLayout, OrthogonalLayoutConfig};
+pub use overlap::OverlapRemovalConfig;
+pub use pipeline::{LayoutPipeline, LayoutStage};
+pub use pivot_mds::PivotMdsConfig;
+pub use progressive::{
+    CancelToken, Cancelled, ProgressiveLayout, ProgressiveLayoutConfig, Stepped,
+};
+pub use snap::SnapConfig;
+pub use spanning_tree::SpanningTreeStrategy;
+pub use spectral::SpectralLayoutConfig;
+pub use stress::StressLayoutConfig;
+pub use suggest::{GraphProfile, LayoutSuggestion, SuggestedLayout};
+pub use sunburst::SunburstLayoutConfig;
+pub use tidy_tree::{TidyTreeLayout, TreeLayoutOptions};
+pub use transform::Transform;
+
+/// Coordinate layouts write for nodes they leave out, the same one target
+/// buffers use for "not in layout".
+pub use crate::simulation::target::SENTINEL;
+use crate::simulation::target::is_sentinel;
+
+/// Whether `(x, y)` is a real placement: finite and not the sentinel.
+#[inline]
+pub fn is_placed(x: f32, y: f32) -> bool {
+    x.is_finite() && y.is_finite() && !is_sentinel(x) && !is_sentinel(y)
+}
+
+/// Position of `slot` in an interleaved buffer [x0, y0, x1, y1, ...], if
+/// it is in range and placed.
+#[inline]
+pub fn placed_point(positions: &[f32], slot: usize) -> Option<(f32, f32)> {
+    let (x, y) = (*positions.get(slot * 2)?, *positions.get(slot * 2 + 1)?);
+    is_placed(x, y).then_some((x, y))
+}
@@ -0,0 +1,436 @@
+//! Multilevel force-directed layout.
+//!
+//! Running a force simulation directly on a very large graph leaves it
+//! tangled: each tick only moves nodes locally, so global structure never
+//! unfolds. The multilevel scheme (as used by OpenOrd, FM³ and Walshaw's
+//! method) fixes this by laying out progressively simpler versions of the
+//! graph first.
+//!
+//! # Algorithm
+//!
+//! 1. **Coarsen.** Repeatedly contract the graph with heavy-edge matching:
+//!    visit nodes in seeded random order and merge each with the unmatched
+//!    neighbor it shares the most edge weight with (relative to both masses).
+//!    Nodes left without a partner join their heaviest neighbor's group, so
+//!    stars and other matching-resistant shapes still shrink. Stop when the
+//!    graph is small enough or stops shrinking.
+//! 2. **Lay out** the coarsest graph from a seeded random placement with the
+//!    CPU force simulation.
+//! 3. **Uncoarsen.** Place every node of the next finer level on its group's
+//!    position (plus a little jitter) and refine with a few ticks, down to the
+//!    original graph.
+//!
+//! Coarse nodes repel in proportion to their mass, and springs at each level
+//! are lengthened by the square root of the mean mass, so a coarse node takes
+//! up roughly the area its members will need once expanded.
+//!
+//! Nodes without edges are not part of the layout and get the sentinel.
+
+use std::collections::HashMap;
+
+use super::SENTINEL;
+use super::distance::UndirectedCsr;
+use crate::rng::{DEFAULT_SEED, SeededRng};
+use crate::simulation::force::{ForceSimulation, SimulationConfig, SimulationFrame};
+
+/// Maximum number of coarsening levels.
+const MAX_LEVELS: usize = 64;
+
+/// A level stops coarsening when it keeps more than this fraction of nodes.
+const MIN_SHRINK: f32 = 0.95;
+
+/// Configuration for the multilevel layout.
+pub struct MultilevelConfig {
+    /// Force settings for every level. `spring_length` is the edge length of
+    /// the finest level; per-node repulsion overrides are replaced by masses.
+    pub simulation: SimulationConfig,
+    /// Stop coarsening once a level has at most this many nodes (default: 100).
+    pub coarsest_size: u32,
+    /// Simulation ticks for the coarsest level (default: 300).
+    pub coarsest_iterations: u32,
+    /// Refinement ticks for each finer level (default: 50).
+    pub level_iterations: u32,
+    /// Seed for matching order, initial placement and jitter.
+    pub seed: u64,
+}
+
+impl Default for MultilevelConfig {
+    fn default() -> Self {
+        Self {
+            simulation: SimulationConfig::default(),
+            coarsest_size: 100,
+            coarsest_iterations: 300,
+            level_iterations: 50,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// One level of the hierarchy: a weighted undirected graph with node masses.
+struct Level {
+    /// For each node: (neighbor, edge weight) pairs, both directions stored.
+    neighbors: Vec<Vec<(usize, f32)>>,
+    /// Number of original nodes each node stands for.
+    mass: Vec<f32>,
+}
+
+impl Level {
+    fn len(&self) -> usize {
+        self.mass.len()
+    }
+
+    /// Contract the level by heavy-edge matching.
+    ///
+    /// Returns the coarser level and, for every node of this level, the index
+    /// of the coarse node it was merged into.
+    fn coarsen(&self, rng: &mut SeededRng) -> (Level, Vec<usize>) {
+        let n = self.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+
+        let mut group = vec![usize::MAX; n];
+        let mut groups = 0;
+        for &node in &order {
+            if group[node] != usize::MAX {
+                continue;
+            }
+            group[node] = groups;
+            if let Some(partner) = self.heaviest_neighbor(node, |j| group[j] == usize::MAX) {
+                group[partner] = groups;
+            }
+            groups += 1;
+        }
+
+        // Nodes that found no partner (all neighbors already matched) join
+        // their heaviest neighbor's group instead of staying alone
+        let mut size = vec![0u32; groups];
+        for &g in &group {
+            size[g] += 1;
+        }
+        for node in 0..n {
+            if size[group[node]] > 1 {
+                continue;
+            }
+            if let Some(neighbor) = self.heaviest_neighbor(node, |_| true) {
+                size[group[node]] = 0;
+                group[node] = group[neighbor];
+                size[group[node]] += 1;
+            }
+        }
+        let (group, groups) = compact(&group);
+
+        let mut mass = vec![0.0f32; groups];
+        let mut weights: HashMap<(usize, usize), f32> = HashMap::new();
+        for node in 0..n {
+            mass[group[node]] += self.mass[node];
+            let a = group[node];
+            let external = self.neighbors[node]
+                .iter()
+                .map(|&(neighbor, w)| (group[neighbor], w))
+                .filter(|&(b, _)| b != a);
+            for (b, w) in external {
+                *weights.entry((a, b)).or_insert(0.0) += w;
+            }
+        }
+        let mut neighbors: Vec<Vec<(usize, f32)>> = vec![Vec::new(); groups];
+        for ((a, b), w) in weights {
+            neighbors[a].push((b, w));
+        }
+        // HashMap iteration order is unspecified; keep the result deterministic
+        for list in &mut neighbors {
+            list.sort_unstable_by_key(|&(b, _)| b);
+        }
+
+        (Level { neighbors, mass }, group)
+    }
+
+    /// Neighbor sharing the most weight with `node` relative to both masses,
+    /// among those accepted by `eligible`. Ties go to the lowest index.
+    fn heaviest_neighbor(&self, node: usize, eligible: impl Fn(usize) -> bool) -> Option<usize> {
+        self.neighbors[node]
+            .iter()
+            .filter(|&&(j, _)| j != node && eligible(j))
+            .map(|&(j, w)| (j, w / (self.mass[node] * self.mass[j])))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(j, _)| j)
+    }
+
+    /// Directed CSR with every undirected edge once, for a simulation frame.
+    fn frame_csr(&self) -> Vec<u32> {
+        let n = self.len();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut targets = Vec::new();
+        offsets.push(0);
+        for (a, list) in self.neighbors.iter().enumerate() {
+            targets.extend(list.iter().filter(|&&(b, _)| b > a).map(|&(b, _)| b as u32));
+            offsets.push(targets.len() as u32);
+        }
+        offsets.extend(targets);
+        offsets
+    }
+}
+
+/// Renumber group ids to `0..count`, in order of first appearance.
+fn compact(group: &[usize]) -> (Vec<usize>, usize) {
+    let mut remap: HashMap<usize, usize> = HashMap::new();
+    let compacted = group
+        .iter()
+        .map(|&g| {
+            let next = remap.len();
+            *remap.entry(g).or_insert(next)
+        })
+        .collect();
+    (compacted, remap.len())
+}
+
+/// Compute a multilevel force-directed layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...].
+/// Nodes without edges get sentinel values (f32::MAX).
+pub fn compute_multilevel_layout(
+    csr: &[u32],
+    node_count: usize,
+    config: &MultilevelConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; node_count * 2];
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let participants: Vec<usize> = (0..node_count).filter(|&i| graph.degree(i) > 0).collect();
+    if participants.is_empty() {
+        return positions;
+    }
+
+    let mut local = vec![0usize; node_count];
+    for (index, &slot) in participants.iter().enumerate() {
+        local[slot] = index;
+    }
+    let finest = Level {
+        neighbors: participants
+            .iter()
+            .map(|&slot| {
+                graph
+                    .neighbors(slot)
+                    .iter()
+                    .map(|&n| (local[n as usize], 1.0))
+                    .collect()
+            })
+            .collect(),
+        mass: vec![1.0; participants.len()],
+    };
+
+    // Coarsen: levels[0] is the original graph, parents[i] maps level i to i + 1
+    let mut rng = SeededRng::new(config.seed);
+    let mut levels = vec![finest];
+    let mut parents: Vec<Vec<usize>> = Vec::new();
+    while levels.len() < MAX_LEVELS {
+        let current = &levels[levels.len() - 1];
+        if current.len() <= config.coarsest_size.max(1) as usize {
+            break;
+        }
+        let (coarse, parent) = current.coarsen(&mut rng);
+        if coarse.len() as f32 > current.len() as f32 * MIN_SHRINK {
+            break;
+        }
+        levels.push(coarse);
+        parents.push(parent);
+    }
+
+    // Lay out the coarsest level from a random start
+    let total_mass = participants.len() as f32;
+    let coarsest = &levels[levels.len() - 1];
+    let spread = config.simulation.spring_length * total_mass.sqrt();
+    let mut pos_x: Vec<f32> = (0..coarsest.len())
+        .map(|_| rng.range_f32(-spread, spread))
+        .collect();
+    let mut pos_y: Vec<f32> = (0..coarsest.len())
+        .map(|_| rng.range_f32(-spread, spread))
+        .collect();
+    refine(
+        coarsest,
+        &mut pos_x,
+        &mut pos_y,
+        config,
+        config.coarsest_iterations,
+    );
+
+    // Uncoarsen: expand each group around its position and refine
+    for depth in (0..parents.len()).rev() {
+        let level = &levels[depth];
+        let jitter = level_spring_length(level, total_mass, config) * 0.1;
+        let parent = &parents[depth];
+        let (fine_x, fine_y): (Vec<f32>, Vec<f32>) = parent
+            .iter()
+            .map(|&p| {
+                let dx = rng.range_f32(-jitter, jitter);
+                let dy = rng.range_f32(-jitter, jitter);
+                (pos_x[p] + dx, pos_y[p] + dy)
+            })
+            .unzip();
+        pos_x = fine_x;
+        pos_y = fine_y;
+        refine(
+            level,
+            &mut pos_x,
+            &mut pos_y,
+            config,
+            config.level_iterations,
+        );
+    }
+
+    for (index, &slot) in participants.iter().enumerate() {
+        positions[slot * 2] = pos_x[index];
+        positions[slot * 2 + 1] = pos_y[index];
+    }
+
+    positions
+}
+
+/// Spring length for a level: the finest edge length scaled by the square
+/// root of the mean node mass.
+fn level_spring_length(level: &Level, total_mass: f32, config: &MultilevelConfig) -> f32 {
+    config.simulation.spring_length * (total_mass / level.len() as f32).sqrt()
+}
+
+/// Run `ticks` simulation steps on a level in place.
+fn refine(
+    level: &Level,
+    pos_x: &mut Vec<f32>,
+    pos_y: &mut Vec<f32>,
+    config: &MultilevelConfig,
+    ticks: u32,
+) {
+    let n = level.len();
+    let total_mass: f32 = level.mass.iter().sum();
+    let spring_length = level_spring_length(level, total_mass, config);
+    let mut simulation = ForceSimulation::new(SimulationConfig {
+        spring_length,
+        // Keep refinement local when the caller did not clamp displacement
+        max_displacement: if config.simulation.max_displacement > 0.0 {
+            config.simulation.max_displacement
+        } else {
+            spring_length
+        },
+        // Freezing state is per engine slot; levels use their own indices
+        freeze_threshold: 0.0,
+        ..config.simulation.clone()
+    });
+    simulation.set_node_repulsion(
+        level
+            .mass
+            .iter()
+            .map(|&m| m * config.simulation.repulsion)
+            .collect(),
+    );
+
+    let mut frame = SimulationFrame {
+        slots: (0..n).collect(),
+        participants: n,
+        movable: vec![true; n],
+        pos_x: std::mem::take(pos_x),
+        pos_y: std::mem::take(pos_y),
+        vel_x: vec![0.0; n],
+        vel_y: vec![0.0; n],
+        csr: level.frame_csr(),
+        edge_ids: Vec::new(),
+    };
+    for _ in 0..ticks {
+        simulation.step(&mut frame);
+    }
+    *pos_x = frame.pos_x;
+    *pos_y = frame.pos_y;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn grid_edges(side: u32) -> Vec<(u32, u32)> {
+        let right = (0..side * side)
+            .filter(|i| i % side + 1 < side)
+            .map(|i| (i, i + 1));
+        let down = (0..side * (side - 1)).map(|i| (i, i + side));
+        right.chain(down).collect()
+    }
+
+    fn level_from(node_count: usize, edges: &[(u32, u32)]) -> Level {
+        let graph = UndirectedCsr::from_csr(&csr_from_edges(node_count, edges), node_count);
+        Level {
+            neighbors: (0..node_count)
+                .map(|i| {
+                    graph
+                        .neighbors(i)
+                        .iter()
+                        .map(|&n| (n as usize, 1.0))
+                        .collect()
+                })
+                .collect(),
+            mass: vec![1.0; node_count],
+        }
+    }
+
+    #[test]
+    fn test_coarsen_preserves_mass_and_shrinks() {
+        let level = level_from(100, &grid_edges(10));
+        let (coarse, parent) = level.coarsen(&mut SeededRng::new(3));
+
+        assert!(coarse.len() <= 55, "Grid barely shrank: {}", coarse.len());
+        assert_eq!(coarse.mass.iter().sum::<f32>(), 100.0);
+        assert_eq!(parent.len(), 100);
+        assert!(parent.iter().all(|&p| p < coarse.len()));
+    }
+
+    #[test]
+    fn test_coarsen_collapses_star() {
+        // Matching alone would only pair the hub with one leaf
+        let edges: Vec<(u32, u32)> = (1..20).map(|leaf| (0, leaf)).collect();
+        let level = level_from(20, &edges);
+        let (coarse, _) = level.coarsen(&mut SeededRng::new(3));
+
+        assert_eq!(coarse.len(), 1);
+        assert_eq!(coarse.mass[0], 20.0);
+    }
+
+    #[test]
+    fn test_grid_unfolds() {
+        let side = 30;
+        let n = (side * side) as usize;
+        let csr = csr_from_edges(n, &grid_edges(side));
+        let config = MultilevelConfig {
+            coarsest_size: 20,
+            ..Default::default()
+        };
+        let positions = compute_multilevel_layout(&csr, n, &config);
+
+        assert!(positions.iter().all(|v| v.is_finite()));
+        // An untangled grid keeps opposite corners far apart
+        let corner = |i: usize| (positions[i * 2], positions[i * 2 + 1]);
+        let ((ax, ay), (bx, by)) = (corner(0), corner(n - 1));
+        let ((cx, cy), (dx, dy)) = (corner(side as usize - 1), corner(n - side as usize));
+        let spring = config.simulation.spring_length;
+        assert!((ax - bx).hypot(ay - by) > spring * 10.0);
+        assert!((cx - dx).hypot(cy - dy) > spring * 10.0);
+    }
+
+    #[test]
+    fn test_isolated_nodes_and_determinism() {
+        let csr = csr_from_edges(6, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let config = MultilevelConfig {
+            coarsest_size: 2,
+            ..Default::default()
+        };
+        let a = compute_multilevel_layout(&csr, 6, &config);
+        let b = compute_multilevel_layout(&csr, 6, &config);
+
+        assert_eq!(a, b);
+        assert_eq!(a[10], SENTINEL);
+        assert!(a[..10].iter().all(|v| v.is_finite()));
+    }
+}
@@ -0,0 +1,358 @@
+//! Orthogonal layout for schematic diagrams.
+//!
+//! Places nodes on a square grid and routes every edge as a chain of
+//! horizontal and vertical segments, as in ER diagrams and circuit
+//! schematics. Meant for small graphs (up to about a thousand nodes).
+//!
+//! # Algorithm
+//!
+//! 1. **Placement.** Compute an exact stress layout with the grid spacing as
+//!    edge length, then snap nodes to grid points from the highest degree
+//!    down; a node whose nearest grid point is taken gets the closest free
+//!    one.
+//! 2. **Routing.** Each edge takes the first route that does not pass
+//!    through another node:
+//!    - a straight segment when both ends share a row or column,
+//!    - an L-shape with one bend (either corner),
+//!    - otherwise a channel route through the half-grid lines next to each
+//!      endpoint. Nodes only sit on grid points, so those lines are always
+//!      free and this route always exists (up to four bends).
+//!
+//! Nodes without edges are not part of the layout and get the sentinel.
+
+use std::collections::HashSet;
+
+use super::SENTINEL;
+use super::distance::{UndirectedCsr, csr_pairs};
+use super::stress::{StressLayoutConfig, compute_stress_layout};
+use crate::rng::DEFAULT_SEED;
+
+/// Configuration for the orthogonal layout.
+pub struct OrthogonalLayoutConfig {
+    /// Distance between neighboring grid points (default: 80.0).
+    pub grid_spacing: f32,
+    /// Seed for the underlying stress placement.
+    pub seed: u64,
+}
+
+impl Default for OrthogonalLayoutConfig {
+    fn default() -> Self {
+        Self {
+            grid_spacing: 80.0,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Result of the orthogonal layout.
+pub struct OrthogonalLayout {
+    /// Interleaved node positions [x0, y0, x1, y1, ...] (sentinel if unplaced).
+    pub positions: Vec<f32>,
+    /// Bend points of every edge, in CSR target order. Straight edges,
+    /// self loops and edges with an unplaced endpoint have none.
+    pub bends: Vec<Vec<(f32, f32)>>,
+}
+
+impl OrthogonalLayout {
+    /// Flatten into a single buffer:
+    /// `[x0, y0, ..., x_{n-1}, y_{n-1}, edge_count,
+    ///   bend_count_0, bx, by, ..., bend_count_1, bx, by, ...]`
+    pub fn to_flat(&self) -> Vec<f32> {
+        let bend_values: usize = self.bends.iter().map(|b| 1 + b.len() * 2).sum();
+        let mut flat = Vec::with_capacity(self.positions.len() + 1 + bend_values);
+        flat.extend_from_slice(&self.positions);
+        flat.push(self.bends.len() as f32);
+        for bends in &self.bends {
+            flat.push(bends.len() as f32);
+            flat.extend(bends.iter().flat_map(|&(x, y)| [x, y]));
+        }
+        flat
+    }
+}
+
+/// Grid cell (column, row).
+type Cell = (i32, i32);
+
+/// Compute an orthogonal layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// Node positions (centered on the origin, on a grid of `grid_spacing`) and
+/// per-edge bend points in CSR target order.
+pub fn compute_orthogonal_layout(
+    csr: &[u32],
+    node_count: usize,
+    config: &OrthogonalLayoutConfig,
+) -> OrthogonalLayout {
+    let spacing = config.grid_spacing.max(f32::EPSILON);
+    let stress = compute_stress_layout(
+        csr,
+        node_count,
+        &StressLayoutConfig {
+            edge_length: spacing,
+            pivots: 0,
+            seed: config.seed,
+            ..StressLayoutConfig::default()
+        },
+    );
+
+    // Snap to the grid, highest degree first so hubs keep their spot
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let mut order: Vec<usize> = (0..node_count)
+        .filter(|&i| stress[i * 2] != SENTINEL)
+        .collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(graph.degree(i)));
+
+    let mut cells: Vec<Option<Cell>> = vec![None; node_count];
+    let mut occupied: HashSet<Cell> = HashSet::new();
+    for &node in &order {
+        let cell = nearest_free(
+            &occupied,
+            stress[node * 2] / spacing,
+            stress[node * 2 + 1] / spacing,
+        );
+        occupied.insert(cell);
+        cells[node] = Some(cell);
+    }
+
+    // Center the occupied grid on the origin
+    let (min_c, max_c) = bounds(occupied.iter().map(|c| c.0));
+    let (min_r, max_r) = bounds(occupied.iter().map(|c| c.1));
+    let shift_x = (min_c + max_c) as f32 * 0.5 * spacing;
+    let shift_y = (min_r + max_r) as f32 * 0.5 * spacing;
+
+    let mut positions = vec![SENTINEL; node_count * 2];
+    for (node, cell) in cells.iter().enumerate() {
+        if let Some((c, r)) = *cell {
+            positions[node * 2] = c as f32 * spacing - shift_x;
+            positions[node * 2 + 1] = r as f32 * spacing - shift_y;
+        }
+    }
+
+    // Routes are computed on the doubled grid, where half-grid lines are odd
+    let half = spacing * 0.5;
+    let bends = csr_pairs(csr, node_count)
+        .map(|(src, tgt)| {
+            match (
+                cells.get(src).copied().flatten(),
+                cells.get(tgt).copied().flatten(),
+            ) {
+                (Some(s), Some(t)) if src != tgt => route(&occupied, s, t)
+                    .into_iter()
+                    .map(|(x, y)| (x as f32 * half - shift_x, y as f32 * half - shift_y))
+                    .collect(),
+                _ => Vec::new(),
+            }
+        })
+        .collect();
+
+    OrthogonalLayout { positions, bends }
+}
+
+/// Minimum and maximum of a non-empty set of coordinates (0, 0 if empty).
+fn bounds(values: impl Iterator<Item = i32>) -> (i32, i32) {
+    values
+        .fold(None, |acc: Option<(i32, i32)>, v| match acc {
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+            None => Some((v, v)),
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Free grid cell closest to the fractional grid position (fx, fy).
+///
+/// Searches square rings of growing radius around the rounded position and
+/// returns the closest free cell of the first ring that has one.
+fn nearest_free(occupied: &HashSet<Cell>, fx: f32, fy: f32) -> Cell {
+    let (cx, cy) = (fx.round() as i32, fy.round() as i32);
+    let distance = |&(c, r): &Cell| (c as f32 - fx).powi(2) + (r as f32 - fy).powi(2);
+    let mut radius = 0;
+    loop {
+        let ring = (-radius..=radius)
+            .flat_map(|d| [(d, -radius), (d, radius), (-radius, d), (radius, d)])
+            .map(|(dc, dr)| (cx + dc, cy + dr))
+            .filter(|cell| !occupied.contains(cell));
+        if let Some(cell) = ring.min_by(|a, b| distance(a).total_cmp(&distance(b))) {
+            return cell;
+        }
+        radius += 1;
+    }
+}
+
+/// Check that no node sits strictly between two cells on a shared row or
+/// column.
+fn clear_between(occupied: &HashSet<Cell>, a: Cell, b: Cell) -> bool {
+    let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs());
+    let (dc, dr) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+    (1..steps).all(|k| !occupied.contains(&(a.0 + dc * k, a.1 + dr * k)))
+}
+
+/// Bend points of an edge from cell `s` to cell `t`, in doubled grid
+/// coordinates.
+fn route(occupied: &HashSet<Cell>, s: Cell, t: Cell) -> Vec<Cell> {
+    let aligned = s.0 == t.0 || s.1 == t.1;
+    if aligned && clear_between(occupied, s, t) {
+        return Vec::new();
+    }
+    if !aligned {
+        for corner in [(t.0, s.1), (s.0, t.1)] {
+            if !occupied.contains(&corner)
+                && clear_between(occupied, s, corner)
+                && clear_between(occupied, corner, t)
+            {
+                return vec![(corner.0 * 2, corner.1 * 2)];
+            }
+        }
+    }
+
+    // Channel route: leave `s` vertically onto a half-row, cross to the
+    // half-column beside `t`, then enter `t` horizontally from a half-row
+    let toward = |from: i32, to: i32| if to == from { 1 } else { (to - from).signum() };
+    let row_s = s.1 * 2 + toward(s.1, t.1);
+    let col_t = t.0 * 2 + toward(t.0, s.0);
+    let row_t = t.1 * 2 + toward(t.1, s.1);
+    let path = [
+        (s.0 * 2, s.1 * 2),
+        (s.0 * 2, row_s),
+        (col_t, row_s),
+        (col_t, row_t),
+        (t.0 * 2, row_t),
+        (t.0 * 2, t.1 * 2),
+    ];
+    simplify(&path)
+}
+
+/// Drop repeated and collinear points from an orthogonal path and return its
+/// interior points.
+fn simplify(path: &[Cell]) -> Vec<Cell> {
+    let mut points: Vec<Cell> = Vec::with_capacity(path.len());
+    for &p in path {
+        if points.last() == Some(&p) {
+            continue;
+        }
+        let collinear = points.len() >= 2 && {
+            let (a, b) = (points[points.len() - 2], points[points.len() - 1]);
+            (a.0 == b.0 && b.0 == p.0) || (a.1 == b.1 && b.1 == p.1)
+        };
+        if collinear {
+            points.pop();
+        }
+        points.push(p);
+    }
+    points[1..points.len() - 1].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn point(layout: &OrthogonalLayout, node: usize) -> (f32, f32) {
+        (layout.positions[node * 2], layout.positions[node * 2 + 1])
+    }
+
+    /// Full polyline of the k-th CSR edge.
+    fn polyline(layout: &OrthogonalLayout, edges: &[(u32, u32)], k: usize) -> Vec<(f32, f32)> {
+        let (src, tgt) = edges[k];
+        let mut line = vec![point(layout, src as usize)];
+        line.extend(&layout.bends[k]);
+        line.push(point(layout, tgt as usize));
+        line
+    }
+
+    /// Whether point `p` lies strictly inside axis-aligned segment a-b.
+    fn on_segment(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> bool {
+        let eps = 1e-3;
+        let within = |v: f32, lo: f32, hi: f32| v > lo.min(hi) + eps && v < lo.max(hi) - eps;
+        ((p.0 - a.0).abs() < eps && (a.0 - b.0).abs() < eps && within(p.1, a.1, b.1))
+            || ((p.1 - a.1).abs() < eps && (a.1 - b.1).abs() < eps && within(p.0, a.0, b.0))
+    }
+
+    fn check_layout(node_count: usize, edges: &[(u32, u32)]) -> OrthogonalLayout {
+        let csr = csr_from_edges(node_count, edges);
+        let layout =
+            compute_orthogonal_layout(&csr, node_count, &OrthogonalLayoutConfig::default());
+        // csr_from_edges keeps edges grouped by source in input order
+        let mut sorted = edges.to_vec();
+        sorted.sort_by_key(|&(src, _)| src);
+        assert_eq!(layout.bends.len(), sorted.len());
+
+        for k in 0..sorted.len() {
+            let line = polyline(&layout, &sorted, k);
+            for w in line.windows(2) {
+                let axis_aligned = (w[0].0 - w[1].0).abs() < 1e-3 || (w[0].1 - w[1].1).abs() < 1e-3;
+                assert!(axis_aligned, "Edge {k} has a diagonal segment: {line:?}");
+                let crossed = (0..node_count).find(|&n| on_segment(point(&layout, n), w[0], w[1]));
+                assert!(
+                    crossed.is_none(),
+                    "Edge {k} passes through {crossed:?}: {line:?}"
+                );
+            }
+        }
+        layout
+    }
+
+    #[test]
+    fn test_nodes_on_distinct_grid_points() {
+        let edges: Vec<(u32, u32)> = (1..9).map(|leaf| (0, leaf)).collect();
+        let layout = check_layout(9, &edges);
+
+        let spacing = OrthogonalLayoutConfig::default().grid_spacing;
+        let origin = point(&layout, 0);
+        let mut cells = HashSet::new();
+        for node in 0..9 {
+            let (x, y) = point(&layout, node);
+            let (c, r) = ((x - origin.0) / spacing, (y - origin.1) / spacing);
+            assert!((c - c.round()).abs() < 1e-3 && (r - r.round()).abs() < 1e-3);
+            assert!(
+                cells.insert((c.round() as i32, r.round() as i32)),
+                "Shared grid point"
+            );
+        }
+    }
+
+    #[test]
+    fn test_routes_are_orthogonal_and_avoid_nodes() {
+        // Two triangles joined by a path, plus a chord
+        let edges = [
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 4),
+            (0, 6),
+        ];
+        check_layout(7, &edges);
+    }
+
+    #[test]
+    fn test_channel_route_around_blocking_nodes() {
+        // s at (0,0), t at (2,2); both corners and the straight lines blocked
+        let occupied: HashSet<Cell> = [(0, 0), (2, 2), (2, 0), (0, 2)].into_iter().collect();
+        let bends = route(&occupied, (0, 0), (2, 2));
+
+        assert_eq!(bends, vec![(0, 1), (3, 1), (3, 3), (4, 3)]);
+    }
+
+    #[test]
+    fn test_flat_output_and_isolated_nodes() {
+        let csr = csr_from_edges(3, &[(0, 1)]);
+        let layout = compute_orthogonal_layout(&csr, 3, &OrthogonalLayoutConfig::default());
+        let flat = layout.to_flat();
+
+        assert_eq!(layout.positions[4], SENTINEL);
+        // 3 nodes * 2 + edge count + one edge with zero bends
+        assert_eq!(flat.len(), 8);
+        assert_eq!(flat[6], 1.0);
+        assert_eq!(flat[7], 0.0);
+    }
+}
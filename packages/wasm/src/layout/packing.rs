@@ -0,0 +1,380 @@
+//! Circle packing primitives.
+//!
+//! Two building blocks for nested circle-packing layouts, after d3-hierarchy:
+//!
+//! - [`pack_siblings`] places circles so none overlap, each new circle
+//!   tangent to two circles of the *front chain* (the outer boundary of
+//!   the circles placed so far), at the spot closest to the centroid. The
+//!   packing comes out tight and round.
+//! - [`enclose`] finds the smallest circle enclosing a set of circles with
+//!   Welzl's randomized algorithm, in expected linear time.
+//!
+//! Computations run in f64; nesting many levels in f32 accumulates visible
+//! error.
+//!
+//! # References
+//!
+//! - Wang et al., "Visualization of Large Hierarchical Data by Circle
+//!   Packing" (2006)
+//! - Welzl, "Smallest enclosing disks (balls and ellipsoids)" (1991)
+
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+/// A circle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub x: f64,
+    pub y: f64,
+    pub r: f64,
+}
+
+impl Circle {
+    pub fn new(x: f64, y: f64, r: f64) -> Self {
+        Self { x, y, r }
+    }
+}
+
+/// Pack circles around the origin without overlap, in the given order
+/// (largest first packs tightest). Only the radii are read.
+///
+/// Returns the enclosing circle of the packing; the circles are moved so
+/// it is centered on the origin.
+pub fn pack_siblings(circles: &mut [Circle]) -> Circle {
+    let n = circles.len();
+    if n == 0 {
+        return Circle::new(0.0, 0.0, 0.0);
+    }
+    circles[0].x = 0.0;
+    circles[0].y = 0.0;
+    if n == 1 {
+        return Circle::new(0.0, 0.0, circles[0].r);
+    }
+    circles[0].x = -circles[1].r;
+    circles[1].x = circles[0].r;
+    circles[1].y = 0.0;
+    if n == 2 {
+        // Already centered: together they span [-(r0 + r1), r0 + r1]
+        return Circle::new(0.0, 0.0, circles[0].r + circles[1].r);
+    }
+    circles[2] = place(circles[1], circles[0], circles[2]);
+
+    // Front chain as a circular doubly linked list over circle indices
+    let mut next = vec![0usize; n];
+    let mut previous = vec![0usize; n];
+    let (mut a, mut b) = (0usize, 1usize);
+    (next[0], previous[2]) = (1, 1);
+    (next[1], previous[0]) = (2, 2);
+    (next[2], previous[1]) = (0, 0);
+
+    let mut i = 3;
+    while i < n {
+        circles[i] = place(circles[a], circles[b], circles[i]);
+        match closest_intersection(circles, &next, &previous, (a, b), i) {
+            // Drop the chain between a and the intersecting circle (or it
+            // and b) and retry
+            Some(Side::Ahead(j)) => {
+                b = j;
+                (next[a], previous[b]) = (b, a);
+                continue;
+            }
+            Some(Side::Behind(k)) => {
+                a = k;
+                (next[a], previous[b]) = (b, a);
+                continue;
+            }
+            None => {}
+        }
+
+        // Insert i between a and b, then find the chain pair closest to
+        // the centroid to place the next circle against
+        (previous[i], next[i]) = (a, b);
+        (next[a], previous[b]) = (i, i);
+        b = i;
+        let mut best = score(circles, &next, a);
+        let mut c = next[b];
+        while c != b {
+            let value = score(circles, &next, c);
+            if value < best {
+                (a, best) = (c, value);
+            }
+            c = next[c];
+        }
+        b = next[a];
+        i += 1;
+    }
+
+    let mut chain = vec![circles[b]];
+    let mut c = next[b];
+    while c != b {
+        chain.push(circles[c]);
+        c = next[c];
+    }
+    let enclosing = enclose(&chain);
+    recenter(circles, enclosing)
+}
+
+/// Smallest circle enclosing all `circles` (Welzl's algorithm over a
+/// seeded shuffle, so results are reproducible).
+pub fn enclose(circles: &[Circle]) -> Circle {
+    let mut shuffled = circles.to_vec();
+    SeededRng::new(DEFAULT_SEED).shuffle(&mut shuffled);
+
+    let mut basis: Vec<Circle> = Vec::new();
+    let mut enclosing: Option<Circle> = None;
+    let mut i = 0;
+    while i < shuffled.len() {
+        let p = shuffled[i];
+        if enclosing.is_some_and(|e| encloses_weak(e, p)) {
+            i += 1;
+            continue;
+        }
+        let Some(extended) = extend_basis(&basis, p) else {
+            // Only reachable through rounding; fall back to a loose bound
+            return bounding_circle(circles);
+        };
+        basis = extended;
+        enclosing = Some(enclose_basis(&basis));
+        i = 0;
+    }
+    enclosing.unwrap_or(Circle::new(0.0, 0.0, 0.0))
+}
+
+/// Move `circles` so `enclosing` is centered on the origin.
+fn recenter(circles: &mut [Circle], enclosing: Circle) -> Circle {
+    for circle in circles.iter_mut() {
+        circle.x -= enclosing.x;
+        circle.y -= enclosing.y;
+    }
+    Circle::new(0.0, 0.0, enclosing.r)
+}
+
+/// Where along the front chain a placed circle first hits another.
+enum Side {
+    /// A circle after `b`.
+    Ahead(usize),
+    /// A circle before `a`.
+    Behind(usize),
+}
+
+/// The front-chain circle intersecting circle `c` that is closest to the
+/// pair (a, b) by distance along the chain, walking both ways at once.
+fn closest_intersection(
+    circles: &[Circle],
+    next: &[usize],
+    previous: &[usize],
+    (a, b): (usize, usize),
+    c: usize,
+) -> Option<Side> {
+    let (mut j, mut k) = (next[b], previous[a]);
+    let (mut sj, mut sk) = (circles[b].r, circles[a].r);
+    loop {
+        if sj <= sk {
+            if intersects(circles[j], circles[c]) {
+                return Some(Side::Ahead(j));
+            }
+            sj += circles[j].r;
+            j = next[j];
+        } else {
+            if intersects(circles[k], circles[c]) {
+                return Some(Side::Behind(k));
+            }
+            sk += circles[k].r;
+            k = previous[k];
+        }
+        if j == next[k] {
+            return None;
+        }
+    }
+}
+
+/// `c` moved to touch both `a` and `b`, on the left of b → a.
+fn place(b: Circle, a: Circle, c: Circle) -> Circle {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let d2 = dx * dx + dy * dy;
+    if d2 <= 0.0 {
+        return Circle::new(a.x + c.r, a.y, c.r);
+    }
+    let a2 = (a.r + c.r) * (a.r + c.r);
+    let b2 = (b.r + c.r) * (b.r + c.r);
+    if a2 > b2 {
+        let x = (d2 + b2 - a2) / (2.0 * d2);
+        let y = (b2 / d2 - x * x).max(0.0).sqrt();
+        Circle::new(b.x - x * dx - y * dy, b.y - x * dy + y * dx, c.r)
+    } else {
+        let x = (d2 + a2 - b2) / (2.0 * d2);
+        let y = (a2 / d2 - x * x).max(0.0).sqrt();
+        Circle::new(a.x + x * dx - y * dy, a.y + x * dy + y * dx, c.r)
+    }
+}
+
+fn intersects(a: Circle, b: Circle) -> bool {
+    let dr = a.r + b.r - 1e-6;
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dr > 0.0 && dr * dr > dx * dx + dy * dy
+}
+
+/// Squared distance from the origin of the weighted midpoint between the
+/// chain circle `node` and the next one.
+fn score(circles: &[Circle], next: &[usize], node: usize) -> f64 {
+    let (a, b) = (circles[node], circles[next[node]]);
+    let ab = a.r + b.r;
+    let dx = (a.x * b.r + b.x * a.r) / ab;
+    let dy = (a.y * b.r + b.y * a.r) / ab;
+    dx * dx + dy * dy
+}
+
+/// The basis of the smallest circle enclosing `basis` and `p`, given that
+/// `p` lies outside the current one.
+fn extend_basis(basis: &[Circle], p: Circle) -> Option<Vec<Circle>> {
+    if encloses_weak_all(p, basis) {
+        return Some(vec![p]);
+    }
+    for &b in basis {
+        if encloses_not(p, b) && encloses_weak_all(enclose_basis2(b, p), basis) {
+            return Some(vec![b, p]);
+        }
+    }
+    for (i, &bi) in basis.iter().enumerate() {
+        for &bj in &basis[i + 1..] {
+            let fits = encloses_not(enclose_basis2(bi, bj), p)
+                && encloses_not(enclose_basis2(bi, p), bj)
+                && encloses_not(enclose_basis2(bj, p), bi)
+                && encloses_weak_all(enclose_basis3(bi, bj, p), basis);
+            if fits {
+                return Some(vec![bi, bj, p]);
+            }
+        }
+    }
+    None
+}
+
+/// Whether `a` fails to contain `b`.
+fn encloses_not(a: Circle, b: Circle) -> bool {
+    let dr = a.r - b.r;
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dr < 0.0 || dr * dr < dx * dx + dy * dy
+}
+
+/// Whether `a` contains `b`, with a little slack for rounding.
+fn encloses_weak(a: Circle, b: Circle) -> bool {
+    let dr = a.r - b.r + a.r.max(b.r).max(1.0) * 1e-9;
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dr > 0.0 && dr * dr > dx * dx + dy * dy
+}
+
+fn encloses_weak_all(a: Circle, basis: &[Circle]) -> bool {
+    basis.iter().all(|&b| encloses_weak(a, b))
+}
+
+fn enclose_basis(basis: &[Circle]) -> Circle {
+    match *basis {
+        [a] => a,
+        [a, b] => enclose_basis2(a, b),
+        [a, b, c] => enclose_basis3(a, b, c),
+        _ => bounding_circle(basis),
+    }
+}
+
+/// Smallest circle touching `a` and `b` from outside.
+fn enclose_basis2(a: Circle, b: Circle) -> Circle {
+    let (x21, y21, r21) = (b.x - a.x, b.y - a.y, b.r - a.r);
+    let l = x21.hypot(y21);
+    if l <= 0.0 {
+        return if a.r >= b.r { a } else { b };
+    }
+    Circle::new(
+        (a.x + b.x + x21 / l * r21) * 0.5,
+        (a.y + b.y + y21 / l * r21) * 0.5,
+        (l + a.r + b.r) * 0.5,
+    )
+}
+
+/// Circle touching `a`, `b` and `c` from outside (Apollonius' problem).
+fn enclose_basis3(a: Circle, b: Circle, c: Circle) -> Circle {
+    let (x1, y1, r1) = (a.x, a.y, a.r);
+    let (a2, a3) = (x1 - b.x, x1 - c.x);
+    let (b2, b3) = (y1 - b.y, y1 - c.y);
+    let (c2, c3) = (b.r - r1, c.r - r1);
+    let d1 = x1 * x1 + y1 * y1 - r1 * r1;
+    let d2 = d1 - b.x * b.x - b.y * b.y + b.r * b.r;
+    let d3 = d1 - c.x * c.x - c.y * c.y + c.r * c.r;
+    let ab = a3 * b2 - a2 * b3;
+    let xa = (b2 * d3 - b3 * d2) / (ab * 2.0) - x1;
+    let xb = (b3 * c2 - b2 * c3) / ab;
+    let ya = (a3 * d2 - a2 * d3) / (ab * 2.0) - y1;
+    let yb = (a2 * c3 - a3 * c2) / ab;
+    let qa = xb * xb + yb * yb - 1.0;
+    let qb = 2.0 * (r1 + xa * xb + ya * yb);
+    let qc = xa * xa + ya * ya - r1 * r1;
+    let r = -(if qa.abs() > 1e-6 {
+        (qb + (qb * qb - 4.0 * qa * qc).sqrt()) / (2.0 * qa)
+    } else {
+        qc / qb
+    });
+    Circle::new(x1 + xa + xb * r, y1 + ya + yb * r, r)
+}
+
+/// A circle around the centroid enclosing every circle; not minimal.
+fn bounding_circle(circles: &[Circle]) -> Circle {
+    let n = circles.len().max(1) as f64;
+    let x = circles.iter().map(|c| c.x).sum::<f64>() / n;
+    let y = circles.iter().map(|c| c.y).sum::<f64>() / n;
+    let r = circles
+        .iter()
+        .map(|c| (c.x - x).hypot(c.y - y) + c.r)
+        .fold(0.0, f64::max);
+    Circle::new(x, y, r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_siblings_do_not_overlap() {
+        let mut rng = SeededRng::new(7);
+        for n in [1, 2, 3, 60] {
+            let mut circles: Vec<Circle> = (0..n)
+                .map(|_| Circle::new(0.0, 0.0, rng.range_f32(1.0, 20.0) as f64))
+                .collect();
+            circles.sort_by(|a, b| b.r.total_cmp(&a.r));
+            let enclosing = pack_siblings(&mut circles);
+
+            assert_eq!((enclosing.x, enclosing.y), (0.0, 0.0));
+            for (i, a) in circles.iter().enumerate() {
+                assert!(a.x.hypot(a.y) + a.r <= enclosing.r + 1e-6, "{n}: circle {i} sticks out");
+                let apart = |b: &Circle| (a.x - b.x).hypot(a.y - b.y) >= a.r + b.r - 1e-6;
+                assert!(circles[i + 1..].iter().all(apart), "{n}: circle {i} overlaps");
+            }
+            // Many circles pack far tighter than the area bound with 82%
+            // packing efficiency
+            if n > 3 {
+                let area: f64 = circles.iter().map(|c| c.r * c.r).sum();
+                assert!(enclosing.r < (area / 0.82).sqrt() * 1.25, "radius {}", enclosing.r);
+            }
+        }
+    }
+
+    #[test]
+    fn test_enclose_is_minimal() {
+        // Two unit circles 4 apart plus a small one inside their hull
+        let circles = [
+            Circle::new(-2.0, 0.0, 1.0),
+            Circle::new(2.0, 0.0, 1.0),
+            Circle::new(0.0, 0.5, 0.5),
+        ];
+        let e = enclose(&circles);
+        assert!(e.x.abs() < 1e-9 && e.y.abs() < 1e-9);
+        assert!((e.r - 3.0).abs() < 1e-9);
+
+        // Three mutually touching unit circles
+        let h = 3.0f64.sqrt();
+        let triangle = [
+            Circle::new(-1.0, 0.0, 1.0),
+            Circle::new(1.0, 0.0, 1.0),
+            Circle::new(0.0, h, 1.0),
+        ];
+        let e = enclose(&triangle);
+        assert!((e.r - (1.0 + 2.0 / h)).abs() < 1e-9, "radius {}", e.r);
+    }
+}
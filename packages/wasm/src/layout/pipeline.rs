@@ -0,0 +1,229 @@
+//! Composable layout pipeline.
+//!
+//! Chains layout stages over one shared position buffer, e.g.
+//! spectral init → stress refine → overlap removal → fit to bounds, so
+//! positions never have to round-trip through JS between steps.
+//!
+//! Stages come from JS as a list of descriptors tagged by `type`, with
+//! camelCase options that fall back to each layout's defaults:
+//!
+//! ```text
+//! [
+//!   { type: "spectral", edgeLength: 60 },
+//!   { type: "stress", iterations: 50 },
+//!   { type: "removeOverlaps", radius: 8 },
+//!   { type: "fitToBounds", minX: 0, minY: 0, maxX: 800, maxY: 600 },
+//! ]
+//! ```
+//!
+//! Layout stages (spectral, pivot MDS) replace the buffer and give nodes
+//! outside their layout the sentinel; refinement and post-processing stages
+//! (stress, overlap removal, fit to bounds) work on the buffer as they find
+//! it and skip sentinel entries.
+
+use serde::Deserialize;
+
+use super::SENTINEL;
+use super::fit::fit_to_bounds;
+use super::overlap::{OverlapRemovalConfig, remove_overlaps};
+use super::pivot_mds::{PivotMdsConfig, compute_pivot_mds_layout};
+use super::spectral::{SpectralLayoutConfig, compute_spectral_layout};
+use super::stress::{StressLayoutConfig, refine_stress_layout};
+
+/// One pipeline stage. Unset options use the stage's default configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LayoutStage {
+    /// Replace positions with a spectral layout.
+    Spectral {
+        edge_length: Option<f32>,
+        max_iterations: Option<u32>,
+    },
+    /// Replace positions with a pivot MDS layout.
+    PivotMds {
+        edge_length: Option<f32>,
+        pivots: Option<u32>,
+    },
+    /// Refine the current positions with stress majorization.
+    Stress {
+        edge_length: Option<f32>,
+        iterations: Option<u32>,
+        pivots: Option<u32>,
+    },
+    /// Push overlapping nodes apart.
+    RemoveOverlaps {
+        radius: Option<f32>,
+        iterations: Option<u32>,
+    },
+    /// Scale and translate the layout to fit a rect, keeping its aspect ratio.
+    FitToBounds {
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+        padding: Option<f32>,
+    },
+}
+
+/// A sequence of layout stages sharing one position buffer.
+pub struct LayoutPipeline {
+    stages: Vec<LayoutStage>,
+    /// Seed passed to every randomized stage.
+    seed: u64,
+}
+
+impl LayoutPipeline {
+    /// Create a pipeline running `stages` in order.
+    pub fn new(stages: Vec<LayoutStage>, seed: u64) -> Self {
+        Self { stages, seed }
+    }
+
+    /// The stages of this pipeline.
+    pub fn stages(&self) -> &[LayoutStage] {
+        &self.stages
+    }
+
+    /// Run every stage on `positions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+    /// * `node_count` - Total number of node slots
+    /// * `positions` - Starting positions [x0, y0, x1, y1, ...]; resized to
+    ///   `node_count` nodes, new entries get the sentinel
+    pub fn run(&self, csr: &[u32], node_count: usize, positions: &mut Vec<f32>) {
+        positions.resize(node_count * 2, SENTINEL);
+        for stage in &self.stages {
+            self.run_stage(stage, csr, node_count, positions);
+        }
+    }
+
+    /// Apply a single stage to the shared buffer.
+    fn run_stage(
+        &self,
+        stage: &LayoutStage,
+        csr: &[u32],
+        node_count: usize,
+        positions: &mut Vec<f32>,
+    ) {
+        match *stage {
+            LayoutStage::Spectral {
+                edge_length,
+                max_iterations,
+            } => {
+                let defaults = SpectralLayoutConfig::default();
+                let config = SpectralLayoutConfig {
+                    edge_length: edge_length.unwrap_or(defaults.edge_length),
+                    max_iterations: max_iterations.unwrap_or(defaults.max_iterations),
+                    seed: self.seed,
+                    ..defaults
+                };
+                *positions = compute_spectral_layout(csr, node_count, &config);
+            }
+            LayoutStage::PivotMds {
+                edge_length,
+                pivots,
+            } => {
+                let defaults = PivotMdsConfig::default();
+                let config = PivotMdsConfig {
+                    edge_length: edge_length.unwrap_or(defaults.edge_length),
+                    pivots: pivots.unwrap_or(defaults.pivots),
+                    seed: self.seed,
+                };
+                *positions = compute_pivot_mds_layout(csr, node_count, &config);
+            }
+            LayoutStage::Stress {
+                edge_length,
+                iterations,
+                pivots,
+            } => {
+                let defaults = StressLayoutConfig::default();
+                let config = StressLayoutConfig {
+                    edge_length: edge_length.unwrap_or(defaults.edge_length),
+                    iterations: iterations.unwrap_or(defaults.iterations),
+                    pivots: pivots.unwrap_or(defaults.pivots),
+                    seed: self.seed,
+                    ..defaults
+                };
+                *positions = refine_stress_layout(csr, node_count, positions, &config);
+            }
+            LayoutStage::RemoveOverlaps { radius, iterations } => {
+                let defaults = OverlapRemovalConfig::default();
+                let config = OverlapRemovalConfig {
+                    radius: radius.unwrap_or(defaults.radius),
+                    iterations: iterations.unwrap_or(defaults.iterations),
+                };
+                remove_overlaps(positions, &config);
+            }
+            LayoutStage::FitToBounds {
+                min_x,
+                min_y,
+                max_x,
+                max_y,
+                padding,
+            } => {
+                fit_to_bounds(
+                    positions,
+                    (min_x, min_y, max_x, max_y),
+                    padding.unwrap_or(0.0),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    #[test]
+    fn test_pipeline_chains_stages_on_shared_buffer() {
+        // Two triangles joined by an edge, plus an isolated node 6
+        let csr = csr_from_edges(7, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)]);
+        let pipeline = LayoutPipeline::new(
+            vec![
+                LayoutStage::Spectral {
+                    edge_length: None,
+                    max_iterations: None,
+                },
+                LayoutStage::Stress {
+                    edge_length: Some(40.0),
+                    iterations: Some(50),
+                    pivots: Some(0),
+                },
+                LayoutStage::RemoveOverlaps {
+                    radius: Some(5.0),
+                    iterations: None,
+                },
+                LayoutStage::FitToBounds {
+                    min_x: 0.0,
+                    min_y: 0.0,
+                    max_x: 400.0,
+                    max_y: 400.0,
+                    padding: None,
+                },
+            ],
+            1,
+        );
+        let mut positions = Vec::new();
+        pipeline.run(&csr, 7, &mut positions);
+
+        assert_eq!(positions.len(), 14);
+        assert_eq!(positions[12], SENTINEL);
+        for &v in &positions[..12] {
+            assert!((0.0..=400.0).contains(&v), "{v} outside bounds");
+        }
+        // The layout touches both sides of its longer axis
+        let xs: Vec<f32> = (0..6).map(|i| positions[i * 2]).collect();
+        let ys: Vec<f32> = (0..6).map(|i| positions[i * 2 + 1]).collect();
+        let span = |v: &[f32]| {
+            v.iter().copied().fold(f32::MIN, f32::max) - v.iter().copied().fold(f32::MAX, f32::min)
+        };
+        assert!((span(&xs).max(span(&ys)) - 400.0).abs() < 1e-2);
+    }
+}
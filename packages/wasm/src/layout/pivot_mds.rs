@@ -0,0 +1,314 @@
+//! Pivot MDS layout.
+//!
+//! Embeds the graph by classical multidimensional scaling of shortest-path
+//! distances, approximated through a small set of pivots so it scales to
+//! hundreds of thousands of nodes. The result captures the global shape of
+//! the graph and is a fast initializer for stress or force refinement.
+//!
+//! # Algorithm
+//!
+//! 1. Choose `k` pivots by max–min sampling and BFS from each, giving an
+//!    n × k matrix of hop distances (times `edge_length`).
+//! 2. Double-center the squared distances to get `C`.
+//! 3. Find the two leading eigenvectors `v` of the k × k matrix `CᵀC` by power
+//!    iteration; node coordinates are `C v / √σ`, with `σ` the matching
+//!    singular value so both axes keep their relative extent.
+//! 4. Scale the embedding so the mean edge length equals `edge_length`.
+//!
+//! Cost is O(k·(n + m)) for the BFS runs plus O(n·k²) for `CᵀC`.
+//!
+//! Pairs in different connected components are treated as one hop farther
+//! apart than the longest finite distance. Nodes without edges get the
+//! sentinel.
+//!
+//! # References
+//!
+//! - Brandes, Pich, "Eigensolver Methods for Progressive Multidimensional
+//!   Scaling of Large Data" (2006)
+
+use super::SENTINEL;
+use super::distance::{UNREACHABLE, UndirectedCsr, select_pivots};
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+/// Power iterations used for each eigenvector of the k × k matrix.
+const EIGEN_ITERATIONS: u32 = 200;
+
+/// Configuration for the pivot MDS layout.
+pub struct PivotMdsConfig {
+    /// Mean distance between adjacent nodes after scaling (default: 50.0).
+    pub edge_length: f32,
+    /// Number of pivots (default: 50). Clamped to the number of nodes.
+    pub pivots: u32,
+    /// Seed for the first pivot and the power iteration start vectors.
+    pub seed: u64,
+}
+
+impl Default for PivotMdsConfig {
+    fn default() -> Self {
+        Self {
+            edge_length: 50.0,
+            pivots: 50,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// Compute a pivot MDS layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...],
+/// centered on the origin. Nodes without edges get sentinel values (f32::MAX).
+pub fn compute_pivot_mds_layout(
+    csr: &[u32],
+    node_count: usize,
+    config: &PivotMdsConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; node_count * 2];
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let participants: Vec<usize> = (0..node_count).filter(|&i| graph.degree(i) > 0).collect();
+    if participants.is_empty() {
+        return positions;
+    }
+
+    let mut rng = SeededRng::new(config.seed);
+    let pivot_count = (config.pivots as usize).max(2);
+    let (_, distances) = select_pivots(&graph, &participants, pivot_count, &mut rng);
+    let centered = double_centered(&participants, &distances, config.edge_length as f64);
+    let k = distances.len();
+
+    // CᵀC (k × k, symmetric)
+    let mut gram = vec![0.0f64; k * k];
+    for row in centered.chunks_exact(k) {
+        accumulate_outer(&mut gram, row);
+    }
+
+    let axes = leading_eigenvectors(&gram, k, 2, &mut rng);
+    let mut x = vec![0.0f64; participants.len()];
+    let mut y = vec![0.0f64; participants.len()];
+    for (out, (vector, singular)) in [&mut x, &mut y].into_iter().zip(&axes) {
+        let factor = if *singular > f64::EPSILON {
+            singular.sqrt().recip()
+        } else {
+            0.0
+        };
+        for (value, row) in out.iter_mut().zip(centered.chunks_exact(k)) {
+            *value = factor * row.iter().zip(vector).map(|(c, v)| c * v).sum::<f64>();
+        }
+    }
+
+    // Scale to the requested mean edge length
+    let mut local = vec![u32::MAX; node_count];
+    for (index, &slot) in participants.iter().enumerate() {
+        local[slot] = index as u32;
+    }
+    let local = &local;
+    let (total, edges) = participants
+        .iter()
+        .enumerate()
+        .flat_map(|(a, &slot)| {
+            graph
+                .neighbors(slot)
+                .iter()
+                .map(move |&n| (a, local[n as usize] as usize))
+        })
+        .filter(|&(a, b)| a < b)
+        .fold((0.0f64, 0usize), |(total, edges), (a, b)| {
+            (total + (x[a] - x[b]).hypot(y[a] - y[b]), edges + 1)
+        });
+    let mean_edge = total / edges.max(1) as f64;
+    let scale = if mean_edge > f64::EPSILON {
+        config.edge_length as f64 / mean_edge
+    } else {
+        0.0
+    };
+
+    let count = participants.len() as f64;
+    let cx = x.iter().sum::<f64>() / count;
+    let cy = y.iter().sum::<f64>() / count;
+    for (i, &slot) in participants.iter().enumerate() {
+        positions[slot * 2] = ((x[i] - cx) * scale) as f32;
+        positions[slot * 2 + 1] = ((y[i] - cy) * scale) as f32;
+    }
+
+    positions
+}
+
+/// Double-centered squared distances, row-major n × k (one row per
+/// participant, one column per pivot).
+fn double_centered(participants: &[usize], distances: &[Vec<u32>], edge_length: f64) -> Vec<f64> {
+    let k = distances.len();
+    let n = participants.len();
+    let fallback = distances
+        .iter()
+        .flat_map(|dist| participants.iter().map(move |&i| dist[i]))
+        .filter(|&d| d != UNREACHABLE)
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let mut matrix: Vec<f64> = participants
+        .iter()
+        .flat_map(|&i| {
+            distances.iter().map(move |dist| {
+                let hops = if dist[i] == UNREACHABLE {
+                    fallback
+                } else {
+                    dist[i]
+                };
+                let d = hops as f64 * edge_length;
+                d * d
+            })
+        })
+        .collect();
+
+    let row_means: Vec<f64> = matrix
+        .chunks_exact(k)
+        .map(|row| row.iter().sum::<f64>() / k as f64)
+        .collect();
+    let mut col_means = vec![0.0f64; k];
+    for row in matrix.chunks_exact(k) {
+        col_means
+            .iter_mut()
+            .zip(row)
+            .for_each(|(mean, v)| *mean += v / n as f64);
+    }
+    let grand_mean = row_means.iter().sum::<f64>() / n as f64;
+
+    for (row, row_mean) in matrix.chunks_exact_mut(k).zip(&row_means) {
+        for (value, col_mean) in row.iter_mut().zip(&col_means) {
+            *value = -0.5 * (*value - row_mean - col_mean + grand_mean);
+        }
+    }
+    matrix
+}
+
+/// Add `row ⊗ row` to the k × k matrix `gram`.
+fn accumulate_outer(gram: &mut [f64], row: &[f64]) {
+    let k = row.len();
+    for (a, &ra) in row.iter().enumerate() {
+        gram[a * k..(a + 1) * k]
+            .iter_mut()
+            .zip(row)
+            .for_each(|(g, &rb)| *g += ra * rb);
+    }
+}
+
+/// Leading `count` eigenvectors of a symmetric positive semi-definite k × k
+/// matrix, each with the square root of its eigenvalue (the singular value
+/// of `C`).
+fn leading_eigenvectors(
+    matrix: &[f64],
+    k: usize,
+    count: usize,
+    rng: &mut SeededRng,
+) -> Vec<(Vec<f64>, f64)> {
+    let mut found: Vec<(Vec<f64>, f64)> = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut v: Vec<f64> = (0..k).map(|_| rng.range_f32(-1.0, 1.0) as f64).collect();
+        let mut eigenvalue = 0.0;
+        for _ in 0..EIGEN_ITERATIONS {
+            orthogonalize(&mut v, &found);
+            let mut next: Vec<f64> = matrix
+                .chunks_exact(k)
+                .map(|row| row.iter().zip(&v).map(|(m, x)| m * x).sum())
+                .collect();
+            orthogonalize(&mut next, &found);
+            eigenvalue = next.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if eigenvalue <= f64::EPSILON {
+                break;
+            }
+            next.iter_mut().for_each(|x| *x /= eigenvalue);
+            let change: f64 = v.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            v = next;
+            if change < 1e-12 {
+                break;
+            }
+        }
+        found.push((v, eigenvalue.max(0.0).sqrt()));
+    }
+    found
+}
+
+/// Remove the projection of `v` onto each unit vector in `basis`.
+fn orthogonalize(v: &mut [f64], basis: &[(Vec<f64>, f64)]) {
+    for (u, _) in basis {
+        let dot: f64 = v.iter().zip(u).map(|(a, b)| a * b).sum();
+        v.iter_mut().zip(u).for_each(|(a, b)| *a -= dot * b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn distance(positions: &[f32], a: usize, b: usize) -> f32 {
+        (positions[a * 2] - positions[b * 2]).hypot(positions[a * 2 + 1] - positions[b * 2 + 1])
+    }
+
+    fn grid_edges(side: u32) -> Vec<(u32, u32)> {
+        let right = (0..side * side)
+            .filter(|i| i % side + 1 < side)
+            .map(|i| (i, i + 1));
+        let down = (0..side * (side - 1)).map(|i| (i, i + side));
+        right.chain(down).collect()
+    }
+
+    #[test]
+    fn test_path_is_a_line() {
+        let edges: Vec<(u32, u32)> = (0..9).map(|i| (i, i + 1)).collect();
+        let csr = csr_from_edges(10, &edges);
+        let config = PivotMdsConfig {
+            pivots: 4,
+            ..Default::default()
+        };
+        let positions = compute_pivot_mds_layout(&csr, 10, &config);
+
+        let end_to_end = distance(&positions, 0, 9);
+        assert!((end_to_end - 450.0).abs() < 20.0, "Path bent: {end_to_end}");
+    }
+
+    #[test]
+    fn test_grid_keeps_global_shape() {
+        let side = 30;
+        let n = (side * side) as usize;
+        let csr = csr_from_edges(n, &grid_edges(side));
+        let config = PivotMdsConfig {
+            pivots: 20,
+            ..Default::default()
+        };
+        let positions = compute_pivot_mds_layout(&csr, n, &config);
+
+        // Corners are a diagonal apart, and the two diagonals are balanced
+        let diagonal = distance(&positions, 0, n - 1);
+        let anti_diagonal = distance(&positions, side as usize - 1, n - side as usize);
+        let expected = 29.0 * 50.0 * std::f32::consts::SQRT_2;
+        assert!(
+            (diagonal - expected).abs() < expected * 0.15,
+            "Diagonal {diagonal}"
+        );
+        assert!(
+            (anti_diagonal - expected).abs() < expected * 0.15,
+            "Anti {anti_diagonal}"
+        );
+    }
+
+    #[test]
+    fn test_isolated_nodes_get_sentinel_and_seed_is_deterministic() {
+        let csr = csr_from_edges(5, &[(0, 1), (1, 2), (2, 3)]);
+        let config = PivotMdsConfig::default();
+        let a = compute_pivot_mds_layout(&csr, 5, &config);
+        let b = compute_pivot_mds_layout(&csr, 5, &config);
+
+        assert_eq!(a, b);
+        assert_eq!(a[8], SENTINEL);
+        assert!(a[..8].iter().all(|v| v.is_finite() && *v < SENTINEL));
+    }
+}
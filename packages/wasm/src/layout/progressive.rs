@@ -0,0 +1,468 @@
+//! Progressive, resumable layout computation.
+//!
+//! Long layouts (stress majorization, Louvain on large graphs) can block the
+//! thread for seconds. A [`ProgressiveLayout`] holds a layout mid-way, so
+//! the host can run it in slices between frames without a worker:
+//!
+//! ```text
+//! const task = graph.beginLayout({ type: "stress", iterations: 300 });
+//! function frame() {
+//!   if (!graph.layoutStep(task, 8)) requestAnimationFrame(frame);
+//!   else graph.startTransition(task.positions(), 500, 3);
+//! }
+//! ```
+//!
+//! Work is split into steps (one stress sweep, one Louvain level, the final
+//! community placement), and [`ProgressiveLayout::run`] keeps stepping until
+//! the time budget is spent. A step is never interrupted, so a slice can
+//! overrun its budget by up to one step; setup (BFS for stress terms) runs
+//! in the first step.
+//!
+//! Layouts are configured from JS with descriptors tagged by `type` and
+//! optional camelCase settings, like the pipeline stages.
+//!
+//! # Cancellation
+//!
+//! Anything that runs in steps ([`Stepped`]) can also run to the end in one
+//! call with [`run_to_end`], which reports progress after every step and
+//! stops early once its [`CancelToken`] is cancelled. Cancellation is
+//! cooperative: it takes effect between steps, so the progress callback
+//! (or, with threads, another thread) is where a host cancels a run.
+//!
+//! Only [`ProgressiveLayout`], Louvain ([`Louvain`]) and betweenness
+//! centrality run in steps. Other community detection (Leiden,
+//! Girvan-Newman, spectral, consensus), the other centralities, cycle
+//! enumeration and the one-shot layout functions run to the end once
+//! started.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Deserialize;
+
+use super::community::{self, CommunityLayoutConfig, Louvain};
+use super::stress::{StressLayoutConfig, StressSolver};
+
+/// Work that runs in steps.
+pub trait Stepped {
+    /// Do one unit of work. Returns true once done.
+    fn step(&mut self) -> bool;
+
+    /// Estimated fraction of the work done, in [0, 1].
+    fn progress(&self) -> f32;
+
+    /// Name of the current phase.
+    fn phase(&self) -> &'static str;
+}
+
+/// Shared flag asking a computation to stop. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every computation holding this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Error of a computation stopped through its [`CancelToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("computation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Step `work` until done, calling `report(progress, phase)` after every
+/// step.
+///
+/// # Returns
+///
+/// `Err(Cancelled)` if `token` was cancelled before the work finished; the
+/// work keeps its state and can be resumed.
+pub fn run_to_end<S: Stepped + ?Sized>(
+    work: &mut S,
+    token: &CancelToken,
+    mut report: impl FnMut(f32, &'static str),
+) -> Result<(), Cancelled> {
+    loop {
+        if token.is_cancelled() {
+            return Err(Cancelled);
+        }
+        let done = work.step();
+        report(work.progress(), work.phase());
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+/// A layout that can run progressively. Unset options use the layout's
+/// default configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ProgressiveLayoutConfig {
+    /// Stress majorization from the current positions.
+    Stress {
+        edge_length: Option<f32>,
+        iterations: Option<u32>,
+        pivots: Option<u32>,
+    },
+    /// Louvain community detection, then the circular community layout.
+    Community {
+        resolution: Option<f32>,
+        max_iterations: Option<u32>,
+        community_spacing: Option<f32>,
+        node_spacing: Option<f32>,
+        spread_factor: Option<f32>,
+    },
+}
+
+/// Where a progressive layout currently is.
+enum Stage {
+    /// Stress terms not built yet.
+    StressSetup {
+        initial: Vec<f32>,
+        config: StressLayoutConfig,
+    },
+    Stress(StressSolver),
+    Communities {
+        louvain: Box<Louvain>,
+        config: CommunityLayoutConfig,
+    },
+    Done,
+}
+
+/// A layout computed in steps.
+pub struct ProgressiveLayout {
+    csr: Vec<u32>,
+    node_count: usize,
+    stage: Stage,
+    /// Latest positions; the starting positions until the first result.
+    positions: Vec<f32>,
+}
+
+impl ProgressiveLayout {
+    /// Prepare a layout without doing any of the work yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Layout descriptor
+    /// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+    /// * `positions` - Current positions [x0, y0, x1, y1, ...] by slot
+    ///   (length `node_count * 2`)
+    /// * `seed` - Seed for randomized steps
+    pub fn new(
+        config: &ProgressiveLayoutConfig,
+        csr: Vec<u32>,
+        positions: Vec<f32>,
+        seed: u64,
+    ) -> Self {
+        let node_count = positions.len() / 2;
+        let stage = match *config {
+            ProgressiveLayoutConfig::Stress {
+                edge_length,
+                iterations,
+                pivots,
+            } => {
+                let defaults = StressLayoutConfig::default();
+                let config = StressLayoutConfig {
+                    edge_length: edge_length.unwrap_or(defaults.edge_length),
+                    iterations: iterations.unwrap_or(defaults.iterations),
+                    pivots: pivots.unwrap_or(defaults.pivots),
+                    seed,
+                    ..defaults
+                };
+                Stage::StressSetup {
+                    initial: positions.clone(),
+                    config,
+                }
+            }
+            ProgressiveLayoutConfig::Community {
+                resolution,
+                max_iterations,
+                community_spacing,
+                node_spacing,
+                spread_factor,
+            } => {
+                let defaults = CommunityLayoutConfig::default();
+                let config = CommunityLayoutConfig {
+                    resolution: resolution.unwrap_or(defaults.resolution),
+                    max_iterations: max_iterations.unwrap_or(defaults.max_iterations),
+                    community_spacing: community_spacing.unwrap_or(defaults.community_spacing),
+                    node_spacing: node_spacing.unwrap_or(defaults.node_spacing),
+                    spread_factor: spread_factor.unwrap_or(defaults.spread_factor),
+                    seed,
+                    ..defaults
+                };
+                let louvain = Box::new(Louvain::new(
+                    &csr,
+                    node_count,
+                    config.resolution,
+                    config.max_iterations,
+                    config.min_modularity_gain,
+                ));
+                Stage::Communities { louvain, config }
+            }
+        };
+
+        Self {
+            csr,
+            node_count,
+            stage,
+            positions,
+        }
+    }
+
+    /// Whether the layout has finished.
+    pub fn is_done(&self) -> bool {
+        matches!(self.stage, Stage::Done)
+    }
+
+    /// Estimated fraction of the work done, in [0, 1].
+    pub fn progress(&self) -> f32 {
+        match &self.stage {
+            Stage::StressSetup { .. } => 0.0,
+            Stage::Stress(solver) => solver.progress(),
+            // Placement after detection is cheap
+            Stage::Communities { louvain, .. } => louvain.progress() * 0.95,
+            Stage::Done => 1.0,
+        }
+    }
+
+    /// Name of the current phase: `"setup"`, `"stress"`, `"communities"`
+    /// or `"done"`.
+    pub fn phase(&self) -> &'static str {
+        match self.stage {
+            Stage::StressSetup { .. } => "setup",
+            Stage::Stress(_) => "stress",
+            Stage::Communities { .. } => "communities",
+            Stage::Done => "done",
+        }
+    }
+
+    /// Latest positions [x0, y0, x1, y1, ...]: intermediate while a stress
+    /// layout runs, final once done. Nodes outside the layout get sentinel
+    /// values (f32::MAX).
+    pub fn positions(&self) -> &[f32] {
+        &self.positions
+    }
+
+    /// Do one unit of work. Returns true once done.
+    pub fn step(&mut self) -> bool {
+        self.stage = match std::mem::replace(&mut self.stage, Stage::Done) {
+            Stage::StressSetup { initial, config } => {
+                let solver = StressSolver::new(&self.csr, self.node_count, &initial, &config);
+                self.positions = solver.positions();
+                Stage::Stress(solver)
+            }
+            Stage::Stress(mut solver) => {
+                let done = solver.sweep();
+                self.positions = solver.positions();
+                if done {
+                    Stage::Done
+                } else {
+                    Stage::Stress(solver)
+                }
+            }
+            Stage::Communities {
+                mut louvain,
+                config,
+            } => {
+                if !louvain.step() {
+                    self.stage = Stage::Communities { louvain, config };
+                    return false;
+                }
+                let detection = louvain.into_result();
+                self.positions = community::compute_community_layout(
+                    &detection.assignments,
+                    detection.community_count,
+                    self.node_count,
+                    &config,
+                );
+                Stage::Done
+            }
+            Stage::Done => Stage::Done,
+        };
+        self.is_done()
+    }
+
+    /// Step until done or until `budget_ms` has passed on `now` (in
+    /// milliseconds). Always runs at least one step.
+    ///
+    /// # Returns
+    ///
+    /// True once the layout is done.
+    pub fn run(&mut self, budget_ms: f64, now: impl Fn() -> f64) -> bool {
+        let start = now();
+        while !self.step() {
+            if now() - start >= budget_ms {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl Stepped for ProgressiveLayout {
+    fn step(&mut self) -> bool {
+        ProgressiveLayout::step(self)
+    }
+
+    fn progress(&self) -> f32 {
+        ProgressiveLayout::progress(self)
+    }
+
+    fn phase(&self) -> &'static str {
+        ProgressiveLayout::phase(self)
+    }
+}
+
+impl Stepped for Louvain {
+    fn step(&mut self) -> bool {
+        Louvain::step(self)
+    }
+
+    fn progress(&self) -> f32 {
+        Louvain::progress(self)
+    }
+
+    fn phase(&self) -> &'static str {
+        if self.is_done() { "done" } else { "communities" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+    use crate::layout::distance::csr_from_edges;
+    use crate::layout::stress::refine_stress_layout;
+    use std::cell::Cell;
+
+    fn ring(n: u32) -> Vec<u32> {
+        let edges: Vec<(u32, u32)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+        csr_from_edges(n as usize, &edges)
+    }
+
+    #[test]
+    fn test_stepped_stress_matches_one_shot() {
+        let csr = ring(12);
+        let config = ProgressiveLayoutConfig::Stress {
+            edge_length: Some(40.0),
+            iterations: Some(30),
+            pivots: None,
+        };
+        let mut layout = ProgressiveLayout::new(&config, csr.clone(), vec![SENTINEL; 24], 7);
+        assert_eq!(layout.phase(), "setup");
+
+        let mut steps = 0;
+        while !layout.step() {
+            steps += 1;
+            assert!(layout.progress() < 1.0);
+        }
+        assert!(steps > 1);
+
+        let one_shot = refine_stress_layout(
+            &csr,
+            12,
+            &[SENTINEL; 24],
+            &StressLayoutConfig {
+                edge_length: 40.0,
+                iterations: 30,
+                seed: 7,
+                ..Default::default()
+            },
+        );
+        assert_eq!(layout.positions(), &one_shot[..]);
+    }
+
+    #[test]
+    fn test_time_budget_slices_work() {
+        // Fake clock advancing 1 ms per reading
+        let clock = Cell::new(0.0);
+        let now = || {
+            clock.set(clock.get() + 1.0);
+            clock.get()
+        };
+        let config = ProgressiveLayoutConfig::Stress {
+            edge_length: None,
+            iterations: Some(50),
+            pivots: None,
+        };
+        let mut layout = ProgressiveLayout::new(&config, ring(8), vec![SENTINEL; 16], 1);
+
+        assert!(!layout.run(3.0, now));
+        assert_eq!(layout.phase(), "stress");
+        while !layout.run(3.0, now) {}
+        assert!(layout.is_done());
+        assert_eq!(layout.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_cancel_from_progress_callback() {
+        let config = ProgressiveLayoutConfig::Stress {
+            edge_length: None,
+            iterations: Some(100),
+            pivots: None,
+        };
+        let mut layout = ProgressiveLayout::new(&config, ring(8), vec![SENTINEL; 16], 1);
+        let token = CancelToken::new();
+        let mut reports = Vec::new();
+        let result = run_to_end(&mut layout, &token, |progress, phase| {
+            reports.push((progress, phase));
+            if reports.len() == 3 {
+                token.cancel();
+            }
+        });
+
+        assert_eq!(result, Err(Cancelled));
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].1, "stress");
+        assert!(!layout.is_done());
+
+        // Resumable with a fresh token
+        let mut last = (0.0, "");
+        run_to_end(&mut layout, &CancelToken::new(), |p, phase| last = (p, phase)).unwrap();
+        assert_eq!(last, (1.0, "done"));
+    }
+
+    #[test]
+    fn test_community_layout_finishes_with_placement() {
+        let config = ProgressiveLayoutConfig::Community {
+            resolution: None,
+            max_iterations: None,
+            community_spacing: None,
+            node_spacing: None,
+            spread_factor: None,
+        };
+        let mut layout = ProgressiveLayout::new(&config, ring(10), vec![0.0; 20], 3);
+        assert_eq!(layout.phase(), "communities");
+        while !layout.step() {}
+
+        assert_eq!(layout.phase(), "done");
+        assert!(
+            layout
+                .positions()
+                .iter()
+                .all(|v| v.is_finite() && *v < SENTINEL)
+        );
+    }
+}
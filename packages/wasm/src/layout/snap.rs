@@ -0,0 +1,236 @@
+//! Grid and angle snapping.
+//!
+//! Post-layout cleanup for diagram-style output: edges are turned to the
+//! nearest multiple of an angle step (45° gives the octilinear look of
+//! hand-drawn diagrams), then nodes are rounded onto a square grid.
+//!
+//! Angle snapping runs a few Gauss–Seidel passes over the edges. Each pass
+//! rotates every edge about its midpoint onto the nearest allowed
+//! direction, keeping its length; an edge with one fixed end turns about
+//! that end instead. Nodes with several edges settle on a compromise, so
+//! angles come out exact on trees and approximate elsewhere.
+//!
+//! Grid snapping then rounds every node to the nearest grid point. Nodes
+//! that would land on an occupied point move to the nearest free one, so
+//! snapping never stacks nodes. Horizontal and vertical edges stay straight
+//! because equal coordinates round alike.
+
+use std::collections::HashSet;
+
+use super::placed_point;
+use super::distance::csr_pairs;
+
+/// Configuration for snapping.
+pub struct SnapConfig {
+    /// Grid spacing; 0 leaves positions off the grid (default: 10.0).
+    pub grid_size: f32,
+    /// Angle step in radians edges are turned to; 0 leaves angles alone
+    /// (default: 0).
+    pub angle_step: f32,
+    /// Angle snapping passes over all edges (default: 10).
+    pub iterations: u32,
+}
+
+impl Default for SnapConfig {
+    fn default() -> Self {
+        Self {
+            grid_size: 10.0,
+            angle_step: 0.0,
+            iterations: 10,
+        }
+    }
+}
+
+/// Snap positions to edge angles and a grid in place.
+///
+/// # Arguments
+///
+/// * `positions` - Interleaved positions [x0, y0, x1, y1, ...] with sentinel
+///   values for nodes without a position (length `node_count * 2`)
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `fixed` - Slots that must stay where they are (pinned nodes); may be
+///   shorter than the node count
+/// * `config` - Snapping configuration
+///
+/// # Returns
+///
+/// The number of nodes moved.
+pub fn snap_positions(
+    positions: &mut [f32],
+    csr: &[u32],
+    fixed: &[bool],
+    config: &SnapConfig,
+) -> u32 {
+    let node_count = positions.len() / 2;
+    let original = positions.to_vec();
+    let movable = |slot: usize| !fixed.get(slot).copied().unwrap_or(false);
+
+    if config.angle_step > 0.0 {
+        let mut edges: Vec<(usize, usize)> = csr_pairs(csr, node_count)
+            .filter(|&(u, v)| u != v && v < node_count)
+            .filter(|&(u, v)| {
+                placed_point(positions, u).is_some() && placed_point(positions, v).is_some()
+            })
+            .map(|(u, v)| (u.min(v), u.max(v)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+
+        for _ in 0..config.iterations {
+            for &(u, v) in &edges {
+                snap_edge(positions, u, v, (movable(u), movable(v)), config.angle_step);
+            }
+        }
+    }
+
+    if config.grid_size > 0.0 {
+        snap_to_grid(positions, &movable, config.grid_size);
+    }
+
+    (0..node_count)
+        .filter(|&i| positions[i * 2..i * 2 + 2] != original[i * 2..i * 2 + 2])
+        .count() as u32
+}
+
+/// Turn edge `u`–`v` onto the nearest multiple of `step`, keeping its
+/// length.
+fn snap_edge(positions: &mut [f32], u: usize, v: usize, movable: (bool, bool), step: f32) {
+    let (ux, uy) = (positions[u * 2], positions[u * 2 + 1]);
+    let (vx, vy) = (positions[v * 2], positions[v * 2 + 1]);
+    let (dx, dy) = (vx - ux, vy - uy);
+    let length = dx.hypot(dy);
+    if length < f32::EPSILON {
+        return;
+    }
+    let angle = (dy.atan2(dx) / step).round() * step;
+    let (ex, ey) = (angle.cos() * length, angle.sin() * length);
+
+    let (start, end) = match movable {
+        (true, true) => {
+            let (mx, my) = ((ux + vx) * 0.5, (uy + vy) * 0.5);
+            (
+                (mx - ex * 0.5, my - ey * 0.5),
+                (mx + ex * 0.5, my + ey * 0.5),
+            )
+        }
+        (false, true) => ((ux, uy), (ux + ex, uy + ey)),
+        (true, false) => ((vx - ex, vy - ey), (vx, vy)),
+        (false, false) => return,
+    };
+    (positions[u * 2], positions[u * 2 + 1]) = start;
+    (positions[v * 2], positions[v * 2 + 1]) = end;
+}
+
+/// Round movable nodes to grid points, moving nodes off points already
+/// taken (fixed nodes take the point nearest to them).
+fn snap_to_grid(positions: &mut [f32], movable: &impl Fn(usize) -> bool, grid_size: f32) {
+    let node_count = positions.len() / 2;
+    let cell = |i: usize| {
+        (
+            (positions[i * 2] / grid_size).round() as i64,
+            (positions[i * 2 + 1] / grid_size).round() as i64,
+        )
+    };
+    let placed: Vec<usize> = (0..node_count)
+        .filter(|&i| placed_point(positions, i).is_some())
+        .collect();
+    let mut taken: HashSet<(i64, i64)> = placed
+        .iter()
+        .filter(|&&i| !movable(i))
+        .map(|&i| cell(i))
+        .collect();
+
+    let mut cells = Vec::new();
+    for &i in placed.iter().filter(|&&i| movable(i)) {
+        let exact = (
+            positions[i * 2] / grid_size,
+            positions[i * 2 + 1] / grid_size,
+        );
+        let chosen = free_cell(&taken, cell(i), exact);
+        taken.insert(chosen);
+        cells.push((i, chosen));
+    }
+    for (i, (cx, cy)) in cells {
+        positions[i * 2] = cx as f32 * grid_size;
+        positions[i * 2 + 1] = cy as f32 * grid_size;
+    }
+}
+
+/// The free grid cell closest to `exact` (in grid units), searching rings
+/// around `home`.
+fn free_cell(taken: &HashSet<(i64, i64)>, home: (i64, i64), exact: (f32, f32)) -> (i64, i64) {
+    if !taken.contains(&home) {
+        return home;
+    }
+    let distance = |(cx, cy): (i64, i64)| (cx as f32 - exact.0).hypot(cy as f32 - exact.1);
+    // Every ring r holds 8r cells, so a free one turns up within
+    // sqrt(taken) rings
+    (1i64..)
+        .find_map(|r| {
+            (-r..=r)
+                .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+                .filter(|&(dx, dy)| dx.abs() == r || dy.abs() == r)
+                .map(|(dx, dy)| (home.0 + dx, home.1 + dy))
+                .filter(|c| !taken.contains(c))
+                .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+        })
+        .unwrap_or(home)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+    use crate::layout::distance::csr_from_edges;
+
+    #[test]
+    fn test_grid_snap_avoids_stacking() {
+        let mut positions = vec![12.0, 18.0, 8.0, 21.0, SENTINEL, SENTINEL, 31.0, -4.0];
+        let moved = snap_positions(&mut positions, &[], &[], &SnapConfig::default());
+
+        assert_eq!(moved, 3);
+        assert_eq!(&positions[..2], &[10.0, 20.0]);
+        // Node 1 also rounds to (10, 20), so it takes the nearest free point
+        assert_eq!(&positions[2..4], &[0.0, 20.0]);
+        assert_eq!(positions[4], SENTINEL);
+        assert_eq!(&positions[6..], &[30.0, 0.0]);
+    }
+
+    #[test]
+    fn test_angle_snap_straightens_path() {
+        // Path 0-1-2 with slightly skewed edges
+        let csr = csr_from_edges(3, &[(0, 1), (1, 2)]);
+        let mut positions = vec![0.0, 0.0, 100.0, 8.0, 170.0, 78.0];
+        let config = SnapConfig {
+            grid_size: 0.0,
+            angle_step: std::f32::consts::FRAC_PI_4,
+            ..Default::default()
+        };
+        snap_positions(&mut positions, &csr, &[], &config);
+
+        let angle = |a: usize, b: usize| {
+            let (dx, dy) = (
+                positions[b * 2] - positions[a * 2],
+                positions[b * 2 + 1] - positions[a * 2 + 1],
+            );
+            dy.atan2(dx).to_degrees()
+        };
+        assert!(angle(0, 1).abs() < 0.5, "{}", angle(0, 1));
+        assert!((angle(1, 2) - 45.0).abs() < 0.5, "{}", angle(1, 2));
+    }
+
+    #[test]
+    fn test_fixed_nodes_stay() {
+        let csr = csr_from_edges(2, &[(0, 1)]);
+        let mut positions = vec![3.0, 3.0, 50.0, 10.0];
+        let config = SnapConfig {
+            angle_step: std::f32::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        snap_positions(&mut positions, &csr, &[true], &config);
+
+        assert_eq!(&positions[..2], &[3.0, 3.0]);
+        // Turned to horizontal about node 0, then onto the grid
+        assert_eq!(&positions[2..], &[50.0, 0.0]);
+    }
+}
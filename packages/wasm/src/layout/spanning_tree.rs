@@ -0,0 +1,269 @@
+//! Spanning tree extraction.
+//!
+//! Picks a tree out of a general graph so that graphs which are "almost
+//! trees" can go through the tree layouts: the tree edges drive the layout
+//! and the remaining edges are only drawn. Edges count as undirected; the
+//! result is directed away from the roots, ready for `computeTreeLayout`.
+//!
+//! Strategies:
+//!
+//! - **BFS**: every node hangs off a node on the shortest path from its
+//!   root, so the tree is as shallow as possible.
+//! - **DFS**: follows paths as deep as they go, which keeps long chains
+//!   (pipelines, call sequences) on one branch.
+//! - **Minimum weight**: the minimum spanning forest by edge weight
+//!   (Kruskal), oriented breadth-first from the roots. Gives the tree of the
+//!   strongest ties when weights are distances or costs.
+//!
+//! Each connected component gets its own tree. Roots are picked in order:
+//! the requested root, then nodes without incoming edges, then any node,
+//! lowest slot first.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+/// How to choose the tree edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanningTreeStrategy {
+    /// Breadth-first search from the roots.
+    Bfs,
+    /// Depth-first search from the roots.
+    Dfs,
+    /// Minimum total edge weight.
+    MinWeight,
+}
+
+/// Extract a spanning forest.
+///
+/// # Arguments
+///
+/// * `node_count` - Total number of node slots
+/// * `edges` - Directed edges as (source, target, weight); out-of-range
+///   slots and self loops are ignored
+/// * `root` - Slot to root its component at (or None to pick automatically)
+/// * `strategy` - How to choose the tree edges
+///
+/// # Returns
+///
+/// Flat parent→child pairs [parent0, child0, parent1, child1, ...], in
+/// traversal order from the roots.
+pub fn spanning_tree(
+    node_count: usize,
+    edges: &[(u32, u32, f32)],
+    root: Option<u32>,
+    strategy: SpanningTreeStrategy,
+) -> Vec<u32> {
+    let valid: Vec<(usize, usize, f32)> = edges
+        .iter()
+        .map(|&(u, v, w)| (u as usize, v as usize, w))
+        .filter(|&(u, v, _)| u != v && u < node_count && v < node_count)
+        .collect();
+
+    let mut has_parent = vec![false; node_count];
+    for &(_, v, _) in &valid {
+        has_parent[v] = true;
+    }
+    let roots = root
+        .map(|r| r as usize)
+        .filter(|&r| r < node_count)
+        .into_iter()
+        .chain((0..node_count).filter(|&i| !has_parent[i]))
+        .chain(0..node_count);
+
+    let tree_edges = match strategy {
+        SpanningTreeStrategy::MinWeight => minimum_spanning_forest(node_count, valid),
+        _ => valid,
+    };
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for &(u, v, _) in &tree_edges {
+        neighbors[u].push(v);
+        neighbors[v].push(u);
+    }
+    for list in &mut neighbors {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let mut visited = vec![false; node_count];
+    let mut pairs = Vec::new();
+    for r in roots {
+        if visited[r] {
+            continue;
+        }
+        match strategy {
+            SpanningTreeStrategy::Dfs => depth_first(r, &neighbors, &mut visited, &mut pairs),
+            _ => breadth_first(r, &neighbors, &mut visited, &mut pairs),
+        }
+    }
+    pairs
+}
+
+/// Minimum spanning forest with the edges it uses and a parent per slot.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinimumSpanningForest {
+    /// Indices of the forest's edges in the input (the engine maps them to
+    /// edge IDs), ascending.
+    pub edges: Vec<u32>,
+    /// Parent slot per slot, `u32::MAX` for roots and isolated slots.
+    pub parents: Vec<u32>,
+    /// Sum of the forest's edge weights.
+    pub total_weight: f64,
+}
+
+/// Minimum spanning forest (Kruskal) of `edges`, oriented away from the
+/// same roots as `spanning_tree`. Of parallel edges, the lightest (then the
+/// first) is used.
+pub fn minimum_spanning_forest_with_parents(
+    node_count: usize,
+    edges: &[(u32, u32, f32)],
+    root: Option<u32>,
+) -> MinimumSpanningForest {
+    let pairs = spanning_tree(node_count, edges, root, SpanningTreeStrategy::MinWeight);
+
+    // Lightest, then first, edge per unordered pair
+    let mut lightest: HashMap<(u32, u32), (f32, usize)> = HashMap::new();
+    for (i, &(u, v, w)) in edges.iter().enumerate() {
+        let best = lightest.entry((u.min(v), u.max(v))).or_insert((w, i));
+        if w.total_cmp(&best.0).is_lt() {
+            *best = (w, i);
+        }
+    }
+
+    let mut parents = vec![u32::MAX; node_count];
+    let mut tree_edges = Vec::with_capacity(pairs.len() / 2);
+    let mut total_weight = 0.0;
+    for pair in pairs.chunks_exact(2) {
+        let (parent, child) = (pair[0], pair[1]);
+        parents[child as usize] = parent;
+        let (weight, index) = lightest[&(parent.min(child), parent.max(child))];
+        tree_edges.push(index as u32);
+        total_weight += f64::from(weight);
+    }
+    tree_edges.sort_unstable();
+
+    MinimumSpanningForest {
+        edges: tree_edges,
+        parents,
+        total_weight,
+    }
+}
+
+/// Tree edges reached breadth-first from `root`.
+fn breadth_first(
+    root: usize,
+    neighbors: &[Vec<usize>],
+    visited: &mut [bool],
+    pairs: &mut Vec<u32>,
+) {
+    visited[root] = true;
+    let mut queue = VecDeque::from([root]);
+    while let Some(u) = queue.pop_front() {
+        for &v in &neighbors[u] {
+            if !visited[v] {
+                visited[v] = true;
+                pairs.extend([u as u32, v as u32]);
+                queue.push_back(v);
+            }
+        }
+    }
+}
+
+/// Tree edges of an iterative depth-first search from `root`. A node's
+/// parent is the node it was last discovered from, as in a recursive DFS.
+fn depth_first(root: usize, neighbors: &[Vec<usize>], visited: &mut [bool], pairs: &mut Vec<u32>) {
+    let mut stack = vec![(root, None)];
+    while let Some((u, parent)) = stack.pop() {
+        if visited[u] {
+            continue;
+        }
+        visited[u] = true;
+        if let Some(p) = parent {
+            pairs.extend([p as u32, u as u32]);
+        }
+        // Reversed so the lowest neighbor is explored first
+        for &v in neighbors[u].iter().rev().filter(|&&v| !visited[v]) {
+            stack.push((v, Some(u)));
+        }
+    }
+}
+
+/// Kruskal's minimum spanning forest over undirected edges. Ties keep edge
+/// order.
+fn minimum_spanning_forest(
+    node_count: usize,
+    mut edges: Vec<(usize, usize, f32)>,
+) -> Vec<(usize, usize, f32)> {
+    edges.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    let mut parent: Vec<usize> = (0..node_count).collect();
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+
+    edges
+        .into_iter()
+        .filter(|&(u, v, _)| {
+            let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+            if ru == rv {
+                return false;
+            }
+            parent[ru] = rv;
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(edges: &[(u32, u32)]) -> Vec<(u32, u32, f32)> {
+        edges.iter().map(|&(u, v)| (u, v, 1.0)).collect()
+    }
+
+    #[test]
+    fn test_bfs_and_dfs_trees() {
+        // Square 0-1-2-3-0 with a tail 2 → 4
+        let edges = unit(&[(0, 1), (1, 2), (2, 3), (3, 0), (2, 4)]);
+
+        let bfs = spanning_tree(5, &edges, None, SpanningTreeStrategy::Bfs);
+        assert_eq!(bfs, vec![0, 1, 0, 3, 1, 2, 2, 4]);
+
+        let dfs = spanning_tree(5, &edges, None, SpanningTreeStrategy::Dfs);
+        assert_eq!(dfs, vec![0, 1, 1, 2, 2, 3, 2, 4]);
+    }
+
+    #[test]
+    fn test_min_weight_tree() {
+        // Triangle where the direct edge 0-2 is the most expensive
+        let edges = [(0, 1, 1.0), (1, 2, 2.0), (0, 2, 5.0)];
+        let tree = spanning_tree(3, &edges, Some(2), SpanningTreeStrategy::MinWeight);
+        assert_eq!(tree, vec![2, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_with_parents() {
+        // Triangle 0-1-2 with a heavy parallel 0-1, plus a separate 3 → 4;
+        // slot 5 is isolated
+        let edges = [(0, 1, 3.0), (1, 2, 2.0), (0, 2, 5.0), (1, 0, 1.0), (3, 4, 4.0)];
+        let forest = minimum_spanning_forest_with_parents(6, &edges, Some(2));
+        assert_eq!(forest.edges, vec![1, 3, 4]);
+        assert_eq!(forest.parents, vec![1, 2, u32::MAX, u32::MAX, 3, u32::MAX]);
+        assert_eq!(forest.total_weight, 7.0);
+    }
+
+    #[test]
+    fn test_forest_roots() {
+        // Two components: 1 → 0 (root 1 has no incoming edge) and a
+        // cycle 2 → 3 → 4 → 2 (no such node; lowest slot wins); 5 isolated
+        let edges = unit(&[(1, 0), (2, 3), (3, 4), (4, 2), (2, 2)]);
+        let tree = spanning_tree(6, &edges, None, SpanningTreeStrategy::Bfs);
+        assert_eq!(tree, vec![1, 0, 2, 3, 2, 4]);
+    }
+}
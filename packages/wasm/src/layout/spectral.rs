@@ -0,0 +1,521 @@
+//! Spectral layout.
+//!
+//! Places nodes at the coordinates given by the two smallest non-trivial
+//! eigenvectors of the graph Laplacian (the Fiedler vector and the next one).
+//! Connected nodes get similar coordinates, which makes this a good
+//! deterministic initial placement before force-directed refinement.
+//!
+//! # Algorithm
+//!
+//! Uses Koren's degree-normalized formulation: the eigenvectors are computed
+//! by power iteration on `½(I + D⁻¹A)`, D-orthogonalizing against the constant
+//! vector and the previously found eigenvector on every step. No linear
+//! algebra dependencies are needed and each step is O(n + m).
+//!
+//! Disconnected graphs have one zero eigenvalue per component, so each
+//! connected component is laid out on its own, scaled so its mean edge length
+//! equals `edge_length`, and the components are placed side by side from
+//! largest to smallest. Nodes without edges get the sentinel.
+//!
+//! # Spectral clustering
+//!
+//! [`spectral_clusters`] splits a graph into a requested number `k` of
+//! groups (Shi–Malik): the same power iteration finds the `k - 1` leading
+//! non-trivial eigenvectors, over the whole graph at once, and k-means
+//! (with k-means++ seeding) groups the nodes by their rows. Unlike
+//! modularity optimization, the number of groups is fixed up front.
+//!
+//! # References
+//!
+//! - Koren, "Drawing Graphs by Eigenvectors: Theory and Practice" (2005)
+//! - Shi & Malik, "Normalized Cuts and Image Segmentation" (2000)
+//! - Arthur & Vassilvitskii, "k-means++: The Advantages of Careful
+//!   Seeding" (2007)
+
+use super::SENTINEL;
+use super::distance::UndirectedCsr;
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+/// Configuration for the spectral layout.
+pub struct SpectralLayoutConfig {
+    /// Mean distance between adjacent nodes after scaling (default: 50.0).
+    pub edge_length: f32,
+    /// Maximum power iterations per eigenvector (default: 1000).
+    pub max_iterations: u32,
+    /// Stop when successive iterates differ by less than this (default: 1e-7).
+    pub tolerance: f64,
+    /// Seed for the power iteration start vectors (and k-means seeding).
+    pub seed: u64,
+}
+
+/// Maximum k-means (Lloyd) iterations.
+const KMEANS_ITERATIONS: u32 = 100;
+
+impl Default for SpectralLayoutConfig {
+    fn default() -> Self {
+        Self {
+            edge_length: 50.0,
+            max_iterations: 1000,
+            tolerance: 1e-7,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// A connected component with local indices.
+struct Component {
+    /// Global slot of each local node.
+    slots: Vec<usize>,
+    /// Local CSR offsets (len = slots.len() + 1).
+    offsets: Vec<u32>,
+    /// Local neighbor indices.
+    neighbors: Vec<u32>,
+}
+
+impl Component {
+    fn new(graph: &UndirectedCsr, slots: Vec<usize>, local: &mut [u32]) -> Self {
+        for (index, &slot) in slots.iter().enumerate() {
+            local[slot] = index as u32;
+        }
+        let mut offsets = Vec::with_capacity(slots.len() + 1);
+        let mut neighbors = Vec::new();
+        offsets.push(0);
+        for &slot in &slots {
+            neighbors.extend(graph.neighbors(slot).iter().map(|&n| local[n as usize]));
+            offsets.push(neighbors.len() as u32);
+        }
+        Self {
+            slots,
+            offsets,
+            neighbors,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn neighbors(&self, node: usize) -> &[u32] {
+        &self.neighbors[self.offsets[node] as usize..self.offsets[node + 1] as usize]
+    }
+
+    fn degree(&self, node: usize) -> f64 {
+        (self.offsets[node + 1] - self.offsets[node]) as f64
+    }
+
+    /// Each undirected edge once, as local (a, b) with a < b.
+    fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len()).flat_map(move |a| {
+            self.neighbors(a)
+                .iter()
+                .map(|&b| b as usize)
+                .filter(move |&b| a < b)
+                .map(move |b| (a, b))
+        })
+    }
+}
+
+/// Compute a spectral layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...],
+/// centered on the origin. Nodes without edges get sentinel values (f32::MAX).
+pub fn compute_spectral_layout(
+    csr: &[u32],
+    node_count: usize,
+    config: &SpectralLayoutConfig,
+) -> Vec<f32> {
+    let mut positions = vec![SENTINEL; node_count * 2];
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let mut components = graph.components();
+    if components.is_empty() {
+        return positions;
+    }
+    // Largest first; the sort is stable so equal sizes keep slot order
+    components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+    let mut rng = SeededRng::new(config.seed);
+    let mut local = vec![0u32; node_count];
+    let gap = config.edge_length * 2.0;
+    let mut cursor = 0.0f32;
+    let (mut min_y, mut max_y) = (f32::MAX, f32::MIN);
+
+    for slots in components {
+        let component = Component::new(&graph, slots, &mut local);
+        let (xs, ys) = embed_component(&component, config, &mut rng);
+
+        let left = xs.iter().copied().fold(f32::MAX, f32::min);
+        let right = xs.iter().copied().fold(f32::MIN, f32::max);
+        let top = ys.iter().copied().fold(f32::MAX, f32::min);
+        let bottom = ys.iter().copied().fold(f32::MIN, f32::max);
+        let mid_y = (top + bottom) * 0.5;
+        for (i, &slot) in component.slots.iter().enumerate() {
+            positions[slot * 2] = xs[i] - left + cursor;
+            positions[slot * 2 + 1] = ys[i] - mid_y;
+        }
+        cursor += right - left + gap;
+        min_y = min_y.min(top - mid_y);
+        max_y = max_y.max(bottom - mid_y);
+    }
+
+    // Center the bounding box of all components on the origin
+    let shift_x = (cursor - gap) * 0.5;
+    let shift_y = (min_y + max_y) * 0.5;
+    for i in 0..node_count {
+        if positions[i * 2] != SENTINEL {
+            positions[i * 2] -= shift_x;
+            positions[i * 2 + 1] -= shift_y;
+        }
+    }
+
+    positions
+}
+
+/// Spectral coordinates of one component, scaled to the configured mean
+/// edge length.
+fn embed_component(
+    component: &Component,
+    config: &SpectralLayoutConfig,
+    rng: &mut SeededRng,
+) -> (Vec<f32>, Vec<f32>) {
+    let n = component.len();
+    let ones = vec![1.0f64; n];
+    let x = if n >= 2 {
+        eigenvector(component, &[&ones], config, rng)
+    } else {
+        vec![0.0; n]
+    };
+    let y = if n >= 3 {
+        eigenvector(component, &[&ones, &x], config, rng)
+    } else {
+        vec![0.0; n]
+    };
+
+    let edge_count = component.edges().count().max(1) as f64;
+    let mean_edge = component
+        .edges()
+        .map(|(a, b)| (x[a] - x[b]).hypot(y[a] - y[b]))
+        .sum::<f64>()
+        / edge_count;
+    let scale = if mean_edge > f64::EPSILON {
+        config.edge_length as f64 / mean_edge
+    } else {
+        0.0
+    };
+
+    (
+        x.iter().map(|&v| (v * scale) as f32).collect(),
+        y.iter().map(|&v| (v * scale) as f32).collect(),
+    )
+}
+
+/// Split a graph into `clusters` groups by spectral clustering.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `clusters` - Number of groups; fewer come out only when fewer nodes
+///   have edges
+/// * `config` - Power iteration settings and seed (`edge_length` unused)
+///
+/// # Returns
+///
+/// The group of each slot, numbered in order of first node. Nodes without
+/// edges carry no structure and join the smallest group.
+pub fn spectral_clusters(
+    csr: &[u32],
+    node_count: usize,
+    clusters: usize,
+    config: &SpectralLayoutConfig,
+) -> Vec<u32> {
+    let graph = UndirectedCsr::from_csr(csr, node_count);
+    let slots: Vec<usize> = (0..node_count)
+        .filter(|&slot| !graph.neighbors(slot).is_empty())
+        .collect();
+    let mut local = vec![0u32; node_count];
+    let component = Component::new(&graph, slots, &mut local);
+    let n = component.len();
+    let k = clusters.clamp(1, n.max(1));
+
+    // Rows of the k - 1 leading non-trivial eigenvectors
+    let mut rng = SeededRng::new(config.seed);
+    let ones = vec![1.0f64; n];
+    let mut vectors: Vec<Vec<f64>> = vec![ones];
+    for _ in 1..k {
+        let previous: Vec<&[f64]> = vectors.iter().map(Vec::as_slice).collect();
+        let next = eigenvector(&component, &previous, config, &mut rng);
+        vectors.push(next);
+    }
+    let rows: Vec<Vec<f64>> = (0..n)
+        .map(|i| vectors[1..].iter().map(|v| v[i]).collect())
+        .collect();
+    let labels = kmeans(&rows, k, &mut rng);
+
+    let mut sizes = vec![0usize; k];
+    labels.iter().for_each(|&l| sizes[l] += 1);
+    let smallest = (0..k).min_by_key(|&c| sizes[c]).unwrap_or(0);
+    let mut groups = vec![smallest; node_count];
+    for (i, &slot) in component.slots.iter().enumerate() {
+        groups[slot] = labels[i];
+    }
+
+    // Number groups in order of first node
+    let mut renumber = vec![u32::MAX; k];
+    let mut next = 0u32;
+    groups
+        .iter()
+        .map(|&g| {
+            if renumber[g] == u32::MAX {
+                renumber[g] = next;
+                next += 1;
+            }
+            renumber[g]
+        })
+        .collect()
+}
+
+/// Lloyd's k-means with k-means++ seeding. Returns the cluster of each row;
+/// clusters that empty out are reseeded with the row farthest from its
+/// center.
+fn kmeans(rows: &[Vec<f64>], k: usize, rng: &mut SeededRng) -> Vec<usize> {
+    let distance = |a: &[f64], b: &[f64]| -> f64 {
+        a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+    };
+    if rows.is_empty() {
+        return Vec::new();
+    }
+
+    // k-means++: each next center drawn with probability proportional to
+    // the squared distance to the nearest center so far
+    let mut centers: Vec<Vec<f64>> = vec![rows[rng.below(rows.len())].clone()];
+    let mut nearest: Vec<f64> = rows.iter().map(|r| distance(r, &centers[0])).collect();
+    while centers.len() < k {
+        let total: f64 = nearest.iter().sum();
+        let mut target = rng.next_f32() as f64 * total;
+        let pick = nearest
+            .iter()
+            .position(|&d| {
+                target -= d;
+                target <= 0.0 && d > 0.0
+            })
+            .unwrap_or_else(|| farthest(&nearest));
+        centers.push(rows[pick].clone());
+        for (d, row) in nearest.iter_mut().zip(rows) {
+            *d = d.min(distance(row, &centers[centers.len() - 1]));
+        }
+    }
+
+    let mut labels = vec![0usize; rows.len()];
+    for iteration in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (i, row) in rows.iter().enumerate() {
+            let best = (0..k)
+                .min_by(|&a, &b| distance(row, &centers[a]).total_cmp(&distance(row, &centers[b])))
+                .unwrap_or(0);
+            changed |= best != labels[i];
+            labels[i] = best;
+        }
+        if !changed && iteration > 0 {
+            break;
+        }
+        update_centers(rows, &labels, &mut centers, distance);
+    }
+    labels
+}
+
+/// Move each center to the mean of its rows; an empty cluster takes the row
+/// farthest from its own center.
+fn update_centers(
+    rows: &[Vec<f64>],
+    labels: &[usize],
+    centers: &mut [Vec<f64>],
+    distance: impl Fn(&[f64], &[f64]) -> f64,
+) {
+    let dims = rows[0].len();
+    let mut sums = vec![vec![0.0f64; dims]; centers.len()];
+    let mut counts = vec![0usize; centers.len()];
+    for (row, &label) in rows.iter().zip(labels) {
+        counts[label] += 1;
+        sums[label].iter_mut().zip(row).for_each(|(s, x)| *s += x);
+    }
+    let spread: Vec<f64> = rows
+        .iter()
+        .zip(labels)
+        .map(|(row, &label)| distance(row, &centers[label]))
+        .collect();
+    for (c, center) in centers.iter_mut().enumerate() {
+        if counts[c] == 0 {
+            *center = rows[farthest(&spread)].clone();
+        } else {
+            *center = sums[c].iter().map(|s| s / counts[c] as f64).collect();
+        }
+    }
+}
+
+/// Index of the largest value.
+fn farthest(values: &[f64]) -> usize {
+    (0..values.len())
+        .max_by(|&a, &b| values[a].total_cmp(&values[b]))
+        .unwrap_or(0)
+}
+
+/// Power iteration for the next eigenvector of `½(I + D⁻¹A)`, kept
+/// D-orthogonal to `previous`.
+fn eigenvector(
+    component: &Component,
+    previous: &[&[f64]],
+    config: &SpectralLayoutConfig,
+    rng: &mut SeededRng,
+) -> Vec<f64> {
+    let n = component.len();
+    let mut u: Vec<f64> = (0..n).map(|_| rng.range_f32(-1.0, 1.0) as f64).collect();
+    d_orthogonalize(component, &mut u, previous);
+    normalize(&mut u);
+    let mut next = vec![0.0f64; n];
+
+    for _ in 0..config.max_iterations {
+        for (i, value) in next.iter_mut().enumerate() {
+            let sum: f64 = component.neighbors(i).iter().map(|&j| u[j as usize]).sum();
+            *value = 0.5 * (u[i] + sum / component.degree(i));
+        }
+        d_orthogonalize(component, &mut next, previous);
+        // Only a zero-eigenvalue direction is left (e.g. a single edge);
+        // `u` already is that eigenvector
+        if normalize(&mut next) <= f64::EPSILON {
+            break;
+        }
+
+        let similarity: f64 = u.iter().zip(&next).map(|(a, b)| a * b).sum();
+        std::mem::swap(&mut u, &mut next);
+        if similarity > 1.0 - config.tolerance {
+            break;
+        }
+    }
+
+    u
+}
+
+/// Remove the D-weighted projection of `u` onto each vector in `previous`.
+fn d_orthogonalize(component: &Component, u: &mut [f64], previous: &[&[f64]]) {
+    for v in previous {
+        let mut uv = 0.0;
+        let mut vv = 0.0;
+        for i in 0..u.len() {
+            let d = component.degree(i);
+            uv += d * u[i] * v[i];
+            vv += d * v[i] * v[i];
+        }
+        if vv <= f64::EPSILON {
+            continue;
+        }
+        let factor = uv / vv;
+        u.iter_mut()
+            .zip(v.iter())
+            .for_each(|(a, b)| *a -= factor * b);
+    }
+}
+
+/// Scale `u` to unit length, returning its previous length.
+fn normalize(u: &mut [f64]) -> f64 {
+    let norm = u.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > f64::EPSILON {
+        u.iter_mut().for_each(|v| *v /= norm);
+    }
+    norm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn distance(positions: &[f32], a: usize, b: usize) -> f32 {
+        (positions[a * 2] - positions[b * 2]).hypot(positions[a * 2 + 1] - positions[b * 2 + 1])
+    }
+
+    #[test]
+    fn test_path_is_ordered_along_fiedler_vector() {
+        let edges: Vec<(u32, u32)> = (0..9).map(|i| (i, i + 1)).collect();
+        let csr = csr_from_edges(10, &edges);
+        let positions = compute_spectral_layout(&csr, 10, &SpectralLayoutConfig::default());
+
+        let xs: Vec<f32> = (0..10).map(|i| positions[i * 2]).collect();
+        let increasing = xs.windows(2).all(|w| w[0] < w[1]);
+        let decreasing = xs.windows(2).all(|w| w[0] > w[1]);
+        assert!(increasing || decreasing, "Path not monotonic: {xs:?}");
+    }
+
+    #[test]
+    fn test_cycle_becomes_circle() {
+        let n = 12;
+        let edges: Vec<(u32, u32)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+        let csr = csr_from_edges(n as usize, &edges);
+        let positions = compute_spectral_layout(&csr, n as usize, &SpectralLayoutConfig::default());
+
+        let radii: Vec<f32> = (0..n as usize)
+            .map(|i| positions[i * 2].hypot(positions[i * 2 + 1]))
+            .collect();
+        let mean = radii.iter().sum::<f32>() / radii.len() as f32;
+        for r in &radii {
+            assert!(
+                (r - mean).abs() < mean * 0.05,
+                "Radii not uniform: {radii:?}"
+            );
+        }
+        let mean_edge = (0..n as usize)
+            .map(|i| distance(&positions, i, (i + 1) % n as usize))
+            .sum::<f32>()
+            / n as f32;
+        assert!(
+            (mean_edge - 50.0).abs() < 0.5,
+            "Mean edge length {mean_edge}"
+        );
+    }
+
+    #[test]
+    fn test_components_do_not_overlap() {
+        // Triangle 0-1-2, edge 3-4, isolated 5
+        let csr = csr_from_edges(6, &[(0, 1), (1, 2), (2, 0), (3, 4)]);
+        let positions = compute_spectral_layout(&csr, 6, &SpectralLayoutConfig::default());
+
+        let triangle_right = (0..3).map(|i| positions[i * 2]).fold(f32::MIN, f32::max);
+        let pair_left = (3..5).map(|i| positions[i * 2]).fold(f32::MAX, f32::min);
+        assert!(pair_left > triangle_right, "Components overlap");
+        assert!((distance(&positions, 3, 4) - 50.0).abs() < 0.01);
+        assert_eq!(positions[10], SENTINEL);
+    }
+
+    #[test]
+    fn test_spectral_clusters_split_cliques() {
+        // Three 5-cliques joined in a ring by single edges; node 15 isolated
+        let mut edges = Vec::new();
+        for c in 0..3u32 {
+            let b = c * 5;
+            edges.extend((0..5).flat_map(|i| (i + 1..5).map(move |j| (b + i, b + j))));
+            edges.push((b, (b + 5) % 15));
+        }
+        let csr = csr_from_edges(16, &edges);
+        let clusters = spectral_clusters(&csr, 16, 3, &SpectralLayoutConfig::default());
+
+        assert_eq!(clusters[..15], [0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2]);
+        assert!(clusters[15] < 3);
+    }
+
+    #[test]
+    fn test_same_seed_same_layout() {
+        let csr = csr_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 0), (0, 4)]);
+        let config = SpectralLayoutConfig::default();
+        assert_eq!(
+            compute_spectral_layout(&csr, 5, &config),
+            compute_spectral_layout(&csr, 5, &config)
+        );
+    }
+}
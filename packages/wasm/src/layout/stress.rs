@@ -0,0 +1,458 @@
+//! Stress majorization layout.
+//!
+//! Places nodes so that Euclidean distances match graph-theoretic (hop)
+//! distances scaled by an ideal edge length, minimizing the Kamada–Kawai
+//! stress `Σ w_ij (|x_i - x_j| - d_ij)²` with `w_ij = d_ij⁻²`.
+//!
+//! # Algorithm
+//!
+//! 1. Compute hop distances with BFS. Small graphs use all pairs. Larger
+//!    graphs use *sparse stress*: every node keeps exact terms for its
+//!    neighbors plus one term per pivot, where pivots are chosen by max–min
+//!    sampling and each pivot term is weighted by the number of nodes closest
+//!    to that pivot. This brings the cost down to O(k·(n + m)) per run.
+//! 2. Starting from a seeded random placement, repeatedly move each node to
+//!    the weighted average position its terms ask for (localized SMACOF).
+//!    Stop after `iterations` sweeps or when no node moves more than
+//!    `tolerance × edge_length`.
+//!
+//! Pairs in different connected components are treated as one hop farther
+//! apart than the longest finite distance, so components do not overlap.
+//! Nodes without edges are not part of the layout and get the sentinel.
+//!
+//! # References
+//!
+//! - Gansner, Koren, North, "Graph Drawing by Stress Majorization" (2004)
+//! - Ortmann, Klimenta, Brandes, "A Sparse Stress Model" (2016)
+
+use super::{SENTINEL, placed_point};
+use super::distance::{UNREACHABLE, UndirectedCsr, select_pivots};
+use crate::rng::{DEFAULT_SEED, SeededRng};
+
+/// Configuration for the stress layout.
+pub struct StressLayoutConfig {
+    /// Ideal distance between adjacent nodes (default: 50.0).
+    pub edge_length: f32,
+    /// Maximum number of majorization sweeps (default: 100).
+    pub iterations: u32,
+    /// Number of pivots for sparse stress (default: 50).
+    /// 0, or at least as many pivots as nodes, uses exact all-pairs stress.
+    pub pivots: u32,
+    /// Convergence threshold as a fraction of `edge_length` (default: 0.0001).
+    pub tolerance: f32,
+    /// Seed for the initial placement and the first pivot.
+    pub seed: u64,
+}
+
+impl Default for StressLayoutConfig {
+    fn default() -> Self {
+        Self {
+            edge_length: 50.0,
+            iterations: 100,
+            pivots: 50,
+            tolerance: 0.0001,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+/// One stress term of a node: target node, ideal distance and weight.
+#[derive(Clone, Copy)]
+struct Term {
+    other: u32,
+    distance: f32,
+    weight: f32,
+}
+
+/// Compute a stress majorization layout.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `node_count` - Total number of node slots
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of interleaved target positions [x0, y0, x1, y1, ...],
+/// centered on the origin. Nodes without edges get sentinel values (f32::MAX).
+pub fn compute_stress_layout(
+    csr: &[u32],
+    node_count: usize,
+    config: &StressLayoutConfig,
+) -> Vec<f32> {
+    refine_stress_layout(csr, node_count, &[], config)
+}
+
+/// Compute a stress majorization layout starting from given positions.
+///
+/// Same as [`compute_stress_layout`], but nodes start at `initial`
+/// ([x0, y0, x1, y1, ...]) instead of a random placement, so an existing
+/// layout (e.g. spectral or pivot MDS) is refined rather than replaced.
+/// Nodes whose initial position is missing or non-finite (including the
+/// sentinel) start at a seeded random position.
+pub fn refine_stress_layout(
+    csr: &[u32],
+    node_count: usize,
+    initial: &[f32],
+    config: &StressLayoutConfig,
+) -> Vec<f32> {
+    let mut solver = StressSolver::new(csr, node_count, initial, config);
+    while !solver.sweep() {}
+    solver.positions()
+}
+
+/// Stress majorization one sweep at a time, for layouts that are spread
+/// over several frames.
+pub struct StressSolver {
+    node_count: usize,
+    participants: Vec<usize>,
+    /// Stress terms per participant.
+    terms: Vec<Vec<Term>>,
+    x: Vec<f32>,
+    y: Vec<f32>,
+    /// Largest move that still counts as progress.
+    threshold: f32,
+    iterations: u32,
+    done_iterations: u32,
+    converged: bool,
+}
+
+impl StressSolver {
+    /// Set up the stress terms and starting positions (the expensive BFS
+    /// part). Arguments as in [`refine_stress_layout`].
+    pub fn new(
+        csr: &[u32],
+        node_count: usize,
+        initial: &[f32],
+        config: &StressLayoutConfig,
+    ) -> Self {
+        let graph = UndirectedCsr::from_csr(csr, node_count);
+        let participants: Vec<usize> = (0..node_count).filter(|&i| graph.degree(i) > 0).collect();
+
+        let edge_length = config.edge_length.max(f32::EPSILON);
+        let mut rng = SeededRng::new(config.seed);
+        let pivot_count = config.pivots as usize;
+        let terms = if participants.is_empty() {
+            Vec::new()
+        } else if pivot_count == 0 || pivot_count >= participants.len() {
+            exact_terms(&graph, &participants, edge_length)
+        } else {
+            sparse_terms(&graph, &participants, pivot_count, edge_length, &mut rng)
+        };
+
+        // Seeded random start inside a square sized for the node count, unless
+        // the node has an initial position
+        let extent = edge_length * (participants.len() as f32).sqrt();
+        let mut x = vec![0.0f32; node_count];
+        let mut y = vec![0.0f32; node_count];
+        for &i in &participants {
+            let (rx, ry) = (rng.range_f32(-extent, extent), rng.range_f32(-extent, extent));
+            (x[i], y[i]) = placed_point(initial, i).unwrap_or((rx, ry));
+        }
+
+        Self {
+            node_count,
+            converged: participants.is_empty(),
+            participants,
+            terms,
+            x,
+            y,
+            threshold: config.tolerance * edge_length,
+            iterations: config.iterations,
+            done_iterations: 0,
+        }
+    }
+
+    /// Whether the solver has converged or used up its iterations.
+    pub fn is_done(&self) -> bool {
+        self.converged || self.done_iterations >= self.iterations
+    }
+
+    /// Fraction of the iteration budget used, 1 once done.
+    pub fn progress(&self) -> f32 {
+        if self.is_done() {
+            1.0
+        } else {
+            self.done_iterations as f32 / self.iterations as f32
+        }
+    }
+
+    /// Run one majorization sweep over all nodes. Returns true once done.
+    pub fn sweep(&mut self) -> bool {
+        if self.is_done() {
+            return true;
+        }
+        let mut max_move = 0.0f32;
+        for (&i, node_terms) in self.participants.iter().zip(&self.terms) {
+            let (nx, ny) = majorize(i, node_terms, &self.x, &self.y);
+            max_move = max_move.max((nx - self.x[i]).hypot(ny - self.y[i]));
+            self.x[i] = nx;
+            self.y[i] = ny;
+        }
+        self.done_iterations += 1;
+        self.converged = max_move < self.threshold;
+        self.is_done()
+    }
+
+    /// Current positions as [x0, y0, x1, y1, ...], centered on the origin.
+    /// Nodes without edges get sentinel values (f32::MAX).
+    pub fn positions(&self) -> Vec<f32> {
+        let mut positions = vec![SENTINEL; self.node_count * 2];
+        if self.participants.is_empty() {
+            return positions;
+        }
+        let count = self.participants.len() as f32;
+        let cx = self.participants.iter().map(|&i| self.x[i]).sum::<f32>() / count;
+        let cy = self.participants.iter().map(|&i| self.y[i]).sum::<f32>() / count;
+        for &i in &self.participants {
+            positions[i * 2] = self.x[i] - cx;
+            positions[i * 2 + 1] = self.y[i] - cy;
+        }
+        positions
+    }
+}
+
+/// Position that minimizes the stress of node `i` with all others fixed.
+fn majorize(i: usize, terms: &[Term], x: &[f32], y: &[f32]) -> (f32, f32) {
+    let (mut num_x, mut num_y, mut den) = (0.0f32, 0.0f32, 0.0f32);
+    for term in terms {
+        let j = term.other as usize;
+        let dx = x[i] - x[j];
+        let dy = y[i] - y[j];
+        let dist = dx.hypot(dy);
+        num_x += term.weight * x[j];
+        num_y += term.weight * y[j];
+        if dist > f32::EPSILON {
+            num_x += term.weight * term.distance * dx / dist;
+            num_y += term.weight * term.distance * dy / dist;
+        }
+        den += term.weight;
+    }
+    if den <= 0.0 {
+        return (x[i], y[i]);
+    }
+    (num_x / den, num_y / den)
+}
+
+/// Ideal distance for a hop count, mapping unreachable pairs to
+/// `fallback_hops`.
+fn ideal_distance(hops: u32, fallback_hops: u32, edge_length: f32) -> f32 {
+    let hops = if hops == UNREACHABLE {
+        fallback_hops
+    } else {
+        hops
+    };
+    hops.max(1) as f32 * edge_length
+}
+
+/// All-pairs stress terms (one BFS per participant).
+fn exact_terms(graph: &UndirectedCsr, participants: &[usize], edge_length: f32) -> Vec<Vec<Term>> {
+    let mut queue = Vec::new();
+    let distances: Vec<Vec<u32>> = participants
+        .iter()
+        .map(|&source| {
+            let mut dist = Vec::new();
+            graph.bfs(source, &mut dist, &mut queue);
+            dist
+        })
+        .collect();
+    let fallback = longest_finite(&distances) + 1;
+
+    participants
+        .iter()
+        .zip(&distances)
+        .map(|(&i, dist)| {
+            participants
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| {
+                    let distance = ideal_distance(dist[j], fallback, edge_length);
+                    Term {
+                        other: j as u32,
+                        distance,
+                        weight: distance.powi(-2),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Sparse stress terms: neighbors plus region-weighted pivots.
+///
+/// Following Ortmann et al., the term between node `i` and pivot `p` is
+/// weighted by the number of nodes in `p`'s region that are at most half as
+/// far from `p` as `i` is, approximating the terms it replaces.
+fn sparse_terms(
+    graph: &UndirectedCsr,
+    participants: &[usize],
+    pivot_count: usize,
+    edge_length: f32,
+    rng: &mut SeededRng,
+) -> Vec<Vec<Term>> {
+    let (pivots, distances) = select_pivots(graph, participants, pivot_count, rng);
+    let fallback = longest_finite(&distances) + 1;
+
+    // Each node joins the region of its nearest pivot; keep the hop distances
+    // of each region's members sorted so pivot weights can count them
+    let mut region_hops: Vec<Vec<u32>> = vec![Vec::new(); pivots.len()];
+    for &node in participants {
+        let nearest = (0..pivots.len())
+            .filter(|&p| distances[p][node] != UNREACHABLE)
+            .min_by_key(|&p| distances[p][node]);
+        if let Some(p) = nearest {
+            region_hops[p].push(distances[p][node]);
+        }
+    }
+    for hops in &mut region_hops {
+        hops.sort_unstable();
+    }
+
+    let neighbor_weight = edge_length.powi(-2);
+    participants
+        .iter()
+        .map(|&i| {
+            let neighbors = graph.neighbors(i);
+            let mut terms: Vec<Term> = neighbors
+                .iter()
+                .map(|&j| Term {
+                    other: j,
+                    distance: edge_length,
+                    weight: neighbor_weight,
+                })
+                .collect();
+            for (p, &pivot) in pivots.iter().enumerate() {
+                if pivot == i || neighbors.binary_search(&(pivot as u32)).is_ok() {
+                    continue;
+                }
+                let distance = ideal_distance(distances[p][i], fallback, edge_length);
+                // A pivot stands in for the part of its region that lies
+                // closer to it than half the distance to `i`
+                let half = distances[p][i] / 2;
+                let represented = region_hops[p].partition_point(|&h| h <= half).max(1);
+                let weight = represented as f32 / (distance * distance);
+                terms.push(Term {
+                    other: pivot as u32,
+                    distance,
+                    weight,
+                });
+            }
+            terms
+        })
+        .collect()
+}
+
+/// Longest finite hop distance across all BFS results.
+fn longest_finite(distances: &[Vec<u32>]) -> u32 {
+    distances
+        .iter()
+        .flat_map(|dist| dist.iter().copied())
+        .filter(|&d| d != UNREACHABLE)
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::distance::csr_from_edges;
+
+    fn distance(positions: &[f32], a: usize, b: usize) -> f32 {
+        (positions[a * 2] - positions[b * 2]).hypot(positions[a * 2 + 1] - positions[b * 2 + 1])
+    }
+
+    fn grid_edges(side: u32) -> Vec<(u32, u32)> {
+        let right = (0..side * side)
+            .filter(|i| i % side + 1 < side)
+            .map(|i| (i, i + 1));
+        let down = (0..side * (side - 1)).map(|i| (i, i + side));
+        right.chain(down).collect()
+    }
+
+    #[test]
+    fn test_path_is_straightened() {
+        let csr = csr_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4)]);
+        let positions = compute_stress_layout(&csr, 5, &StressLayoutConfig::default());
+
+        for i in 0..4 {
+            let d = distance(&positions, i, i + 1);
+            assert!((d - 50.0).abs() < 5.0, "Edge {i} length {d}");
+        }
+        let end_to_end = distance(&positions, 0, 4);
+        assert!(
+            (end_to_end - 200.0).abs() < 20.0,
+            "Path should be straight, got {end_to_end}"
+        );
+    }
+
+    #[test]
+    fn test_isolated_nodes_get_sentinel() {
+        let csr = csr_from_edges(3, &[(0, 1)]);
+        let positions = compute_stress_layout(&csr, 3, &StressLayoutConfig::default());
+
+        assert_eq!(positions.len(), 6);
+        assert!(positions[0] < SENTINEL);
+        assert_eq!(positions[4], SENTINEL);
+        assert_eq!(positions[5], SENTINEL);
+
+        let empty =
+            compute_stress_layout(&csr_from_edges(2, &[]), 2, &StressLayoutConfig::default());
+        assert!(empty.iter().all(|&v| v == SENTINEL));
+    }
+
+    #[test]
+    fn test_refine_keeps_initial_orientation() {
+        // A vertical path at twice the edge length contracts but stays vertical
+        let csr = csr_from_edges(4, &[(0, 1), (1, 2), (2, 3)]);
+        let initial: Vec<f32> = (0..4).flat_map(|i| [0.0, i as f32 * 100.0]).collect();
+        let positions = refine_stress_layout(&csr, 4, &initial, &StressLayoutConfig::default());
+
+        for i in 0..4 {
+            assert!(positions[i * 2].abs() < 1.0, "Node {i} left the axis");
+        }
+        assert!((distance(&positions, 0, 3) - 150.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_same_seed_same_layout() {
+        let csr = csr_from_edges(16, &grid_edges(4));
+        let config = StressLayoutConfig {
+            seed: 9,
+            ..Default::default()
+        };
+        let a = compute_stress_layout(&csr, 16, &config);
+        let b = compute_stress_layout(&csr, 16, &config);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_sparse_grid_keeps_global_shape() {
+        let side = 20;
+        let n = (side * side) as usize;
+        let csr = csr_from_edges(n, &grid_edges(side));
+        let config = StressLayoutConfig {
+            pivots: 30,
+            ..Default::default()
+        };
+        let positions = compute_stress_layout(&csr, n, &config);
+
+        let edges = grid_edges(side);
+        let mean_edge: f32 = edges
+            .iter()
+            .map(|&(a, b)| distance(&positions, a as usize, b as usize))
+            .sum::<f32>()
+            / edges.len() as f32;
+        assert!(
+            (mean_edge - 50.0).abs() < 12.5,
+            "Mean edge length {mean_edge}"
+        );
+
+        // Opposite corners should be roughly a diagonal apart, not folded
+        let diagonal = distance(&positions, 0, n - 1);
+        assert!(
+            diagonal > 19.0 * 50.0,
+            "Grid folded: corner distance {diagonal}"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Automatic layout recommendation.
+//!
+//! Profiles the graph structure and picks the layout that usually suits it
+//! best, with starting parameters. The checks run from the most specific
+//! structure to the most general:
+//!
+//! 1. **No edges** → grid.
+//! 2. **Forest** (no undirected cycles) → tidy tree, radial for large trees.
+//! 3. **Sparse DAG** (directed acyclic, at most 1.5 edges per node) → tidy
+//!    tree, which follows the edge direction and cuts the extra parents.
+//! 4. **Bipartite** → two-layer bipartite layout.
+//! 5. **Strong communities** (modularity ≥ 0.4 over at least 3 groups) →
+//!    community layout.
+//! 6. **Small and dense** (up to 100 nodes, density ≥ 0.3) → circle grouped
+//!    by community; node-link drawings of such graphs are hairballs anyway.
+//! 7. Otherwise **stress** (sparse stress above 1000 nodes), or the
+//!    **multilevel** force layout above 5000 nodes.
+
+use serde::Serialize;
+
+use super::bipartite::bipartite_sides;
+use super::community;
+use super::distance::{UndirectedCsr, csr_from_edges, csr_pairs};
+use super::stress::StressLayoutConfig;
+
+/// Largest edges-per-node ratio of a DAG still drawn as a tree.
+const TREE_DAG_RATIO: f32 = 1.5;
+/// Smallest modularity counted as community structure.
+const COMMUNITY_MODULARITY: f64 = 0.4;
+/// Largest graph for the dense circular recommendation.
+const DENSE_MAX_NODES: u32 = 100;
+/// Smallest density counted as dense.
+const DENSE_DENSITY: f32 = 0.3;
+/// Largest graph for exact stress; larger graphs use pivots.
+const EXACT_STRESS_NODES: u32 = 1000;
+/// Largest graph for stress; larger graphs use the multilevel layout.
+const STRESS_MAX_NODES: u32 = 5000;
+/// Smallest tree drawn radially.
+const RADIAL_TREE_NODES: u32 = 200;
+
+/// Structural profile of a graph. Edges count as undirected and once per
+/// node pair unless noted.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphProfile {
+    /// Number of nodes.
+    pub node_count: u32,
+    /// Number of distinct node pairs joined by an edge (self loops excluded).
+    pub edge_count: u32,
+    /// Connected components among nodes with edges.
+    pub components: u32,
+    /// Nodes without any edge.
+    pub isolated: u32,
+    /// Edge count over the number of possible node pairs.
+    pub density: f32,
+    /// Largest number of neighbors of a node.
+    pub max_degree: u32,
+    /// No undirected cycles or self loops.
+    pub is_forest: bool,
+    /// No directed cycles or self loops.
+    pub is_dag: bool,
+    /// Nodes can be split into two sides with edges only between them.
+    pub is_bipartite: bool,
+    /// Modularity of the Louvain communities.
+    pub modularity: f64,
+    /// Number of Louvain communities among nodes with edges.
+    pub community_count: u32,
+}
+
+/// A recommended layout and its parameters, tagged by `type` like the
+/// pipeline stage descriptors.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SuggestedLayout {
+    /// `computeGridLayout`.
+    Grid { columns: u32 },
+    /// `computeTreeLayoutFromGraph`.
+    Tree { radial: bool },
+    /// `computeBipartiteLayout` with automatic sides.
+    Bipartite,
+    /// `computeCommunityLayoutFromGraph`.
+    Community { resolution: f32 },
+    /// `computeCircularLayout`; ordering 1 groups by community.
+    Circular { ordering: u32 },
+    /// `computeStressLayout`; 0 pivots = exact stress.
+    Stress { edge_length: f32, pivots: u32 },
+    /// `computeMultilevelLayout`.
+    Multilevel,
+}
+
+/// Layout recommendation with the reasoning behind it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutSuggestion {
+    /// The recommended layout.
+    pub layout: SuggestedLayout,
+    /// Short human-readable reason for the choice.
+    pub reason: &'static str,
+    /// The structural profile the choice is based on.
+    pub profile: GraphProfile,
+}
+
+/// Profile the structure of a graph.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+pub fn profile_graph(csr: &[u32], present: &[bool]) -> GraphProfile {
+    let n = present.len();
+    let directed: Vec<(usize, usize)> = csr_pairs(csr, n)
+        .filter(|&(u, v)| v < n && present[u] && present[v])
+        .collect();
+    let self_loops = directed.iter().any(|&(u, v)| u == v);
+    let graph = UndirectedCsr::from_csr(&csr_of(n, &directed), n);
+
+    let node_count = present.iter().filter(|&&p| p).count() as u32;
+    let degree = |i: usize| {
+        graph
+            .neighbors(i)
+            .iter()
+            .filter(|&&j| j as usize != i)
+            .count()
+    };
+    let edge_count = ((0..n).map(degree).sum::<usize>() / 2) as u32;
+    let components = graph.components();
+    let connected: usize = components.iter().map(Vec::len).sum();
+    let pairs = node_count as f64 * node_count.saturating_sub(1) as f64 / 2.0;
+
+    let sides = bipartite_sides(&graph, present, &[]);
+    let is_bipartite = !self_loops && directed.iter().all(|&(u, v)| sides[u] != sides[v]);
+    let communities = community::detect_communities(&csr_of(n, &directed), n, 1.0, 100, 0.0001);
+    let mut groups: Vec<u32> = components
+        .iter()
+        .flatten()
+        .map(|&i| communities.assignments[i])
+        .collect();
+    groups.sort_unstable();
+    groups.dedup();
+
+    GraphProfile {
+        node_count,
+        edge_count,
+        components: components.len() as u32,
+        isolated: node_count - connected as u32,
+        density: if pairs > 0.0 {
+            (edge_count as f64 / pairs) as f32
+        } else {
+            0.0
+        },
+        max_degree: (0..n).map(degree).max().unwrap_or(0) as u32,
+        is_forest: !self_loops && edge_count as usize + components.len() == connected,
+        is_dag: !self_loops && is_acyclic(n, &directed),
+        is_bipartite,
+        modularity: communities.modularity,
+        community_count: groups.len() as u32,
+    }
+}
+
+/// Recommend a layout for a graph.
+///
+/// # Arguments
+///
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `present` - Whether each slot holds a node (length `node_bound`)
+pub fn suggest_layout(csr: &[u32], present: &[bool]) -> LayoutSuggestion {
+    let profile = profile_graph(csr, present);
+    let (layout, reason) = choose(&profile);
+    LayoutSuggestion {
+        layout,
+        reason,
+        profile,
+    }
+}
+
+/// Pick a layout from a profile.
+fn choose(p: &GraphProfile) -> (SuggestedLayout, &'static str) {
+    let n = p.node_count;
+    if p.edge_count == 0 {
+        let columns = (n as f32).sqrt().ceil().max(1.0) as u32;
+        return (SuggestedLayout::Grid { columns }, "no edges");
+    }
+    if p.is_forest {
+        let radial = n >= RADIAL_TREE_NODES;
+        return (SuggestedLayout::Tree { radial }, "tree structure");
+    }
+    if p.is_dag && p.edge_count as f32 <= TREE_DAG_RATIO * n as f32 {
+        let radial = n >= RADIAL_TREE_NODES;
+        return (
+            SuggestedLayout::Tree { radial },
+            "sparse directed acyclic graph",
+        );
+    }
+    if p.is_bipartite {
+        return (SuggestedLayout::Bipartite, "bipartite");
+    }
+    if p.modularity >= COMMUNITY_MODULARITY && p.community_count >= 3 {
+        let layout = SuggestedLayout::Community { resolution: 1.0 };
+        return (layout, "strong community structure");
+    }
+    if n <= DENSE_MAX_NODES && p.density >= DENSE_DENSITY {
+        let layout = SuggestedLayout::Circular { ordering: 1 };
+        return (layout, "small dense graph");
+    }
+    if n > STRESS_MAX_NODES {
+        return (SuggestedLayout::Multilevel, "large general graph");
+    }
+    let defaults = StressLayoutConfig::default();
+    let layout = SuggestedLayout::Stress {
+        edge_length: defaults.edge_length,
+        pivots: if n <= EXACT_STRESS_NODES {
+            0
+        } else {
+            defaults.pivots
+        },
+    };
+    (layout, "general graph")
+}
+
+/// Whether the directed edges have no cycle (Kahn's algorithm).
+fn is_acyclic(n: usize, edges: &[(usize, usize)]) -> bool {
+    let mut in_degree = vec![0u32; n];
+    let mut out: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        in_degree[v] += 1;
+        out[u].push(v);
+    }
+    let mut stack: Vec<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+    let mut visited = 0;
+    while let Some(u) = stack.pop() {
+        visited += 1;
+        for &v in &out[u] {
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                stack.push(v);
+            }
+        }
+    }
+    visited == n
+}
+
+/// Directed CSR of the given edges.
+fn csr_of(n: usize, edges: &[(usize, usize)]) -> Vec<u32> {
+    let pairs: Vec<(u32, u32)> = edges.iter().map(|&(u, v)| (u as u32, v as u32)).collect();
+    csr_from_edges(n, &pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest(n: usize, edges: &[(u32, u32)]) -> LayoutSuggestion {
+        suggest_layout(&csr_from_edges(n, edges), &vec![true; n])
+    }
+
+    #[test]
+    fn test_trees_and_dags_get_tree_layout() {
+        let tree = suggest(5, &[(0, 1), (0, 2), (1, 3), (1, 4)]);
+        assert!(tree.profile.is_forest && tree.profile.is_dag);
+        assert_eq!(tree.layout, SuggestedLayout::Tree { radial: false });
+
+        // Diamond: not a forest, but a sparse DAG
+        let diamond = suggest(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert!(!diamond.profile.is_forest && diamond.profile.is_dag);
+        assert_eq!(diamond.profile.components, 1);
+        assert_eq!(diamond.reason, "sparse directed acyclic graph");
+    }
+
+    #[test]
+    fn test_bipartite_cycle_and_dense_clique() {
+        // Directed 4-cycle: bipartite, not a DAG
+        let square = suggest(4, &[(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert!(square.profile.is_bipartite && !square.profile.is_dag);
+        assert_eq!(square.layout, SuggestedLayout::Bipartite);
+
+        // Cyclic K5: dense
+        let edges: Vec<(u32, u32)> = (0..5)
+            .flat_map(|i| (0..5).filter(move |&j| j != i).map(move |j| (i, j)))
+            .collect();
+        let clique = suggest(5, &edges);
+        assert_eq!(clique.profile.edge_count, 10);
+        assert_eq!(clique.profile.density, 1.0);
+        assert_eq!(clique.profile.max_degree, 4);
+        assert_eq!(clique.layout, SuggestedLayout::Circular { ordering: 1 });
+    }
+
+    #[test]
+    fn test_communities_and_edgeless_graphs() {
+        // Four directed triangles joined in a ring
+        let mut edges = Vec::new();
+        for c in 0..4u32 {
+            let b = c * 3;
+            edges.extend([(b, b + 1), (b + 1, b + 2), (b + 2, b), (b, (b + 3) % 12)]);
+        }
+        let rings = suggest(12, &edges);
+        assert!(rings.profile.community_count >= 3);
+        assert_eq!(rings.layout, SuggestedLayout::Community { resolution: 1.0 });
+
+        let empty = suggest(10, &[]);
+        assert_eq!(empty.profile.isolated, 10);
+        assert_eq!(empty.layout, SuggestedLayout::Grid { columns: 4 });
+    }
+}
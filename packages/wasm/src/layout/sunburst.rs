@@ -0,0 +1,116 @@
+//! Sunburst layout.
+//!
+//! The radial variant of the icicle layout: the root is the center, each
+//! depth level is a ring, and every node covers an angular sector sized by
+//! its subtree. Uses the same hierarchy and spans as the icicle layout
+//! ([`partition_spans`]), so both views of a tree stay consistent.
+
+use std::f32::consts::TAU;
+
+use super::SENTINEL;
+use super::icicle::partition_spans;
+
+/// Configuration for the sunburst layout.
+pub struct SunburstLayoutConfig {
+    /// Radial thickness of each ring (default: 40.0).
+    pub ring_width: f32,
+    /// Inner radius of the root; 0 draws the root as a full disk
+    /// (default: 0.0).
+    pub inner_radius: f32,
+    /// Angle where the root's sector starts, in radians, 0 = +x, y down
+    /// (default: -π/2, top).
+    pub start_angle: f32,
+}
+
+impl Default for SunburstLayoutConfig {
+    fn default() -> Self {
+        Self {
+            ring_width: 40.0,
+            inner_radius: 0.0,
+            start_angle: -std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+/// Compute a sunburst layout from the containment hierarchy.
+///
+/// # Arguments
+///
+/// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+/// * `weights` - Optional size per leaf slot (empty = 1 per leaf)
+/// * `node_count` - Total number of node slots
+/// * `root_id` - Optional root node ID (None = auto-detect)
+/// * `config` - Layout configuration
+///
+/// # Returns
+///
+/// A `Vec<f32>` of per-node sectors
+/// [startAngle0, endAngle0, innerRadius0, outerRadius0, ...], angles in
+/// radians increasing clockwise on screen (y down), centered on the origin.
+/// Nodes not in the tree get sentinel values (f32::MAX).
+pub fn compute_sunburst_layout(
+    containment_edges: &[u32],
+    weights: &[f32],
+    node_count: usize,
+    root_id: Option<u32>,
+    config: &SunburstLayoutConfig,
+) -> Vec<f32> {
+    let mut sectors = vec![SENTINEL; node_count * 4];
+    let Some(spans) = partition_spans(containment_edges, weights, node_count, root_id) else {
+        return sectors;
+    };
+
+    for &node in &spans.order {
+        let start = config.start_angle + spans.start[node] as f32 * TAU;
+        let end = start + spans.extent[node] as f32 * TAU;
+        let inner = config.inner_radius + spans.depth[node] as f32 * config.ring_width;
+        sectors[node * 4..node * 4 + 4].copy_from_slice(&[
+            start,
+            end,
+            inner,
+            inner + config.ring_width,
+        ]);
+    }
+
+    sectors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sectors_split_by_subtree_and_rings_by_depth() {
+        // 0 → {1, 2}, 1 → {3, 4}
+        let edges = [0, 1, 0, 2, 1, 3, 1, 4];
+        let config = SunburstLayoutConfig {
+            start_angle: 0.0,
+            ..Default::default()
+        };
+        let sectors = compute_sunburst_layout(&edges, &[], 5, None, &config);
+        let sector = |slot: usize| &sectors[slot * 4..slot * 4 + 4];
+
+        assert_eq!(sector(0), &[0.0, TAU, 0.0, 40.0]);
+        assert!((sector(1)[1] - TAU * 2.0 / 3.0).abs() < 1e-5);
+        assert_eq!(&sector(1)[2..], &[40.0, 80.0]);
+        assert_eq!(sector(2)[0], sector(1)[1]);
+        assert!((sector(2)[1] - TAU).abs() < 1e-5);
+        assert_eq!(sector(3)[0], 0.0);
+        assert_eq!(&sector(4)[2..], &[80.0, 120.0]);
+    }
+
+    #[test]
+    fn test_inner_radius_and_missing_tree() {
+        let config = SunburstLayoutConfig {
+            inner_radius: 25.0,
+            ..Default::default()
+        };
+        let sectors = compute_sunburst_layout(&[0, 1], &[], 3, Some(0), &config);
+        assert_eq!(&sectors[2..4], &[25.0, 65.0]);
+        assert_eq!(&sectors[6..8], &[65.0, 105.0]);
+        assert_eq!(sectors[8], SENTINEL);
+
+        let odd = compute_sunburst_layout(&[0, 1, 2], &[], 3, None, &config);
+        assert!(odd.iter().all(|&v| v == SENTINEL));
+    }
+}
@@ -5,23 +5,44 @@
 //! m-ary trees with compact, aesthetically pleasing positioning.
 //!
 //! The algorithm produces (x, depth) coordinates per node, which can be
-//! transformed into either linear (top-down) or radial (polar) coordinates
-//! for visualization.
+//! transformed into either linear or radial (polar) coordinates for
+//! visualization. Linear trees grow top-down, bottom-up, left-right or
+//! right-left; radial trees start at any angle, go either way around, and
+//! can cover a partial fan and leave a hole around the root for other
+//! content.
 //!
 //! # Algorithm Overview
 //!
-//! 1. **First walk (bottom-up):** Recursively assign preliminary x-coordinates
-//!    to each node by merging subtree contours. Uses threads for O(1) amortized
-//!    contour traversal.
-//! 2. **Second walk (top-down):** Apply accumulated modifiers to convert
-//!    preliminary x-coordinates to final positions.
+//! 1. **First walk (bottom-up):** Visit the nodes in reverse preorder, taken
+//!    from an explicit stack rather than recursion, and assign preliminary
+//!    x-coordinates by merging subtree contours. Uses threads for O(1)
+//!    amortized contour traversal.
+//! 2. **Second walk (top-down):** Visit the nodes in preorder and apply
+//!    accumulated modifiers to convert preliminary x-coordinates to final
+//!    positions. Neither walk recurses, so arbitrarily deep trees are safe.
 //! 3. **Coordinate transform:** Convert (x, depth) to desired coordinate system
 //!    (linear or radial).
+//!
+//! # Node Sizes
+//!
+//! Nodes can carry an extent (width along the sibling axis, height along the
+//! level axis). Contour separation then keeps the given gap between node
+//! edges rather than centers, and each level is as deep as its tallest
+//! node, so large labeled nodes get room and small leaves pack tightly.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::Deserialize;
 
-use std::collections::{HashMap, HashSet};
+use super::SENTINEL;
+
+/// Rank value meaning "no level constraint" in a rank buffer.
+pub const FREE_RANK: u32 = u32::MAX;
 
 /// Coordinate mode for the final layout.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum CoordinateMode {
     /// Linear top-down layout: x = horizontal, y = depth * level_spacing.
     Linear,
@@ -29,8 +50,25 @@ pub enum CoordinateMode {
     Radial,
 }
 
-/// Configuration for the tidy tree layout.
-#[derive(Debug, Clone)]
+/// Direction the levels of a linear tree run in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeOrientation {
+    /// Root at the top, levels going down.
+    #[default]
+    TopDown,
+    /// Root at the bottom, levels going up.
+    BottomUp,
+    /// Root on the left, levels going right.
+    LeftRight,
+    /// Root on the right, levels going left.
+    RightLeft,
+}
+
+/// Configuration for the tidy tree layout. Deserializes from camelCase JS
+/// options, with defaults for omitted fields.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct TidyTreeConfig {
     /// Minimum horizontal separation between sibling nodes.
     pub sibling_separation: f32,
@@ -40,6 +78,24 @@ pub struct TidyTreeConfig {
     pub level_separation: f32,
     /// Coordinate output mode.
     pub coordinate_mode: CoordinateMode,
+    /// Direction of the levels in linear mode (default: top-down).
+    pub orientation: TreeOrientation,
+    /// Angle of the leftmost subtree in radial mode, in radians from the +x
+    /// axis toward +y (default: 0).
+    pub start_angle: f32,
+    /// Whether radial mode goes around clockwise on a y-down screen, i.e.
+    /// toward +y (default: true).
+    pub clockwise: bool,
+    /// Angle covered by radial mode from `start_angle`, in radians; less
+    /// than a full turn lays the tree out as a fan (default: TAU).
+    pub angular_span: f32,
+    /// Radius of the empty hole radial mode leaves around the root; the
+    /// rings move out by this much (default: 0).
+    pub inner_radius: f32,
+    /// Whether radial mode pushes crowded rings outward until neighbors on
+    /// a ring are as far apart as in linear mode, instead of spacing rings
+    /// `level_separation` apart (default: false).
+    pub expand_crowded_rings: bool,
 }
 
 impl Default for TidyTreeConfig {
@@ -49,10 +105,51 @@ impl Default for TidyTreeConfig {
             subtree_separation: 2.0,
             level_separation: 80.0,
             coordinate_mode: CoordinateMode::Radial,
+            orientation: TreeOrientation::TopDown,
+            start_angle: 0.0,
+            clockwise: true,
+            angular_span: std::f32::consts::TAU,
+            inner_radius: 0.0,
+            expand_crowded_rings: false,
         }
     }
 }
 
+/// Options of the tree layout entry points, as a JS object:
+///
+/// ```text
+/// { rootId: 0, levelSeparation: 100, coordinateMode: "linear",
+///   orientation: "leftRight", ranks: [...] }
+/// ```
+///
+/// Every field is optional; the layout fields are those of
+/// [`TidyTreeConfig`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreeLayoutOptions {
+    /// Root node ID (default: auto-detect).
+    pub root_id: Option<u32>,
+    /// Level per node slot ([`FREE_RANK`] = free), see
+    /// [`TidyTreeLayout::compute_with_inputs`].
+    pub ranks: Vec<u32>,
+    /// Layout configuration.
+    #[serde(flatten)]
+    pub config: TidyTreeConfig,
+}
+
+/// Per-node inputs of the tidy tree layout, by node slot. Empty or short
+/// buffers leave the remaining nodes unconstrained.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeNodeInputs<'a> {
+    /// Requested level per slot ([`FREE_RANK`] = one below the parent).
+    pub ranks: &'a [u32],
+    /// Node extents [w0, h0, w1, h1, ...]: width along the sibling axis and
+    /// height along the level axis, in output units (0 = a point).
+    pub extents: &'a [f32],
+    /// Collapsed nodes: laid out themselves, but their children are not.
+    pub collapsed: &'a [bool],
+}
+
 /// Internal node data used during the Buchheim algorithm.
 #[derive(Debug)]
 struct LayoutNode {
@@ -80,6 +177,8 @@ struct LayoutNode {
     change: f32,
     /// Number (left-to-right index among siblings).
     number: usize,
+    /// Half the node width, in x units.
+    half_width: f32,
 }
 
 /// Result of the tidy tree layout computation.
@@ -88,6 +187,12 @@ pub struct TidyTreeResult {
     pub positions_x: Vec<f32>,
     /// Target Y positions (one per node in graph slot order).
     pub positions_y: Vec<f32>,
+    /// Tree level per slot (0 = root, after ranks), `u32::MAX` for nodes not
+    /// in the tree.
+    pub depths: Vec<u32>,
+    /// Number of nodes in each slot's subtree, itself included (0 for nodes
+    /// not in the tree).
+    pub subtree_sizes: Vec<u32>,
     /// Number of nodes laid out.
     pub node_count: usize,
 }
@@ -127,14 +232,47 @@ impl TidyTreeLayout {
         edges: &[u32],
         root_id: Option<u32>,
     ) -> TidyTreeResult {
-        // Sentinel value for "not part of tree". The GPU shader checks for this
-        // to skip non-tree nodes. Using a very large value that no real layout
-        // position would produce — the shader checks `target_pos.x >= SENTINEL`.
-        const SENTINEL: f32 = 3.402_823e+38;
+        self.compute_with_inputs(node_count, edges, root_id, &TreeNodeInputs::default())
+    }
 
+    /// Compute the tidy tree layout with per-node levels and sizes.
+    ///
+    /// `inputs.ranks` holds the level per node slot ([`FREE_RANK`] or
+    /// missing = one below its parent). A rank can push a node further down,
+    /// never above its parent: the effective level is at least the parent's
+    /// plus one. Edges spanning several levels get invisible dummy nodes on
+    /// the levels in between, so the contour separation still keeps subtrees
+    /// apart on every level they cross.
+    ///
+    /// With `inputs.extents`, sibling and subtree separations are measured
+    /// between node edges, and level separation between the bottom of a
+    /// level's tallest node and the top of the next level's tallest node.
+    ///
+    /// Nodes reachable only through `inputs.collapsed` nodes are left out
+    /// (sentinel positions) and the rest of the tree closes up around them.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_count` - Total number of node slots (may include holes from removals)
+    /// * `edges` - Flat array of directed edge pairs [src0, tgt0, src1, tgt1, ...]
+    ///   representing parent→child relationships
+    /// * `root_id` - The root node ID (or None to auto-detect)
+    /// * `inputs` - Requested levels and extents per node slot
+    pub fn compute_with_inputs(
+        &self,
+        node_count: usize,
+        edges: &[u32],
+        root_id: Option<u32>,
+        inputs: &TreeNodeInputs,
+    ) -> TidyTreeResult {
+        // Nodes not part of the tree get SENTINEL, a value no real layout
+        // position would produce; the GPU shader skips them by checking
+        // `target_pos.x >= SENTINEL`.
         let empty_result = || TidyTreeResult {
             positions_x: vec![SENTINEL; node_count],
             positions_y: vec![SENTINEL; node_count],
+            depths: vec![u32::MAX; node_count],
+            subtree_sizes: vec![0; node_count],
             node_count: 0,
         };
 
@@ -150,7 +288,7 @@ impl TidyTreeLayout {
         // Build adjacency: parent → children
         let mut children_map: HashMap<u32, Vec<u32>> = HashMap::new();
         let mut has_parent: HashMap<u32, bool> = HashMap::new();
-        let mut all_nodes: HashSet<u32> = HashSet::new();
+        let mut all_nodes: BTreeSet<u32> = BTreeSet::new();
 
         let edge_count = edges.len() / 2;
         for i in 0..edge_count {
@@ -194,10 +332,10 @@ impl TidyTreeLayout {
             } else if roots.len() == 1 {
                 roots[0]
             } else {
-                // Multiple roots: pick the one with most descendants
+                // Multiple roots: pick the one with most descendants (lowest ID on ties)
                 roots
                     .iter()
-                    .max_by_key(|&&r| Self::count_descendants(r, &children_map))
+                    .max_by_key(|&&r| (Self::count_descendants(r, &children_map), Reverse(r)))
                     .copied()
                     .unwrap_or(roots[0])
             }
@@ -210,28 +348,31 @@ impl TidyTreeLayout {
 
         Self::build_layout_tree(
             root,
-            None,
-            0,
             &children_map,
+            inputs.collapsed,
             &mut layout_nodes,
             &mut node_to_layout,
             &mut visited,
         );
 
+        if !inputs.ranks.is_empty() {
+            Self::apply_ranks(&mut layout_nodes, inputs.ranks);
+        }
+        let level_offsets = self.apply_extents(&mut layout_nodes, inputs.extents);
+
         if layout_nodes.is_empty() {
-            return TidyTreeResult {
-                positions_x: vec![SENTINEL; node_count],
-                positions_y: vec![SENTINEL; node_count],
-                node_count: 0,
-            };
+            return empty_result();
         }
 
-        // Run Buchheim's algorithm
-        self.first_walk(0, &mut layout_nodes);
+        // Run Buchheim's algorithm. Both walks iterate over a preorder of the
+        // (possibly rank-bridged) tree instead of recursing, so deep chains
+        // cannot overflow the stack.
+        let order = Self::preorder(&layout_nodes);
+        self.first_walk(&order, &mut layout_nodes);
 
         // Collect final prelim values after second walk
         let mut final_x: Vec<f32> = vec![0.0; layout_nodes.len()];
-        self.second_walk_collect(0, 0.0, &layout_nodes, &mut final_x);
+        Self::second_walk_collect(&order, &layout_nodes, &mut final_x);
 
         // Center the tree: find min x and shift everything so min_x = 0
         let min_x = final_x.iter().copied().fold(f32::INFINITY, f32::min);
@@ -245,6 +386,16 @@ impl TidyTreeLayout {
         let mut positions_x = vec![SENTINEL; node_count];
         let mut positions_y = vec![SENTINEL; node_count];
         let mut laid_out = 0;
+        // Radial angles grow toward +y when clockwise on a y-down screen
+        let turn = if self.config.clockwise { 1.0 } else { -1.0 };
+        let span = self.config.angular_span.clamp(0.0, std::f32::consts::TAU);
+        // A full circle keeps a sibling gap between the last subtree and the
+        // first; a fan runs exactly from its start angle to its end angle
+        let wrap_gap = if span < std::f32::consts::TAU {
+            0.0
+        } else {
+            self.config.sibling_separation
+        };
 
         match self.config.coordinate_mode {
             CoordinateMode::Linear => {
@@ -253,25 +404,27 @@ impl TidyTreeLayout {
                 for (layout_idx, node) in layout_nodes.iter().enumerate() {
                     let slot = node.slot;
                     if slot < node_count {
-                        positions_x[slot] =
+                        let across =
                             (final_x[layout_idx] + x_offset) * self.config.level_separation;
-                        positions_y[slot] =
-                            node.depth as f32 * self.config.level_separation;
+                        let along = level_offsets[node.depth as usize];
+                        (positions_x[slot], positions_y[slot]) = self.orient(across, along);
                         laid_out += 1;
                     }
                 }
             }
             CoordinateMode::Radial => {
-                // Map x range to angular range (0..2*PI), depth to radius
-                let divisor = x_range + self.config.sibling_separation;
+                // Map x range to the angular span, depth to radius
+                let divisor = x_range + wrap_gap;
                 if x_range > 0.0 && divisor > f32::EPSILON {
-                    let angular_scale = std::f32::consts::TAU / divisor;
+                    let angular_scale = turn * span / divisor;
+                    let radii =
+                        self.ring_radii(&layout_nodes, &final_x, angular_scale, &level_offsets);
                     for (layout_idx, node) in layout_nodes.iter().enumerate() {
                         let slot = node.slot;
                         if slot < node_count {
                             let normalized_x = final_x[layout_idx] - min_x;
-                            let angle = normalized_x * angular_scale;
-                            let radius = (node.depth as f32 + 1.0) * self.config.level_separation;
+                            let angle = self.config.start_angle + normalized_x * angular_scale;
+                            let radius = radii[node.depth as usize];
                             positions_x[slot] = radius * angle.cos();
                             positions_y[slot] = radius * angle.sin();
                             laid_out += 1;
@@ -299,13 +452,97 @@ impl TidyTreeLayout {
             }
         }
 
+        let (depths, subtree_sizes) = Self::levels_and_sizes(&order, &layout_nodes, node_count);
         TidyTreeResult {
             positions_x,
             positions_y,
+            depths,
+            subtree_sizes,
             node_count: laid_out,
         }
     }
 
+    /// Depth and subtree size per slot. Dummy nodes from ranks count
+    /// toward neither.
+    fn levels_and_sizes(
+        order: &[usize],
+        nodes: &[LayoutNode],
+        node_count: usize,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let mut sizes = vec![0u32; nodes.len()];
+        // Reverse preorder finishes every subtree before its root
+        for &v in order.iter().rev() {
+            let own = u32::from(nodes[v].slot < node_count);
+            sizes[v] = own + nodes[v].children.iter().map(|&c| sizes[c]).sum::<u32>();
+        }
+
+        let mut depths = vec![u32::MAX; node_count];
+        let mut subtree_sizes = vec![0; node_count];
+        for (v, node) in nodes.iter().enumerate() {
+            if node.slot < node_count {
+                depths[node.slot] = node.depth;
+                subtree_sizes[node.slot] = sizes[v];
+            }
+        }
+        (depths, subtree_sizes)
+    }
+
+    /// Radius of every level's ring in radial mode.
+    ///
+    /// Rings sit `level_separation` beyond the linear level offsets. With
+    /// `expand_crowded_rings`, a ring also moves out until the arc between
+    /// any two neighbors on it is at least their linear separation, and the
+    /// rings outside it move along to keep their spacing.
+    fn ring_radii(
+        &self,
+        nodes: &[LayoutNode],
+        final_x: &[f32],
+        angular_scale: f32,
+        level_offsets: &[f32],
+    ) -> Vec<f32> {
+        let base: Vec<f32> = level_offsets
+            .iter()
+            .map(|offset| self.config.inner_radius + offset + self.config.level_separation)
+            .collect();
+        if !self.config.expand_crowded_rings {
+            return base;
+        }
+
+        // (x, half width) of the nodes on each ring
+        let mut rings: Vec<Vec<(f32, f32)>> = vec![Vec::new(); base.len()];
+        for (v, node) in nodes.iter().enumerate() {
+            rings[node.depth as usize].push((final_x[v], node.half_width));
+        }
+        let crowded_radius = |ring: &mut Vec<(f32, f32)>| {
+            ring.sort_by(|a, b| a.0.total_cmp(&b.0));
+            ring.windows(2)
+                .map(|pair| {
+                    let angle = (pair[1].0 - pair[0].0) * angular_scale.abs();
+                    let gap = self.config.sibling_separation + pair[0].1 + pair[1].1;
+                    gap * self.config.level_separation / angle.max(f32::EPSILON)
+                })
+                .fold(0.0, f32::max)
+        };
+
+        let mut radii = base.clone();
+        for depth in 1..radii.len() {
+            let spaced = radii[depth - 1] + base[depth] - base[depth - 1];
+            radii[depth] = spaced.max(crowded_radius(&mut rings[depth]));
+        }
+        radii
+    }
+
+    /// Map a linear position (`across` the levels, `along` them from the
+    /// root) to output coordinates for the configured orientation.
+    fn orient(&self, across: f32, along: f32) -> (f32, f32) {
+        match self.config.orientation {
+            TreeOrientation::TopDown => (across, along),
+            TreeOrientation::BottomUp => (across, -along),
+            TreeOrientation::LeftRight => (along, across),
+            TreeOrientation::RightLeft => (-along, across),
+        }
+    }
+
     /// Count descendants of a node (for root selection heuristic).
     /// Uses visited set to handle cycles safely.
     fn count_descendants(node: u32, children_map: &HashMap<u32, Vec<u32>>) -> usize {
@@ -326,72 +563,187 @@ impl TidyTreeLayout {
         count
     }
 
-    /// Build the layout tree via DFS from root.
-    /// Uses a visited set to prevent infinite recursion on cyclic graphs.
-    /// Nodes already visited are skipped (breaking the cycle).
+    /// Build the layout tree via DFS from root, in preorder.
+    /// Uses a visited set to break cycles: nodes already visited are
+    /// skipped. The DFS runs on an explicit stack so that arbitrarily deep
+    /// trees don't overflow the call stack. Collapsed nodes become leaves.
     fn build_layout_tree(
-        node_id: u32,
-        parent_layout_idx: Option<usize>,
-        depth: u32,
+        root: u32,
         children_map: &HashMap<u32, Vec<u32>>,
+        collapsed: &[bool],
         layout_nodes: &mut Vec<LayoutNode>,
         node_to_layout: &mut HashMap<u32, usize>,
         visited: &mut HashSet<u32>,
     ) {
-        // Cycle detection: skip already-visited nodes
-        if !visited.insert(node_id) {
-            return;
-        }
+        // (node, parent layout index, depth, number among the parent's children)
+        let mut stack: Vec<(u32, Option<usize>, u32, usize)> = vec![(root, None, 0, 0)];
 
-        let layout_idx = layout_nodes.len();
-        node_to_layout.insert(node_id, layout_idx);
-
-        layout_nodes.push(LayoutNode {
-            slot: node_id as usize,
-            depth,
-            parent: parent_layout_idx,
-            children: Vec::new(),
-            prelim: 0.0,
-            modifier: 0.0,
-            thread_left: None,
-            thread_right: None,
-            ancestor: layout_idx,
-            shift: 0.0,
-            change: 0.0,
-            number: 0,
-        });
+        while let Some((node_id, parent_layout_idx, depth, number)) = stack.pop() {
+            // Cycle detection: skip already-visited nodes
+            if !visited.insert(node_id) {
+                continue;
+            }
 
-        if let Some(children) = children_map.get(&node_id) {
-            let mut child_layout_indices: Vec<usize> = Vec::with_capacity(children.len());
-
-            for (number, &child_id) in children.iter().enumerate() {
-                let before_len = layout_nodes.len();
-                Self::build_layout_tree(
-                    child_id,
-                    Some(layout_idx),
-                    depth + 1,
-                    children_map,
-                    layout_nodes,
-                    node_to_layout,
-                    visited,
-                );
-                // Only add to children list if the node was actually inserted
-                // (it won't be if it was a cycle back-edge)
-                if layout_nodes.len() > before_len {
-                    if let Some(&child_idx) = node_to_layout.get(&child_id) {
-                        layout_nodes[child_idx].number = number;
-                        child_layout_indices.push(child_idx);
-                    }
+            let layout_idx = layout_nodes.len();
+            node_to_layout.insert(node_id, layout_idx);
+
+            layout_nodes.push(LayoutNode {
+                slot: node_id as usize,
+                depth,
+                parent: parent_layout_idx,
+                children: Vec::new(),
+                prelim: 0.0,
+                modifier: 0.0,
+                thread_left: None,
+                thread_right: None,
+                ancestor: layout_idx,
+                shift: 0.0,
+                change: 0.0,
+                number,
+                half_width: 0.0,
+            });
+            // Children pop in order, each after the whole subtree of the one
+            // before it, so they are appended to their parent in order
+            if let Some(parent) = parent_layout_idx {
+                layout_nodes[parent].children.push(layout_idx);
+            }
+
+            if collapsed.get(node_id as usize).copied().unwrap_or(false) {
+                continue;
+            }
+            if let Some(children) = children_map.get(&node_id) {
+                for (number, &child_id) in children.iter().enumerate().rev() {
+                    stack.push((child_id, Some(layout_idx), depth + 1, number));
                 }
             }
+        }
+    }
+
+    /// Layout node indices in DFS preorder from the root (index 0), every
+    /// parent before its children.
+    fn preorder(nodes: &[LayoutNode]) -> Vec<usize> {
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut stack = vec![0];
+        while let Some(v) = stack.pop() {
+            order.push(v);
+            stack.extend(nodes[v].children.iter().rev());
+        }
+        order
+    }
+
+    /// Move nodes to their requested levels and bridge the skipped levels
+    /// with dummy nodes (slot `usize::MAX`, never written to the output).
+    fn apply_ranks(layout_nodes: &mut Vec<LayoutNode>, ranks: &[u32]) {
+        let requested = |slot: usize| ranks.get(slot).copied().filter(|&r| r != FREE_RANK);
+
+        // Layout nodes are in DFS preorder, so parents get their level first
+        for v in 0..layout_nodes.len() {
+            let slot = layout_nodes[v].slot;
+            layout_nodes[v].depth = match layout_nodes[v].parent {
+                Some(p) => requested(slot).unwrap_or(0).max(layout_nodes[p].depth + 1),
+                None => requested(slot).unwrap_or(0),
+            };
+        }
+
+        for v in 0..layout_nodes.len() {
+            if let Some(parent) = layout_nodes[v].parent {
+                Self::bridge_levels(layout_nodes, parent, v);
+            }
+        }
+    }
+
+    /// Replace the edge `parent → v` with a chain of dummy nodes, one per
+    /// level strictly between them.
+    fn bridge_levels(layout_nodes: &mut Vec<LayoutNode>, parent: usize, v: usize) {
+        let (top, bottom) = (layout_nodes[parent].depth, layout_nodes[v].depth);
+        if bottom <= top + 1 {
+            return;
+        }
+
+        let first = layout_nodes.len();
+        let mut above = parent;
+        for depth in top + 1..bottom {
+            let dummy = layout_nodes.len();
+            layout_nodes.push(LayoutNode {
+                slot: usize::MAX,
+                depth,
+                parent: Some(above),
+                children: Vec::new(),
+                prelim: 0.0,
+                modifier: 0.0,
+                thread_left: None,
+                thread_right: None,
+                ancestor: dummy,
+                shift: 0.0,
+                change: 0.0,
+                number: 0,
+                half_width: 0.0,
+            });
+            if above != parent {
+                layout_nodes[above].children.push(dummy);
+            }
+            above = dummy;
+        }
+
+        // The first dummy takes v's place among the parent's children
+        layout_nodes[first].number = layout_nodes[v].number;
+        if let Some(i) = layout_nodes[parent].children.iter().position(|&c| c == v) {
+            layout_nodes[parent].children[i] = first;
+        }
+        layout_nodes[above].children.push(v);
+        layout_nodes[v].parent = Some(above);
+        layout_nodes[v].number = 0;
+    }
+
+    /// Set node half widths from `extents` and return the output offset of
+    /// every level along the level axis.
+    ///
+    /// Preliminary x is in units of `level_separation` (the linear output
+    /// scale), so widths are converted to that unit.
+    fn apply_extents(&self, layout_nodes: &mut [LayoutNode], extents: &[f32]) -> Vec<f32> {
+        let unit = self.config.level_separation.max(f32::EPSILON);
+        let extent = |slot: usize, axis: usize| {
+            extents
+                .get(slot * 2 + axis)
+                .copied()
+                .filter(|v| v.is_finite())
+                .unwrap_or(0.0)
+                .max(0.0)
+        };
 
-            layout_nodes[layout_idx].children = child_layout_indices;
+        let levels = layout_nodes.iter().map(|n| n.depth as usize + 1).max().unwrap_or(0);
+        let mut tallest = vec![0.0f32; levels];
+        for node in layout_nodes.iter_mut().filter(|n| n.slot != usize::MAX) {
+            node.half_width = extent(node.slot, 0) * 0.5 / unit;
+            let depth = node.depth as usize;
+            tallest[depth] = tallest[depth].max(extent(node.slot, 1));
         }
+
+        // Centers of consecutive levels are `level_separation` apart plus
+        // half of each level's tallest node
+        let mut offsets = vec![0.0f32; levels];
+        for depth in 1..levels {
+            offsets[depth] = offsets[depth - 1]
+                + self.config.level_separation
+                + (tallest[depth - 1] + tallest[depth]) * 0.5;
+        }
+        offsets
     }
 
     /// Buchheim first walk: bottom-up assignment of preliminary x-coordinates.
-    fn first_walk(&self, v: usize, nodes: &mut Vec<LayoutNode>) {
-        // Clone children indices to avoid borrow conflict during recursion
+    ///
+    /// Visits nodes in reverse preorder, so every subtree is placed before
+    /// its root.
+    fn first_walk(&self, order: &[usize], nodes: &mut Vec<LayoutNode>) {
+        for &v in order.iter().rev() {
+            self.place_children(v, nodes);
+        }
+    }
+
+    /// Place the (already laid out) child subtrees of `v` side by side and
+    /// center `v` over them.
+    fn place_children(&self, v: usize, nodes: &mut Vec<LayoutNode>) {
+        // Clone children indices to avoid borrow conflict
         let children: Vec<usize> = nodes[v].children.clone();
 
         if children.is_empty() {
@@ -400,11 +752,6 @@ impl TidyTreeLayout {
             return;
         }
 
-        // Recursively walk children
-        for &child in &children {
-            self.first_walk(child, nodes);
-        }
-
         // Default ancestor for the apportion step
         let mut default_ancestor = children[0];
 
@@ -447,11 +794,13 @@ impl TidyTreeLayout {
             let left_x = nodes[left_contour].prelim + left_mod;
             let right_x = nodes[right_contour].prelim + right_mod;
 
-            let desired_sep = if self.are_siblings(left_contour, right_contour, nodes) {
+            let gap = if self.are_siblings(left_contour, right_contour, nodes) {
                 self.config.sibling_separation
             } else {
                 self.config.subtree_separation
             };
+            let desired_sep =
+                gap + nodes[left_contour].half_width + nodes[right_contour].half_width;
 
             let overlap = left_x + desired_sep - right_x;
             if overlap > max_shift {
@@ -553,7 +902,9 @@ impl TidyTreeLayout {
 
             let shift = (nodes[v_inner_right].prelim + s_inner_right)
                 - (nodes[v_inner_left].prelim + s_inner_left)
-                + self.config.subtree_separation;
+                + self.config.subtree_separation
+                + nodes[v_inner_right].half_width
+                + nodes[v_inner_left].half_width;
 
             if shift > 0.0 {
                 let ancestor_v = nodes[v].ancestor;
@@ -640,17 +991,16 @@ impl TidyTreeLayout {
     }
 
     /// Second walk: apply accumulated modifiers to get final x-coordinates.
-    fn second_walk_collect(
-        &self,
-        v: usize,
-        modifier_sum: f32,
-        nodes: &[LayoutNode],
-        final_x: &mut Vec<f32>,
-    ) {
-        final_x[v] = nodes[v].prelim + modifier_sum;
-
-        for &child in &nodes[v].children {
-            self.second_walk_collect(child, modifier_sum + nodes[v].modifier, nodes, final_x);
+    ///
+    /// Visits nodes in preorder, so each parent's modifier sum is known
+    /// before its children need it.
+    fn second_walk_collect(order: &[usize], nodes: &[LayoutNode], final_x: &mut [f32]) {
+        let mut modifier_sum = vec![0.0f32; nodes.len()];
+        for &v in order {
+            final_x[v] = nodes[v].prelim + modifier_sum[v];
+            for &child in &nodes[v].children {
+                modifier_sum[child] = modifier_sum[v] + nodes[v].modifier;
+            }
         }
     }
 }
@@ -678,6 +1028,7 @@ mod tests {
             level_separation: 100.0,
             sibling_separation: 1.0,
             subtree_separation: 2.0,
+            ..Default::default()
         });
 
         // Tree:  0 → 1, 0 → 2
@@ -775,6 +1126,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_linear_orientations() {
+        // Tree: 0 → 1, 0 → 2
+        let edges = [0, 1, 0, 2];
+        let compute = |orientation| {
+            let layout = TidyTreeLayout::new(TidyTreeConfig {
+                coordinate_mode: CoordinateMode::Linear,
+                level_separation: 50.0,
+                orientation,
+                ..Default::default()
+            });
+            let result = layout.compute(3, &edges, Some(0));
+            (result.positions_x, result.positions_y)
+        };
+
+        let (xs, ys) = compute(TreeOrientation::TopDown);
+        assert!(ys[1] > ys[0] && xs[1] < xs[2]);
+        let (bx, by) = compute(TreeOrientation::BottomUp);
+        assert_eq!((bx.clone(), by), (xs.clone(), ys.iter().map(|y| -y).collect()));
+        let (lx, ly) = compute(TreeOrientation::LeftRight);
+        assert_eq!((lx, ly), (ys.clone(), xs.clone()));
+        let (rx, ry) = compute(TreeOrientation::RightLeft);
+        assert_eq!((rx, ry), (ys.iter().map(|y| -y).collect(), xs));
+    }
+
+    #[test]
+    fn test_radial_direction_and_start_angle() {
+        // Tree: 0 → 1, 0 → 2, 0 → 3
+        let edges = [0, 1, 0, 2, 0, 3];
+        let compute = |start_angle, clockwise| {
+            let layout = TidyTreeLayout::new(TidyTreeConfig {
+                start_angle,
+                clockwise,
+                ..Default::default()
+            });
+            let result = layout.compute(4, &edges, Some(0));
+            (result.positions_x, result.positions_y)
+        };
+        let close = |a: f32, b: f32| (a - b).abs() < 0.01;
+
+        let (xs, ys) = compute(0.0, true);
+        // Counterclockwise mirrors the layout across the x axis
+        let (cx, cy) = compute(0.0, false);
+        for i in 1..4 {
+            assert!(close(cx[i], xs[i]) && close(cy[i], -ys[i]), "node {i}");
+        }
+        // A quarter-turn start angle rotates it by 90°: (x, y) → (-y, x)
+        let (qx, qy) = compute(std::f32::consts::FRAC_PI_2, true);
+        for i in 1..4 {
+            assert!(close(qx[i], -ys[i]) && close(qy[i], xs[i]), "node {i}");
+        }
+    }
+
+    #[test]
+    fn test_radial_fan_with_inner_radius() {
+        // Tree: 0 → 1, 0 → 2, 0 → 3, 1 → 4
+        let edges = [0, 1, 0, 2, 0, 3, 1, 4];
+        let layout = TidyTreeLayout::new(TidyTreeConfig {
+            level_separation: 100.0,
+            angular_span: std::f32::consts::PI,
+            inner_radius: 50.0,
+            ..Default::default()
+        });
+        let result = layout.compute(5, &edges, Some(0));
+        let (xs, ys) = (&result.positions_x, &result.positions_y);
+
+        assert_eq!((xs[0], ys[0]), (0.0, 0.0));
+        // The fan runs from the first subtree at 0° to the last at 180°,
+        // through the lower half (+y)
+        let distance = |i: usize| xs[i].hypot(ys[i]);
+        assert!((1..5).all(|i| ys[i] >= -0.01), "{ys:?}");
+        assert!(ys[1].abs() < 0.01 && xs[1] > 0.0);
+        assert!(ys[3].abs() < 0.01 && xs[3] < 0.0);
+        assert!((distance(2) - 250.0).abs() < 0.5);
+        assert!((distance(4) - 350.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_crowded_rings_expand() {
+        // Root with 4 children of 10 leaves each: 40 leaves on one ring
+        let mut edges = Vec::new();
+        for child in 1..=4u32 {
+            edges.extend([0, child]);
+            for leaf in 0..10 {
+                edges.extend([child, 5 + (child - 1) * 10 + leaf]);
+            }
+        }
+        let compute = |expand_crowded_rings| {
+            let layout = TidyTreeLayout::new(TidyTreeConfig {
+                level_separation: 50.0,
+                expand_crowded_rings,
+                ..Default::default()
+            });
+            layout.compute(45, &edges, Some(0))
+        };
+        let nearest_leaves = |result: &TidyTreeResult| {
+            let (xs, ys) = (&result.positions_x, &result.positions_y);
+            (5..44)
+                .map(|i| (xs[i + 1] - xs[i]).hypot(ys[i + 1] - ys[i]))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        // Uniform rings cram the leaves closer than the sibling separation
+        let uniform = compute(false);
+        assert!(nearest_leaves(&uniform) < 40.0);
+
+        let expanded = compute(true);
+        assert!(nearest_leaves(&expanded) >= 50.0 * 0.99);
+        // The inner ring is not crowded and keeps its radius
+        let radius = |result: &TidyTreeResult, i: usize| {
+            result.positions_x[i].hypot(result.positions_y[i])
+        };
+        assert!((radius(&expanded, 1) - radius(&uniform, 1)).abs() < 0.01);
+        assert!(radius(&expanded, 5) > radius(&uniform, 5));
+    }
+
     #[test]
     fn test_auto_root_detection() {
         let layout = TidyTreeLayout::new(TidyTreeConfig {
@@ -792,6 +1259,20 @@ mod tests {
         assert!(result.positions_y[0].abs() < 0.01, "Auto-detected root at depth 0");
     }
 
+    #[test]
+    fn test_root_ties_pick_lowest_id() {
+        let layout = TidyTreeLayout::new(TidyTreeConfig::default());
+
+        // Two equal trees, 3 → 4 and 1 → 2 (plus slot 0 unused)
+        let edges = [3, 4, 1, 2];
+        for _ in 0..5 {
+            let result = layout.compute(5, &edges, None);
+            assert_eq!(result.node_count, 2);
+            assert!(result.positions_y[1].abs() < 0.01);
+            assert!(result.positions_x[3] >= SENTINEL);
+        }
+    }
+
     #[test]
     fn test_cyclic_graph_does_not_hang() {
         let layout = TidyTreeLayout::new(TidyTreeConfig {
@@ -852,6 +1333,24 @@ mod tests {
         assert_eq!(result.node_count, 2, "Self-loop should be skipped");
     }
 
+    #[test]
+    fn test_deep_chain_does_not_overflow() {
+        // 100k-deep path 0 → 1 → ... → n-1, far deeper than the call stack
+        // would allow with recursive walks
+        let n = 100_000u32;
+        let edges: Vec<u32> = (0..n - 1).flat_map(|i| [i, i + 1]).collect();
+        let layout = TidyTreeLayout::new(TidyTreeConfig {
+            coordinate_mode: CoordinateMode::Linear,
+            level_separation: 10.0,
+            ..Default::default()
+        });
+        let result = layout.compute(n as usize, &edges, Some(0));
+
+        assert_eq!(result.node_count, n as usize);
+        assert!(result.positions_x.iter().all(|&x| x.abs() < 0.01));
+        assert_eq!(result.positions_y[n as usize - 1], (n - 1) as f32 * 10.0);
+    }
+
     #[test]
     fn test_asymmetric_tree() {
         let layout = TidyTreeLayout::new(TidyTreeConfig {
@@ -859,6 +1358,7 @@ mod tests {
             level_separation: 50.0,
             sibling_separation: 1.0,
             subtree_separation: 2.0,
+            ..Default::default()
         });
 
         // Asymmetric: left subtree deeper than right
@@ -887,4 +1387,104 @@ mod tests {
             "Subtrees should not overlap: left max x = {left_max_x}, right min x = {right_min_x}"
         );
     }
+
+    #[test]
+    fn test_extents_make_room_for_large_nodes() {
+        let layout = TidyTreeLayout::new(TidyTreeConfig {
+            coordinate_mode: CoordinateMode::Linear,
+            level_separation: 10.0,
+            sibling_separation: 1.0,
+            subtree_separation: 2.0,
+            ..Default::default()
+        });
+
+        // 0 → 1, 0 → 2, 1 → 3: node 1 is 40 wide and 30 tall, root 6 tall
+        let edges = [0, 1, 0, 2, 1, 3];
+        let extents = [0.0, 6.0, 40.0, 30.0, 0.0, 0.0, 0.0, 0.0];
+        let inputs = TreeNodeInputs {
+            extents: &extents,
+            ..Default::default()
+        };
+        let result = layout.compute_with_inputs(4, &edges, Some(0), &inputs);
+
+        // Sibling gap of 10 between the edge of node 1 and the center of 2
+        let xs = &result.positions_x;
+        assert!((xs[2] - xs[1] - 30.0).abs() < 0.01, "{xs:?}");
+        // Level 1 sits 10 + 3 + 15 below the root, level 2 10 + 15 below that
+        let ys = &result.positions_y;
+        assert_eq!((ys[0], ys[1], ys[3]), (0.0, 28.0, 53.0));
+    }
+
+    #[test]
+    fn test_depths_and_subtree_sizes() {
+        let layout = TidyTreeLayout::with_defaults();
+
+        // 0 → 1, 0 → 2, 1 → 3, 1 → 4; slot 5 is not in the tree
+        let edges = [0, 1, 0, 2, 1, 3, 1, 4];
+        let result = layout.compute(6, &edges, Some(0));
+        assert_eq!(result.depths, vec![0, 1, 1, 2, 2, u32::MAX]);
+        assert_eq!(result.subtree_sizes, vec![5, 3, 1, 1, 1, 0]);
+
+        // Pinning 2 to level 3 adds dummies, which neither count nor shift
+        // other depths
+        let ranks = [FREE_RANK, FREE_RANK, 3];
+        let inputs = TreeNodeInputs {
+            ranks: &ranks,
+            ..Default::default()
+        };
+        let result = layout.compute_with_inputs(6, &edges, Some(0), &inputs);
+        assert_eq!(result.depths, vec![0, 1, 3, 2, 2, u32::MAX]);
+        assert_eq!(result.subtree_sizes, vec![5, 3, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_collapsed_subtrees_are_hidden() {
+        let layout = TidyTreeLayout::new(TidyTreeConfig {
+            coordinate_mode: CoordinateMode::Linear,
+            ..Default::default()
+        });
+        // 0 → 1, 0 → 2, 1 → 3, 1 → 4, 3 → 5; collapse 1
+        let edges = [0, 1, 0, 2, 1, 3, 1, 4, 3, 5];
+        let collapsed = [false, true];
+        let inputs = TreeNodeInputs {
+            collapsed: &collapsed,
+            ..Default::default()
+        };
+        let result = layout.compute_with_inputs(6, &edges, Some(0), &inputs);
+
+        assert_eq!(result.node_count, 3);
+        assert!((3..6).all(|i| result.positions_x[i] == SENTINEL));
+        // 1 and 2 are now plain siblings, spaced as if 1 were a leaf
+        let expanded = layout.compute(3, &[0, 1, 0, 2], Some(0));
+        assert_eq!(&result.positions_x[..3], &expanded.positions_x[..]);
+        assert_eq!(result.subtree_sizes[..3], [3, 1, 1]);
+    }
+
+    #[test]
+    fn test_ranks_push_nodes_down() {
+        let layout = TidyTreeLayout::new(TidyTreeConfig {
+            coordinate_mode: CoordinateMode::Linear,
+            level_separation: 10.0,
+            sibling_separation: 1.0,
+            subtree_separation: 2.0,
+            ..Default::default()
+        });
+
+        // 0 → 1, 0 → 2, 1 → 3: pin 2 to level 3; 3 asks for level 0, which
+        // would put it above its parent
+        let edges = [0, 1, 0, 2, 1, 3];
+        let ranks = [FREE_RANK, FREE_RANK, 3, 0];
+        let inputs = TreeNodeInputs {
+            ranks: &ranks,
+            ..Default::default()
+        };
+        let result = layout.compute_with_inputs(4, &edges, Some(0), &inputs);
+
+        assert_eq!(result.node_count, 4);
+        let ys = &result.positions_y;
+        assert_eq!((ys[0], ys[1], ys[2], ys[3]), (0.0, 10.0, 30.0, 20.0));
+        // The dummy chain keeps 2 clear of 1's subtree on levels 1 and 2
+        let xs = &result.positions_x;
+        assert!(xs[2] - xs[1] >= 10.0 - 0.01 && xs[2] - xs[3] >= 10.0 - 0.01);
+    }
 }
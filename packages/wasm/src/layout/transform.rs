@@ -0,0 +1,124 @@
+//! Affine transforms of positions.
+//!
+//! Scale, rotate, mirror and move a whole layout (or part of it) without
+//! round-tripping every coordinate through JS. The linear part is applied
+//! about the centroid of the transformed nodes, so rotating or scaling a
+//! layout keeps it where it is; the translation is applied afterwards.
+
+use super::is_placed;
+
+/// A similarity transform: mirror, then scale and rotate about a pivot,
+/// then translate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// Uniform scale factor.
+    pub scale: f32,
+    /// Counter-clockwise rotation in radians (clockwise on a y-down screen).
+    pub rotation: f32,
+    /// Mirror horizontally (x → -x about the pivot) before rotating.
+    pub mirror: bool,
+    /// Offset added after scaling and rotating.
+    pub translate: (f32, f32),
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            rotation: 0.0,
+            mirror: false,
+            translate: (0.0, 0.0),
+        }
+    }
+}
+
+impl Transform {
+    /// Apply the linear part (mirror, scale, rotation) to a vector.
+    pub fn apply_linear(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = if self.mirror { -x } else { x };
+        (
+            self.scale * (x * cos - y * sin),
+            self.scale * (x * sin + y * cos),
+        )
+    }
+
+    /// Transform a point about `pivot`.
+    pub fn apply(&self, (x, y): (f32, f32), pivot: (f32, f32)) -> (f32, f32) {
+        let (dx, dy) = self.apply_linear((x - pivot.0, y - pivot.1));
+        (
+            pivot.0 + dx + self.translate.0,
+            pivot.1 + dy + self.translate.1,
+        )
+    }
+}
+
+/// Transform an interleaved position buffer [x0, y0, x1, y1, ...] in place
+/// about the centroid of its positioned entries. Sentinel entries stay as
+/// they are.
+///
+/// # Returns
+///
+/// The number of positions transformed.
+pub fn transform_positions(positions: &mut [f32], transform: &Transform) -> u32 {
+    let placed: Vec<usize> = (0..positions.len() / 2)
+        .filter(|&i| is_placed(positions[i * 2], positions[i * 2 + 1]))
+        .collect();
+    let pivot = centroid(
+        placed
+            .iter()
+            .map(|&i| (positions[i * 2], positions[i * 2 + 1])),
+    );
+    for &i in &placed {
+        let (x, y) = transform.apply((positions[i * 2], positions[i * 2 + 1]), pivot);
+        positions[i * 2] = x;
+        positions[i * 2 + 1] = y;
+    }
+    placed.len() as u32
+}
+
+/// Mean of a set of points, the origin if empty.
+pub fn centroid(points: impl Iterator<Item = (f32, f32)>) -> (f32, f32) {
+    let (sx, sy, n) = points.fold((0.0f32, 0.0f32, 0u32), |(sx, sy, n), (x, y)| {
+        (sx + x, sy + y, n + 1)
+    });
+    if n == 0 {
+        (0.0, 0.0)
+    } else {
+        (sx / n as f32, sy / n as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::SENTINEL;
+
+    #[test]
+    fn test_rotate_and_scale_about_centroid() {
+        let mut positions = vec![0.0, 0.0, 10.0, 0.0, SENTINEL, SENTINEL];
+        let transform = Transform {
+            scale: 2.0,
+            rotation: std::f32::consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        assert_eq!(transform_positions(&mut positions, &transform), 2);
+
+        // Centroid (5, 0) stays; the segment turns vertical and doubles
+        assert!((positions[0] - 5.0).abs() < 1e-4 && (positions[1] + 10.0).abs() < 1e-4);
+        assert!((positions[2] - 5.0).abs() < 1e-4 && (positions[3] - 10.0).abs() < 1e-4);
+        assert_eq!(positions[4], SENTINEL);
+    }
+
+    #[test]
+    fn test_mirror_then_translate() {
+        let mut positions = vec![0.0, 0.0, 10.0, 4.0];
+        let transform = Transform {
+            mirror: true,
+            translate: (100.0, -1.0),
+            ..Default::default()
+        };
+        transform_positions(&mut positions, &transform);
+        assert_eq!(positions, vec![110.0, -1.0, 100.0, 3.0]);
+    }
+}
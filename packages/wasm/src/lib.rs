@@ -8,19 +8,70 @@
 //!
 //! - `graph`: Graph data structure using petgraph's StableGraph
 //! - `spatial`: R-tree spatial indexing for O(log n) hit testing
+//! - `labels`: Non-overlapping label placement per zoom level
 //! - `layout`: Force calculation utilities (CPU-side, for validation)
 //! - `algorithms`: Graph algorithms (clustering, traversal, etc.)
+//! - `rng`: Seeded random number generation for reproducible output
+//! - `simulation`: CPU force simulation and kernels with optional wasm SIMD acceleration
 
 use js_sys::Float32Array;
+use serde::de::DeserializeOwned;
 use wasm_bindgen::prelude::*;
 
 pub mod graph;
+pub mod labels;
 pub mod layout;
+pub mod rng;
+pub mod simulation;
 pub mod spatial;
 
-use graph::{GraphEngine, NodeId};
-use layout::community::{self, CommunityLayoutConfig};
-use layout::tidy_tree::{CoordinateMode, TidyTreeConfig, TidyTreeLayout};
+// Exported to JS as `initThreadPool(threads)`, which starts the rayon workers
+#[cfg(all(feature = "threads", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+use graph::{
+    BetweennessConfig, CliqueConfig, ClosenessConfig, EigenvectorConfig, GraphEngine,
+    HarmonicConfig, KatzConfig, NodeId, PageRankConfig, RichClubConfig,
+};
+use labels::LabelPlacementConfig;
+use simulation::force::{ForceSimulation, Integrator, Viewport};
+use simulation::transition::{Easing, Transition};
+use layout::transform::{self, Transform};
+use layout::{SENTINEL, align, fit};
+use layout::arc::{self, ArcLayoutConfig};
+use layout::balloon::{self, BalloonLayoutConfig};
+use layout::bipartite::{self, BipartiteLayoutConfig};
+use layout::bubble::{self, BubbleConfig};
+use layout::bundling::{self, EdgeBundlingConfig, HierarchicalBundlingConfig};
+use layout::circular::{self, CircularLayoutConfig, CircularOrdering};
+use layout::community::{
+    self, CommunityLayoutConfig, CommunityLayoutOptions, ConsensusConfig, DetectionConfig,
+};
+use layout::concentric::{self, ConcentricLayoutConfig};
+use layout::constraints::{self, Constraint, ConstraintConfig, ConstraintNodes};
+use layout::crossings::{self, EdgeCrossingConfig};
+use layout::curves::{self, EdgeCurveConfig};
+use layout::distance::csr_pairs;
+use layout::grid::{self, GridLayoutConfig};
+use layout::hierarchy::Hierarchy;
+use layout::hull::{self, GroupHullConfig};
+use layout::icicle::{self, IcicleLayoutConfig};
+use layout::metrics::{self, LayoutMetricsConfig};
+use layout::multilevel::{self, MultilevelConfig};
+use layout::orthogonal::{self, OrthogonalLayoutConfig};
+use layout::overlap::{self, OverlapRemovalConfig};
+use layout::pipeline::{LayoutPipeline, LayoutStage};
+use layout::pivot_mds::{self, PivotMdsConfig};
+use layout::progressive::{
+    self, CancelToken, ProgressiveLayout, ProgressiveLayoutConfig, Stepped,
+};
+use layout::snap::{self, SnapConfig};
+use layout::spanning_tree::{self, SpanningTreeStrategy};
+use layout::spectral::{self, SpectralLayoutConfig};
+use layout::stress::{self, StressLayoutConfig};
+use layout::suggest;
+use layout::sunburst::{self, SunburstLayoutConfig};
+use layout::tidy_tree::{TidyTreeLayout, TreeLayoutOptions, TreeNodeInputs};
 
 /// Initialize the WASM module.
 #[wasm_bindgen(start)]
@@ -35,6 +86,18 @@ pub fn init() {
 #[wasm_bindgen]
 pub struct HeroineGraphWasm {
     engine: GraphEngine,
+    /// Seed for every randomized step (jitter, spiral offsets, sampling).
+    seed: u64,
+    /// CPU force simulation settings used by `tick`.
+    simulation: ForceSimulation,
+    /// Position transition driven by `animationTick`, if one is running.
+    transition: Option<Transition>,
+    /// Layout constraints projected after every `tick`.
+    constraints: Vec<Constraint>,
+    /// Node extents [w0, h0, w1, h1, ...] by slot for size-aware layouts.
+    node_extents: Vec<f32>,
+    /// Collapsed nodes by slot, whose subtrees the tree layouts leave out.
+    collapsed: Vec<bool>,
 }
 
 #[wasm_bindgen]
@@ -44,6 +107,12 @@ impl HeroineGraphWasm {
     pub fn new() -> Self {
         Self {
             engine: GraphEngine::new(),
+            seed: rng::DEFAULT_SEED,
+            simulation: ForceSimulation::default(),
+            transition: None,
+            constraints: Vec::new(),
+            node_extents: Vec::new(),
+            collapsed: Vec::new(),
         }
     }
 
@@ -57,9 +126,24 @@ impl HeroineGraphWasm {
     pub fn with_capacity(node_capacity: usize, edge_capacity: usize) -> Self {
         Self {
             engine: GraphEngine::with_capacity(node_capacity, edge_capacity),
+            seed: rng::DEFAULT_SEED,
+            simulation: ForceSimulation::default(),
+            transition: None,
+            constraints: Vec::new(),
+            node_extents: Vec::new(),
+            collapsed: Vec::new(),
         }
     }
 
+    /// Set the seed used by all randomized placement and detection steps.
+    ///
+    /// The same graph with the same seed always produces the same output,
+    /// which makes snapshot tests and screenshots reproducible.
+    #[wasm_bindgen(js_name = setSeed)]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed as u64;
+    }
+
     // =========================================================================
     // Node Operations
     // =========================================================================
@@ -121,6 +205,15 @@ impl HeroineGraphWasm {
     }
 
     /// Pin a node (exclude from simulation).
+    ///
+    /// Layouts leave pinned nodes where they are: their targets are their
+    /// current positions. The orthogonal and arc layouts are the exception,
+    /// as their edge routes are built around the nodes they place.
+    ///
+    /// Only the layout result is overwritten: the layout itself still runs
+    /// as if the node were free, so stress, spectral and the other layouts
+    /// place its neighbors around wherever they would have put it, not
+    /// around its pinned position.
     #[wasm_bindgen(js_name = pinNode)]
     pub fn pin_node(&mut self, node_id: u32) {
         self.engine.pin_node(NodeId(node_id));
@@ -138,6 +231,162 @@ impl HeroineGraphWasm {
         self.engine.is_node_pinned(NodeId(node_id))
     }
 
+    /// Offset every unpinned node by a seeded random amount in
+    /// `[-amount, amount]` on each axis.
+    ///
+    /// Useful for breaking symmetry (e.g. coincident nodes) before a force
+    /// simulation. Uses the seed set via `setSeed`.
+    #[wasm_bindgen(js_name = jitterPositions)]
+    pub fn jitter_positions(&mut self, amount: f32) {
+        self.engine.jitter_positions(amount, self.seed);
+    }
+
+    /// Position just-added nodes near their neighbors instead of where they
+    /// were created.
+    ///
+    /// Each node is placed next to the centroid of its already positioned
+    /// neighbors; nodes without one go on the periphery of the current
+    /// layout. Offsets use the simulation's spring length and the seed set via
+    /// `setSeed`. Other nodes are not moved. Returns the number of nodes
+    /// placed.
+    #[wasm_bindgen(js_name = placeNewNodes)]
+    pub fn place_new_nodes(&mut self, node_ids: &[u32]) -> u32 {
+        let ids: Vec<NodeId> = node_ids.iter().map(|&id| NodeId(id)).collect();
+        let spacing = self.simulation.config.spring_length;
+        self.engine.place_new_nodes(&ids, spacing, self.seed)
+    }
+
+    /// Scale, rotate, mirror and move nodes in place.
+    ///
+    /// The mirror (x → -x), scale and rotation are applied about the
+    /// centroid of the affected nodes, then the translation is added.
+    /// Velocities are turned along with the positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - Uniform scale factor (1 = unchanged)
+    /// * `rotation` - Rotation in radians, clockwise on a y-down screen
+    /// * `tx`, `ty` - Translation
+    /// * `mirror` - Mirror horizontally before rotating
+    /// * `node_ids` - Nodes to transform (omit for all nodes)
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes transformed.
+    #[wasm_bindgen(js_name = transformPositions)]
+    pub fn transform_positions(
+        &mut self,
+        scale: f32,
+        rotation: f32,
+        tx: f32,
+        ty: f32,
+        mirror: bool,
+        node_ids: Option<Vec<u32>>,
+    ) -> u32 {
+        let transform = Transform {
+            scale,
+            rotation,
+            mirror,
+            translate: (tx, ty),
+        };
+        let ids: Option<Vec<NodeId>> =
+            node_ids.map(|ids| ids.into_iter().map(NodeId).collect());
+        self.engine.transform_positions(&transform, ids.as_deref())
+    }
+
+    /// Snap nodes to a grid and edges to angle steps in place.
+    ///
+    /// Post-layout cleanup for diagram-style output. Edges are first turned
+    /// to the nearest multiple of `angle_snap_degrees` (exact on trees,
+    /// approximate where nodes have several edges), then nodes are rounded
+    /// to the nearest grid point; nodes that would share a point move to the
+    /// nearest free one. Pinned nodes stay where they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_size` - Grid spacing (0 = no grid snapping)
+    /// * `angle_snap_degrees` - Angle step in degrees, e.g. 45 or 90 (omit or
+    ///   0 = no angle snapping)
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes moved.
+    #[wasm_bindgen(js_name = snapPositions)]
+    pub fn snap_positions(&mut self, grid_size: f32, angle_snap_degrees: Option<f32>) -> u32 {
+        self.engine.snap_positions(&snap_config(grid_size, angle_snap_degrees))
+    }
+
+    /// Move nodes a step toward layout target positions.
+    ///
+    /// Call once per tick to animate the graph into a computed layout on the
+    /// CPU. Pinned nodes and nodes whose target is the sentinel value are not
+    /// moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    /// * `strength` - Fraction of the remaining distance covered per call (0–1)
+    ///
+    /// # Returns
+    ///
+    /// The number of nodes moved.
+    #[wasm_bindgen(js_name = applyTargetAttraction)]
+    pub fn apply_target_attraction(&mut self, targets: &[f32], strength: f32) -> u32 {
+        self.engine.apply_target_attraction(targets, strength)
+    }
+
+    /// Start animating nodes from their current positions to `targets`.
+    ///
+    /// Replaces any running transition. Drive it by calling `animationTick`
+    /// once per frame; the clock starts at the first tick. Pinned nodes and
+    /// nodes whose target is the sentinel value are not moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    /// * `duration_ms` - Transition length in milliseconds
+    /// * `easing` - 0 = linear, 1 = ease-in, 2 = ease-out, 3 = ease-in-out
+    #[wasm_bindgen(js_name = startTransition)]
+    pub fn start_transition(&mut self, targets: &[f32], duration_ms: f64, easing: u32) {
+        let easing = match easing {
+            0 => Easing::Linear,
+            1 => Easing::EaseIn,
+            2 => Easing::EaseOut,
+            _ => Easing::EaseInOut,
+        };
+        let transition = self.engine.start_transition(targets, duration_ms, easing);
+        self.transition = Some(transition);
+    }
+
+    /// Advance the running transition to time `now` and write the
+    /// interpolated positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current time in milliseconds, e.g. `performance.now()`
+    ///
+    /// # Returns
+    ///
+    /// True while the transition is running, false once it has finished or
+    /// if there is none.
+    #[wasm_bindgen(js_name = animationTick)]
+    pub fn animation_tick(&mut self, now: f64) -> bool {
+        let Some(transition) = self.transition.as_mut() else {
+            return false;
+        };
+        let running = self.engine.step_transition(transition, now);
+        if !running {
+            self.transition = None;
+        }
+        running
+    }
+
+    /// Stop the running transition, leaving nodes where they are.
+    #[wasm_bindgen(js_name = cancelTransition)]
+    pub fn cancel_transition(&mut self) {
+        self.transition = None;
+    }
+
     // =========================================================================
     // Edge Operations
     // =========================================================================
@@ -184,30 +433,667 @@ impl HeroineGraphWasm {
         self.engine.get_neighbors(NodeId(node_id))
     }
 
+    /// Breadth-first traversal from a node.
+    ///
+    /// Returns `{ order, parents, depths }`: reached node slots in visit
+    /// order (the start first), then per node slot the slot it was reached
+    /// from and its hop distance from the start. Both are 4294967295 for
+    /// unreached nodes, and the start has no parent. Neighbors are visited
+    /// in ascending slot order.
+    ///
+    /// * `node_id` - Start node; throws if it does not exist
+    /// * `undirected` - Follow edges in both directions instead of only
+    ///   outgoing ones as `getNeighbors` does (default: false)
+    #[wasm_bindgen(js_name = bfsFrom)]
+    pub fn bfs_from(&self, node_id: u32, undirected: Option<bool>) -> Result<JsValue, JsError> {
+        let traversal = self
+            .engine
+            .bfs_from(NodeId(node_id), undirected.unwrap_or(false))
+            .ok_or_else(|| JsError::new(&format!("Node {node_id} does not exist")))?;
+        Ok(serde_wasm_bindgen::to_value(&traversal)?)
+    }
+
+    /// Depth-first traversal from a node.
+    ///
+    /// Returns `{ order, parents, depths }` as `bfsFrom` does, with `order`
+    /// the preorder of a recursive DFS and `depths` the depth in the DFS
+    /// tree rather than the hop distance.
+    ///
+    /// * `node_id` - Start node; throws if it does not exist
+    /// * `undirected` - Follow edges in both directions (default: false)
+    #[wasm_bindgen(js_name = dfsFrom)]
+    pub fn dfs_from(&self, node_id: u32, undirected: Option<bool>) -> Result<JsValue, JsError> {
+        let traversal = self
+            .engine
+            .dfs_from(NodeId(node_id), undirected.unwrap_or(false))
+            .ok_or_else(|| JsError::new(&format!("Node {node_id} does not exist")))?;
+        Ok(serde_wasm_bindgen::to_value(&traversal)?)
+    }
+
+    /// Score every node's relevance to a set of seed nodes with personalized
+    /// PageRank.
+    ///
+    /// A random walk follows out-edges in proportion to their weight and
+    /// jumps back to a seed with the restart probability, or when it gets
+    /// stuck; a node's score is the share of time spent there. Scores sum
+    /// to 1 and are highest at and around the seeds, so thresholding them
+    /// picks out the neighborhood that matters to a selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `seeds` - Seed node IDs; empty seeds every node, giving classic
+    ///   PageRank. IDs of missing nodes are skipped
+    /// * `options` - Optional object:
+    ///   - `restartProbability` - Chance per step of jumping back to a seed
+    ///     (default: 0.15)
+    ///   - `maxIterations` - Power iteration cap (default: 100)
+    ///   - `tolerance` - Total score change to stop at (default: 1e-6)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot (0 for removed slots and
+    /// nodes the walk never reaches).
+    #[wasm_bindgen(js_name = personalizedPageRank)]
+    pub fn personalized_pagerank(
+        &self,
+        seeds: &[u32],
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let config: PageRankConfig = layout_options(options)?;
+        let seeds: Vec<NodeId> = seeds.iter().map(|&id| NodeId(id)).collect();
+        let scores = self.engine.personalized_pagerank(&seeds, &config);
+        Ok(Float32Array::from(&scores[..]))
+    }
+
+    /// Compute betweenness centrality: how many shortest paths between other
+    /// nodes run through each node (and optionally each edge).
+    ///
+    /// Bridges between clusters score highest, which makes this the measure
+    /// for emphasizing gatekeeper nodes and for edge-betweenness clustering.
+    /// Paths are counted in hops, with Brandes' algorithm in O(nodes ×
+    /// edges); on large graphs, sampling pivot sources estimates the scores
+    /// in a fraction of the time.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `samples` - Pivot sources to sample; 0 is exact (default: 0)
+    ///   - `normalized` - Divide by the number of node pairs, giving scores
+    ///     in [0, 1] (default: false)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///   - `includeEdges` - Also score edges (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot, followed with
+    /// `includeEdges` by one score per edge ID.
+    #[wasm_bindgen(js_name = computeBetweenness)]
+    pub fn compute_betweenness(&self, options: JsValue) -> Result<Float32Array, JsError> {
+        let config = BetweennessConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let scores = self.engine.betweenness(&config);
+        let mut result = scores.nodes;
+        result.extend(scores.edges);
+        Ok(Float32Array::from(&result[..]))
+    }
+
+    /// Compute betweenness like `computeBetweenness`, reporting progress
+    /// and stopping early if cancelled.
+    ///
+    /// Sources are searched in batches of about 1% each; the optional
+    /// `onProgress(fraction, phase)` is called after every batch, and the
+    /// computation stops with an error once `token` is cancelled (or
+    /// `onProgress` throws). Returns the same array as
+    /// `computeBetweenness`.
+    #[wasm_bindgen(js_name = computeBetweennessWithProgress)]
+    pub fn compute_betweenness_with_progress(
+        &self,
+        options: JsValue,
+        token: &CancellationToken,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<Float32Array, JsError> {
+        let config = BetweennessConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let report = progress_reporter(token, on_progress.as_ref());
+        let scores = self.engine.betweenness_reported(&config, &token.inner, report)?;
+        let mut result = scores.nodes;
+        result.extend(scores.edges);
+        Ok(Float32Array::from(&result[..]))
+    }
+
+    /// Compute closeness centrality: the inverse of each node's mean hop
+    /// distance to the nodes it reaches.
+    ///
+    /// Nodes a few hops from everything score highest, which suits sizing
+    /// nodes by how central they are. Only reached nodes count, so on graphs
+    /// with several components a node of a small component can outscore
+    /// the hubs of a large one; `computeHarmonicCentrality` avoids that.
+    /// Exact closeness runs a breadth-first search per node; sampling pivots
+    /// estimates it from that many searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `samples` - Pivots to sample; 0 is exact (default: 0)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot (0 for nodes reaching no
+    /// other node and for removed slots).
+    #[wasm_bindgen(js_name = computeCloseness)]
+    pub fn compute_closeness(&self, options: JsValue) -> Result<Float32Array, JsError> {
+        let config = ClosenessConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let scores = self.engine.closeness(&config);
+        Ok(Float32Array::from(&scores[..]))
+    }
+
+    /// Compute harmonic centrality: the sum of inverse hop distances from
+    /// each node to every other node.
+    ///
+    /// Unreachable nodes add nothing rather than breaking the score, so
+    /// unlike closeness it stays meaningful on graphs with several
+    /// components. Exact scores run a breadth-first search per node;
+    /// sampling pivots estimates them from that many searches.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `samples` - Pivots to sample; 0 is exact (default: 0)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///   - `normalized` - Divide by the number of other nodes, giving
+    ///     scores in [0, 1] (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot (0 for removed slots).
+    #[wasm_bindgen(js_name = computeHarmonicCentrality)]
+    pub fn compute_harmonic_centrality(&self, options: JsValue) -> Result<Float32Array, JsError> {
+        let config = HarmonicConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let scores = self.engine.harmonic_centrality(&config);
+        Ok(Float32Array::from(&scores[..]))
+    }
+
+    /// Compute eigenvector centrality: a node scores high when high-scoring
+    /// nodes link to it.
+    ///
+    /// Scores are the principal eigenvector of the adjacency matrix along
+    /// in-edges, found by power iteration and scaled to unit length. On
+    /// directed graphs, nodes without in-edges from the strongly connected
+    /// core score 0; Katz centrality handles those.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `maxIterations` - Power iteration cap (default: 100)
+    ///   - `tolerance` - Mean score change per node to stop at
+    ///     (default: 1e-6)
+    ///   - `weighted` - Scale in-edges by their weight (default: true)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot. Throws if the scores do
+    /// not converge within `maxIterations`.
+    #[wasm_bindgen(js_name = computeEigenvectorCentrality)]
+    pub fn compute_eigenvector_centrality(
+        &self,
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let config: EigenvectorConfig = layout_options(options)?;
+        let scores = self.engine.eigenvector_centrality(&config).ok_or_else(|| {
+            JsError::new(&format!(
+                "Eigenvector centrality did not converge in {} iterations",
+                config.max_iterations
+            ))
+        })?;
+        Ok(Float32Array::from(&scores[..]))
+    }
+
+    /// Compute Katz centrality: every walk into a node adds to its score,
+    /// damped by `alpha` per step, on top of a baseline `beta`.
+    ///
+    /// Unlike eigenvector centrality, nodes that no cycle feeds keep a
+    /// score, which makes it the better fit for directed acyclic parts of a
+    /// graph. `alpha` must stay below the inverse of the adjacency matrix's
+    /// largest eigenvalue, or the scores grow without bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `alpha` - Attenuation per step (default: 0.1)
+    ///   - `beta` - Baseline score per node (default: 1.0)
+    ///   - `maxIterations` - Iteration cap (default: 1000)
+    ///   - `tolerance` - Mean score change per node to stop at
+    ///     (default: 1e-6)
+    ///   - `weighted` - Scale in-edges by their weight (default: true)
+    ///   - `undirected` - Follow edges in both directions (default: false)
+    ///   - `normalized` - Scale scores to unit length (default: true)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one score per node slot. Throws if the scores do
+    /// not converge within `maxIterations`.
+    #[wasm_bindgen(js_name = computeKatzCentrality)]
+    pub fn compute_katz_centrality(&self, options: JsValue) -> Result<Float32Array, JsError> {
+        let config: KatzConfig = layout_options(options)?;
+        let scores = self.engine.katz_centrality(&config).ok_or_else(|| {
+            JsError::new(&format!(
+                "Katz centrality did not converge in {} iterations; try a smaller alpha",
+                config.max_iterations
+            ))
+        })?;
+        Ok(Float32Array::from(&scores[..]))
+    }
+
+    /// Find the maximal cliques: groups of nodes all linked to each other
+    /// that no further node links to in full.
+    ///
+    /// The graph is taken as undirected and simple. Cliques are enumerated
+    /// by Bron–Kerbosch with pivoting in degeneracy order, which is fast on
+    /// sparse graphs but can be exponential on dense ones, hence the cap.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional object:
+    ///   - `minSize` - Smallest clique to report (default: 3)
+    ///   - `maxCliques` - Stop after this many cliques; 0 for no cap
+    ///     (default: 10000)
+    ///
+    /// # Returns
+    ///
+    /// `[clique_count, offsets..., ids...]` with `clique_count + 1`
+    /// CSR-style offsets: clique `c` is `ids[offsets[c]..offsets[c + 1]]`,
+    /// ascending.
+    #[wasm_bindgen(js_name = findMaximalCliques)]
+    pub fn find_maximal_cliques(&self, options: JsValue) -> Result<Vec<u32>, JsError> {
+        let config: CliqueConfig = layout_options(options)?;
+        Ok(flatten_groups(self.engine.maximal_cliques(&config)))
+    }
+
+    /// Find a maximum cardinality matching: as many edges as possible with
+    /// no two sharing a node.
+    ///
+    /// The graph is taken as undirected and simple. Bipartite graphs use
+    /// Hopcroft–Karp; others use Edmonds' blossom algorithm, which is cubic
+    /// in the worst case.
+    ///
+    /// # Returns
+    ///
+    /// IDs of the matched edges, ascending. Of parallel edges between a
+    /// matched pair, the lowest ID is returned.
+    #[wasm_bindgen(js_name = maximumMatching)]
+    pub fn maximum_matching(&self) -> Vec<u32> {
+        self.engine.maximum_matching()
+    }
+
+    /// Compute summary statistics for an overview of the graph in one call.
+    ///
+    /// Apart from the edge count, everything treats the graph as undirected
+    /// and simple: direction, weights, parallel edges and self-loops are
+    /// ignored. The diameter comes from a double breadth-first sweep in each
+    /// component, a lower bound that is usually exact.
+    ///
+    /// # Returns
+    ///
+    /// Object with `nodeCount`, `edgeCount`, `density` (share of node pairs
+    /// linked), `componentCount`, `degreeHistogram` (entry `d` counts the
+    /// nodes with `d` neighbors), `diameter` (in hops) and
+    /// `averageClustering` (nodes with under two neighbors count as 0).
+    #[wasm_bindgen(js_name = computeGraphStats)]
+    pub fn compute_graph_stats(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(&self.engine.graph_stats())?)
+    }
+
+    /// Compute the rich-club coefficient curve: how densely the nodes above
+    /// each degree threshold link to each other.
+    ///
+    /// The graph is taken as undirected and simple (direction, weights,
+    /// parallel edges and self-loops ignored). Entry `k` is the share of
+    /// possible links present among nodes with degree above `k`. Normalized,
+    /// it is divided by the same share on a degree-preserving randomization
+    /// of the graph, so values above 1 mean hubs favor each other. The
+    /// randomization uses the seed set via `setSeed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_k` - Highest degree threshold
+    /// * `options` - Optional object:
+    ///   - `normalized` - Divide by the randomized coefficient (default: true)
+    ///   - `swapsPerEdge` - Double edge swaps attempted per edge when
+    ///     randomizing (default: 10)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array of `max_k + 1` coefficients, NaN where fewer than two
+    /// nodes have a degree above the threshold.
+    #[wasm_bindgen(js_name = richClubCoefficients)]
+    pub fn rich_club_coefficients(
+        &self,
+        max_k: u32,
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let config = RichClubConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let coefficients = self.engine.rich_club_coefficients(max_k, &config);
+        Ok(Float32Array::from(&coefficients[..]))
+    }
+
+    /// Find the elementary cycles of the directed graph: closed paths that
+    /// visit no node twice (Johnson's algorithm).
+    ///
+    /// Each cycle lists its nodes in edge order, starting from its lowest
+    /// node ID; cycles come ordered by that node. A self-loop is a cycle of
+    /// one node. Graphs can have exponentially many cycles, so cap the
+    /// search on anything but small graphs.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_cycles` - Stop after this many cycles (0 = no cap)
+    /// * `max_length` - Skip cycles of more nodes than this (0 = no cap)
+    ///
+    /// # Returns
+    ///
+    /// `[cycle_count, offsets..., ids...]` with `cycle_count + 1` CSR-style
+    /// offsets: cycle `c` is `ids[offsets[c]..offsets[c + 1]]`.
+    #[wasm_bindgen(js_name = findCycles)]
+    pub fn find_cycles(&self, max_cycles: u32, max_length: u32) -> Vec<u32> {
+        flatten_groups(self.engine.elementary_cycles(max_cycles, max_length))
+    }
+
+    /// Find the edges a transitive reduction removes.
+    ///
+    /// An edge `u → v` is redundant when `v` is also reachable from `u`
+    /// through other edges; of parallel edges all but one are. Hiding the
+    /// returned edges keeps reachability the same with the fewest edges,
+    /// which makes dependency graphs far easier to read. Needs O(nodes²)
+    /// bits of memory.
+    ///
+    /// Returns a Uint32Array of redundant edge IDs, ascending. Throws if the
+    /// graph has a cycle.
+    #[wasm_bindgen(js_name = transitiveReduction)]
+    pub fn transitive_reduction(&self) -> Result<Vec<u32>, JsError> {
+        self.engine
+            .transitive_reduction()
+            .ok_or_else(|| JsError::new("Transitive reduction needs a graph without cycles"))
+    }
+
+    /// Compute the transitive closure: every pair of nodes connected by a
+    /// directed path.
+    ///
+    /// Returns a Uint32Array of node slot pairs [source0, target0, source1,
+    /// target1, ...], sorted by source then target, leaving out nodes
+    /// reaching themselves. Needs O(nodes²) bits of memory. Throws if the
+    /// graph has a cycle.
+    #[wasm_bindgen(js_name = transitiveClosure)]
+    pub fn transitive_closure(&self) -> Result<Vec<u32>, JsError> {
+        self.engine
+            .transitive_closure()
+            .ok_or_else(|| JsError::new("Transitive closure needs a graph without cycles"))
+    }
+
+    /// Precompute a reachability index so `isReachable` answers in near
+    /// constant time.
+    ///
+    /// The index condenses the graph's strongly connected components and
+    /// labels the resulting DAG with intervals (O(nodes + edges) to build).
+    /// It is dropped on the next node or edge change; call again after
+    /// edits to keep queries fast.
+    #[wasm_bindgen(js_name = buildReachabilityIndex)]
+    pub fn build_reachability_index(&mut self) {
+        self.engine.build_reachability_index(self.seed);
+    }
+
+    /// Whether the reachability index is built and current.
+    #[wasm_bindgen(js_name = hasReachabilityIndex)]
+    pub fn has_reachability_index(&self) -> bool {
+        self.engine.has_reachability_index()
+    }
+
+    /// Whether `target` can be reached from `source` along edge directions.
+    ///
+    /// Every node reaches itself. Uses the index from
+    /// `buildReachabilityIndex` while it is current, and a breadth-first
+    /// search otherwise. Throws if a node does not exist.
+    #[wasm_bindgen(js_name = isReachable)]
+    pub fn is_reachable(&self, source: u32, target: u32) -> Result<bool, JsError> {
+        self.engine
+            .is_reachable(NodeId(source), NodeId(target))
+            .ok_or_else(|| JsError::new(&format!("Node {source} or {target} does not exist")))
+    }
+
+    /// Find the lightest path between two nodes using edge weights
+    /// (Dijkstra).
+    ///
+    /// Returns `{ nodes, edges, distance }`: the node slots along the path
+    /// (source first), the IDs of the edges it takes and the sum of their
+    /// weights, or undefined if no path connects the nodes.
+    ///
+    /// Throws if a node does not exist or an edge weight is negative.
+    ///
+    /// * `source` - Start node
+    /// * `target` - End node
+    /// * `undirected` - Follow edges in both directions (default: false)
+    #[wasm_bindgen(js_name = shortestPath)]
+    pub fn shortest_path(
+        &self,
+        source: u32,
+        target: u32,
+        undirected: Option<bool>,
+    ) -> Result<JsValue, JsError> {
+        self.check_path_query(source, target)?;
+        let path = self.engine.shortest_path(
+            NodeId(source),
+            NodeId(target),
+            undirected.unwrap_or(false),
+        );
+        Ok(serde_wasm_bindgen::to_value(&path)?)
+    }
+
+    /// Find a shortest path between two nodes with A*, guided by the
+    /// straight-line distance from each node's current position to the
+    /// target.
+    ///
+    /// Returns the same `{ nodes, edges, distance }` as `shortestPath`, or
+    /// undefined if no path connects the nodes. Throws if a node does not
+    /// exist or an edge weight is negative.
+    ///
+    /// * `source` - Start node
+    /// * `target` - End node
+    /// * `undirected` - Follow edges in both directions (default: false)
+    /// * `heuristic_scale` - Edge weight per unit of distance assumed for the
+    ///   estimate. Defaults to the smallest weight-to-length ratio over all
+    ///   edges, which keeps the path exact; larger values search fewer nodes
+    ///   but may return a longer path
+    #[wasm_bindgen(js_name = shortestPathAStar)]
+    pub fn shortest_path_a_star(
+        &self,
+        source: u32,
+        target: u32,
+        undirected: Option<bool>,
+        heuristic_scale: Option<f32>,
+    ) -> Result<JsValue, JsError> {
+        self.check_path_query(source, target)?;
+        let path = self.engine.shortest_path_a_star(
+            NodeId(source),
+            NodeId(target),
+            undirected.unwrap_or(false),
+            heuristic_scale,
+        );
+        Ok(serde_wasm_bindgen::to_value(&path)?)
+    }
+
+    /// Find up to `k` alternative routes between two nodes, lightest first
+    /// (Yen's algorithm).
+    ///
+    /// Returns an array of `{ nodes, edges, distance }` in the
+    /// `shortestPath` format. No path visits a node twice, and paths of
+    /// equal weight come fewest edges first. Throws if a node does not exist
+    /// or an edge weight is negative.
+    ///
+    /// * `source` - Start node
+    /// * `target` - End node
+    /// * `k` - Maximum number of paths
+    /// * `undirected` - Follow edges in both directions (default: false)
+    #[wasm_bindgen(js_name = kShortestPaths)]
+    pub fn k_shortest_paths(
+        &self,
+        source: u32,
+        target: u32,
+        k: u32,
+        undirected: Option<bool>,
+    ) -> Result<JsValue, JsError> {
+        self.check_path_query(source, target)?;
+        let paths = self.engine.k_shortest_paths(
+            NodeId(source),
+            NodeId(target),
+            k as usize,
+            undirected.unwrap_or(false),
+        );
+        Ok(serde_wasm_bindgen::to_value(&paths)?)
+    }
+
+    /// Compute highlight bitmasks for the nodes and edges on the lightest
+    /// path between two nodes (as `shortestPath` finds it).
+    ///
+    /// Returns a Uint32Array `[nodeWordCount, nodeWords..., edgeWords...]`:
+    /// bit `i % 32` of word `i / 32` is set for node slot `i` (covering
+    /// `node_bound` slots) and for edge ID `i` (covering every edge ID handed
+    /// out) on the path. Both masks are zero if no path connects the nodes.
+    /// Throws if a node does not exist or an edge weight is negative.
+    ///
+    /// * `source` - Start node
+    /// * `target` - End node
+    /// * `undirected` - Follow edges in both directions (default: false)
+    #[wasm_bindgen(js_name = computePathHighlight)]
+    pub fn compute_path_highlight(
+        &self,
+        source: u32,
+        target: u32,
+        undirected: Option<bool>,
+    ) -> Result<Vec<u32>, JsError> {
+        self.check_path_query(source, target)?;
+        let path = self.engine.shortest_path(
+            NodeId(source),
+            NodeId(target),
+            undirected.unwrap_or(false),
+        );
+        Ok(graph::path_masks(
+            path.as_ref(),
+            self.engine.node_bound() as usize,
+            self.engine.edge_id_bound() as usize,
+        ))
+    }
+
+    /// Find the lightest path between two nodes when edge weights may be
+    /// negative (Bellman–Ford).
+    ///
+    /// Returns `{ path, negativeCycle }` with at most one set: the path in
+    /// the `shortestPath` format, or, when a cycle of negative total weight
+    /// is reachable from `source` and no shortest path exists, that cycle
+    /// in the same format with its first node repeated at the end. Neither
+    /// is set if no path connects the nodes. Slower than `shortestPath`
+    /// (every edge is relaxed once per node); throws if a node does not
+    /// exist.
+    ///
+    /// * `source` - Start node
+    /// * `target` - End node
+    /// * `undirected` - Follow edges in both directions (default: false);
+    ///   every negative edge is then a negative cycle
+    #[wasm_bindgen(js_name = shortestPathBellmanFord)]
+    pub fn shortest_path_bellman_ford(
+        &self,
+        source: u32,
+        target: u32,
+        undirected: Option<bool>,
+    ) -> Result<JsValue, JsError> {
+        let result = self
+            .engine
+            .shortest_path_bellman_ford(NodeId(source), NodeId(target), undirected.unwrap_or(false))
+            .ok_or_else(|| JsError::new(&format!("Node {source} or {target} does not exist")))?;
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
+    /// Find the maximum flow from `source` to `target` with edge weights as
+    /// capacities (Dinic's algorithm), and a minimum cut: the bottleneck
+    /// edges whose capacities sum to the flow.
+    ///
+    /// Returns `{ value, flows, cutEdges }`: the total flow, the flow per
+    /// edge ID (0 for unused and removed IDs; negative where it runs against
+    /// the edge's direction, which only undirected flow does) and the IDs
+    /// of the cut edges, ascending. Throws if a node does not exist, the
+    /// nodes are the same, or an edge weight is negative or not finite.
+    ///
+    /// * `source` - Node the flow leaves
+    /// * `target` - Node the flow reaches
+    /// * `undirected` - Let edges carry flow either way (default: false)
+    #[wasm_bindgen(js_name = maxFlow)]
+    pub fn max_flow(
+        &self,
+        source: u32,
+        target: u32,
+        undirected: Option<bool>,
+    ) -> Result<JsValue, JsError> {
+        if source == target {
+            return Err(JsError::new("Flow source and target must differ"));
+        }
+        for id in [source, target] {
+            if !self.engine.contains_node(NodeId(id)) {
+                return Err(JsError::new(&format!("Node {id} does not exist")));
+            }
+        }
+        let result = self
+            .engine
+            .max_flow(NodeId(source), NodeId(target), undirected.unwrap_or(false))
+            .ok_or_else(|| JsError::new("Edge capacities must be non-negative and finite"))?;
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
     // =========================================================================
     // Position Buffer Access (Zero-Copy)
     // =========================================================================
 
     /// Get a zero-copy view of X positions.
     ///
+    /// In double-buffer mode this is the front buffer published by the last
+    /// `swapBuffers` call. It grows with every added node, but nodes added
+    /// since the last swap read as unplaced (3.402823e38) until the next one.
+    ///
     /// # Safety
     ///
     /// The returned view is invalidated if any Rust allocation occurs.
     /// Use immediately for GPU upload, do not store.
     #[wasm_bindgen(js_name = getPositionsXView)]
     pub fn get_positions_x_view(&self) -> Float32Array {
-        unsafe { Float32Array::view(self.engine.positions_x()) }
+        unsafe { Float32Array::view(self.engine.published_positions_x()) }
     }
 
     /// Get a zero-copy view of Y positions.
     ///
+    /// In double-buffer mode this is the front buffer published by the last
+    /// `swapBuffers` call. It grows with every added node, but nodes added
+    /// since the last swap read as unplaced (3.402823e38) until the next one.
+    ///
     /// # Safety
     ///
     /// The returned view is invalidated if any Rust allocation occurs.
     /// Use immediately for GPU upload, do not store.
     #[wasm_bindgen(js_name = getPositionsYView)]
     pub fn get_positions_y_view(&self) -> Float32Array {
-        unsafe { Float32Array::view(self.engine.positions_y()) }
+        unsafe { Float32Array::view(self.engine.published_positions_y()) }
     }
 
     /// Get a zero-copy view of X velocities.
@@ -227,60 +1113,404 @@ impl HeroineGraphWasm {
     /// Used for creating views after WASM memory growth.
     #[wasm_bindgen(js_name = positionsXPtr)]
     pub fn positions_x_ptr(&self) -> *const f32 {
-        self.engine.positions_x().as_ptr()
+        self.engine.published_positions_x().as_ptr()
     }
 
     /// Get the length of the positions buffer.
+    ///
+    /// Matches the working buffer in double-buffer mode too, since the front
+    /// buffer grows as nodes are added.
     #[wasm_bindgen(js_name = positionsLen)]
     pub fn positions_len(&self) -> usize {
-        self.engine.positions_x().len()
+        self.engine.published_positions_x().len()
+    }
+
+    /// Enable or disable double-buffered position output.
+    ///
+    /// When enabled, ticks and position edits write to a back buffer and the
+    /// position views only change when `swapBuffers` publishes it, so the
+    /// renderer never reads a half-updated frame. Views must be re-acquired
+    /// after toggling.
+    #[wasm_bindgen(js_name = setDoubleBuffered)]
+    pub fn set_double_buffered(&mut self, enabled: bool) {
+        self.engine.set_double_buffered(enabled);
+    }
+
+    /// Publish the back position buffer to the views.
+    ///
+    /// Call once per frame after the last tick. No-op when double buffering
+    /// is disabled.
+    #[wasm_bindgen(js_name = swapBuffers)]
+    pub fn swap_buffers(&mut self) {
+        self.engine.swap_buffers();
     }
 
     // =========================================================================
-    // Spatial Queries
+    // CPU Simulation
     // =========================================================================
 
-    /// Find the nearest node to a point.
+    /// Advance the CPU force simulation by one tick.
     ///
-    /// Returns the node ID, or None if the graph is empty.
-    #[wasm_bindgen(js_name = findNearestNode)]
-    pub fn find_nearest_node(&self, x: f32, y: f32) -> Option<u32> {
-        self.engine.find_nearest_node(x, y).map(|id| id.0)
+    /// Returns the number of nodes that moved.
+    pub fn tick(&mut self) -> u32 {
+        let moved = self.engine.tick(&mut self.simulation);
+        if !self.constraints.is_empty() {
+            self.engine.apply_constraints(&self.constraints, &ConstraintConfig::default());
+        }
+        moved
     }
 
-    /// Find the nearest node within a maximum distance.
+    /// Set the force parameters of the CPU simulation.
     ///
-    /// Returns the node ID, or None if no node is within the distance.
-    #[wasm_bindgen(js_name = findNearestNodeWithin)]
-    pub fn find_nearest_node_within(&self, x: f32, y: f32, max_distance: f32) -> Option<u32> {
-        self.engine
-            .find_nearest_node_within(x, y, max_distance)
-            .map(|id| id.0)
+    /// # Arguments
+    ///
+    /// * `repulsion` - Repulsion constant (magnitude is repulsion / distance)
+    /// * `spring_length` - Natural edge length
+    /// * `spring_stiffness` - Edge spring constant
+    /// * `gravity` - Pull toward the origin, proportional to distance
+    #[wasm_bindgen(js_name = setSimulationForces)]
+    pub fn set_simulation_forces(
+        &mut self,
+        repulsion: f32,
+        spring_length: f32,
+        spring_stiffness: f32,
+        gravity: f32,
+    ) {
+        let config = &mut self.simulation.config;
+        config.repulsion = repulsion;
+        config.spring_length = spring_length;
+        config.spring_stiffness = spring_stiffness;
+        config.gravity = gravity;
     }
 
-    /// Find all nodes within a rectangular region.
+    /// Set the integration parameters of the CPU simulation.
     ///
-    /// Returns a Uint32Array of node IDs.
-    #[wasm_bindgen(js_name = findNodesInRect)]
-    pub fn find_nodes_in_rect(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Vec<u32> {
-        self.engine.find_nodes_in_rect(min_x, min_y, max_x, max_y)
+    /// # Arguments
+    ///
+    /// * `damping` - Fraction of velocity removed per tick (0–1)
+    /// * `time_step` - Integration time step
+    /// * `theta` - Barnes–Hut opening angle (0 for exact repulsion)
+    #[wasm_bindgen(js_name = setSimulationIntegration)]
+    pub fn set_simulation_integration(&mut self, damping: f32, time_step: f32, theta: f32) {
+        let config = &mut self.simulation.config;
+        config.damping = damping;
+        config.time_step = time_step;
+        config.theta = theta;
     }
 
-    /// Rebuild the spatial index after position changes.
+    /// Set per-node repulsion strengths for the CPU simulation.
     ///
-    /// Call this after bulk position updates for accurate spatial queries.
-    #[wasm_bindgen(js_name = rebuildSpatialIndex)]
-    pub fn rebuild_spatial_index(&mut self) {
-        self.engine.rebuild_spatial_index();
+    /// Takes one strength per node slot (node_bound entries); e.g. scale by
+    /// degree so hubs clear more space. Node `j` pushes others with magnitude
+    /// `strength[j] / distance`. Missing or NaN entries fall back to the
+    /// global repulsion; an empty array clears the overrides.
+    #[wasm_bindgen(js_name = setNodeRepulsion)]
+    pub fn set_node_repulsion(&mut self, strengths: &[f32]) {
+        self.simulation.set_node_repulsion(strengths.to_vec());
     }
 
-    // =========================================================================
-    // Graph Utilities
-    // =========================================================================
+    /// Set per-node damping (0–1) for the CPU simulation.
+    ///
+    /// Takes one value per node slot (node_bound entries). Missing or NaN
+    /// entries fall back to the global damping; an empty array clears the
+    /// overrides.
+    #[wasm_bindgen(js_name = setNodeDamping)]
+    pub fn set_node_damping(&mut self, damping: &[f32]) {
+        self.simulation.set_node_damping(damping.to_vec());
+    }
 
-    /// Get the bounding box of all nodes.
+    /// Choose the integrator used by the CPU simulation.
     ///
-    /// Returns [min_x, min_y, max_x, max_y], or None if graph is empty.
+    /// # Arguments
+    ///
+    /// * `verlet` - Use velocity Verlet instead of semi-implicit Euler
+    /// * `max_displacement` - Maximum distance a node may move per tick (0 = unlimited)
+    #[wasm_bindgen(js_name = setIntegrator)]
+    pub fn set_integrator(&mut self, verlet: bool, max_displacement: f32) {
+        let config = &mut self.simulation.config;
+        config.integrator = if verlet {
+            Integrator::Verlet
+        } else {
+            Integrator::Euler
+        };
+        config.max_displacement = max_displacement.max(0.0);
+    }
+
+    /// Configure the directional "magnetic" force of the CPU simulation.
+    ///
+    /// Biases every edge to point from source to target along the given
+    /// direction (e.g. `(0, 1)` for top-down flow), producing readable flow
+    /// diagrams without a layered layout. Use a strength of 0 to disable.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction_x`, `direction_y` - Preferred edge direction (normalized internally)
+    /// * `strength` - Strength for edges without a per-type override
+    #[wasm_bindgen(js_name = setMagneticForce)]
+    pub fn set_magnetic_force(&mut self, direction_x: f32, direction_y: f32, strength: f32) {
+        let config = &mut self.simulation.config;
+        config.magnetic_direction = (direction_x, direction_y);
+        config.magnetic_strength = strength;
+    }
+
+    /// Assign a type index to every edge, indexed by edge ID.
+    ///
+    /// Type indices select a strength from `setMagneticStrengthsByType`.
+    /// Use `0xFFFFFFFF` for edges without a type.
+    #[wasm_bindgen(js_name = setEdgeTypes)]
+    pub fn set_edge_types(&mut self, types: &[u32]) {
+        self.simulation.set_edge_types(types.to_vec());
+    }
+
+    /// Set the magnetic strength per edge type index.
+    ///
+    /// Missing or NaN entries fall back to the strength passed to
+    /// `setMagneticForce`.
+    #[wasm_bindgen(js_name = setMagneticStrengthsByType)]
+    pub fn set_magnetic_strengths_by_type(&mut self, strengths: &[f32]) {
+        self.simulation.set_type_magnetism(strengths.to_vec());
+    }
+
+    /// Assign every node to a group for group-aware forces.
+    ///
+    /// Takes one group id per node slot (node_bound entries), typically the
+    /// assignments from `detectCommunities` without the trailing count.
+    /// `0xFFFFFFFF` marks ungrouped nodes; an empty array disables group
+    /// forces. Strengths are set with `setGroupForces`.
+    #[wasm_bindgen(js_name = setNodeGroups)]
+    pub fn set_node_groups(&mut self, groups: &[u32]) {
+        self.simulation.set_node_groups(groups.to_vec());
+    }
+
+    /// Configure the group-aware forces of the CPU simulation.
+    ///
+    /// Members of a group are pulled toward the group's centroid and groups
+    /// push each other apart, giving clustered but still continuous layouts
+    /// on top of the regular forces. Use 0 for both to disable.
+    ///
+    /// # Arguments
+    ///
+    /// * `attraction` - Pull toward the group centroid, proportional to distance
+    /// * `separation` - Repulsion constant between group centroids
+    #[wasm_bindgen(js_name = setGroupForces)]
+    pub fn set_group_forces(&mut self, attraction: f32, separation: f32) {
+        let config = &mut self.simulation.config;
+        config.group_attraction = attraction;
+        config.group_separation = separation;
+    }
+
+    /// Only simulate nodes inside a rect (grown by `margin`).
+    ///
+    /// Nodes outside are frozen: they keep their position and velocity and
+    /// act as fixed endpoints for edges crossing into the rect. Useful when
+    /// zoomed into one region of a very large graph.
+    #[wasm_bindgen(js_name = setSimulationViewport)]
+    pub fn set_simulation_viewport(&mut self, min_x: f32, min_y: f32, max_x: f32, max_y: f32, margin: f32) {
+        let viewport = Viewport::with_margin(min_x, min_y, max_x, max_y, margin);
+        self.simulation.set_viewport(Some(viewport));
+    }
+
+    /// Enable adaptive freezing of converged nodes.
+    ///
+    /// A node whose per-tick displacement stays below `threshold` for
+    /// `ticks` consecutive ticks (at least 1) stops being simulated until a
+    /// neighbor moves. Pass a threshold of 0 to disable.
+    #[wasm_bindgen(js_name = setAdaptiveFreezing)]
+    pub fn set_adaptive_freezing(&mut self, threshold: f32, ticks: u32) {
+        self.simulation.config.freeze_threshold = threshold.max(0.0);
+        self.simulation.config.freeze_ticks = ticks;
+    }
+
+    /// Unfreeze every node, e.g. after the user drags a node or the graph
+    /// topology changes.
+    #[wasm_bindgen(js_name = wakeSimulation)]
+    pub fn wake_simulation(&mut self) {
+        self.simulation.wake_all();
+    }
+
+    /// Number of nodes currently frozen by adaptive freezing.
+    #[wasm_bindgen(js_name = frozenNodeCount)]
+    pub fn frozen_node_count(&self) -> u32 {
+        self.simulation.frozen_count()
+    }
+
+    /// Simulate every node again after `setSimulationViewport`.
+    #[wasm_bindgen(js_name = clearSimulationViewport)]
+    pub fn clear_simulation_viewport(&mut self) {
+        self.simulation.set_viewport(None);
+    }
+
+    // =========================================================================
+    // Layout Constraints
+    // =========================================================================
+
+    /// Set the layout constraints projected after every `tick`.
+    ///
+    /// `constraints` is an array of descriptors on node IDs, each with a
+    /// `type` and an `axis` (`"x"` or `"y"`):
+    ///
+    /// - `{ type: "align", axis, nodes }`: nodes share one coordinate
+    /// - `{ type: "order", axis, nodes, gap? }`: nodes in increasing order,
+    ///   consecutive ones at least `gap` apart (default: 0)
+    /// - `{ type: "separation", axis, left, right, gap, exact? }`: `right`
+    ///   at least (exactly, if `exact`) `gap` beyond `left`
+    ///
+    /// Pinned nodes never move; constraints on them move the other nodes.
+    /// Replaces the previous constraints and projects the current positions
+    /// once. Throws if a descriptor is malformed.
+    ///
+    /// # Returns
+    ///
+    /// The largest remaining violation (0 when every constraint holds).
+    #[wasm_bindgen(js_name = setConstraints)]
+    pub fn set_constraints(&mut self, constraints: JsValue) -> Result<f32, JsError> {
+        self.constraints = serde_wasm_bindgen::from_value(constraints)?;
+        Ok(self.engine.apply_constraints(&self.constraints, &ConstraintConfig::default()))
+    }
+
+    /// Remove all layout constraints.
+    #[wasm_bindgen(js_name = clearConstraints)]
+    pub fn clear_constraints(&mut self) {
+        self.constraints.clear();
+    }
+
+    /// Project a layout result onto the current constraints.
+    ///
+    /// Use after computing any layout so the targets already satisfy the
+    /// constraints. Pinned nodes keep their target; sentinel entries are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    ///
+    /// # Returns
+    ///
+    /// A Float32Array with the constrained targets.
+    #[wasm_bindgen(js_name = constrainTargets)]
+    pub fn constrain_targets(&self, targets: &[f32]) -> Float32Array {
+        let (mut xs, mut ys): (Vec<f32>, Vec<f32>) =
+            targets.chunks_exact(2).map(|p| (p[0], p[1])).unzip();
+        let occupied = self.engine.occupied_slots();
+        let present: Vec<bool> = (0..xs.len())
+            .map(|i| occupied.get(i).copied().unwrap_or(false) && layout::is_placed(xs[i], ys[i]))
+            .collect();
+        let fixed = self.engine.pinned_slots();
+        let nodes = ConstraintNodes {
+            present: &present,
+            fixed: &fixed,
+        };
+        let config = ConstraintConfig::default();
+        constraints::project_constraints(&mut xs, &mut ys, &nodes, &self.constraints, &config);
+
+        let projected: Vec<f32> = xs.iter().zip(&ys).flat_map(|(&x, &y)| [x, y]).collect();
+        Float32Array::from(&projected[..])
+    }
+
+    // =========================================================================
+    // Spatial Queries
+    // =========================================================================
+
+    /// Find the nearest node to a point.
+    ///
+    /// Returns the node ID, or None if the graph is empty.
+    #[wasm_bindgen(js_name = findNearestNode)]
+    pub fn find_nearest_node(&self, x: f32, y: f32) -> Option<u32> {
+        self.engine.find_nearest_node(x, y).map(|id| id.0)
+    }
+
+    /// Find the nearest node within a maximum distance.
+    ///
+    /// Returns the node ID, or None if no node is within the distance.
+    #[wasm_bindgen(js_name = findNearestNodeWithin)]
+    pub fn find_nearest_node_within(&self, x: f32, y: f32, max_distance: f32) -> Option<u32> {
+        self.engine
+            .find_nearest_node_within(x, y, max_distance)
+            .map(|id| id.0)
+    }
+
+    /// Find all nodes within a rectangular region.
+    ///
+    /// Returns a Uint32Array of node IDs.
+    #[wasm_bindgen(js_name = findNodesInRect)]
+    pub fn find_nodes_in_rect(&self, min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Vec<u32> {
+        self.engine.find_nodes_in_rect(min_x, min_y, max_x, max_y)
+    }
+
+    /// Compute the distance from a point to every node slot.
+    ///
+    /// Returns a Float32Array with one distance per slot (node_bound entries).
+    /// Uses the SIMD kernel when built with the `simd` feature.
+    #[wasm_bindgen(js_name = computeDistancesFrom)]
+    pub fn compute_distances_from(&self, x: f32, y: f32) -> Float32Array {
+        let mut distances = vec![0.0f32; self.engine.positions_x().len()];
+        simulation::kernels::distances_from(
+            x,
+            y,
+            self.engine.positions_x(),
+            self.engine.positions_y(),
+            &mut distances,
+        );
+        Float32Array::from(&distances[..])
+    }
+
+    /// Rebuild the spatial index after position changes.
+    ///
+    /// Call this after bulk position updates for accurate spatial queries.
+    #[wasm_bindgen(js_name = rebuildSpatialIndex)]
+    pub fn rebuild_spatial_index(&mut self) {
+        self.engine.rebuild_spatial_index();
+    }
+
+    // =========================================================================
+    // Label Placement
+    // =========================================================================
+
+    /// Place node labels without overlaps at each zoom level.
+    ///
+    /// Labels are placed by descending priority at one of four anchors
+    /// around their node (right, left, above, below); labels that fit nowhere
+    /// are hidden at that level. Uses the current node positions.
+    ///
+    /// # Arguments
+    ///
+    /// * `sizes` - Label size per node slot [w0, h0, w1, h1, ...] in screen
+    ///   pixels, as measured in JS (0 = no label)
+    /// * `priorities` - Priority per node slot, higher wins (empty = all equal)
+    /// * `zoom_levels` - Screen pixels per world unit for each level, ascending
+    /// * `node_gap` - Screen distance from the node center to its label
+    /// * `padding` - Minimum screen gap between labels
+    ///
+    /// # Returns
+    ///
+    /// Float32Array with one block of `node_bound` label offsets per zoom
+    /// level: the top-left corner of each label relative to its node in
+    /// screen pixels [dx0, dy0, dx1, dy1, ...]. Hidden labels and removed
+    /// nodes get sentinel values (f32::MAX), so the block doubles as the
+    /// visibility mask.
+    #[wasm_bindgen(js_name = computeLabelPlacement)]
+    pub fn compute_label_placement(
+        &self,
+        sizes: &[f32],
+        priorities: &[f32],
+        zoom_levels: &[f32],
+        node_gap: f32,
+        padding: f32,
+    ) -> Float32Array {
+        let config = LabelPlacementConfig { node_gap, padding };
+        let positions = self.current_positions();
+        let offsets =
+            labels::compute_label_placement(&positions, sizes, priorities, zoom_levels, &config);
+        Float32Array::from(&offsets[..])
+    }
+
+    // =========================================================================
+    // Graph Utilities
+    // =========================================================================
+
+    /// Get the bounding box of all nodes.
+    ///
+    /// Returns [min_x, min_y, max_x, max_y], or None if graph is empty.
     #[wasm_bindgen(js_name = getBounds)]
     pub fn get_bounds(&self) -> Option<Vec<f32>> {
         self.engine.get_bounds().map(|(min_x, min_y, max_x, max_y)| {
@@ -320,120 +1550,157 @@ impl HeroineGraphWasm {
         self.engine.get_node_degrees()
     }
 
+    /// Get node strengths (summed edge weights) as
+    /// [out_0, in_0, out_1, in_1, ...].
+    ///
+    /// Returns a flat array with 2 * node_count elements, laid out like
+    /// `getNodeDegrees`; add each pair for the total strength. Useful for
+    /// degree-based encodings that should respect edge weights.
+    #[wasm_bindgen(js_name = getNodeStrengths)]
+    pub fn get_node_strengths(&self) -> Float32Array {
+        Float32Array::from(&self.engine.get_node_strengths()[..])
+    }
+
     // =========================================================================
     // Layout Algorithms
     // =========================================================================
 
+    /// Set node sizes for the size-aware layouts (tidy tree, community).
+    ///
+    /// Takes [w0, h0, w1, h1, ...] per node slot: width along the sibling
+    /// axis and height along the level axis, in layout units. Separations
+    /// are then measured between node edges instead of centers; the
+    /// community layout packs nodes by their bounding circles. Missing,
+    /// NaN or negative entries count as 0 (a point); an empty array clears
+    /// the sizes.
+    #[wasm_bindgen(js_name = setNodeExtents)]
+    pub fn set_node_extents(&mut self, extents: &[f32]) {
+        self.node_extents = extents.to_vec();
+    }
+
+    /// Set which nodes are collapsed in the tree layouts.
+    ///
+    /// Takes one byte per node slot (nonzero = collapsed). A collapsed node
+    /// is still laid out, but its subtree gets sentinel positions (f32::MAX)
+    /// and the rest of the tree closes the gap. Missing entries count as
+    /// expanded; an empty array expands everything.
+    #[wasm_bindgen(js_name = setCollapsedNodes)]
+    pub fn set_collapsed_nodes(&mut self, collapsed: &[u8]) {
+        self.collapsed = collapsed.iter().map(|&c| c != 0).collect();
+    }
+
     /// Compute a tidy tree layout using Buchheim's O(n) algorithm.
     ///
     /// Takes the tree edges as [parent0, child0, parent1, child1, ...] pairs.
     /// Returns a Float32Array of target positions [x0, y0, x1, y1, ...] with
-    /// one (x, y) pair per node slot.
+    /// one (x, y) pair per node slot. Node sizes set with `setNodeExtents`
+    /// are kept clear of each other, and subtrees of nodes collapsed with
+    /// `setCollapsedNodes` are left out.
     ///
     /// # Arguments
     ///
     /// * `edges` - Flat array of directed parent→child edge pairs
-    /// * `root_id` - The root node ID (u32::MAX means auto-detect)
-    /// * `level_separation` - Spacing between tree levels (default: 80)
-    /// * `sibling_separation` - Minimum separation between siblings (default: 1)
-    /// * `subtree_separation` - Minimum separation between subtrees (default: 2)
-    /// * `radial` - If true, use radial coordinates; if false, linear top-down
+    /// * `options` - Object with optional fields (undefined = all defaults):
+    ///   - `rootId` - The root node ID (default: auto-detect)
+    ///   - `levelSeparation` - Spacing between tree levels (default: 80)
+    ///   - `siblingSeparation` - Minimum separation between siblings (default: 1)
+    ///   - `subtreeSeparation` - Minimum separation between subtrees (default: 2)
+    ///   - `coordinateMode` - `"radial"` (default) or `"linear"`
+    ///   - `orientation` - Direction of the levels in linear mode:
+    ///     `"topDown"` (default), `"bottomUp"`, `"leftRight"` or `"rightLeft"`
+    ///   - `startAngle` - Angle of the first subtree in radial mode, in
+    ///     radians from the +x axis (default: 0)
+    ///   - `angularSpan` - Angle covered in radial mode, in radians; less
+    ///     than 2π gives a fan (default: 2π)
+    ///   - `clockwise` - Direction around the circle on screen (default: true)
+    ///   - `innerRadius` - Empty hole left around the root (default: 0)
+    ///   - `expandCrowdedRings` - Push crowded rings outward until neighbors
+    ///     on a ring are spaced as in linear mode (default: false)
+    ///   - `ranks` - Level per node slot that pins nodes to a level
+    ///     (u32::MAX = free), e.g. all test files on level 3. A node never
+    ///     goes above its parent's level + 1; edges skipping levels are kept
+    ///     clear of the subtrees they pass.
+    ///
+    /// Throws if the options are malformed.
     #[wasm_bindgen(js_name = computeTreeLayout)]
     pub fn compute_tree_layout(
         &self,
         edges: &[u32],
-        root_id: u32,
-        level_separation: f32,
-        sibling_separation: f32,
-        subtree_separation: f32,
-        radial: bool,
-    ) -> Float32Array {
-        let config = TidyTreeConfig {
-            level_separation,
-            sibling_separation,
-            subtree_separation,
-            coordinate_mode: if radial {
-                CoordinateMode::Radial
-            } else {
-                CoordinateMode::Linear
-            },
-        };
-
-        let layout = TidyTreeLayout::new(config);
-        let node_count = self.engine.node_bound() as usize;
-        let root = if root_id == u32::MAX {
-            None
-        } else {
-            Some(root_id)
-        };
-
-        let result = layout.compute(node_count, edges, root);
-
-        // Interleave x and y into [x0, y0, x1, y1, ...]
-        let mut positions = Vec::with_capacity(node_count * 2);
-        for i in 0..node_count {
-            positions.push(result.positions_x[i]);
-            positions.push(result.positions_y[i]);
-        }
-
-        Float32Array::from(&positions[..])
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let options = layout_options(options)?;
+        Ok(self.tidy_tree_layout(edges, options).positions())
     }
 
     /// Compute a tidy tree layout using the graph's own edges.
     ///
     /// This uses the edges already stored in the graph engine rather than
-    /// requiring external edge data. Returns a Float32Array of target
+    /// requiring external edge data. Takes the same options as
+    /// `computeTreeLayout` and returns the same Float32Array of target
     /// positions [x0, y0, x1, y1, ...].
+    #[wasm_bindgen(js_name = computeTreeLayoutFromGraph)]
+    pub fn compute_tree_layout_from_graph(&self, options: JsValue) -> Result<Float32Array, JsError> {
+        Ok(self.compute_tree_layout_detailed(options)?.positions())
+    }
+
+    /// Compute a tidy tree layout using the graph's own edges, with the
+    /// depth and subtree size of every node for level-based coloring and
+    /// level of detail.
+    ///
+    /// Takes the same options as `computeTreeLayout`.
+    #[wasm_bindgen(js_name = computeTreeLayoutDetailed)]
+    pub fn compute_tree_layout_detailed(
+        &self,
+        options: JsValue,
+    ) -> Result<TreeLayoutResult, JsError> {
+        let options = layout_options(options)?;
+
+        // With no edges every slot gets the sentinel
+        Ok(self.tidy_tree_layout(&self.edge_pairs(), options))
+    }
+
+    /// Extract a spanning tree of the graph as parent→child edge pairs.
+    ///
+    /// Lets graphs that are almost trees go through `computeTreeLayout`:
+    /// lay out the returned tree edges and draw the rest as extra edges.
+    /// Edges count as undirected; every connected component gets its own
+    /// tree, rooted at `root_id` or else at a node without incoming edges.
+    /// Returns a Uint32Array [parent0, child0, parent1, child1, ...].
     ///
     /// # Arguments
     ///
+    /// * `strategy` - 0 = breadth-first (shallowest tree), 1 = depth-first
+    ///   (long chains stay on one branch), 2 = minimum total edge weight
     /// * `root_id` - The root node ID (u32::MAX means auto-detect)
-    /// * `level_separation` - Spacing between tree levels
-    /// * `sibling_separation` - Minimum separation between siblings
-    /// * `subtree_separation` - Minimum separation between subtrees
-    /// * `radial` - If true, use radial coordinates; if false, linear top-down
-    #[wasm_bindgen(js_name = computeTreeLayoutFromGraph)]
-    pub fn compute_tree_layout_from_graph(
-        &self,
-        root_id: u32,
-        level_separation: f32,
-        sibling_separation: f32,
-        subtree_separation: f32,
-        radial: bool,
-    ) -> Float32Array {
-        // Extract edges from the graph engine's CSR format
-        let csr = self.engine.get_edges_csr();
+    #[wasm_bindgen(js_name = extractSpanningTree)]
+    pub fn extract_spanning_tree(&self, strategy: u32, root_id: u32) -> Vec<u32> {
+        let strategy = match strategy {
+            1 => SpanningTreeStrategy::Dfs,
+            2 => SpanningTreeStrategy::MinWeight,
+            _ => SpanningTreeStrategy::Bfs,
+        };
+        let root = (root_id != u32::MAX).then_some(root_id);
         let node_bound = self.engine.node_bound() as usize;
+        spanning_tree::spanning_tree(node_bound, &self.engine.weighted_edges(), root, strategy)
+    }
 
-        if csr.len() <= node_bound + 1 {
-            // No edges — return sentinel-filled positions
-            let sentinel = 3.402_823e+38_f32;
-            let positions = vec![sentinel; node_bound * 2];
-            return Float32Array::from(&positions[..]);
-        }
-
-        let offsets = &csr[..node_bound + 1];
-        let targets = &csr[node_bound + 1..];
-
-        // Convert CSR to flat edge pairs [src0, tgt0, src1, tgt1, ...]
-        let mut edges = Vec::with_capacity(targets.len() * 2);
-        for src in 0..node_bound {
-            let start = offsets[src] as usize;
-            let end = offsets[src + 1] as usize;
-            for &tgt in &targets[start..end.min(targets.len())] {
-                edges.push(src as u32);
-                edges.push(tgt);
-            }
-        }
-
-        self.compute_tree_layout(
-            &edges,
-            root_id,
-            level_separation,
-            sibling_separation,
-            subtree_separation,
-            radial,
-        )
+    /// Find the minimum spanning forest by edge weight (Kruskal), edges
+    /// counting as undirected.
+    ///
+    /// The forest is the same one `extractSpanningTree` gives with the
+    /// minimum weight strategy, rooted the same way, so the parents can go
+    /// straight into the tree layouts to "tree-ify" a weighted graph.
+    ///
+    /// Returns `{ edges, parents, totalWeight }`: the forest's edge IDs
+    /// (ascending; the lightest of parallel edges), the parent node per
+    /// node slot (u32::MAX for roots, isolated nodes and empty slots), and
+    /// the sum of the forest's weights.
+    ///
+    /// * `root_id` - Node to root its component at (default: auto-detect)
+    #[wasm_bindgen(js_name = minimumSpanningTree)]
+    pub fn minimum_spanning_tree(&self, root_id: Option<u32>) -> Result<JsValue, JsError> {
+        let forest = self.engine.minimum_spanning_forest(root_id.map(NodeId));
+        Ok(serde_wasm_bindgen::to_value(&forest)?)
     }
 
     // =========================================================================
@@ -478,97 +1745,1542 @@ impl HeroineGraphWasm {
         output
     }
 
-    /// Compute community layout positions from community assignments.
+    /// Detect communities with Louvain and return every level, not just
+    /// the best, for drilling down from coarse to fine communities.
     ///
-    /// Takes community assignments (from `detectCommunities`) and computes
-    /// target positions with communities arranged in a circle.
+    /// Returns an object with:
+    /// - `levels`: finest first, each `{ assignments, communityCount,
+    ///   modularity, parents }`, where `parents[c]` is the community of the
+    ///   next level that community `c` merged into (empty on the last level)
+    /// - `bestLevel`: index of the level `detectCommunities` returns, or
+    ///   `undefined` when no level beat every node on its own
     ///
-    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Arguments as in `detectCommunities`.
+    #[wasm_bindgen(js_name = detectCommunityHierarchy)]
+    pub fn detect_community_hierarchy(
+        &self,
+        resolution: f32,
+        max_iterations: u32,
+        min_modularity_gain: f64,
+    ) -> Result<JsValue, JsError> {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let hierarchy = community::detect_community_hierarchy(
+            &csr,
+            node_count,
+            resolution,
+            max_iterations,
+            min_modularity_gain,
+        );
+        Ok(serde_wasm_bindgen::to_value(&hierarchy)?)
+    }
+
+    /// Exact divisive clustering (Girvan–Newman) with the full dendrogram.
     ///
-    /// # Arguments
+    /// Removes the edge of highest betweenness until none are left,
+    /// recording every split. For small graphs, demos and checking Louvain
+    /// on fixtures. Returns an object like `detectCommunityHierarchy`:
+    /// `levels` from every node on its own (first) to the connected
+    /// components (last), and `bestLevel`, the level of highest modularity.
     ///
-    /// * `assignments` - Community assignment per node (from `detectCommunities`, without trailing count)
-    /// * `community_count` - Number of distinct communities
-    /// * `community_spacing` - Space between community clusters (default: 50.0)
-    /// * `node_spacing` - Space between nodes within a community (default: 10.0)
-    /// * `spread_factor` - Global scale multiplier (default: 1.5)
-    #[wasm_bindgen(js_name = computeCommunityLayout)]
-    pub fn compute_community_layout(
+    /// Throws if the graph has more than 2000 edges.
+    #[wasm_bindgen(js_name = detectCommunitiesGirvanNewman)]
+    pub fn detect_communities_girvan_newman(&self) -> Result<JsValue, JsError> {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let hierarchy = community::detect_communities_girvan_newman(&csr, node_count)
+            .ok_or_else(|| {
+                JsError::new(&format!(
+                    "Girvan-Newman supports at most {} edges",
+                    community::GIRVAN_NEWMAN_MAX_EDGES
+                ))
+            })?;
+        Ok(serde_wasm_bindgen::to_value(&hierarchy)?)
+    }
+
+    /// Split the graph into a fixed number of communities by spectral
+    /// clustering.
+    ///
+    /// For views that need exactly `clusters` groups, which modularity
+    /// optimization cannot target: k-means on the leading eigenvectors of
+    /// the normalized Laplacian. Nodes without edges join the smallest
+    /// community. Same return layout as `detectCommunities`; the count is
+    /// below `clusters` only if fewer nodes have edges.
+    #[wasm_bindgen(js_name = detectCommunitiesSpectral)]
+    pub fn detect_communities_spectral(&self, clusters: u32) -> Vec<u32> {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let result =
+            community::detect_communities_spectral(&csr, node_count, clusters, self.seed);
+        let mut output = result.assignments;
+        output.push(result.community_count);
+        output
+    }
+
+    /// Detect communities using the Leiden algorithm.
+    ///
+    /// Like `detectCommunities`, but every community is guaranteed to be
+    /// connected: Leiden refines each level's communities into
+    /// well-connected subcommunities before aggregating. Same arguments and
+    /// return layout as `detectCommunities`.
+    #[wasm_bindgen(js_name = detectCommunitiesLeiden)]
+    pub fn detect_communities_leiden(
         &self,
-        assignments: &[u32],
-        community_count: u32,
-        community_spacing: f32,
-        node_spacing: f32,
-        spread_factor: f32,
-    ) -> Float32Array {
+        resolution: f32,
+        max_iterations: u32,
+        min_modularity_gain: f64,
+    ) -> Vec<u32> {
+        let csr = self.engine.get_edges_csr();
         let node_count = self.engine.node_bound() as usize;
 
-        let config = CommunityLayoutConfig {
-            community_spacing,
-            node_spacing,
-            spread_factor,
-            ..CommunityLayoutConfig::default()
-        };
+        let result = community::detect_communities_leiden(
+            &csr,
+            node_count,
+            resolution,
+            max_iterations,
+            min_modularity_gain,
+        );
+
+        let mut output = result.assignments;
+        output.push(result.community_count);
+        output
+    }
+
+    /// Detect communities several times and return the consensus, so
+    /// output does not change from run to run.
+    ///
+    /// Each run visits nodes in a different order drawn from the seed set
+    /// via `setSeed`; edge endpoints that more than `threshold` of the runs
+    /// put together stay together. Returns an object with:
+    /// - `assignments`: consensus community per node slot
+    /// - `communityCount`: number of consensus communities
+    /// - `stability`: per node slot, the fraction (0–1) of runs agreeing
+    ///   with the consensus about its neighbors
+    ///
+    /// # Arguments
+    ///
+    /// * `runs` - Number of detection runs (at least 1; 10 is typical)
+    /// * `options` - Optional object; omitted fields use defaults:
+    ///   - `algorithm` - `"louvain"` (default), `"leiden"` or `"spectral"`
+    ///   - `resolution` - Resolution parameter (default: 1.0)
+    ///   - `maxIterations` - Maximum iterations per level (default: 100)
+    ///   - `minModularityGain` - Convergence threshold (default: 0.0001)
+    ///   - `clusters` - Number of communities for `"spectral"` (default: 2)
+    ///   - `threshold` - Fraction of runs needed to keep an edge's endpoints
+    ///     together (default: 0.5)
+    #[wasm_bindgen(js_name = detectCommunitiesConsensus)]
+    pub fn detect_communities_consensus(
+        &self,
+        runs: u32,
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let mut config: ConsensusConfig = layout_options(options)?;
+        config.detection.seed = self.seed;
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let result = community::detect_communities_consensus(
+            &csr,
+            node_count,
+            runs,
+            self.seed,
+            &config,
+        );
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
+    /// Detect communities among the given nodes only, e.g. the current
+    /// filter or selection, as if the rest of the graph did not exist.
+    ///
+    /// Returns the same layout as `detectCommunities` (one entry per node
+    /// slot plus the trailing community count), with `0xFFFFFFFF` for
+    /// slots outside the subset.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_ids` - Nodes to cluster; edges leaving them are ignored
+    /// * `options` - Optional object; omitted fields use defaults:
+    ///   - `algorithm` - `"louvain"` (default), `"leiden"` or `"spectral"`
+    ///   - `resolution` - Resolution parameter (default: 1.0)
+    ///   - `maxIterations` - Maximum iterations per level (default: 100)
+    ///   - `minModularityGain` - Convergence threshold (default: 0.0001)
+    ///   - `clusters` - Number of communities for `"spectral"` (default: 2)
+    #[wasm_bindgen(js_name = detectCommunitiesInSubset)]
+    pub fn detect_communities_in_subset(
+        &self,
+        node_ids: &[u32],
+        options: JsValue,
+    ) -> Result<Vec<u32>, JsError> {
+        let config = DetectionConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let csr = self.engine.get_edges_csr();
+        let present = self.engine.occupied_slots();
+        let nodes: Vec<u32> = node_ids
+            .iter()
+            .copied()
+            .filter(|&id| present.get(id as usize).copied().unwrap_or(false))
+            .collect();
+
+        let result =
+            community::detect_communities_in_subset(&csr, present.len(), &nodes, &config);
+        let mut output = result.assignments;
+        output.push(result.community_count);
+        Ok(output)
+    }
+
+    /// Re-detect communities, keeping the IDs of an earlier detection where
+    /// communities still match, so colors do not reshuffle after small
+    /// graph changes.
+    ///
+    /// New communities are matched to previous ones by the largest number
+    /// of shared nodes; unmatched ones get fresh IDs above every previous
+    /// one. Returns an object with:
+    /// - `assignments`: community per node slot (IDs need not be contiguous)
+    /// - `communityCount`: number of distinct communities
+    /// - `changed`: slots whose community ID changed, including new nodes
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_assignments` - Community per node slot from the earlier
+    ///   detection, without the trailing count; `0xFFFFFFFF` for none
+    /// * `options` - Optional detection options as in
+    ///   `detectCommunitiesInSubset`
+    #[wasm_bindgen(js_name = detectCommunitiesStable)]
+    pub fn detect_communities_stable(
+        &self,
+        previous_assignments: &[u32],
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let config = DetectionConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let result = config.detect(&csr, node_count);
+        let stable = community::match_communities(previous_assignments, &result.assignments);
+        Ok(serde_wasm_bindgen::to_value(&stable)?)
+    }
+
+    /// Update communities and positions after a small graph change, without
+    /// re-running detection and layout.
+    ///
+    /// Only the nodes around the change are reassigned (local moving from
+    /// the previous partition, which never splits communities; re-detect
+    /// now and then after many updates). Nodes that changed community move
+    /// next to their new neighbors; every other node keeps its current
+    /// position. Returns an object with:
+    /// - `assignments`, `communityCount`, `changed`: as in
+    ///   `detectCommunitiesStable`
+    /// - `positions`: interleaved target positions [x0, y0, x1, y1, ...]
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_assignments` - Community per node slot before the
+    ///   change, without the trailing count; `0xFFFFFFFF` for none
+    /// * `changed_nodes` - Added nodes and the ends of added or removed
+    ///   edges
+    /// * `options` - Object with optional fields (undefined = all defaults):
+    ///   - `resolution` - Resolution parameter (default: 1.0)
+    ///   - `nodeSpacing` - Largest offset of a moved node from its target
+    ///     (default: 10.0)
+    ///
+    /// Throws if the options are malformed.
+    #[wasm_bindgen(js_name = updateCommunities)]
+    pub fn update_communities(
+        &self,
+        previous_assignments: &[u32],
+        changed_nodes: &[u32],
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let config = CommunityLayoutConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let communities = community::update_communities(
+            &csr,
+            node_count,
+            previous_assignments,
+            changed_nodes,
+            config.resolution,
+        );
+        let mut positions = self.current_positions();
+        community::place_updated_nodes(
+            &csr,
+            &communities.assignments,
+            &communities.changed,
+            &mut positions,
+            &config,
+        );
+        self.hold_pinned(&mut positions);
+        let update = community::CommunityUpdate {
+            communities,
+            positions,
+        };
+        Ok(serde_wasm_bindgen::to_value(&update)?)
+    }
+
+    /// Detect communities over a range of resolutions, for offering a
+    /// sensible default or a slider with meaningful stops.
+    ///
+    /// Returns an array of `{ resolution, communityCount, modularity }`,
+    /// one per step, where `modularity` is the standard (resolution 1)
+    /// modularity so samples compare directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_resolution`, `max_resolution` - Range swept, both included
+    /// * `steps` - Number of evenly spaced resolutions
+    /// * `options` - Optional detection options as in
+    ///   `detectCommunitiesInSubset`; `resolution` is ignored
+    #[wasm_bindgen(js_name = sweepResolution)]
+    pub fn sweep_resolution(
+        &self,
+        min_resolution: f32,
+        max_resolution: f32,
+        steps: u32,
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let config = DetectionConfig {
+            seed: self.seed,
+            ..layout_options(options)?
+        };
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let samples = community::sweep_resolution(
+            &csr,
+            node_count,
+            min_resolution,
+            max_resolution,
+            steps,
+            &config,
+        );
+        Ok(serde_wasm_bindgen::to_value(&samples)?)
+    }
+
+    /// Build a new graph with one node per community, for zoomed-out
+    /// rendering or for laying out communities with any layout.
+    ///
+    /// Node `c` of the new graph stands for community `c` and sits at the
+    /// centroid of its members' current positions. Edges between two
+    /// communities merge into one edge (regardless of direction) whose
+    /// weight is the sum of theirs; edges inside a community are dropped.
+    /// Community IDs without members still get an (isolated) node so IDs
+    /// line up. The new graph uses this graph's seed.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignments` - Community per node slot, e.g. from
+    ///   `detectCommunities` without the trailing count; `0xFFFFFFFF`
+    ///   leaves a node out
+    #[wasm_bindgen(js_name = buildCommunityGraph)]
+    pub fn build_community_graph(&self, assignments: &[u32]) -> HeroineGraphWasm {
+        let meta = community::community_graph(
+            &self.engine.weighted_edges(),
+            assignments,
+            &self.current_positions(),
+        );
+
+        let mut graph = HeroineGraphWasm::with_capacity(meta.sizes.len(), meta.edges.len());
+        graph.seed = self.seed;
+        graph.engine.add_nodes_from_positions(&meta.centroids);
+        for (a, b, weight) in meta.edges {
+            graph.engine.add_edge(NodeId(a), NodeId(b), weight);
+        }
+        graph
+    }
+
+    /// Sum the edges between each pair of communities.
+    ///
+    /// Feeds bundled edges between clusters at low zoom. Returns a
+    /// Float32Array of [a0, b0, weight0, count0, a1, b1, ...]: one entry per
+    /// community pair (a < b) with at least one edge between them, sorted by
+    /// pair, with the summed weight and number of edges. Edges in both
+    /// directions add up.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignments` - Community per node slot, e.g. from
+    ///   `detectCommunities` without the trailing count; `0xFFFFFFFF`
+    ///   leaves a node out
+    #[wasm_bindgen(js_name = aggregateEdgesByCommunity)]
+    pub fn aggregate_edges_by_community(&self, assignments: &[u32]) -> Float32Array {
+        let aggregated =
+            community::aggregate_community_edges(&self.engine.weighted_edges(), assignments);
+        let flat: Vec<f32> = aggregated
+            .iter()
+            .flat_map(|edge| {
+                [edge.source as f32, edge.target as f32, edge.weight, edge.count as f32]
+            })
+            .collect();
+        Float32Array::from(&flat[..])
+    }
+
+    /// Assign each community a color index that differs from its neighbors'.
+    ///
+    /// Greedily colors the community meta-graph, so communities joined by an
+    /// edge get different indices whenever the palette allows; with too few
+    /// colors, clashes fall on the most weakly connected pairs. Returns a
+    /// Uint32Array with the color index (below `paletteSize`) per node slot,
+    /// ready for GPU upload; `0xFFFFFFFF` for slots left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignments` - Community per node slot, e.g. from
+    ///   `detectCommunities` without the trailing count; `0xFFFFFFFF`
+    ///   leaves a node out
+    /// * `palette_size` - Number of available colors (at least 1)
+    #[wasm_bindgen(js_name = assignCommunityColors)]
+    pub fn assign_community_colors(&self, assignments: &[u32], palette_size: u32) -> Vec<u32> {
+        community::assign_community_colors(
+            &self.engine.weighted_edges(),
+            assignments,
+            palette_size,
+        )
+    }
+
+    /// Detect communities like `detectCommunities`, reporting progress and
+    /// stopping early if cancelled.
+    ///
+    /// Detection stops with an error once `token` is cancelled (or
+    /// `onProgress` throws); the optional `onProgress(fraction, phase)` is
+    /// called after every Louvain level. Returns the same array as
+    /// `detectCommunities`.
+    #[wasm_bindgen(js_name = detectCommunitiesWithProgress)]
+    pub fn detect_communities_with_progress(
+        &self,
+        resolution: f32,
+        max_iterations: u32,
+        min_modularity_gain: f64,
+        token: &CancellationToken,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<Vec<u32>, JsError> {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let mut louvain = community::Louvain::new(
+            &csr,
+            node_count,
+            resolution,
+            max_iterations,
+            min_modularity_gain,
+        );
+        run_reported(&mut louvain, token, on_progress.as_ref())?;
+
+        let result = louvain.into_result();
+        let mut output = result.assignments;
+        output.push(result.community_count);
+        Ok(output)
+    }
+
+    /// Compute community layout positions from community assignments.
+    ///
+    /// Takes community assignments (from `detectCommunities`) and computes
+    /// target positions with communities arranged in a circle.
+    ///
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    ///
+    /// # Arguments
+    ///
+    /// * `assignments` - Community assignment per node (from `detectCommunities`, without trailing count)
+    /// * `community_count` - Number of distinct communities
+    /// * `options` - Object with optional fields (undefined = all defaults):
+    ///   - `communitySpacing` - Space between community clusters (default: 50.0)
+    ///   - `nodeSpacing` - Space between nodes within a community (default: 10.0)
+    ///   - `spreadFactor` - Global scale multiplier (default: 1.5)
+    ///   - `arrangement` - `"circle"` (default), `"grid"` (rows of cells
+    ///     sized to each community) or `"packed"` (discs sized by member
+    ///     count packed around the largest)
+    ///   - `spiralRotation` - Largest seeded random turn, in radians, of each
+    ///     community's spiral; 0 keeps them unrotated (default: 0.0)
+    ///   - `keepCenters` - Keep each community centered where its nodes
+    ///     currently are and only rearrange nodes within it, so re-clustering
+    ///     a live view does not move clusters (default: false)
+    ///
+    /// Throws if the options are malformed.
+    #[wasm_bindgen(js_name = computeCommunityLayout)]
+    pub fn compute_community_layout(
+        &self,
+        assignments: &[u32],
+        community_count: u32,
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let options: CommunityLayoutOptions = layout_options(options)?;
+        let positions = self.community_layout(assignments, community_count, options);
+        Ok(Float32Array::from(&positions[..]))
+    }
+
+    /// Detect communities and compute layout in a single call.
+    ///
+    /// Combines `detectCommunities` and `computeCommunityLayout` for convenience.
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Object with the optional fields of `computeCommunityLayout`
+    ///   and the detection settings:
+    ///   - `resolution` - Louvain resolution parameter (default: 1.0)
+    ///   - `maxIterations` - Maximum Louvain iterations (default: 100)
+    ///   - `minModularityGain` - Convergence threshold (default: 0.0001)
+    ///
+    /// Throws if the options are malformed.
+    #[wasm_bindgen(js_name = computeCommunityLayoutFromGraph)]
+    pub fn compute_community_layout_from_graph(
+        &self,
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+        let options: CommunityLayoutOptions = layout_options(options)?;
+
+        // Detect communities
+        let detection = community::detect_communities(
+            &csr,
+            node_count,
+            options.config.resolution,
+            options.config.max_iterations,
+            options.config.min_modularity_gain,
+        );
+
+        // Compute layout
+        let positions =
+            self.community_layout(&detection.assignments, detection.community_count, options);
+        Ok(Float32Array::from(&positions[..]))
+    }
+
+    /// Compute the bounding circle of each community.
+    ///
+    /// Meant for drawing community backgrounds: pass the positions returned
+    /// by a community layout to get circles for its end state, or an empty
+    /// array for the current positions. Circles cover whole nodes when
+    /// sizes are set with `setNodeExtents`.
+    ///
+    /// Returns a Float32Array of [cx0, cy0, r0, cx1, cy1, r1, ...] per
+    /// community; communities without placed members get sentinel centers
+    /// (f32::MAX) and radius 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `assignments` - Community assignment per node (from `detectCommunities`, without trailing count)
+    /// * `community_count` - Number of distinct communities
+    /// * `positions` - Interleaved positions [x0, y0, x1, y1, ...] by slot,
+    ///   or empty for the current positions
+    #[wasm_bindgen(js_name = computeCommunityBounds)]
+    pub fn compute_community_bounds(
+        &self,
+        assignments: &[u32],
+        community_count: u32,
+        positions: &[f32],
+    ) -> Float32Array {
+        let current;
+        let positions = if positions.is_empty() {
+            current = self.current_positions();
+            &current[..]
+        } else {
+            positions
+        };
+        let bounds =
+            community::community_bounds(assignments, community_count, positions, &self.node_extents);
+        Float32Array::from(&bounds[..])
+    }
+
+    // =========================================================================
+    // Stress Layout
+    // =========================================================================
+
+    /// Compute a stress majorization (Kamada–Kawai style) layout from the graph's edges.
+    ///
+    /// Places nodes so that screen distances match hop distances times
+    /// `edge_length`. Graphs with more nodes than `pivots` use sparse stress
+    /// (neighbor terms plus pivot terms) so large graphs stay tractable.
+    ///
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Isolated and removed nodes get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_length` - Ideal distance between adjacent nodes (default: 50.0)
+    /// * `iterations` - Maximum number of majorization sweeps (default: 100)
+    /// * `pivots` - Pivot count for sparse stress (default: 50, 0 = exact all-pairs)
+    #[wasm_bindgen(js_name = computeStressLayout)]
+    pub fn compute_stress_layout(
+        &self,
+        edge_length: f32,
+        iterations: u32,
+        pivots: u32,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let config = StressLayoutConfig {
+            edge_length,
+            iterations,
+            pivots,
+            seed: self.seed,
+            ..StressLayoutConfig::default()
+        };
+
+        let mut positions = stress::compute_stress_layout(&csr, node_count, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Spectral Layout
+    // =========================================================================
+
+    /// Compute a spectral layout from the graph's edges.
+    ///
+    /// Uses the Laplacian's two smallest non-trivial eigenvectors (found by
+    /// power iteration) as x/y coordinates. Each connected component is scaled
+    /// so its mean edge length is `edge_length`, and components are placed
+    /// side by side. Deterministic for a given seed, which makes it a good
+    /// starting point for force refinement.
+    ///
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Isolated and removed nodes get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_length` - Mean distance between adjacent nodes (default: 50.0)
+    /// * `max_iterations` - Maximum power iterations per eigenvector (default: 1000)
+    #[wasm_bindgen(js_name = computeSpectralLayout)]
+    pub fn compute_spectral_layout(&self, edge_length: f32, max_iterations: u32) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let config = SpectralLayoutConfig {
+            edge_length,
+            max_iterations,
+            seed: self.seed,
+            ..SpectralLayoutConfig::default()
+        };
+
+        let mut positions = spectral::compute_spectral_layout(&csr, node_count, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Pivot MDS Layout
+    // =========================================================================
+
+    /// Compute a pivot MDS layout from the graph's edges.
+    ///
+    /// Runs BFS from `pivots` sampled nodes and embeds the shortest-path
+    /// distances with classical MDS. Captures the global shape of very large
+    /// graphs quickly and works well as an initializer for force refinement.
+    ///
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Isolated and removed nodes get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `edge_length` - Mean distance between adjacent nodes (default: 50.0)
+    /// * `pivots` - Number of pivot nodes (default: 50)
+    #[wasm_bindgen(js_name = computePivotMdsLayout)]
+    pub fn compute_pivot_mds_layout(&self, edge_length: f32, pivots: u32) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let config = PivotMdsConfig {
+            edge_length,
+            pivots,
+            seed: self.seed,
+        };
+
+        let mut positions = pivot_mds::compute_pivot_mds_layout(&csr, node_count, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Multilevel Layout
+    // =========================================================================
+
+    /// Compute a multilevel force-directed layout from the graph's edges.
+    ///
+    /// Coarsens the graph by heavy-edge matching, lays out the coarsest level
+    /// with the CPU force simulation, then expands and refines level by level.
+    /// Untangles very large graphs that a direct force layout cannot. Uses the
+    /// current simulation forces (`setSimulationForces` and related setters);
+    /// `spring_length` is the edge length of the final layout.
+    ///
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Isolated and removed nodes get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `coarsest_size` - Stop coarsening at this many nodes (default: 100)
+    /// * `coarsest_iterations` - Simulation ticks for the coarsest level (default: 300)
+    /// * `level_iterations` - Refinement ticks for each finer level (default: 50)
+    #[wasm_bindgen(js_name = computeMultilevelLayout)]
+    pub fn compute_multilevel_layout(
+        &self,
+        coarsest_size: u32,
+        coarsest_iterations: u32,
+        level_iterations: u32,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let config = MultilevelConfig {
+            simulation: self.simulation.config.clone(),
+            coarsest_size,
+            coarsest_iterations,
+            level_iterations,
+            seed: self.seed,
+        };
+
+        let mut positions = multilevel::compute_multilevel_layout(&csr, node_count, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Orthogonal Layout
+    // =========================================================================
+
+    /// Compute an orthogonal (grid, axis-aligned edges) layout from the graph's edges.
+    ///
+    /// Intended for small schematic or ERD-style diagrams (up to ~1k nodes).
+    /// Nodes are placed on distinct grid points and every edge is routed with
+    /// horizontal and vertical segments that avoid other nodes.
+    ///
+    /// Returns a Float32Array with node positions followed by edge routes:
+    /// `[x0, y0, ..., x_{n-1}, y_{n-1}, edge_count,
+    ///   bend_count_0, bx, by, ..., bend_count_1, bx, by, ...]`
+    /// where `n = node_bound` and edges are in `getEdgesCsr` target order.
+    /// Isolated and removed nodes get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `grid_spacing` - Distance between neighboring grid points (default: 80.0)
+    #[wasm_bindgen(js_name = computeOrthogonalLayout)]
+    pub fn compute_orthogonal_layout(&self, grid_spacing: f32) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let node_count = self.engine.node_bound() as usize;
+
+        let config = OrthogonalLayoutConfig {
+            grid_spacing,
+            seed: self.seed,
+        };
+
+        let layout = orthogonal::compute_orthogonal_layout(&csr, node_count, &config);
+        Float32Array::from(&layout.to_flat()[..])
+    }
+
+    // =========================================================================
+    // Circular Layout
+    // =========================================================================
+
+    /// Compute a circular layout with every node evenly spaced on one circle.
+    ///
+    /// Removed slots get sentinel values (f32::MAX); nodes without edges are
+    /// still placed. Returns a Float32Array of interleaved target positions
+    /// [x0, y0, x1, y1, ...].
+    ///
+    /// # Arguments
+    ///
+    /// * `ordering` - 0 = by degree (hubs first), 1 = grouped by Louvain
+    ///   community, 2 = `custom_order`
+    /// * `custom_order` - Node IDs in circle order (ordering 2 only; unlisted
+    ///   nodes follow in slot order)
+    /// * `radius` - Circle radius (0 = automatic, 30 units of arc per node)
+    #[wasm_bindgen(js_name = computeCircularLayout)]
+    pub fn compute_circular_layout(
+        &self,
+        ordering: u32,
+        custom_order: &[u32],
+        radius: f32,
+    ) -> Float32Array {
+        let present = self.engine.occupied_slots();
+        let (degrees, assignments) = self.ordering_inputs(ordering);
+        let ordering = match ordering {
+            1 => CircularOrdering::Community {
+                assignments: &assignments,
+                degrees: &degrees,
+            },
+            2 => CircularOrdering::Custom(custom_order),
+            _ => CircularOrdering::Degree(&degrees),
+        };
+
+        let config = CircularLayoutConfig {
+            radius,
+            ..CircularLayoutConfig::default()
+        };
+
+        let mut positions = circular::compute_circular_layout(&present, &ordering, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Concentric Layout
+    // =========================================================================
+
+    /// Compute a concentric layout with nodes on rings bucketed by a metric.
+    ///
+    /// Nodes with the highest metric go in the center; each ring spaces its
+    /// nodes evenly by angle and grows outward when crowded. Removed slots get
+    /// sentinel values (f32::MAX); nodes without edges are still placed.
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - One value per node slot (e.g. degree, depth, centrality)
+    /// * `levels` - Number of equal-width rings (0 = one ring per distinct value)
+    /// * `ring_spacing` - Minimum distance between rings (default: 60.0)
+    #[wasm_bindgen(js_name = computeConcentricLayout)]
+    pub fn compute_concentric_layout(
+        &self,
+        metric: &[f32],
+        levels: u32,
+        ring_spacing: f32,
+    ) -> Float32Array {
+        let present = self.engine.occupied_slots();
+
+        let config = ConcentricLayoutConfig {
+            levels,
+            ring_spacing,
+            ..ConcentricLayoutConfig::default()
+        };
+
+        let mut positions = concentric::compute_concentric_layout(&present, metric, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Grid Layout
+    // =========================================================================
+
+    /// Compute a grid layout, filling cells row by row.
+    ///
+    /// Removed slots get sentinel values (f32::MAX); nodes without edges are
+    /// still placed. Returns a Float32Array of interleaved target positions
+    /// [x0, y0, x1, y1, ...] at cell centers, with the grid centered on the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Number of columns (0 = square grid)
+    /// * `cell_width` - Cell width (default: 50.0)
+    /// * `cell_height` - Cell height (default: 50.0)
+    /// * `ordering` - Sort key per node slot, ascending (empty = slot order)
+    #[wasm_bindgen(js_name = computeGridLayout)]
+    pub fn compute_grid_layout(
+        &self,
+        columns: u32,
+        cell_width: f32,
+        cell_height: f32,
+        ordering: &[f32],
+    ) -> Float32Array {
+        let present = self.engine.occupied_slots();
+
+        let config = GridLayoutConfig {
+            columns,
+            cell_width,
+            cell_height,
+        };
+
+        let mut positions = grid::compute_grid_layout(&present, ordering, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Bipartite Layout
+    // =========================================================================
+
+    /// Compute a two-layer bipartite layout from the graph's edges.
+    ///
+    /// Side 0 is placed on an upper line and side 1 on a lower line, each
+    /// ordered by barycenter sweeps to reduce edge crossings. Removed slots get
+    /// sentinel values (f32::MAX); nodes without edges are placed at the end
+    /// of their line. Returns a Float32Array of interleaved target positions
+    /// [x0, y0, x1, y1, ...].
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - Side (0 or 1) per node slot; empty = auto-detect by 2-coloring
+    /// * `layer_gap` - Distance between the two lines (default: 200.0)
+    /// * `node_spacing` - Distance between neighbors on a line (default: 30.0)
+    #[wasm_bindgen(js_name = computeBipartiteLayout)]
+    pub fn compute_bipartite_layout(
+        &self,
+        partition: &[u8],
+        layer_gap: f32,
+        node_spacing: f32,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let present = self.engine.occupied_slots();
+
+        let config = BipartiteLayoutConfig {
+            layer_gap,
+            node_spacing,
+            ..BipartiteLayoutConfig::default()
+        };
+
+        let mut positions = bipartite::compute_bipartite_layout(&csr, &present, partition, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Arc Diagram Layout
+    // =========================================================================
+
+    /// Compute an arc diagram: nodes on one horizontal axis, edges as arcs.
+    ///
+    /// Returns a Float32Array with node positions followed by one quadratic
+    /// Bézier control point per edge:
+    /// `[x0, y0, ..., x_{n-1}, y_{n-1}, edge_count, cx0, cy0, cx1, cy1, ...]`
+    /// where `n = node_bound` and edges are in `getEdgesCsr` target order.
+    /// Removed slots (and edges touching them) get sentinel values (f32::MAX);
+    /// nodes without edges are still placed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ordering` - 0 = by degree (hubs first), 1 = grouped by Louvain
+    ///   community, 2 = `custom_order`
+    /// * `custom_order` - Node IDs from left to right (ordering 2 only;
+    ///   unlisted nodes follow in slot order)
+    /// * `node_spacing` - Distance between neighbors on the axis (default: 30.0)
+    /// * `split_by_direction` - Draw right-to-left edges below the axis
+    #[wasm_bindgen(js_name = computeArcLayout)]
+    pub fn compute_arc_layout(
+        &self,
+        ordering: u32,
+        custom_order: &[u32],
+        node_spacing: f32,
+        split_by_direction: bool,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let present = self.engine.occupied_slots();
+        let (degrees, assignments) = self.ordering_inputs(ordering);
+        let ordering = match ordering {
+            1 => CircularOrdering::Community {
+                assignments: &assignments,
+                degrees: &degrees,
+            },
+            2 => CircularOrdering::Custom(custom_order),
+            _ => CircularOrdering::Degree(&degrees),
+        };
+
+        let config = ArcLayoutConfig {
+            node_spacing,
+            split_by_direction,
+            ..ArcLayoutConfig::default()
+        };
+
+        let layout = arc::compute_arc_layout(&csr, &present, &ordering, &config);
+        Float32Array::from(&layout.to_flat()[..])
+    }
+
+    // =========================================================================
+    // Icicle Layout
+    // =========================================================================
+
+    /// Compute an icicle (adjacency partition) layout of a containment hierarchy.
+    ///
+    /// Depth maps to one axis and subtree size (in leaves, or summed leaf
+    /// weights) to extent on the other, so children exactly fill their
+    /// parent's span. Returns a Float32Array of per-node rectangles
+    /// [x0, y0, w0, h0, x1, y1, w1, h1, ...] with (x, y) the top-left corner.
+    /// Nodes not in the tree get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `weights` - Size per leaf node (empty = 1 per leaf)
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    /// * `breadth` - Total extent of the root (default: 1000.0)
+    /// * `level_size` - Thickness of each depth level (default: 40.0)
+    /// * `horizontal` - If true, depth runs left to right; if false, top-down
+    #[wasm_bindgen(js_name = computeIcicleLayout)]
+    pub fn compute_icicle_layout(
+        &self,
+        containment_edges: &[u32],
+        weights: &[f32],
+        root_id: u32,
+        breadth: f32,
+        level_size: f32,
+        horizontal: bool,
+    ) -> Float32Array {
+        let node_count = self.engine.node_bound() as usize;
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        let config = IcicleLayoutConfig {
+            breadth,
+            level_size,
+            horizontal,
+        };
+
+        let rects =
+            icicle::compute_icicle_layout(containment_edges, weights, node_count, root, &config);
+        Float32Array::from(&rects[..])
+    }
+
+    // =========================================================================
+    // Sunburst Layout
+    // =========================================================================
+
+    /// Compute a sunburst (radial partition) layout of a containment hierarchy.
+    ///
+    /// The root sits in the center and each depth level is a ring; every node
+    /// covers an angular sector proportional to its subtree size (in leaves, or
+    /// summed leaf weights). Returns a Float32Array of per-node sectors
+    /// [startAngle0, endAngle0, innerRadius0, outerRadius0, ...] with angles in
+    /// radians, starting at the top and increasing clockwise on screen.
+    /// Nodes not in the tree get sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `weights` - Size per leaf node (empty = 1 per leaf)
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    /// * `ring_width` - Radial thickness of each ring (default: 40.0)
+    /// * `inner_radius` - Inner radius of the root ring, 0 = full disk (default: 0.0)
+    #[wasm_bindgen(js_name = computeSunburstLayout)]
+    pub fn compute_sunburst_layout(
+        &self,
+        containment_edges: &[u32],
+        weights: &[f32],
+        root_id: u32,
+        ring_width: f32,
+        inner_radius: f32,
+    ) -> Float32Array {
+        let node_count = self.engine.node_bound() as usize;
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        let config = SunburstLayoutConfig {
+            ring_width,
+            inner_radius,
+            ..SunburstLayoutConfig::default()
+        };
+
+        let sectors = sunburst::compute_sunburst_layout(
+            containment_edges,
+            weights,
+            node_count,
+            root,
+            &config,
+        );
+        Float32Array::from(&sectors[..])
+    }
+
+    // =========================================================================
+    // Balloon Tree Layout
+    // =========================================================================
+
+    /// Compute a balloon tree layout of a containment hierarchy.
+    ///
+    /// Each subtree occupies a circle, and the circles of a node's children
+    /// sit on a ring around it with wedges proportional to their size.
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...]
+    /// with the root at the origin. Nodes not in the tree get sentinel values
+    /// (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    /// * `node_radius` - Radius reserved for each node (default: 10.0)
+    /// * `spacing` - Minimum gap between neighboring subtree circles (default: 10.0)
+    #[wasm_bindgen(js_name = computeBalloonLayout)]
+    pub fn compute_balloon_layout(
+        &self,
+        containment_edges: &[u32],
+        root_id: u32,
+        node_radius: f32,
+        spacing: f32,
+    ) -> Float32Array {
+        let node_count = self.engine.node_bound() as usize;
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        let config = BalloonLayoutConfig {
+            node_radius,
+            spacing,
+        };
+
+        let mut positions =
+            balloon::compute_balloon_layout(containment_edges, node_count, root, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Layout Recommendation
+    // =========================================================================
+
+    /// Recommend a layout for the current graph.
+    ///
+    /// Profiles the structure (tree-ness, DAG-ness, bipartiteness, density,
+    /// community structure) and picks a layout with starting parameters.
+    /// Returns an object with:
+    /// - `layout`: `{ type, ...params }`, where `type` is one of `grid`,
+    ///   `tree`, `bipartite`, `community`, `circular`, `stress` or
+    ///   `multilevel`, named after the matching `compute...Layout` call
+    /// - `reason`: short explanation of the choice
+    /// - `profile`: the structural measurements (`nodeCount`, `edgeCount`,
+    ///   `components`, `isolated`, `density`, `maxDegree`, `isForest`,
+    ///   `isDag`, `isBipartite`, `modularity`, `communityCount`)
+    #[wasm_bindgen(js_name = suggestLayout)]
+    pub fn suggest_layout(&self) -> Result<JsValue, JsError> {
+        let csr = self.engine.get_edges_csr();
+        let present = self.engine.occupied_slots();
+        let suggestion = suggest::suggest_layout(&csr, &present);
+        Ok(serde_wasm_bindgen::to_value(&suggestion)?)
+    }
+
+    // =========================================================================
+    // Progressive Layout
+    // =========================================================================
+
+    /// Prepare a layout that runs in slices, so the host can interleave it
+    /// with rendering without moving it into a worker.
+    ///
+    /// `config` is a descriptor with a `type` and optional camelCase
+    /// settings (defaults as in the single layout calls):
+    ///
+    /// - `{ type: "stress", edgeLength?, iterations?, pivots? }` (refines the
+    ///   current positions)
+    /// - `{ type: "community", resolution?, maxIterations?,
+    ///   communitySpacing?, nodeSpacing?, spreadFactor? }`
+    ///
+    /// The task works on a snapshot of the current edges and positions;
+    /// later graph edits do not affect it. Drive it with `layoutStep`.
+    /// Throws if the descriptor is malformed.
+    #[wasm_bindgen(js_name = beginLayout)]
+    pub fn begin_layout(&self, config: JsValue) -> Result<LayoutTask, JsError> {
+        let config: ProgressiveLayoutConfig = serde_wasm_bindgen::from_value(config)?;
+        let csr = self.engine.get_edges_csr();
+        let positions = self.current_positions();
+        Ok(LayoutTask {
+            inner: ProgressiveLayout::new(&config, csr, positions, self.seed),
+            pinned: self.pinned_positions(),
+        })
+    }
+
+    /// Run a layout to the end in one call, reporting progress and stopping
+    /// early if cancelled.
+    ///
+    /// Takes the same descriptors as `beginLayout`. The run stops with an
+    /// error once `token` is cancelled. After every step, the optional
+    /// `onProgress(fraction, phase)` is called with the fraction done (0–1)
+    /// and the phase name; as the call blocks the thread, that callback is
+    /// where the host cancels. A throwing `onProgress` cancels the run too.
+    ///
+    /// Returns a Float32Array of interleaved target positions
+    /// [x0, y0, x1, y1, ...]. Throws if the descriptor is malformed or the
+    /// run was cancelled.
+    #[wasm_bindgen(js_name = runLayout)]
+    pub fn run_layout(
+        &self,
+        config: JsValue,
+        token: &CancellationToken,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<Float32Array, JsError> {
+        let mut task = self.begin_layout(config)?;
+        run_reported(&mut task.inner, token, on_progress.as_ref())?;
+        Ok(task.positions())
+    }
+
+    /// Advance a layout task for about `budget_ms` milliseconds.
+    ///
+    /// Runs at least one step; a step is never cut short, so a call can
+    /// overrun its budget by one step. Returns true once the task is done
+    /// and `task.positions()` holds the final layout.
+    #[wasm_bindgen(js_name = layoutStep)]
+    pub fn layout_step(&self, task: &mut LayoutTask, budget_ms: f64) -> bool {
+        task.inner.run(budget_ms, js_sys::Date::now)
+    }
+
+    // =========================================================================
+    // Layout Pipeline
+    // =========================================================================
+
+    /// Run a chain of layout stages on the current node positions.
+    ///
+    /// `stages` is an array of stage descriptors, each an object with a
+    /// `type` and optional camelCase settings (defaults as in the single
+    /// layout calls):
+    ///
+    /// - `{ type: "spectral", edgeLength?, maxIterations? }`
+    /// - `{ type: "pivotMds", edgeLength?, pivots? }`
+    /// - `{ type: "stress", edgeLength?, iterations?, pivots? }` (refines the
+    ///   current buffer)
+    /// - `{ type: "removeOverlaps", radius?, iterations? }`
+    /// - `{ type: "fitToBounds", minX, minY, maxX, maxY, padding? }`
+    ///
+    /// Stages share one position buffer, starting from the engine's
+    /// positions. Returns a Float32Array of interleaved target positions
+    /// [x0, y0, x1, y1, ...]; removed slots, and nodes left out by a
+    /// spectral or pivot MDS stage, get sentinel values (f32::MAX).
+    /// Throws if a descriptor is malformed.
+    #[wasm_bindgen(js_name = runLayoutPipeline)]
+    pub fn run_layout_pipeline(&self, stages: JsValue) -> Result<Float32Array, JsError> {
+        let stages: Vec<LayoutStage> = serde_wasm_bindgen::from_value(stages)?;
+        let csr = self.engine.get_edges_csr();
+        let mut positions = self.current_positions();
+
+        let pipeline = LayoutPipeline::new(stages, self.seed);
+        pipeline.run(&csr, positions.len() / 2, &mut positions);
+        self.hold_pinned(&mut positions);
+        Ok(Float32Array::from(&positions[..]))
+    }
+
+    /// Push overlapping nodes apart, treating each node as a disk.
+    ///
+    /// Works on the engine's current positions and returns a Float32Array of
+    /// interleaved target positions [x0, y0, x1, y1, ...]. Removed slots get
+    /// sentinel values (f32::MAX).
+    ///
+    /// # Arguments
+    ///
+    /// * `radius` - Radius of every node (default: 10.0)
+    /// * `iterations` - Maximum number of passes (default: 50)
+    #[wasm_bindgen(js_name = removeOverlaps)]
+    pub fn remove_overlaps(&self, radius: f32, iterations: u32) -> Float32Array {
+        let mut positions = self.current_positions();
+        let config = OverlapRemovalConfig { radius, iterations };
+        overlap::remove_overlaps(&mut positions, &config);
+        self.hold_pinned(&mut positions);
+        Float32Array::from(&positions[..])
+    }
+
+    // =========================================================================
+    // Layout Result Utilities
+    // =========================================================================
+
+    /// Rescale a layout result to a requested size.
+    ///
+    /// Scales uniformly (keeping the aspect ratio) and centers the layout on
+    /// the origin so it fits a `width` × `height` box minus `padding` on
+    /// every side. Works on the output of any layout; sentinel entries stay
+    /// as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    /// * `width` - Width of the box
+    /// * `height` - Height of the box
+    /// * `padding` - Margin kept free inside the box
+    ///
+    /// # Returns
+    ///
+    /// A Float32Array with the rescaled targets.
+    #[wasm_bindgen(js_name = fitPositions)]
+    pub fn fit_positions(
+        &self,
+        targets: &[f32],
+        width: f32,
+        height: f32,
+        padding: f32,
+    ) -> Float32Array {
+        let mut fitted = targets.to_vec();
+        let (half_w, half_h) = (width * 0.5, height * 0.5);
+        fit::fit_to_bounds(&mut fitted, (-half_w, -half_h, half_w, half_h), padding);
+        Float32Array::from(&fitted[..])
+    }
+
+    /// Scale, rotate, mirror and move a layout result.
+    ///
+    /// Same transform as `transformPositions`, applied about the centroid of
+    /// the positioned entries of `targets`; sentinel entries stay as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    /// * `scale` - Uniform scale factor (1 = unchanged)
+    /// * `rotation` - Rotation in radians, clockwise on a y-down screen
+    /// * `tx`, `ty` - Translation
+    /// * `mirror` - Mirror horizontally before rotating
+    ///
+    /// # Returns
+    ///
+    /// A Float32Array with the transformed targets.
+    #[wasm_bindgen(js_name = transformTargets)]
+    pub fn transform_targets(
+        &self,
+        targets: &[f32],
+        scale: f32,
+        rotation: f32,
+        tx: f32,
+        ty: f32,
+        mirror: bool,
+    ) -> Float32Array {
+        let transform = Transform {
+            scale,
+            rotation,
+            mirror,
+            translate: (tx, ty),
+        };
+        let mut transformed = targets.to_vec();
+        transform::transform_positions(&mut transformed, &transform);
+        Float32Array::from(&transformed[..])
+    }
+
+    /// Snap a layout result to a grid and edges to angle steps.
+    ///
+    /// Same snapping as `snapPositions`, applied to `targets` instead of
+    /// the live positions; sentinel entries stay as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    /// * `grid_size` - Grid spacing (0 = no grid snapping)
+    /// * `angle_snap_degrees` - Angle step in degrees (omit or 0 = no angle
+    ///   snapping)
+    ///
+    /// # Returns
+    ///
+    /// A Float32Array with the snapped targets.
+    #[wasm_bindgen(js_name = snapTargets)]
+    pub fn snap_targets(
+        &self,
+        targets: &[f32],
+        grid_size: f32,
+        angle_snap_degrees: Option<f32>,
+    ) -> Float32Array {
+        let mut snapped = targets.to_vec();
+        let csr = self.engine.get_edges_csr();
+        let config = snap_config(grid_size, angle_snap_degrees);
+        snap::snap_positions(&mut snapped, &csr, &[], &config);
+        Float32Array::from(&snapped[..])
+    }
+
+    /// Align a layout result to the current positions.
+    ///
+    /// Rotates, translates and possibly mirrors `targets` as a whole so that
+    /// nodes move as little as possible from where they are now. Use after
+    /// recomputing any layout so the view does not spin or flip. Scale is
+    /// kept; sentinel entries stay as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...] by node slot
+    ///
+    /// # Returns
+    ///
+    /// A Float32Array with the aligned targets.
+    #[wasm_bindgen(js_name = alignToCurrent)]
+    pub fn align_to_current(&self, targets: &[f32]) -> Float32Array {
+        let mut aligned = targets.to_vec();
+        align::align_positions(&mut aligned, &self.current_positions(), true);
+        Float32Array::from(&aligned[..])
+    }
+
+    /// Measure the quality of a layout.
+    ///
+    /// Returns an object with:
+    /// - `stress`: normalized stress, scale invariant (lower is better)
+    /// - `edgeLengthVariance`: edge length variance over the squared mean
+    ///   (lower is more uniform)
+    /// - `neighborhoodPreservation`: mean Jaccard similarity (0–1) between
+    ///   graph neighbors and nearest nodes in the layout (higher is better)
+    /// - `edgeCrossings`: number of edge crossings
+    /// - `crossingsEstimated`: whether `edgeCrossings` was estimated from
+    ///   sampled edge pairs (large graphs)
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - Interleaved positions [x0, y0, x1, y1, ...] by node slot,
+    ///   or omitted to evaluate the current positions
+    #[wasm_bindgen(js_name = evaluateLayout)]
+    pub fn evaluate_layout(&self, positions: Option<Vec<f32>>) -> Result<JsValue, JsError> {
+        let positions = positions.unwrap_or_else(|| self.current_positions());
+        let csr = self.engine.get_edges_csr();
+        let result = metrics::evaluate_layout(&csr, &positions, &LayoutMetricsConfig::default());
+        Ok(serde_wasm_bindgen::to_value(&result)?)
+    }
+
+    /// Count edge crossings in a layout.
+    ///
+    /// Edges are straight undirected segments; parallel edges count once and
+    /// edges sharing a node never cross. Exact up to 5000 edges; larger
+    /// graphs are estimated from a fixed random sample of 5000 edges, so the
+    /// result is deterministic but approximate.
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - Interleaved positions [x0, y0, x1, y1, ...] by node slot,
+    ///   or omitted to use the current positions
+    ///
+    /// # Returns
+    ///
+    /// The number of crossing edge pairs.
+    #[wasm_bindgen(js_name = countEdgeCrossings)]
+    pub fn count_edge_crossings(&self, positions: Option<Vec<f32>>) -> f64 {
+        let positions = positions.unwrap_or_else(|| self.current_positions());
+        let csr = self.engine.get_edges_csr();
+        crossings::count_edge_crossings(&csr, &positions, &EdgeCrossingConfig::default()).count
+    }
+
+    // =========================================================================
+    // Edge Bundling
+    // =========================================================================
+
+    /// Bundle edges with force-directed edge bundling at the current
+    /// positions.
+    ///
+    /// Each edge becomes a polyline whose subdivision points are pulled
+    /// toward those of compatible edges (similar direction, length and
+    /// position), so dense groups of parallel edges merge into bundles.
+    ///
+    /// # Arguments
+    ///
+    /// * `cycles` - Subdivision cycles; edges end up with 2^(cycles-1)
+    ///   subdivision points (default: 6)
+    /// * `iterations` - Iterations of the first cycle (default: 50)
+    /// * `compatibility_threshold` - Minimum compatibility (0–1) for two edges
+    ///   to attract each other (default: 0.6)
+    /// * `stiffness` - Spring constant keeping edges straight (default: 0.1)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array `[edge_count, offset0, ..., offset_edge_count, x0, y0, ...]`
+    /// with one polyline per edge in `getEdgesCsr` order; edge `e` uses points
+    /// `offset_e..offset_{e+1}`, endpoints included.
+    #[wasm_bindgen(js_name = computeEdgeBundling)]
+    pub fn compute_edge_bundling(
+        &self,
+        cycles: u32,
+        iterations: u32,
+        compatibility_threshold: f32,
+        stiffness: f32,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let positions = self.current_positions();
+
+        let config = EdgeBundlingConfig {
+            cycles,
+            iterations,
+            compatibility_threshold,
+            stiffness,
+            ..EdgeBundlingConfig::default()
+        };
+
+        let polylines = bundling::compute_force_bundling(&csr, &positions, &config);
+        Float32Array::from(&polylines.to_flat()[..])
+    }
+
+    /// Bundle edges along a containment hierarchy at the current positions.
+    ///
+    /// Each edge is routed through the positions of the tree nodes between
+    /// its endpoints (up to their lowest common ancestor and back down), so
+    /// edges between the same subtrees share a path. Edges with an endpoint
+    /// outside the tree stay straight.
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    /// * `beta` - Bundling strength, 0 = straight to 1 = follow the tree (default: 0.85)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array in the same polyline format as `computeEdgeBundling`.
+    #[wasm_bindgen(js_name = computeHierarchicalEdgeBundling)]
+    pub fn compute_hierarchical_edge_bundling(
+        &self,
+        containment_edges: &[u32],
+        root_id: u32,
+        beta: f32,
+    ) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let positions = self.current_positions();
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        let config = HierarchicalBundlingConfig { beta };
+
+        let polylines = bundling::compute_hierarchical_bundling(
+            &csr,
+            &positions,
+            containment_edges,
+            root,
+            &config,
+        );
+        Float32Array::from(&polylines.to_flat()[..])
+    }
+
+    // =========================================================================
+    // Edge Curves
+    // =========================================================================
+
+    /// Tessellate every edge into a quadratic Bézier line strip at the
+    /// current positions.
+    ///
+    /// Parallel edges between the same two nodes fan out on both sides of
+    /// the straight line; self loops become circles above their node.
+    ///
+    /// # Arguments
+    ///
+    /// * `curvature` - Control point offset as a fraction of the edge length
+    ///   (default: 0.2)
+    /// * `segments` - Line segments per edge (default: 16)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array of `segments + 1` interleaved vertices [x0, y0, x1, y1, ...]
+    /// per edge in `getEdgesCsr` order, from source to target. Edges touching
+    /// a removed node get sentinel values (f32::MAX).
+    #[wasm_bindgen(js_name = computeEdgeCurves)]
+    pub fn compute_edge_curves(&self, curvature: f32, segments: u32) -> Float32Array {
+        let csr = self.engine.get_edges_csr();
+        let positions = self.current_positions();
 
-        let positions = community::compute_community_layout(
-            assignments,
-            community_count,
-            node_count,
-            &config,
-        );
+        let config = EdgeCurveConfig {
+            curvature,
+            segments,
+            ..EdgeCurveConfig::default()
+        };
 
-        Float32Array::from(&positions[..])
+        let vertices = curves::compute_edge_curves(&csr, &positions, &config);
+        Float32Array::from(&vertices[..])
     }
 
-    /// Detect communities and compute layout in a single call.
+    // =========================================================================
+    // Group Hulls
+    // =========================================================================
+
+    /// Compute a padded convex outline per group at the current positions.
     ///
-    /// Combines `detectCommunities` and `computeCommunityLayout` for convenience.
-    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Use to draw community or cluster "blobs" behind the nodes, e.g. with
+    /// the assignments from `detectCommunities`.
     ///
     /// # Arguments
     ///
-    /// * `resolution` - Louvain resolution parameter (default: 1.0)
-    /// * `max_iterations` - Maximum Louvain iterations (default: 100)
-    /// * `community_spacing` - Space between community clusters (default: 50.0)
-    /// * `node_spacing` - Space between nodes within a community (default: 10.0)
-    /// * `spread_factor` - Global scale multiplier (default: 1.5)
-    #[wasm_bindgen(js_name = computeCommunityLayoutFromGraph)]
-    pub fn compute_community_layout_from_graph(
+    /// * `assignments` - Group id per node slot (u32::MAX = no group)
+    /// * `padding` - Distance between the outline and the outermost nodes
+    /// * `rounded` - Round the corners (true) or cut them (false)
+    ///
+    /// # Returns
+    ///
+    /// Float32Array `[group_count, group0, point_count0, x, y, ..., group1, ...]`
+    /// with one counter-clockwise outline per group by ascending group id.
+    #[wasm_bindgen(js_name = computeGroupHulls)]
+    pub fn compute_group_hulls(
         &self,
-        resolution: f32,
-        max_iterations: u32,
-        community_spacing: f32,
-        node_spacing: f32,
-        spread_factor: f32,
+        assignments: &[u32],
+        padding: f32,
+        rounded: bool,
     ) -> Float32Array {
-        let csr = self.engine.get_edges_csr();
-        let node_count = self.engine.node_bound() as usize;
-
-        // Detect communities
-        let detection = community::detect_communities(
-            &csr,
-            node_count,
-            resolution,
-            max_iterations,
-            0.0001, // default convergence threshold
-        );
-
-        // Compute layout
-        let config = CommunityLayoutConfig {
-            community_spacing,
-            node_spacing,
-            spread_factor,
-            ..CommunityLayoutConfig::default()
-        };
-
-        let positions = community::compute_community_layout(
-            &detection.assignments,
-            detection.community_count,
-            node_count,
-            &config,
-        );
+        let positions = self.current_positions();
+        let config = GroupHullConfig { padding, rounded };
 
-        Float32Array::from(&positions[..])
+        let hulls = hull::compute_group_hulls(&positions, assignments, &config);
+        Float32Array::from(&hull::hulls_to_flat(&hulls)[..])
     }
 
     // =========================================================================
@@ -581,48 +3293,55 @@ impl HeroineGraphWasm {
     /// then produces a circle-packing layout where directories contain files
     /// which contain symbols.
     ///
-    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...].
+    /// Returns a Float32Array of interleaved target positions [x0, y0, x1, y1, ...],
+    /// followed by the radius of every node's circle [r0, r1, ...] when
+    /// `includeRadii` is set (0 for nodes outside the hierarchy).
     ///
     /// # Arguments
     ///
     /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
     /// * `node_categories` - One u8 per node (0=repo, 1=dir, 2=file, 3=symbol, 4=other)
-    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
-    /// * `directory_padding` - Padding within directory circles (default: 15.0)
-    /// * `file_padding` - Padding within file circles (default: 8.0)
-    /// * `spread_factor` - Global scale multiplier (default: 1.5)
+    /// * `options` - Object with optional fields (undefined = all defaults):
+    ///   - `rootId` - Root node ID (default: auto-detect)
+    ///   - `directoryPadding` - Padding within directory circles (default: 15.0)
+    ///   - `filePadding` - Padding within file circles (default: 8.0)
+    ///   - `symbolRadius` - Base radius for symbol nodes (default: 5.0)
+    ///   - `fileRadius` - Minimum radius for file nodes (default: 12.0)
+    ///   - `directoryRadius` - Minimum radius for directory nodes (default: 25.0)
+    ///   - `spreadFactor` - Global scale multiplier (default: 1.5)
+    ///   - `sortKeys` - Per-node packing order by slot; siblings pack in
+    ///     ascending key order, unkeyed ones after, largest first (default: [])
+    ///   - `includeRadii` - Append the circle radii, scaled like the
+    ///     positions, for drawing directory and file boundaries (default: false)
+    ///
+    /// Throws if the options are malformed.
     #[wasm_bindgen(js_name = computeCodebaseLayout)]
     pub fn compute_codebase_layout(
         &self,
         containment_edges: &[u32],
         node_categories: &[u8],
-        root_id: u32,
-        directory_padding: f32,
-        file_padding: f32,
-        spread_factor: f32,
-    ) -> Float32Array {
-        use layout::codebase::{CodebaseLayoutConfig, self};
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        use layout::codebase::{self, CodebaseLayoutOptions};
 
         let node_count = self.engine.node_bound() as usize;
+        let options: CodebaseLayoutOptions = layout_options(options)?;
+        let root = options.root_id.filter(|&r| r != u32::MAX);
 
-        let config = CodebaseLayoutConfig {
-            directory_padding,
-            file_padding,
-            spread_factor,
-            ..CodebaseLayoutConfig::default()
-        };
-
-        let root = if root_id == u32::MAX { None } else { Some(root_id) };
-
-        let positions = codebase::compute_codebase_layout(
+        let mut layout = codebase::compute_codebase_layout_with_radii(
             containment_edges,
             node_categories,
             node_count,
             root,
-            &config,
+            &options.config,
         );
+        self.hold_pinned(&mut layout.positions);
 
-        Float32Array::from(&positions[..])
+        if options.include_radii {
+            Ok(Float32Array::from(&layout.to_flat()[..]))
+        } else {
+            Ok(Float32Array::from(&layout.positions[..]))
+        }
     }
 
     /// Compute codebase layout using the graph's own edges.
@@ -633,83 +3352,136 @@ impl HeroineGraphWasm {
     /// # Arguments
     ///
     /// * `node_categories` - One u8 per node (0=repo, 1=dir, 2=file, 3=symbol, 4=other)
-    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
-    /// * `directory_padding` - Padding within directory circles (default: 15.0)
-    /// * `file_padding` - Padding within file circles (default: 8.0)
-    /// * `spread_factor` - Global scale multiplier (default: 1.5)
+    /// * `options` - Same options as `computeCodebaseLayout`
     #[wasm_bindgen(js_name = computeCodebaseLayoutFromGraph)]
     pub fn compute_codebase_layout_from_graph(
         &self,
         node_categories: &[u8],
-        root_id: u32,
-        directory_padding: f32,
-        file_padding: f32,
-        spread_factor: f32,
-    ) -> Float32Array {
-        // Extract edges from CSR
-        let csr = self.engine.get_edges_csr();
-        let node_bound = self.engine.node_bound() as usize;
+        options: JsValue,
+    ) -> Result<Float32Array, JsError> {
+        // With no edges every slot gets the sentinel
+        self.compute_codebase_layout(&self.edge_pairs(), node_categories, options)
+    }
 
-        if csr.len() <= node_bound + 1 {
-            let sentinel = 3.402_823e+38_f32;
-            let positions = vec![sentinel; node_bound * 2];
-            return Float32Array::from(&positions[..]);
-        }
+    /// Update a codebase layout after files or symbols were added or
+    /// removed, repacking only the affected parent chains and moving
+    /// siblings as little as possible.
+    ///
+    /// Returns `{ slots, positions, radii }` listing only the nodes that
+    /// moved or resized: positions are interleaved [x0, y0, x1, y1, ...] and
+    /// scaled like the full layout. Nodes no longer in the hierarchy get
+    /// sentinel positions and radius 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - The current hierarchy, as in `computeCodebaseLayout`
+    /// * `node_categories` - One u8 per node (0=repo, 1=dir, 2=file, 3=symbol, 4=other)
+    /// * `previous` - The previous layout with `includeRadii`: positions,
+    ///   then radii (with the updates applied, for repeated calls)
+    /// * `changed_nodes` - Nodes whose children were added or removed. New
+    ///   nodes are found automatically; list the parents of removed nodes so
+    ///   their circles shrink.
+    /// * `options` - Same options as `computeCodebaseLayout`; `includeRadii`
+    ///   is ignored
+    ///
+    /// Throws if the options are malformed.
+    #[wasm_bindgen(js_name = updateCodebaseLayout)]
+    pub fn update_codebase_layout(
+        &self,
+        containment_edges: &[u32],
+        node_categories: &[u8],
+        previous: &[f32],
+        changed_nodes: &[u32],
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        use layout::codebase::{self, CodebaseLayout, CodebaseLayoutOptions};
 
-        let offsets = &csr[..node_bound + 1];
-        let targets = &csr[node_bound + 1..];
+        let node_count = self.engine.node_bound() as usize;
+        let options: CodebaseLayoutOptions = layout_options(options)?;
+        let root = options.root_id.filter(|&r| r != u32::MAX);
 
-        // Convert CSR to flat containment edge pairs
-        let mut edges = Vec::with_capacity(targets.len() * 2);
-        for src in 0..node_bound {
-            let start = offsets[src] as usize;
-            let end = offsets[src + 1] as usize;
-            for &tgt in &targets[start..end.min(targets.len())] {
-                edges.push(src as u32);
-                edges.push(tgt);
+        let previous_count = previous.len() / 3;
+        let previous = CodebaseLayout {
+            positions: previous[..previous_count * 2].to_vec(),
+            radii: previous[previous_count * 2..previous_count * 3].to_vec(),
+        };
+        let mut update = codebase::update_codebase_layout(
+            containment_edges,
+            node_categories,
+            node_count,
+            root,
+            &previous,
+            changed_nodes,
+            &options.config,
+        );
+        // Pinned nodes come back at their current positions
+        for (slot, position) in self.pinned_positions() {
+            if let Ok(at) = update.slots.binary_search(&(slot as u32)) {
+                update.positions[at * 2..at * 2 + 2].copy_from_slice(&position);
             }
         }
 
-        self.compute_codebase_layout(
-            &edges,
-            node_categories,
-            root_id,
-            directory_padding,
-            file_padding,
-            spread_factor,
-        )
+        Ok(serde_wasm_bindgen::to_value(&update)?)
+    }
+
+    /// Update a codebase layout using the graph's own edges; see
+    /// `updateCodebaseLayout`.
+    #[wasm_bindgen(js_name = updateCodebaseLayoutFromGraph)]
+    pub fn update_codebase_layout_from_graph(
+        &self,
+        node_categories: &[u8],
+        previous: &[f32],
+        changed_nodes: &[u32],
+        options: JsValue,
+    ) -> Result<JsValue, JsError> {
+        let edges = self.edge_pairs();
+        self.update_codebase_layout(&edges, node_categories, previous, changed_nodes, options)
     }
 
     /// Compute bubble data (well radii + depths) from the graph's containment hierarchy.
     ///
     /// Returns a `Float32Array` of length `2 * node_bound`:
     /// `[wellRadius_0, ..., wellRadius_{n-1}, depth_0, ..., depth_{n-1}]`.
+    /// Every parentless node roots its own tree, so each component of a
+    /// forest (say, several repositories) gets its own radii and depths.
     ///
     /// # Arguments
     ///
     /// * `base_radius` - Base bubble radius for leaf nodes (default: 10.0)
     /// * `padding` - Padding added to internal node radii (default: 5.0)
+    /// * `component_depth_offset` - Depth added per component, largest tree
+    ///   first, to keep trees in separate wells (default: 0.0)
+    /// * `weights` - Size per leaf node, e.g. lines of code (default: 1 per
+    ///   leaf). Bubble area grows with the weight: a leaf of weight `w` gets
+    ///   radius `base_radius * sqrt(w)`, so normalize large metrics.
     #[wasm_bindgen(js_name = computeBubbleData)]
-    pub fn compute_bubble_data(&self, base_radius: f32, padding: f32) -> Float32Array {
-        use layout::bubble::{self, BubbleConfig};
-
+    pub fn compute_bubble_data(
+        &self,
+        base_radius: f32,
+        padding: f32,
+        component_depth_offset: Option<f32>,
+        weights: Option<Vec<f32>>,
+    ) -> Float32Array {
         let node_bound = self.engine.node_bound() as usize;
 
         if node_bound == 0 {
             return Float32Array::from(&[][..]);
         }
 
+        let config = BubbleConfig {
+            base_radius,
+            padding,
+            component_depth_offset: component_depth_offset.unwrap_or(0.0),
+            ..BubbleConfig::default()
+        };
+        let weights = weights.unwrap_or_default();
+
         // Extract edges from CSR
         let csr = self.engine.get_edges_csr();
 
         if csr.len() <= node_bound + 1 {
             // No edges — return defaults
-            let config = BubbleConfig {
-                base_radius,
-                padding,
-                ..BubbleConfig::default()
-            };
-            let result = bubble::compute_bubble_data(&[], node_bound, None, &config);
+            let result = bubble::compute_bubble_data(&[], &weights, node_bound, None, &config);
             return Float32Array::from(&result[..]);
         }
 
@@ -727,14 +3499,233 @@ impl HeroineGraphWasm {
             }
         }
 
-        let config = BubbleConfig {
-            base_radius,
-            padding,
-            ..BubbleConfig::default()
+        let result = bubble::compute_bubble_data(&edges, &weights, node_bound, None, &config);
+        Float32Array::from(&result[..])
+    }
+
+    /// Count the descendants of every node in a containment hierarchy.
+    ///
+    /// Returns one count per node slot, the node itself excluded; leaves and
+    /// nodes outside the tree get 0. A node reachable from several parents
+    /// counts under the first one, as in the hierarchy layouts.
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    #[wasm_bindgen(js_name = computeSubtreeCounts)]
+    pub fn compute_subtree_counts(&self, containment_edges: &[u32], root_id: u32) -> Vec<u32> {
+        let node_count = self.engine.node_bound() as usize;
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        Hierarchy::from_containment(containment_edges, node_count, root)
+            .map(|tree| tree.descendant_counts())
+            .unwrap_or_else(|| vec![0; node_count])
+    }
+
+    /// Group the nodes of a containment hierarchy by depth, for level-based
+    /// progressive disclosure (draw containers up to depth k, then deepen).
+    ///
+    /// Returns `[level_count, offsets..., ids...]` with `level_count + 1`
+    /// CSR-style offsets: the nodes at depth `d` are
+    /// `ids[offsets[d]..offsets[d + 1]]`, siblings together. Nodes outside
+    /// the tree are left out; without a tree the result is `[0, 0]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `containment_edges` - Flat array of [parent0, child0, parent1, child1, ...] pairs
+    /// * `root_id` - Root node ID (u32::MAX = auto-detect)
+    #[wasm_bindgen(js_name = groupNodesByDepth)]
+    pub fn group_nodes_by_depth(&self, containment_edges: &[u32], root_id: u32) -> Vec<u32> {
+        let node_count = self.engine.node_bound() as usize;
+        let root = if root_id == u32::MAX { None } else { Some(root_id) };
+
+        let (offsets, ids) = Hierarchy::from_containment(containment_edges, node_count, root)
+            .map(|tree| tree.levels())
+            .unwrap_or_else(|| (vec![0], Vec::new()));
+        let mut flat = vec![offsets.len() as u32 - 1];
+        flat.extend(offsets);
+        flat.extend(ids);
+        flat
+    }
+
+    /// Well circle of every container node at its current position, for
+    /// drawing bubble boundaries in one instanced SDF draw call.
+    ///
+    /// Uses the graph's edges as the containment hierarchy, like
+    /// `computeBubbleData`. Returns a Float32Array of `[x, y, radius, depth]`
+    /// per node with children, outermost first so nested circles draw back
+    /// to front.
+    ///
+    /// # Arguments
+    ///
+    /// * `bubble_data` - Output of `computeBubbleData`
+    #[wasm_bindgen(js_name = computeBubbleCircles)]
+    pub fn compute_bubble_circles(&self, bubble_data: &[f32]) -> Float32Array {
+        let circles =
+            bubble::bubble_circles(&self.edge_pairs(), bubble_data, &self.current_positions());
+        let flat: Vec<f32> = circles.iter().flatten().copied().collect();
+        Float32Array::from(&flat[..])
+    }
+
+    /// Tessellate the bubble wells of `computeBubbleCircles` into a line
+    /// list: `segments` line segments per circle, each as two vertices
+    /// [x0, y0, x1, y1], for a single line-list draw call.
+    ///
+    /// # Arguments
+    ///
+    /// * `bubble_data` - Output of `computeBubbleData`
+    /// * `segments` - Segments per circle (at least 3; default: 64)
+    #[wasm_bindgen(js_name = tessellateBubbleOutlines)]
+    pub fn tessellate_bubble_outlines(
+        &self,
+        bubble_data: &[f32],
+        segments: Option<u32>,
+    ) -> Float32Array {
+        let circles =
+            bubble::bubble_circles(&self.edge_pairs(), bubble_data, &self.current_positions());
+        let vertices = bubble::tessellate_circles(&circles, segments.unwrap_or(64));
+        Float32Array::from(&vertices[..])
+    }
+}
+
+impl HeroineGraphWasm {
+    /// Tidy tree layout over `node_bound` slots.
+    fn tidy_tree_layout(&self, edges: &[u32], options: TreeLayoutOptions) -> TreeLayoutResult {
+        let layout = TidyTreeLayout::new(options.config);
+        let node_count = self.engine.node_bound() as usize;
+
+        let inputs = TreeNodeInputs {
+            ranks: &options.ranks,
+            extents: &self.node_extents,
+            collapsed: &self.collapsed,
         };
+        // u32::MAX also means auto-detect, as in the other tree entry points
+        let root = options.root_id.filter(|&r| r != u32::MAX);
+        let result = layout.compute_with_inputs(node_count, edges, root, &inputs);
 
-        let result = bubble::compute_bubble_data(&edges, node_bound, None, &config);
-        Float32Array::from(&result[..])
+        // Interleave x and y into [x0, y0, x1, y1, ...]
+        let mut positions = Vec::with_capacity(node_count * 2);
+        for i in 0..node_count {
+            positions.push(result.positions_x[i]);
+            positions.push(result.positions_y[i]);
+        }
+        self.hold_pinned(&mut positions);
+
+        TreeLayoutResult {
+            positions,
+            depths: result.depths,
+            subtree_sizes: result.subtree_sizes,
+        }
+    }
+
+    /// Community layout with the engine seed, around the current community
+    /// centers if `keepCenters` is set.
+    fn community_layout(
+        &self,
+        assignments: &[u32],
+        community_count: u32,
+        options: CommunityLayoutOptions,
+    ) -> Vec<f32> {
+        let config = CommunityLayoutConfig {
+            extents: self.node_extents.clone(),
+            seed: self.seed,
+            ..options.config
+        };
+        let mut positions = if options.keep_centers {
+            let current = self.current_positions();
+            community::compute_community_layout_around(
+                assignments,
+                community_count,
+                &current,
+                &config,
+            )
+        } else {
+            let node_count = self.engine.node_bound() as usize;
+            community::compute_community_layout(assignments, community_count, node_count, &config)
+        };
+        self.hold_pinned(&mut positions);
+        positions
+    }
+
+    /// Current positions as [x0, y0, x1, y1, ...] over `node_bound` slots,
+    /// with the sentinel for removed slots.
+    fn current_positions(&self) -> Vec<f32> {
+        let (xs, ys) = (self.engine.positions_x(), self.engine.positions_y());
+        self.engine
+            .occupied_slots()
+            .iter()
+            .enumerate()
+            .flat_map(|(slot, &present)| {
+                if present {
+                    [xs[slot], ys[slot]]
+                } else {
+                    [SENTINEL, SENTINEL]
+                }
+            })
+            .collect()
+    }
+
+    /// The graph's edges as flat pairs [src0, tgt0, src1, tgt1, ...], in
+    /// CSR order; the hierarchy layouts read them as containment edges.
+    fn edge_pairs(&self) -> Vec<u32> {
+        let csr = self.engine.get_edges_csr();
+        let node_bound = self.engine.node_bound() as usize;
+        csr_pairs(&csr, node_bound)
+            .flat_map(|(src, tgt)| [src as u32, tgt as u32])
+            .collect()
+    }
+
+    /// Current positions of the pinned nodes, by slot.
+    fn pinned_positions(&self) -> Vec<(usize, [f32; 2])> {
+        let (xs, ys) = (self.engine.positions_x(), self.engine.positions_y());
+        self.engine
+            .pinned_slots()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &pinned)| pinned)
+            .map(|(slot, _)| (slot, [xs[slot], ys[slot]]))
+            .collect()
+    }
+
+    /// Keep pinned nodes where they are in layout `targets`.
+    fn hold_pinned(&self, targets: &mut [f32]) {
+        hold_positions(targets, &self.pinned_positions());
+    }
+
+    /// Reject path queries between missing nodes or over negative (or NaN)
+    /// edge weights.
+    fn check_path_query(&self, source: u32, target: u32) -> Result<(), JsError> {
+        for id in [source, target] {
+            if !self.engine.contains_node(NodeId(id)) {
+                return Err(JsError::new(&format!("Node {id} does not exist")));
+            }
+        }
+        let edges = self.engine.weighted_edges();
+        if edges.iter().any(|&(_, _, weight)| weight < 0.0 || weight.is_nan()) {
+            return Err(JsError::new("Negative edge weights need shortestPathBellmanFord"));
+        }
+        Ok(())
+    }
+
+    /// Total degree per slot, plus Louvain community assignments when
+    /// `ordering` is 1 (community ordering), for `CircularOrdering`.
+    fn ordering_inputs(&self, ordering: u32) -> (Vec<u32>, Vec<u32>) {
+        let degrees: Vec<u32> = self
+            .engine
+            .get_node_degrees()
+            .chunks_exact(2)
+            .map(|d| d[0] + d[1])
+            .collect();
+
+        let assignments = if ordering == 1 {
+            let csr = self.engine.get_edges_csr();
+            let node_count = self.engine.node_bound() as usize;
+            community::detect_communities(&csr, node_count, 1.0, 100, 0.0001).assignments
+        } else {
+            Vec::new()
+        };
+        (degrees, assignments)
     }
 }
 
@@ -744,9 +3735,183 @@ impl Default for HeroineGraphWasm {
     }
 }
 
+/// Overwrite the targets of `held` slots with their positions, so a layout
+/// never pulls a pinned node away from where the user dragged it.
+fn hold_positions(targets: &mut [f32], held: &[(usize, [f32; 2])]) {
+    for &(slot, position) in held {
+        if let Some(target) = targets.get_mut(slot * 2..slot * 2 + 2) {
+            target.copy_from_slice(&position);
+        }
+    }
+}
+
+/// Options object from JS; `undefined` or `null` gives the defaults.
+fn layout_options<T: DeserializeOwned + Default>(options: JsValue) -> Result<T, JsError> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(T::default());
+    }
+    Ok(serde_wasm_bindgen::from_value(options)?)
+}
+
+/// Snapping settings from the wasm arguments; negative values count as 0.
+fn snap_config(grid_size: f32, angle_snap_degrees: Option<f32>) -> SnapConfig {
+    SnapConfig {
+        grid_size: grid_size.max(0.0),
+        angle_step: angle_snap_degrees.unwrap_or(0.0).max(0.0).to_radians(),
+        ..SnapConfig::default()
+    }
+}
+
+/// Groups as `[group_count, offsets..., ids...]` with `group_count + 1`
+/// CSR-style offsets: group `g` is `ids[offsets[g]..offsets[g + 1]]`.
+fn flatten_groups(groups: Vec<Vec<u32>>) -> Vec<u32> {
+    let mut flat = vec![groups.len() as u32, 0];
+    let mut offset = 0;
+    for group in &groups {
+        offset += group.len() as u32;
+        flat.push(offset);
+    }
+    flat.extend(groups.into_iter().flatten());
+    flat
+}
+
+/// Flag for cancelling a long computation. Cancel it from the progress
+/// callback (or from another thread with the `threads` build); the
+/// computation stops after its current step.
+///
+/// Only `runLayout`, `detectCommunitiesWithProgress` and
+/// `computeBetweennessWithProgress` take a token. Every other call runs to
+/// the end once started, including Leiden, Girvan-Newman, spectral and
+/// consensus clustering, the other centralities, `findCycles`, and the
+/// direct layout calls such as `computeStressLayout`; run those in a worker
+/// to keep them off the main thread, or use `beginLayout` and `layoutStep`
+/// for layouts.
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: CancelToken,
+}
+
+#[wasm_bindgen]
+impl CancellationToken {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask the computation using this token to stop.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Whether `cancel` has been called.
+    #[wasm_bindgen(js_name = isCancelled)]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+}
+
+/// Run `work` to the end, calling `on_progress(fraction, phase)` after each
+/// step. A throwing callback cancels the run.
+fn run_reported<S: Stepped>(
+    work: &mut S,
+    token: &CancellationToken,
+    on_progress: Option<&js_sys::Function>,
+) -> Result<(), JsError> {
+    progressive::run_to_end(work, &token.inner, progress_reporter(token, on_progress))?;
+    Ok(())
+}
+
+/// Progress report for [`progressive::run_to_end`] calling
+/// `on_progress(fraction, phase)`. A throwing callback cancels `token`.
+fn progress_reporter<'a>(
+    token: &'a CancellationToken,
+    on_progress: Option<&'a js_sys::Function>,
+) -> impl FnMut(f32, &'static str) + 'a {
+    let token = &token.inner;
+    move |fraction, phase| {
+        let Some(callback) = on_progress else {
+            return;
+        };
+        let result = callback.call2(&JsValue::NULL, &fraction.into(), &phase.into());
+        if result.is_err() {
+            token.cancel();
+        }
+    }
+}
+
+/// A layout in progress, created by `beginLayout` and advanced by
+/// `layoutStep`.
+#[wasm_bindgen]
+pub struct LayoutTask {
+    inner: ProgressiveLayout,
+    /// Pinned nodes when the task began, held at those positions.
+    pinned: Vec<(usize, [f32; 2])>,
+}
+
+#[wasm_bindgen]
+impl LayoutTask {
+    /// Estimated fraction of the work done, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.inner.progress()
+    }
+
+    /// Current phase: `"setup"`, `"stress"`, `"communities"` or `"done"`.
+    pub fn phase(&self) -> String {
+        self.inner.phase().to_string()
+    }
+
+    /// Whether the layout has finished.
+    #[wasm_bindgen(js_name = isDone)]
+    pub fn is_done(&self) -> bool {
+        self.inner.is_done()
+    }
+
+    /// Latest positions as interleaved [x0, y0, x1, y1, ...]: intermediate
+    /// while a stress layout runs, final once done. Removed slots get
+    /// sentinel values (f32::MAX).
+    pub fn positions(&self) -> Float32Array {
+        let mut positions = self.inner.positions().to_vec();
+        hold_positions(&mut positions, &self.pinned);
+        Float32Array::from(&positions[..])
+    }
+}
+
+/// A tidy tree layout with per-node structure, returned by
+/// `computeTreeLayoutDetailed`.
+#[wasm_bindgen]
+pub struct TreeLayoutResult {
+    positions: Vec<f32>,
+    depths: Vec<u32>,
+    subtree_sizes: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl TreeLayoutResult {
+    /// Target positions as interleaved [x0, y0, x1, y1, ...]; nodes not in
+    /// the tree get sentinel values (f32::MAX).
+    pub fn positions(&self) -> Float32Array {
+        Float32Array::from(&self.positions[..])
+    }
+
+    /// Tree level per node slot (0 = root); u32::MAX for nodes not in the
+    /// tree.
+    pub fn depths(&self) -> Vec<u32> {
+        self.depths.clone()
+    }
+
+    /// Number of nodes in each slot's subtree, the node itself included; 0
+    /// for nodes not in the tree.
+    #[wasm_bindgen(js_name = subtreeSizes)]
+    pub fn subtree_sizes(&self) -> Vec<u32> {
+        self.subtree_sizes.clone()
+    }
+}
+
 #[cfg(test)]
 mod integration_tests {
     use super::*;
+    use layout::tidy_tree::{CoordinateMode, TidyTreeConfig};
 
     /// Test the full pipeline: engine → CSR → tidy tree layout
     /// This simulates exactly what computeTreeLayoutFromGraph does,
@@ -801,6 +3966,7 @@ mod integration_tests {
             sibling_separation: 1.0,
             subtree_separation: 2.0,
             coordinate_mode: CoordinateMode::Radial,
+            ..Default::default()
         };
         let layout = TidyTreeLayout::new(config);
         let result = layout.compute(node_bound, &edges, None);
@@ -812,10 +3978,9 @@ mod integration_tests {
         assert_eq!(result.node_count, 5, "All 5 nodes should be laid out");
 
         // Check that non-sentinel positions exist
-        let sentinel = 3.402_823e+38_f32;
         let non_sentinel: Vec<_> = result.positions_x.iter()
             .enumerate()
-            .filter(|&(_, x)| *x < sentinel)
+            .filter(|&(_, x)| *x < SENTINEL)
             .collect();
         assert_eq!(non_sentinel.len(), 5, "All 5 nodes should have non-sentinel x positions");
 
@@ -888,6 +4053,7 @@ mod integration_tests {
             sibling_separation: 1.0,
             subtree_separation: 2.0,
             coordinate_mode: CoordinateMode::Radial,
+            ..Default::default()
         });
         let result = layout.compute(node_bound, &edges, None);
         println!("Layout laid out {} of {} nodes", result.node_count, node_bound);
@@ -993,13 +4159,13 @@ mod integration_tests {
             sibling_separation: 1.0,
             subtree_separation: 2.0,
             coordinate_mode: CoordinateMode::Radial,
+            ..Default::default()
         });
         let result = layout.compute(node_bound, &edges, None);
         println!("Layout: {} nodes laid out of {} total", result.node_count, node_bound);
 
         // Check how many non-sentinel positions
-        let sentinel = 3.402_823e+38_f32;
-        let non_sentinel = result.positions_x.iter().filter(|&&x| x < sentinel).count();
+        let non_sentinel = result.positions_x.iter().filter(|&&x| x < SENTINEL).count();
         println!("Non-sentinel positions: {}", non_sentinel);
 
         // All connected nodes should be laid out
@@ -1007,6 +4173,26 @@ mod integration_tests {
             "Expected at least half the nodes laid out, got {}", result.node_count);
     }
 
+    #[test]
+    fn test_layouts_hold_pinned_nodes() {
+        let mut graph = HeroineGraphWasm::new();
+        graph.engine.add_node(0.0, 0.0);
+        let child = graph.engine.add_node(123.0, -45.0);
+        graph.engine.add_node(7.0, 7.0);
+        graph.engine.pin_node(child);
+
+        let edges = [0u32, 1, 0, 2];
+        let result = graph.tidy_tree_layout(&edges, TreeLayoutOptions::default());
+        assert_eq!(&result.positions[2..4], &[123.0, -45.0]);
+        // Unpinned nodes still get their layout targets
+        assert_eq!(&result.positions[..2], &[0.0, 0.0]);
+        assert_ne!(&result.positions[4..], &[7.0, 7.0]);
+
+        graph.engine.unpin_node(child);
+        let result = graph.tidy_tree_layout(&edges, TreeLayoutOptions::default());
+        assert_ne!(&result.positions[2..4], &[123.0, -45.0]);
+    }
+
     /// Test that clear() + reload works correctly.
     /// This replicates the mission control bug: first load 1000 nodes,
     /// then clear and reload 10000 nodes. Without the fix, next_node_id
@@ -1089,6 +4275,7 @@ mod integration_tests {
             sibling_separation: 1.0,
             subtree_separation: 2.0,
             coordinate_mode: CoordinateMode::Radial,
+            ..Default::default()
         });
         let result = layout.compute(node_bound, &edges_flat, None);
         println!("After reload: {} nodes laid out of {}", result.node_count, node_bound);
@@ -0,0 +1,399 @@
+//! Barnes–Hut approximation for repulsion forces.
+//!
+//! Builds a quadtree over node positions where every cell stores its total
+//! mass (node count, or summed strength for per-node strengths) and center
+//! of mass. Distant cells are treated as a
+//! single body, reducing repulsion from O(n²) to O(n log n).
+//!
+//! With the `threads` feature, per-node force evaluation runs in parallel on
+//! the rayon pool; the tree itself is built on the calling thread.
+
+/// Maximum quadtree depth. Coincident nodes stop subdividing here and are
+/// stored together in one leaf.
+const MAX_DEPTH: u32 = 24;
+
+/// Marker for "no body" / "no child".
+const NONE: u32 = u32::MAX;
+
+/// A quadtree cell.
+struct Cell {
+    /// Half the side length of the cell.
+    half_size: f32,
+    /// Total mass of the bodies in the cell (body count when unweighted).
+    mass: f32,
+    /// Center of mass X.
+    com_x: f32,
+    /// Center of mass Y.
+    com_y: f32,
+    /// Child cell indices (NONE when absent). All NONE for leaves.
+    children: [u32; 4],
+    /// Body index when the cell is a leaf holding exactly one body.
+    body: u32,
+}
+
+/// Quadtree with aggregated mass for Barnes–Hut force evaluation.
+pub struct QuadTree {
+    cells: Vec<Cell>,
+}
+
+impl QuadTree {
+    /// Build a quadtree over the given positions, each body with unit mass.
+    pub fn build(pos_x: &[f32], pos_y: &[f32]) -> Self {
+        Self::build_weighted(pos_x, pos_y, None)
+    }
+
+    /// Build a quadtree where body `i` has mass `masses[i]`.
+    ///
+    /// Cells store the total mass and the mass-weighted center. With `None`
+    /// every body has unit mass. Negative masses are treated as zero.
+    pub fn build_weighted(pos_x: &[f32], pos_y: &[f32], masses: Option<&[f32]>) -> Self {
+        let n = pos_x.len().min(pos_y.len());
+        let mut tree = Self { cells: Vec::with_capacity(n * 2) };
+        if n == 0 {
+            return tree;
+        }
+
+        let mut min_x = f32::INFINITY;
+        let mut min_y = f32::INFINITY;
+        let mut max_x = f32::NEG_INFINITY;
+        let mut max_y = f32::NEG_INFINITY;
+        for i in 0..n {
+            min_x = min_x.min(pos_x[i]);
+            min_y = min_y.min(pos_y[i]);
+            max_x = max_x.max(pos_x[i]);
+            max_y = max_y.max(pos_y[i]);
+        }
+
+        let half_size = ((max_x - min_x).max(max_y - min_y) / 2.0).max(f32::EPSILON);
+        let cx = (min_x + max_x) / 2.0;
+        let cy = (min_y + max_y) / 2.0;
+
+        let mut indices: Vec<u32> = (0..n as u32).collect();
+        let bodies = Bodies {
+            pos_x,
+            pos_y,
+            masses,
+        };
+        tree.build_cell(&mut indices, cx, cy, half_size, 0, &bodies);
+        tree
+    }
+
+    /// Recursively build the cell covering `indices`. Returns the cell index.
+    fn build_cell(
+        &mut self,
+        indices: &mut [u32],
+        cx: f32,
+        cy: f32,
+        half_size: f32,
+        depth: u32,
+        bodies: &Bodies,
+    ) -> u32 {
+        let (pos_x, pos_y) = (bodies.pos_x, bodies.pos_y);
+        let cell_idx = self.cells.len() as u32;
+        let (mass, com_x, com_y) = bodies.center_of_mass(indices);
+        self.cells.push(Cell {
+            half_size,
+            mass,
+            com_x,
+            com_y,
+            children: [NONE; 4],
+            body: if indices.len() == 1 { indices[0] } else { NONE },
+        });
+
+        if indices.len() <= 1 || depth >= MAX_DEPTH {
+            return cell_idx;
+        }
+
+        // Partition into quadrants: [x<cx,y<cy] [x>=cx,y<cy] [x<cx,y>=cy] [x>=cx,y>=cy]
+        let split_y = partition(indices, |i| pos_y[i as usize] < cy);
+        let (lower, upper) = indices.split_at_mut(split_y);
+        let split_lower = partition(lower, |i| pos_x[i as usize] < cx);
+        let split_upper = partition(upper, |i| pos_x[i as usize] < cx);
+        let (q0, q1) = lower.split_at_mut(split_lower);
+        let (q2, q3) = upper.split_at_mut(split_upper);
+
+        let quarter = half_size / 2.0;
+        let quadrants: [(&mut [u32], f32, f32); 4] = [
+            (q0, cx - quarter, cy - quarter),
+            (q1, cx + quarter, cy - quarter),
+            (q2, cx - quarter, cy + quarter),
+            (q3, cx + quarter, cy + quarter),
+        ];
+
+        for (slot, (members, qx, qy)) in quadrants.into_iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            let child = self.build_cell(members, qx, qy, quarter, depth + 1, bodies);
+            self.cells[cell_idx as usize].children[slot] = child;
+        }
+
+        cell_idx
+    }
+
+    /// Compute the approximate repulsion force on a body at `(x, y)`.
+    ///
+    /// `body` is the body's own index, used to skip self-interaction.
+    /// Cells whose size / distance ratio is below `theta` are approximated
+    /// by their center of mass.
+    pub fn force_on(
+        &self,
+        body: u32,
+        x: f32,
+        y: f32,
+        strength: f32,
+        min_distance: f32,
+        theta: f32,
+    ) -> (f32, f32) {
+        if self.cells.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let min_dist_sq = min_distance * min_distance;
+        let theta_sq = theta * theta;
+        let mut fx = 0.0f32;
+        let mut fy = 0.0f32;
+        let mut stack: Vec<u32> = vec![0];
+
+        while let Some(idx) = stack.pop() {
+            let cell = &self.cells[idx as usize];
+            if cell.body == body {
+                continue;
+            }
+
+            let dx = x - cell.com_x;
+            let dy = y - cell.com_y;
+            let dist_sq = dx * dx + dy * dy;
+            let size = cell.half_size * 2.0;
+            let is_leaf = cell.children.iter().all(|&c| c == NONE);
+
+            if is_leaf || size * size < theta_sq * dist_sq {
+                let scale = strength * cell.mass / dist_sq.max(min_dist_sq);
+                fx += dx * scale;
+                fy += dy * scale;
+                continue;
+            }
+
+            stack.extend(cell.children.iter().copied().filter(|&c| c != NONE));
+        }
+
+        (fx, fy)
+    }
+}
+
+/// Accumulate Barnes–Hut approximated repulsion forces.
+///
+/// Same force law as [`super::kernels::accumulate_repulsion`] (magnitude
+/// `strength / distance`), with cells approximated when their size divided by
+/// their distance is below `theta`. `theta = 0` reproduces the exact result.
+pub fn accumulate_repulsion_barnes_hut(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strength: f32,
+    min_distance: f32,
+    theta: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x.len().min(pos_y.len()).min(force_x.len()).min(force_y.len());
+    let tree = QuadTree::build(&pos_x[..n], &pos_y[..n]);
+    let force = |i: usize| tree.force_on(i as u32, pos_x[i], pos_y[i], strength, min_distance, theta);
+    apply_forces(n, force, force_x, force_y);
+}
+
+/// Accumulate Barnes–Hut approximated repulsion with per-node strengths.
+///
+/// Same force law as [`super::kernels::accumulate_weighted_repulsion`]: node
+/// `j` pushes every other node with magnitude `strengths[j] / distance`.
+/// Cells aggregate the strengths of their bodies.
+pub fn accumulate_weighted_repulsion_barnes_hut(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strengths: &[f32],
+    min_distance: f32,
+    theta: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x
+        .len()
+        .min(pos_y.len())
+        .min(strengths.len())
+        .min(force_x.len())
+        .min(force_y.len());
+    let tree = QuadTree::build_weighted(&pos_x[..n], &pos_y[..n], Some(&strengths[..n]));
+    let force = |i: usize| tree.force_on(i as u32, pos_x[i], pos_y[i], 1.0, min_distance, theta);
+    apply_forces(n, force, force_x, force_y);
+}
+
+/// Add `force(i)` to the accumulators of the first `n` nodes.
+fn apply_forces(
+    n: usize,
+    force: impl Fn(usize) -> (f32, f32) + Sync,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    #[cfg(feature = "threads")]
+    {
+        use rayon::prelude::*;
+        force_x[..n]
+            .par_iter_mut()
+            .zip(force_y[..n].par_iter_mut())
+            .enumerate()
+            .for_each(|(i, (fx, fy))| {
+                let (dx, dy) = force(i);
+                *fx += dx;
+                *fy += dy;
+            });
+    }
+    #[cfg(not(feature = "threads"))]
+    {
+        for i in 0..n {
+            let (dx, dy) = force(i);
+            force_x[i] += dx;
+            force_y[i] += dy;
+        }
+    }
+}
+
+/// Body positions and optional masses used while building the tree.
+struct Bodies<'a> {
+    pos_x: &'a [f32],
+    pos_y: &'a [f32],
+    masses: Option<&'a [f32]>,
+}
+
+impl Bodies<'_> {
+    /// Mass of body `i` (1 when unweighted, never negative).
+    #[inline]
+    fn mass(&self, i: usize) -> f32 {
+        self.masses.map_or(1.0, |m| m[i].max(0.0))
+    }
+
+    /// Total mass and mass-weighted center of a set of bodies.
+    ///
+    /// Falls back to the plain centroid when the total mass is zero.
+    fn center_of_mass(&self, indices: &[u32]) -> (f32, f32, f32) {
+        if indices.is_empty() {
+            return (0.0, 0.0, 0.0);
+        }
+        let (mut mass, mut sum_x, mut sum_y) = (0.0f32, 0.0f32, 0.0f32);
+        let (mut plain_x, mut plain_y) = (0.0f32, 0.0f32);
+        for &i in indices {
+            let i = i as usize;
+            let m = self.mass(i);
+            mass += m;
+            sum_x += self.pos_x[i] * m;
+            sum_y += self.pos_y[i] * m;
+            plain_x += self.pos_x[i];
+            plain_y += self.pos_y[i];
+        }
+        if mass > 0.0 {
+            (mass, sum_x / mass, sum_y / mass)
+        } else {
+            let count = indices.len() as f32;
+            (0.0, plain_x / count, plain_y / count)
+        }
+    }
+}
+
+/// In-place partition: moves elements satisfying `pred` to the front.
+/// Returns the number of such elements.
+fn partition(items: &mut [u32], pred: impl Fn(u32) -> bool) -> usize {
+    let mut split = 0;
+    for i in 0..items.len() {
+        if pred(items[i]) {
+            items.swap(i, split);
+            split += 1;
+        }
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::kernels::accumulate_repulsion_scalar;
+
+    fn scattered(n: usize) -> (Vec<f32>, Vec<f32>) {
+        let pos_x = (0..n).map(|i| (i as f32 * 12.9898).sin() * 400.0).collect();
+        let pos_y = (0..n).map(|i| (i as f32 * 78.233).cos() * 400.0).collect();
+        (pos_x, pos_y)
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let mut fx: [f32; 0] = [];
+        let mut fy: [f32; 0] = [];
+        accumulate_repulsion_barnes_hut(&[], &[], 1.0, 1.0, 0.5, &mut fx, &mut fy);
+    }
+
+    #[test]
+    fn test_theta_zero_matches_exact() {
+        let (pos_x, pos_y) = scattered(64);
+        let mut fx = vec![0.0; 64];
+        let mut fy = vec![0.0; 64];
+        accumulate_repulsion_barnes_hut(&pos_x, &pos_y, 50.0, 1.0, 0.0, &mut fx, &mut fy);
+
+        let mut ex = vec![0.0; 64];
+        let mut ey = vec![0.0; 64];
+        accumulate_repulsion_scalar(&pos_x, &pos_y, 50.0, 1.0, &mut ex, &mut ey);
+
+        for i in 0..64 {
+            assert!((fx[i] - ex[i]).abs() < 1e-3, "fx[{i}]: {} vs {}", fx[i], ex[i]);
+            assert!((fy[i] - ey[i]).abs() < 1e-3, "fy[{i}]: {} vs {}", fy[i], ey[i]);
+        }
+    }
+
+    #[test]
+    fn test_approximation_close_to_exact() {
+        let (pos_x, pos_y) = scattered(500);
+        let mut fx = vec![0.0; 500];
+        let mut fy = vec![0.0; 500];
+        accumulate_repulsion_barnes_hut(&pos_x, &pos_y, 50.0, 1.0, 0.5, &mut fx, &mut fy);
+
+        let mut ex = vec![0.0; 500];
+        let mut ey = vec![0.0; 500];
+        accumulate_repulsion_scalar(&pos_x, &pos_y, 50.0, 1.0, &mut ex, &mut ey);
+
+        let error: f32 = (0..500).map(|i| (fx[i] - ex[i]).hypot(fy[i] - ey[i])).sum();
+        let magnitude: f32 = (0..500).map(|i| ex[i].hypot(ey[i])).sum();
+        assert!(
+            error / magnitude < 0.05,
+            "Relative error too large: {}",
+            error / magnitude
+        );
+    }
+
+    #[test]
+    fn test_weighted_theta_zero_matches_exact() {
+        use crate::simulation::kernels::accumulate_weighted_repulsion_scalar;
+
+        let (pos_x, pos_y) = scattered(64);
+        let strengths: Vec<f32> = (0..64).map(|i| 10.0 + (i % 7) as f32 * 20.0).collect();
+        let mut fx = vec![0.0; 64];
+        let mut fy = vec![0.0; 64];
+        accumulate_weighted_repulsion_barnes_hut(
+            &pos_x, &pos_y, &strengths, 1.0, 0.0, &mut fx, &mut fy,
+        );
+
+        let mut ex = vec![0.0; 64];
+        let mut ey = vec![0.0; 64];
+        accumulate_weighted_repulsion_scalar(&pos_x, &pos_y, &strengths, 1.0, &mut ex, &mut ey);
+
+        for i in 0..64 {
+            assert!((fx[i] - ex[i]).abs() < 1e-2, "fx[{i}]: {} vs {}", fx[i], ex[i]);
+            assert!((fy[i] - ey[i]).abs() < 1e-2, "fy[{i}]: {} vs {}", fy[i], ey[i]);
+        }
+    }
+
+    #[test]
+    fn test_coincident_nodes_terminate() {
+        let pos_x = vec![1.0; 100];
+        let pos_y = vec![1.0; 100];
+        let mut fx = vec![0.0; 100];
+        let mut fy = vec![0.0; 100];
+        accumulate_repulsion_barnes_hut(&pos_x, &pos_y, 1.0, 1.0, 0.5, &mut fx, &mut fy);
+        assert!(fx.iter().chain(fy.iter()).all(|f| f.is_finite()));
+    }
+}
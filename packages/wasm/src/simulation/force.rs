@@ -0,0 +1,912 @@
+//! CPU force-directed simulation step.
+//!
+//! A tick operates on a compact [`SimulationFrame`] gathered by the engine:
+//! the nodes taking part in the tick ("participants") followed by "anchor"
+//! nodes that are only reachable through an edge. Participants repel each
+//! other and are pulled by springs; anchors hold their position and act as
+//! fixed spring endpoints. This lets a tick cover only part of the graph
+//! (e.g. the viewport) without the rest of it snapping inward.
+//!
+//! With adaptive freezing enabled, nodes whose per-tick displacement stays
+//! below a threshold for several ticks stop being integrated until one of
+//! their neighbors moves again.
+
+use std::collections::HashMap;
+
+use super::barnes_hut::{
+    accumulate_repulsion_barnes_hut, accumulate_weighted_repulsion_barnes_hut,
+};
+use super::kernels::{
+    accumulate_repulsion, accumulate_springs, accumulate_weighted_repulsion, split_csr,
+};
+
+/// Integration scheme for the CPU simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    /// Semi-implicit Euler. Cheap, but unstable at large time steps.
+    Euler,
+    /// Velocity Verlet. Second-order accurate, so it tolerates fewer,
+    /// larger steps.
+    Verlet,
+}
+
+/// Configuration for the CPU force simulation.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Repulsion constant (force magnitude is `repulsion / distance`).
+    pub repulsion: f32,
+    /// Distance below which repulsion stops growing.
+    pub min_distance: f32,
+    /// Natural edge length.
+    pub spring_length: f32,
+    /// Edge spring constant.
+    pub spring_stiffness: f32,
+    /// Pull toward the origin, proportional to distance.
+    pub gravity: f32,
+    /// Fraction of velocity removed each tick (0 = no friction, 1 = no inertia).
+    pub damping: f32,
+    /// Integration time step.
+    pub time_step: f32,
+    /// Barnes–Hut opening angle. 0 selects exact all-pairs repulsion.
+    pub theta: f32,
+    /// Per-tick displacement below which a node counts as at rest.
+    /// 0 disables adaptive freezing.
+    pub freeze_threshold: f32,
+    /// Consecutive resting ticks after which a node is frozen (0 counts as
+    /// 1, so only a node that rested for a tick freezes).
+    pub freeze_ticks: u32,
+    /// Integration scheme.
+    pub integrator: Integrator,
+    /// Maximum distance a node may move in one tick. 0 disables clamping.
+    pub max_displacement: f32,
+    /// Direction edges are biased to point in (source → target). Does not
+    /// need to be normalized; y grows downward in screen space.
+    pub magnetic_direction: (f32, f32),
+    /// Magnetic strength for edges without a per-type override.
+    pub magnetic_strength: f32,
+    /// Pull of grouped nodes toward their group's centroid, proportional to
+    /// distance. Groups are set with [`ForceSimulation::set_node_groups`].
+    pub group_attraction: f32,
+    /// Repulsion constant between group centroids (magnitude is
+    /// `group_separation * size / distance`, like node repulsion with
+    /// groups weighted by their size).
+    pub group_separation: f32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            repulsion: 200.0,
+            min_distance: 1.0,
+            spring_length: 30.0,
+            spring_stiffness: 0.05,
+            gravity: 0.02,
+            damping: 0.4,
+            time_step: 1.0,
+            theta: 0.5,
+            freeze_threshold: 0.0,
+            freeze_ticks: 30,
+            integrator: Integrator::Euler,
+            max_displacement: 0.0,
+            magnetic_direction: (0.0, 1.0),
+            magnetic_strength: 0.0,
+            group_attraction: 0.0,
+            group_separation: 0.0,
+        }
+    }
+}
+
+/// Axis-aligned region restricting which nodes are simulated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl Viewport {
+    /// Create a viewport from a rect grown by `margin` on every side.
+    pub fn with_margin(min_x: f32, min_y: f32, max_x: f32, max_y: f32, margin: f32) -> Self {
+        let margin = margin.max(0.0);
+        Self {
+            min_x: min_x.min(max_x) - margin,
+            min_y: min_y.min(max_y) - margin,
+            max_x: max_x.max(min_x) + margin,
+            max_y: max_y.max(min_y) + margin,
+        }
+    }
+
+    /// Check whether a point lies inside the viewport (edges inclusive).
+    #[inline]
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+}
+
+/// Compact simulation state for one tick.
+///
+/// Entries `0..participants` are simulated; the remaining entries are
+/// anchors. `slots` maps each entry back to the engine's node slot.
+#[derive(Debug, Default)]
+pub struct SimulationFrame {
+    /// Engine node slot of each entry.
+    pub slots: Vec<usize>,
+    /// Number of leading entries that take part in the tick.
+    pub participants: usize,
+    /// Whether each participant may move (false for pinned nodes).
+    pub movable: Vec<bool>,
+    pub pos_x: Vec<f32>,
+    pub pos_y: Vec<f32>,
+    pub vel_x: Vec<f32>,
+    pub vel_y: Vec<f32>,
+    /// Edges between entries in CSR format: [offsets...(len+1), targets...]
+    pub csr: Vec<u32>,
+    /// Stable edge id of each CSR target entry.
+    pub edge_ids: Vec<u32>,
+}
+
+/// CPU force simulation: configuration, the optional viewport and per-node
+/// rest tracking for adaptive freezing.
+#[derive(Debug, Clone, Default)]
+pub struct ForceSimulation {
+    pub config: SimulationConfig,
+    viewport: Option<Viewport>,
+    /// Consecutive resting ticks per node slot.
+    rest_ticks: Vec<u32>,
+    /// Position per node slot at the end of the previous tick, to catch
+    /// moves made between ticks (drags, `setNodePosition`).
+    last_positions: Vec<Option<(f32, f32)>>,
+    /// Per-slot repulsion strength overriding `config.repulsion`.
+    node_repulsion: Vec<f32>,
+    /// Per-slot damping overriding `config.damping`.
+    node_damping: Vec<f32>,
+    /// Force from the previous tick per slot, used by the Verlet integrator.
+    prev_force: Vec<Option<(f32, f32)>>,
+    /// Edge type per stable edge id (u32::MAX = untyped).
+    edge_types: Vec<u32>,
+    /// Magnetic strength per edge type, overriding `config.magnetic_strength`.
+    type_magnetism: Vec<f32>,
+    /// Group id per slot (u32::MAX = ungrouped).
+    node_groups: Vec<u32>,
+}
+
+impl ForceSimulation {
+    /// Create a simulation with the given configuration.
+    pub fn new(config: SimulationConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Restrict simulation to nodes inside `viewport`, or simulate every node
+    /// when `None`.
+    pub fn set_viewport(&mut self, viewport: Option<Viewport>) {
+        self.viewport = viewport;
+    }
+
+    /// The current viewport restriction.
+    pub fn viewport(&self) -> Option<Viewport> {
+        self.viewport
+    }
+
+    /// Set per-node repulsion strengths, indexed by node slot.
+    ///
+    /// Slots beyond the buffer or with non-finite values use
+    /// `config.repulsion`. An empty buffer restores the global constant.
+    pub fn set_node_repulsion(&mut self, strengths: Vec<f32>) {
+        self.node_repulsion = strengths;
+    }
+
+    /// Set per-node damping, indexed by node slot.
+    ///
+    /// Slots beyond the buffer or with non-finite values use
+    /// `config.damping`. An empty buffer restores the global constant.
+    pub fn set_node_damping(&mut self, damping: Vec<f32>) {
+        self.node_damping = damping;
+    }
+
+    /// Repulsion strength of a node slot.
+    fn repulsion_of(&self, slot: usize) -> f32 {
+        per_node(&self.node_repulsion, slot).unwrap_or(self.config.repulsion)
+    }
+
+    /// Damping of a node slot.
+    fn damping_of(&self, slot: usize) -> f32 {
+        per_node(&self.node_damping, slot).unwrap_or(self.config.damping)
+    }
+
+    /// Set the type of every edge, indexed by stable edge id.
+    ///
+    /// Types are small integers chosen by the caller (e.g. the index of the
+    /// edge's type name) and select a strength from
+    /// [`Self::set_type_magnetism`].
+    pub fn set_edge_types(&mut self, types: Vec<u32>) {
+        self.edge_types = types;
+    }
+
+    /// Set the magnetic strength per edge type.
+    ///
+    /// Types beyond the buffer or with non-finite values use
+    /// `config.magnetic_strength`.
+    pub fn set_type_magnetism(&mut self, strengths: Vec<f32>) {
+        self.type_magnetism = strengths;
+    }
+
+    /// Magnetic strength of an edge.
+    fn magnetism_of(&self, edge_id: u32) -> f32 {
+        self.edge_types
+            .get(edge_id as usize)
+            .and_then(|&edge_type| per_node(&self.type_magnetism, edge_type as usize))
+            .unwrap_or(self.config.magnetic_strength)
+    }
+
+    /// Whether any edge can have a non-zero magnetic strength.
+    fn has_magnetism(&self) -> bool {
+        self.config.magnetic_strength != 0.0 || self.type_magnetism.iter().any(|&k| k != 0.0)
+    }
+
+    /// Set the group of every node, indexed by node slot.
+    ///
+    /// Groups are arbitrary ids, typically community assignments. Slots
+    /// beyond the buffer or with `u32::MAX` are ungrouped and feel no group
+    /// forces. An empty buffer disables group forces.
+    pub fn set_node_groups(&mut self, groups: Vec<u32>) {
+        self.node_groups = groups;
+    }
+
+    /// Whether group forces are enabled.
+    fn has_groups(&self) -> bool {
+        !self.node_groups.is_empty()
+            && (self.config.group_attraction != 0.0 || self.config.group_separation != 0.0)
+    }
+
+    /// Check whether a node slot has been frozen by adaptive freezing.
+    ///
+    /// Frozen nodes still exert forces but are not integrated until they or
+    /// a neighbor move, including being moved between ticks, or
+    /// [`Self::wake_all`] is called.
+    pub fn is_frozen(&self, slot: usize) -> bool {
+        let freeze_ticks = self.config.freeze_ticks.max(1);
+        self.config.freeze_threshold > 0.0
+            && self.rest_ticks.get(slot).is_some_and(|&ticks| ticks >= freeze_ticks)
+    }
+
+    /// Number of node slots currently frozen.
+    pub fn frozen_count(&self) -> u32 {
+        (0..self.rest_ticks.len()).filter(|&slot| self.is_frozen(slot)).count() as u32
+    }
+
+    /// Unfreeze every node and reset rest tracking.
+    pub fn wake_all(&mut self) {
+        self.rest_ticks.clear();
+        self.last_positions.clear();
+    }
+
+    /// Advance the frame by one tick.
+    ///
+    /// Updates velocities and positions of movable participants in place and,
+    /// with adaptive freezing enabled, updates rest tracking. Returns the
+    /// number of entries that moved.
+    pub fn step(&mut self, frame: &mut SimulationFrame) -> u32 {
+        let (force_x, force_y) = self.accumulate_forces(frame);
+        let participants = frame.participants.min(frame.slots.len());
+        let mut displacement = vec![0.0f32; participants];
+        let mut moved = 0;
+
+        for i in 0..participants {
+            if !frame.movable.get(i).copied().unwrap_or(false) {
+                continue;
+            }
+            let (dx, dy) = match self.config.integrator {
+                Integrator::Euler => self.integrate_euler(frame, i, force_x[i], force_y[i]),
+                Integrator::Verlet => self.integrate_verlet(frame, i, force_x[i], force_y[i]),
+            };
+            frame.pos_x[i] += dx;
+            frame.pos_y[i] += dy;
+            displacement[i] = dx.hypot(dy);
+            moved += 1;
+        }
+
+        if self.config.freeze_threshold > 0.0 {
+            self.track_rest(frame, &displacement);
+        }
+        moved
+    }
+
+    /// Compute the total force on every frame entry.
+    ///
+    /// Forces on anchors are computed but never applied.
+    fn accumulate_forces(&self, frame: &SimulationFrame) -> (Vec<f32>, Vec<f32>) {
+        let config = &self.config;
+        let len = frame.slots.len();
+        let participants = frame.participants.min(len);
+        let mut force_x = vec![0.0f32; len];
+        let mut force_y = vec![0.0f32; len];
+
+        // Repulsion only among participants; anchors are outside the region
+        let (px, py) = (&frame.pos_x[..participants], &frame.pos_y[..participants]);
+        let (fx, fy) = (&mut force_x[..participants], &mut force_y[..participants]);
+        if !self.node_repulsion.is_empty() {
+            let strengths: Vec<f32> = frame.slots[..participants]
+                .iter()
+                .map(|&slot| self.repulsion_of(slot))
+                .collect();
+            if config.theta > 0.0 {
+                accumulate_weighted_repulsion_barnes_hut(
+                    px,
+                    py,
+                    &strengths,
+                    config.min_distance,
+                    config.theta,
+                    fx,
+                    fy,
+                );
+            } else {
+                accumulate_weighted_repulsion(px, py, &strengths, config.min_distance, fx, fy);
+            }
+        } else if config.theta > 0.0 {
+            accumulate_repulsion_barnes_hut(px, py, config.repulsion, config.min_distance, config.theta, fx, fy);
+        } else {
+            accumulate_repulsion(px, py, config.repulsion, config.min_distance, fx, fy);
+        }
+
+        accumulate_springs(
+            &frame.pos_x,
+            &frame.pos_y,
+            &frame.csr,
+            config.spring_length,
+            config.spring_stiffness,
+            &mut force_x,
+            &mut force_y,
+        );
+
+        if self.has_magnetism() {
+            self.accumulate_magnetic(frame, &mut force_x, &mut force_y);
+        }
+
+        if self.has_groups() {
+            self.accumulate_group_forces(frame, &mut force_x, &mut force_y);
+        }
+
+        for i in 0..participants {
+            force_x[i] -= frame.pos_x[i] * config.gravity;
+            force_y[i] -= frame.pos_y[i] * config.gravity;
+        }
+
+        (force_x, force_y)
+    }
+
+    /// Bias every edge to point along `config.magnetic_direction`.
+    ///
+    /// The target is pulled toward the point one edge length from the source
+    /// in the configured direction, and the source is pushed the opposite
+    /// way, so edges rotate into alignment without changing length. The
+    /// force is proportional to the edge's magnetic strength.
+    fn accumulate_magnetic(
+        &self,
+        frame: &SimulationFrame,
+        force_x: &mut [f32],
+        force_y: &mut [f32],
+    ) {
+        let (dir_x, dir_y) = self.config.magnetic_direction;
+        let dir_length = dir_x.hypot(dir_y);
+        if dir_length <= f32::EPSILON {
+            return;
+        }
+        let (dir_x, dir_y) = (dir_x / dir_length, dir_y / dir_length);
+
+        for (src, tgt, k) in csr_edges(&frame.csr, frame.slots.len()) {
+            let strength = frame
+                .edge_ids
+                .get(k)
+                .map_or(self.config.magnetic_strength, |&id| self.magnetism_of(id));
+            let dx = frame.pos_x[tgt] - frame.pos_x[src];
+            let dy = frame.pos_y[tgt] - frame.pos_y[src];
+            let length = dx.hypot(dy);
+            let fx = strength * (dir_x * length - dx);
+            let fy = strength * (dir_y * length - dy);
+            force_x[tgt] += fx;
+            force_y[tgt] += fy;
+            force_x[src] -= fx;
+            force_y[src] -= fy;
+        }
+    }
+
+    /// Pull grouped participants toward their group centroid and push group
+    /// centroids apart.
+    ///
+    /// Centroids are taken over the participants of each group. The
+    /// separation force between two groups acts on every member of each,
+    /// so groups move apart as a whole while their members stay free to
+    /// arrange themselves.
+    fn accumulate_group_forces(
+        &self,
+        frame: &SimulationFrame,
+        force_x: &mut [f32],
+        force_y: &mut [f32],
+    ) {
+        let participants = frame.participants.min(frame.slots.len());
+
+        // Dense index per group: (sum x, sum y, member count)
+        let mut index: HashMap<u32, usize> = HashMap::new();
+        let mut sums: Vec<(f32, f32, f32)> = Vec::new();
+        let mut member_of = vec![usize::MAX; participants];
+        for (i, member) in member_of.iter_mut().enumerate() {
+            let group = self
+                .node_groups
+                .get(frame.slots[i])
+                .copied()
+                .unwrap_or(u32::MAX);
+            if group == u32::MAX {
+                continue;
+            }
+            let g = *index.entry(group).or_insert_with(|| {
+                sums.push((0.0, 0.0, 0.0));
+                sums.len() - 1
+            });
+            sums[g].0 += frame.pos_x[i];
+            sums[g].1 += frame.pos_y[i];
+            sums[g].2 += 1.0;
+            *member = g;
+        }
+        let centroids: Vec<(f32, f32, f32)> = sums
+            .iter()
+            .map(|&(x, y, count)| (x / count, y / count, count))
+            .collect();
+
+        let push = group_push(
+            &centroids,
+            self.config.group_separation,
+            self.config.min_distance,
+        );
+
+        let attraction = self.config.group_attraction;
+        for (i, &g) in member_of
+            .iter()
+            .enumerate()
+            .filter(|&(_, &g)| g != usize::MAX)
+        {
+            let (cx, cy, _) = centroids[g];
+            force_x[i] += (cx - frame.pos_x[i]) * attraction + push[g].0;
+            force_y[i] += (cy - frame.pos_y[i]) * attraction + push[g].1;
+        }
+    }
+
+    /// Semi-implicit Euler: update velocity from the force, then move by the
+    /// new velocity. Returns the clamped displacement.
+    fn integrate_euler(
+        &self,
+        frame: &mut SimulationFrame,
+        i: usize,
+        fx: f32,
+        fy: f32,
+    ) -> (f32, f32) {
+        let dt = self.config.time_step;
+        let retain = 1.0 - self.damping_of(frame.slots[i]).clamp(0.0, 1.0);
+        frame.vel_x[i] = (frame.vel_x[i] + fx * dt) * retain;
+        frame.vel_y[i] = (frame.vel_y[i] + fy * dt) * retain;
+        self.clamp_displacement(frame.vel_x[i] * dt, frame.vel_y[i] * dt)
+    }
+
+    /// Velocity Verlet with one force evaluation per tick.
+    ///
+    /// The force of this tick is the one at the positions the previous
+    /// tick moved to, so the tick first finishes the previous step's
+    /// velocity update with the average of the previous and current force,
+    /// then moves by `v·dt + ½·a·dt²`. The current force is stored for the
+    /// next tick; the first tick has no step to finish and only moves.
+    fn integrate_verlet(
+        &mut self,
+        frame: &mut SimulationFrame,
+        i: usize,
+        fx: f32,
+        fy: f32,
+    ) -> (f32, f32) {
+        let dt = self.config.time_step;
+        let slot = frame.slots[i];
+        let retain = 1.0 - self.damping_of(slot).clamp(0.0, 1.0);
+        if self.prev_force.len() <= slot {
+            self.prev_force.resize(slot + 1, None);
+        }
+        if let Some((prev_x, prev_y)) = self.prev_force[slot] {
+            frame.vel_x[i] = (frame.vel_x[i] + 0.5 * (prev_x + fx) * dt) * retain;
+            frame.vel_y[i] = (frame.vel_y[i] + 0.5 * (prev_y + fy) * dt) * retain;
+        }
+        self.prev_force[slot] = Some((fx, fy));
+        self.clamp_displacement(
+            frame.vel_x[i] * dt + 0.5 * fx * dt * dt,
+            frame.vel_y[i] * dt + 0.5 * fy * dt * dt,
+        )
+    }
+
+    /// Limit a displacement to `config.max_displacement` (0 = unlimited).
+    fn clamp_displacement(&self, dx: f32, dy: f32) -> (f32, f32) {
+        let max = self.config.max_displacement;
+        let length = dx.hypot(dy);
+        if max <= 0.0 || length <= max {
+            return (dx, dy);
+        }
+        let scale = max / length;
+        (dx * scale, dy * scale)
+    }
+
+    /// Update rest counters from this tick's displacements.
+    ///
+    /// Movable nodes that stayed below the threshold accumulate rest ticks;
+    /// nodes that moved, whether integrated or moved since the previous
+    /// tick, reset theirs and wake every neighbor in the frame. A pinned
+    /// node being dragged thus wakes its neighbors, and a frozen node moved
+    /// from outside wakes itself.
+    fn track_rest(&mut self, frame: &SimulationFrame, displacement: &[f32]) {
+        let threshold = self.config.freeze_threshold;
+        let bound = frame.slots.iter().max().map_or(0, |&slot| slot + 1);
+        if self.rest_ticks.len() < bound {
+            self.rest_ticks.resize(bound, 0);
+        }
+
+        let moving: Vec<bool> = self
+            .travel(frame, displacement)
+            .iter()
+            .map(|&d| d >= threshold)
+            .collect();
+        for (entry, &is_moving) in moving.iter().enumerate() {
+            let ticks = &mut self.rest_ticks[frame.slots[entry]];
+            if is_moving {
+                *ticks = 0;
+            } else if frame.movable.get(entry).copied().unwrap_or(false) {
+                *ticks = ticks.saturating_add(1);
+            }
+        }
+
+        let is_moving = |entry: usize| moving.get(entry).copied().unwrap_or(false);
+        for (src, tgt, _) in csr_edges(&frame.csr, frame.slots.len()) {
+            if is_moving(src) || is_moving(tgt) {
+                self.rest_ticks[frame.slots[src]] = 0;
+                self.rest_ticks[frame.slots[tgt]] = 0;
+            }
+        }
+    }
+
+    /// Distance each frame entry covered since the end of the previous
+    /// tick: its integrated displacement, or how far it is from where the
+    /// previous tick left it if that is further. Records the current
+    /// positions for the next tick.
+    fn travel(&mut self, frame: &SimulationFrame, displacement: &[f32]) -> Vec<f32> {
+        let bound = self.rest_ticks.len();
+        if self.last_positions.len() < bound {
+            self.last_positions.resize(bound, None);
+        }
+        (0..frame.slots.len())
+            .map(|entry| {
+                let (x, y) = (frame.pos_x[entry], frame.pos_y[entry]);
+                let step = displacement.get(entry).copied().unwrap_or(0.0);
+                let last = self.last_positions[frame.slots[entry]].replace((x, y));
+                // The integrated step is part of the distance from `last`
+                let (dx, dy) = last.map_or((0.0, 0.0), |(lx, ly)| (x - lx, y - ly));
+                step.max(dx.hypot(dy))
+            })
+            .collect()
+    }
+}
+
+/// Iterate the edges of a frame CSR as (source, target, target entry index),
+/// skipping self loops and out-of-range targets.
+fn csr_edges(csr: &[u32], len: usize) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    let (offsets, targets) = split_csr(csr, len).unwrap_or((&[], &[]));
+    (0..offsets.len().saturating_sub(1))
+        .flat_map(move |src| {
+            let end = (offsets[src + 1] as usize).min(targets.len());
+            let start = (offsets[src] as usize).min(end);
+            (start..end).map(move |k| (src, targets[k] as usize, k))
+        })
+        .filter(move |&(src, tgt, _)| tgt < len && tgt != src)
+}
+
+/// Repulsion on each group from every other group's centroid, given
+/// centroids as (x, y, member count).
+fn group_push(
+    centroids: &[(f32, f32, f32)],
+    separation: f32,
+    min_distance: f32,
+) -> Vec<(f32, f32)> {
+    let mut push = vec![(0.0f32, 0.0f32); centroids.len()];
+    if separation == 0.0 {
+        return push;
+    }
+    let pairs = (0..centroids.len()).flat_map(|a| (a + 1..centroids.len()).map(move |b| (a, b)));
+    for (a, b) in pairs {
+        let (ax, ay, a_count) = centroids[a];
+        let (bx, by, b_count) = centroids[b];
+        let (dx, dy) = (ax - bx, ay - by);
+        let distance = dx.hypot(dy).max(min_distance);
+        let scale = separation / (distance * distance);
+        push[a].0 += dx * scale * b_count;
+        push[a].1 += dy * scale * b_count;
+        push[b].0 -= dx * scale * a_count;
+        push[b].1 -= dy * scale * a_count;
+    }
+    push
+}
+
+/// Finite per-node override at `slot`, if any.
+fn per_node(values: &[f32], slot: usize) -> Option<f32> {
+    values.get(slot).copied().filter(|v| v.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(pos: &[(f32, f32)], participants: usize, csr: Vec<u32>) -> SimulationFrame {
+        SimulationFrame {
+            slots: (0..pos.len()).collect(),
+            participants,
+            movable: vec![true; participants],
+            pos_x: pos.iter().map(|p| p.0).collect(),
+            pos_y: pos.iter().map(|p| p.1).collect(),
+            vel_x: vec![0.0; pos.len()],
+            vel_y: vec![0.0; pos.len()],
+            edge_ids: (0..csr.len().saturating_sub(pos.len() + 1) as u32).collect(),
+            csr,
+        }
+    }
+
+    fn no_gravity() -> SimulationConfig {
+        SimulationConfig { gravity: 0.0, theta: 0.0, ..Default::default() }
+    }
+
+    #[test]
+    fn test_unconnected_nodes_repel() {
+        let mut sim = ForceSimulation::new(no_gravity());
+        let mut f = frame(&[(-1.0, 0.0), (1.0, 0.0)], 2, vec![0, 0, 0]);
+
+        assert_eq!(sim.step(&mut f), 2);
+        assert!(f.pos_x[0] < -1.0);
+        assert!(f.pos_x[1] > 1.0);
+    }
+
+    #[test]
+    fn test_anchor_holds_and_pulls() {
+        let mut sim = ForceSimulation::new(no_gravity());
+        // Entry 1 is an anchor far away, connected by an edge 0 → 1
+        let mut f = frame(&[(0.0, 0.0), (500.0, 0.0)], 1, vec![0, 1, 1, 1]);
+
+        assert_eq!(sim.step(&mut f), 1);
+        assert!(f.pos_x[0] > 0.0, "Participant should be pulled toward anchor");
+        assert_eq!(f.pos_x[1], 500.0);
+    }
+
+    #[test]
+    fn test_immovable_participant_still_repels() {
+        let mut sim = ForceSimulation::new(no_gravity());
+        let mut f = frame(&[(0.0, 0.0), (2.0, 0.0)], 2, vec![0, 0, 0]);
+        f.movable[0] = false;
+
+        assert_eq!(sim.step(&mut f), 1);
+        assert_eq!(f.pos_x[0], 0.0);
+        assert!(f.pos_x[1] > 2.0);
+    }
+
+    #[test]
+    fn test_resting_nodes_freeze_and_wake() {
+        let config = SimulationConfig { freeze_threshold: 0.01, freeze_ticks: 2, ..no_gravity() };
+        let mut sim = ForceSimulation::new(config);
+        // Two nodes at rest length on a spring, far enough apart that repulsion is tiny
+        let mut f = frame(&[(0.0, 0.0), (30.0, 0.0)], 2, vec![0, 1, 1, 1]);
+        sim.config.repulsion = 0.0;
+
+        sim.step(&mut f);
+        assert!(!sim.is_frozen(0));
+        sim.step(&mut f);
+        assert!(sim.is_frozen(0) && sim.is_frozen(1));
+        assert_eq!(sim.frozen_count(), 2);
+
+        // Node 1 is dragged away; node 0 is frozen (not movable) but must wake
+        f.pos_x[1] = 100.0;
+        f.movable[0] = false;
+        sim.step(&mut f);
+        assert!(!sim.is_frozen(0), "Neighbor movement should wake the node");
+        assert!(!sim.is_frozen(1));
+    }
+
+    #[test]
+    fn test_zero_freeze_ticks_freeze_only_resting_nodes() {
+        let config = SimulationConfig {
+            freeze_threshold: 0.01,
+            freeze_ticks: 0,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        // Nodes 0 and 1 repel each other; node 2 is far from both
+        let mut f = frame(&[(0.0, 0.0), (2.0, 0.0), (1e6, 0.0)], 3, vec![0, 0, 0, 0]);
+
+        sim.step(&mut f);
+        assert!(!sim.is_frozen(0) && !sim.is_frozen(1));
+        assert!(sim.is_frozen(2));
+        let x = f.pos_x[1];
+        sim.step(&mut f);
+        assert!(f.pos_x[1] > x, "Moving nodes must keep being simulated");
+    }
+
+    #[test]
+    fn test_dragging_a_pinned_node_wakes_neighbors() {
+        let config = SimulationConfig {
+            freeze_threshold: 0.01,
+            freeze_ticks: 2,
+            repulsion: 0.0,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        // Node 1 is pinned; node 2 is isolated
+        let mut f = frame(&[(0.0, 0.0), (30.0, 0.0), (-500.0, 0.0)], 3, vec![0, 1, 1, 1, 1]);
+        f.movable[1] = false;
+        for _ in 0..3 {
+            sim.step(&mut f);
+        }
+        assert!(sim.is_frozen(0) && sim.is_frozen(2));
+
+        // The pinned node is dragged between ticks: it is never integrated,
+        // yet its frozen neighbor wakes
+        f.pos_x[1] = 100.0;
+        f.movable[0] = false;
+        f.movable[2] = false;
+        sim.step(&mut f);
+        assert!(!sim.is_frozen(0), "Dragging a pinned node should wake its neighbors");
+        assert!(sim.is_frozen(2));
+
+        // A frozen node moved from outside wakes itself
+        f.pos_x[2] = -400.0;
+        sim.step(&mut f);
+        assert!(!sim.is_frozen(2), "A frozen node moved from outside should wake");
+    }
+
+    #[test]
+    fn test_freezing_disabled_by_default() {
+        let mut sim = ForceSimulation::new(no_gravity());
+        let mut f = frame(&[(0.0, 0.0)], 1, vec![0, 0]);
+        for _ in 0..100 {
+            sim.step(&mut f);
+        }
+        assert_eq!(sim.frozen_count(), 0);
+    }
+
+    #[test]
+    fn test_per_node_repulsion_and_damping() {
+        let mut sim = ForceSimulation::new(no_gravity());
+        // Node 0 repels with 10x the strength of node 1
+        sim.set_node_repulsion(vec![1000.0, 100.0]);
+        let mut f = frame(&[(0.0, 0.0), (10.0, 0.0)], 2, vec![0, 0, 0]);
+        sim.step(&mut f);
+        let pushed_light = f.pos_x[1] - 10.0;
+        let pushed_heavy = -f.pos_x[0];
+        assert!(pushed_light > pushed_heavy * 5.0, "{pushed_light} vs {pushed_heavy}");
+
+        // Full damping on node 1 stops it dead; node 0 uses the global value
+        sim.set_node_damping(vec![f32::NAN, 1.0]);
+        sim.step(&mut f);
+        assert_eq!(f.vel_x[1], 0.0);
+        assert!(f.vel_x[0] < 0.0);
+    }
+
+    #[test]
+    fn test_verlet_stable_at_large_time_step() {
+        // An undamped spring stretched 10 past its rest length with a large
+        // step and no clamping: Euler overshoots further every swing,
+        // Verlet keeps swinging within the starting stretch.
+        let run = |integrator| {
+            let config = SimulationConfig {
+                repulsion: 0.0,
+                spring_stiffness: 0.45,
+                damping: 0.0,
+                time_step: 2.0,
+                integrator,
+                ..no_gravity()
+            };
+            let mut sim = ForceSimulation::new(config);
+            let mut f = frame(&[(0.0, 0.0), (40.0, 0.0)], 2, vec![0, 1, 1, 1]);
+            let mut stretch = 0.0f32;
+            for _ in 0..200 {
+                sim.step(&mut f);
+                stretch = stretch.max(((f.pos_x[1] - f.pos_x[0]).abs() - 30.0).abs());
+            }
+            stretch
+        };
+
+        let euler = run(Integrator::Euler);
+        let verlet = run(Integrator::Verlet);
+        assert!(euler > 20.0, "Euler expected to overshoot, got {euler}");
+        assert!(verlet < 10.1, "Verlet overshot: {verlet}");
+    }
+
+    #[test]
+    fn test_displacement_clamped() {
+        let config = SimulationConfig {
+            max_displacement: 0.5,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        let mut f = frame(&[(0.0, 0.0), (0.1, 0.0)], 2, vec![0, 0, 0]);
+
+        sim.step(&mut f);
+        assert!((f.pos_x[0] + 0.5).abs() < 1e-5, "got {}", f.pos_x[0]);
+        assert!((f.pos_x[1] - 0.6).abs() < 1e-5, "got {}", f.pos_x[1]);
+    }
+
+    #[test]
+    fn test_magnetic_force_rotates_edges() {
+        let config = SimulationConfig {
+            repulsion: 0.0,
+            spring_stiffness: 0.0,
+            magnetic_strength: 0.2,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        // Horizontal edge 0 → 1; magnetism points it downward (+y)
+        let mut f = frame(&[(0.0, 0.0), (10.0, 0.0)], 2, vec![0, 1, 1, 1]);
+        for _ in 0..50 {
+            sim.step(&mut f);
+        }
+        let dx = f.pos_x[1] - f.pos_x[0];
+        let dy = f.pos_y[1] - f.pos_y[0];
+        assert!(
+            dy > 9.0 && dx.abs() < 1.0,
+            "Edge should point down, got ({dx}, {dy})"
+        );
+    }
+
+    #[test]
+    fn test_magnetism_per_edge_type() {
+        let config = SimulationConfig {
+            repulsion: 0.0,
+            spring_stiffness: 0.0,
+            magnetic_strength: 0.2,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        // Edge id 0 has type 1, whose strength is zero
+        sim.set_edge_types(vec![1]);
+        sim.set_type_magnetism(vec![0.2, 0.0]);
+        let mut f = frame(&[(0.0, 0.0), (10.0, 0.0)], 2, vec![0, 1, 1, 1]);
+        sim.step(&mut f);
+        assert_eq!(f.pos_y, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_group_forces_cluster_members() {
+        let config = SimulationConfig {
+            repulsion: 0.0,
+            group_attraction: 0.1,
+            group_separation: 500.0,
+            ..no_gravity()
+        };
+        let mut sim = ForceSimulation::new(config);
+        // Groups interleaved along the x axis; node 4 is ungrouped
+        sim.set_node_groups(vec![0, 1, 0, 1, u32::MAX]);
+        let pos = [
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (20.0, 0.0),
+            (30.0, 0.0),
+            (5.0, 5.0),
+        ];
+        let mut f = frame(&pos, 5, vec![0; 6]);
+        for _ in 0..100 {
+            sim.step(&mut f);
+        }
+
+        let spread = |a: usize, b: usize| (f.pos_x[a] - f.pos_x[b]).abs();
+        assert!(spread(0, 2) < 5.0, "Group 0 should contract");
+        assert!(spread(1, 3) < 5.0, "Group 1 should contract");
+        assert!(spread(0, 1) > 20.0, "Groups should separate");
+        assert_eq!((f.pos_x[4], f.pos_y[4]), (5.0, 5.0));
+    }
+
+    #[test]
+    fn test_viewport_margin() {
+        let viewport = Viewport::with_margin(10.0, 10.0, 0.0, 0.0, 5.0);
+        assert!(viewport.contains(-5.0, 15.0));
+        assert!(!viewport.contains(-5.1, 0.0));
+    }
+}
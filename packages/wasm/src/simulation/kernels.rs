@@ -0,0 +1,400 @@
+//! Force and distance kernels over SoA position buffers.
+//!
+//! Each public kernel dispatches to the `simd128` implementation when the
+//! crate is built with the `simd` feature for a wasm target that supports it,
+//! and to the scalar implementation otherwise. Both implementations compute
+//! the same quantities; only floating-point summation order differs.
+//!
+//! All kernels *accumulate* into the force buffers, so callers can combine
+//! several force passes before integrating.
+
+/// Accumulate all-pairs repulsion forces.
+///
+/// Each node is pushed away from every other node with magnitude
+/// `strength / distance`. Distances below `min_distance` are clamped to avoid
+/// singularities for coincident nodes.
+///
+/// # Arguments
+///
+/// * `pos_x`, `pos_y` - Node positions (one entry per node slot)
+/// * `strength` - Repulsion constant
+/// * `min_distance` - Softening distance
+/// * `force_x`, `force_y` - Force accumulators (same length as positions)
+pub fn accumulate_repulsion(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strength: f32,
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        super::simd::accumulate_repulsion(pos_x, pos_y, strength, min_distance, force_x, force_y);
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+    {
+        accumulate_repulsion_scalar(pos_x, pos_y, strength, min_distance, force_x, force_y);
+    }
+}
+
+/// Accumulate all-pairs repulsion with a per-node strength.
+///
+/// Node `j` pushes every other node with magnitude `strengths[j] / distance`,
+/// so high-strength nodes (e.g. hubs) clear more space around themselves.
+///
+/// # Arguments
+///
+/// * `pos_x`, `pos_y` - Node positions (one entry per node slot)
+/// * `strengths` - Repulsion constant per node
+/// * `min_distance` - Softening distance
+/// * `force_x`, `force_y` - Force accumulators (same length as positions)
+pub fn accumulate_weighted_repulsion(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strengths: &[f32],
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        super::simd::accumulate_weighted_repulsion(
+            pos_x,
+            pos_y,
+            strengths,
+            min_distance,
+            force_x,
+            force_y,
+        );
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+    {
+        accumulate_weighted_repulsion_scalar(
+            pos_x,
+            pos_y,
+            strengths,
+            min_distance,
+            force_x,
+            force_y,
+        );
+    }
+}
+
+/// Accumulate Hooke spring forces along every edge of a CSR graph.
+///
+/// Each edge pulls (or pushes) its endpoints towards `rest_length` with a
+/// force of `stiffness * (distance - rest_length)`.
+///
+/// # Arguments
+///
+/// * `pos_x`, `pos_y` - Node positions (one entry per node slot)
+/// * `csr` - Edges in CSR format: [offsets...(node_count+1), targets...]
+/// * `rest_length` - Natural spring length
+/// * `stiffness` - Spring constant
+/// * `force_x`, `force_y` - Force accumulators (same length as positions)
+pub fn accumulate_springs(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    csr: &[u32],
+    rest_length: f32,
+    stiffness: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        super::simd::accumulate_springs(pos_x, pos_y, csr, rest_length, stiffness, force_x, force_y);
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+    {
+        accumulate_springs_scalar(pos_x, pos_y, csr, rest_length, stiffness, force_x, force_y);
+    }
+}
+
+/// Compute the Euclidean distance from `(x, y)` to every node.
+///
+/// Writes one distance per node slot into `out`.
+pub fn distances_from(x: f32, y: f32, pos_x: &[f32], pos_y: &[f32], out: &mut [f32]) {
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    {
+        super::simd::distances_from(x, y, pos_x, pos_y, out);
+    }
+    #[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+    {
+        distances_from_scalar(x, y, pos_x, pos_y, out);
+    }
+}
+
+/// Split a CSR buffer into (offsets, targets) for `node_count` nodes.
+///
+/// Returns `None` when the buffer holds no edges.
+pub(crate) fn split_csr(csr: &[u32], node_count: usize) -> Option<(&[u32], &[u32])> {
+    if csr.len() <= node_count + 1 {
+        return None;
+    }
+    Some((&csr[..node_count + 1], &csr[node_count + 1..]))
+}
+
+/// Scalar implementation of [`accumulate_repulsion`].
+#[cfg_attr(
+    all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"),
+    allow(dead_code)
+)]
+pub(crate) fn accumulate_repulsion_scalar(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strength: f32,
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x.len().min(pos_y.len()).min(force_x.len()).min(force_y.len());
+    let min_dist_sq = min_distance * min_distance;
+
+    for i in 0..n {
+        let xi = pos_x[i];
+        let yi = pos_y[i];
+        let mut fx = 0.0f32;
+        let mut fy = 0.0f32;
+
+        for j in 0..n {
+            // Self-interaction contributes dx = dy = 0, so no branch is needed
+            let dx = xi - pos_x[j];
+            let dy = yi - pos_y[j];
+            let dist_sq = (dx * dx + dy * dy).max(min_dist_sq);
+            let scale = strength / dist_sq;
+            fx += dx * scale;
+            fy += dy * scale;
+        }
+
+        force_x[i] += fx;
+        force_y[i] += fy;
+    }
+}
+
+/// Scalar implementation of [`accumulate_weighted_repulsion`].
+#[cfg_attr(
+    all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"),
+    allow(dead_code)
+)]
+pub(crate) fn accumulate_weighted_repulsion_scalar(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strengths: &[f32],
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x
+        .len()
+        .min(pos_y.len())
+        .min(strengths.len())
+        .min(force_x.len())
+        .min(force_y.len());
+    let min_dist_sq = min_distance * min_distance;
+
+    for i in 0..n {
+        let xi = pos_x[i];
+        let yi = pos_y[i];
+        let mut fx = 0.0f32;
+        let mut fy = 0.0f32;
+
+        for j in 0..n {
+            let dx = xi - pos_x[j];
+            let dy = yi - pos_y[j];
+            let dist_sq = (dx * dx + dy * dy).max(min_dist_sq);
+            let scale = strengths[j] / dist_sq;
+            fx += dx * scale;
+            fy += dy * scale;
+        }
+
+        force_x[i] += fx;
+        force_y[i] += fy;
+    }
+}
+
+/// Scalar implementation of [`accumulate_springs`].
+#[cfg_attr(
+    all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"),
+    allow(dead_code)
+)]
+pub(crate) fn accumulate_springs_scalar(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    csr: &[u32],
+    rest_length: f32,
+    stiffness: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x.len().min(pos_y.len()).min(force_x.len()).min(force_y.len());
+    let Some((offsets, targets)) = split_csr(csr, n) else {
+        return;
+    };
+
+    for src in 0..n {
+        let start = offsets[src] as usize;
+        let end = (offsets[src + 1] as usize).min(targets.len());
+
+        for &tgt in &targets[start.min(end)..end] {
+            let tgt = tgt as usize;
+            if tgt >= n || tgt == src {
+                continue;
+            }
+            let dx = pos_x[tgt] - pos_x[src];
+            let dy = pos_y[tgt] - pos_y[src];
+            let dist = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+            let scale = stiffness * (dist - rest_length) / dist;
+
+            force_x[src] += dx * scale;
+            force_y[src] += dy * scale;
+            force_x[tgt] -= dx * scale;
+            force_y[tgt] -= dy * scale;
+        }
+    }
+}
+
+/// Scalar implementation of [`distances_from`].
+#[cfg_attr(
+    all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"),
+    allow(dead_code)
+)]
+pub(crate) fn distances_from_scalar(x: f32, y: f32, pos_x: &[f32], pos_y: &[f32], out: &mut [f32]) {
+    let n = pos_x.len().min(pos_y.len()).min(out.len());
+    for i in 0..n {
+        let dx = pos_x[i] - x;
+        let dy = pos_y[i] - y;
+        out[i] = (dx * dx + dy * dy).sqrt();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a CSR buffer from edge pairs.
+    fn build_csr(node_count: usize, edges: &[(u32, u32)]) -> Vec<u32> {
+        let mut offsets = vec![0u32; node_count + 1];
+        for &(src, _) in edges {
+            offsets[src as usize + 1] += 1;
+        }
+        for i in 1..=node_count {
+            offsets[i] += offsets[i - 1];
+        }
+        let mut targets = vec![0u32; edges.len()];
+        let mut current = offsets[..node_count].to_vec();
+        for &(src, tgt) in edges {
+            targets[current[src as usize] as usize] = tgt;
+            current[src as usize] += 1;
+        }
+        offsets.extend(targets);
+        offsets
+    }
+
+    #[test]
+    fn test_repulsion_pushes_apart() {
+        let pos_x = [0.0, 10.0];
+        let pos_y = [0.0, 0.0];
+        let mut fx = [0.0; 2];
+        let mut fy = [0.0; 2];
+
+        accumulate_repulsion(&pos_x, &pos_y, 100.0, 1.0, &mut fx, &mut fy);
+
+        assert!(fx[0] < 0.0, "Left node should be pushed left, got {}", fx[0]);
+        assert!(fx[1] > 0.0, "Right node should be pushed right, got {}", fx[1]);
+        assert!((fx[0] + fx[1]).abs() < 1e-5, "Forces should be equal and opposite");
+        // Magnitude = strength / distance
+        assert!((fx[1] - 10.0).abs() < 1e-4, "Expected magnitude 10, got {}", fx[1]);
+    }
+
+    #[test]
+    fn test_repulsion_coincident_nodes_finite() {
+        let pos_x = [5.0, 5.0, 5.0];
+        let pos_y = [5.0, 5.0, 5.0];
+        let mut fx = [0.0; 3];
+        let mut fy = [0.0; 3];
+
+        accumulate_repulsion(&pos_x, &pos_y, 1.0, 0.5, &mut fx, &mut fy);
+
+        assert!(fx.iter().chain(fy.iter()).all(|f| f.is_finite()));
+    }
+
+    #[test]
+    fn test_weighted_repulsion_uses_source_strength() {
+        let pos_x = [0.0, 10.0];
+        let pos_y = [0.0, 0.0];
+        let strengths = [300.0, 100.0];
+        let mut fx = [0.0; 2];
+        let mut fy = [0.0; 2];
+
+        accumulate_weighted_repulsion(&pos_x, &pos_y, &strengths, 1.0, &mut fx, &mut fy);
+
+        // Node 0 feels node 1's strength, node 1 feels node 0's
+        assert!((fx[0] + 10.0).abs() < 1e-4, "got {}", fx[0]);
+        assert!((fx[1] - 30.0).abs() < 1e-4, "got {}", fx[1]);
+    }
+
+    #[test]
+    fn test_springs_pull_toward_rest_length() {
+        let pos_x = [0.0, 20.0];
+        let pos_y = [0.0, 0.0];
+        let csr = build_csr(2, &[(0, 1)]);
+        let mut fx = [0.0; 2];
+        let mut fy = [0.0; 2];
+
+        accumulate_springs(&pos_x, &pos_y, &csr, 10.0, 0.5, &mut fx, &mut fy);
+
+        // Stretched by 10 with stiffness 0.5 → magnitude 5, pulling together
+        assert!((fx[0] - 5.0).abs() < 1e-5, "got {}", fx[0]);
+        assert!((fx[1] + 5.0).abs() < 1e-5, "got {}", fx[1]);
+        assert_eq!(fy, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_springs_without_edges_is_noop() {
+        let pos_x = [0.0, 1.0];
+        let pos_y = [0.0, 1.0];
+        let csr = build_csr(2, &[]);
+        let mut fx = [0.0; 2];
+        let mut fy = [0.0; 2];
+
+        accumulate_springs(&pos_x, &pos_y, &csr, 10.0, 1.0, &mut fx, &mut fy);
+        assert_eq!(fx, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_distances_from() {
+        let pos_x = [3.0, 0.0, -1.0, 0.0, 6.0];
+        let pos_y = [4.0, 0.0, 0.0, 2.0, 8.0];
+        let mut out = [0.0; 5];
+
+        distances_from(0.0, 0.0, &pos_x, &pos_y, &mut out);
+        assert_eq!(out, [5.0, 0.0, 1.0, 2.0, 10.0]);
+    }
+
+    #[test]
+    fn test_dispatch_matches_scalar() {
+        // Odd node count exercises the SIMD remainder path on wasm builds
+        let n = 11;
+        let pos_x: Vec<f32> = (0..n).map(|i| (i as f32 * 1.7).sin() * 50.0).collect();
+        let pos_y: Vec<f32> = (0..n).map(|i| (i as f32 * 0.9).cos() * 50.0).collect();
+        let edges: Vec<(u32, u32)> = (1..n as u32).map(|i| ((i - 1) / 2, i)).collect();
+        let csr = build_csr(n, &edges);
+
+        let mut fx = vec![0.0; n];
+        let mut fy = vec![0.0; n];
+        accumulate_repulsion(&pos_x, &pos_y, 30.0, 1.0, &mut fx, &mut fy);
+        accumulate_springs(&pos_x, &pos_y, &csr, 5.0, 0.1, &mut fx, &mut fy);
+
+        let mut sx = vec![0.0; n];
+        let mut sy = vec![0.0; n];
+        accumulate_repulsion_scalar(&pos_x, &pos_y, 30.0, 1.0, &mut sx, &mut sy);
+        accumulate_springs_scalar(&pos_x, &pos_y, &csr, 5.0, 0.1, &mut sx, &mut sy);
+
+        for i in 0..n {
+            assert!((fx[i] - sx[i]).abs() < 1e-3, "fx[{i}]: {} vs {}", fx[i], sx[i]);
+            assert!((fy[i] - sy[i]).abs() < 1e-3, "fy[{i}]: {} vs {}", fy[i], sy[i]);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! CPU force simulation.
+//!
+//! This module provides the tick-based simulation (`force`) and the kernels
+//! it is built from, which operate directly on SoA position buffers. The
+//! kernels have a scalar implementation that runs everywhere and, with the
+//! `simd` feature on a `simd128` wasm target, a vectorized implementation
+//! that processes four nodes per instruction. Barnes–Hut repulsion
+//! parallelizes across the rayon pool with the `threads` feature. The target
+//! pass eases nodes toward positions computed by the layout algorithms, and
+//! transitions animate them there over a fixed duration.
+
+pub mod barnes_hut;
+pub mod force;
+pub mod kernels;
+pub mod target;
+pub mod transition;
+
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
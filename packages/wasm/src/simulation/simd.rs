@@ -0,0 +1,243 @@
+//! `simd128` implementations of the simulation kernels.
+//!
+//! Only compiled for wasm32 targets built with `-C target-feature=+simd128`
+//! and the `simd` crate feature. Each kernel processes four nodes per lane
+//! group and handles the remainder with scalar code, so results match the
+//! scalar kernels up to floating-point summation order.
+
+use core::arch::wasm32::{
+    f32x4,
+    f32x4_add,
+    f32x4_div,
+    f32x4_extract_lane,
+    f32x4_max,
+    f32x4_mul,
+    f32x4_splat,
+    f32x4_sqrt,
+    f32x4_sub,
+    v128,
+    v128_load,
+    v128_store,
+};
+
+use super::kernels::split_csr;
+
+/// Load four consecutive floats starting at `slice[i]`.
+///
+/// Caller guarantees `i + 4 <= slice.len()`.
+#[inline]
+fn load4(slice: &[f32], i: usize) -> v128 {
+    debug_assert!(i + 4 <= slice.len());
+    // SAFETY: bounds are checked by the caller; wasm loads allow unaligned access.
+    unsafe { v128_load(slice.as_ptr().add(i) as *const v128) }
+}
+
+/// Sum the four lanes of a vector.
+#[inline]
+fn horizontal_sum(v: v128) -> f32 {
+    f32x4_extract_lane::<0>(v)
+        + f32x4_extract_lane::<1>(v)
+        + f32x4_extract_lane::<2>(v)
+        + f32x4_extract_lane::<3>(v)
+}
+
+/// Vectorized all-pairs repulsion (see [`super::kernels::accumulate_repulsion`]).
+pub(super) fn accumulate_repulsion(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strength: f32,
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x.len().min(pos_y.len()).min(force_x.len()).min(force_y.len());
+    let vector_end = n - n % 4;
+    let min_dist_sq = min_distance * min_distance;
+    let min_dist_sq_v = f32x4_splat(min_dist_sq);
+    let strength_v = f32x4_splat(strength);
+
+    for i in 0..n {
+        let xi = f32x4_splat(pos_x[i]);
+        let yi = f32x4_splat(pos_y[i]);
+        let mut acc_x = f32x4_splat(0.0);
+        let mut acc_y = f32x4_splat(0.0);
+
+        for j in (0..vector_end).step_by(4) {
+            let dx = f32x4_sub(xi, load4(pos_x, j));
+            let dy = f32x4_sub(yi, load4(pos_y, j));
+            let dist_sq = f32x4_max(f32x4_add(f32x4_mul(dx, dx), f32x4_mul(dy, dy)), min_dist_sq_v);
+            let scale = f32x4_div(strength_v, dist_sq);
+            acc_x = f32x4_add(acc_x, f32x4_mul(dx, scale));
+            acc_y = f32x4_add(acc_y, f32x4_mul(dy, scale));
+        }
+
+        let mut fx = horizontal_sum(acc_x);
+        let mut fy = horizontal_sum(acc_y);
+
+        for j in vector_end..n {
+            let dx = pos_x[i] - pos_x[j];
+            let dy = pos_y[i] - pos_y[j];
+            let dist_sq = (dx * dx + dy * dy).max(min_dist_sq);
+            let scale = strength / dist_sq;
+            fx += dx * scale;
+            fy += dy * scale;
+        }
+
+        force_x[i] += fx;
+        force_y[i] += fy;
+    }
+}
+
+/// Vectorized per-node strength repulsion
+/// (see [`super::kernels::accumulate_weighted_repulsion`]).
+pub(super) fn accumulate_weighted_repulsion(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    strengths: &[f32],
+    min_distance: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x
+        .len()
+        .min(pos_y.len())
+        .min(strengths.len())
+        .min(force_x.len())
+        .min(force_y.len());
+    let vector_end = n - n % 4;
+    let min_dist_sq = min_distance * min_distance;
+    let min_dist_sq_v = f32x4_splat(min_dist_sq);
+
+    for i in 0..n {
+        let xi = f32x4_splat(pos_x[i]);
+        let yi = f32x4_splat(pos_y[i]);
+        let mut acc_x = f32x4_splat(0.0);
+        let mut acc_y = f32x4_splat(0.0);
+
+        for j in (0..vector_end).step_by(4) {
+            let dx = f32x4_sub(xi, load4(pos_x, j));
+            let dy = f32x4_sub(yi, load4(pos_y, j));
+            let dist_sq = f32x4_max(f32x4_add(f32x4_mul(dx, dx), f32x4_mul(dy, dy)), min_dist_sq_v);
+            let scale = f32x4_div(load4(strengths, j), dist_sq);
+            acc_x = f32x4_add(acc_x, f32x4_mul(dx, scale));
+            acc_y = f32x4_add(acc_y, f32x4_mul(dy, scale));
+        }
+
+        let mut fx = horizontal_sum(acc_x);
+        let mut fy = horizontal_sum(acc_y);
+
+        for j in vector_end..n {
+            let dx = pos_x[i] - pos_x[j];
+            let dy = pos_y[i] - pos_y[j];
+            let dist_sq = (dx * dx + dy * dy).max(min_dist_sq);
+            let scale = strengths[j] / dist_sq;
+            fx += dx * scale;
+            fy += dy * scale;
+        }
+
+        force_x[i] += fx;
+        force_y[i] += fy;
+    }
+}
+
+/// Vectorized spring forces (see [`super::kernels::accumulate_springs`]).
+///
+/// Each source node's targets are gathered four at a time; the per-lane
+/// forces are summed into the source and scattered back to the targets.
+pub(super) fn accumulate_springs(
+    pos_x: &[f32],
+    pos_y: &[f32],
+    csr: &[u32],
+    rest_length: f32,
+    stiffness: f32,
+    force_x: &mut [f32],
+    force_y: &mut [f32],
+) {
+    let n = pos_x.len().min(pos_y.len()).min(force_x.len()).min(force_y.len());
+    let Some((offsets, targets)) = split_csr(csr, n) else {
+        return;
+    };
+
+    let rest_v = f32x4_splat(rest_length);
+    let stiffness_v = f32x4_splat(stiffness);
+    let epsilon_v = f32x4_splat(f32::EPSILON);
+    // Targets of the current source, reused so the loop never allocates
+    let mut valid: Vec<usize> = Vec::new();
+
+    for src in 0..n {
+        let start = offsets[src] as usize;
+        let end = (offsets[src + 1] as usize).min(targets.len());
+        // Drop out-of-range and self edges up front so lanes never need masking
+        valid.clear();
+        valid.extend(
+            targets[start.min(end)..end]
+                .iter()
+                .map(|&t| t as usize)
+                .filter(|&t| t < n && t != src),
+        );
+
+        let sx = f32x4_splat(pos_x[src]);
+        let sy = f32x4_splat(pos_y[src]);
+        let chunks = valid.chunks_exact(4);
+        let remainder = chunks.remainder();
+
+        for lane in chunks {
+            let tx = f32x4(pos_x[lane[0]], pos_x[lane[1]], pos_x[lane[2]], pos_x[lane[3]]);
+            let ty = f32x4(pos_y[lane[0]], pos_y[lane[1]], pos_y[lane[2]], pos_y[lane[3]]);
+            let dx = f32x4_sub(tx, sx);
+            let dy = f32x4_sub(ty, sy);
+            let dist = f32x4_max(f32x4_sqrt(f32x4_add(f32x4_mul(dx, dx), f32x4_mul(dy, dy))), epsilon_v);
+            let scale = f32x4_div(f32x4_mul(stiffness_v, f32x4_sub(dist, rest_v)), dist);
+            let fx = f32x4_mul(dx, scale);
+            let fy = f32x4_mul(dy, scale);
+
+            force_x[src] += horizontal_sum(fx);
+            force_y[src] += horizontal_sum(fy);
+
+            let mut lane_fx = [0.0f32; 4];
+            let mut lane_fy = [0.0f32; 4];
+            // SAFETY: each array holds exactly four f32 lanes.
+            unsafe {
+                v128_store(lane_fx.as_mut_ptr() as *mut v128, fx);
+                v128_store(lane_fy.as_mut_ptr() as *mut v128, fy);
+            }
+            for k in 0..4 {
+                force_x[lane[k]] -= lane_fx[k];
+                force_y[lane[k]] -= lane_fy[k];
+            }
+        }
+
+        for &tgt in remainder {
+            let dx = pos_x[tgt] - pos_x[src];
+            let dy = pos_y[tgt] - pos_y[src];
+            let dist = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+            let scale = stiffness * (dist - rest_length) / dist;
+            force_x[src] += dx * scale;
+            force_y[src] += dy * scale;
+            force_x[tgt] -= dx * scale;
+            force_y[tgt] -= dy * scale;
+        }
+    }
+}
+
+/// Vectorized bulk distances (see [`super::kernels::distances_from`]).
+pub(super) fn distances_from(x: f32, y: f32, pos_x: &[f32], pos_y: &[f32], out: &mut [f32]) {
+    let n = pos_x.len().min(pos_y.len()).min(out.len());
+    let vector_end = n - n % 4;
+    let px = f32x4_splat(x);
+    let py = f32x4_splat(y);
+
+    for i in (0..vector_end).step_by(4) {
+        let dx = f32x4_sub(load4(pos_x, i), px);
+        let dy = f32x4_sub(load4(pos_y, i), py);
+        let dist = f32x4_sqrt(f32x4_add(f32x4_mul(dx, dx), f32x4_mul(dy, dy)));
+        // SAFETY: i + 4 <= vector_end <= out.len().
+        unsafe { v128_store(out.as_mut_ptr().add(i) as *mut v128, dist) };
+    }
+
+    for i in vector_end..n {
+        let dx = pos_x[i] - x;
+        let dy = pos_y[i] - y;
+        out[i] = (dx * dx + dy * dy).sqrt();
+    }
+}
@@ -0,0 +1,105 @@
+//! Spring-to-target attraction.
+//!
+//! Layout algorithms produce interleaved target positions
+//! `[x0, y0, x1, y1, ...]` with a sentinel for nodes that are not part of
+//! the layout. This pass moves each participating node a fraction of the way
+//! toward its target, so calling it once per tick animates the graph into the
+//! layout on the CPU, mirroring the GPU spring-to-target shader.
+
+/// Sentinel marking nodes that do not participate in a layout.
+pub const SENTINEL: f32 = 3.402_823e+38;
+
+/// Check whether a target coordinate is the "not in layout" sentinel.
+///
+/// Compares against half the sentinel so values that went through lossy
+/// conversions are still recognized.
+#[inline]
+pub fn is_sentinel(value: f32) -> bool {
+    value >= SENTINEL * 0.5
+}
+
+/// Move node positions toward their targets.
+///
+/// Each participating node moves by `(target - position) * strength`, so a
+/// strength of 1 snaps to the target and smaller values ease toward it.
+/// Nodes whose target is the sentinel, whose slot lies beyond the target
+/// buffer, or for which `skip` returns true are left untouched.
+///
+/// # Arguments
+///
+/// * `pos_x`, `pos_y` - Node positions (one entry per node slot)
+/// * `targets` - Interleaved target positions [x0, y0, x1, y1, ...]
+/// * `strength` - Fraction of the remaining distance to cover, clamped to [0, 1]
+/// * `skip` - Returns true for slots that must not move (pinned, removed)
+///
+/// # Returns
+///
+/// The number of nodes that were moved.
+pub fn apply_target_attraction(
+    pos_x: &mut [f32],
+    pos_y: &mut [f32],
+    targets: &[f32],
+    strength: f32,
+    skip: impl Fn(usize) -> bool,
+) -> u32 {
+    let strength = strength.clamp(0.0, 1.0);
+    let n = pos_x.len().min(pos_y.len()).min(targets.len() / 2);
+    let mut moved = 0;
+
+    for i in 0..n {
+        let tx = targets[i * 2];
+        let ty = targets[i * 2 + 1];
+        if is_sentinel(tx) || is_sentinel(ty) || skip(i) {
+            continue;
+        }
+        pos_x[i] += (tx - pos_x[i]) * strength;
+        pos_y[i] += (ty - pos_y[i]) * strength;
+        moved += 1;
+    }
+
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moves_fraction_toward_target() {
+        let mut pos_x = [0.0, 10.0];
+        let mut pos_y = [0.0, 10.0];
+        let targets = [10.0, 0.0, 10.0, 20.0];
+
+        let moved = apply_target_attraction(&mut pos_x, &mut pos_y, &targets, 0.5, |_| false);
+
+        assert_eq!(moved, 2);
+        assert_eq!(pos_x, [5.0, 10.0]);
+        assert_eq!(pos_y, [0.0, 15.0]);
+    }
+
+    #[test]
+    fn test_sentinel_and_skip_are_untouched() {
+        let mut pos_x = [1.0, 2.0, 3.0];
+        let mut pos_y = [1.0, 2.0, 3.0];
+        let targets = [SENTINEL, SENTINEL, 0.0, 0.0, 0.0, 0.0];
+
+        let moved = apply_target_attraction(&mut pos_x, &mut pos_y, &targets, 1.0, |i| i == 2);
+
+        assert_eq!(moved, 1);
+        assert_eq!(pos_x, [1.0, 0.0, 3.0]);
+        assert_eq!(pos_y, [1.0, 0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_short_target_buffer_and_clamped_strength() {
+        let mut pos_x = [0.0, 0.0];
+        let mut pos_y = [0.0, 0.0];
+        // Only one node has a target; strength above 1 must not overshoot
+        let targets = [4.0, 4.0];
+
+        apply_target_attraction(&mut pos_x, &mut pos_y, &targets, 3.0, |_| false);
+
+        assert_eq!(pos_x, [4.0, 0.0]);
+        assert_eq!(pos_y, [4.0, 0.0]);
+    }
+}
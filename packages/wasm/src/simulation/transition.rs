@@ -0,0 +1,182 @@
+//! Timed position transitions.
+//!
+//! Where the target pass eases nodes a fixed fraction per tick, a transition
+//! interpolates every node from where it was when the transition started to
+//! a target buffer over a fixed duration, shaped by an easing curve. Each
+//! frame only needs the current time, so JS never touches per-node data
+//! while a layout change animates.
+//!
+//! The clock is whatever JS passes in (typically `performance.now()`); the
+//! transition starts at the first tick after it was created.
+
+use super::target::is_sentinel;
+
+/// Easing curve applied to the normalized transition time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow (cubic).
+    EaseIn,
+    /// Ends slow (cubic).
+    EaseOut,
+    /// Starts and ends slow (cubic).
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map normalized time `t` in [0, 1] to interpolation progress in [0, 1].
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) * 0.5,
+        }
+    }
+}
+
+/// An in-progress interpolation from start positions to targets.
+pub struct Transition {
+    /// Positions when the transition was created, per node slot.
+    from_x: Vec<f32>,
+    from_y: Vec<f32>,
+    /// Interleaved target positions [x0, y0, x1, y1, ...].
+    targets: Vec<f32>,
+    /// Duration in the caller's time unit (milliseconds from JS).
+    duration: f64,
+    easing: Easing,
+    /// Time of the first tick, set lazily.
+    start: Option<f64>,
+}
+
+impl Transition {
+    /// Create a transition from the current positions to `targets`.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos_x`, `pos_y` - Current node positions (one entry per node slot)
+    /// * `targets` - Interleaved target positions; sentinel entries and slots
+    ///   beyond the buffer keep their position
+    /// * `duration` - Length of the transition; 0 or less jumps on the first tick
+    /// * `easing` - Easing curve
+    pub fn new(
+        pos_x: &[f32],
+        pos_y: &[f32],
+        targets: &[f32],
+        duration: f64,
+        easing: Easing,
+    ) -> Self {
+        Self {
+            from_x: pos_x.to_vec(),
+            from_y: pos_y.to_vec(),
+            targets: targets.to_vec(),
+            duration,
+            easing,
+            start: None,
+        }
+    }
+
+    /// Eased progress at time `now` in [0, 1], starting the clock on the
+    /// first call.
+    pub fn progress(&mut self, now: f64) -> f32 {
+        let start = *self.start.get_or_insert(now);
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        let t = ((now - start) / self.duration) as f32;
+        self.easing.apply(t)
+    }
+
+    /// Write interpolated positions for time `now`.
+    ///
+    /// Nodes for which `skip` returns true (pinned, removed) and nodes added
+    /// after the transition started are not moved.
+    ///
+    /// # Returns
+    ///
+    /// True while the transition is still running, false once every node
+    /// has reached its target.
+    pub fn step(
+        &mut self,
+        now: f64,
+        pos_x: &mut [f32],
+        pos_y: &mut [f32],
+        skip: impl Fn(usize) -> bool,
+    ) -> bool {
+        let p = self.progress(now);
+        let n = pos_x
+            .len()
+            .min(pos_y.len())
+            .min(self.from_x.len())
+            .min(self.targets.len() / 2);
+
+        for i in 0..n {
+            let tx = self.targets[i * 2];
+            let ty = self.targets[i * 2 + 1];
+            if is_sentinel(tx) || is_sentinel(ty) || skip(i) {
+                continue;
+            }
+            pos_x[i] = self.from_x[i] + (tx - self.from_x[i]) * p;
+            pos_y[i] = self.from_y[i] + (ty - self.from_y[i]) * p;
+        }
+
+        p < 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::target::SENTINEL;
+
+    #[test]
+    fn test_easing_endpoints_and_symmetry() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    }
+
+    #[test]
+    fn test_linear_transition_over_time() {
+        let mut pos_x = [0.0, 5.0, 7.0];
+        let mut pos_y = [0.0, 5.0, 7.0];
+        let targets = [100.0, -100.0, SENTINEL, SENTINEL, 1.0, 1.0];
+        let mut transition = Transition::new(&pos_x, &pos_y, &targets, 200.0, Easing::Linear);
+
+        // The clock starts on the first tick
+        assert!(transition.step(1000.0, &mut pos_x, &mut pos_y, |_| false));
+        assert_eq!((pos_x[0], pos_y[0]), (0.0, 0.0));
+
+        assert!(transition.step(1050.0, &mut pos_x, &mut pos_y, |i| i == 2));
+        assert_eq!((pos_x[0], pos_y[0]), (25.0, -25.0));
+        assert_eq!((pos_x[1], pos_x[2]), (5.0, 7.0));
+
+        assert!(!transition.step(1300.0, &mut pos_x, &mut pos_y, |_| false));
+        assert_eq!((pos_x[0], pos_y[0]), (100.0, -100.0));
+        assert_eq!((pos_x[2], pos_y[2]), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_zero_duration_jumps() {
+        let mut pos_x = [0.0];
+        let mut pos_y = [0.0];
+        let mut transition = Transition::new(&pos_x, &pos_y, &[3.0, 4.0], 0.0, Easing::EaseInOut);
+
+        assert!(!transition.step(0.0, &mut pos_x, &mut pos_y, |_| false));
+        assert_eq!((pos_x[0], pos_y[0]), (3.0, 4.0));
+    }
+}